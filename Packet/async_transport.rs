@@ -0,0 +1,170 @@
+//! # Módulo `async_transport`
+//!
+//! Transporte UDP assíncrono sobre `tokio::net::UdpSocket`, disponível com o recurso
+//! `tokio`.
+//!
+//! [`AsyncUdpTransport`] tem a mesma interface de
+//! [`UdpTransport`](crate::transport::UdpTransport), com `send_packet` e `recv_packet`
+//! assíncronos, e pode ser usado dentro de um servidor `tokio` já existente sem
+//! bloquear o executor. [`AsyncUdpTransport::into_stream`] o converte em um
+//! [`PacketStream`], um `futures::Stream` dos pacotes recebidos e de suas origens.
+//!
+//! ## Exemplos
+//! ```rust,no_run
+//! use futures::StreamExt;
+//! use packet::Packet;
+//! use packet::async_transport::AsyncUdpTransport;
+//!
+//! # async fn run() -> Result<(), packet::PacketError> {
+//! let server = AsyncUdpTransport::bind("127.0.0.1:4000").await?;
+//! let client = AsyncUdpTransport::bind("127.0.0.1:0").await?;
+//!
+//! client
+//!     .send_packet(server.local_addr()?, &Packet::new(1, 1, 7, vec![1, 2, 3]))
+//!     .await?;
+//!
+//! let mut packets = server.into_stream();
+//! while let Some((from, packet)) = packets.next().await {
+//!     packets.get_ref().send_packet(from, &packet).await?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::io;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+use futures::Stream;
+use tokio::io::ReadBuf;
+use tokio::net::{ToSocketAddrs, UdpSocket};
+
+use crate::socket::parse_datagram;
+use crate::{recommended_recv_buffer_size, Packet, PacketError};
+
+/// Socket UDP assíncrono com buffer de recepção próprio.
+#[derive(Debug)]
+pub struct AsyncUdpTransport {
+    /// Socket subjacente.
+    socket: UdpSocket,
+    /// Buffer reutilizado a cada recepção.
+    buffer: Vec<u8>,
+}
+
+impl AsyncUdpTransport {
+    /// Cria um transporte associado ao endereço informado.
+    ///
+    /// ## Parâmetros
+    /// - `addr`: Endereço local; use a porta `0` para uma porta qualquer.
+    ///
+    /// ## Retorno
+    /// - `io::Result<Self>`: O transporte ou o erro retornado pelo sistema.
+    pub async fn bind(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        UdpSocket::bind(addr).await.map(Self::from_socket)
+    }
+
+    /// Envolve um socket já configurado.
+    ///
+    /// ## Parâmetros
+    /// - `socket`: Socket UDP do `tokio` associado a um endereço local.
+    ///
+    /// ## Retorno
+    /// - `Self`: O transporte.
+    pub fn from_socket(socket: UdpSocket) -> Self {
+        Self {
+            socket,
+            buffer: vec![0; recommended_recv_buffer_size()],
+        }
+    }
+
+    /// Retorna o socket subjacente.
+    pub fn get_ref(&self) -> &UdpSocket {
+        &self.socket
+    }
+
+    /// Retorna o endereço local do transporte.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.socket.local_addr()
+    }
+
+    /// Serializa e envia um pacote em um único datagrama.
+    ///
+    /// ## Parâmetros
+    /// - `addr`: Endereço de destino.
+    /// - `packet`: Pacote a ser enviado.
+    ///
+    /// ## Retorno
+    /// - `Result<usize, PacketError>`: Quantidade de bytes enviados ou
+    ///   [`PacketError::Io`] com o erro do sistema.
+    pub async fn send_packet(
+        &self,
+        addr: impl ToSocketAddrs,
+        packet: &Packet,
+    ) -> Result<usize, PacketError> {
+        Ok(self.socket.send_to(&packet.to_bytes(), addr).await?)
+    }
+
+    /// Aguarda o próximo datagrama e o interpreta como um pacote, verificando seu
+    /// checksum.
+    ///
+    /// ## Retorno
+    /// - `Result<(SocketAddr, Packet), PacketError>`: O endereço de origem e o pacote;
+    ///   os erros são os de
+    ///   [`UdpTransport::recv_packet`](crate::transport::UdpTransport::recv_packet).
+    pub async fn recv_packet(&mut self) -> Result<(SocketAddr, Packet), PacketError> {
+        let (len, from) = self.socket.recv_from(&mut self.buffer).await?;
+        let packet = parse_datagram(&self.buffer[..len], self.buffer.len())?;
+        Ok((from, packet.to_packet()))
+    }
+
+    /// Converte o transporte em um fluxo dos pacotes recebidos.
+    ///
+    /// ## Retorno
+    /// - `PacketStream`: O fluxo, que ainda permite enviar por
+    ///   [`PacketStream::get_ref`].
+    pub fn into_stream(self) -> PacketStream {
+        PacketStream { transport: self }
+    }
+}
+
+/// Fluxo dos pacotes recebidos por um [`AsyncUdpTransport`], com seus endereços de
+/// origem.
+///
+/// Datagramas malformados ou com checksum inválido são descartados; o fluxo termina
+/// na primeira falha de E/S do socket.
+#[derive(Debug)]
+pub struct PacketStream {
+    /// Transporte de onde os pacotes são recebidos.
+    transport: AsyncUdpTransport,
+}
+
+impl PacketStream {
+    /// Retorna o transporte subjacente, para enviar pacotes enquanto o fluxo recebe.
+    pub fn get_ref(&self) -> &AsyncUdpTransport {
+        &self.transport
+    }
+
+    /// Retorna o transporte subjacente, encerrando o fluxo.
+    pub fn into_inner(self) -> AsyncUdpTransport {
+        self.transport
+    }
+}
+
+impl Stream for PacketStream {
+    type Item = (SocketAddr, Packet);
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let transport = &mut self.get_mut().transport;
+        let buffer_len = transport.buffer.len();
+        loop {
+            let mut buf = ReadBuf::new(&mut transport.buffer);
+            let Ok(from) = ready!(transport.socket.poll_recv_from(cx, &mut buf)) else {
+                return Poll::Ready(None);
+            };
+            if let Ok(packet) = parse_datagram(buf.filled(), buffer_len) {
+                return Poll::Ready(Some((from, packet.to_packet())));
+            }
+        }
+    }
+}
@@ -0,0 +1,264 @@
+//! # Módulo `auth`
+//!
+//! Tokens de conexão emitidos por um serviço de autenticação, no estilo do
+//! netcode.io.
+//!
+//! Em servidores dedicados, o cliente não se conecta diretamente: ele se autentica
+//! em um serviço da aplicação, que emite um [`ConnectToken`] assinado com Ed25519
+//! contendo o `player_id`, o instante de expiração e os endereços dos servidores em
+//! que ele vale. O cliente repassa os bytes do token no aperto de mão com
+//! [`Client::with_connect_token`](crate::connection::Client::with_connect_token), e
+//! o servidor, configurado com
+//! [`Server::with_connect_tokens`](crate::connection::Server::with_connect_tokens),
+//! verifica assinatura, expiração e endereço antes de responder ao pedido ou de
+//! alocar qualquer estado da conexão. O cliente não precisa do recurso `crypto`:
+//! para ele o token é opaco.
+//!
+//! O token tem sempre [`CONNECT_TOKEN_SIZE`] bytes, todos little-endian:
+//!
+//! | Offset | Tamanho | Campo                                                       |
+//! |--------|---------|-------------------------------------------------------------|
+//! | `0`    | `8`     | `player_id`                                                 |
+//! | `8`    | `8`     | Expiração, em segundos desde a época Unix                   |
+//! | `16`   | `1`     | Quantidade de endereços, até [`MAX_TOKEN_ADDRESSES`]       |
+//! | `17`   | `76`    | Endereços: família (`4` ou `6`), 16 bytes de IP e a porta   |
+//! | `93`   | `3`     | Zeros                                                       |
+//! | `96`   | `64`    | Assinatura Ed25519 dos bytes anteriores                     |
+//!
+//! ## Exemplos
+//! ```rust
+//! # #[cfg(feature = "crypto")]
+//! # {
+//! use std::time::{Duration, SystemTime};
+//! use ed25519_dalek::SigningKey;
+//! use packet::PacketError;
+//! use packet::auth::ConnectToken;
+//!
+//! let backend_key = SigningKey::from_bytes(&[7; 32]);
+//! let server_addr = "203.0.113.5:4000".parse().unwrap();
+//! let now = SystemTime::now();
+//!
+//! let token = ConnectToken::new(42, now + Duration::from_secs(30), &[server_addr]).unwrap();
+//! let bytes = token.sign(&backend_key);
+//!
+//! let verified = ConnectToken::verify(&bytes, &backend_key.verifying_key(), now, server_addr);
+//! assert_eq!(verified.unwrap().player_id, 42);
+//!
+//! let later = now + Duration::from_secs(60);
+//! assert_eq!(
+//!     ConnectToken::verify(&bytes, &backend_key.verifying_key(), later, server_addr),
+//!     Err(PacketError::TokenExpired)
+//! );
+//! # }
+//! ```
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::{Duration, SystemTime};
+
+#[cfg(feature = "crypto")]
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
+
+use crate::PacketError;
+
+/// Tamanho, em bytes, de um token de conexão assinado.
+pub const CONNECT_TOKEN_SIZE: usize = 160;
+
+/// Quantidade máxima de endereços de servidor em um token.
+pub const MAX_TOKEN_ADDRESSES: usize = 4;
+
+/// Tamanho da parte assinada do token.
+#[cfg(feature = "crypto")]
+const BODY_SIZE: usize = 96;
+/// Tamanho de cada endereço serializado.
+const ADDRESS_SIZE: usize = 19;
+/// Prefixo da mensagem assinada, para que a assinatura não valha em outro contexto.
+#[cfg(feature = "crypto")]
+const SIGNING_CONTEXT: &[u8] = b"packet connect token v1";
+
+/// Conteúdo de um token de conexão.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectToken {
+    /// Jogador autenticado pelo serviço.
+    pub player_id: u64,
+    /// Instante de expiração, em segundos desde a época Unix.
+    pub expires_at: u64,
+    /// Servidores em que o token vale.
+    pub server_addresses: Vec<SocketAddr>,
+}
+
+impl ConnectToken {
+    /// Cria o conteúdo de um token.
+    ///
+    /// ## Parâmetros
+    /// - `player_id`: Jogador autenticado.
+    /// - `expires_at`: Instante a partir do qual o token deixa de valer; arredondado
+    ///   para baixo ao segundo.
+    /// - `server_addresses`: Servidores em que o token vale.
+    ///
+    /// ## Retorno
+    /// - `Result<Self, PacketError>`: O token, ou [`PacketError::InvalidToken`] se há
+    ///   mais de [`MAX_TOKEN_ADDRESSES`] endereços.
+    pub fn new(
+        player_id: u64,
+        expires_at: SystemTime,
+        server_addresses: &[SocketAddr],
+    ) -> Result<Self, PacketError> {
+        if server_addresses.len() > MAX_TOKEN_ADDRESSES {
+            return Err(PacketError::InvalidToken {
+                reason: "endereços demais no token",
+            });
+        }
+        Ok(Self {
+            player_id,
+            expires_at: unix_seconds(expires_at),
+            server_addresses: server_addresses.to_vec(),
+        })
+    }
+
+    /// Interpreta os bytes de um token sem verificar a assinatura nem a expiração.
+    ///
+    /// ## Parâmetros
+    /// - `bytes`: Token serializado.
+    ///
+    /// ## Retorno
+    /// - `Result<Self, PacketError>`: O conteúdo do token, ou
+    ///   [`PacketError::InvalidToken`] se os endereços são inválidos.
+    pub fn parse(bytes: &[u8; CONNECT_TOKEN_SIZE]) -> Result<Self, PacketError> {
+        let invalid = |reason| PacketError::InvalidToken { reason };
+        let word = |offset: usize| {
+            u64::from_le_bytes(bytes[offset..offset + 8].try_into().expect("8 bytes"))
+        };
+        let count = bytes[16] as usize;
+        if count > MAX_TOKEN_ADDRESSES {
+            return Err(invalid("endereços demais no token"));
+        }
+
+        let mut server_addresses = Vec::with_capacity(count);
+        for address in bytes[17..].chunks_exact(ADDRESS_SIZE).take(count) {
+            let ip: IpAddr = match address[0] {
+                4 => Ipv4Addr::from(<[u8; 4]>::try_from(&address[1..5]).expect("4 bytes")).into(),
+                6 => {
+                    Ipv6Addr::from(<[u8; 16]>::try_from(&address[1..17]).expect("16 bytes")).into()
+                }
+                _ => return Err(invalid("família de endereço desconhecida")),
+            };
+            let port = u16::from_le_bytes([address[17], address[18]]);
+            server_addresses.push(SocketAddr::new(ip, port));
+        }
+        Ok(Self {
+            player_id: word(0),
+            expires_at: word(8),
+            server_addresses,
+        })
+    }
+
+    /// Indica se o token já expirou em `now`.
+    pub fn is_expired(&self, now: SystemTime) -> bool {
+        unix_seconds(now) >= self.expires_at
+    }
+
+    /// Indica se o token vale no servidor de endereço `server`.
+    pub fn allows(&self, server: SocketAddr) -> bool {
+        self.server_addresses.contains(&server)
+    }
+
+    /// Serializa a parte assinada do token.
+    #[cfg(feature = "crypto")]
+    fn body(&self) -> [u8; BODY_SIZE] {
+        let mut body = [0; BODY_SIZE];
+        body[0..8].copy_from_slice(&self.player_id.to_le_bytes());
+        body[8..16].copy_from_slice(&self.expires_at.to_le_bytes());
+        body[16] = self.server_addresses.len() as u8;
+        for (address, slot) in self
+            .server_addresses
+            .iter()
+            .zip(body[17..].chunks_exact_mut(ADDRESS_SIZE))
+        {
+            match address.ip() {
+                IpAddr::V4(ip) => {
+                    slot[0] = 4;
+                    slot[1..5].copy_from_slice(&ip.octets());
+                }
+                IpAddr::V6(ip) => {
+                    slot[0] = 6;
+                    slot[1..17].copy_from_slice(&ip.octets());
+                }
+            }
+            slot[17..19].copy_from_slice(&address.port().to_le_bytes());
+        }
+        body
+    }
+
+    /// Assina o token com a chave do serviço de autenticação.
+    ///
+    /// Disponível com o recurso `crypto`.
+    ///
+    /// ## Parâmetros
+    /// - `signing_key`: Chave privada do serviço.
+    ///
+    /// ## Retorno
+    /// - `[u8; CONNECT_TOKEN_SIZE]`: Os bytes a entregar ao cliente.
+    #[cfg(feature = "crypto")]
+    pub fn sign(&self, signing_key: &SigningKey) -> [u8; CONNECT_TOKEN_SIZE] {
+        let mut bytes = [0; CONNECT_TOKEN_SIZE];
+        bytes[..BODY_SIZE].copy_from_slice(&self.body());
+        let signature = signing_key.sign(&signed_message(&bytes));
+        bytes[BODY_SIZE..].copy_from_slice(&signature.to_bytes());
+        bytes
+    }
+
+    /// Verifica um token recebido: assinatura, expiração e endereço do servidor.
+    ///
+    /// Disponível com o recurso `crypto`.
+    ///
+    /// ## Parâmetros
+    /// - `bytes`: Token recebido do cliente.
+    /// - `verifying_key`: Chave pública do serviço de autenticação.
+    /// - `now`: Instante atual.
+    /// - `server`: Endereço pelo qual os clientes conhecem este servidor.
+    ///
+    /// ## Retorno
+    /// - `Result<Self, PacketError>`: O conteúdo do token válido;
+    ///   [`PacketError::InvalidToken`] se a assinatura não confere, se o token é
+    ///   malformado ou se não vale em `server`; ou [`PacketError::TokenExpired`].
+    #[cfg(feature = "crypto")]
+    pub fn verify(
+        bytes: &[u8; CONNECT_TOKEN_SIZE],
+        verifying_key: &VerifyingKey,
+        now: SystemTime,
+        server: SocketAddr,
+    ) -> Result<Self, PacketError> {
+        let signature = Signature::from_bytes(bytes[BODY_SIZE..].try_into().expect("64 bytes"));
+        if verifying_key
+            .verify_strict(&signed_message(bytes), &signature)
+            .is_err()
+        {
+            return Err(PacketError::InvalidToken {
+                reason: "assinatura inválida",
+            });
+        }
+        let token = Self::parse(bytes)?;
+        if token.is_expired(now) {
+            return Err(PacketError::TokenExpired);
+        }
+        if !token.allows(server) {
+            return Err(PacketError::InvalidToken {
+                reason: "servidor fora do token",
+            });
+        }
+        Ok(token)
+    }
+}
+
+/// Mensagem coberta pela assinatura: o contexto seguido da parte assinada.
+#[cfg(feature = "crypto")]
+fn signed_message(bytes: &[u8; CONNECT_TOKEN_SIZE]) -> Vec<u8> {
+    [SIGNING_CONTEXT, &bytes[..BODY_SIZE]].concat()
+}
+
+/// Segundos inteiros desde a época Unix, ou zero antes dela.
+fn unix_seconds(time: SystemTime) -> u64 {
+    time.duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or(Duration::ZERO)
+        .as_secs()
+}
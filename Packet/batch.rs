@@ -0,0 +1,894 @@
+//! # Módulo `batch`
+//!
+//! Agrupa vários pacotes pequenos em um único datagrama, reduzindo o custo de
+//! cabeçalhos UDP e de chamadas de sistema.
+//!
+//! Cada pacote do lote é precedido por um prefixo de tamanho de 2 bytes
+//! (little-endian) com o tamanho do pacote serializado. Com
+//! [`pack_batch_with_format`] e [`unpack_batch_with_format`], o prefixo e os pacotes
+//! seguem a ordem de bytes de um [`WireFormat`].
+//!
+//! [`BatchTransport`] faz o agrupamento sobre um [`UdpTransport`]: os pacotes enviados
+//! a um mesmo destino durante um tick são acumulados em um lote por destino, enviado
+//! quando o próximo pacote não cabe no MTU ou em [`BatchTransport::flush`], ao fim do
+//! tick. Na recepção, [`unpack_datagram`] aceita tanto lotes quanto pacotes isolados,
+//! de modo que os dois lados não precisam combinar o uso de lotes.
+//!
+//! ## Exemplos
+//! ```rust
+//! use packet::Packet;
+//! use packet::batch::{pack_batch, unpack_batch};
+//!
+//! let packets = vec![
+//!     Packet::new(1, 1, 7, vec![1, 2, 3]),
+//!     Packet::new(1, 2, 7, vec![4, 5]),
+//! ];
+//! let datagram = pack_batch(&packets).unwrap();
+//! assert_eq!(unpack_batch(&datagram).unwrap(), packets);
+//! ```
+
+use std::collections::VecDeque;
+use std::io;
+use std::net::{SocketAddr, UdpSocket};
+use std::time::{Duration, Instant};
+
+use crate::config::ParseConfig;
+use crate::transport::{Transport, UdpTransport};
+use crate::wire::WireFormat;
+use crate::{recommended_recv_buffer_size, Packet, PacketError, PacketHeader};
+
+/// Tamanho, em bytes, do prefixo que antecede cada pacote no lote.
+pub const BATCH_LENGTH_PREFIX: usize = 2;
+
+/// Retorna quantos bytes um pacote ocupa dentro de um lote.
+///
+/// ## Parâmetros
+/// - `packet`: Pacote a ser medido.
+///
+/// ## Retorno
+/// - `usize`: Tamanho serializado do pacote somado ao prefixo de tamanho.
+pub fn batch_entry_len(packet: &Packet) -> usize {
+    BATCH_LENGTH_PREFIX + packet.serialized_len()
+}
+
+/// Acrescenta um pacote, com seu prefixo de tamanho, ao final de um lote.
+fn push_entry(buffer: &mut Vec<u8>, packet: &Packet) -> Result<(), PacketError> {
+    push_entry_with_format(buffer, packet, WireFormat::STANDARD)
+}
+
+/// Acrescenta um pacote ao final de um lote, com o prefixo e o pacote em um formato.
+fn push_entry_with_format(
+    buffer: &mut Vec<u8>,
+    packet: &Packet,
+    format: WireFormat,
+) -> Result<(), PacketError> {
+    let size = packet.serialized_len();
+    let len = u16::try_from(size).map_err(|_| PacketError::PacketTooLarge {
+        size,
+        limit: u16::MAX as usize,
+    })?;
+    buffer.extend(&format.endianness.u16_bytes(len));
+    if format == WireFormat::STANDARD {
+        packet.to_bytes_into(buffer);
+    } else {
+        buffer.extend(packet.to_bytes_with_format(format));
+    }
+    Ok(())
+}
+
+/// Retorna quantos pacotes do início de `packets` cabem juntos em um lote de até `mtu` bytes.
+fn fitting_prefix_len(packets: &[Packet], mtu: usize) -> usize {
+    let mut len = 0;
+    packets
+        .iter()
+        .take_while(|packet| {
+            len += batch_entry_len(packet);
+            len <= mtu
+        })
+        .count()
+}
+
+/// Serializa uma sequência de pacotes em um único datagrama.
+///
+/// ## Parâmetros
+/// - `packets`: Pacotes a serem agrupados, na ordem de envio.
+///
+/// ## Retorno
+/// - `Result<Vec<u8>, PacketError>`: Os bytes do lote ou [`PacketError::PacketTooLarge`]
+///   caso algum pacote exceda `u16::MAX` bytes serializado.
+pub fn pack_batch(packets: &[Packet]) -> Result<Vec<u8>, PacketError> {
+    let mut buffer = Vec::with_capacity(packets.iter().map(batch_entry_len).sum());
+    for packet in packets {
+        push_entry(&mut buffer, packet)?;
+    }
+    Ok(buffer)
+}
+
+/// Reconstrói os pacotes contidos em um datagrama produzido por [`pack_batch`].
+///
+/// ## Parâmetros
+/// - `bytes`: Fatia de bytes do lote.
+///
+/// ## Retorno
+/// - `Result<Vec<Packet>, PacketError>`: Os pacotes na ordem em que foram agrupados ou o
+///   erro encontrado se o lote estiver truncado ou algum pacote for inválido.
+pub fn unpack_batch(bytes: &[u8]) -> Result<Vec<Packet>, PacketError> {
+    unpack_entries(bytes, WireFormat::STANDARD, None, None)
+}
+
+/// Serializa uma sequência de pacotes em um lote como [`pack_batch`], com os prefixos
+/// de tamanho e os pacotes em um formato da rede.
+///
+/// ## Parâmetros
+/// - `packets`: Pacotes a serem agrupados, na ordem de envio.
+/// - `format`: Formato dos prefixos e dos pacotes.
+///
+/// ## Retorno
+/// - `Result<Vec<u8>, PacketError>`: Os bytes do lote ou o erro de [`pack_batch`].
+///
+/// ## Exemplos
+/// ```rust
+/// use packet::Packet;
+/// use packet::batch::{pack_batch_with_format, unpack_batch_with_format};
+/// use packet::wire::WireFormat;
+///
+/// let packets = vec![Packet::new(1, 1, 7, vec![1, 2, 3]), Packet::new(1, 2, 7, vec![])];
+/// let datagram = pack_batch_with_format(&packets, WireFormat::NETWORK).unwrap();
+///
+/// // O prefixo de tamanho vem em ordem de rede.
+/// let len = packets[0].serialized_len() as u16;
+/// assert_eq!(datagram[..2], len.to_be_bytes());
+/// assert_eq!(
+///     unpack_batch_with_format(&datagram, WireFormat::NETWORK).unwrap(),
+///     packets
+/// );
+/// ```
+pub fn pack_batch_with_format(
+    packets: &[Packet],
+    format: WireFormat,
+) -> Result<Vec<u8>, PacketError> {
+    let mut buffer = Vec::with_capacity(packets.iter().map(batch_entry_len).sum());
+    for packet in packets {
+        push_entry_with_format(&mut buffer, packet, format)?;
+    }
+    Ok(buffer)
+}
+
+/// Reconstrói os pacotes de um lote produzido por [`pack_batch_with_format`].
+///
+/// ## Parâmetros
+/// - `bytes`: Fatia de bytes do lote.
+/// - `format`: Formato dos prefixos e dos pacotes.
+///
+/// ## Retorno
+/// - `Result<Vec<Packet>, PacketError>`: Os pacotes ou o erro de [`unpack_batch`].
+pub fn unpack_batch_with_format(
+    bytes: &[u8],
+    format: WireFormat,
+) -> Result<Vec<Packet>, PacketError> {
+    unpack_entries(bytes, format, None, None)
+}
+
+/// Reconstrói os pacotes de um lote como [`unpack_batch`], aplicando os limites.
+///
+/// Cada pacote é lido por [`Packet::from_bytes_with_limits`], e o lote é rejeitado ao
+/// passar de [`ParseConfig::max_batch`] pacotes, antes de ler os seguintes.
+///
+/// ## Parâmetros
+/// - `bytes`: Fatia de bytes do lote.
+/// - `limits`: Limites da leitura.
+///
+/// ## Retorno
+/// - `Result<Vec<Packet>, PacketError>`: Os pacotes, o erro de [`unpack_batch`], o de
+///   [`ParseConfig::check`] ou [`PacketError::BatchTooLarge`].
+///
+/// ## Exemplos
+/// ```rust
+/// use packet::{Packet, PacketError};
+/// use packet::batch::{pack_batch, unpack_batch_with_limits};
+/// use packet::config::ParseConfig;
+///
+/// let packets: Vec<_> = (0..3).map(|sequence| Packet::new(1, sequence, 7, vec![])).collect();
+/// let datagram = pack_batch(&packets).unwrap();
+///
+/// let limits = ParseConfig {
+///     max_batch: 2,
+///     ..ParseConfig::default()
+/// };
+/// assert_eq!(
+///     unpack_batch_with_limits(&datagram, &limits),
+///     Err(PacketError::BatchTooLarge { limit: 2 })
+/// );
+/// ```
+pub fn unpack_batch_with_limits(
+    bytes: &[u8],
+    limits: &ParseConfig,
+) -> Result<Vec<Packet>, PacketError> {
+    unpack_entries(bytes, WireFormat::STANDARD, Some(limits), None)
+}
+
+/// Reconstrói os pacotes de um datagrama que pode ser um lote ou um pacote isolado.
+///
+/// Um pacote isolado começa por [`PacketHeader::MAGIC`]; em um lote, os bytes mágicos
+/// do primeiro pacote vêm depois do prefixo de tamanho. Os checksums não são
+/// verificados, como em [`unpack_batch`].
+///
+/// ## Parâmetros
+/// - `bytes`: Bytes do datagrama.
+///
+/// ## Retorno
+/// - `Result<Vec<Packet>, PacketError>`: Os pacotes do datagrama ou o erro de
+///   interpretação.
+///
+/// ## Exemplos
+/// ```rust
+/// use packet::Packet;
+/// use packet::batch::{pack_batch, unpack_datagram};
+///
+/// let packet = Packet::new(1, 1, 7, vec![1, 2, 3]);
+/// assert_eq!(unpack_datagram(&packet.to_bytes()).unwrap(), [packet.clone()]);
+/// assert_eq!(unpack_datagram(&pack_batch(&[packet.clone()]).unwrap()).unwrap(), [packet]);
+/// ```
+pub fn unpack_datagram(bytes: &[u8]) -> Result<Vec<Packet>, PacketError> {
+    if bytes.starts_with(&PacketHeader::MAGIC) {
+        Packet::from_bytes(bytes).map(|packet| vec![packet])
+    } else {
+        unpack_batch(bytes)
+    }
+}
+
+/// Reconstrói os pacotes de um datagrama como [`unpack_datagram`], aplicando os limites
+/// de [`unpack_batch_with_limits`].
+///
+/// ## Parâmetros
+/// - `bytes`: Bytes do datagrama.
+/// - `limits`: Limites da leitura.
+///
+/// ## Retorno
+/// - `Result<Vec<Packet>, PacketError>`: Os pacotes do datagrama ou o erro de
+///   interpretação.
+pub fn unpack_datagram_with_limits(
+    bytes: &[u8],
+    limits: &ParseConfig,
+) -> Result<Vec<Packet>, PacketError> {
+    if bytes.starts_with(&PacketHeader::MAGIC) {
+        Packet::from_bytes_with_limits(bytes, limits).map(|packet| vec![packet])
+    } else {
+        unpack_batch_with_limits(bytes, limits)
+    }
+}
+
+/// Resumo dos pacotes de um lote, calculado por [`unpack_batch_with_stats`].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct PacketBatchStats {
+    /// Quantidade de pacotes no lote.
+    pub count: usize,
+    /// Soma dos tamanhos dos payloads, em bytes.
+    pub payload_bytes: usize,
+    /// Menor sequência do lote; `None` se o lote está vazio.
+    pub min_sequence: Option<u32>,
+    /// Maior sequência do lote; `None` se o lote está vazio.
+    pub max_sequence: Option<u32>,
+    /// Quantidade de pacotes cujo checksum não confere.
+    pub checksum_failures: usize,
+    /// Soma das sequências, usada pela média.
+    sequence_sum: u64,
+}
+
+impl PacketBatchStats {
+    /// Retorna a média das sequências do lote.
+    ///
+    /// A média é aritmética e ignora a volta do contador de sequência.
+    ///
+    /// ## Retorno
+    /// - `Option<f64>`: A média ou `None` se o lote está vazio.
+    pub fn average_sequence(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.sequence_sum as f64 / self.count as f64)
+    }
+
+    /// Acrescenta um pacote ao resumo.
+    fn record(&mut self, packet: &Packet) {
+        let sequence = packet.header.sequence;
+        self.count += 1;
+        self.payload_bytes += packet.payload.len();
+        self.min_sequence = Some(self.min_sequence.map_or(sequence, |min| min.min(sequence)));
+        self.max_sequence = Some(self.max_sequence.map_or(sequence, |max| max.max(sequence)));
+        self.sequence_sum += u64::from(sequence);
+        if !packet.verify_checksum() {
+            self.checksum_failures += 1;
+        }
+    }
+}
+
+/// Reconstrói os pacotes de um lote como [`unpack_batch`] e os resume na mesma passada.
+///
+/// Os pacotes com checksum inválido são mantidos no resultado e contados em
+/// [`PacketBatchStats::checksum_failures`]; cabe ao chamador descartá-los.
+///
+/// ## Parâmetros
+/// - `bytes`: Fatia de bytes do lote.
+///
+/// ## Retorno
+/// - `Result<(Vec<Packet>, PacketBatchStats), PacketError>`: Os pacotes e o resumo, ou
+///   o mesmo erro de [`unpack_batch`].
+///
+/// ## Exemplos
+/// ```rust
+/// use packet::Packet;
+/// use packet::batch::{pack_batch, unpack_batch_with_stats};
+///
+/// let mut corrupted = Packet::new(1, 9, 7, vec![6]);
+/// corrupted.header.checksum ^= 1;
+/// let packets = vec![
+///     Packet::new(1, 3, 7, vec![1, 2, 3]),
+///     Packet::new(1, 6, 7, vec![4, 5]),
+///     corrupted,
+/// ];
+/// let datagram = pack_batch(&packets).unwrap();
+///
+/// let (unpacked, stats) = unpack_batch_with_stats(&datagram).unwrap();
+/// assert_eq!(unpacked, packets);
+/// assert_eq!(stats.count, 3);
+/// assert_eq!(stats.payload_bytes, 6);
+/// assert_eq!(stats.min_sequence, Some(3));
+/// assert_eq!(stats.max_sequence, Some(9));
+/// assert_eq!(stats.average_sequence(), Some(6.0));
+/// assert_eq!(stats.checksum_failures, 1);
+/// ```
+pub fn unpack_batch_with_stats(
+    bytes: &[u8],
+) -> Result<(Vec<Packet>, PacketBatchStats), PacketError> {
+    let mut stats = PacketBatchStats::default();
+    let packets = unpack_entries(bytes, WireFormat::STANDARD, None, Some(&mut stats))?;
+    Ok((packets, stats))
+}
+
+/// Percorre as entradas de um lote, aplicando `limits` e resumindo-as em `stats`
+/// quando informados.
+fn unpack_entries(
+    bytes: &[u8],
+    format: WireFormat,
+    limits: Option<&ParseConfig>,
+    mut stats: Option<&mut PacketBatchStats>,
+) -> Result<Vec<Packet>, PacketError> {
+    let mut packets = Vec::new();
+    let mut rest = bytes;
+
+    while !rest.is_empty() {
+        if let Some(limits) = limits {
+            limits.check_batch(packets.len() + 1)?;
+        }
+        if rest.len() < BATCH_LENGTH_PREFIX {
+            return Err(PacketError::TooShort {
+                expected: BATCH_LENGTH_PREFIX,
+                actual: rest.len(),
+            });
+        }
+        let len = format.endianness.read_u16([rest[0], rest[1]]) as usize;
+        rest = &rest[BATCH_LENGTH_PREFIX..];
+
+        if rest.len() < len {
+            return Err(PacketError::TooShort {
+                expected: len,
+                actual: rest.len(),
+            });
+        }
+        let packet = match limits {
+            Some(limits) => Packet::from_bytes_with_limits(&rest[..len], limits)?,
+            None => Packet::from_bytes_with_format(&rest[..len], format)?,
+        };
+        if let Some(stats) = stats.as_deref_mut() {
+            stats.record(&packet);
+        }
+        packets.push(packet);
+        rest = &rest[len..];
+    }
+
+    Ok(packets)
+}
+
+impl Packet {
+    /// Prepara o pacote para envio em datagramas de até `mtu` bytes.
+    ///
+    /// Um pacote que não cabe no MTU é dividido com [`Packet::fragment`], e os
+    /// fragmentos são agrupados em lotes no formato de [`pack_batch`], tantos por
+    /// datagrama quanto couberem. Todo datagrama retornado é um lote, mesmo quando
+    /// contém um único pacote, para que o receptor sempre use [`unpack_batch`].
+    ///
+    /// ## Parâmetros
+    /// - `mtu`: Tamanho máximo de cada datagrama, em bytes.
+    ///
+    /// ## Retorno
+    /// - `Result<Vec<Vec<u8>>, PacketError>`: Os datagramas, na ordem de envio, ou o
+    ///   erro de [`Packet::fragment`] se o MTU não comporta nem um fragmento mínimo.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::Packet;
+    /// use packet::batch::unpack_batch;
+    ///
+    /// let small = Packet::new(1, 1, 7, vec![1, 2, 3]);
+    /// let datagrams = small.split_into_mtu_datagrams(1200).unwrap();
+    /// assert_eq!(datagrams.len(), 1);
+    /// assert_eq!(unpack_batch(&datagrams[0]).unwrap(), [small]);
+    ///
+    /// let large = Packet::new(1, 2, 7, vec![0xAB; 3000]);
+    /// let datagrams = large.split_into_mtu_datagrams(1200).unwrap();
+    /// assert_eq!(datagrams.len(), 3);
+    /// assert!(datagrams.iter().all(|datagram| datagram.len() <= 1200));
+    /// ```
+    pub fn split_into_mtu_datagrams(&self, mtu: usize) -> Result<Vec<Vec<u8>>, PacketError> {
+        let max_payload = mtu.saturating_sub(BATCH_LENGTH_PREFIX + PacketHeader::SIZE);
+        let fragments = if batch_entry_len(self) <= mtu {
+            vec![self.clone()]
+        } else {
+            self.fragment(max_payload)?
+        };
+
+        let mut datagrams = Vec::new();
+        let mut rest = fragments.as_slice();
+        while !rest.is_empty() {
+            let count = fitting_prefix_len(rest, mtu).max(1);
+            datagrams.push(pack_batch(&rest[..count])?);
+            rest = &rest[count..];
+        }
+        Ok(datagrams)
+    }
+}
+
+/// Acumula pacotes pequenos e os libera como um único lote, no estilo do
+/// algoritmo de Nagle.
+///
+/// Um lote é liberado quando a janela de tempo desde o primeiro pacote acumulado
+/// expira ou quando o limite de bytes é atingido. Nenhum lote ultrapassa o MTU:
+/// se um novo pacote não couber no lote atual, o lote é fechado antecipadamente
+/// e fica disponível na próxima chamada a [`BufferedSender::flush_due`].
+///
+/// ## Exemplos
+/// ```rust
+/// use std::time::{Duration, Instant};
+/// use packet::Packet;
+/// use packet::batch::{unpack_batch, BufferedSender};
+///
+/// let mut sender = BufferedSender::new(Duration::from_millis(10), 1024, 1200);
+/// sender.enqueue(Packet::new(1, 1, 7, vec![1, 2, 3])).unwrap();
+/// sender.enqueue(Packet::new(1, 2, 7, vec![4, 5])).unwrap();
+///
+/// assert!(sender.flush_due(Instant::now()).is_none());
+///
+/// let later = Instant::now() + Duration::from_millis(10);
+/// let datagram = sender.flush_due(later).unwrap();
+/// assert_eq!(unpack_batch(&datagram).unwrap().len(), 2);
+/// ```
+#[derive(Debug)]
+pub struct BufferedSender {
+    /// Tempo máximo que um pacote pode aguardar no lote.
+    window: Duration,
+    /// Quantidade de bytes a partir da qual o lote é liberado sem esperar a janela.
+    byte_threshold: usize,
+    /// Tamanho máximo de um lote, em bytes.
+    mtu: usize,
+    /// Lote em construção.
+    current: Vec<u8>,
+    /// Instante em que o primeiro pacote do lote atual foi acumulado.
+    started_at: Option<Instant>,
+    /// Lotes fechados antecipadamente, aguardando envio.
+    ready: VecDeque<Vec<u8>>,
+}
+
+impl BufferedSender {
+    /// Cria um novo acumulador.
+    ///
+    /// ## Parâmetros
+    /// - `window`: Tempo máximo de espera de um pacote antes do envio.
+    /// - `byte_threshold`: Tamanho do lote que dispara o envio imediato.
+    /// - `mtu`: Tamanho máximo de cada lote, em bytes.
+    ///
+    /// ## Retorno
+    /// - `Self`: Um acumulador vazio.
+    pub fn new(window: Duration, byte_threshold: usize, mtu: usize) -> Self {
+        Self {
+            window,
+            byte_threshold,
+            mtu,
+            current: Vec::new(),
+            started_at: None,
+            ready: VecDeque::new(),
+        }
+    }
+
+    /// Acumula um pacote para envio, no instante de [`Instant::now`].
+    ///
+    /// Se o pacote não couber no lote atual sem ultrapassar o MTU, o lote atual é
+    /// fechado e o pacote inicia um novo lote. Quem passa a [`BufferedSender::flush_due`]
+    /// instantes de outro relógio deve usar [`BufferedSender::enqueue_at`].
+    ///
+    /// ## Parâmetros
+    /// - `packet`: Pacote a ser enviado.
+    ///
+    /// ## Retorno
+    /// - `Result<(), PacketError>`: [`PacketError::PacketTooLarge`] se o pacote sozinho não
+    ///   couber no MTU; nesse caso ele deve ser fragmentado antes.
+    pub fn enqueue(&mut self, packet: Packet) -> Result<(), PacketError> {
+        self.enqueue_at(packet, Instant::now())
+    }
+
+    /// Acumula um pacote para envio no instante informado, que inicia a janela de
+    /// tempo se o pacote é o primeiro do lote.
+    ///
+    /// ## Parâmetros
+    /// - `packet`: Pacote a ser enviado.
+    /// - `now`: Instante atual, do mesmo relógio passado a [`BufferedSender::flush_due`].
+    ///
+    /// ## Retorno
+    /// - `Result<(), PacketError>`: O mesmo de [`BufferedSender::enqueue`].
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use std::time::Duration;
+    /// use packet::Packet;
+    /// use packet::batch::{unpack_batch, BufferedSender};
+    /// use packet::clock::{Clock, MockClock};
+    ///
+    /// let clock = MockClock::new(0);
+    /// let mut sender = BufferedSender::new(Duration::from_millis(10), 1024, 1200);
+    /// sender.enqueue_at(Packet::new(1, 1, 7, vec![1]), clock.now()).unwrap();
+    ///
+    /// clock.advance(9);
+    /// sender.enqueue_at(Packet::new(1, 2, 7, vec![2]), clock.now()).unwrap();
+    /// assert!(sender.flush_due(clock.now()).is_none());
+    ///
+    /// // A janela conta do primeiro pacote do lote.
+    /// clock.advance(1);
+    /// let datagram = sender.flush_due(clock.now()).unwrap();
+    /// assert_eq!(unpack_batch(&datagram).unwrap().len(), 2);
+    /// ```
+    pub fn enqueue_at(&mut self, packet: Packet, now: Instant) -> Result<(), PacketError> {
+        let entry_len = batch_entry_len(&packet);
+        if entry_len > self.mtu {
+            return Err(PacketError::PacketTooLarge {
+                size: entry_len,
+                limit: self.mtu,
+            });
+        }
+
+        if self.current.len() + entry_len > self.mtu {
+            self.close_current();
+        }
+
+        push_entry(&mut self.current, &packet)?;
+        self.started_at.get_or_insert(now);
+        Ok(())
+    }
+
+    /// Retorna o próximo lote pronto para envio, se houver.
+    ///
+    /// Lotes fechados antecipadamente são retornados primeiro. O lote atual é
+    /// liberado quando sua janela de tempo expirou ou o limite de bytes foi atingido.
+    ///
+    /// ## Parâmetros
+    /// - `now`: Instante atual.
+    ///
+    /// ## Retorno
+    /// - `Option<Vec<u8>>`: Os bytes do lote ou `None` se nada está pronto.
+    pub fn flush_due(&mut self, now: Instant) -> Option<Vec<u8>> {
+        if let Some(batch) = self.ready.pop_front() {
+            return Some(batch);
+        }
+
+        let started_at = self.started_at?;
+        let expired = now.saturating_duration_since(started_at) >= self.window;
+        if expired || self.current.len() >= self.byte_threshold {
+            return self.take_current();
+        }
+        None
+    }
+
+    /// Libera imediatamente o próximo lote, ignorando a janela de tempo.
+    ///
+    /// ## Retorno
+    /// - `Option<Vec<u8>>`: Os bytes do lote ou `None` se não há pacotes acumulados.
+    pub fn flush(&mut self) -> Option<Vec<u8>> {
+        self.ready.pop_front().or_else(|| self.take_current())
+    }
+
+    /// Envia imediatamente uma sequência de pacotes, agrupando em cada datagrama
+    /// tantos pacotes quanto couberem no MTU.
+    ///
+    /// Os pacotes acumulados por [`BufferedSender::enqueue`] não são afetados. Assim
+    /// como [`io::Write::write`], o envio para no primeiro erro: se algum datagrama já
+    /// foi enviado, o retorno indica quantos pacotes saíram, e o restante pode ser
+    /// reenviado pelo chamador.
+    ///
+    /// ## Parâmetros
+    /// - `socket`: Socket usado para o envio.
+    /// - `packets`: Pacotes a serem enviados, na ordem de envio.
+    /// - `addr`: Endereço de destino.
+    ///
+    /// ## Retorno
+    /// - `io::Result<usize>`: Quantidade de pacotes enviados. Um pacote que sozinho não
+    ///   cabe no MTU resulta em [`io::ErrorKind::InvalidInput`].
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use std::net::UdpSocket;
+    /// use std::time::Duration;
+    /// use packet::Packet;
+    /// use packet::batch::{unpack_batch, BufferedSender};
+    ///
+    /// let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+    /// let client = UdpSocket::bind("127.0.0.1:0").unwrap();
+    ///
+    /// let packets: Vec<_> = (0..4).map(|seq| Packet::new(1, seq, 7, vec![0; 40])).collect();
+    /// // Cabem dois pacotes por datagrama.
+    /// let sender = BufferedSender::new(Duration::ZERO, 0, 200);
+    ///
+    /// let sent = sender.send_batch(&server, &packets, client.local_addr().unwrap()).unwrap();
+    /// assert_eq!(sent, 4);
+    ///
+    /// let mut buffer = [0u8; 1500];
+    /// let len = client.recv(&mut buffer).unwrap();
+    /// assert_eq!(unpack_batch(&buffer[..len]).unwrap(), packets[..2]);
+    /// ```
+    pub fn send_batch(
+        &self,
+        socket: &UdpSocket,
+        packets: &[Packet],
+        addr: SocketAddr,
+    ) -> io::Result<usize> {
+        let mut sent = 0;
+        while sent < packets.len() {
+            match self.send_datagram(socket, &packets[sent..], addr) {
+                Ok(count) => sent += count,
+                Err(_) if sent > 0 => break,
+                Err(error) => return Err(error),
+            }
+        }
+        Ok(sent)
+    }
+
+    /// Indica se não há pacotes acumulados.
+    pub fn is_empty(&self) -> bool {
+        self.ready.is_empty() && self.current.is_empty()
+    }
+
+    /// Envia em um único datagrama o maior prefixo de `packets` que cabe no MTU.
+    fn send_datagram(
+        &self,
+        socket: &UdpSocket,
+        packets: &[Packet],
+        addr: SocketAddr,
+    ) -> io::Result<usize> {
+        let count = fitting_prefix_len(packets, self.mtu);
+        if count == 0 {
+            let error = PacketError::PacketTooLarge {
+                size: batch_entry_len(&packets[0]),
+                limit: self.mtu,
+            };
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, error));
+        }
+
+        let datagram = pack_batch(&packets[..count])
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?;
+        socket.send_to(&datagram, addr)?;
+        Ok(count)
+    }
+
+    fn close_current(&mut self) {
+        if let Some(batch) = self.take_current() {
+            self.ready.push_back(batch);
+        }
+    }
+
+    fn take_current(&mut self) -> Option<Vec<u8>> {
+        if self.current.is_empty() {
+            return None;
+        }
+        self.started_at = None;
+        Some(std::mem::take(&mut self.current))
+    }
+}
+
+/// Transporte UDP que agrupa os pacotes de cada destino em lotes de até um MTU.
+///
+/// Os pacotes de [`Transport::send_packet`] são acumulados no lote do seu destino. Um
+/// lote é enviado quando o próximo pacote para o mesmo destino não cabe nele, ou por
+/// [`BatchTransport::flush`], que deve ser chamado ao fim de cada tick. A recepção
+/// devolve os pacotes de cada datagrama um a um, na ordem do lote, verificando o
+/// checksum de cada um e os limites de
+/// [`UdpTransport::parse_config`](crate::transport::UdpTransport::parse_config).
+///
+/// ## Exemplos
+/// ```rust
+/// use packet::Packet;
+/// use packet::batch::BatchTransport;
+/// use packet::transport::{Transport, UdpTransport};
+///
+/// let mut server = BatchTransport::new(UdpTransport::bind("127.0.0.1:0").unwrap(), 1200);
+/// let mut client = BatchTransport::new(UdpTransport::bind("127.0.0.1:0").unwrap(), 1200);
+/// let server_addr = server.get_ref().local_addr().unwrap();
+///
+/// for sequence in 0..3 {
+///     let packet = Packet::new(1, sequence, 7, vec![0; 16]);
+///     // Só acumulado; nada é enviado ainda.
+///     assert_eq!(client.send_packet(server_addr, &packet).unwrap(), 0);
+/// }
+/// // Os três pacotes saem em um único datagrama.
+/// assert_eq!(client.flush().unwrap(), 1);
+///
+/// for sequence in 0..3 {
+///     let (_, packet) = server.recv_packet().unwrap();
+///     assert_eq!(packet.header.sequence, sequence);
+/// }
+/// ```
+#[derive(Debug)]
+pub struct BatchTransport {
+    /// Transporte subjacente.
+    transport: UdpTransport,
+    /// Tamanho máximo de cada datagrama, em bytes.
+    mtu: usize,
+    /// Lotes em construção, por destino, na ordem do primeiro pacote.
+    pending: Vec<(SocketAddr, Vec<u8>)>,
+    /// Pacotes já recebidos e ainda não entregues, com o resultado da verificação.
+    received: VecDeque<(SocketAddr, Result<Packet, PacketError>)>,
+    /// Buffer reutilizado a cada recepção.
+    buffer: Vec<u8>,
+}
+
+impl BatchTransport {
+    /// Envolve um transporte, agrupando os envios em lotes de até `mtu` bytes.
+    ///
+    /// ## Parâmetros
+    /// - `transport`: Transporte subjacente.
+    /// - `mtu`: Tamanho máximo de cada datagrama, em bytes.
+    ///
+    /// ## Retorno
+    /// - `Self`: O transporte, sem lotes pendentes.
+    pub fn new(transport: UdpTransport, mtu: usize) -> Self {
+        Self {
+            transport,
+            mtu,
+            pending: Vec::new(),
+            received: VecDeque::new(),
+            buffer: vec![0; recommended_recv_buffer_size()],
+        }
+    }
+
+    /// Retorna o transporte subjacente.
+    pub fn get_ref(&self) -> &UdpTransport {
+        &self.transport
+    }
+
+    /// Retorna o transporte subjacente, descartando os lotes não enviados e os pacotes
+    /// recebidos e não entregues.
+    pub fn into_inner(self) -> UdpTransport {
+        self.transport
+    }
+
+    /// Retorna o tamanho máximo de cada datagrama.
+    pub fn mtu(&self) -> usize {
+        self.mtu
+    }
+
+    /// Define o tamanho máximo dos próximos datagramas, como o descoberto por
+    /// [`MtuDiscovery`](crate::mtu::MtuDiscovery); os lotes pendentes já montados não
+    /// são divididos.
+    pub fn set_mtu(&mut self, mtu: usize) {
+        self.mtu = mtu;
+    }
+
+    /// Retorna a quantidade de destinos com um lote pendente.
+    pub fn pending_peers(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Envia os lotes pendentes de todos os destinos.
+    ///
+    /// Como em [`BufferedSender::send_batch`], o envio para no primeiro erro; os lotes
+    /// ainda não enviados continuam pendentes.
+    ///
+    /// ## Retorno
+    /// - `Result<usize, PacketError>`: Quantidade de datagramas enviados, ou
+    ///   [`PacketError::Io`] com o erro do sistema.
+    pub fn flush(&mut self) -> Result<usize, PacketError> {
+        let mut sent = 0;
+        while let Some((addr, batch)) = self.pending.first() {
+            self.transport.get_ref().send_to(batch, *addr)?;
+            self.pending.remove(0);
+            sent += 1;
+        }
+        Ok(sent)
+    }
+
+    /// Interpreta o datagrama de `len` bytes no buffer, guardando os pacotes além do
+    /// primeiro para as próximas recepções.
+    fn unpack(&mut self, from: SocketAddr, len: usize) -> Result<Packet, PacketError> {
+        let limits = self.transport.parse_config();
+        let mut packets = unpack_datagram_with_limits(&self.buffer[..len], limits)?.into_iter();
+        let first = packets.next().ok_or(PacketError::TooShort {
+            expected: PacketHeader::SIZE,
+            actual: 0,
+        })?;
+        for packet in packets {
+            let checked = packet.check_checksum().map(|()| packet);
+            self.received.push_back((from, checked));
+        }
+        first.check_checksum()?;
+        Ok(first)
+    }
+}
+
+impl Transport for BatchTransport {
+    /// Acumula um pacote no lote do destino.
+    ///
+    /// Se o pacote não cabe no lote pendente, esse lote é enviado antes e o pacote
+    /// inicia um novo.
+    ///
+    /// ## Retorno
+    /// - `Result<usize, PacketError>`: Quantidade de bytes enviados agora, zero se o
+    ///   pacote só foi acumulado; [`PacketError::PacketTooLarge`] se o pacote sozinho
+    ///   não cabe no MTU, ou [`PacketError::Io`] se o envio do lote anterior falhou.
+    fn send_packet(&mut self, addr: SocketAddr, packet: &Packet) -> Result<usize, PacketError> {
+        let entry_len = batch_entry_len(packet);
+        if entry_len > self.mtu {
+            return Err(PacketError::PacketTooLarge {
+                size: entry_len,
+                limit: self.mtu,
+            });
+        }
+
+        let mut sent = 0;
+        let index = match self.pending.iter().position(|(peer, _)| *peer == addr) {
+            Some(index) if self.pending[index].1.len() + entry_len > self.mtu => {
+                sent = self
+                    .transport
+                    .get_ref()
+                    .send_to(&self.pending[index].1, addr)?;
+                self.pending[index].1.clear();
+                index
+            }
+            Some(index) => index,
+            None => {
+                self.pending.push((addr, Vec::with_capacity(self.mtu)));
+                self.pending.len() - 1
+            }
+        };
+        push_entry(&mut self.pending[index].1, packet)?;
+        Ok(sent)
+    }
+
+    /// Recebe o próximo pacote, de um lote ou de um datagrama com um pacote isolado.
+    ///
+    /// ## Retorno
+    /// - `Result<(SocketAddr, Packet), PacketError>`: A origem e o pacote; o erro de
+    ///   interpretação do datagrama, o de checksum de um pacote do lote, que não impede
+    ///   a entrega dos demais, ou [`PacketError::Io`] se a recepção falhou.
+    fn recv_packet(&mut self) -> Result<(SocketAddr, Packet), PacketError> {
+        if let Some((from, packet)) = self.received.pop_front() {
+            return packet.map(|packet| (from, packet));
+        }
+
+        let (len, from) = self.transport.get_ref().recv_from(&mut self.buffer)?;
+        self.unpack(from, len).map(|packet| (from, packet))
+    }
+
+    fn recv_from(&mut self) -> io::Result<Option<(SocketAddr, Result<Packet, PacketError>)>> {
+        if let Some(received) = self.received.pop_front() {
+            return Ok(Some(received));
+        }
+
+        match self.transport.get_ref().recv_from(&mut self.buffer) {
+            Ok((len, from)) => Ok(Some((from, self.unpack(from, len)))),
+            Err(error) if error.kind() == io::ErrorKind::WouldBlock => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+
+    fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.transport.local_addr()
+    }
+
+    fn set_nonblocking(&mut self, nonblocking: bool) -> io::Result<()> {
+        self.transport.get_ref().set_nonblocking(nonblocking)
+    }
+}
@@ -0,0 +1,84 @@
+//! Comparação dos algoritmos de checksum e dos caminhos da soma de bytes.
+//!
+//! Mede [`ChecksumAlgorithm::compute`] com cada algoritmo e a soma byte a byte usada
+//! antes, como referência para a soma em palavras de 64 bits de
+//! [`ChecksumAlgorithm::ByteSum`], com payloads de 16 B até 64 KiB; o tamanho de
+//! 1.200 B é o [`DEFAULT_MTU`]. O grupo `packet` mede o ciclo completo de um pacote:
+//! serializar, ler e verificar o checksum.
+//!
+//! Execute com `cargo bench --bench checksum`.
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use packet::checksum::{ChecksumAlgorithm, Crc32Variant, BYTE_SUM_SEED};
+use packet::{Packet, DEFAULT_MTU, MAX_PAYLOAD_SIZE};
+
+/// Tamanhos de payload medidos; o último é limitado ao máximo aceito pelo pacote.
+const PAYLOAD_SIZES: [usize; 6] = [16, 64, 256, DEFAULT_MTU, 4 * 1024, 64 * 1024];
+
+const ALGORITHMS: [(&str, ChecksumAlgorithm); 4] = [
+    ("byte_sum", ChecksumAlgorithm::ByteSum),
+    ("crc32", ChecksumAlgorithm::Crc32(Crc32Variant::Ieee)),
+    ("crc32c", ChecksumAlgorithm::Crc32(Crc32Variant::Castagnoli)),
+    ("xxhash32", ChecksumAlgorithm::XxHash32),
+];
+
+/// Soma byte a byte, equivalente a [`ChecksumAlgorithm::ByteSum`].
+fn scalar_byte_sum(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(BYTE_SUM_SEED, |sum, &b| {
+        sum.wrapping_add(1).wrapping_add(b as u32)
+    })
+}
+
+/// Cria um payload do tamanho informado.
+fn sample_payload(size: usize) -> Vec<u8> {
+    let size = size.min(MAX_PAYLOAD_SIZE);
+    (0..size).map(|i| (i * 31) as u8).collect()
+}
+
+fn algorithms(c: &mut Criterion) {
+    let mut group = c.benchmark_group("algorithms");
+    for size in PAYLOAD_SIZES {
+        let payload = sample_payload(size);
+        group.throughput(Throughput::Bytes(payload.len() as u64));
+        assert_eq!(
+            ChecksumAlgorithm::ByteSum.compute(&payload),
+            scalar_byte_sum(&payload)
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("byte_sum/scalar", size),
+            &payload,
+            |b, payload| b.iter(|| scalar_byte_sum(black_box(payload))),
+        );
+        for (name, algorithm) in ALGORITHMS {
+            group.bench_with_input(BenchmarkId::new(name, size), &payload, |b, payload| {
+                b.iter(|| black_box(algorithm).compute(black_box(payload)))
+            });
+        }
+    }
+    group.finish();
+}
+
+fn packet(c: &mut Criterion) {
+    let mut group = c.benchmark_group("packet");
+    for size in PAYLOAD_SIZES {
+        for (name, algorithm) in ALGORITHMS {
+            let packet =
+                Packet::new(1, 1, 7, sample_payload(size)).with_checksum_algorithm(algorithm);
+            group.throughput(Throughput::Bytes(packet.serialized_len() as u64));
+            group.bench_with_input(BenchmarkId::new(name, size), &packet, |b, packet| {
+                b.iter(|| {
+                    let bytes = black_box(packet).to_bytes();
+                    let received = Packet::from_bytes(black_box(&bytes)).unwrap();
+                    received.verify_checksum()
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, algorithms, packet);
+criterion_main!(benches);
@@ -0,0 +1,155 @@
+//! Comparação das estratégias de serialização e leitura de pacotes.
+//!
+//! Mede [`Packet::to_bytes`], [`Packet::to_bytes_into`], [`Packet::from_bytes`] e
+//! [`PacketRef::from_bytes`] com payloads de 16 B, 256 B, 4 KiB e 64 KiB. Além dos
+//! tempos do `criterion`, cada caso imprime quantas alocações uma operação faz,
+//! contadas por um alocador global instrumentado. O grupo `checksum` compara a
+//! verificação com cada [`ChecksumScope`], inclusive o custo evitado por
+//! [`ChecksumScope::None`].
+//!
+//! Execute com `cargo bench --bench serialization`.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use packet::checksum::ChecksumScope;
+use packet::view::PacketRef;
+use packet::{Packet, MAX_PAYLOAD_SIZE};
+
+/// Tamanhos de payload medidos; o último é limitado ao máximo aceito pelo pacote.
+const PAYLOAD_SIZES: [usize; 4] = [16, 256, 4 * 1024, 64 * 1024];
+
+/// Repetições usadas para calcular a média de alocações por operação.
+const ALLOCATION_SAMPLES: usize = 1_000;
+
+/// Alocador do sistema que conta as alocações feitas.
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Imprime a média de alocações por chamada de `operation`.
+fn report_allocations(name: &str, size: usize, mut operation: impl FnMut()) {
+    operation();
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    for _ in 0..ALLOCATION_SAMPLES {
+        operation();
+    }
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+    println!(
+        "{name}/{size}: {:.2} alocações por operação",
+        allocations as f64 / ALLOCATION_SAMPLES as f64
+    );
+}
+
+/// Cria o pacote medido, com um payload do tamanho informado.
+fn sample_packet(size: usize) -> Packet {
+    let size = size.min(MAX_PAYLOAD_SIZE);
+    let payload = (0..size).map(|i| i as u8).collect();
+    Packet::new(1, 1, 7, payload)
+}
+
+fn serialization(c: &mut Criterion) {
+    let mut group = c.benchmark_group("serialization");
+    for size in PAYLOAD_SIZES {
+        let packet = sample_packet(size);
+        group.throughput(Throughput::Bytes(packet.serialized_len() as u64));
+
+        report_allocations("to_bytes", size, || {
+            black_box(black_box(&packet).to_bytes());
+        });
+        group.bench_with_input(BenchmarkId::new("to_bytes", size), &packet, |b, packet| {
+            b.iter(|| black_box(packet).to_bytes())
+        });
+
+        let mut buffer = Vec::with_capacity(packet.serialized_len());
+        report_allocations("to_bytes_into", size, || {
+            buffer.clear();
+            black_box(&packet).to_bytes_into(&mut buffer);
+            black_box(&buffer);
+        });
+        group.bench_with_input(
+            BenchmarkId::new("to_bytes_into", size),
+            &packet,
+            |b, packet| {
+                b.iter(|| {
+                    buffer.clear();
+                    black_box(packet).to_bytes_into(&mut buffer);
+                    black_box(&buffer);
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn parsing(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parsing");
+    for size in PAYLOAD_SIZES {
+        let bytes = sample_packet(size).to_bytes();
+        group.throughput(Throughput::Bytes(bytes.len() as u64));
+
+        report_allocations("Packet::from_bytes", size, || {
+            black_box(Packet::from_bytes(black_box(&bytes)).unwrap());
+        });
+        group.bench_with_input(
+            BenchmarkId::new("Packet::from_bytes", size),
+            &bytes,
+            |b, bytes| b.iter(|| Packet::from_bytes(black_box(bytes)).unwrap()),
+        );
+
+        report_allocations("PacketRef::from_bytes", size, || {
+            black_box(PacketRef::from_bytes(black_box(&bytes)).unwrap());
+        });
+        group.bench_with_input(
+            BenchmarkId::new("PacketRef::from_bytes", size),
+            &bytes,
+            |b, bytes| b.iter(|| PacketRef::from_bytes(black_box(bytes)).unwrap()),
+        );
+    }
+    group.finish();
+}
+
+fn checksum(c: &mut Criterion) {
+    let mut group = c.benchmark_group("checksum");
+    let scopes = [
+        ("payload", ChecksumScope::Payload),
+        ("header_and_payload", ChecksumScope::HeaderAndPayload),
+        ("none", ChecksumScope::None),
+    ];
+    for size in PAYLOAD_SIZES {
+        for (name, scope) in scopes {
+            let packet = sample_packet(size).with_checksum_scope(scope);
+            group.throughput(Throughput::Bytes(packet.serialized_len() as u64));
+            group.bench_with_input(
+                BenchmarkId::new(format!("verify/{name}"), size),
+                &packet,
+                |b, packet| b.iter(|| black_box(packet).verify_checksum()),
+            );
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, serialization, parsing, checksum);
+criterion_main!(benches);
@@ -0,0 +1,141 @@
+//! # Módulo `bits`
+//!
+//! Escrita e leitura de valores com largura arbitrária em bits, para payloads
+//! compactos.
+//!
+//! Os bits são gravados do menos significativo para o mais significativo, começando
+//! pelo bit `0` do primeiro byte; o último byte é completado com zeros.
+//!
+//! ## Exemplos
+//! ```rust
+//! use packet::bits::{BitReader, BitWriter};
+//!
+//! let mut writer = BitWriter::new();
+//! writer.write_bits(0b101, 3);
+//! writer.write_bool(true);
+//! writer.write_bits(0xABC, 12);
+//! let bytes = writer.finish();
+//! assert_eq!(bytes.len(), 2);
+//!
+//! let mut reader = BitReader::new(&bytes);
+//! assert_eq!(reader.read_bits(3), Ok(0b101));
+//! assert_eq!(reader.read_bool(), Ok(true));
+//! assert_eq!(reader.read_bits(12), Ok(0xABC));
+//! assert!(reader.read_bits(1).is_err());
+//! ```
+
+use crate::PacketError;
+
+/// Acumula valores bit a bit em um vetor de bytes.
+#[derive(Debug, Clone, Default)]
+pub struct BitWriter {
+    /// Bytes gravados; o último pode estar incompleto.
+    bytes: Vec<u8>,
+    /// Quantidade de bits gravados.
+    bit_len: usize,
+}
+
+impl BitWriter {
+    /// Cria um escritor vazio.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Grava os `count` bits menos significativos de `value`.
+    ///
+    /// ## Parâmetros
+    /// - `value`: Valor a gravar; os bits acima de `count` são ignorados.
+    /// - `count`: Quantidade de bits, de `0` a `64`.
+    ///
+    /// ## Pânico
+    /// Se `count` é maior que `64`.
+    pub fn write_bits(&mut self, value: u64, count: u32) {
+        assert!(count <= 64, "no máximo 64 bits por escrita");
+        for bit in 0..count {
+            if self.bit_len.is_multiple_of(8) {
+                self.bytes.push(0);
+            }
+            if value >> bit & 1 != 0 {
+                *self
+                    .bytes
+                    .last_mut()
+                    .expect("byte acabou de ser acrescentado") |= 1 << (self.bit_len % 8);
+            }
+            self.bit_len += 1;
+        }
+    }
+
+    /// Grava um único bit.
+    pub fn write_bool(&mut self, value: bool) {
+        self.write_bits(value as u64, 1);
+    }
+
+    /// Retorna a quantidade de bits gravados.
+    pub fn bit_len(&self) -> usize {
+        self.bit_len
+    }
+
+    /// Encerra a escrita.
+    ///
+    /// ## Retorno
+    /// - `Vec<u8>`: Os bytes gravados, com o último completado com zeros.
+    pub fn finish(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+/// Lê valores bit a bit de bytes gravados por [`BitWriter`].
+#[derive(Debug, Clone)]
+pub struct BitReader<'a> {
+    /// Bytes lidos.
+    bytes: &'a [u8],
+    /// Posição do próximo bit.
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    /// Inicia a leitura no primeiro bit de `bytes`.
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, position: 0 }
+    }
+
+    /// Lê `count` bits como um valor sem sinal.
+    ///
+    /// ## Parâmetros
+    /// - `count`: Quantidade de bits, de `0` a `64`.
+    ///
+    /// ## Retorno
+    /// - `Result<u64, PacketError>`: O valor lido, ou [`PacketError::InvalidPayload`]
+    ///   se restam menos de `count` bits; nesse caso a posição não avança.
+    ///
+    /// ## Pânico
+    /// Se `count` é maior que `64`.
+    pub fn read_bits(&mut self, count: u32) -> Result<u64, PacketError> {
+        assert!(count <= 64, "no máximo 64 bits por leitura");
+        if self.remaining_bits() < count as usize {
+            return Err(PacketError::InvalidPayload {
+                reason: "payload termina antes dos bits esperados",
+            });
+        }
+
+        let mut value = 0;
+        for bit in 0..count {
+            let byte = self.bytes[self.position / 8];
+            if byte >> (self.position % 8) & 1 != 0 {
+                value |= 1 << bit;
+            }
+            self.position += 1;
+        }
+        Ok(value)
+    }
+
+    /// Lê um único bit.
+    pub fn read_bool(&mut self) -> Result<bool, PacketError> {
+        self.read_bits(1).map(|bit| bit != 0)
+    }
+
+    /// Retorna a quantidade de bits ainda não lidos, incluindo os de preenchimento.
+    pub fn remaining_bits(&self) -> usize {
+        self.bytes.len() * 8 - self.position
+    }
+}
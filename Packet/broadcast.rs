@@ -0,0 +1,122 @@
+//! # Módulo `broadcast`
+//!
+//! Envio de um mesmo pacote a todos os membros de um grupo (sala), como no
+//! roteamento de estado de jogos multijogador.
+
+use std::net::SocketAddr;
+
+use crate::checksum::ChecksumScope;
+use crate::Packet;
+
+/// Conjunto de membros de um grupo que recebem pacotes em comum.
+///
+/// ## Exemplos
+/// ```rust
+/// use packet::Packet;
+/// use packet::broadcast::Broadcaster;
+///
+/// let mut room = Broadcaster::new(9);
+/// room.add_member(1, "127.0.0.1:4001".parse().unwrap());
+/// room.add_member(2, "127.0.0.1:4002".parse().unwrap());
+///
+/// let datagrams = room.prepare(&Packet::new(1, 5, 0, vec![1, 2, 3]));
+/// assert_eq!(datagrams.len(), 2);
+///
+/// let (addr, bytes) = &datagrams[1];
+/// let packet = Packet::from_bytes(bytes).unwrap();
+/// assert_eq!(addr.port(), 4002);
+/// assert_eq!(packet.header.player_id, 2);
+/// assert_eq!(packet.header.group_id, 9);
+/// assert!(packet.verify_checksum());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Broadcaster {
+    /// Identificador do grupo.
+    group_id: u32,
+    /// Membros do grupo, na ordem em que entraram.
+    members: Vec<(u64, SocketAddr)>,
+}
+
+impl Broadcaster {
+    /// Cria um grupo sem membros.
+    ///
+    /// ## Parâmetros
+    /// - `group_id`: Identificador do grupo gravado em cada pacote enviado.
+    ///
+    /// ## Retorno
+    /// - `Self`: Um grupo vazio.
+    pub fn new(group_id: u32) -> Self {
+        Self {
+            group_id,
+            members: Vec::new(),
+        }
+    }
+
+    /// Retorna o identificador do grupo.
+    pub fn group_id(&self) -> u32 {
+        self.group_id
+    }
+
+    /// Adiciona um membro ao grupo, substituindo o endereço se o jogador já for membro.
+    ///
+    /// ## Parâmetros
+    /// - `player_id`: Identificador do jogador.
+    /// - `addr`: Endereço para onde os pacotes do jogador são enviados.
+    pub fn add_member(&mut self, player_id: u64, addr: SocketAddr) {
+        match self.members.iter_mut().find(|(id, _)| *id == player_id) {
+            Some(member) => member.1 = addr,
+            None => self.members.push((player_id, addr)),
+        }
+    }
+
+    /// Remove um membro do grupo.
+    ///
+    /// ## Parâmetros
+    /// - `player_id`: Identificador do jogador.
+    ///
+    /// ## Retorno
+    /// - `bool`: `true` se o jogador era membro.
+    pub fn remove_member(&mut self, player_id: u64) -> bool {
+        let before = self.members.len();
+        self.members.retain(|(id, _)| *id != player_id);
+        self.members.len() != before
+    }
+
+    /// Retorna os membros do grupo.
+    pub fn members(&self) -> &[(u64, SocketAddr)] {
+        &self.members
+    }
+
+    /// Produz um datagrama por membro a partir de um pacote modelo.
+    ///
+    /// Cada datagrama recebe o `player_id` do destinatário e o `group_id` do grupo.
+    /// Quando o checksum cobre apenas o payload, ele é calculado uma única vez no
+    /// modelo; com [`ChecksumScope::HeaderAndPayload`] ele é recalculado para cada
+    /// destinatário.
+    ///
+    /// ## Parâmetros
+    /// - `template`: Pacote modelo; seu `player_id` é ignorado.
+    ///
+    /// ## Retorno
+    /// - `Vec<(SocketAddr, Vec<u8>)>`: O endereço e os bytes a enviar para cada membro.
+    pub fn prepare(&self, template: &Packet) -> Vec<(SocketAddr, Vec<u8>)> {
+        let mut header = template.header.clone();
+        header.group_id = self.group_id;
+        let scope = header.checksum_scope();
+
+        self.members
+            .iter()
+            .map(|&(player_id, addr)| {
+                header.player_id = player_id;
+                if scope == ChecksumScope::HeaderAndPayload {
+                    header.recompute(&template.payload, scope);
+                } else {
+                    header.header_checksum = header.expected_header_checksum();
+                }
+                let mut bytes = header.to_bytes();
+                bytes.extend_from_slice(&template.payload);
+                (addr, bytes)
+            })
+            .collect()
+    }
+}
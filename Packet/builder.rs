@@ -0,0 +1,297 @@
+//! # Módulo `builder`
+//!
+//! Define [`PacketBuilder`], que permite montar o payload de um pacote
+//! incrementalmente e só então selá-lo, calculando `payload_size` e checksum uma
+//! única vez.
+//!
+//! [`Packet::builder`] inicia a montagem encadeada, em que os campos do cabeçalho, as
+//! flags e o payload são definidos por métodos `with_*`.
+//!
+//! ## Exemplos
+//! ```rust
+//! use std::io::Write;
+//! use packet::Packet;
+//! use packet::builder::PacketBuilder;
+//!
+//! let mut builder = PacketBuilder::new(1, 42, 12345);
+//! builder.extend([1u8, 2]);
+//! builder.write_all(&[3, 4]).unwrap();
+//! builder.extend(&[5u8]);
+//!
+//! let packet = builder.seal();
+//! assert_eq!(packet, Packet::new(1, 42, 12345, vec![1, 2, 3, 4, 5]));
+//! ```
+
+use std::io;
+
+use crate::checksum::ChecksumAlgorithm;
+use crate::config::ProtocolConfig;
+use crate::message::MessageType;
+use crate::options::OPTIONS_LENGTH_SIZE;
+use crate::{Packet, PacketFlags};
+
+/// Pacote "aberto", cujo payload ainda está sendo montado.
+#[derive(Debug, Clone, Default)]
+pub struct PacketBuilder {
+    /// Tipo da mensagem.
+    message_type: u8,
+    /// Sequência do pacote.
+    sequence: u32,
+    /// Identificador único do jogador.
+    player_id: u64,
+    /// Flags acrescentadas ao pacote ao selar.
+    pub(crate) flags: PacketFlags,
+    /// Opções TLV acrescentadas com [`PacketBuilder::add_option`].
+    pub(crate) options: Vec<u8>,
+    /// Payload montado até o momento.
+    payload: Vec<u8>,
+    /// Algoritmo de checksum aplicado ao selar; `None` mantém o padrão de [`Packet::new`].
+    checksum_algorithm: Option<ChecksumAlgorithm>,
+}
+
+impl PacketBuilder {
+    /// Inicia a montagem de um pacote com payload vazio.
+    ///
+    /// ## Parâmetros
+    /// - `message_type`: Tipo da mensagem.
+    /// - `sequence`: Sequência do pacote.
+    /// - `player_id`: Identificador único do jogador.
+    ///
+    /// ## Retorno
+    /// - `Self`: Um construtor aberto.
+    pub fn new(message_type: u8, sequence: u32, player_id: u64) -> Self {
+        Self {
+            message_type,
+            sequence,
+            player_id,
+            flags: PacketFlags::empty(),
+            options: Vec::new(),
+            payload: Vec::new(),
+            checksum_algorithm: None,
+        }
+    }
+
+    /// Aplica a configuração do protocolo ao pacote montado.
+    ///
+    /// Ao selar, o checksum é calculado com
+    /// [`ProtocolConfig::checksum_algorithm`](crate::config::ProtocolConfig::checksum_algorithm).
+    ///
+    /// ## Parâmetros
+    /// - `config`: Configuração do protocolo.
+    ///
+    /// ## Retorno
+    /// - `Self`: O construtor configurado.
+    pub fn with_config(mut self, config: &ProtocolConfig) -> Self {
+        self.checksum_algorithm = Some(config.checksum_algorithm);
+        self
+    }
+
+    /// Inicia um pacote de entrada do jogador ([`MessageType::INPUT`]).
+    ///
+    /// ## Parâmetros
+    /// - `player_id`: Identificador único do jogador.
+    /// - `sequence`: Sequência do pacote.
+    ///
+    /// ## Retorno
+    /// - `Self`: Um construtor aberto, com payload vazio.
+    pub fn input(player_id: u64, sequence: u32) -> Self {
+        Self::new(MessageType::INPUT.into(), sequence, player_id)
+    }
+
+    /// Inicia um pacote de estado do jogo ([`MessageType::STATE`]).
+    ///
+    /// ## Parâmetros
+    /// - `player_id`: Identificador único do jogador.
+    /// - `sequence`: Sequência do pacote.
+    ///
+    /// ## Retorno
+    /// - `Self`: Um construtor aberto, com payload vazio.
+    pub fn state(player_id: u64, sequence: u32) -> Self {
+        Self::new(MessageType::STATE.into(), sequence, player_id)
+    }
+
+    /// Inicia um pacote de chat ([`MessageType::CHAT`]) com o texto codificado em UTF-8.
+    ///
+    /// O texto pode ser lido de volta com [`Packet::chat_text`].
+    ///
+    /// ## Parâmetros
+    /// - `player_id`: Identificador único do jogador.
+    /// - `sequence`: Sequência do pacote.
+    /// - `text`: Texto da mensagem.
+    ///
+    /// ## Retorno
+    /// - `Self`: Um construtor aberto, com o texto no payload.
+    pub fn chat(player_id: u64, sequence: u32, text: &str) -> Self {
+        let mut builder = Self::new(MessageType::CHAT.into(), sequence, player_id);
+        builder.payload.extend_from_slice(text.as_bytes());
+        builder
+    }
+
+    /// Define o tipo da mensagem.
+    ///
+    /// ## Parâmetros
+    /// - `message_type`: Tipo da mensagem, como um [`MessageType`] ou seu byte.
+    ///
+    /// ## Retorno
+    /// - `Self`: O construtor com o novo tipo.
+    pub fn with_message_type(mut self, message_type: impl Into<u8>) -> Self {
+        self.message_type = message_type.into();
+        self
+    }
+
+    /// Define a sequência do pacote.
+    ///
+    /// ## Parâmetros
+    /// - `sequence`: Sequência do pacote.
+    ///
+    /// ## Retorno
+    /// - `Self`: O construtor com a nova sequência.
+    pub fn with_sequence(mut self, sequence: u32) -> Self {
+        self.sequence = sequence;
+        self
+    }
+
+    /// Define o identificador do jogador.
+    ///
+    /// ## Parâmetros
+    /// - `player_id`: Identificador único do jogador.
+    ///
+    /// ## Retorno
+    /// - `Self`: O construtor com o novo jogador.
+    pub fn with_player_id(mut self, player_id: u64) -> Self {
+        self.player_id = player_id;
+        self
+    }
+
+    /// Acrescenta flags ao pacote selado.
+    ///
+    /// O checksum é calculado ao selar de acordo com as flags, de modo que
+    /// [`PacketFlags::CRC32`] ou [`PacketFlags::HEADER_CHECKSUM`] também escolhem o
+    /// algoritmo e o escopo. [`PacketFlags::COMPRESSED`] só marca o pacote; use
+    /// [`PacketBuilder::mark_compressed`] para registrar também o tamanho original.
+    ///
+    /// ## Parâmetros
+    /// - `flags`: Flags a acrescentar, combináveis com `|`.
+    ///
+    /// ## Retorno
+    /// - `Self`: O construtor com as flags acrescentadas.
+    pub fn with_flag(mut self, flags: PacketFlags) -> Self {
+        self.flags.insert(flags);
+        self
+    }
+
+    /// Substitui o payload montado até o momento.
+    ///
+    /// ## Parâmetros
+    /// - `payload`: Novo payload.
+    ///
+    /// ## Retorno
+    /// - `Self`: O construtor com o novo payload.
+    pub fn with_payload(mut self, payload: impl Into<Vec<u8>>) -> Self {
+        self.payload = payload.into();
+        self
+    }
+
+    /// Retorna o payload montado até o momento.
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+
+    /// Finaliza o pacote, calculando `payload_size` e checksum sobre o payload montado.
+    ///
+    /// ## Retorno
+    /// - `Packet`: O pacote selado; com a flag [`PacketFlags::OPTIONS`] e a área de
+    ///   opções antes do payload se alguma opção foi acrescentada.
+    pub fn seal(self) -> Packet {
+        let checksum_algorithm = self.checksum_algorithm;
+        let packet = self.seal_with_default_checksum();
+        match checksum_algorithm {
+            Some(algorithm) => packet.with_checksum_algorithm(algorithm),
+            None => packet,
+        }
+    }
+
+    /// Sela o pacote com o checksum padrão de [`Packet::new`].
+    fn seal_with_default_checksum(self) -> Packet {
+        if self.options.is_empty() && self.flags.is_empty() {
+            return Packet::new(
+                self.message_type,
+                self.sequence,
+                self.player_id,
+                self.payload,
+            );
+        }
+
+        let mut flags = self.flags;
+        let payload = if self.options.is_empty() {
+            self.payload
+        } else {
+            flags.insert(PacketFlags::OPTIONS);
+            let mut payload =
+                Vec::with_capacity(OPTIONS_LENGTH_SIZE + self.options.len() + self.payload.len());
+            payload.extend_from_slice(&(self.options.len() as u16).to_le_bytes());
+            payload.extend_from_slice(&self.options);
+            payload.extend_from_slice(&self.payload);
+            payload
+        };
+
+        let mut packet = Packet::new(self.message_type, self.sequence, self.player_id, payload);
+        packet.header.flags.insert(flags);
+        packet.recompute_checksum();
+        packet
+    }
+}
+
+impl Packet {
+    /// Inicia a montagem encadeada de um pacote, com tipo, sequência e jogador zerados
+    /// e payload vazio.
+    ///
+    /// ## Retorno
+    /// - `PacketBuilder`: Um construtor aberto.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::{Packet, PacketFlags};
+    /// use packet::checksum::{ChecksumAlgorithm, Crc32Variant};
+    /// use packet::message::MessageType;
+    ///
+    /// let packet = Packet::builder()
+    ///     .with_message_type(MessageType::STATE)
+    ///     .with_sequence(3)
+    ///     .with_player_id(42)
+    ///     .with_flag(PacketFlags::RELIABLE | PacketFlags::CRC32)
+    ///     .with_payload([1, 2, 3])
+    ///     .seal();
+    ///
+    /// assert_eq!(packet.header.player_id, 42);
+    /// assert!(packet.header.flags.contains(PacketFlags::RELIABLE));
+    /// assert_eq!(packet.checksum_algorithm(), ChecksumAlgorithm::Crc32(Crc32Variant::Ieee));
+    /// assert!(Packet::from_bytes(&packet.to_bytes()).is_ok());
+    /// ```
+    pub fn builder() -> PacketBuilder {
+        PacketBuilder::default()
+    }
+}
+
+impl Extend<u8> for PacketBuilder {
+    fn extend<I: IntoIterator<Item = u8>>(&mut self, iter: I) {
+        self.payload.extend(iter);
+    }
+}
+
+impl<'a> Extend<&'a u8> for PacketBuilder {
+    fn extend<I: IntoIterator<Item = &'a u8>>(&mut self, iter: I) {
+        self.payload.extend(iter);
+    }
+}
+
+impl io::Write for PacketBuilder {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.payload.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,464 @@
+//! # Módulo `capture`
+//!
+//! Gravação dos pacotes de uma sessão em arquivo e repetição da gravação, para
+//! reproduzir dessincronizações de forma determinística.
+//!
+//! [`CaptureTransport`] envolve outro [`Transport`] e grava, com [`CaptureWriter`],
+//! cada pacote enviado e recebido com o tempo decorrido desde o início da gravação.
+//! [`CaptureReader`] lê a gravação de volta, e [`ReplayTransport`] entrega os pacotes
+//! recebidos gravados pelo caminho de recepção, no ritmo original ou o mais rápido
+//! possível, guardando o que a aplicação envia para comparação com o envio gravado.
+//!
+//! O arquivo começa com [`CAPTURE_MAGIC`], seguido dos registros, todos
+//! little-endian:
+//!
+//! | Tamanho | Campo                                                   |
+//! |---------|---------------------------------------------------------|
+//! | `1`     | Direção: `0` recebido, `1` enviado                      |
+//! | `8`     | Tempo decorrido, em microssegundos                      |
+//! | `19`    | Endereço: família (`4` ou `6`), 16 bytes de IP e a porta |
+//! | `4`     | Tamanho do pacote serializado                           |
+//! | —       | Pacote serializado                                      |
+//!
+//! ## Exemplos
+//! ```rust
+//! use std::io::Cursor;
+//! use std::net::UdpSocket;
+//! use packet::Packet;
+//! use packet::capture::{CaptureReader, CaptureTransport, ReplayMode, ReplayTransport};
+//! use packet::transport::{Transport, UdpTransport};
+//!
+//! let mut server = UdpTransport::bind("127.0.0.1:0").unwrap();
+//! let peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+//! let server_addr = server.local_addr().unwrap();
+//! peer.send_to(&Packet::new(1, 1, 7, vec![1, 2, 3]).to_bytes(), server_addr).unwrap();
+//!
+//! // Grava uma recepção e uma resposta.
+//! let mut capture = CaptureTransport::new(server, Vec::new()).unwrap();
+//! let (from, packet) = capture.recv_packet().unwrap();
+//! capture.send_packet(from, &Packet::new(2, 1, 7, packet.payload.clone())).unwrap();
+//! let file = capture.into_inner().1;
+//!
+//! // Repete a gravação sem rede.
+//! let reader = CaptureReader::new(Cursor::new(file)).unwrap();
+//! let mut replay = ReplayTransport::from_reader(reader, ReplayMode::AsFastAsPossible).unwrap();
+//! let (replayed_from, replayed) = replay.recv_packet().unwrap();
+//! assert_eq!((replayed_from, &replayed), (from, &packet));
+//! assert!(replay.recv_packet().is_err());
+//! assert!(replay.is_finished());
+//! ```
+
+use std::collections::VecDeque;
+use std::io::{self, Read, Write};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::{Duration, Instant};
+
+use crate::transport::Transport;
+use crate::{Packet, PacketError};
+
+/// Assinatura no início de um arquivo de gravação.
+pub const CAPTURE_MAGIC: [u8; 8] = *b"PKTCAP01";
+
+/// Tamanho dos campos de um registro antes do pacote.
+const RECORD_HEADER_SIZE: usize = 32;
+
+/// Sentido de um pacote gravado.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// Recebido do endereço gravado.
+    Inbound,
+    /// Enviado ao endereço gravado.
+    Outbound,
+}
+
+/// Pacote gravado.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaptureRecord {
+    /// Sentido do pacote.
+    pub direction: Direction,
+    /// Tempo decorrido desde o início da gravação.
+    pub elapsed: Duration,
+    /// Origem, nos recebidos, ou destino, nos enviados.
+    pub addr: SocketAddr,
+    /// Pacote enviado ou recebido.
+    pub packet: Packet,
+}
+
+/// Escreve registros de gravação em um fluxo.
+#[derive(Debug)]
+pub struct CaptureWriter<W> {
+    /// Fluxo de destino.
+    writer: W,
+}
+
+impl<W: Write> CaptureWriter<W> {
+    /// Inicia uma gravação, escrevendo [`CAPTURE_MAGIC`].
+    ///
+    /// ## Parâmetros
+    /// - `writer`: Fluxo de destino, em geral um `BufWriter<File>`.
+    ///
+    /// ## Retorno
+    /// - `io::Result<Self>`: O gravador, ou o erro da escrita.
+    pub fn new(mut writer: W) -> io::Result<Self> {
+        writer.write_all(&CAPTURE_MAGIC)?;
+        Ok(Self { writer })
+    }
+
+    /// Acrescenta um registro.
+    ///
+    /// ## Parâmetros
+    /// - `record`: Pacote gravado.
+    ///
+    /// ## Retorno
+    /// - `io::Result<()>`: O erro da escrita, se houver.
+    pub fn write_record(&mut self, record: &CaptureRecord) -> io::Result<()> {
+        let bytes = record.packet.to_bytes();
+        let mut header = [0; RECORD_HEADER_SIZE];
+        header[0] = match record.direction {
+            Direction::Inbound => 0,
+            Direction::Outbound => 1,
+        };
+        let micros = u64::try_from(record.elapsed.as_micros()).unwrap_or(u64::MAX);
+        header[1..9].copy_from_slice(&micros.to_le_bytes());
+        match record.addr.ip() {
+            IpAddr::V4(ip) => {
+                header[9] = 4;
+                header[10..14].copy_from_slice(&ip.octets());
+            }
+            IpAddr::V6(ip) => {
+                header[9] = 6;
+                header[10..26].copy_from_slice(&ip.octets());
+            }
+        }
+        header[26..28].copy_from_slice(&record.addr.port().to_le_bytes());
+        header[28..32].copy_from_slice(&(bytes.len() as u32).to_le_bytes());
+        self.writer.write_all(&header)?;
+        self.writer.write_all(&bytes)
+    }
+
+    /// Descarrega o fluxo de destino.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Retorna o fluxo de destino.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Lê os registros de uma gravação.
+///
+/// Também é um [`Iterator`] de `io::Result<CaptureRecord>`.
+#[derive(Debug)]
+pub struct CaptureReader<R> {
+    /// Fluxo de origem.
+    reader: R,
+}
+
+impl<R: Read> CaptureReader<R> {
+    /// Abre uma gravação, verificando [`CAPTURE_MAGIC`].
+    ///
+    /// ## Parâmetros
+    /// - `reader`: Fluxo de origem, em geral um `BufReader<File>`.
+    ///
+    /// ## Retorno
+    /// - `io::Result<Self>`: O leitor, ou [`io::ErrorKind::InvalidData`] se o fluxo não
+    ///   começa com a assinatura.
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        let mut magic = [0; CAPTURE_MAGIC.len()];
+        reader.read_exact(&mut magic)?;
+        if magic != CAPTURE_MAGIC {
+            return Err(invalid_data("assinatura de gravação inválida"));
+        }
+        Ok(Self { reader })
+    }
+
+    /// Lê o próximo registro.
+    ///
+    /// ## Retorno
+    /// - `io::Result<Option<CaptureRecord>>`: O registro, `None` no fim da gravação,
+    ///   [`io::ErrorKind::UnexpectedEof`] se ela termina no meio de um registro, ou
+    ///   [`io::ErrorKind::InvalidData`] com o [`PacketError`] de um registro inválido.
+    pub fn read_record(&mut self) -> io::Result<Option<CaptureRecord>> {
+        let mut header = [0; RECORD_HEADER_SIZE];
+        match self.reader.read(&mut header[..1])? {
+            0 => return Ok(None),
+            _ => self.reader.read_exact(&mut header[1..])?,
+        }
+        let direction = match header[0] {
+            0 => Direction::Inbound,
+            1 => Direction::Outbound,
+            _ => return Err(invalid_data("direção de registro desconhecida")),
+        };
+        let micros = u64::from_le_bytes(header[1..9].try_into().expect("8 bytes"));
+        let ip: IpAddr = match header[9] {
+            4 => Ipv4Addr::from(<[u8; 4]>::try_from(&header[10..14]).expect("4 bytes")).into(),
+            6 => Ipv6Addr::from(<[u8; 16]>::try_from(&header[10..26]).expect("16 bytes")).into(),
+            _ => return Err(invalid_data("família de endereço desconhecida")),
+        };
+        let port = u16::from_le_bytes([header[26], header[27]]);
+        let len = u32::from_le_bytes(header[28..32].try_into().expect("4 bytes"));
+
+        let mut bytes = Vec::new();
+        self.reader
+            .by_ref()
+            .take(u64::from(len))
+            .read_to_end(&mut bytes)?;
+        if bytes.len() != len as usize {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        Ok(Some(CaptureRecord {
+            direction,
+            elapsed: Duration::from_micros(micros),
+            addr: SocketAddr::new(ip, port),
+            packet: Packet::from_bytes(&bytes).map_err(io::Error::from)?,
+        }))
+    }
+}
+
+impl<R: Read> Iterator for CaptureReader<R> {
+    type Item = io::Result<CaptureRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_record().transpose()
+    }
+}
+
+/// Transporte que grava os pacotes enviados e recebidos por outro.
+///
+/// Os pacotes são gravados depois de enviados ou recebidos com sucesso; erros do
+/// transporte envolvido não são gravados.
+#[derive(Debug)]
+pub struct CaptureTransport<T, W> {
+    /// Transporte envolvido.
+    inner: T,
+    /// Gravador.
+    writer: CaptureWriter<W>,
+    /// Início da gravação.
+    started_at: Instant,
+}
+
+impl<T: Transport, W: Write> CaptureTransport<T, W> {
+    /// Envolve um transporte, iniciando a gravação agora.
+    ///
+    /// ## Parâmetros
+    /// - `inner`: Transporte envolvido.
+    /// - `writer`: Fluxo de destino da gravação.
+    ///
+    /// ## Retorno
+    /// - `io::Result<Self>`: O transporte, ou o erro da escrita da assinatura.
+    pub fn new(inner: T, writer: W) -> io::Result<Self> {
+        Ok(Self {
+            inner,
+            writer: CaptureWriter::new(writer)?,
+            started_at: Instant::now(),
+        })
+    }
+
+    /// Retorna o transporte envolvido.
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Retorna o transporte envolvido, para configurá-lo.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Descarrega o fluxo de destino da gravação.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Retorna o transporte envolvido e o fluxo de destino da gravação.
+    pub fn into_inner(self) -> (T, W) {
+        (self.inner, self.writer.into_inner())
+    }
+
+    /// Grava um pacote com o tempo decorrido até agora.
+    fn record(
+        &mut self,
+        direction: Direction,
+        addr: SocketAddr,
+        packet: &Packet,
+    ) -> Result<(), PacketError> {
+        let record = CaptureRecord {
+            direction,
+            elapsed: self.started_at.elapsed(),
+            addr,
+            packet: packet.clone(),
+        };
+        self.writer.write_record(&record).map_err(PacketError::from)
+    }
+}
+
+impl<T: Transport, W: Write> Transport for CaptureTransport<T, W> {
+    fn send_packet(&mut self, addr: SocketAddr, packet: &Packet) -> Result<usize, PacketError> {
+        let sent = self.inner.send_packet(addr, packet)?;
+        self.record(Direction::Outbound, addr, packet)?;
+        Ok(sent)
+    }
+
+    fn recv_packet(&mut self) -> Result<(SocketAddr, Packet), PacketError> {
+        let (addr, packet) = self.inner.recv_packet()?;
+        self.record(Direction::Inbound, addr, &packet)?;
+        Ok((addr, packet))
+    }
+
+    fn recv_from(&mut self) -> io::Result<Option<(SocketAddr, Result<Packet, PacketError>)>> {
+        let received = self.inner.recv_from()?;
+        if let Some((addr, Ok(packet))) = &received {
+            self.record(Direction::Inbound, *addr, packet)?;
+        }
+        Ok(received)
+    }
+
+    fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.inner.local_addr()
+    }
+
+    fn set_nonblocking(&mut self, nonblocking: bool) -> io::Result<()> {
+        self.inner.set_nonblocking(nonblocking)
+    }
+}
+
+/// Ritmo de entrega de um [`ReplayTransport`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ReplayMode {
+    /// Cada pacote só é entregue depois do seu tempo gravado, contado a partir da
+    /// primeira recepção.
+    #[default]
+    Realtime,
+    /// Os pacotes são entregues em sequência, sem espera.
+    AsFastAsPossible,
+}
+
+/// Transporte que entrega pelo caminho de recepção os pacotes recebidos em uma
+/// gravação.
+///
+/// Sem pacotes a entregar, [`Transport::recv_packet`] retorna [`PacketError::Io`] com
+/// [`io::ErrorKind::WouldBlock`], como um transporte não bloqueante. Os envios não
+/// saem do processo: são guardados e podem ser comparados com os enviados gravados,
+/// de [`ReplayTransport::recorded_outbound`].
+///
+/// ## Exemplos
+/// ```rust
+/// use std::time::Duration;
+/// use packet::Packet;
+/// use packet::capture::{CaptureRecord, Direction, ReplayMode, ReplayTransport};
+/// use packet::transport::Transport;
+///
+/// let addr = "127.0.0.1:4000".parse().unwrap();
+/// let records = vec![CaptureRecord {
+///     direction: Direction::Inbound,
+///     elapsed: Duration::from_millis(30),
+///     addr,
+///     packet: Packet::new(1, 1, 7, vec![1]),
+/// }];
+/// let mut replay = ReplayTransport::new(records, ReplayMode::Realtime);
+///
+/// // O pacote só é entregue 30 ms após a primeira recepção.
+/// assert!(replay.recv_packet().is_err());
+/// std::thread::sleep(Duration::from_millis(30));
+/// assert_eq!(replay.recv_packet().unwrap().1.payload, [1]);
+///
+/// replay.send_packet(addr, &Packet::new(2, 1, 7, vec![])).unwrap();
+/// assert_eq!(replay.sent().len(), 1);
+/// ```
+#[derive(Debug)]
+pub struct ReplayTransport {
+    /// Pacotes recebidos gravados ainda não entregues, em ordem.
+    inbound: VecDeque<CaptureRecord>,
+    /// Pacotes enviados gravados.
+    outbound: Vec<CaptureRecord>,
+    /// Pacotes enviados pela aplicação durante a repetição.
+    sent: Vec<(SocketAddr, Packet)>,
+    /// Ritmo de entrega.
+    mode: ReplayMode,
+    /// Instante da primeira recepção.
+    started_at: Option<Instant>,
+}
+
+impl ReplayTransport {
+    /// Cria uma repetição a partir dos registros de uma gravação.
+    ///
+    /// ## Parâmetros
+    /// - `records`: Registros em ordem de gravação.
+    /// - `mode`: Ritmo de entrega.
+    ///
+    /// ## Retorno
+    /// - `Self`: A repetição, que começa na primeira chamada de
+    ///   [`Transport::recv_packet`].
+    pub fn new(records: impl IntoIterator<Item = CaptureRecord>, mode: ReplayMode) -> Self {
+        let (inbound, outbound): (Vec<_>, _) = records
+            .into_iter()
+            .partition(|record| record.direction == Direction::Inbound);
+        Self {
+            inbound: inbound.into(),
+            outbound,
+            sent: Vec::new(),
+            mode,
+            started_at: None,
+        }
+    }
+
+    /// Cria uma repetição lendo toda uma gravação.
+    ///
+    /// ## Parâmetros
+    /// - `reader`: Leitor da gravação.
+    /// - `mode`: Ritmo de entrega.
+    ///
+    /// ## Retorno
+    /// - `io::Result<Self>`: A repetição, ou o primeiro erro de leitura.
+    pub fn from_reader<R: Read>(reader: CaptureReader<R>, mode: ReplayMode) -> io::Result<Self> {
+        let records = reader.collect::<io::Result<Vec<_>>>()?;
+        Ok(Self::new(records, mode))
+    }
+
+    /// Retorna a quantidade de pacotes recebidos ainda não entregues.
+    pub fn remaining(&self) -> usize {
+        self.inbound.len()
+    }
+
+    /// Indica se todos os pacotes recebidos gravados foram entregues.
+    pub fn is_finished(&self) -> bool {
+        self.inbound.is_empty()
+    }
+
+    /// Retorna os pacotes que a aplicação enviou durante a repetição, em ordem.
+    pub fn sent(&self) -> &[(SocketAddr, Packet)] {
+        &self.sent
+    }
+
+    /// Retorna os pacotes enviados na gravação, em ordem.
+    pub fn recorded_outbound(&self) -> &[CaptureRecord] {
+        &self.outbound
+    }
+}
+
+impl Transport for ReplayTransport {
+    /// Guarda o pacote em [`ReplayTransport::sent`] sem enviá-lo.
+    fn send_packet(&mut self, addr: SocketAddr, packet: &Packet) -> Result<usize, PacketError> {
+        self.sent.push((addr, packet.clone()));
+        Ok(packet.serialized_len())
+    }
+
+    fn recv_packet(&mut self) -> Result<(SocketAddr, Packet), PacketError> {
+        let started_at = *self.started_at.get_or_insert_with(Instant::now);
+        let due = self.inbound.front().is_some_and(|record| match self.mode {
+            ReplayMode::Realtime => started_at.elapsed() >= record.elapsed,
+            ReplayMode::AsFastAsPossible => true,
+        });
+        if !due {
+            return Err(io::Error::from(io::ErrorKind::WouldBlock).into());
+        }
+        let record = self.inbound.pop_front().expect("registro verificado");
+        Ok((record.addr, record.packet))
+    }
+}
+
+/// Erro [`io::ErrorKind::InvalidData`] de uma gravação malformada.
+fn invalid_data(reason: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, reason)
+}
@@ -0,0 +1,647 @@
+//! # Módulo `channel`
+//!
+//! Canais lógicos com garantias de entrega independentes.
+//!
+//! Cada [`Channel`] é identificado pelo `channel_id` do cabeçalho e configurado com um
+//! [`DeliveryMode`]. O canal numera os próprios pacotes, de modo que a perda no canal
+//! do chat não atrasa nem reordena as atualizações de posição enviadas em outro.
+//! Canais confiáveis retransmitem o que não foi confirmado e confirmam, nos pacotes
+//! que enviam, o que receberam do mesmo canal no outro lado.
+//!
+//! [`ChannelSet`] reúne os canais de uma conexão e encaminha cada pacote recebido ao
+//! canal indicado em seu cabeçalho.
+//!
+//! Um canal pode ter uma prioridade, gravada no `priority` de cada pacote que envia,
+//! para que o [`PriorityScheduler`](crate::queue::PriorityScheduler) envie antes, com
+//! a banda limitada, a voz e as entradas que os dados em volume.
+//!
+//! Nos modos não confiáveis, um canal pode repetir em cada pacote as últimas mensagens,
+//! com [`Channel::with_redundancy`], e enviar a paridade de cada grupo de mensagens,
+//! com [`Channel::with_fec`], para que as perdas sejam recuperadas sem esperar uma
+//! retransmissão (veja [`fec`](crate::fec)).
+//!
+//! Voz e vídeo usam um [`StreamChannel`] no mesmo conjunto, com
+//! [`ChannelSet::with_stream_channel`]: os quadros recebidos esperam no buffer de
+//! jitter do canal e saem por [`ChannelSet::playout_due`] no ritmo em que foram
+//! gravados, em vez de serem entregues ao chegar.
+//!
+//! ## Exemplos
+//! ```rust
+//! use std::time::{Duration, Instant};
+//! use packet::Packet;
+//! use packet::channel::{ChannelSet, DeliveryMode};
+//!
+//! const CHAT: u8 = 0;
+//! const POSITION: u8 = 1;
+//!
+//! let new_set = || {
+//!     ChannelSet::new(Duration::from_millis(100))
+//!         .with_channel(CHAT, DeliveryMode::ReliableOrdered)
+//!         .with_channel(POSITION, DeliveryMode::UnreliableSequenced)
+//! };
+//! let (mut client, mut server) = (new_set(), new_set());
+//! let now = Instant::now();
+//!
+//! let hello = client.send(CHAT, Packet::new(1, 0, 7, b"oi".to_vec()), now).unwrap();
+//! let world = client.send(CHAT, Packet::new(1, 0, 7, b"tudo bem?".to_vec()), now).unwrap();
+//! let old = client.send(POSITION, Packet::new(2, 0, 7, vec![1]), now).unwrap();
+//! let new = client.send(POSITION, Packet::new(2, 0, 7, vec![2]), now).unwrap();
+//!
+//! // O chat é entregue em ordem; a posição antiga, que chegou depois, é descartada.
+//! assert!(server.receive(world).is_empty());
+//! assert_eq!(server.receive(new)[0].payload, [2]);
+//! assert!(server.receive(old).is_empty());
+//! let chat: Vec<_> = server.receive(hello).into_iter().map(|p| p.payload).collect();
+//! assert_eq!(chat, [b"oi".to_vec(), b"tudo bem?".to_vec()]);
+//! ```
+
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use crate::fec::{FecDecoder, FecEncoder, Redundancy};
+use crate::ordered::OrderedChannel;
+use crate::reliability::ReliableEndpoint;
+use crate::sequence::{sequence_greater_than, ReplayStatus, ReplayWindow};
+use crate::streaming::{StreamChannel, StreamPlayout};
+use crate::{Packet, PacketFlags};
+
+/// Garantia de entrega de um [`Channel`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DeliveryMode {
+    /// Todos os pacotes chegam, uma única vez e na ordem de envio.
+    ReliableOrdered,
+    /// Todos os pacotes chegam, uma única vez, na ordem em que forem recebidos.
+    ReliableUnordered,
+    /// Pacotes são retransmitidos, mas só é entregue um pacote mais novo que todos os
+    /// já entregues.
+    ReliableSequenced,
+    /// Pacotes podem ser perdidos, duplicados ou chegar fora de ordem.
+    Unreliable,
+    /// Pacotes podem ser perdidos; os mais antigos que o último entregue são descartados.
+    UnreliableSequenced,
+}
+
+impl DeliveryMode {
+    /// Indica se os pacotes do modo são retransmitidos até a confirmação.
+    pub fn is_reliable(self) -> bool {
+        matches!(
+            self,
+            Self::ReliableOrdered | Self::ReliableUnordered | Self::ReliableSequenced
+        )
+    }
+}
+
+/// Canal lógico com sequências, confirmações e ordem de entrega próprias.
+///
+/// ## Exemplos
+/// ```rust
+/// use std::time::{Duration, Instant};
+/// use packet::Packet;
+/// use packet::channel::{Channel, DeliveryMode};
+///
+/// let timeout = Duration::from_millis(100);
+/// let mut sender = Channel::new(3, DeliveryMode::ReliableOrdered, timeout);
+/// let mut receiver = Channel::new(3, DeliveryMode::ReliableOrdered, timeout);
+/// let now = Instant::now();
+///
+/// let sent = sender.send(Packet::new(1, 0, 7, vec![1]), now);
+/// assert_eq!((sent.header.channel_id, sent.header.sequence), (3, 0));
+/// assert!(sent.is_reliable());
+///
+/// // Perdido, o pacote é retransmitido até ser confirmado.
+/// let resent = sender.resend_due(now + timeout);
+/// assert_eq!(receiver.receive(resent[0].clone()).len(), 1);
+/// assert!(receiver.receive(resent[0].clone()).is_empty());
+///
+/// let reply = receiver.send(Packet::new(1, 0, 9, vec![]), now);
+/// sender.receive(reply);
+/// assert_eq!(sender.pending_count(), 0);
+/// ```
+#[derive(Debug)]
+pub struct Channel {
+    /// Identificador do canal no cabeçalho.
+    id: u8,
+    /// Garantia de entrega.
+    mode: DeliveryMode,
+    /// Sequência do próximo pacote enviado.
+    next_sequence: u32,
+    /// Confirmações, retransmissões e descarte de duplicados dos modos confiáveis.
+    endpoint: ReliableEndpoint,
+    /// Pacotes retidos à espera de sequências anteriores, em
+    /// [`DeliveryMode::ReliableOrdered`].
+    ordered: OrderedChannel,
+    /// Sequência do pacote mais novo entregue, nos modos sequenciados.
+    newest: Option<u32>,
+    /// Prioridade gravada nos pacotes enviados; `None` mantém a de cada pacote.
+    priority: Option<u8>,
+    /// Repetição das últimas mensagens enviadas, nos modos não confiáveis.
+    redundancy: Option<Redundancy>,
+    /// Paridade dos grupos enviados e reconstrução dos recebidos, nos modos não
+    /// confiáveis.
+    fec: Option<(FecEncoder, FecDecoder)>,
+    /// Pacotes de paridade ainda não retornados por [`Channel::parity_due`].
+    parity: Vec<Packet>,
+    /// Sequências entregues, para descartar as mensagens repetidas ou reconstruídas.
+    delivered: ReplayWindow,
+}
+
+impl Channel {
+    /// Cria um canal sem pacotes enviados nem recebidos.
+    ///
+    /// ## Parâmetros
+    /// - `id`: Identificador do canal, gravado no `channel_id` dos pacotes enviados.
+    /// - `mode`: Garantia de entrega.
+    /// - `resend_timeout`: Tempo sem confirmação após o qual um pacote confiável é
+    ///   retransmitido; ignorado nos modos não confiáveis.
+    ///
+    /// ## Retorno
+    /// - `Self`: O canal, cuja primeira sequência é `0`.
+    pub fn new(id: u8, mode: DeliveryMode, resend_timeout: Duration) -> Self {
+        Self {
+            id,
+            mode,
+            next_sequence: 0,
+            endpoint: ReliableEndpoint::new(resend_timeout),
+            ordered: OrderedChannel::new(0),
+            newest: None,
+            priority: None,
+            redundancy: None,
+            fec: None,
+            parity: Vec::new(),
+            delivered: ReplayWindow::default(),
+        }
+    }
+
+    /// Define a prioridade do canal, gravada no `priority` de cada pacote enviado.
+    ///
+    /// ## Parâmetros
+    /// - `priority`: Prioridade; maior é enviada antes.
+    ///
+    /// ## Retorno
+    /// - `Self`: O canal com a prioridade.
+    pub fn with_priority(mut self, priority: u8) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    /// Passa a repetir em cada pacote enviado as últimas mensagens do canal, com
+    /// [`Redundancy`], e a separá-las nos pacotes recebidos.
+    ///
+    /// Ignorado nos modos confiáveis. Os dois lados devem configurar o canal da mesma
+    /// forma; as mensagens já entregues são descartadas, mesmo em
+    /// [`DeliveryMode::Unreliable`].
+    ///
+    /// ## Parâmetros
+    /// - `depth`: Quantidade de mensagens anteriores repetidas em cada pacote.
+    ///
+    /// ## Retorno
+    /// - `Self`: O canal com a redundância.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use std::time::{Duration, Instant};
+    /// use packet::Packet;
+    /// use packet::channel::{Channel, DeliveryMode};
+    ///
+    /// let new_channel = || {
+    ///     Channel::new(1, DeliveryMode::Unreliable, Duration::from_millis(100))
+    ///         .with_redundancy(2)
+    /// };
+    /// let (mut sender, mut receiver) = (new_channel(), new_channel());
+    /// let now = Instant::now();
+    ///
+    /// let first = sender.send(Packet::new(2, 0, 7, vec![1]), now);
+    /// let _lost = sender.send(Packet::new(2, 0, 7, vec![2]), now);
+    /// let third = sender.send(Packet::new(2, 0, 7, vec![3]), now);
+    ///
+    /// // O terceiro pacote entrega também a mensagem perdida, sem repetir a primeira.
+    /// assert_eq!(receiver.receive(first).len(), 1);
+    /// let payloads: Vec<_> = receiver.receive(third).into_iter().map(|p| p.payload).collect();
+    /// assert_eq!(payloads, [vec![2], vec![3]]);
+    /// ```
+    pub fn with_redundancy(mut self, depth: usize) -> Self {
+        self.redundancy = Some(Redundancy::new(depth));
+        self
+    }
+
+    /// Passa a gerar a paridade de cada grupo de mensagens enviadas, com
+    /// [`FecEncoder`], e a reconstruir uma mensagem perdida por grupo recebido.
+    ///
+    /// Ignorado nos modos confiáveis. Os pacotes de paridade são retornados por
+    /// [`Channel::parity_due`] e devem ser enviados depois das mensagens do grupo. Os
+    /// dois lados devem configurar o canal da mesma forma.
+    ///
+    /// ## Parâmetros
+    /// - `group_size`: Mensagens por grupo.
+    ///
+    /// ## Retorno
+    /// - `Self`: O canal com a correção de erros.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use std::time::{Duration, Instant};
+    /// use packet::Packet;
+    /// use packet::channel::{Channel, DeliveryMode};
+    ///
+    /// let new_channel = || {
+    ///     Channel::new(1, DeliveryMode::UnreliableSequenced, Duration::from_millis(100))
+    ///         .with_fec(3)
+    /// };
+    /// let (mut sender, mut receiver) = (new_channel(), new_channel());
+    /// let now = Instant::now();
+    ///
+    /// let sent: Vec<_> = (1..=3)
+    ///     .map(|i| sender.send(Packet::new(2, 0, 7, vec![i; 8]), now))
+    ///     .collect();
+    /// let parity = sender.parity_due();
+    /// assert_eq!(parity.len(), 1);
+    ///
+    /// // A última mensagem se perde e é reconstruída da paridade.
+    /// receiver.receive(sent[0].clone());
+    /// receiver.receive(sent[1].clone());
+    /// let recovered = receiver.receive(parity[0].clone());
+    /// assert_eq!(recovered[0].header.sequence, 2);
+    /// assert_eq!(recovered[0].payload, [3; 8]);
+    /// ```
+    pub fn with_fec(mut self, group_size: usize) -> Self {
+        self.fec = Some((FecEncoder::new(group_size), FecDecoder::new()));
+        self
+    }
+
+    /// Retorna a prioridade do canal, se definida.
+    pub fn priority(&self) -> Option<u8> {
+        self.priority
+    }
+
+    /// Retorna o identificador do canal.
+    pub fn id(&self) -> u8 {
+        self.id
+    }
+
+    /// Retorna a garantia de entrega do canal.
+    pub fn mode(&self) -> DeliveryMode {
+        self.mode
+    }
+
+    /// Prepara um pacote para envio no canal.
+    ///
+    /// O pacote recebe o `channel_id` do canal, a próxima sequência dele e a prioridade
+    /// do canal, se definida. Nos modos
+    /// confiáveis, recebe também a flag [`PacketFlags::RELIABLE`] e a confirmação do
+    /// que o canal recebeu, e passa a ser rastreado até ser confirmado. Nos não
+    /// confiáveis, entra no grupo da paridade e leva as mensagens anteriores, se
+    /// configurados.
+    ///
+    /// ## Parâmetros
+    /// - `packet`: Pacote a ser enviado; sua sequência é substituída.
+    /// - `now`: Instante do envio.
+    ///
+    /// ## Retorno
+    /// - `Packet`: O pacote pronto para ser transmitido.
+    pub fn send(&mut self, packet: Packet, now: Instant) -> Packet {
+        let sequence = self.next_sequence;
+        self.next_sequence = sequence.wrapping_add(1);
+        let mut packet = packet.with_channel_id(self.id).with_sequence(sequence);
+        if let Some(priority) = self.priority {
+            packet = packet.with_priority(priority);
+        }
+
+        if !self.mode.is_reliable() {
+            if let Some((encoder, _)) = &mut self.fec {
+                self.parity.extend(encoder.push(&packet));
+            }
+            if let Some(redundancy) = &mut self.redundancy {
+                packet = redundancy.wrap(packet);
+            }
+            return packet;
+        }
+        packet.header.flags.insert(PacketFlags::RELIABLE);
+        packet.header_edited();
+        self.endpoint.send(packet, now)
+    }
+
+    /// Processa um pacote recebido do canal.
+    ///
+    /// ## Parâmetros
+    /// - `packet`: Pacote recebido; deve pertencer a este canal.
+    ///
+    /// ## Retorno
+    /// - `Vec<Packet>`: Os pacotes que passam a poder ser entregues, conforme o modo;
+    ///   vazio se o pacote foi retido ou descartado.
+    pub fn receive(&mut self, packet: Packet) -> Vec<Packet> {
+        if !self.mode.is_reliable() {
+            return self.receive_unreliable(packet);
+        }
+        let Some(packet) = self.endpoint.receive(packet) else {
+            return Vec::new();
+        };
+        match self.mode {
+            DeliveryMode::ReliableOrdered => self.ordered.receive(packet),
+            _ => self.deliver(packet).into_iter().collect(),
+        }
+    }
+
+    /// Processa um pacote recebido nos modos não confiáveis, separando as mensagens
+    /// repetidas e reconstruindo as perdidas.
+    fn receive_unreliable(&mut self, packet: Packet) -> Vec<Packet> {
+        let messages = if packet.header.flags.contains(PacketFlags::PARITY) {
+            match &mut self.fec {
+                Some((_, decoder)) => decoder
+                    .recover(&packet)
+                    .ok()
+                    .flatten()
+                    .into_iter()
+                    .collect(),
+                None => Vec::new(),
+            }
+        } else {
+            let Ok(messages) = packet.unwrap_redundant() else {
+                return Vec::new();
+            };
+            if let Some((_, decoder)) = &mut self.fec {
+                messages.iter().for_each(|message| decoder.record(message));
+            }
+            messages
+        };
+
+        let deduplicate = self.redundancy.is_some() || self.fec.is_some();
+        let mut delivered = Vec::new();
+        for message in messages {
+            if deduplicate
+                && self.delivered.check(message.header.sequence) != ReplayStatus::Accepted
+            {
+                continue;
+            }
+            delivered.extend(self.deliver(message));
+        }
+        delivered
+    }
+
+    /// Entrega um pacote conforme o modo, descartando nos modos sequenciados os mais
+    /// antigos que o último entregue.
+    fn deliver(&mut self, packet: Packet) -> Option<Packet> {
+        if matches!(
+            self.mode,
+            DeliveryMode::ReliableSequenced | DeliveryMode::UnreliableSequenced
+        ) {
+            let sequence = packet.header.sequence;
+            if self
+                .newest
+                .is_some_and(|newest| !sequence_greater_than(sequence, newest))
+            {
+                return None;
+            }
+            self.newest = Some(sequence);
+        }
+        Some(packet)
+    }
+
+    /// Retorna os pacotes de paridade dos grupos completados desde a última chamada,
+    /// com [`Channel::with_fec`].
+    ///
+    /// ## Retorno
+    /// - `Vec<Packet>`: Pacotes a serem enviados depois das mensagens do grupo; sempre
+    ///   vazio sem a correção de erros.
+    pub fn parity_due(&mut self) -> Vec<Packet> {
+        std::mem::take(&mut self.parity)
+    }
+
+    /// Retorna os pacotes confiáveis cuja confirmação expirou, com a confirmação
+    /// atualizada.
+    ///
+    /// ## Parâmetros
+    /// - `now`: Instante atual.
+    ///
+    /// ## Retorno
+    /// - `Vec<Packet>`: Pacotes a serem retransmitidos; sempre vazio nos modos não
+    ///   confiáveis.
+    pub fn resend_due(&mut self, now: Instant) -> Vec<Packet> {
+        self.endpoint.resend_due(now)
+    }
+
+    /// Retorna a quantidade de pacotes aguardando confirmação.
+    pub fn pending_count(&self) -> usize {
+        self.endpoint.pending_count()
+    }
+}
+
+/// Conjunto dos canais de uma conexão, indexados pelo `channel_id`.
+///
+/// Pacotes recebidos de canais não configurados são descartados.
+#[derive(Debug)]
+pub struct ChannelSet {
+    /// Tempo de retransmissão dos canais confiáveis.
+    resend_timeout: Duration,
+    /// Canais configurados.
+    channels: BTreeMap<u8, Channel>,
+    /// Canais de voz e vídeo configurados.
+    streams: BTreeMap<u8, StreamChannel>,
+}
+
+impl ChannelSet {
+    /// Cria um conjunto sem canais.
+    ///
+    /// ## Parâmetros
+    /// - `resend_timeout`: Tempo de retransmissão dos canais confiáveis.
+    ///
+    /// ## Retorno
+    /// - `Self`: Um conjunto vazio.
+    pub fn new(resend_timeout: Duration) -> Self {
+        Self {
+            resend_timeout,
+            channels: BTreeMap::new(),
+            streams: BTreeMap::new(),
+        }
+    }
+
+    /// Retorna o conjunto com um canal configurado, substituindo um canal de mesmo
+    /// identificador.
+    ///
+    /// ## Parâmetros
+    /// - `id`: Identificador do canal.
+    /// - `mode`: Garantia de entrega do canal.
+    ///
+    /// ## Retorno
+    /// - `Self`: O conjunto com o canal.
+    pub fn with_channel(self, id: u8, mode: DeliveryMode) -> Self {
+        let channel = Channel::new(id, mode, self.resend_timeout);
+        self.with_configured_channel(channel)
+    }
+
+    /// Retorna o conjunto com um canal configurado como em
+    /// [`ChannelSet::with_channel`], com a prioridade de [`Channel::with_priority`].
+    ///
+    /// ## Parâmetros
+    /// - `id`: Identificador do canal.
+    /// - `mode`: Garantia de entrega do canal.
+    /// - `priority`: Prioridade dos pacotes do canal.
+    ///
+    /// ## Retorno
+    /// - `Self`: O conjunto com o canal.
+    pub fn with_channel_priority(self, id: u8, mode: DeliveryMode, priority: u8) -> Self {
+        let channel = Channel::new(id, mode, self.resend_timeout).with_priority(priority);
+        self.with_configured_channel(channel)
+    }
+
+    /// Retorna o conjunto com um canal criado e configurado à parte, por exemplo com
+    /// [`Channel::with_redundancy`] ou [`Channel::with_fec`], substituindo um canal de
+    /// mesmo identificador.
+    ///
+    /// ## Parâmetros
+    /// - `channel`: Canal a incluir.
+    ///
+    /// ## Retorno
+    /// - `Self`: O conjunto com o canal.
+    pub fn with_configured_channel(mut self, channel: Channel) -> Self {
+        self.streams.remove(&channel.id());
+        self.channels.insert(channel.id(), channel);
+        self
+    }
+
+    /// Retorna o conjunto com um canal de voz ou vídeo, substituindo um canal de mesmo
+    /// identificador.
+    ///
+    /// ## Parâmetros
+    /// - `channel`: Canal a incluir.
+    ///
+    /// ## Retorno
+    /// - `Self`: O conjunto com o canal.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use std::time::{Duration, Instant};
+    /// use packet::Packet;
+    /// use packet::channel::{ChannelSet, DeliveryMode};
+    /// use packet::streaming::{StreamChannel, StreamPlayout};
+    ///
+    /// const CHAT: u8 = 0;
+    /// const VOICE: u8 = 1;
+    ///
+    /// let new_set = || {
+    ///     ChannelSet::new(Duration::from_millis(100))
+    ///         .with_channel(CHAT, DeliveryMode::ReliableOrdered)
+    ///         .with_stream_channel(StreamChannel::new(VOICE, 160))
+    /// };
+    /// let (mut client, mut server) = (new_set(), new_set());
+    /// let now = Instant::now();
+    ///
+    /// let voice = client.stream_mut(VOICE).unwrap().send(&[1; 160], now).unwrap();
+    /// let chat = client.send(CHAT, Packet::new(1, 0, 7, b"oi".to_vec()), now).unwrap();
+    ///
+    /// // O chat é entregue ao chegar; a voz espera o atraso alvo.
+    /// assert!(server.receive_at(voice, now).is_empty());
+    /// assert_eq!(server.receive_at(chat, now).len(), 1);
+    /// assert!(server.playout_due(now).is_empty());
+    ///
+    /// let played = server.playout_due(now + Duration::from_millis(60));
+    /// assert!(matches!(&played[..], [(VOICE, StreamPlayout::Frame(frame))] if frame.data == [1; 160]));
+    /// ```
+    pub fn with_stream_channel(mut self, channel: StreamChannel) -> Self {
+        self.channels.remove(&channel.id());
+        self.streams.insert(channel.id(), channel);
+        self
+    }
+
+    /// Retorna o canal com o identificador informado, se configurado.
+    pub fn channel(&self, id: u8) -> Option<&Channel> {
+        self.channels.get(&id)
+    }
+
+    /// Retorna o canal de voz ou vídeo com o identificador informado, se configurado.
+    pub fn stream(&self, id: u8) -> Option<&StreamChannel> {
+        self.streams.get(&id)
+    }
+
+    /// Retorna o canal de voz ou vídeo com o identificador informado para alteração,
+    /// como para enviar quadros com [`StreamChannel::send`].
+    pub fn stream_mut(&mut self, id: u8) -> Option<&mut StreamChannel> {
+        self.streams.get_mut(&id)
+    }
+
+    /// Prepara um pacote para envio em um canal, como em [`Channel::send`].
+    ///
+    /// ## Parâmetros
+    /// - `id`: Canal de envio.
+    /// - `packet`: Pacote a ser enviado.
+    /// - `now`: Instante do envio.
+    ///
+    /// ## Retorno
+    /// - `Option<Packet>`: O pacote pronto para ser transmitido, ou `None` se o canal
+    ///   não está configurado.
+    pub fn send(&mut self, id: u8, packet: Packet, now: Instant) -> Option<Packet> {
+        Some(self.channels.get_mut(&id)?.send(packet, now))
+    }
+
+    /// Encaminha um pacote recebido ao canal indicado em seu cabeçalho.
+    ///
+    /// ## Parâmetros
+    /// - `packet`: Pacote recebido.
+    ///
+    /// ## Retorno
+    /// - `Vec<Packet>`: Os pacotes do canal que passam a poder ser entregues; vazio se o
+    ///   canal não está configurado.
+    pub fn receive(&mut self, packet: Packet) -> Vec<Packet> {
+        self.receive_at(packet, Instant::now())
+    }
+
+    /// Como [`ChannelSet::receive`], com o instante de chegada usado pelo buffer de
+    /// jitter dos canais de voz e vídeo.
+    ///
+    /// ## Parâmetros
+    /// - `packet`: Pacote recebido.
+    /// - `now`: Instante da chegada.
+    ///
+    /// ## Retorno
+    /// - `Vec<Packet>`: Os pacotes do canal que passam a poder ser entregues; sempre
+    ///   vazio para os canais de voz e vídeo, cujos quadros saem por
+    ///   [`ChannelSet::playout_due`].
+    pub fn receive_at(&mut self, packet: Packet, now: Instant) -> Vec<Packet> {
+        let id = packet.header.channel_id;
+        if let Some(stream) = self.streams.get_mut(&id) {
+            stream.receive(&packet, now);
+            return Vec::new();
+        }
+        match self.channels.get_mut(&id) {
+            Some(channel) => channel.receive(packet),
+            None => Vec::new(),
+        }
+    }
+
+    /// Retorna os quadros na hora de serem tocados de todos os canais de voz e vídeo, em
+    /// ordem de canal, como em [`StreamChannel::poll`].
+    ///
+    /// ## Parâmetros
+    /// - `now`: Instante atual.
+    ///
+    /// ## Retorno
+    /// - `Vec<(u8, StreamPlayout)>`: O canal e cada quadro a tocar.
+    pub fn playout_due(&mut self, now: Instant) -> Vec<(u8, StreamPlayout)> {
+        self.streams
+            .iter_mut()
+            .flat_map(|(id, stream)| stream.poll(now).into_iter().map(|playout| (*id, playout)))
+            .collect()
+    }
+
+    /// Retorna os pacotes a retransmitir de todos os canais, em ordem de canal.
+    ///
+    /// ## Parâmetros
+    /// - `now`: Instante atual.
+    ///
+    /// ## Retorno
+    /// - `Vec<Packet>`: Pacotes a serem retransmitidos.
+    pub fn resend_due(&mut self, now: Instant) -> Vec<Packet> {
+        self.channels
+            .values_mut()
+            .flat_map(|channel| channel.resend_due(now))
+            .collect()
+    }
+
+    /// Retorna os pacotes de paridade pendentes de todos os canais, em ordem de canal,
+    /// como em [`Channel::parity_due`].
+    pub fn parity_due(&mut self) -> Vec<Packet> {
+        self.channels
+            .values_mut()
+            .flat_map(Channel::parity_due)
+            .collect()
+    }
+}
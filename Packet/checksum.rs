@@ -0,0 +1,587 @@
+//! # Módulo `checksum`
+//!
+//! Algoritmos e escopos de checksum selecionáveis por pacote.
+//!
+//! O algoritmo ([`ChecksumAlgorithm`]) e a parte do pacote coberta pelo checksum
+//! ([`ChecksumScope`]) são indicados pelas flags do cabeçalho, de modo que um mesmo
+//! fluxo pode misturar pacotes com configurações diferentes e cada um é verificado
+//! da forma correta. A soma de bytes ([`ChecksumAlgorithm::ByteSum`]) continua sendo o
+//! padrão para que o tráfego existente siga decodificável; CRC-32 e xxHash32 detectam
+//! trocas de posição e a maior parte das corrupções reais.
+//!
+//! ## Exemplos
+//! ```rust
+//! use packet::Packet;
+//! use packet::checksum::{ChecksumAlgorithm, Crc32Variant};
+//!
+//! let stream = [
+//!     Packet::new(1, 1, 7, vec![1, 2, 3]),
+//!     Packet::new(1, 2, 7, vec![1, 2, 3]).with_checksum_algorithm(ChecksumAlgorithm::CRC32),
+//!     Packet::new(1, 3, 7, vec![1, 2, 3])
+//!         .with_checksum_algorithm(ChecksumAlgorithm::Crc32(Crc32Variant::Castagnoli)),
+//!     Packet::new(1, 4, 7, vec![1, 2, 3]).with_checksum_algorithm(ChecksumAlgorithm::XxHash32),
+//! ];
+//!
+//! for sent in &stream {
+//!     let received = Packet::from_bytes(&sent.to_bytes()).unwrap();
+//!     assert_eq!(received.checksum_algorithm(), sent.checksum_algorithm());
+//!     assert!(received.verify_checksum());
+//! }
+//! ```
+
+use crate::PacketFlags;
+
+/// Valor inicial do checksum [`ChecksumAlgorithm::ByteSum`].
+///
+/// Uma soma simples dos bytes vale `0` tanto para um payload vazio quanto para um
+/// payload só de zeros, e um checksum corrompido para `0` passaria por válido em
+/// ambos os casos. Partindo de uma constante diferente de zero e somando também o
+/// tamanho dos dados (cada byte contribui com `valor + 1`), o checksum de um payload
+/// vazio nunca é `0` e difere do de qualquer payload de zeros.
+///
+/// ## Exemplos
+/// ```rust
+/// use packet::Packet;
+///
+/// let empty = Packet::new(1, 1, 7, vec![]);
+/// let zeros = Packet::new(1, 1, 7, vec![0; 4]);
+/// assert_ne!(empty.header.checksum, 0);
+/// assert_ne!(empty.header.checksum, zeros.header.checksum);
+///
+/// let corrupted = Packet::new_with_raw_checksum(1, 1, 7, vec![], 0);
+/// assert!(!corrupted.verify_checksum());
+/// ```
+pub const BYTE_SUM_SEED: u32 = 0x5EED_0000;
+
+/// Algoritmo usado para calcular o checksum do payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ChecksumAlgorithm {
+    /// Soma dos bytes do payload, partindo de [`BYTE_SUM_SEED`] e somada ao tamanho dos
+    /// dados. Barata, mas não detecta bytes trocados de posição.
+    ///
+    /// Os bytes são somados em palavras de 64 bits, com o mesmo resultado da soma byte
+    /// a byte:
+    /// ```rust
+    /// use packet::checksum::{ChecksumAlgorithm, BYTE_SUM_SEED};
+    ///
+    /// let data: Vec<u8> = (0..5_000u32).map(|i| (i * 7 + 3) as u8).collect();
+    /// for (start, end) in [(0, 0), (3, 10), (1, 1_029), (5, 5_000)] {
+    ///     let bytes = &data[start..end];
+    ///     let expected = bytes
+    ///         .iter()
+    ///         .fold(BYTE_SUM_SEED, |sum, &b| sum.wrapping_add(1 + b as u32));
+    ///     assert_eq!(ChecksumAlgorithm::ByteSum.compute(bytes), expected);
+    /// }
+    /// ```
+    #[default]
+    ByteSum,
+    /// CRC-32 na variante informada, indicado pela flag [`PacketFlags::CRC32`] e, para
+    /// [`Crc32Variant::Castagnoli`], também pela flag [`PacketFlags::CRC32C`].
+    Crc32(Crc32Variant),
+    /// xxHash32 com semente `0`, indicado pela flag [`PacketFlags::XXHASH32`]. Mais
+    /// rápido que CRC-32 em payloads grandes, com detecção de erros equivalente.
+    XxHash32,
+    /// Escolhe [`ChecksumAlgorithm::ByteSum`] para payloads menores que `threshold`
+    /// bytes e [`ChecksumAlgorithm::CRC32`] para os demais.
+    ///
+    /// A escolha é feita ao gravar o checksum e registrada nas flags como o algoritmo
+    /// concreto; por isso [`ChecksumAlgorithm::from_flags`] nunca retorna este valor.
+    Adaptive {
+        /// Tamanho de payload, em bytes, a partir do qual CRC-32 é usado.
+        threshold: usize,
+    },
+}
+
+/// Interface comum aos algoritmos de checksum, para código genérico sobre o cálculo.
+///
+/// O algoritmo gravado no cabeçalho é sempre um [`ChecksumAlgorithm`], pois precisa
+/// ser identificável pelas flags; a trait permite que ferramentas e testes tratem
+/// algoritmos e variantes de forma uniforme.
+///
+/// ## Exemplos
+/// ```rust
+/// use packet::checksum::{Checksum, ChecksumAlgorithm, Crc32Variant};
+///
+/// fn differs(checksum: &impl Checksum, a: &[u8], b: &[u8]) -> bool {
+///     checksum.checksum(a) != checksum.checksum(b)
+/// }
+///
+/// assert!(!differs(&ChecksumAlgorithm::ByteSum, &[1, 2], &[2, 1]));
+/// assert!(differs(&Crc32Variant::Ieee, &[1, 2], &[2, 1]));
+/// assert!(differs(&ChecksumAlgorithm::XxHash32, &[1, 2], &[2, 1]));
+/// ```
+pub trait Checksum {
+    /// Calcula o checksum dos bytes informados.
+    fn checksum(&self, bytes: &[u8]) -> u32;
+}
+
+impl Checksum for ChecksumAlgorithm {
+    fn checksum(&self, bytes: &[u8]) -> u32 {
+        self.compute(bytes)
+    }
+}
+
+impl Checksum for Crc32Variant {
+    fn checksum(&self, bytes: &[u8]) -> u32 {
+        ChecksumAlgorithm::Crc32(*self).compute(bytes)
+    }
+}
+
+/// Polinômio e valores inicial e final de uma variante de CRC-32.
+///
+/// Todas as variantes usam a forma refletida, em que o bit menos significativo de cada
+/// byte é processado primeiro.
+///
+/// ## Exemplos
+/// Os valores de verificação de `b"123456789"` são os do catálogo de CRCs:
+/// ```rust
+/// use packet::checksum::{ChecksumAlgorithm, Crc32Variant};
+///
+/// let check = |variant| ChecksumAlgorithm::Crc32(variant).compute(b"123456789");
+/// assert_eq!(check(Crc32Variant::Ieee), 0xCBF4_3926);
+/// assert_eq!(check(Crc32Variant::Castagnoli), 0xE306_9283);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Crc32Variant {
+    /// CRC-32 da IEEE 802.3 (Ethernet, zlib, PNG), polinômio `0x04C11DB7`.
+    #[default]
+    Ieee,
+    /// CRC-32C de Castagnoli (iSCSI, SCTP, ext4), polinômio `0x1EDC6F41`.
+    Castagnoli,
+}
+
+impl Crc32Variant {
+    /// Retorna a tabela de consulta da variante.
+    fn table(self) -> &'static [u32; 256] {
+        match self {
+            Self::Ieee => &CRC32_IEEE_TABLE,
+            Self::Castagnoli => &CRC32_CASTAGNOLI_TABLE,
+        }
+    }
+}
+
+/// Limite usado por [`ChecksumAlgorithm::adaptive`].
+pub const DEFAULT_ADAPTIVE_THRESHOLD: usize = 256;
+
+impl ChecksumAlgorithm {
+    /// CRC-32 na variante IEEE 802.3, a usada por padrão.
+    pub const CRC32: Self = Self::Crc32(Crc32Variant::Ieee);
+
+    /// Flags lidas por [`ChecksumAlgorithm::from_flags`] e gravadas por
+    /// [`ChecksumAlgorithm::apply_to`].
+    pub const FLAGS: PacketFlags = PacketFlags::from_bits_retain(
+        PacketFlags::CRC32.bits() | PacketFlags::CRC32C.bits() | PacketFlags::XXHASH32.bits(),
+    );
+
+    /// Retorna o modo adaptativo com o limite [`DEFAULT_ADAPTIVE_THRESHOLD`].
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::Packet;
+    /// use packet::checksum::ChecksumAlgorithm;
+    ///
+    /// let small = Packet::new(1, 1, 7, vec![1; 16]).with_checksum_algorithm(ChecksumAlgorithm::adaptive());
+    /// let large = Packet::new(1, 2, 7, vec![1; 1024]).with_checksum_algorithm(ChecksumAlgorithm::adaptive());
+    ///
+    /// assert_eq!(small.checksum_algorithm(), ChecksumAlgorithm::ByteSum);
+    /// assert_eq!(large.checksum_algorithm(), ChecksumAlgorithm::CRC32);
+    ///
+    /// let received = Packet::from_bytes(&large.to_bytes()).unwrap();
+    /// assert!(received.verify_checksum());
+    /// ```
+    pub fn adaptive() -> Self {
+        Self::Adaptive {
+            threshold: DEFAULT_ADAPTIVE_THRESHOLD,
+        }
+    }
+
+    /// Resolve o modo adaptativo no algoritmo concreto para um payload do tamanho informado.
+    ///
+    /// ## Parâmetros
+    /// - `payload_len`: Tamanho dos dados cobertos pelo checksum, em bytes.
+    ///
+    /// ## Retorno
+    /// - `Self`: [`ChecksumAlgorithm::ByteSum`] ou [`ChecksumAlgorithm::CRC32`]; os
+    ///   algoritmos concretos são retornados sem alteração.
+    pub fn resolve(self, payload_len: usize) -> Self {
+        match self {
+            Self::Adaptive { threshold } if payload_len >= threshold => Self::CRC32,
+            Self::Adaptive { .. } => Self::ByteSum,
+            concrete => concrete,
+        }
+    }
+
+    /// Determina o algoritmo indicado pelas flags de um cabeçalho.
+    ///
+    /// ## Parâmetros
+    /// - `flags`: Flags do cabeçalho.
+    ///
+    /// ## Retorno
+    /// - `Self`: O algoritmo usado pelo pacote.
+    pub fn from_flags(flags: PacketFlags) -> Self {
+        if flags.contains(PacketFlags::XXHASH32) {
+            Self::XxHash32
+        } else if !flags.contains(PacketFlags::CRC32) {
+            Self::ByteSum
+        } else if flags.contains(PacketFlags::CRC32C) {
+            Self::Crc32(Crc32Variant::Castagnoli)
+        } else {
+            Self::CRC32
+        }
+    }
+
+    /// Grava o algoritmo nas flags de um cabeçalho, sem alterar as demais flags.
+    ///
+    /// O modo adaptativo é resolvido como para um payload vazio; resolva-o antes com
+    /// [`ChecksumAlgorithm::resolve`] para considerar o tamanho real.
+    ///
+    /// ## Parâmetros
+    /// - `flags`: Flags do cabeçalho a serem atualizadas.
+    pub fn apply_to(self, flags: &mut PacketFlags) {
+        flags.remove(Self::FLAGS);
+        match self.resolve(0) {
+            Self::XxHash32 => flags.insert(PacketFlags::XXHASH32),
+            Self::Crc32(Crc32Variant::Ieee) => flags.insert(PacketFlags::CRC32),
+            Self::Crc32(Crc32Variant::Castagnoli) => {
+                flags.insert(PacketFlags::CRC32 | PacketFlags::CRC32C)
+            }
+            _ => {}
+        }
+    }
+
+    /// Calcula o checksum de um payload com este algoritmo.
+    ///
+    /// ## Parâmetros
+    /// - `payload`: Bytes do payload.
+    ///
+    /// ## Retorno
+    /// - `u32`: O checksum calculado.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::checksum::{ChecksumAlgorithm, BYTE_SUM_SEED};
+    ///
+    /// assert_eq!(ChecksumAlgorithm::ByteSum.compute(&[1, 2, 3]), BYTE_SUM_SEED + 3 + 6);
+    /// assert_eq!(ChecksumAlgorithm::CRC32.compute(b"123456789"), 0xCBF4_3926);
+    /// assert_eq!(ChecksumAlgorithm::XxHash32.compute(b"123456789"), 0x937B_AD67);
+    /// ```
+    pub fn compute(self, payload: &[u8]) -> u32 {
+        self.compute_parts(&[payload])
+    }
+
+    /// Calcula o checksum da concatenação de `parts`, sem copiá-las.
+    pub(crate) fn compute_parts(self, parts: &[&[u8]]) -> u32 {
+        let len = parts.iter().map(|part| part.len()).sum();
+        match self.resolve(len) {
+            Self::ByteSum => parts
+                .iter()
+                .fold(BYTE_SUM_SEED.wrapping_add(len as u32), |sum, part| {
+                    sum.wrapping_add(byte_sum(part))
+                }),
+            Self::Crc32(variant) => {
+                let table = variant.table();
+                !parts
+                    .iter()
+                    .fold(!0, |crc, part| crc32_update(table, crc, part))
+            }
+            Self::XxHash32 => {
+                let mut state = XxHash32::new(len);
+                parts.iter().for_each(|part| state.update(part));
+                state.finish()
+            }
+            Self::Adaptive { .. } => unreachable!("resolve retorna um algoritmo concreto"),
+        }
+    }
+}
+
+/// Parte do pacote coberta pelo checksum.
+///
+/// ## Exemplos
+/// Com o cabeçalho coberto, a alteração de um campo por um intermediário é detectada:
+/// ```rust
+/// use packet::Packet;
+/// use packet::checksum::ChecksumScope;
+///
+/// let packet = Packet::new(1, 1, 7, vec![1, 2, 3]).with_checksum_scope(ChecksumScope::HeaderAndPayload);
+/// let mut received = Packet::from_bytes(&packet.to_bytes()).unwrap();
+/// assert!(received.verify_checksum());
+///
+/// received.header.player_id = 8;
+/// assert!(!received.verify_checksum());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ChecksumScope {
+    /// Apenas o payload. Campos do cabeçalho podem ser alterados sem recalcular o checksum.
+    #[default]
+    Payload,
+    /// O cabeçalho (com o campo `checksum` zerado) seguido do payload, indicado pela
+    /// flag [`PacketFlags::HEADER_CHECKSUM`].
+    HeaderAndPayload,
+    /// Nenhum byte, indicado pela flag [`PacketFlags::NO_CHECKSUM`]. O campo `checksum`
+    /// é gravado como `0` e a verificação sempre aceita o pacote sem calcular nada.
+    ///
+    /// Destinado apenas a transportes confiáveis, como canais dentro do processo ou
+    /// `localhost`, em que não há risco de corrupção. Um receptor que aceite tráfego
+    /// externo deve rejeitar essa flag, pois ela desativa a detecção de erros.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::{Packet, PacketFlags};
+    /// use packet::checksum::ChecksumScope;
+    ///
+    /// let packet = Packet::new(1, 1, 7, vec![0; 4096]).with_checksum_scope(ChecksumScope::None);
+    /// assert_eq!(packet.header.checksum, 0);
+    /// assert!(packet.header.flags.contains(PacketFlags::NO_CHECKSUM));
+    ///
+    /// let mut received = Packet::from_bytes(&packet.to_bytes()).unwrap();
+    /// received.payload[0] = 1;
+    /// assert!(received.verify_checksum());
+    /// ```
+    None,
+}
+
+impl ChecksumScope {
+    /// Flags lidas por [`ChecksumScope::from_flags`] e gravadas por
+    /// [`ChecksumScope::apply_to`].
+    pub const FLAGS: PacketFlags = PacketFlags::from_bits_retain(
+        PacketFlags::HEADER_CHECKSUM.bits() | PacketFlags::NO_CHECKSUM.bits(),
+    );
+
+    /// Determina o escopo indicado pelas flags de um cabeçalho.
+    ///
+    /// ## Parâmetros
+    /// - `flags`: Flags do cabeçalho.
+    ///
+    /// ## Retorno
+    /// - `Self`: O escopo usado pelo pacote.
+    pub fn from_flags(flags: PacketFlags) -> Self {
+        if flags.contains(PacketFlags::NO_CHECKSUM) {
+            Self::None
+        } else if flags.contains(PacketFlags::HEADER_CHECKSUM) {
+            Self::HeaderAndPayload
+        } else {
+            Self::Payload
+        }
+    }
+
+    /// Grava o escopo nas flags de um cabeçalho, sem alterar as demais flags.
+    ///
+    /// ## Parâmetros
+    /// - `flags`: Flags do cabeçalho a serem atualizadas.
+    pub fn apply_to(self, flags: &mut PacketFlags) {
+        flags.remove(Self::FLAGS);
+        match self {
+            Self::Payload => {}
+            Self::HeaderAndPayload => flags.insert(PacketFlags::HEADER_CHECKSUM),
+            Self::None => flags.insert(PacketFlags::NO_CHECKSUM),
+        }
+    }
+}
+
+/// Palavras de 64 bits acumuladas por [`byte_sum`] antes de reduzir as faixas: cada
+/// faixa de 16 bits recebe no máximo `2 * 255` por palavra, e 128 palavras ainda cabem
+/// nela.
+const BYTE_SUM_BLOCK_WORDS: usize = 128;
+
+/// Soma os bytes informados, oito de cada vez.
+///
+/// Os bytes pares e ímpares de cada palavra de 64 bits são somados em quatro faixas de
+/// 16 bits de um mesmo acumulador, um laço sem dependências entre as palavras que o
+/// compilador vetoriza onde há SIMD; os bytes que não completam uma palavra são
+/// somados um a um.
+fn byte_sum(bytes: &[u8]) -> u32 {
+    const LOW_BYTES: u64 = 0x00FF_00FF_00FF_00FF;
+    let mut sum = 0u32;
+    for block in bytes.chunks(8 * BYTE_SUM_BLOCK_WORDS) {
+        let mut words = block.chunks_exact(8);
+        let mut lanes = 0u64;
+        for word in &mut words {
+            let word = u64::from_le_bytes(word.try_into().expect("8 bytes"));
+            lanes += (word & LOW_BYTES) + ((word >> 8) & LOW_BYTES);
+        }
+        let lanes =
+            (lanes & 0xFFFF) + ((lanes >> 16) & 0xFFFF) + ((lanes >> 32) & 0xFFFF) + (lanes >> 48);
+        sum = words
+            .remainder()
+            .iter()
+            .fold(sum.wrapping_add(lanes as u32), |sum, &b| {
+                sum.wrapping_add(b as u32)
+            });
+    }
+    sum
+}
+
+/// Tabela do CRC-32 IEEE refletido (polinômio `0xEDB88320`), gerada em tempo de compilação.
+const CRC32_IEEE_TABLE: [u32; 256] = crc32_table(0xEDB8_8320);
+
+/// Tabela do CRC-32C refletido (polinômio `0x82F63B78`), gerada em tempo de compilação.
+const CRC32_CASTAGNOLI_TABLE: [u32; 256] = crc32_table(0x82F6_3B78);
+
+/// Gera a tabela de consulta de um CRC-32 refletido com o polinômio informado.
+const fn crc32_table(polynomial: u32) -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ polynomial
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+/// Acumula uma sequência de bytes no estado intermediário de um CRC-32.
+fn crc32_update(table: &[u32; 256], crc: u32, bytes: &[u8]) -> u32 {
+    bytes.iter().fold(crc, |crc, &byte| {
+        table[((crc ^ byte as u32) & 0xFF) as usize] ^ (crc >> 8)
+    })
+}
+
+/// Constantes primas do xxHash32.
+const XXH_PRIME_1: u32 = 0x9E37_79B1;
+const XXH_PRIME_2: u32 = 0x85EB_CA77;
+const XXH_PRIME_3: u32 = 0xC2B2_AE3D;
+const XXH_PRIME_4: u32 = 0x27D4_EB2F;
+const XXH_PRIME_5: u32 = 0x1656_67B1;
+
+/// Estado incremental do xxHash32 com semente `0`, alimentado por partes.
+struct XxHash32 {
+    /// Tamanho total dos dados.
+    len: usize,
+    /// Acumuladores dos blocos de 16 bytes.
+    lanes: [u32; 4],
+    /// Bytes que ainda não completam um bloco.
+    pending: [u8; 16],
+    /// Quantidade de bytes em `pending`.
+    pending_len: usize,
+}
+
+impl XxHash32 {
+    fn new(len: usize) -> Self {
+        Self {
+            len,
+            lanes: [
+                XXH_PRIME_1.wrapping_add(XXH_PRIME_2),
+                XXH_PRIME_2,
+                0,
+                XXH_PRIME_1.wrapping_neg(),
+            ],
+            pending: [0; 16],
+            pending_len: 0,
+        }
+    }
+
+    fn round(lane: u32, input: u32) -> u32 {
+        lane.wrapping_add(input.wrapping_mul(XXH_PRIME_2))
+            .rotate_left(13)
+            .wrapping_mul(XXH_PRIME_1)
+    }
+
+    fn update(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.pending[self.pending_len] = byte;
+            self.pending_len += 1;
+            if self.pending_len == self.pending.len() {
+                for (lane, word) in self.lanes.iter_mut().zip(self.pending.chunks_exact(4)) {
+                    let word = u32::from_le_bytes(word.try_into().expect("4 bytes"));
+                    *lane = Self::round(*lane, word);
+                }
+                self.pending_len = 0;
+            }
+        }
+    }
+
+    fn finish(self) -> u32 {
+        let [v1, v2, v3, v4] = self.lanes;
+        let mut hash = if self.len >= 16 {
+            v1.rotate_left(1)
+                .wrapping_add(v2.rotate_left(7))
+                .wrapping_add(v3.rotate_left(12))
+                .wrapping_add(v4.rotate_left(18))
+        } else {
+            XXH_PRIME_5
+        };
+        hash = hash.wrapping_add(self.len as u32);
+
+        let tail = &self.pending[..self.pending_len];
+        let mut words = tail.chunks_exact(4);
+        for word in &mut words {
+            let word = u32::from_le_bytes(word.try_into().expect("4 bytes"));
+            hash = hash
+                .wrapping_add(word.wrapping_mul(XXH_PRIME_3))
+                .rotate_left(17)
+                .wrapping_mul(XXH_PRIME_4);
+        }
+        for &byte in words.remainder() {
+            hash = hash
+                .wrapping_add((byte as u32).wrapping_mul(XXH_PRIME_5))
+                .rotate_left(11)
+                .wrapping_mul(XXH_PRIME_1);
+        }
+
+        hash ^= hash >> 15;
+        hash = hash.wrapping_mul(XXH_PRIME_2);
+        hash ^= hash >> 13;
+        hash = hash.wrapping_mul(XXH_PRIME_3);
+        hash ^ (hash >> 16)
+    }
+}
+
+/// Tabela do CRC-8 com o polinômio `0x07`, gerada em tempo de compilação.
+const CRC8_TABLE: [u8; 256] = crc8_table(0x07);
+
+/// Gera a tabela de consulta de um CRC-8 não refletido com o polinômio informado.
+const fn crc8_table(polynomial: u8) -> [u8; 256] {
+    let mut table = [0u8; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u8;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ polynomial
+            } else {
+                crc << 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+/// Calcula o CRC-8 (polinômio `0x07`, valor inicial `0`) usado no campo
+/// `header_checksum` do cabeçalho.
+///
+/// Detecta todo erro em um único byte e toda rajada de até 8 bits, o suficiente
+/// para os poucos bytes do cabeçalho.
+///
+/// ## Parâmetros
+/// - `bytes`: Bytes cobertos pelo checksum.
+///
+/// ## Retorno
+/// - `u8`: O CRC-8 dos bytes.
+///
+/// ## Exemplos
+/// ```rust
+/// use packet::checksum::crc8;
+///
+/// assert_eq!(crc8(b"123456789"), 0xF4);
+/// assert_eq!(crc8(&[]), 0);
+/// ```
+pub fn crc8(bytes: &[u8]) -> u8 {
+    bytes
+        .iter()
+        .fold(0, |crc, &byte| CRC8_TABLE[(crc ^ byte) as usize])
+}
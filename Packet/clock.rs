@@ -0,0 +1,223 @@
+//! # Módulo `clock`
+//!
+//! Fontes de tempo injetáveis e carimbo de tempo dos pacotes.
+//!
+//! Operações que dependem do tempo recebem um [`Clock`] em vez de consultar o relógio
+//! do sistema diretamente, de forma que testes de RTT e de sincronização de relógio
+//! podem usar um [`MockClock`] e obter resultados determinísticos.
+//!
+//! Os temporizadores da pilha (retransmissões, pings, tempos limite, controle de
+//! congestionamento) recebem o instante atual como `Instant` em cada chamada, como
+//! `poll(now)` e `update(now)` de [`Server`](crate::connection::Server) e
+//! [`Client`](crate::connection::Client). [`Clock::now`] converte qualquer relógio
+//! nesse instante: com um [`MockClock`], a pilha inteira avança só quando o teste
+//! avança o relógio, sem depender do tempo real. O carimbo de tempo
+//! viaja na opção TLV [`TIMESTAMP_OPTION`] (veja [`options`](crate::options)), sem
+//! aumentar o cabeçalho dos pacotes que não o usam.
+//!
+//! ## Exemplos
+//! ```rust
+//! use packet::Packet;
+//! use packet::clock::MockClock;
+//!
+//! let clock = MockClock::new(1_000);
+//! let packet = Packet::new_timestamped_with(1, 1, 7, vec![1, 2, 3], &clock);
+//!
+//! clock.advance(40);
+//! let received = Packet::from_bytes(&packet.to_bytes()).unwrap();
+//! assert_eq!(received.timestamp(), Some(1_000));
+//! assert_eq!(received.body(), [1, 2, 3]);
+//! ```
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use crate::builder::PacketBuilder;
+use crate::Packet;
+
+/// Tipo da opção TLV que guarda o carimbo de tempo, em milissegundos `u32`
+/// little-endian.
+pub const TIMESTAMP_OPTION: u8 = 0xF1;
+
+/// Fonte de tempo em milissegundos.
+///
+/// O valor é um `u32` que dá a volta a cada ~49 dias; compare carimbos com
+/// `wrapping_sub`, como as sequências.
+pub trait Clock {
+    /// Retorna o instante atual, em milissegundos desde uma origem arbitrária.
+    fn now_millis(&self) -> u32;
+
+    /// Retorna o instante atual como `Instant`, para os temporizadores da pilha.
+    ///
+    /// A implementação padrão soma [`Clock::now_millis`] a uma origem fixa do
+    /// processo, então dois relógios com os mesmos milissegundos dão o mesmo instante.
+    /// Ela deixa de ser monotônica quando os milissegundos dão a volta.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use std::time::Duration;
+    /// use packet::clock::{Clock, MockClock};
+    ///
+    /// let clock = MockClock::new(100);
+    /// let start = clock.now();
+    /// clock.advance(250);
+    /// assert_eq!(clock.now() - start, Duration::from_millis(250));
+    /// assert_eq!(MockClock::new(350).now(), clock.now());
+    /// ```
+    fn now(&self) -> Instant {
+        origin() + Duration::from_millis(self.now_millis() as u64)
+    }
+}
+
+impl<C: Clock + ?Sized> Clock for &C {
+    fn now_millis(&self) -> u32 {
+        (**self).now_millis()
+    }
+
+    fn now(&self) -> Instant {
+        (**self).now()
+    }
+}
+
+/// Origem do relógio do processo, fixada na primeira consulta.
+fn origin() -> Instant {
+    static ORIGIN: OnceLock<Instant> = OnceLock::new();
+    *ORIGIN.get_or_init(Instant::now)
+}
+
+/// Relógio monotônico do processo, contado a partir da primeira consulta.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_millis(&self) -> u32 {
+        origin().elapsed().as_millis() as u32
+    }
+
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Relógio controlado manualmente, para testes.
+///
+/// É `Send + Sync`: em um `Arc`, o teste avança o mesmo relógio lido pelas threads de
+/// um [`ServerRuntime`](crate::runtime::ServerRuntime).
+///
+/// ## Exemplos
+/// ```rust
+/// use packet::clock::{Clock, MockClock};
+///
+/// let clock = MockClock::new(u32::MAX);
+/// clock.advance(2);
+/// assert_eq!(clock.now_millis(), 1);
+///
+/// clock.set(500);
+/// assert_eq!(clock.now_millis(), 500);
+/// ```
+#[derive(Debug, Default)]
+pub struct MockClock {
+    /// Instante atual, em milissegundos.
+    now: AtomicU32,
+}
+
+impl Clone for MockClock {
+    /// Retorna um relógio independente, parado no instante atual deste.
+    fn clone(&self) -> Self {
+        Self::new(self.now_millis())
+    }
+}
+
+impl MockClock {
+    /// Cria um relógio parado no instante informado.
+    ///
+    /// ## Parâmetros
+    /// - `start`: Instante inicial, em milissegundos.
+    ///
+    /// ## Retorno
+    /// - `Self`: O relógio.
+    pub fn new(start: u32) -> Self {
+        Self {
+            now: AtomicU32::new(start),
+        }
+    }
+
+    /// Define o instante atual.
+    ///
+    /// ## Parâmetros
+    /// - `millis`: Novo instante, em milissegundos.
+    pub fn set(&self, millis: u32) {
+        self.now.store(millis, Ordering::Relaxed);
+    }
+
+    /// Avança o relógio, dando a volta após `u32::MAX`.
+    ///
+    /// ## Parâmetros
+    /// - `millis`: Milissegundos a avançar.
+    pub fn advance(&self, millis: u32) {
+        self.now.fetch_add(millis, Ordering::Relaxed);
+    }
+}
+
+impl Clock for MockClock {
+    fn now_millis(&self) -> u32 {
+        self.now.load(Ordering::Relaxed)
+    }
+}
+
+impl Packet {
+    /// Cria um pacote carimbado com o instante atual de [`SystemClock`].
+    ///
+    /// Equivalente a [`Packet::new_timestamped_with`] com `&SystemClock`.
+    pub fn new_timestamped(
+        message_type: u8,
+        sequence: u32,
+        player_id: u64,
+        payload: Vec<u8>,
+    ) -> Self {
+        Self::new_timestamped_with(message_type, sequence, player_id, payload, &SystemClock)
+    }
+
+    /// Cria um pacote carimbado com o instante atual do relógio informado.
+    ///
+    /// O carimbo é gravado na opção [`TIMESTAMP_OPTION`]; o payload informado passa a
+    /// ser o [`Packet::body`] do pacote.
+    ///
+    /// ## Parâmetros
+    /// - `message_type`: Tipo da mensagem.
+    /// - `sequence`: Sequência do pacote.
+    /// - `player_id`: Identificador único do jogador.
+    /// - `payload`: Dados da aplicação.
+    /// - `clock`: Fonte do carimbo de tempo.
+    ///
+    /// ## Retorno
+    /// - `Self`: O pacote com a flag [`PacketFlags::OPTIONS`](crate::PacketFlags::OPTIONS)
+    ///   e o carimbo de tempo.
+    pub fn new_timestamped_with(
+        message_type: u8,
+        sequence: u32,
+        player_id: u64,
+        payload: Vec<u8>,
+        clock: &impl Clock,
+    ) -> Self {
+        let mut builder = PacketBuilder::new(message_type, sequence, player_id);
+        builder
+            .add_option(TIMESTAMP_OPTION, &clock.now_millis().to_le_bytes())
+            .expect("a primeira opção sempre cabe na área de opções");
+        builder.extend(payload);
+        builder.seal()
+    }
+
+    /// Retorna o carimbo de tempo do pacote.
+    ///
+    /// ## Retorno
+    /// - `Option<u32>`: O instante registrado na opção [`TIMESTAMP_OPTION`], em
+    ///   milissegundos, ou `None` se o pacote não tem a opção.
+    pub fn timestamp(&self) -> Option<u32> {
+        self.options()
+            .find(|&(option_type, _)| option_type == TIMESTAMP_OPTION)
+            .and_then(|(_, value)| value.try_into().ok())
+            .map(u32::from_le_bytes)
+    }
+}
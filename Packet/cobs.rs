@@ -0,0 +1,133 @@
+//! # Módulo `cobs`
+//!
+//! Implementa COBS (*Consistent Overhead Byte Stuffing*), que elimina os bytes zero
+//! de um bloco de dados para que o zero possa ser usado como delimitador de pacotes
+//! em fluxos de bytes, como uma UART.
+//!
+//! O custo é de no máximo um byte a cada 254 bytes de entrada, mais o delimitador.
+//!
+//! ## Exemplos
+//! ```rust
+//! use packet::Packet;
+//! use packet::cobs::{cobs_decode, cobs_encode};
+//!
+//! let packet = Packet::new(1, 42, 12345, vec![0, 1, 0, 2]);
+//! let frame = cobs_encode(&packet.to_bytes());
+//!
+//! assert_eq!(frame.last(), Some(&0));
+//! assert!(!frame[..frame.len() - 1].contains(&0));
+//!
+//! let decoded = Packet::from_bytes(&cobs_decode(&frame).unwrap()).unwrap();
+//! assert_eq!(decoded, packet);
+//! ```
+
+use crate::PacketError;
+
+/// Byte que delimita o fim de cada quadro COBS.
+pub const COBS_DELIMITER: u8 = 0;
+
+/// Codifica os bytes em COBS e acrescenta o delimitador final.
+///
+/// ## Parâmetros
+/// - `bytes`: Dados a serem codificados, tipicamente um pacote serializado.
+///
+/// ## Retorno
+/// - `Vec<u8>`: O quadro codificado, sem zeros internos e terminado por [`COBS_DELIMITER`].
+pub fn cobs_encode(bytes: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(bytes.len() + bytes.len() / 254 + 2);
+    let mut code_index = out.len();
+    out.push(0);
+    let mut code = 1u8;
+
+    for &byte in bytes {
+        if byte == 0 {
+            out[code_index] = code;
+            code_index = out.len();
+            out.push(0);
+            code = 1;
+        } else {
+            out.push(byte);
+            code += 1;
+            if code == 0xFF {
+                out[code_index] = code;
+                code_index = out.len();
+                out.push(0);
+                code = 1;
+            }
+        }
+    }
+
+    out[code_index] = code;
+    out.push(COBS_DELIMITER);
+    out
+}
+
+/// Decodifica um quadro COBS terminado pelo delimitador.
+///
+/// O quadro deve conter exatamente um delimitador, na última posição. Zeros no
+/// meio do quadro, a ausência do delimitador ou um bloco que ultrapassa o fim do
+/// quadro são rejeitados.
+///
+/// ## Parâmetros
+/// - `bytes`: Quadro codificado, incluindo o delimitador final.
+///
+/// ## Retorno
+/// - `Result<Vec<u8>, PacketError>`: Os dados originais ou [`PacketError::MalformedFrame`].
+///
+/// ## Exemplos
+/// ```rust
+/// use packet::cobs::cobs_decode;
+///
+/// assert_eq!(cobs_decode(&[0x03, 0x11, 0x22, 0x02, 0x33, 0x00]).unwrap(), [0x11, 0x22, 0x00, 0x33]);
+///
+/// // Sem delimitador final.
+/// assert!(cobs_decode(&[0x02, 0x11]).is_err());
+/// // Delimitador no meio do quadro.
+/// assert!(cobs_decode(&[0x02, 0x00, 0x11, 0x00]).is_err());
+/// // Bloco que ultrapassa o fim do quadro.
+/// assert!(cobs_decode(&[0x05, 0x11, 0x00]).is_err());
+/// ```
+pub fn cobs_decode(bytes: &[u8]) -> Result<Vec<u8>, PacketError> {
+    let Some((&COBS_DELIMITER, body)) = bytes.split_last() else {
+        return Err(PacketError::MalformedFrame {
+            reason: "quadro COBS sem delimitador final",
+        });
+    };
+    if body.is_empty() {
+        return Err(PacketError::MalformedFrame {
+            reason: "quadro COBS vazio",
+        });
+    }
+
+    let mut out = Vec::with_capacity(body.len());
+    let mut index = 0;
+
+    while index < body.len() {
+        let code = body[index] as usize;
+        if code == 0 {
+            return Err(PacketError::MalformedFrame {
+                reason: "delimitador COBS fora do fim do quadro",
+            });
+        }
+        if index + code > body.len() {
+            return Err(PacketError::MalformedFrame {
+                reason: "bloco COBS ultrapassa o fim do quadro",
+            });
+        }
+
+        let block = &body[index + 1..index + code];
+        if block.contains(&COBS_DELIMITER) {
+            return Err(PacketError::MalformedFrame {
+                reason: "delimitador COBS fora do fim do quadro",
+            });
+        }
+
+        out.extend(block);
+        index += code;
+        if code < 0xFF && index < body.len() {
+            out.push(0);
+        }
+    }
+
+    Ok(out)
+}
@@ -0,0 +1,208 @@
+//! # Módulo `codec`
+//!
+//! Integração com o `tokio`, disponível com o recurso `tokio`.
+//!
+//! [`PacketCodec`] implementa os traits de `tokio_util::codec` sobre o formato do
+//! pacote: como o cabeçalho já declara o tamanho do payload, nenhum enquadramento
+//! adicional é necessário, e os bytes no fluxo são os mesmos de [`Packet::to_bytes`].
+//! [`PacketStream`] e [`PacketSink`] expõem as duas metades de uma conexão como
+//! `futures::Stream` e `futures::Sink`.
+//!
+//! ## Exemplos
+//! ```rust,no_run
+//! use futures::{SinkExt, StreamExt};
+//! use packet::Packet;
+//! use packet::codec;
+//! use tokio::net::TcpStream;
+//!
+//! # async fn run() -> Result<(), packet::PacketError> {
+//! let stream = TcpStream::connect("127.0.0.1:4000").await?;
+//! let (mut packets, mut sink) = codec::split(stream);
+//!
+//! sink.send(Packet::new(1, 1, 7, vec![1, 2, 3])).await?;
+//! while let Some(packet) = packets.next().await {
+//!     println!("recebido: {}", packet?.header.sequence);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use bytes::BytesMut;
+use futures::{Sink, Stream};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::TcpStream;
+use tokio_util::codec::{Decoder, Encoder, FramedRead, FramedWrite};
+
+use crate::config::ParseConfig;
+use crate::{Packet, PacketError, PacketHeader};
+
+/// Codificador e decodificador de pacotes para `tokio_util::codec`.
+///
+/// O decodificador aplica um [`ParseConfig`]: rejeita um `payload_size` acima de
+/// [`ParseConfig::max_payload`] antes de reservar espaço para o payload e verifica os
+/// limites e o checksum de cada pacote.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PacketCodec {
+    /// Limites dos pacotes aceitos.
+    limits: ParseConfig,
+}
+
+impl PacketCodec {
+    /// Cria o codec com os limites de [`ParseConfig::default`].
+    ///
+    /// ## Retorno
+    /// - `Self`: O codec.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Define os limites dos pacotes aceitos pelo decodificador.
+    ///
+    /// ## Parâmetros
+    /// - `limits`: Limites da leitura.
+    ///
+    /// ## Retorno
+    /// - `Self`: O codec com os novos limites.
+    pub fn with_parse_config(mut self, limits: ParseConfig) -> Self {
+        self.limits = limits;
+        self
+    }
+}
+
+impl Decoder for PacketCodec {
+    type Item = Packet;
+    type Error = PacketError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Packet>, PacketError> {
+        if src.len() < PacketHeader::SIZE {
+            src.reserve(PacketHeader::SIZE - src.len());
+            return Ok(None);
+        }
+
+        let header = PacketHeader::from_bytes(&src[..PacketHeader::SIZE])?;
+        self.limits.check_header(&header)?;
+
+        let len = header.packet_len()?;
+        if src.len() < len {
+            src.reserve(len - src.len());
+            return Ok(None);
+        }
+
+        let packet = Packet::from_bytes_with_limits(&src.split_to(len), &self.limits)?;
+        packet.check_checksum()?;
+        Ok(Some(packet))
+    }
+}
+
+impl Encoder<Packet> for PacketCodec {
+    type Error = PacketError;
+
+    fn encode(&mut self, packet: Packet, dst: &mut BytesMut) -> Result<(), PacketError> {
+        dst.reserve(packet.serialized_len());
+        dst.extend_from_slice(&packet.header.to_array());
+        dst.extend_from_slice(&packet.payload);
+        Ok(())
+    }
+}
+
+/// Fluxo assíncrono de pacotes lidos de uma conexão.
+///
+/// Após o primeiro erro de decodificação, o fluxo termina.
+#[derive(Debug)]
+pub struct PacketStream<R = OwnedReadHalf> {
+    inner: FramedRead<R, PacketCodec>,
+}
+
+impl<R: AsyncRead> PacketStream<R> {
+    /// Cria um fluxo de pacotes sobre um leitor assíncrono.
+    ///
+    /// ## Parâmetros
+    /// - `reader`: Leitor, normalmente a metade de leitura de um `TcpStream`.
+    ///
+    /// ## Retorno
+    /// - `Self`: Um fluxo que produz os pacotes na ordem em que chegam.
+    pub fn new(reader: R) -> Self {
+        Self {
+            inner: FramedRead::new(reader, PacketCodec::new()),
+        }
+    }
+
+    /// Define os limites dos pacotes lidos, como em [`PacketCodec::with_parse_config`].
+    ///
+    /// ## Parâmetros
+    /// - `limits`: Limites da leitura.
+    ///
+    /// ## Retorno
+    /// - `Self`: O fluxo com os novos limites.
+    pub fn with_parse_config(mut self, limits: ParseConfig) -> Self {
+        *self.inner.decoder_mut() = PacketCodec::new().with_parse_config(limits);
+        self
+    }
+}
+
+impl<R: AsyncRead + Unpin> Stream for PacketStream<R> {
+    type Item = Result<Packet, PacketError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.inner).poll_next(cx)
+    }
+}
+
+/// Destino assíncrono de pacotes escritos em uma conexão.
+#[derive(Debug)]
+pub struct PacketSink<W = OwnedWriteHalf> {
+    inner: FramedWrite<W, PacketCodec>,
+}
+
+impl<W: AsyncWrite> PacketSink<W> {
+    /// Cria um destino de pacotes sobre um escritor assíncrono.
+    ///
+    /// ## Parâmetros
+    /// - `writer`: Escritor, normalmente a metade de escrita de um `TcpStream`.
+    ///
+    /// ## Retorno
+    /// - `Self`: Um destino que acumula os pacotes e os escreve em `poll_flush`.
+    pub fn new(writer: W) -> Self {
+        Self {
+            inner: FramedWrite::new(writer, PacketCodec::new()),
+        }
+    }
+}
+
+impl<W: AsyncWrite + Unpin> Sink<Packet> for PacketSink<W> {
+    type Error = PacketError;
+
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), PacketError>> {
+        Pin::new(&mut self.inner).poll_ready(cx)
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, packet: Packet) -> Result<(), PacketError> {
+        Pin::new(&mut self.inner).start_send(packet)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), PacketError>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), PacketError>> {
+        Pin::new(&mut self.inner).poll_close(cx)
+    }
+}
+
+/// Divide uma conexão TCP em um fluxo de leitura e um destino de escrita de pacotes.
+///
+/// As metades são independentes e podem ser usadas em tarefas diferentes.
+///
+/// ## Parâmetros
+/// - `stream`: Conexão estabelecida.
+///
+/// ## Retorno
+/// - `(PacketStream, PacketSink)`: As metades de leitura e de escrita.
+pub fn split(stream: TcpStream) -> (PacketStream, PacketSink) {
+    let (reader, writer) = stream.into_split();
+    (PacketStream::new(reader), PacketSink::new(writer))
+}
@@ -0,0 +1,395 @@
+//! # Módulo `compare`
+//!
+//! Comparação de pacotes que desconsidera campos do cabeçalho alterados
+//! legitimamente no caminho, como `ttl` (decrementado por relays) e os checksums.
+//!
+//! ## Exemplos
+//! ```rust
+//! use packet::Packet;
+//! use packet::checksum::ChecksumScope;
+//! use packet::compare::HeaderFieldSet;
+//!
+//! let sent = Packet::new(1, 1, 7, vec![1, 2, 3]).with_checksum_scope(ChecksumScope::HeaderAndPayload);
+//! let mut relayed = sent.clone();
+//! relayed.decrement_ttl().unwrap();
+//!
+//! assert_ne!(relayed, sent);
+//! let relay_fields = HeaderFieldSet::TTL | HeaderFieldSet::HEADER_CHECKSUM | HeaderFieldSet::CHECKSUM;
+//! assert!(relayed.eq_ignoring(&sent, relay_fields));
+//! assert!(!relayed.eq_ignoring(&sent, HeaderFieldSet::TTL));
+//! assert_eq!(relayed.header.differing_fields(&sent.header), relay_fields);
+//! ```
+
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::{BitOr, BitOrAssign};
+
+use crate::{Packet, PacketError, PacketHeader};
+
+/// Conjunto de campos do cabeçalho, usado por [`Packet::eq_ignoring`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct HeaderFieldSet(u16);
+
+impl HeaderFieldSet {
+    /// Campo `message_type`.
+    pub const MESSAGE_TYPE: Self = Self(1 << 0);
+    /// Campo `flags`.
+    pub const FLAGS: Self = Self(1 << 1);
+    /// Campo `priority`.
+    pub const PRIORITY: Self = Self(1 << 2);
+    /// Campo `channel_id`.
+    pub const CHANNEL_ID: Self = Self(1 << 3);
+    /// Campo `content_type`.
+    pub const CONTENT_TYPE: Self = Self(1 << 4);
+    /// Campo `ttl`.
+    pub const TTL: Self = Self(1 << 5);
+    /// Campo `sequence`.
+    pub const SEQUENCE: Self = Self(1 << 6);
+    /// Campo `player_id`.
+    pub const PLAYER_ID: Self = Self(1 << 7);
+    /// Campo `group_id`.
+    pub const GROUP_ID: Self = Self(1 << 8);
+    /// Campo `ack`.
+    pub const ACK: Self = Self(1 << 9);
+    /// Campo `ack_bits`.
+    pub const ACK_BITS: Self = Self(1 << 10);
+    /// Campo `payload_size`.
+    pub const PAYLOAD_SIZE: Self = Self(1 << 11);
+    /// Campo `checksum`.
+    pub const CHECKSUM: Self = Self(1 << 12);
+    /// Campo `header_checksum`.
+    pub const HEADER_CHECKSUM: Self = Self(1 << 13);
+
+    /// Campos e seus nomes, na ordem do cabeçalho.
+    const NAMED: [(&'static str, Self); 14] = [
+        ("message_type", Self::MESSAGE_TYPE),
+        ("flags", Self::FLAGS),
+        ("priority", Self::PRIORITY),
+        ("channel_id", Self::CHANNEL_ID),
+        ("content_type", Self::CONTENT_TYPE),
+        ("ttl", Self::TTL),
+        ("sequence", Self::SEQUENCE),
+        ("player_id", Self::PLAYER_ID),
+        ("group_id", Self::GROUP_ID),
+        ("ack", Self::ACK),
+        ("ack_bits", Self::ACK_BITS),
+        ("payload_size", Self::PAYLOAD_SIZE),
+        ("header_checksum", Self::HEADER_CHECKSUM),
+        ("checksum", Self::CHECKSUM),
+    ];
+
+    /// Retorna um conjunto sem nenhum campo.
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Indica se todos os campos de `other` pertencem ao conjunto.
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Acrescenta os campos de `other` ao conjunto.
+    pub fn insert(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+
+    /// Indica se o conjunto não tem nenhum campo.
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Retorna os nomes dos campos do conjunto, na ordem do cabeçalho.
+    pub fn names(self) -> impl Iterator<Item = &'static str> {
+        Self::NAMED
+            .into_iter()
+            .filter(move |(_, field)| self.contains(*field))
+            .map(|(name, _)| name)
+    }
+}
+
+impl BitOr for HeaderFieldSet {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for HeaderFieldSet {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// Uma diferença entre dois pacotes, produzida por [`Packet::diff`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldDiff {
+    /// Um campo do cabeçalho tem valores diferentes.
+    Header {
+        /// Nome do campo, como em [`HeaderFieldSet::names`].
+        field: &'static str,
+        /// Valor no pacote comparado.
+        old: u64,
+        /// Valor no outro pacote.
+        new: u64,
+    },
+    /// Os payloads diferem.
+    Payload {
+        /// Posição do primeiro byte diferente; igual ao menor tamanho quando um
+        /// payload é prefixo do outro.
+        offset: usize,
+        /// Tamanho do payload no pacote comparado.
+        old_len: usize,
+        /// Tamanho do payload no outro pacote.
+        new_len: usize,
+    },
+}
+
+impl fmt::Display for FieldDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Header { field, old, new } => write!(f, "{field}: {old:#x} -> {new:#x}"),
+            Self::Payload {
+                offset,
+                old_len,
+                new_len,
+            } => write!(
+                f,
+                "payload: difere a partir do byte {offset} ({old_len} -> {new_len} bytes)"
+            ),
+        }
+    }
+}
+
+impl PacketHeader {
+    /// Retorna os valores dos campos, na ordem de [`HeaderFieldSet::NAMED`].
+    fn field_values(&self) -> [u64; 14] {
+        let PacketHeader {
+            message_type,
+            flags,
+            priority,
+            channel_id,
+            content_type,
+            ttl,
+            sequence,
+            player_id,
+            group_id,
+            ack,
+            ack_bits,
+            payload_size,
+            header_checksum,
+            checksum,
+        } = self;
+        [
+            u64::from(*message_type),
+            u64::from(flags.bits()),
+            u64::from(*priority),
+            u64::from(*channel_id),
+            u64::from(*content_type),
+            u64::from(*ttl),
+            u64::from(*sequence),
+            *player_id,
+            u64::from(*group_id),
+            u64::from(*ack),
+            u64::from(*ack_bits),
+            u64::from(*payload_size),
+            u64::from(*header_checksum),
+            u64::from(*checksum),
+        ]
+    }
+
+    /// Retorna os campos em que dois cabeçalhos diferem.
+    ///
+    /// ## Parâmetros
+    /// - `other`: Cabeçalho comparado.
+    ///
+    /// ## Retorno
+    /// - `HeaderFieldSet`: Os campos com valores diferentes; vazio se os cabeçalhos são iguais.
+    pub fn differing_fields(&self, other: &PacketHeader) -> HeaderFieldSet {
+        // A desestruturação exaustiva obriga a incluir aqui os campos novos.
+        let PacketHeader {
+            message_type,
+            flags,
+            priority,
+            channel_id,
+            content_type,
+            ttl,
+            sequence,
+            player_id,
+            group_id,
+            ack,
+            ack_bits,
+            payload_size,
+            header_checksum,
+            checksum,
+        } = self;
+
+        let mut differing = HeaderFieldSet::empty();
+        let mut compare = |field, equal: bool| {
+            if !equal {
+                differing.insert(field);
+            }
+        };
+        compare(
+            HeaderFieldSet::MESSAGE_TYPE,
+            *message_type == other.message_type,
+        );
+        compare(HeaderFieldSet::FLAGS, *flags == other.flags);
+        compare(HeaderFieldSet::PRIORITY, *priority == other.priority);
+        compare(HeaderFieldSet::CHANNEL_ID, *channel_id == other.channel_id);
+        compare(
+            HeaderFieldSet::CONTENT_TYPE,
+            *content_type == other.content_type,
+        );
+        compare(HeaderFieldSet::TTL, *ttl == other.ttl);
+        compare(HeaderFieldSet::SEQUENCE, *sequence == other.sequence);
+        compare(HeaderFieldSet::PLAYER_ID, *player_id == other.player_id);
+        compare(HeaderFieldSet::GROUP_ID, *group_id == other.group_id);
+        compare(HeaderFieldSet::ACK, *ack == other.ack);
+        compare(HeaderFieldSet::ACK_BITS, *ack_bits == other.ack_bits);
+        compare(
+            HeaderFieldSet::PAYLOAD_SIZE,
+            *payload_size == other.payload_size,
+        );
+        compare(
+            HeaderFieldSet::HEADER_CHECKSUM,
+            *header_checksum == other.header_checksum,
+        );
+        compare(HeaderFieldSet::CHECKSUM, *checksum == other.checksum);
+        differing
+    }
+
+    /// Compara dois cabeçalhos desconsiderando os campos em `ignore`.
+    ///
+    /// ## Parâmetros
+    /// - `other`: Cabeçalho comparado.
+    /// - `ignore`: Campos que podem diferir.
+    ///
+    /// ## Retorno
+    /// - `bool`: `true` se todos os demais campos são iguais.
+    pub fn eq_ignoring(&self, other: &PacketHeader, ignore: HeaderFieldSet) -> bool {
+        ignore.contains(self.differing_fields(other))
+    }
+}
+
+impl Packet {
+    /// Compara dois pacotes desconsiderando os campos do cabeçalho em `ignore`.
+    ///
+    /// O payload é sempre comparado.
+    ///
+    /// ## Parâmetros
+    /// - `other`: Pacote comparado.
+    /// - `ignore`: Campos do cabeçalho que podem diferir.
+    ///
+    /// ## Retorno
+    /// - `bool`: `true` se os payloads e os demais campos do cabeçalho são iguais.
+    pub fn eq_ignoring(&self, other: &Packet, ignore: HeaderFieldSet) -> bool {
+        self.payload == other.payload && self.header.eq_ignoring(&other.header, ignore)
+    }
+
+    /// Lista as diferenças entre dois pacotes, campo a campo.
+    ///
+    /// Destinado a testes e depuração: em vez de comparar dois `Debug` inteiros,
+    /// mostra apenas o que mudou.
+    ///
+    /// ## Parâmetros
+    /// - `other`: Pacote comparado.
+    ///
+    /// ## Retorno
+    /// - `Vec<FieldDiff>`: Os campos do cabeçalho que diferem, na ordem do cabeçalho,
+    ///   seguidos de [`FieldDiff::Payload`] se os payloads diferem; vazio se os pacotes
+    ///   são iguais.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::Packet;
+    /// use packet::compare::FieldDiff;
+    ///
+    /// let sent = Packet::new(1, 1, 7, vec![1, 2, 3]);
+    /// assert!(sent.diff(&sent).is_empty());
+    ///
+    /// let mut received = sent.clone();
+    /// received.header.sequence = 2;
+    /// received.payload[1] = 9;
+    ///
+    /// let diff = received.diff(&sent);
+    /// assert_eq!(
+    ///     diff[0],
+    ///     FieldDiff::Header { field: "sequence", old: 2, new: 1 }
+    /// );
+    /// assert_eq!(
+    ///     diff[1],
+    ///     FieldDiff::Payload { offset: 1, old_len: 3, new_len: 3 }
+    /// );
+    /// assert_eq!(diff[0].to_string(), "sequence: 0x2 -> 0x1");
+    /// ```
+    pub fn diff(&self, other: &Packet) -> Vec<FieldDiff> {
+        let differing = self.header.differing_fields(&other.header);
+        let old = self.header.field_values();
+        let new = other.header.field_values();
+
+        let mut diffs: Vec<FieldDiff> = HeaderFieldSet::NAMED
+            .iter()
+            .zip(old.into_iter().zip(new))
+            .filter(|((_, field), _)| differing.contains(*field))
+            .map(|((name, _), (old, new))| FieldDiff::Header {
+                field: name,
+                old,
+                new,
+            })
+            .collect();
+
+        if self.payload != other.payload {
+            let offset = self
+                .payload
+                .iter()
+                .zip(&other.payload)
+                .position(|(old, new)| old != new)
+                .unwrap_or_else(|| self.payload.len().min(other.payload.len()));
+            diffs.push(FieldDiff::Payload {
+                offset,
+                old_len: self.payload.len(),
+                new_len: other.payload.len(),
+            });
+        }
+        diffs
+    }
+
+    /// Serializa o pacote, interpreta os bytes de volta e compara com o original.
+    ///
+    /// Destinado a verificações de desenvolvimento no caminho de envio, como
+    /// `debug_assert!(packet.debug_roundtrip().is_ok())`, para que um pacote que não
+    /// sobrevive à rede seja detectado na origem e não no receptor.
+    ///
+    /// ## Retorno
+    /// - `Result<(), PacketError>`: O erro de [`Packet::from_bytes`] se os bytes não
+    ///   puderem ser interpretados, ou [`PacketError::RoundtripMismatch`] com os campos
+    ///   que mudaram.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::{Packet, PacketError};
+    ///
+    /// let packet = Packet::new(1, 1, 7, vec![1, 2, 3]);
+    /// assert_eq!(packet.debug_roundtrip(), Ok(()));
+    ///
+    /// // Um `payload_size` editado à mão não corresponde ao payload serializado,
+    /// // mesmo com o `header_checksum` atualizado.
+    /// let mut broken = packet.clone();
+    /// broken.header.payload_size = 2;
+    /// broken.header.header_checksum = broken.header.expected_header_checksum();
+    /// assert!(matches!(
+    ///     broken.debug_roundtrip(),
+    ///     Err(PacketError::PayloadSizeMismatch { .. })
+    /// ));
+    /// ```
+    pub fn debug_roundtrip(&self) -> Result<(), PacketError> {
+        let parsed = Packet::from_bytes(&self.to_bytes())?;
+        let fields = self.header.differing_fields(&parsed.header);
+        let payload = self.payload != parsed.payload;
+        if !fields.is_empty() || payload {
+            return Err(PacketError::RoundtripMismatch { fields, payload });
+        }
+        Ok(())
+    }
+}
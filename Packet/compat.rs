@@ -0,0 +1,456 @@
+//! # Módulo `compat`
+//!
+//! Convivência com o formato de cabeçalho original, para migrar jogos em produção sem
+//! desconectar os clientes antigos.
+//!
+//! O cabeçalho original tem [`LEGACY_HEADER_SIZE`] (21) bytes, sem bytes mágicos:
+//! `message_type`, `sequence`, `player_id`, `payload_size` e `checksum`, em
+//! little-endian, com o checksum igual à soma simples dos bytes do payload. Ele é
+//! tratado como a versão [`LEGACY_VERSION`] (`0`) do formato, e o cabeçalho atual,
+//! iniciado por [`PacketHeader::MAGIC`], como a versão gravada nele. [`decode_any`]
+//! aceita as duas; [`Packet::to_legacy_bytes`] grava o formato original, que só leva
+//! os campos que ele conhece.
+//!
+//! [`CompatTransport`] aplica isso por conexão: guarda a versão usada por cada origem
+//! e responde a ela na mesma versão, de modo que um
+//! [`Server`](crate::connection::Server) sobre ele atende os dois tipos de cliente. O
+//! primeiro datagrama antigo de cada origem produz um [`CompatEvent::Deprecated`], e
+//! [`CompatTransport::legacy_peers`] diz quantos clientes antigos restam. Os
+//! datagramas antigos não são autenticados, então uma origem que já usou o formato
+//! atual nunca volta ao original, e o número de origens guardadas é limitado.
+//!
+//! ## Exemplos
+//! ```rust
+//! use packet::Packet;
+//! use packet::compat::{decode_any, LEGACY_HEADER_SIZE, LEGACY_VERSION};
+//!
+//! let packet = Packet::new(1, 9, 7, vec![1, 2, 3]);
+//! let legacy = packet.to_legacy_bytes();
+//! assert_eq!(legacy.len(), LEGACY_HEADER_SIZE + 3);
+//!
+//! let (version, decoded) = decode_any(&legacy).unwrap();
+//! assert_eq!(version, LEGACY_VERSION);
+//! assert_eq!(decoded, packet);
+//!
+//! let (version, decoded) = decode_any(&packet.to_bytes()).unwrap();
+//! assert_eq!(version, packet::PacketHeader::VERSION);
+//! assert_eq!(decoded, packet);
+//! ```
+
+use std::collections::HashMap;
+use std::io;
+use std::net::SocketAddr;
+
+use crate::socket::parse_datagram;
+use crate::transport::{Transport, UdpTransport};
+use crate::{recommended_recv_buffer_size, Packet, PacketError, PacketHeader};
+
+/// Versão atribuída ao formato de cabeçalho original, sem bytes mágicos.
+pub const LEGACY_VERSION: u8 = 0;
+
+/// Tamanho do cabeçalho original, em bytes.
+pub const LEGACY_HEADER_SIZE: usize = 21;
+
+/// Quantidade padrão de origens cuja versão um [`CompatTransport`] guarda.
+pub const DEFAULT_MAX_PEERS: usize = 4096;
+
+/// Soma simples dos bytes, o checksum do formato original.
+fn legacy_checksum(payload: &[u8]) -> u32 {
+    payload
+        .iter()
+        .fold(0u32, |sum, &b| sum.wrapping_add(b as u32))
+}
+
+/// Retorna a versão do formato de um datagrama, sem interpretá-lo.
+///
+/// ## Parâmetros
+/// - `bytes`: Bytes recebidos.
+///
+/// ## Retorno
+/// - `u8`: A versão gravada após [`PacketHeader::MAGIC`], ou [`LEGACY_VERSION`] se o
+///   datagrama não começa pelos bytes mágicos.
+pub fn header_version(bytes: &[u8]) -> u8 {
+    match bytes.get(PacketHeader::VERSION_OFFSET) {
+        Some(&version) if bytes.starts_with(&PacketHeader::MAGIC) => version,
+        _ => LEGACY_VERSION,
+    }
+}
+
+/// Interpreta um datagrama no formato original ou no atual.
+///
+/// Um pacote antigo cujos primeiros bytes coincidem com [`PacketHeader::MAGIC`] ainda
+/// é aceito: se o formato atual o recusa, o original é tentado.
+///
+/// ## Parâmetros
+/// - `bytes`: Bytes de um único pacote.
+///
+/// ## Retorno
+/// - `Result<(u8, Packet), PacketError>`: A versão do formato e o pacote, ou o erro do
+///   formato indicado pelos primeiros bytes.
+pub fn decode_any(bytes: &[u8]) -> Result<(u8, Packet), PacketError> {
+    if header_version(bytes) == LEGACY_VERSION {
+        return Ok((LEGACY_VERSION, Packet::from_legacy_bytes(bytes)?));
+    }
+    match Packet::from_bytes(bytes) {
+        Ok(packet) => Ok((PacketHeader::VERSION, packet)),
+        Err(error) => Packet::from_legacy_bytes(bytes)
+            .map(|packet| (LEGACY_VERSION, packet))
+            .map_err(|_| error),
+    }
+}
+
+impl Packet {
+    /// Serializa o pacote no formato de cabeçalho original.
+    ///
+    /// Só `message_type`, `sequence`, `player_id` e o payload são gravados; as flags,
+    /// o canal, as confirmações e os demais campos do cabeçalho atual se perdem.
+    ///
+    /// ## Retorno
+    /// - `Vec<u8>`: Os [`LEGACY_HEADER_SIZE`] bytes do cabeçalho seguidos do payload.
+    pub fn to_legacy_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(LEGACY_HEADER_SIZE + self.payload.len());
+        bytes.push(self.header.message_type);
+        bytes.extend_from_slice(&self.header.sequence.to_le_bytes());
+        bytes.extend_from_slice(&self.header.player_id.to_le_bytes());
+        bytes.extend_from_slice(&(self.payload.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&legacy_checksum(&self.payload).to_le_bytes());
+        bytes.extend_from_slice(&self.payload);
+        bytes
+    }
+
+    /// Reconstrói um pacote serializado no formato de cabeçalho original.
+    ///
+    /// ## Parâmetros
+    /// - `bytes`: Bytes de um único pacote antigo.
+    ///
+    /// ## Retorno
+    /// - `Result<Packet, PacketError>`: O pacote, como criado por [`Packet::new`] com os
+    ///   campos do cabeçalho original; [`PacketError::TooShort`],
+    ///   [`PacketError::PayloadSizeMismatch`] ou [`PacketError::ChecksumMismatch`] se
+    ///   os bytes não formam um pacote antigo válido.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::{Packet, PacketError};
+    ///
+    /// // message_type, sequence, player_id, payload_size, checksum e payload.
+    /// let mut bytes = vec![1, 5, 0, 0, 0, 7, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 30, 0, 0, 0];
+    /// bytes.extend([10, 20]);
+    /// let packet = Packet::from_legacy_bytes(&bytes).unwrap();
+    /// assert_eq!((packet.header.sequence, packet.header.player_id), (5, 7));
+    /// assert_eq!(packet.payload, [10, 20]);
+    ///
+    /// bytes[21] = 11;
+    /// assert!(matches!(
+    ///     Packet::from_legacy_bytes(&bytes),
+    ///     Err(PacketError::ChecksumMismatch { .. })
+    /// ));
+    /// ```
+    pub fn from_legacy_bytes(bytes: &[u8]) -> Result<Packet, PacketError> {
+        if bytes.len() < LEGACY_HEADER_SIZE {
+            return Err(PacketError::TooShort {
+                expected: LEGACY_HEADER_SIZE,
+                actual: bytes.len(),
+            });
+        }
+        let word = |offset: usize| {
+            u32::from_le_bytes(bytes[offset..offset + 4].try_into().expect("4 bytes"))
+        };
+        let player_id = u64::from_le_bytes(bytes[5..13].try_into().expect("8 bytes"));
+        let (declared, expected) = (word(13) as usize, word(17));
+        let payload = &bytes[LEGACY_HEADER_SIZE..];
+        if payload.len() != declared {
+            return Err(PacketError::PayloadSizeMismatch {
+                declared,
+                actual: payload.len(),
+            });
+        }
+        let actual = legacy_checksum(payload);
+        if actual != expected {
+            return Err(PacketError::ChecksumMismatch { expected, actual });
+        }
+        Ok(Packet::new(bytes[0], word(1), player_id, payload.to_vec()))
+    }
+}
+
+/// Evento de [`CompatTransport`] para acompanhar a migração.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CompatEvent {
+    /// Uma origem enviou seu primeiro datagrama em um formato obsoleto.
+    Deprecated {
+        /// Origem do datagrama.
+        addr: SocketAddr,
+        /// Versão do formato usada.
+        version: u8,
+    },
+    /// Uma origem que usava um formato obsoleto passou ao atual.
+    Upgraded {
+        /// Origem do datagrama.
+        addr: SocketAddr,
+    },
+}
+
+/// Transporte UDP que recebe os formatos original e atual e responde a cada origem no
+/// formato que ela usa.
+///
+/// ## Exemplos
+/// ```rust
+/// use std::net::UdpSocket;
+/// use packet::Packet;
+/// use packet::compat::{CompatEvent, CompatTransport, LEGACY_VERSION};
+/// use packet::transport::{Transport, UdpTransport};
+///
+/// let mut server = CompatTransport::new(UdpTransport::bind("127.0.0.1:0").unwrap());
+/// let server_addr = server.local_addr().unwrap();
+/// let old_client = UdpSocket::bind("127.0.0.1:0").unwrap();
+/// let old_addr = old_client.local_addr().unwrap();
+///
+/// // Um cliente antigo envia o cabeçalho original e recebe a resposta nele.
+/// let hello = Packet::new(1, 1, 7, b"oi".to_vec());
+/// old_client.send_to(&hello.to_legacy_bytes(), server_addr).unwrap();
+/// let (from, received) = server.recv_packet().unwrap();
+/// assert_eq!((from, received.payload.as_slice()), (old_addr, &b"oi"[..]));
+/// assert_eq!(server.peer_version(old_addr), Some(LEGACY_VERSION));
+///
+/// server.send_packet(old_addr, &Packet::new(1, 1, 0, b"ola".to_vec())).unwrap();
+/// let mut buffer = [0; 64];
+/// let len = old_client.recv(&mut buffer).unwrap();
+/// assert_eq!(Packet::from_legacy_bytes(&buffer[..len]).unwrap().payload, b"ola");
+///
+/// assert_eq!(server.legacy_peers(), 1);
+/// assert_eq!(
+///     server.take_events(),
+///     [CompatEvent::Deprecated { addr: old_addr, version: LEGACY_VERSION }]
+/// );
+/// ```
+#[derive(Debug)]
+pub struct CompatTransport {
+    /// Transporte subjacente.
+    transport: UdpTransport,
+    /// Versão do formato usada por cada origem.
+    versions: HashMap<SocketAddr, u8>,
+    /// Limite de origens em `versions`.
+    max_peers: usize,
+    /// Eventos ainda não retornados por [`CompatTransport::take_events`].
+    events: Vec<CompatEvent>,
+    /// Buffer reutilizado a cada recepção.
+    buffer: Vec<u8>,
+}
+
+impl CompatTransport {
+    /// Envolve um transporte, sem origens conhecidas, guardando até
+    /// [`DEFAULT_MAX_PEERS`] origens.
+    ///
+    /// ## Parâmetros
+    /// - `transport`: Transporte subjacente; seus limites de
+    ///   [`UdpTransport::parse_config`] valem para os dois formatos.
+    ///
+    /// ## Retorno
+    /// - `Self`: O transporte.
+    pub fn new(transport: UdpTransport) -> Self {
+        Self {
+            transport,
+            versions: HashMap::new(),
+            max_peers: DEFAULT_MAX_PEERS,
+            events: Vec::new(),
+            buffer: vec![0; recommended_recv_buffer_size()],
+        }
+    }
+
+    /// Retorna o transporte limitando as origens cuja versão é guardada.
+    ///
+    /// Com o limite atingido, uma nova origem toma o lugar de uma que usa um formato
+    /// obsoleto; se todas usam o atual, a nova não é guardada e recebe o formato atual.
+    /// As origens no formato atual só saem por [`CompatTransport::forget`].
+    ///
+    /// ## Parâmetros
+    /// - `max_peers`: Quantidade máxima de origens.
+    ///
+    /// ## Retorno
+    /// - `Self`: O transporte com o limite.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use std::net::UdpSocket;
+    /// use packet::Packet;
+    /// use packet::compat::{CompatTransport, LEGACY_VERSION};
+    /// use packet::transport::{Transport, UdpTransport};
+    ///
+    /// let mut server = CompatTransport::new(UdpTransport::bind("127.0.0.1:0").unwrap())
+    ///     .with_max_peers(1);
+    /// let server_addr = server.local_addr().unwrap();
+    /// let legacy = Packet::new(1, 1, 7, vec![1]).to_legacy_bytes();
+    ///
+    /// let (first, second) = (
+    ///     UdpSocket::bind("127.0.0.1:0").unwrap(),
+    ///     UdpSocket::bind("127.0.0.1:0").unwrap(),
+    /// );
+    /// for client in [&first, &second] {
+    ///     client.send_to(&legacy, server_addr).unwrap();
+    ///     server.recv_packet().unwrap();
+    /// }
+    ///
+    /// // A origem antiga mais recente ocupa a única vaga.
+    /// assert_eq!(server.peer_version(first.local_addr().unwrap()), None);
+    /// assert_eq!(
+    ///     server.peer_version(second.local_addr().unwrap()),
+    ///     Some(LEGACY_VERSION)
+    /// );
+    /// assert_eq!(server.legacy_peers(), 1);
+    /// ```
+    pub fn with_max_peers(mut self, max_peers: usize) -> Self {
+        self.max_peers = max_peers;
+        self
+    }
+
+    /// Retorna o transporte subjacente.
+    pub fn get_ref(&self) -> &UdpTransport {
+        &self.transport
+    }
+
+    /// Retorna a versão do formato usada por uma origem, se ela já enviou algum
+    /// datagrama válido.
+    ///
+    /// A versão só sobe: depois de um datagrama no formato atual, os antigos da mesma
+    /// origem, que qualquer um pode forjar, ainda são recebidos mas não a rebaixam.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use std::net::UdpSocket;
+    /// use packet::{Packet, PacketHeader};
+    /// use packet::compat::CompatTransport;
+    /// use packet::transport::{Transport, UdpTransport};
+    ///
+    /// let mut server = CompatTransport::new(UdpTransport::bind("127.0.0.1:0").unwrap());
+    /// let server_addr = server.local_addr().unwrap();
+    /// let client = UdpSocket::bind("127.0.0.1:0").unwrap();
+    /// let client_addr = client.local_addr().unwrap();
+    ///
+    /// let packet = Packet::new(1, 1, 7, vec![1]);
+    /// client.send_to(&packet.to_bytes(), server_addr).unwrap();
+    /// server.recv_packet().unwrap();
+    /// client.send_to(&packet.to_legacy_bytes(), server_addr).unwrap();
+    /// server.recv_packet().unwrap();
+    ///
+    /// assert_eq!(server.peer_version(client_addr), Some(PacketHeader::VERSION));
+    /// assert_eq!(server.legacy_peers(), 0);
+    /// assert!(server.take_events().is_empty());
+    /// ```
+    pub fn peer_version(&self, addr: SocketAddr) -> Option<u8> {
+        self.versions.get(&addr).copied()
+    }
+
+    /// Retorna a quantidade de origens que usam um formato obsoleto.
+    pub fn legacy_peers(&self) -> usize {
+        self.versions
+            .values()
+            .filter(|&&version| version < PacketHeader::VERSION)
+            .count()
+    }
+
+    /// Esquece a versão de uma origem, por exemplo ao encerrar sua conexão; os
+    /// próximos envios a ela usam o formato atual.
+    ///
+    /// ## Retorno
+    /// - `Option<u8>`: A versão que estava registrada.
+    pub fn forget(&mut self, addr: SocketAddr) -> Option<u8> {
+        self.versions.remove(&addr)
+    }
+
+    /// Retorna e descarta os eventos ocorridos desde a última chamada.
+    pub fn take_events(&mut self) -> Vec<CompatEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Registra a versão usada por uma origem, gerando os eventos da mudança.
+    ///
+    /// Versões inferiores à registrada são ignoradas, e uma origem nova só entra com
+    /// vaga, se necessário no lugar de uma que usa um formato obsoleto.
+    fn record(&mut self, addr: SocketAddr, version: u8) {
+        let previous = self.versions.get(&addr).copied();
+        if previous.is_some_and(|previous| version <= previous) {
+            return;
+        }
+        if previous.is_none() && self.versions.len() >= self.max_peers {
+            let legacy = self
+                .versions
+                .iter()
+                .find(|&(_, &version)| version < PacketHeader::VERSION)
+                .map(|(&addr, _)| addr);
+            let Some(legacy) = legacy else {
+                return;
+            };
+            self.versions.remove(&legacy);
+        }
+        self.versions.insert(addr, version);
+        if version < PacketHeader::VERSION {
+            self.events.push(CompatEvent::Deprecated { addr, version });
+        } else if previous.is_some_and(|previous| previous < PacketHeader::VERSION) {
+            self.events.push(CompatEvent::Upgraded { addr });
+        }
+    }
+
+    /// Interpreta o datagrama de `len` bytes no buffer, no formato que ele indica.
+    fn decode(&self, len: usize) -> Result<(u8, Packet), PacketError> {
+        let datagram = &self.buffer[..len];
+        let decoded = match header_version(datagram) {
+            LEGACY_VERSION => {
+                Packet::from_legacy_bytes(datagram).map(|packet| (LEGACY_VERSION, packet))
+            }
+            version => parse_datagram(datagram, self.buffer.len())
+                .map(|packet| (version, packet.to_packet()))
+                .or_else(|error| {
+                    Packet::from_legacy_bytes(datagram)
+                        .map(|packet| (LEGACY_VERSION, packet))
+                        .map_err(|_| error)
+                }),
+        };
+        let (version, packet) = decoded?;
+        self.transport
+            .parse_config()
+            .check(&packet.header, &packet.payload)?;
+        Ok((version, packet))
+    }
+
+    /// Interpreta o datagrama recebido de `from` e registra a versão que ele usa.
+    fn receive(&mut self, from: SocketAddr, len: usize) -> Result<Packet, PacketError> {
+        let (version, packet) = self.decode(len)?;
+        self.record(from, version);
+        Ok(packet)
+    }
+}
+
+impl Transport for CompatTransport {
+    /// Envia um pacote no formato usado pelo destino, ou no atual se o destino ainda
+    /// não enviou nada.
+    fn send_packet(&mut self, addr: SocketAddr, packet: &Packet) -> Result<usize, PacketError> {
+        let bytes = match self.versions.get(&addr) {
+            Some(&LEGACY_VERSION) => packet.to_legacy_bytes(),
+            _ => packet.to_bytes(),
+        };
+        Ok(self.transport.get_ref().send_to(&bytes, addr)?)
+    }
+
+    /// Recebe o próximo pacote, em qualquer dos formatos, registrando a versão usada
+    /// pela origem.
+    fn recv_packet(&mut self) -> Result<(SocketAddr, Packet), PacketError> {
+        let (len, from) = self.transport.get_ref().recv_from(&mut self.buffer)?;
+        self.receive(from, len).map(|packet| (from, packet))
+    }
+
+    fn recv_from(&mut self) -> io::Result<Option<(SocketAddr, Result<Packet, PacketError>)>> {
+        match self.transport.get_ref().recv_from(&mut self.buffer) {
+            Ok((len, from)) => Ok(Some((from, self.receive(from, len)))),
+            Err(error) if error.kind() == io::ErrorKind::WouldBlock => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+
+    fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.transport.local_addr()
+    }
+
+    fn set_nonblocking(&mut self, nonblocking: bool) -> io::Result<()> {
+        self.transport.get_ref().set_nonblocking(nonblocking)
+    }
+}
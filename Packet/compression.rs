@@ -0,0 +1,356 @@
+//! # Módulo `compression`
+//!
+//! Marcação de payloads comprimidos e métricas de compressão.
+//!
+//! Um pacote com a flag [`PacketFlags::COMPRESSED`] tem os dados da aplicação
+//! ([`Packet::body`]) comprimidos e registra o tamanho original na opção TLV
+//! [`UNCOMPRESSED_LEN_OPTION`] (veja [`options`](crate::options)). O tamanho original
+//! serve tanto para métricas quanto como limite confiável para o descompressor, que
+//! pode rejeitar dados que se expandem além do declarado.
+//!
+//! Com o recurso `compression`, o crate também comprime e descomprime: um
+//! [`Compressor`] comprime os dados com LZ4 (`lz4_flex`) ou zstd (`zstd`), registrando
+//! o algoritmo na opção [`COMPRESSION_ALGORITHM_OPTION`], e [`Packet::decompress`]
+//! restaura o pacote original. Dados menores que o limite do compressor, ou que não
+//! diminuem, seguem sem compressão.
+//!
+//! ## Exemplos
+//! ```rust
+//! use packet::builder::PacketBuilder;
+//!
+//! // Dados comprimidos pela aplicação: 1000 bytes reduzidos a 250.
+//! let mut builder = PacketBuilder::new(1, 1, 7);
+//! builder.mark_compressed(1000).unwrap();
+//! builder.extend(vec![0u8; 250]);
+//! let packet = builder.seal();
+//!
+//! assert_eq!(packet.uncompressed_len(), Some(1000));
+//! assert_eq!(packet.compression_ratio(), 0.25);
+//! ```
+
+use crate::builder::PacketBuilder;
+#[cfg(feature = "compression")]
+use crate::options::OPTIONS_LENGTH_SIZE;
+#[cfg(feature = "compression")]
+use crate::MAX_PAYLOAD_SIZE;
+use crate::{Packet, PacketError, PacketFlags};
+
+/// Tipo da opção TLV que guarda o tamanho original, em `u32` little-endian.
+///
+/// Os tipos de opção a partir de `0xF0` são reservados ao crate.
+pub const UNCOMPRESSED_LEN_OPTION: u8 = 0xF0;
+
+/// Tipo da opção TLV que guarda, em um byte, o [`CompressionAlgorithm`] usado.
+#[cfg(feature = "compression")]
+pub const COMPRESSION_ALGORITHM_OPTION: u8 = 0xF2;
+
+/// Tamanho mínimo, em bytes, dos dados comprimidos por um [`Compressor`] padrão.
+#[cfg(feature = "compression")]
+pub const DEFAULT_COMPRESSION_THRESHOLD: usize = 128;
+
+impl Packet {
+    /// Retorna o tamanho dos dados antes da compressão.
+    ///
+    /// ## Retorno
+    /// - `Option<usize>`: O tamanho registrado na opção [`UNCOMPRESSED_LEN_OPTION`], ou
+    ///   `None` se o pacote não tem a flag [`PacketFlags::COMPRESSED`] ou a opção.
+    pub fn uncompressed_len(&self) -> Option<usize> {
+        if !self.header.flags.contains(PacketFlags::COMPRESSED) {
+            return None;
+        }
+        self.options()
+            .find(|&(option_type, _)| option_type == UNCOMPRESSED_LEN_OPTION)
+            .and_then(|(_, value)| value.try_into().ok())
+            .map(|len| u32::from_le_bytes(len) as usize)
+    }
+
+    /// Retorna a razão entre o tamanho comprimido e o original dos dados da aplicação.
+    ///
+    /// ## Retorno
+    /// - `f32`: Valores abaixo de `1.0` indicam que a compressão reduziu os dados;
+    ///   `1.0` para pacotes sem [`Packet::uncompressed_len`] ou com dados originais vazios.
+    pub fn compression_ratio(&self) -> f32 {
+        match self.uncompressed_len() {
+            Some(original) if original > 0 => self.body().len() as f32 / original as f32,
+            _ => 1.0,
+        }
+    }
+}
+
+impl PacketBuilder {
+    /// Marca o payload montado como comprimido, registrando o tamanho original.
+    ///
+    /// Ao selar, o pacote recebe a flag [`PacketFlags::COMPRESSED`] e a opção
+    /// [`UNCOMPRESSED_LEN_OPTION`].
+    ///
+    /// ## Parâmetros
+    /// - `uncompressed_len`: Tamanho dos dados antes da compressão.
+    ///
+    /// ## Retorno
+    /// - `Result<(), PacketError>`: O erro de [`PacketBuilder::add_option`] se a área de
+    ///   opções está cheia; nesse caso o pacote não é marcado.
+    pub fn mark_compressed(&mut self, uncompressed_len: u32) -> Result<(), PacketError> {
+        self.add_option(UNCOMPRESSED_LEN_OPTION, &uncompressed_len.to_le_bytes())?;
+        self.flags.insert(PacketFlags::COMPRESSED);
+        Ok(())
+    }
+}
+
+/// Algoritmo de compressão, disponível com o recurso `compression`.
+#[cfg(feature = "compression")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CompressionAlgorithm {
+    /// LZ4 em blocos: rápido, com compressão moderada.
+    Lz4,
+    /// zstd no nível padrão: mais lento, com compressão maior.
+    Zstd,
+}
+
+#[cfg(feature = "compression")]
+impl CompressionAlgorithm {
+    /// Todos os algoritmos, na ordem de seus identificadores.
+    pub const ALL: [Self; 2] = [Self::Lz4, Self::Zstd];
+
+    /// Retorna o identificador do algoritmo, gravado em [`COMPRESSION_ALGORITHM_OPTION`].
+    ///
+    /// Cada identificador é um bit distinto, de modo que um conjunto de algoritmos cabe
+    /// em um byte.
+    pub fn id(self) -> u8 {
+        match self {
+            Self::Lz4 => 1,
+            Self::Zstd => 2,
+        }
+    }
+
+    /// Retorna o algoritmo com o identificador informado.
+    pub fn from_id(id: u8) -> Option<Self> {
+        Self::ALL.into_iter().find(|algorithm| algorithm.id() == id)
+    }
+
+    /// Comprime os dados.
+    fn compress(self, data: &[u8]) -> Vec<u8> {
+        match self {
+            Self::Lz4 => lz4_flex::block::compress(data),
+            Self::Zstd => zstd::bulk::compress(data, zstd::DEFAULT_COMPRESSION_LEVEL)
+                .expect("compressão zstd em memória"),
+        }
+    }
+
+    /// Descomprime os dados, que não podem se expandir além de `len` bytes.
+    fn decompress(self, data: &[u8], len: usize) -> Option<Vec<u8>> {
+        match self {
+            Self::Lz4 => lz4_flex::block::decompress(data, len).ok(),
+            Self::Zstd => zstd::bulk::decompress(data, len).ok(),
+        }
+    }
+}
+
+/// Compressor de pacotes com um algoritmo e um limite de tamanho.
+///
+/// ## Exemplos
+/// ```rust
+/// use packet::Packet;
+/// use packet::compression::{CompressionAlgorithm, Compressor};
+///
+/// let compressor = Compressor::new(CompressionAlgorithm::Lz4).with_min_size(64);
+///
+/// let snapshot = Packet::new(1, 1, 7, vec![0; 1000]);
+/// let compressed = compressor.compress(snapshot.clone());
+/// assert!(compressed.payload.len() < 100);
+/// assert_eq!(compressed.uncompressed_len(), Some(1000));
+/// assert_eq!(compressed.decompress().unwrap(), snapshot);
+///
+/// // Abaixo do limite, o pacote não é alterado.
+/// let small = Packet::new(1, 2, 7, vec![0; 32]);
+/// assert_eq!(compressor.compress(small.clone()), small);
+/// ```
+#[cfg(feature = "compression")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Compressor {
+    /// Algoritmo usado.
+    algorithm: CompressionAlgorithm,
+    /// Dados menores que este tamanho não são comprimidos.
+    min_size: usize,
+}
+
+#[cfg(feature = "compression")]
+impl Compressor {
+    /// Cria um compressor com [`DEFAULT_COMPRESSION_THRESHOLD`].
+    ///
+    /// ## Parâmetros
+    /// - `algorithm`: Algoritmo de compressão.
+    ///
+    /// ## Retorno
+    /// - `Self`: O compressor.
+    pub fn new(algorithm: CompressionAlgorithm) -> Self {
+        Self {
+            algorithm,
+            min_size: DEFAULT_COMPRESSION_THRESHOLD,
+        }
+    }
+
+    /// Define o tamanho mínimo dos dados comprimidos.
+    ///
+    /// ## Parâmetros
+    /// - `min_size`: Dados da aplicação menores que este tamanho seguem sem compressão.
+    ///
+    /// ## Retorno
+    /// - `Self`: O compressor com o novo limite.
+    pub fn with_min_size(mut self, min_size: usize) -> Self {
+        self.min_size = min_size;
+        self
+    }
+
+    /// Retorna o algoritmo do compressor.
+    pub fn algorithm(&self) -> CompressionAlgorithm {
+        self.algorithm
+    }
+
+    /// Comprime os dados da aplicação ([`Packet::body`]) de um pacote.
+    ///
+    /// As opções já presentes são preservadas. Comprima antes de cifrar ou assinar:
+    /// pacotes já comprimidos, cifrados, assinados ou autenticados, fragmentos e pacotes
+    /// cujos dados são menores que o limite, ou não diminuiriam, são devolvidos sem
+    /// alteração.
+    ///
+    /// ## Parâmetros
+    /// - `packet`: Pacote a ser comprimido.
+    ///
+    /// ## Retorno
+    /// - `Packet`: O pacote com a flag [`PacketFlags::COMPRESSED`], as opções
+    ///   [`UNCOMPRESSED_LEN_OPTION`] e [`COMPRESSION_ALGORITHM_OPTION`] e os dados
+    ///   comprimidos, ou o pacote original.
+    pub fn compress(&self, packet: Packet) -> Packet {
+        let flags = packet.header.flags;
+        let body = packet.body();
+        let skipped = PacketFlags::COMPRESSED
+            | PacketFlags::FRAGMENT
+            | PacketFlags::ENCRYPTED
+            | PacketFlags::SIGNED
+            | PacketFlags::AUTHENTICATED;
+        if body.len() < self.min_size || !(flags & skipped).is_empty() {
+            return packet;
+        }
+        let Ok(len) = u32::try_from(body.len()) else {
+            return packet;
+        };
+
+        let compressed = self.algorithm.compress(body);
+        let mut options = encode_options(packet.options());
+        options.extend([UNCOMPRESSED_LEN_OPTION, 4]);
+        options.extend(len.to_le_bytes());
+        options.extend([COMPRESSION_ALGORITHM_OPTION, 1, self.algorithm.id()]);
+
+        let Ok(options_len) = u16::try_from(options.len()) else {
+            return packet;
+        };
+        if OPTIONS_LENGTH_SIZE + options.len() + compressed.len() >= packet.payload.len() {
+            return packet;
+        }
+
+        let mut payload = options_len.to_le_bytes().to_vec();
+        payload.extend(options);
+        payload.extend(compressed);
+        let mut compressed = Packet {
+            header: packet.header,
+            payload,
+        };
+        compressed
+            .header
+            .flags
+            .insert(PacketFlags::COMPRESSED | PacketFlags::OPTIONS);
+        compressed.refresh_header();
+        compressed
+    }
+}
+
+#[cfg(feature = "compression")]
+impl Packet {
+    /// Descomprime um pacote comprimido por um [`Compressor`].
+    ///
+    /// O tamanho original vem do remetente e reserva a memória da descompressão; por
+    /// isso ele é recusado acima de [`MAX_PAYLOAD_SIZE`] antes de chegar ao algoritmo.
+    ///
+    /// ## Retorno
+    /// - `Result<Packet, PacketError>`: O pacote original, sem a flag
+    ///   [`PacketFlags::COMPRESSED`] e sem as opções de compressão; uma cópia do próprio
+    ///   pacote se ele não está comprimido; [`PacketError::PayloadTooLarge`] se o tamanho
+    ///   original declarado excede [`MAX_PAYLOAD_SIZE`]; ou [`PacketError::InvalidPayload`]
+    ///   se o algoritmo é desconhecido ou os dados não se descomprimem exatamente no
+    ///   tamanho declarado.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::builder::PacketBuilder;
+    /// use packet::compression::COMPRESSION_ALGORITHM_OPTION;
+    /// use packet::{PacketError, MAX_PAYLOAD_SIZE};
+    ///
+    /// // Um pacote de poucos bytes que declara 4 GiB de dados originais.
+    /// let mut builder = PacketBuilder::new(1, 1, 7);
+    /// builder.mark_compressed(u32::MAX).unwrap();
+    /// builder.add_option(COMPRESSION_ALGORITHM_OPTION, &[1]).unwrap();
+    /// builder.extend([1, 0]);
+    ///
+    /// assert_eq!(
+    ///     builder.seal().decompress(),
+    ///     Err(PacketError::PayloadTooLarge {
+    ///         size: u32::MAX as usize,
+    ///         limit: MAX_PAYLOAD_SIZE,
+    ///     })
+    /// );
+    /// ```
+    pub fn decompress(&self) -> Result<Packet, PacketError> {
+        if !self.header.flags.contains(PacketFlags::COMPRESSED) {
+            return Ok(self.clone());
+        }
+        let invalid = PacketError::InvalidPayload {
+            reason: "payload comprimido malformado",
+        };
+        let len = self.uncompressed_len().ok_or(invalid.clone())?;
+        if len > MAX_PAYLOAD_SIZE {
+            return Err(PacketError::PayloadTooLarge {
+                size: len,
+                limit: MAX_PAYLOAD_SIZE,
+            });
+        }
+        let algorithm = self
+            .options()
+            .find(|&(option_type, _)| option_type == COMPRESSION_ALGORITHM_OPTION)
+            .and_then(|(_, value)| CompressionAlgorithm::from_id(*value.first()?))
+            .ok_or(invalid.clone())?;
+        let body = algorithm
+            .decompress(self.body(), len)
+            .filter(|body| body.len() == len)
+            .ok_or(invalid)?;
+
+        let options = encode_options(self.options().filter(|&(option_type, _)| {
+            option_type != UNCOMPRESSED_LEN_OPTION && option_type != COMPRESSION_ALGORITHM_OPTION
+        }));
+
+        let mut packet = Packet {
+            header: self.header.clone(),
+            payload: Vec::new(),
+        };
+        packet.header.flags.remove(PacketFlags::COMPRESSED);
+        if options.is_empty() {
+            packet.header.flags.remove(PacketFlags::OPTIONS);
+        } else {
+            packet.payload.extend((options.len() as u16).to_le_bytes());
+            packet.payload.extend(options);
+        }
+        packet.payload.extend(body);
+        packet.refresh_header();
+        Ok(packet)
+    }
+}
+
+/// Serializa opções TLV de volta ao formato da área de opções, sem o tamanho inicial.
+#[cfg(feature = "compression")]
+fn encode_options<'a>(options: impl Iterator<Item = (u8, &'a [u8])>) -> Vec<u8> {
+    let mut area = Vec::new();
+    for (option_type, value) in options {
+        area.extend([option_type, value.len() as u8]);
+        area.extend_from_slice(value);
+    }
+    area
+}
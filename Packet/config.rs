@@ -0,0 +1,266 @@
+//! # Módulo `config`
+//!
+//! Parâmetros do protocolo ajustáveis por ambiente, reunidos em [`ProtocolConfig`].
+//!
+//! Um único objeto de configuração alimenta a leitura de pacotes
+//! ([`Packet::from_bytes_with_config`]), a montagem
+//! ([`PacketBuilder::with_config`](crate::builder::PacketBuilder::with_config)) e os
+//! canais de confiabilidade e de ordenação, em vez de cada parâmetro ser repassado
+//! separadamente. Com o recurso `serde`, a configuração pode ser lida de arquivos
+//! TOML ou JSON; campos ausentes recebem os valores de [`ProtocolConfig::default`].
+//!
+//! Os limites da leitura de bytes não confiáveis ficam em [`ParseConfig`]: o maior
+//! payload, a maior quantidade de fragmentos de um pacote e a maior quantidade de
+//! pacotes de um lote. Eles são verificados a partir do cabeçalho, antes de qualquer
+//! alocação proporcional ao que o remetente declara, e os receptores da rede
+//! ([`UdpTransport`](crate::transport::UdpTransport),
+//! [`Reassembler`](crate::fragment::Reassembler),
+//! [`PacketDecoder`](crate::decoder::PacketDecoder)) aplicam [`ParseConfig::default`]
+//! quando nada é configurado.
+//!
+//! ## Exemplos
+//! ```rust
+//! use packet::{Packet, PacketError};
+//! use packet::builder::PacketBuilder;
+//! use packet::checksum::ChecksumAlgorithm;
+//! use packet::config::ProtocolConfig;
+//!
+//! let config = ProtocolConfig {
+//!     max_payload_size: 512,
+//!     checksum_algorithm: ChecksumAlgorithm::CRC32,
+//!     ..ProtocolConfig::default()
+//! };
+//!
+//! let mut builder = PacketBuilder::new(1, 1, 7).with_config(&config);
+//! builder.extend(vec![0; 600]);
+//! let packet = builder.seal();
+//! assert_eq!(packet.checksum_algorithm(), ChecksumAlgorithm::CRC32);
+//!
+//! let error = Packet::from_bytes_with_config(&packet.to_bytes(), &config).unwrap_err();
+//! assert_eq!(error, PacketError::PayloadTooLarge { size: 600, limit: 512 });
+//! ```
+
+use std::time::Duration;
+
+use crate::checksum::ChecksumAlgorithm;
+use crate::fragment::FRAGMENT_HEADER_SIZE;
+use crate::view::PacketRef;
+use crate::{Packet, PacketError, PacketFlags, PacketHeader, DEFAULT_MTU, MAX_PAYLOAD_SIZE};
+
+/// Maior quantidade padrão de fragmentos de um pacote.
+pub const DEFAULT_MAX_FRAGMENTS: usize = 256;
+
+/// Maior quantidade padrão de pacotes em um lote.
+pub const DEFAULT_MAX_BATCH: usize = 256;
+
+/// Parâmetros ajustáveis do protocolo.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ProtocolConfig {
+    /// Maior payload aceito por [`Packet::from_bytes_with_config`]; valores acima de
+    /// [`MAX_PAYLOAD_SIZE`] não têm efeito.
+    pub max_payload_size: usize,
+    /// Algoritmo de checksum dos pacotes montados com
+    /// [`PacketBuilder::with_config`](crate::builder::PacketBuilder::with_config).
+    pub checksum_algorithm: ChecksumAlgorithm,
+    /// Tamanho máximo de um pacote serializado, para fragmentação e snapshots.
+    pub mtu: usize,
+    /// Tempo sem confirmação, em milissegundos, após o qual um pacote confiável é
+    /// retransmitido.
+    pub resend_timeout_ms: u64,
+    /// Maior distância, em sequências, à frente da próxima esperada que um canal
+    /// ordenado retém; pacotes além dela são descartados.
+    pub reliability_window: u32,
+}
+
+impl ProtocolConfig {
+    /// Retorna [`ProtocolConfig::resend_timeout_ms`] como [`Duration`].
+    pub fn resend_timeout(&self) -> Duration {
+        Duration::from_millis(self.resend_timeout_ms)
+    }
+}
+
+impl Default for ProtocolConfig {
+    /// Retorna os valores usados pelo crate quando nada é configurado: os limites de
+    /// [`MAX_PAYLOAD_SIZE`] e [`DEFAULT_MTU`], [`ChecksumAlgorithm::ByteSum`], 100 ms
+    /// de tempo de retransmissão e uma janela de 1024 sequências.
+    fn default() -> Self {
+        Self {
+            max_payload_size: MAX_PAYLOAD_SIZE,
+            checksum_algorithm: ChecksumAlgorithm::default(),
+            mtu: DEFAULT_MTU,
+            resend_timeout_ms: 100,
+            reliability_window: 1024,
+        }
+    }
+}
+
+/// Limites aplicados à leitura de bytes recebidos.
+///
+/// ## Exemplos
+/// ```rust
+/// use packet::{Packet, PacketError};
+/// use packet::config::ParseConfig;
+///
+/// let limits = ParseConfig {
+///     max_fragments: 2,
+///     ..ParseConfig::default()
+/// };
+///
+/// let fragments = Packet::new(1, 9, 7, vec![0; 10]).fragment(8).unwrap();
+/// let error = Packet::from_bytes_with_limits(&fragments[0].to_bytes(), &limits).unwrap_err();
+/// assert_eq!(error, PacketError::TooManyFragments { count: 3, limit: 2 });
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ParseConfig {
+    /// Maior payload aceito em um pacote, em bytes.
+    pub max_payload: usize,
+    /// Maior quantidade de fragmentos declarada por um fragmento.
+    pub max_fragments: usize,
+    /// Maior quantidade de pacotes em um lote.
+    pub max_batch: usize,
+}
+
+impl Default for ParseConfig {
+    /// Retorna [`MAX_PAYLOAD_SIZE`], [`DEFAULT_MAX_FRAGMENTS`] e [`DEFAULT_MAX_BATCH`].
+    fn default() -> Self {
+        Self {
+            max_payload: MAX_PAYLOAD_SIZE,
+            max_fragments: DEFAULT_MAX_FRAGMENTS,
+            max_batch: DEFAULT_MAX_BATCH,
+        }
+    }
+}
+
+impl From<&ProtocolConfig> for ParseConfig {
+    /// Usa [`ProtocolConfig::max_payload_size`] e os demais limites padrão.
+    fn from(config: &ProtocolConfig) -> Self {
+        Self {
+            max_payload: config.max_payload_size,
+            ..Self::default()
+        }
+    }
+}
+
+impl ParseConfig {
+    /// Verifica o tamanho de payload declarado em um cabeçalho, antes de ler o payload.
+    ///
+    /// ## Parâmetros
+    /// - `header`: Cabeçalho recebido.
+    ///
+    /// ## Retorno
+    /// - `Result<(), PacketError>`: [`PacketError::PayloadTooLarge`] se `payload_size`
+    ///   excede [`ParseConfig::max_payload`].
+    pub fn check_header(&self, header: &PacketHeader) -> Result<(), PacketError> {
+        let size = header.payload_size as usize;
+        if size > self.max_payload {
+            return Err(PacketError::PayloadTooLarge {
+                size,
+                limit: self.max_payload,
+            });
+        }
+        Ok(())
+    }
+
+    /// Verifica um pacote lido: o tamanho do payload e, se ele é um fragmento, a
+    /// quantidade de fragmentos declarada.
+    ///
+    /// ## Parâmetros
+    /// - `header`: Cabeçalho do pacote.
+    /// - `payload`: Payload do pacote.
+    ///
+    /// ## Retorno
+    /// - `Result<(), PacketError>`: O erro de [`ParseConfig::check_header`] ou
+    ///   [`PacketError::TooManyFragments`] se o fragmento excede
+    ///   [`ParseConfig::max_fragments`].
+    pub fn check(&self, header: &PacketHeader, payload: &[u8]) -> Result<(), PacketError> {
+        self.check_header(header)?;
+        if !header.flags.contains(PacketFlags::FRAGMENT) {
+            return Ok(());
+        }
+        if let Some(fragment) = payload.get(..FRAGMENT_HEADER_SIZE) {
+            let count = u16::from_le_bytes([fragment[2], fragment[3]]) as usize;
+            if count > self.max_fragments {
+                return Err(PacketError::TooManyFragments {
+                    count,
+                    limit: self.max_fragments,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Verifica a quantidade de pacotes já lidos de um lote.
+    pub(crate) fn check_batch(&self, count: usize) -> Result<(), PacketError> {
+        if count > self.max_batch {
+            return Err(PacketError::BatchTooLarge {
+                limit: self.max_batch,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl<'a> PacketRef<'a> {
+    /// Interpreta o pacote como [`PacketRef::from_bytes`], aplicando os limites.
+    ///
+    /// ## Parâmetros
+    /// - `bytes`: Fatia de bytes representando o pacote completo.
+    /// - `limits`: Limites da leitura.
+    ///
+    /// ## Retorno
+    /// - `Result<Self, PacketError>`: O pacote emprestado, o erro de
+    ///   [`PacketRef::from_bytes`] ou o de [`ParseConfig::check`].
+    pub fn from_bytes_with_limits(
+        bytes: &'a [u8],
+        limits: &ParseConfig,
+    ) -> Result<Self, PacketError> {
+        limits.check_header(&PacketHeader::from_bytes(bytes)?)?;
+        let packet = Self::from_bytes(bytes)?;
+        limits.check(&packet.header, packet.payload)?;
+        Ok(packet)
+    }
+}
+
+impl Packet {
+    /// Reconstrói o pacote como [`Packet::from_bytes`], aplicando os limites.
+    ///
+    /// O tamanho declarado é verificado antes da cópia do payload.
+    ///
+    /// ## Parâmetros
+    /// - `bytes`: Fatia de bytes representando o pacote completo.
+    /// - `limits`: Limites da leitura.
+    ///
+    /// ## Retorno
+    /// - `Result<Self, PacketError>`: O pacote, o erro de [`Packet::from_bytes`] ou o
+    ///   de [`ParseConfig::check`].
+    pub fn from_bytes_with_limits(bytes: &[u8], limits: &ParseConfig) -> Result<Self, PacketError> {
+        limits.check_header(&PacketHeader::from_bytes(bytes)?)?;
+        let packet = Self::from_bytes(bytes)?;
+        limits.check(&packet.header, &packet.payload)?;
+        Ok(packet)
+    }
+
+    /// Reconstrói o pacote como [`Packet::from_bytes`], aplicando os limites da
+    /// configuração.
+    ///
+    /// ## Parâmetros
+    /// - `bytes`: Fatia de bytes representando o pacote completo.
+    /// - `config`: Configuração do protocolo.
+    ///
+    /// ## Retorno
+    /// - `Result<Self, PacketError>`: O pacote, o erro de [`Packet::from_bytes`] ou
+    ///   [`PacketError::PayloadTooLarge`] se o payload excede
+    ///   [`ProtocolConfig::max_payload_size`].
+    pub fn from_bytes_with_config(
+        bytes: &[u8],
+        config: &ProtocolConfig,
+    ) -> Result<Self, PacketError> {
+        let packet = Self::from_bytes(bytes)?;
+        ParseConfig::from(config).check_header(&packet.header)?;
+        Ok(packet)
+    }
+}
@@ -0,0 +1,340 @@
+//! # Módulo `congestion`
+//!
+//! Controle de congestionamento e limitação da taxa de envio.
+//!
+//! [`RateController`] escolhe entre duas taxas de envio, no modelo "bom/ruim": a
+//! conexão começa no modo ruim e passa ao modo bom depois de um período de
+//! penalidade com RTT e perda abaixo dos limites; qualquer piora a devolve ao modo
+//! ruim. Uma volta ao modo ruim logo depois de sair dele dobra a penalidade, e cada
+//! período longo no modo bom a reduz à metade, de forma que conexões instáveis não
+//! alternam de modo a todo momento.
+//!
+//! [`BandwidthBudget`] aplica a taxa escolhida aos pacotes de uma conexão, como um
+//! balde de fichas em bytes: o que excede o orçamento espera em uma fila limitada, e
+//! quando ela enche os pacotes de menor `priority` são descartados primeiro.
+//!
+//! ## Exemplos
+//! ```rust
+//! use std::time::{Duration, Instant};
+//! use packet::Packet;
+//! use packet::congestion::{BandwidthBudget, RateController};
+//!
+//! let mut controller = RateController::new(64_000, 16_000);
+//! let mut budget = BandwidthBudget::new(controller.send_rate(), 1_000, 32);
+//! let now = Instant::now();
+//!
+//! // A cada atualização das medições, a taxa do orçamento acompanha o controlador.
+//! controller.update(Duration::from_millis(40), 0.0, now);
+//! budget.set_rate(controller.send_rate());
+//!
+//! budget.push(Packet::new(1, 1, 7, vec![0; 1_000]));
+//! budget.push(Packet::new(1, 2, 7, vec![0; 1_000]));
+//! assert_eq!(budget.poll(now).len(), 1);
+//! assert_eq!(budget.queued(), 1);
+//! ```
+
+use std::time::{Duration, Instant};
+
+use crate::queue::{BoundedQueue, DropPolicy};
+use crate::Packet;
+
+/// RTT acima do qual [`RateController`] considera a conexão ruim, por padrão.
+pub const DEFAULT_RTT_THRESHOLD: Duration = Duration::from_millis(250);
+
+/// Fração de pacotes perdidos acima da qual [`RateController`] considera a conexão
+/// ruim, por padrão.
+pub const DEFAULT_LOSS_THRESHOLD: f64 = 0.1;
+
+/// Penalidade inicial antes de passar ao modo bom.
+const INITIAL_PENALTY: Duration = Duration::from_secs(4);
+
+/// Menor penalidade possível.
+const MIN_PENALTY: Duration = Duration::from_secs(1);
+
+/// Maior penalidade possível.
+const MAX_PENALTY: Duration = Duration::from_secs(60);
+
+/// Tempo no modo bom que reduz a penalidade à metade; sair do modo bom antes dele
+/// dobra a penalidade.
+const PENALTY_PERIOD: Duration = Duration::from_secs(10);
+
+/// Modo de envio escolhido por [`RateController`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CongestionMode {
+    /// Conexão saudável, com a taxa de envio maior.
+    Good,
+    /// Conexão congestionada ou ainda não avaliada, com a taxa de envio menor.
+    Bad,
+}
+
+/// Controlador de taxa de envio com modos bom e ruim, guiado por RTT e perda.
+///
+/// ## Exemplos
+/// ```rust
+/// use std::time::{Duration, Instant};
+/// use packet::congestion::{CongestionMode, RateController};
+///
+/// let mut controller = RateController::new(64_000, 16_000);
+/// let start = Instant::now();
+/// let at = |secs| start + Duration::from_secs(secs);
+/// let (good_rtt, bad_rtt) = (Duration::from_millis(50), Duration::from_millis(400));
+///
+/// // Começa no modo ruim e passa ao bom após 4 s de boas medições.
+/// assert_eq!(controller.update(good_rtt, 0.0, at(0)), CongestionMode::Bad);
+/// assert_eq!(controller.update(good_rtt, 0.0, at(4)), CongestionMode::Good);
+/// assert_eq!(controller.send_rate(), 64_000);
+///
+/// // Piorar logo em seguida dobra a penalidade.
+/// assert_eq!(controller.update(bad_rtt, 0.0, at(5)), CongestionMode::Bad);
+/// assert_eq!(controller.penalty(), Duration::from_secs(8));
+/// assert_eq!(controller.send_rate(), 16_000);
+///
+/// // Perda acima do limite também conta como conexão ruim.
+/// controller.update(good_rtt, 0.0, at(6));
+/// assert_eq!(controller.update(good_rtt, 0.5, at(20)), CongestionMode::Bad);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RateController {
+    /// Taxa de envio do modo bom, em bytes por segundo.
+    good_rate: u32,
+    /// Taxa de envio do modo ruim, em bytes por segundo.
+    bad_rate: u32,
+    /// RTT acima do qual a conexão é ruim.
+    rtt_threshold: Duration,
+    /// Perda acima da qual a conexão é ruim.
+    loss_threshold: f64,
+    /// Modo atual.
+    mode: CongestionMode,
+    /// Tempo com boas medições exigido para passar ao modo bom.
+    penalty: Duration,
+    /// Início do modo atual, após a primeira atualização.
+    mode_since: Option<Instant>,
+    /// Início da sequência atual de boas medições, ou da última redução da penalidade
+    /// no modo bom.
+    good_since: Option<Instant>,
+}
+
+impl RateController {
+    /// Cria um controlador no modo ruim, com [`DEFAULT_RTT_THRESHOLD`] e
+    /// [`DEFAULT_LOSS_THRESHOLD`].
+    ///
+    /// ## Parâmetros
+    /// - `good_rate`: Taxa de envio do modo bom, em bytes por segundo.
+    /// - `bad_rate`: Taxa de envio do modo ruim, em bytes por segundo.
+    ///
+    /// ## Retorno
+    /// - `Self`: O controlador, com penalidade inicial de 4 s.
+    pub fn new(good_rate: u32, bad_rate: u32) -> Self {
+        Self {
+            good_rate,
+            bad_rate,
+            rtt_threshold: DEFAULT_RTT_THRESHOLD,
+            loss_threshold: DEFAULT_LOSS_THRESHOLD,
+            mode: CongestionMode::Bad,
+            penalty: INITIAL_PENALTY,
+            mode_since: None,
+            good_since: None,
+        }
+    }
+
+    /// Define os limites acima dos quais a conexão é considerada ruim.
+    ///
+    /// ## Parâmetros
+    /// - `rtt`: RTT máximo de uma conexão boa.
+    /// - `loss`: Fração máxima de pacotes perdidos, de `0.0` a `1.0`.
+    ///
+    /// ## Retorno
+    /// - `Self`: O controlador com os novos limites.
+    pub fn with_thresholds(mut self, rtt: Duration, loss: f64) -> Self {
+        self.rtt_threshold = rtt;
+        self.loss_threshold = loss;
+        self
+    }
+
+    /// Atualiza o modo com as medições mais recentes da conexão.
+    ///
+    /// ## Parâmetros
+    /// - `rtt`: RTT suavizado, como o de [`RttEstimator`](crate::rtt::RttEstimator).
+    /// - `loss`: Fração dos pacotes enviados recentemente que foram perdidos.
+    /// - `now`: Instante das medições.
+    ///
+    /// ## Retorno
+    /// - `CongestionMode`: O modo após a atualização.
+    pub fn update(&mut self, rtt: Duration, loss: f64, now: Instant) -> CongestionMode {
+        let bad = rtt > self.rtt_threshold || loss > self.loss_threshold;
+        let mode_since = *self.mode_since.get_or_insert(now);
+
+        match self.mode {
+            CongestionMode::Good if bad => {
+                if now.saturating_duration_since(mode_since) < PENALTY_PERIOD {
+                    self.penalty = (self.penalty * 2).min(MAX_PENALTY);
+                }
+                self.enter(CongestionMode::Bad, now);
+            }
+            CongestionMode::Good => {
+                let good_since = *self.good_since.get_or_insert(now);
+                if now.saturating_duration_since(good_since) >= PENALTY_PERIOD {
+                    self.penalty = (self.penalty / 2).max(MIN_PENALTY);
+                    self.good_since = Some(now);
+                }
+            }
+            CongestionMode::Bad if bad => self.good_since = None,
+            CongestionMode::Bad => {
+                let good_since = *self.good_since.get_or_insert(now);
+                if now.saturating_duration_since(good_since) >= self.penalty {
+                    self.enter(CongestionMode::Good, now);
+                }
+            }
+        }
+        self.mode
+    }
+
+    /// Retorna o modo atual.
+    pub fn mode(&self) -> CongestionMode {
+        self.mode
+    }
+
+    /// Retorna a taxa de envio do modo atual, em bytes por segundo.
+    pub fn send_rate(&self) -> u32 {
+        match self.mode {
+            CongestionMode::Good => self.good_rate,
+            CongestionMode::Bad => self.bad_rate,
+        }
+    }
+
+    /// Retorna o tempo com boas medições exigido para passar ao modo bom.
+    pub fn penalty(&self) -> Duration {
+        self.penalty
+    }
+
+    /// Passa ao modo informado, reiniciando a contagem das boas medições.
+    fn enter(&mut self, mode: CongestionMode, now: Instant) {
+        self.mode = mode;
+        self.mode_since = Some(now);
+        self.good_since = (mode == CongestionMode::Good).then_some(now);
+    }
+}
+
+/// Orçamento de banda de uma conexão, em bytes por segundo.
+///
+/// Um pacote é liberado sempre que o orçamento não está negativo, de modo que pacotes
+/// maiores que a rajada não ficam presos; o excesso é descontado das liberações
+/// seguintes.
+///
+/// ## Exemplos
+/// ```rust
+/// use std::time::{Duration, Instant};
+/// use packet::Packet;
+/// use packet::congestion::BandwidthBudget;
+///
+/// let mut budget = BandwidthBudget::new(10_000, 1_000, 2);
+/// let now = Instant::now();
+///
+/// budget.push(Packet::new(1, 1, 7, vec![0; 1_000]).with_priority(5));
+/// budget.push(Packet::new(1, 2, 7, vec![0; 1_000]).with_priority(1));
+/// // A fila está cheia: o pacote de menor prioridade é descartado.
+/// let dropped = budget.push(Packet::new(1, 3, 7, vec![0; 1_000]).with_priority(3));
+/// assert_eq!(dropped.unwrap().header.sequence, 2);
+///
+/// assert_eq!(budget.poll(now)[0].header.sequence, 1);
+/// assert!(budget.poll(now).is_empty());
+/// // 10 000 bytes/s repõem o orçamento de um pacote em pouco mais de 100 ms.
+/// let later = budget.poll(now + Duration::from_millis(120));
+/// assert_eq!(later[0].header.sequence, 3);
+/// assert_eq!(budget.dropped(), 1);
+/// ```
+#[derive(Debug)]
+pub struct BandwidthBudget {
+    /// Taxa de reposição, em bytes por segundo.
+    rate: u32,
+    /// Maior orçamento acumulado, em bytes.
+    burst: usize,
+    /// Orçamento disponível, em bytes; negativo após liberar um pacote maior que ele.
+    available: f64,
+    /// Instante da última reposição.
+    last_refill: Option<Instant>,
+    /// Pacotes aguardando orçamento.
+    queue: BoundedQueue,
+}
+
+impl BandwidthBudget {
+    /// Cria um orçamento cheio, sem pacotes na fila.
+    ///
+    /// ## Parâmetros
+    /// - `rate`: Taxa de envio, em bytes por segundo.
+    /// - `burst`: Maior orçamento acumulado, em bytes, liberável de uma só vez.
+    /// - `queue_capacity`: Quantidade máxima de pacotes aguardando orçamento.
+    ///
+    /// ## Retorno
+    /// - `Self`: O orçamento.
+    pub fn new(rate: u32, burst: usize, queue_capacity: usize) -> Self {
+        Self {
+            rate,
+            burst,
+            available: burst as f64,
+            last_refill: None,
+            queue: BoundedQueue::new(queue_capacity, DropPolicy::DropLowestPriority),
+        }
+    }
+
+    /// Altera a taxa de envio, por exemplo para a de [`RateController::send_rate`].
+    ///
+    /// ## Parâmetros
+    /// - `rate`: Nova taxa, em bytes por segundo.
+    pub fn set_rate(&mut self, rate: u32) {
+        self.rate = rate;
+    }
+
+    /// Retorna a taxa de envio, em bytes por segundo.
+    pub fn rate(&self) -> u32 {
+        self.rate
+    }
+
+    /// Enfileira um pacote para envio.
+    ///
+    /// ## Parâmetros
+    /// - `packet`: Pacote a ser enviado.
+    ///
+    /// ## Retorno
+    /// - `Option<Packet>`: O pacote descartado se a fila estava cheia, como em
+    ///   [`DropPolicy::DropLowestPriority`].
+    pub fn push(&mut self, packet: Packet) -> Option<Packet> {
+        self.queue.push(packet)
+    }
+
+    /// Libera, na ordem de chegada, os pacotes que cabem no orçamento.
+    ///
+    /// ## Parâmetros
+    /// - `now`: Instante atual.
+    ///
+    /// ## Retorno
+    /// - `Vec<Packet>`: Os pacotes a serem enviados agora.
+    pub fn poll(&mut self, now: Instant) -> Vec<Packet> {
+        if let Some(last) = self.last_refill {
+            let elapsed = now.saturating_duration_since(last).as_secs_f64();
+            self.available =
+                (self.available + elapsed * f64::from(self.rate)).min(self.burst as f64);
+        }
+        self.last_refill = Some(now);
+
+        let mut released = Vec::new();
+        while self.available >= 0.0 {
+            let Some(packet) = self.queue.pop() else {
+                break;
+            };
+            self.available -= packet.serialized_len() as f64;
+            released.push(packet);
+        }
+        released
+    }
+
+    /// Retorna a quantidade de pacotes aguardando orçamento.
+    pub fn queued(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Retorna o total de pacotes descartados por falta de espaço na fila.
+    pub fn dropped(&self) -> u64 {
+        self.queue.dropped()
+    }
+}
@@ -0,0 +1,3220 @@
+//! # Módulo `connection`
+//!
+//! Conexões com aperto de mão por desafio e resposta, sobre [`UdpTransport`] ou
+//! qualquer outro [`Transport`].
+//!
+//! O cliente pede a conexão com um valor aleatório próprio (`salt`); o servidor
+//! responde com um desafio derivado do endereço de origem, do `salt` e de um segredo
+//! gerado na criação do servidor, sem guardar estado. Só quem recebe o desafio no
+//! endereço declarado consegue devolvê-lo, o que impede conexões com endereço de
+//! origem forjado. Ao receber a resposta correta, o servidor atribui um identificador
+//! de sessão, usado como `player_id` em todos os pacotes seguintes dos dois lados.
+//!
+//! As mensagens do aperto de mão são pacotes [`MessageType::Connect`], retransmitidos
+//! pelo cliente até a conclusão. Depois dela, cada lado envia um
+//! [`MessageType::Ping`] a cada quarto do tempo limite, que o outro responde com um
+//! [`MessageType::Pong`] de mesma sequência; as respostas alimentam um
+//! [`RttEstimator`] e mantêm viva a conexão, encerrada por [`Liveness`] quando o
+//! outro lado para de responder. Ambos os lados são conduzidos por `poll`, que não
+//! bloqueia e retorna os eventos ocorridos; datagramas malformados ou de endereços
+//! desconhecidos são descartados.
+//!
+//! Para laços de jogo que consomem a rede uma vez por tick, `poll_events` faz o mesmo
+//! que `poll` e entrega os eventos dos dois lados no formato único de [`NetEvent`],
+//! incluindo avisos sobre os datagramas descartados, como falhas de checksum.
+//!
+//! Os pacotes da aplicação recebidos passam por uma
+//! [`ReplayWindow`](crate::sequence::ReplayWindow) de cada conexão: cópias repetidas e
+//! pacotes mais antigos que a janela são descartados, então a aplicação deve numerar
+//! seus pacotes com sequências crescentes, que podem dar a volta.
+//!
+//! O pedido e a resposta do cliente levam também a [`ProtocolVersion`] da aplicação,
+//! definida com [`Client::with_protocol_version`] e
+//! [`Server::with_protocol_version`]. Versões com `major` diferente são
+//! incompatíveis: o servidor recusa o pedido informando a sua, e os dois lados
+//! recebem um evento `Rejected` com [`PacketError::IncompatibleVersion`].
+//!
+//! Após a conexão, cada lado descobre o MTU do caminho com pings preenchidos de
+//! tamanho crescente, por [`MtuDiscovery`](crate::mtu::MtuDiscovery), e os pacotes da
+//! aplicação maiores que o MTU descoberto são divididos com
+//! [`Packet::fragment`](crate::Packet::fragment) e remontados ao chegar.
+//!
+//! Os pongs levam o relógio de quem responde, e cada lado estima o do outro com um
+//! [`TimeSync`]; o cliente expõe o relógio do servidor em [`Client::server_time_now`].
+//!
+//! A aplicação pode inspecionar, alterar ou descartar os pacotes que entram e saem dos
+//! dois lados com os ganchos de [`hook`](crate::hook), registrados com
+//! [`Server::add_inbound_hook`] e [`Server::add_outbound_hook`] e os equivalentes do
+//! [`Client`], e chamados na ordem de registro.
+//!
+//! Com o recurso `crypto`, [`Server::with_encryption`] e [`Client::connect_encrypted`]
+//! acrescentam ao aperto de mão uma troca de chaves
+//! [`KeyExchange`](crate::encrypt::KeyExchange): o pedido e a resposta do cliente
+//! levam sua chave pública, e a aceitação leva a do servidor. A partir daí os pacotes
+//! da aplicação são cifrados com [`Packet::seal`](crate::Packet::seal) nos dois
+//! sentidos e levam a flag [`PacketFlags::ENCRYPTED`](crate::PacketFlags::ENCRYPTED);
+//! pacotes sem a flag ou que não passam na verificação são descartados, assim como
+//! pacotes cifrados em conexões sem cifragem. Um servidor cifrado não aceita clientes
+//! sem cifragem, e vice-versa. Como o nonce vem da sequência, a aplicação não deve
+//! repetir sequências em uma sessão. Pings, pongs e desconexões, que têm sequências
+//! próprias, seguem em claro, mas com a etiqueta HMAC-SHA256 das chaves de
+//! autenticação da mesma troca.
+//!
+//! Quando só a adulteração precisa ser impedida, [`Server::with_security`] e
+//! [`Client::with_security`] com [`SecurityLevel::Signed`] fazem a mesma troca de
+//! chaves, mas os pacotes da aplicação seguem em claro, com a etiqueta HMAC-SHA256 de
+//! [`Packet::authenticate`](crate::Packet::authenticate) e a flag
+//! [`PacketFlags::AUTHENTICATED`](crate::PacketFlags::AUTHENTICATED), verificada antes
+//! do descarte das repetições e da descompressão. Os dois lados precisam escolher o
+//! mesmo [`SecurityLevel`].
+//!
+//! Nos dois níveis, todo pacote recebido de uma sessão é verificado antes de
+//! qualquer efeito: um ping, pong ou desconexão forjado não mantém a sessão viva, não
+//! altera o RTT, o relógio estimado ou o MTU e não encerra a conexão. Fragmentos são
+//! verificados depois de remontados, já que a etiqueta cobre o pacote inteiro.
+//!
+//! Também com o recurso `crypto`, [`Server::with_connect_tokens`] passa a exigir um
+//! [`ConnectToken`](crate::auth::ConnectToken) assinado pelo serviço de autenticação
+//! da aplicação, que o cliente envia no pedido e na resposta com
+//! [`Client::with_connect_token`]. O servidor verifica assinatura, expiração e
+//! endereço antes de responder ao pedido e antes de criar a sessão, e associa a ela o
+//! `player_id` do token.
+//!
+//! Com o recurso `compression`, [`Client::with_compression`] anuncia no aperto de mão
+//! os algoritmos de [`CompressionAlgorithm`](crate::compression::CompressionAlgorithm)
+//! disponíveis, e um servidor criado com [`Server::with_compression`] escolhe o seu se
+//! ele foi anunciado. Os pacotes da aplicação são então comprimidos antes de serem
+//! cifrados, acima do tamanho mínimo de cada lado; sem acordo, seguem sem compressão.
+//!
+//! ## Exemplos
+//! ```rust
+//! use std::time::{Duration, Instant};
+//! use packet::Packet;
+//! use packet::connection::{Client, ConnectionState, Server, ServerEvent};
+//!
+//! let mut server = Server::listen("127.0.0.1:0").unwrap();
+//! let mut client = Client::connect(server.local_addr().unwrap(), Instant::now()).unwrap();
+//!
+//! let mut session = None;
+//! for _ in 0..200 {
+//!     let now = Instant::now();
+//!     for event in server.poll(now).unwrap() {
+//!         if let ServerEvent::Connected { session_id, .. } = event {
+//!             session = Some(session_id);
+//!         }
+//!     }
+//!     client.poll(now).unwrap();
+//!     if client.state() == ConnectionState::Connected {
+//!         break;
+//!     }
+//!     std::thread::sleep(Duration::from_millis(5));
+//! }
+//! assert_eq!(client.state(), ConnectionState::Connected);
+//! let session_id = client.session_id().unwrap();
+//!
+//! client.send(Packet::new(1, 1, session_id, vec![1, 2, 3])).unwrap();
+//! let mut received = Vec::new();
+//! while received.is_empty() {
+//!     received = server.poll(Instant::now()).unwrap();
+//! }
+//! assert!(session.is_some());
+//! assert!(matches!(
+//!     &received[0],
+//!     ServerEvent::Packet { session_id: id, packet } if Some(*id) == session && packet.payload == [1, 2, 3]
+//! ));
+//! ```
+
+use std::collections::HashMap;
+use std::hash::{BuildHasher, RandomState};
+use std::io;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs};
+#[cfg(feature = "crypto")]
+use std::time::SystemTime;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "crypto")]
+use ed25519_dalek::VerifyingKey;
+
+#[cfg(feature = "crypto")]
+use crate::auth::ConnectToken;
+use crate::auth::CONNECT_TOKEN_SIZE;
+use crate::clock::{Clock, SystemClock};
+#[cfg(feature = "compression")]
+use crate::compression::{CompressionAlgorithm, Compressor};
+use crate::disconnect::DisconnectReason;
+#[cfg(feature = "crypto")]
+use crate::encrypt::{KeyExchange, SessionKeys};
+use crate::fragment::Reassembler;
+use crate::hook::{HookDecision, HookPipeline};
+use crate::liveness::Liveness;
+use crate::lobby::{Lobby, LobbyEvent};
+use crate::message::MessageType;
+use crate::mtu::{MtuDiscovery, MAX_PROBE_MTU};
+use crate::payload::PacketPayload;
+use crate::query::{QueryResponder, ServerStatus};
+use crate::ratelimit::{FloodGuard, Verdict};
+use crate::rtt::RttEstimator;
+use crate::sequence::{ReplayStatus, ReplayWindow, SequenceBuffer};
+use crate::stats::{NetworkStats, StatsRecorder, StatsReporter};
+use crate::timesync::TimeSync;
+use crate::transport::{Transport, UdpTransport};
+use crate::view::PacketView;
+use crate::{Packet, PacketError, PacketFlags, PacketHeader, DEFAULT_MTU};
+
+pub use crate::message::ProtocolVersion;
+
+/// Intervalo entre retransmissões das mensagens do aperto de mão.
+pub const HANDSHAKE_RESEND_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Tempo máximo para concluir o aperto de mão.
+pub const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Tempo sem pacotes após o qual uma conexão estabelecida expira.
+pub const DEFAULT_CONNECTION_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Quantidade de pings sem resposta lembrados para o cálculo do RTT.
+const PING_HISTORY: usize = 64;
+
+/// Quantidade de cópias do pacote de desconexão enviadas pelo cliente, uma por `poll`.
+pub const DISCONNECT_REDUNDANCY: u32 = 3;
+
+/// Estado da conexão de um [`Client`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConnectionState {
+    /// Aperto de mão em andamento.
+    Connecting,
+    /// Conexão estabelecida.
+    Connected,
+    /// Enviando as cópias do pacote de desconexão.
+    Disconnecting,
+    /// Conexão encerrada por um dos lados.
+    Disconnected,
+    /// O aperto de mão não foi concluído a tempo ou o servidor parou de responder.
+    TimedOut,
+}
+
+/// Proteção dos pacotes da aplicação de uma conexão, escolhida com
+/// [`Server::with_security`] e [`Client::with_security`].
+///
+/// As duas proteções partem da mesma troca de chaves no aperto de mão; os dois lados
+/// precisam escolher o mesmo nível.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SecurityLevel {
+    /// Pacotes em claro e sem verificação.
+    #[default]
+    None,
+    /// Pacotes em claro, autenticados com [`Packet::authenticate`](crate::Packet::authenticate)
+    /// ao custo de 16 bytes.
+    Signed,
+    /// Pacotes cifrados com [`Packet::seal`](crate::Packet::seal), também ao custo de
+    /// 16 bytes.
+    Encrypted,
+}
+
+/// Evento produzido por [`Server::poll`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ServerEvent {
+    /// Um cliente concluiu o aperto de mão.
+    Connected {
+        /// Sessão atribuída ao cliente.
+        session_id: u64,
+        /// Endereço do cliente.
+        addr: SocketAddr,
+    },
+    /// Um cliente encerrou a conexão.
+    Disconnected {
+        /// Sessão encerrada.
+        session_id: u64,
+        /// Motivo informado, se pôde ser decodificado.
+        reason: Option<DisconnectReason>,
+    },
+    /// Um cliente parou de enviar pacotes e sua sessão expirou.
+    TimedOut {
+        /// Sessão expirada.
+        session_id: u64,
+    },
+    /// Um pedido de conexão foi recusado.
+    Rejected {
+        /// Endereço do cliente.
+        addr: SocketAddr,
+        /// Motivo da recusa, como [`PacketError::IncompatibleVersion`].
+        error: PacketError,
+    },
+    /// Pacote da aplicação recebido de uma sessão.
+    Packet {
+        /// Sessão de origem.
+        session_id: u64,
+        /// Pacote recebido.
+        packet: Packet,
+    },
+}
+
+/// Evento produzido por [`Client::poll`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClientEvent {
+    /// O aperto de mão foi concluído.
+    Connected {
+        /// Sessão atribuída pelo servidor.
+        session_id: u64,
+    },
+    /// O servidor encerrou a conexão.
+    Disconnected {
+        /// Motivo informado, se pôde ser decodificado.
+        reason: Option<DisconnectReason>,
+    },
+    /// O aperto de mão não foi concluído a tempo ou o servidor parou de responder.
+    TimedOut,
+    /// O servidor recusou a conexão; o estado passa a
+    /// [`ConnectionState::Disconnected`].
+    Rejected {
+        /// Motivo da recusa, como [`PacketError::IncompatibleVersion`].
+        error: PacketError,
+    },
+    /// Pacote da aplicação recebido do servidor.
+    Packet(Packet),
+}
+
+/// Evento de [`Server::poll_events`] e [`Client::poll_events`], no mesmo formato nos
+/// dois lados.
+///
+/// No cliente, a sessão é a atribuída pelo servidor e o endereço é o do servidor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NetEvent {
+    /// Uma conexão foi estabelecida.
+    ClientConnected {
+        /// Sessão da conexão.
+        session_id: u64,
+        /// Endereço do outro lado.
+        addr: SocketAddr,
+    },
+    /// Uma conexão estabelecida foi encerrada.
+    ClientDisconnected {
+        /// Sessão encerrada.
+        session_id: u64,
+        /// Motivo informado pelo outro lado, ou [`DisconnectReason::Timeout`] se ele
+        /// parou de responder; `None` se o motivo recebido não pôde ser decodificado.
+        reason: Option<DisconnectReason>,
+    },
+    /// Um aperto de mão falhou: foi recusado, ou, no cliente, não foi concluído a
+    /// tempo, com [`PacketError::Io`] de [`io::ErrorKind::TimedOut`].
+    ConnectionFailed {
+        /// Endereço do outro lado.
+        addr: SocketAddr,
+        /// Motivo da falha.
+        error: PacketError,
+    },
+    /// Pacote da aplicação recebido.
+    MessageReceived {
+        /// Sessão de origem.
+        session_id: u64,
+        /// Canal lógico do pacote, o `channel_id` do cabeçalho.
+        channel: u8,
+        /// Pacote recebido.
+        packet: Packet,
+    },
+    /// Um datagrama foi descartado porque um checksum não confere.
+    ChecksumFailure {
+        /// Endereço de origem do datagrama.
+        addr: SocketAddr,
+        /// [`PacketError::ChecksumMismatch`] ou [`PacketError::HeaderChecksumMismatch`].
+        error: PacketError,
+    },
+    /// Um datagrama foi descartado porque não é um pacote válido.
+    MalformedPacket {
+        /// Endereço de origem do datagrama.
+        addr: SocketAddr,
+        /// Erro de interpretação.
+        error: PacketError,
+    },
+    /// Mudança em uma sala do [`Lobby`] do servidor; só ocorre no servidor.
+    Lobby(LobbyEvent),
+}
+
+impl NetEvent {
+    /// Converte um datagrama descartado no aviso correspondente.
+    fn dropped(addr: SocketAddr, error: PacketError) -> Self {
+        match error {
+            PacketError::ChecksumMismatch { .. } | PacketError::HeaderChecksumMismatch { .. } => {
+                Self::ChecksumFailure { addr, error }
+            }
+            error => Self::MalformedPacket { addr, error },
+        }
+    }
+
+    /// Recebe um pacote da aplicação, com o canal do cabeçalho.
+    fn message(session_id: u64, packet: Packet) -> Self {
+        Self::MessageReceived {
+            session_id,
+            channel: packet.header.channel_id,
+            packet,
+        }
+    }
+}
+
+impl From<ServerEvent> for NetEvent {
+    fn from(event: ServerEvent) -> Self {
+        match event {
+            ServerEvent::Connected { session_id, addr } => {
+                Self::ClientConnected { session_id, addr }
+            }
+            ServerEvent::Disconnected { session_id, reason } => {
+                Self::ClientDisconnected { session_id, reason }
+            }
+            ServerEvent::TimedOut { session_id } => Self::ClientDisconnected {
+                session_id,
+                reason: Some(DisconnectReason::Timeout),
+            },
+            ServerEvent::Rejected { addr, error } => Self::ConnectionFailed { addr, error },
+            ServerEvent::Packet { session_id, packet } => Self::message(session_id, packet),
+        }
+    }
+}
+
+/// Parâmetros opcionais da conexão anunciados no aperto de mão.
+///
+/// Serializados após os campos da etapa: o token de conexão e a chave pública, se
+/// presentes, um byte `0` nas conexões assinadas e o byte de compressão, se diferente
+/// de zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+struct Offer {
+    /// Token de conexão emitido pelo serviço de autenticação, do cliente.
+    connect_token: Option<[u8; CONNECT_TOKEN_SIZE]>,
+    /// Chave pública da troca de chaves, nas conexões assinadas ou cifradas.
+    public_key: Option<[u8; 32]>,
+    /// Indica se a troca de chaves é para autenticar os pacotes em vez de cifrá-los.
+    signed: bool,
+    /// Do cliente, os identificadores dos algoritmos de compressão aceitos; do
+    /// servidor, o do algoritmo escolhido; `0` sem compressão.
+    compression: u8,
+}
+
+impl Offer {
+    /// Acrescenta os parâmetros ao fim de uma mensagem.
+    fn encode_into(&self, payload: &mut Vec<u8>) {
+        if let Some(connect_token) = self.connect_token {
+            payload.extend_from_slice(&connect_token);
+        }
+        if let Some(public_key) = self.public_key {
+            payload.extend_from_slice(&public_key);
+            if self.signed {
+                payload.push(0);
+            }
+        }
+        if self.compression != 0 {
+            payload.push(self.compression);
+        }
+    }
+
+    /// Interpreta os bytes que seguem os campos da etapa.
+    fn decode(bytes: &[u8]) -> Option<Self> {
+        // Sem o token, os parâmetros têm no máximo 34 bytes.
+        let (connect_token, bytes) = match bytes.split_first_chunk() {
+            Some((token, rest)) => (Some(*token), rest),
+            None => (None, bytes),
+        };
+        let (public_key, signed, rest) = match bytes.len() {
+            0 | 1 => (None, false, bytes),
+            32..=34 => {
+                let (key, rest) = bytes.split_at(32);
+                match rest.split_first() {
+                    Some((0, rest)) => (Some(key.try_into().ok()?), true, rest),
+                    _ => (Some(key.try_into().ok()?), false, rest),
+                }
+            }
+            _ => return None,
+        };
+        let compression = match rest {
+            [] => 0,
+            [compression] if *compression != 0 => *compression,
+            _ => return None,
+        };
+        Some(Self {
+            connect_token,
+            public_key,
+            signed,
+            compression,
+        })
+    }
+
+    /// Proteção dos pacotes anunciada.
+    fn security(&self) -> SecurityLevel {
+        match (self.public_key, self.signed) {
+            (None, _) => SecurityLevel::None,
+            (Some(_), true) => SecurityLevel::Signed,
+            (Some(_), false) => SecurityLevel::Encrypted,
+        }
+    }
+}
+
+/// Mensagens do aperto de mão, no payload de pacotes [`MessageType::Connect`].
+///
+/// O primeiro byte identifica a etapa, seguido dos campos em little-endian e, exceto
+/// no desafio e na recusa, da [`Offer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Handshake {
+    /// Cliente → servidor: pedido de conexão.
+    Request {
+        salt: u64,
+        version: ProtocolVersion,
+        offer: Offer,
+    },
+    /// Servidor → cliente: desafio a ser devolvido.
+    Challenge { salt: u64, token: u64 },
+    /// Cliente → servidor: devolução do desafio.
+    Response {
+        salt: u64,
+        token: u64,
+        version: ProtocolVersion,
+        offer: Offer,
+    },
+    /// Servidor → cliente: conexão aceita.
+    Accepted {
+        salt: u64,
+        session_id: u64,
+        offer: Offer,
+    },
+    /// Servidor → cliente: pedido recusado por versão incompatível, com a do servidor.
+    Rejected { salt: u64, version: ProtocolVersion },
+}
+
+impl PacketPayload for Handshake {
+    const MESSAGE_TYPE: u8 = 3;
+
+    fn encode(&self) -> Vec<u8> {
+        let none = Offer::default();
+        let (step, salt, value, version, offer) = match *self {
+            Self::Request {
+                salt,
+                version,
+                offer,
+            } => (0, salt, None, Some(version), offer),
+            Self::Challenge { salt, token } => (1, salt, Some(token), None, none),
+            Self::Response {
+                salt,
+                token,
+                version,
+                offer,
+            } => (2, salt, Some(token), Some(version), offer),
+            Self::Accepted {
+                salt,
+                session_id,
+                offer,
+            } => (3, salt, Some(session_id), None, offer),
+            Self::Rejected { salt, version } => (4, salt, None, Some(version), none),
+        };
+        let mut payload = vec![step];
+        payload.extend_from_slice(&salt.to_le_bytes());
+        if let Some(value) = value {
+            payload.extend_from_slice(&value.to_le_bytes());
+        }
+        if let Some(version) = version {
+            payload.extend_from_slice(&version.major.to_le_bytes());
+            payload.extend_from_slice(&version.minor.to_le_bytes());
+        }
+        offer.encode_into(&mut payload);
+        payload
+    }
+
+    fn decode(payload: &[u8]) -> Result<Self, PacketError> {
+        let invalid = PacketError::InvalidPayload {
+            reason: "mensagem de aperto de mão malformada",
+        };
+        let word = |offset: usize| -> Option<u64> {
+            let bytes = payload.get(offset..offset + 8)?;
+            Some(u64::from_le_bytes(bytes.try_into().ok()?))
+        };
+        let version = |offset: usize| -> Option<ProtocolVersion> {
+            let bytes = payload.get(offset..offset + 4)?;
+            Some(ProtocolVersion::new(
+                u16::from_le_bytes([bytes[0], bytes[1]]),
+                u16::from_le_bytes([bytes[2], bytes[3]]),
+            ))
+        };
+        let offer = |offset: usize| payload.get(offset..).and_then(Offer::decode);
+        let salt = word(1).ok_or(invalid.clone())?;
+        let decoded = match (payload[0], word(9)) {
+            (0, _) => version(9)
+                .zip(offer(13))
+                .map(|(version, offer)| Self::Request {
+                    salt,
+                    version,
+                    offer,
+                }),
+            (1, Some(token)) if payload.len() == 17 => Some(Self::Challenge { salt, token }),
+            (2, Some(token)) => version(17)
+                .zip(offer(21))
+                .map(|(version, offer)| Self::Response {
+                    salt,
+                    token,
+                    version,
+                    offer,
+                }),
+            (3, Some(session_id)) => offer(17).map(|offer| Self::Accepted {
+                salt,
+                session_id,
+                offer,
+            }),
+            (4, _) if payload.len() == 13 => {
+                version(9).map(|version| Self::Rejected { salt, version })
+            }
+            _ => None,
+        };
+        decoded.ok_or(invalid)
+    }
+}
+
+/// Estado de uma conexão estabelecida comum aos dois lados: pings, RTT e liveness.
+#[derive(Debug)]
+struct Link {
+    /// Tempo desde o último pacote do outro lado.
+    liveness: Liveness,
+    /// RTT e jitter medidos pelos pongs.
+    rtt: RttEstimator,
+    /// Instante de envio dos pings ainda sem resposta, pela sequência.
+    pings: SequenceBuffer<Instant>,
+    /// Sequência do próximo ping.
+    next_ping: u32,
+    /// Instante do último ping enviado.
+    last_ping: Instant,
+    /// Sequência do último ping de manutenção, contado como perdido se ainda não foi
+    /// respondido quando o seguinte é enviado.
+    last_ping_sequence: Option<u32>,
+    /// Pings de manutenção respondidos.
+    pings_answered: u64,
+    /// Pings de manutenção sem resposta.
+    pings_lost: u64,
+    /// Intervalo entre pings.
+    ping_interval: Duration,
+    /// Descoberta do MTU do caminho, por pings preenchidos.
+    mtu: MtuDiscovery,
+    /// Remontagem dos pacotes da aplicação fragmentados pelo outro lado.
+    fragments: Reassembler,
+    /// Origem do relógio local, gravado nos pongs enviados.
+    epoch: Instant,
+    /// Estimativa do relógio do outro lado, pelos pongs recebidos.
+    sync: TimeSync,
+}
+
+impl Link {
+    fn new(timeout: Duration, now: Instant, epoch: Instant) -> Self {
+        Self {
+            liveness: Liveness::new(timeout, now),
+            rtt: RttEstimator::new(),
+            pings: SequenceBuffer::new(PING_HISTORY),
+            next_ping: 0,
+            last_ping: now,
+            last_ping_sequence: None,
+            pings_answered: 0,
+            pings_lost: 0,
+            ping_interval: timeout / 4,
+            mtu: MtuDiscovery::new(DEFAULT_MTU, MAX_PROBE_MTU),
+            fragments: Reassembler::new(timeout),
+            epoch,
+            sync: TimeSync::new(),
+        }
+    }
+
+    /// Produz o próximo ping, se já passou o intervalo ou a sincronização dos relógios
+    /// o pede, e a próxima sonda de MTU, e descarta as remontagens expiradas.
+    ///
+    /// A sonda é preenchida de modo a ter o tamanho sondado depois de receber os
+    /// `overhead` bytes da proteção da conexão.
+    fn poll(&mut self, now: Instant, session_id: u64, overhead: usize) -> [Option<Packet>; 2] {
+        self.fragments.expire(now);
+        let sync = self.sync.poll(now);
+        let due = now.saturating_duration_since(self.last_ping) >= self.ping_interval;
+        let ping = if due {
+            self.last_ping = now;
+            if let Some(previous) = self.last_ping_sequence {
+                self.pings_lost += u64::from(self.pings.remove(previous).is_some());
+            }
+            let ping = self.ping(now, session_id, 0);
+            self.last_ping_sequence = Some(ping.header.sequence);
+            Some(ping)
+        } else {
+            sync.then(|| self.ping(now, session_id, 0))
+        };
+        let probe = self.mtu.poll(now, self.next_ping).map(|size| {
+            let padding = size.saturating_sub(PacketHeader::SIZE + overhead);
+            self.ping(now, session_id, padding)
+        });
+        [ping, probe]
+    }
+
+    /// Monta um ping com `padding` bytes de payload.
+    fn ping(&mut self, now: Instant, session_id: u64, padding: usize) -> Packet {
+        let sequence = self.next_ping;
+        self.next_ping = sequence.wrapping_add(1);
+        self.pings.insert(sequence, now);
+        Packet::new(
+            MessageType::Ping.into(),
+            sequence,
+            session_id,
+            vec![0; padding],
+        )
+    }
+
+    /// Remonta um pacote da aplicação recebido, até a chegada do último fragmento.
+    fn reassemble(&mut self, packet: Packet, now: Instant) -> Option<Packet> {
+        self.fragments.insert(packet, now).ok().flatten()
+    }
+
+    /// Registra um pacote recebido, retornando o pong a enviar se ele é um ping.
+    fn record(&mut self, packet: &Packet, now: Instant) -> Option<Packet> {
+        self.liveness.record_received(now);
+        let sequence = packet.header.sequence;
+        match packet.message_type() {
+            Ok(MessageType::Ping) => Some(Packet::pong(
+                sequence,
+                packet.header.player_id,
+                now.saturating_duration_since(self.epoch),
+            )),
+            Ok(MessageType::Pong) => {
+                self.mtu.acknowledge(sequence);
+                if let Some(sent) = self.pings.remove(sequence) {
+                    self.rtt.record(now.saturating_duration_since(sent));
+                    self.pings_answered += u64::from(self.last_ping_sequence == Some(sequence));
+                    if let Some(time) = packet.pong_time() {
+                        self.sync.record(sent, now, time);
+                    }
+                }
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// Completa as estatísticas de tráfego com RTT, jitter e pings.
+    fn stats(&self, traffic: NetworkStats) -> NetworkStats {
+        NetworkStats {
+            pings_answered: self.pings_answered,
+            pings_lost: self.pings_lost,
+            rtt: self.rtt.rtt(),
+            jitter: self.rtt.jitter(),
+            ..traffic
+        }
+    }
+}
+
+/// Compressão e autenticação ou cifragem dos pacotes da aplicação, conforme negociadas
+/// no aperto de mão, e descarte dos pacotes recebidos repetidos; sem compressão nem
+/// chaves, os pacotes seguem como estão.
+#[derive(Debug, Default)]
+struct Codec {
+    /// Sequências dos pacotes da aplicação recebidos.
+    replay: ReplayWindow,
+    /// Chaves combinadas no aperto de mão, nas conexões assinadas ou cifradas.
+    #[cfg(feature = "crypto")]
+    keys: Option<SessionKeys>,
+    /// Indica se as chaves autenticam os pacotes em vez de cifrá-los.
+    #[cfg(feature = "crypto")]
+    signed: bool,
+    /// Compressor dos pacotes enviados, nas conexões com compressão.
+    #[cfg(feature = "compression")]
+    compressor: Option<Compressor>,
+}
+
+impl Codec {
+    /// Prepara um pacote a ser enviado: comprime e depois autentica ou cifra.
+    fn encode(&self, packet: Packet) -> Packet {
+        #[cfg(feature = "compression")]
+        let packet = match &self.compressor {
+            Some(compressor) => compressor.compress(packet),
+            None => packet,
+        };
+        #[cfg(feature = "crypto")]
+        if let Some(keys) = &self.keys {
+            return match self.signed {
+                true => keys.authenticate(packet),
+                false => keys.seal(packet),
+            };
+        }
+        packet
+    }
+
+    /// Prepara um ping, pong ou desconexão a ser enviado: autentica, sem comprimir nem
+    /// cifrar, se a conexão é assinada ou cifrada.
+    ///
+    /// As sequências dessas mensagens não são as dos pacotes da aplicação, então
+    /// cifrá-las repetiria nonces; a etiqueta HMAC usa chaves próprias e não tem nonce.
+    fn encode_control(&self, packet: Packet) -> Packet {
+        #[cfg(feature = "crypto")]
+        if let Some(keys) = &self.keys {
+            return keys.authenticate(packet);
+        }
+        packet
+    }
+
+    /// Bytes acrescidos por [`Codec::encode_control`], descontados das sondas de MTU.
+    fn control_overhead(&self) -> usize {
+        #[cfg(feature = "crypto")]
+        if self.keys.is_some() {
+            return crate::mac::MAC_SIZE;
+        }
+        0
+    }
+
+    /// Restaura um pacote da aplicação já verificado por [`Codec::open`], descartando-o
+    /// se sua sequência já foi recebida ou é antiga demais.
+    ///
+    /// Com o recurso `compression`, pacotes comprimidos só são aceitos se a compressão
+    /// foi negociada, e [`Packet::decompress`] limita o tamanho original declarado.
+    fn decode(&mut self, packet: Packet) -> Option<Packet> {
+        #[cfg(feature = "compression")]
+        if self.compressor.is_none() && packet.header.flags.contains(PacketFlags::COMPRESSED) {
+            return None;
+        }
+        if self.replay.check(packet.header.sequence) != ReplayStatus::Accepted {
+            return None;
+        }
+        #[cfg(feature = "compression")]
+        let packet = packet.decompress().ok()?;
+        Some(packet)
+    }
+
+    /// Verifica e decifra um pacote recebido, antes de qualquer outro processamento,
+    /// se a conexão é assinada ou cifrada.
+    ///
+    /// Pings, pongs e desconexões são verificados como em [`Codec::encode_control`];
+    /// os demais, pelo nível de proteção da conexão.
+    fn open(&self, packet: Packet) -> Option<Packet> {
+        #[cfg(feature = "crypto")]
+        if let Some(keys) = &self.keys {
+            let control = matches!(
+                packet.message_type(),
+                Ok(MessageType::Ping | MessageType::Pong | MessageType::Disconnect)
+            );
+            return match self.signed || control {
+                true => keys.verify_mac(&packet).ok(),
+                false => keys.open(&packet).ok(),
+            };
+        }
+        let flags = packet.header.flags;
+        (!flags.contains(PacketFlags::ENCRYPTED) && !flags.contains(PacketFlags::AUTHENTICATED))
+            .then_some(packet)
+    }
+
+    /// Identificador do algoritmo de compressão negociado, ou `0`.
+    fn compression_id(&self) -> u8 {
+        #[cfg(feature = "compression")]
+        {
+            self.compressor
+                .map_or(0, |compressor| compressor.algorithm().id())
+        }
+        #[cfg(not(feature = "compression"))]
+        {
+            0
+        }
+    }
+}
+
+/// Conexão estabelecida, do ponto de vista do servidor.
+#[derive(Debug)]
+struct Session {
+    /// Identificador da sessão.
+    id: u64,
+    /// `salt` do aperto de mão, para reenviar a aceitação a retransmissões.
+    salt: u64,
+    /// Parâmetros enviados na aceitação, para reenviá-la a retransmissões.
+    offer: Offer,
+    /// Jogador autenticado pelo token de conexão, se o servidor exige tokens.
+    player_id: Option<u64>,
+    /// Compressão e cifragem dos pacotes da aplicação.
+    codec: Codec,
+    /// Pings, RTT e liveness do cliente.
+    link: Link,
+    /// Tráfego da sessão.
+    stats: StatsRecorder,
+}
+
+/// Servidor que aceita conexões por desafio e resposta.
+///
+/// O transporte padrão é o [`UdpTransport`]; [`Server::from_transport`] aceita
+/// qualquer [`Transport`].
+#[derive(Debug)]
+pub struct Server<T = UdpTransport> {
+    /// Transporte em modo não bloqueante.
+    transport: T,
+    /// Segredo usado para derivar os desafios.
+    secret: RandomState,
+    /// Sessões estabelecidas, pelo endereço do cliente.
+    sessions: HashMap<SocketAddr, Session>,
+    /// Identificador da próxima sessão.
+    next_session_id: u64,
+    /// Incremento entre os identificadores de sessão.
+    session_id_step: u64,
+    /// Tempo sem pacotes após o qual uma sessão expira.
+    timeout: Duration,
+    /// Versão do protocolo aceita.
+    version: ProtocolVersion,
+    /// Tráfego de todo o servidor, incluindo os apertos de mão e as sessões encerradas.
+    stats: StatsRecorder,
+    /// Entrega periódica das estatísticas.
+    reporter: Option<StatsReporter>,
+    /// Ganchos dos pacotes recebidos.
+    inbound_hooks: HookPipeline,
+    /// Ganchos dos pacotes enviados.
+    outbound_hooks: HookPipeline,
+    /// Salas dos jogadores.
+    lobby: Lobby,
+    /// Limitação da taxa de recepção por origem, se ativa.
+    flood_guard: Option<FloodGuard>,
+    /// Respostas às consultas de estado, se ativas.
+    query: Option<QueryResponder>,
+    /// Origem do relógio do servidor, gravado nos pongs.
+    epoch: Instant,
+    /// Instante do último `poll` ou `update`, usado pelos envios fora deles.
+    last_tick: Instant,
+    /// Hora do sistema em `epoch`, para a validade dos tokens de conexão no instante
+    /// de cada tick.
+    #[cfg(feature = "crypto")]
+    wall_epoch: SystemTime,
+    /// Proteção dos pacotes das sessões.
+    #[cfg(feature = "crypto")]
+    security: SecurityLevel,
+    /// Chave pública do serviço de autenticação e endereço deste servidor nos
+    /// tokens, se os tokens de conexão são exigidos.
+    #[cfg(feature = "crypto")]
+    connect_tokens: Option<(VerifyingKey, SocketAddr)>,
+    /// Compressor oferecido aos clientes que anunciam seu algoritmo.
+    #[cfg(feature = "compression")]
+    compressor: Option<Compressor>,
+}
+
+impl Server {
+    /// Cria um servidor associado ao endereço informado.
+    ///
+    /// ## Parâmetros
+    /// - `addr`: Endereço local; use a porta `0` para uma porta qualquer.
+    ///
+    /// ## Retorno
+    /// - `io::Result<Self>`: O servidor, com [`DEFAULT_CONNECTION_TIMEOUT`], ou o erro
+    ///   retornado pelo sistema.
+    pub fn listen(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        Self::from_transport(UdpTransport::bind(addr)?)
+    }
+}
+
+impl<T: Transport> Server<T> {
+    /// Cria um servidor sobre um transporte qualquer.
+    ///
+    /// ## Parâmetros
+    /// - `transport`: Transporte já associado ao endereço do servidor; é colocado em
+    ///   modo não bloqueante.
+    ///
+    /// ## Retorno
+    /// - `io::Result<Self>`: O servidor, com [`DEFAULT_CONNECTION_TIMEOUT`], ou o erro
+    ///   de [`Transport::set_nonblocking`].
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::connection::Server;
+    /// use packet::simulator::{NetworkConditions, SimulatedTransport};
+    /// use packet::transport::UdpTransport;
+    ///
+    /// let socket = UdpTransport::bind("127.0.0.1:0").unwrap();
+    /// let transport = SimulatedTransport::new(socket, NetworkConditions::default(), 7);
+    /// let server = Server::from_transport(transport).unwrap();
+    /// assert_eq!(server.session_count(), 0);
+    /// ```
+    pub fn from_transport(transport: T) -> io::Result<Self> {
+        Self::from_transport_with_clock(transport, &SystemClock)
+    }
+
+    /// Cria um servidor sobre um transporte qualquer, com a origem do relógio tirada de
+    /// um relógio.
+    ///
+    /// Com um [`MockClock`](crate::clock::MockClock), e os instantes de `poll` e
+    /// `update` tirados do mesmo relógio, o servidor não depende do tempo real: o
+    /// relógio gravado nos pongs, os instantes dos envios fora do `poll` e a validade
+    /// dos tokens de conexão passam a ser os do relógio. Os tokens guardam a hora do
+    /// sistema, então a origem do relógio corresponde à hora do sistema na criação do
+    /// servidor; com o recurso `crypto`, [`Server::with_wall_epoch`] a fixa.
+    ///
+    /// ## Parâmetros
+    /// - `transport`: Transporte já associado ao endereço do servidor; é colocado em
+    ///   modo não bloqueante.
+    /// - `clock`: Relógio consultado uma vez, por [`Clock::now`].
+    ///
+    /// ## Retorno
+    /// - `io::Result<Self>`: O servidor, como em [`Server::from_transport`].
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use std::time::Duration;
+    /// use packet::clock::{Clock, MockClock};
+    /// use packet::connection::Server;
+    /// use packet::transport::UdpTransport;
+    ///
+    /// let clock = MockClock::new(0);
+    /// let transport = UdpTransport::bind("127.0.0.1:0").unwrap();
+    /// let server = Server::from_transport_with_clock(transport, &clock).unwrap();
+    /// clock.advance(1_500);
+    /// assert_eq!(server.server_time(clock.now()), Duration::from_millis(1_500));
+    /// ```
+    pub fn from_transport_with_clock(mut transport: T, clock: &impl Clock) -> io::Result<Self> {
+        transport.set_nonblocking(true)?;
+        let now = clock.now();
+        Ok(Self {
+            transport,
+            secret: RandomState::new(),
+            sessions: HashMap::new(),
+            next_session_id: 1,
+            session_id_step: 1,
+            timeout: DEFAULT_CONNECTION_TIMEOUT,
+            version: ProtocolVersion::default(),
+            stats: StatsRecorder::new(),
+            reporter: None,
+            inbound_hooks: HookPipeline::new(),
+            outbound_hooks: HookPipeline::new(),
+            lobby: Lobby::new(),
+            flood_guard: None,
+            query: None,
+            epoch: now,
+            last_tick: now,
+            #[cfg(feature = "crypto")]
+            wall_epoch: SystemTime::now(),
+            #[cfg(feature = "crypto")]
+            security: SecurityLevel::None,
+            #[cfg(feature = "crypto")]
+            connect_tokens: None,
+            #[cfg(feature = "compression")]
+            compressor: None,
+        })
+    }
+
+    /// Ativa a proteção contra inundação na recepção.
+    ///
+    /// Cada pacote recebido passa por [`FloodGuard::check`] antes de ser interpretado,
+    /// inclusive os do aperto de mão; os recusados são descartados e contados em
+    /// [`Server::stats`].
+    ///
+    /// ## Parâmetros
+    /// - `guard`: Limites e banimentos aplicados às origens.
+    ///
+    /// ## Retorno
+    /// - `Self`: O servidor com a proteção ativa.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use std::net::UdpSocket;
+    /// use std::time::{Duration, Instant};
+    /// use packet::Packet;
+    /// use packet::connection::Server;
+    /// use packet::ratelimit::{FloodGuard, RateLimit};
+    ///
+    /// let guard = FloodGuard::new()
+    ///     .with_unconnected_limit(RateLimit::new(5, 4_096))
+    ///     .with_bans(1, Duration::from_secs(60));
+    /// let mut server = Server::listen("127.0.0.1:0").unwrap().with_flood_protection(guard);
+    ///
+    /// let attacker = UdpSocket::bind("127.0.0.1:0").unwrap();
+    /// let bytes = Packet::new(1, 1, 0, vec![0; 16]).to_bytes();
+    /// for _ in 0..50 {
+    ///     attacker.send_to(&bytes, server.local_addr().unwrap()).unwrap();
+    /// }
+    /// std::thread::sleep(Duration::from_millis(20));
+    /// server.poll(Instant::now()).unwrap();
+    ///
+    /// let stats = server.stats();
+    /// assert_eq!(stats.bans, 1);
+    /// assert!(stats.packets_from_banned > 0);
+    /// assert!(server
+    ///     .flood_guard()
+    ///     .unwrap()
+    ///     .is_banned(attacker.local_addr().unwrap(), Instant::now()));
+    /// ```
+    pub fn with_flood_protection(mut self, guard: FloodGuard) -> Self {
+        self.flood_guard = Some(guard);
+        self
+    }
+
+    /// Ativa as respostas às consultas de estado sem conexão (veja
+    /// [`query`](crate::query)).
+    ///
+    /// As consultas são respondidas por um [`QueryResponder`], limitado por origem, sem
+    /// criar sessões; o campo [`ServerStatus::players`] é sempre a quantidade de sessões
+    /// estabelecidas.
+    ///
+    /// ## Parâmetros
+    /// - `status`: Estado respondido.
+    ///
+    /// ## Retorno
+    /// - `Result<Self, PacketError>`: O servidor com as respostas ativas, ou
+    ///   [`PacketError::PayloadTooLarge`] se o estado não cabe em um único datagrama.
+    pub fn with_query_status(mut self, status: ServerStatus) -> Result<Self, PacketError> {
+        self.query = Some(QueryResponder::new(status)?);
+        Ok(self)
+    }
+
+    /// Define o tempo sem pacotes após o qual uma sessão expira.
+    ///
+    /// ## Parâmetros
+    /// - `timeout`: Novo tempo limite; os pings são enviados a cada quarto dele.
+    ///
+    /// ## Retorno
+    /// - `Self`: O servidor com o novo tempo limite.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Define a versão do protocolo da aplicação.
+    ///
+    /// Pedidos de clientes com versão incompatível são recusados com uma resposta que
+    /// leva a versão do servidor e produzem um [`ServerEvent::Rejected`].
+    ///
+    /// ## Parâmetros
+    /// - `version`: Versão do servidor; o padrão é `0.0`.
+    ///
+    /// ## Retorno
+    /// - `Self`: O servidor com a nova versão.
+    pub fn with_protocol_version(mut self, version: ProtocolVersion) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Registra uma função chamada periodicamente por [`Server::poll`] com as
+    /// estatísticas do servidor e de cada sessão.
+    ///
+    /// ## Parâmetros
+    /// - `interval`: Intervalo mínimo entre as entregas; a primeira acontece no
+    ///   primeiro `poll`.
+    /// - `callback`: Recebe `None` e as estatísticas de [`Server::stats`], seguidas de
+    ///   `Some(session_id)` e as de [`Server::session_stats`] para cada sessão.
+    ///
+    /// ## Retorno
+    /// - `Self`: O servidor com a entrega registrada.
+    pub fn with_stats_callback(
+        mut self,
+        interval: Duration,
+        callback: impl FnMut(Option<u64>, &NetworkStats) + Send + 'static,
+    ) -> Self {
+        self.reporter = Some(StatsReporter::new(interval, callback));
+        self
+    }
+
+    /// Acrescenta um gancho ao fim dos ganchos de recepção (veja [`hook`](crate::hook)).
+    ///
+    /// Os ganchos veem cada datagrama válido depois da proteção contra inundação e
+    /// antes de qualquer outro processamento, como o aperto de mão, a decifragem e a
+    /// remontagem dos fragmentos; um pacote descartado é ignorado pelo servidor.
+    ///
+    /// ## Parâmetros
+    /// - `hook`: Função chamada com a origem e a visão de cada pacote recebido.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// use std::sync::Arc;
+    /// use std::time::{Duration, Instant};
+    /// use packet::Packet;
+    /// use packet::connection::{Client, Server, ServerEvent};
+    /// use packet::hook::HookDecision;
+    ///
+    /// const CHEAT: u8 = 9;
+    ///
+    /// let mut server = Server::listen("127.0.0.1:0").unwrap();
+    /// let suspicious = Arc::new(AtomicUsize::new(0));
+    /// let counter = Arc::clone(&suspicious);
+    /// server.add_inbound_hook(move |_, view| match view.header.message_type {
+    ///     CHEAT => {
+    ///         counter.fetch_add(1, Ordering::Relaxed);
+    ///         HookDecision::Drop
+    ///     }
+    ///     _ => HookDecision::Continue,
+    /// });
+    /// let mut client = Client::connect(server.local_addr().unwrap(), Instant::now()).unwrap();
+    ///
+    /// let mut received = Vec::new();
+    /// while received.is_empty() {
+    ///     let now = Instant::now();
+    ///     for event in server.poll(now).unwrap() {
+    ///         if let ServerEvent::Packet { packet, .. } = event {
+    ///             received.push(packet.header.message_type);
+    ///         }
+    ///     }
+    ///     client.poll(now).unwrap();
+    ///     if let Some(session_id) = client.session_id() {
+    ///         client.send(Packet::new(CHEAT, 1, session_id, vec![])).unwrap();
+    ///         client.send(Packet::new(1, 2, session_id, vec![])).unwrap();
+    ///     }
+    ///     std::thread::sleep(Duration::from_millis(1));
+    /// }
+    ///
+    /// assert!(received.iter().all(|message_type| *message_type == 1));
+    /// assert!(suspicious.load(Ordering::Relaxed) > 0);
+    /// ```
+    pub fn add_inbound_hook(
+        &mut self,
+        hook: impl FnMut(&SocketAddr, &PacketView<'_>) -> HookDecision + Send + 'static,
+    ) {
+        self.inbound_hooks.add(hook);
+    }
+
+    /// Acrescenta um gancho ao fim dos ganchos de envio (veja [`hook`](crate::hook)).
+    ///
+    /// Os ganchos veem cada pacote enviado pelo servidor, inclusive os do aperto de mão
+    /// e os pings, com o endereço de destino, depois da compressão e da cifragem e antes
+    /// da fragmentação; um pacote descartado não é enviado, e [`Server::send`] retorna
+    /// `0` bytes.
+    ///
+    /// ## Parâmetros
+    /// - `hook`: Função chamada com o destino e a visão de cada pacote a enviar.
+    pub fn add_outbound_hook(
+        &mut self,
+        hook: impl FnMut(&SocketAddr, &PacketView<'_>) -> HookDecision + Send + 'static,
+    ) {
+        self.outbound_hooks.add(hook);
+    }
+
+    /// Passa a exigir conexões cifradas, com uma troca de chaves no aperto de mão.
+    ///
+    /// Disponível com o recurso `crypto`. Clientes que não usam
+    /// [`Client::connect_encrypted`] deixam de ser aceitos.
+    ///
+    /// ## Retorno
+    /// - `Self`: O servidor com a cifragem habilitada.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use std::time::{Duration, Instant};
+    /// use packet::connection::{Client, ConnectionState, Server, ServerEvent};
+    /// use packet::{Packet, PacketFlags};
+    ///
+    /// let mut server = Server::listen("127.0.0.1:0").unwrap().with_encryption();
+    /// let server_addr = server.local_addr().unwrap();
+    /// let mut client = Client::connect_encrypted(server_addr, Instant::now()).unwrap();
+    /// let mut plaintext = Client::connect(server_addr, Instant::now()).unwrap();
+    ///
+    /// let mut received = None;
+    /// for _ in 0..400 {
+    ///     let now = Instant::now();
+    ///     for event in server.poll(now).unwrap() {
+    ///         if let ServerEvent::Packet { packet, .. } = event {
+    ///             received = Some(packet);
+    ///         }
+    ///     }
+    ///     client.poll(now).unwrap();
+    ///     plaintext.poll(now).unwrap();
+    ///     if client.state() == ConnectionState::Connected && received.is_none() {
+    ///         let session_id = client.session_id().unwrap();
+    ///         client.send(Packet::new(1, 1, session_id, vec![1, 2, 3])).unwrap();
+    ///     }
+    ///     if received.is_some() {
+    ///         break;
+    ///     }
+    ///     std::thread::sleep(Duration::from_millis(1));
+    /// }
+    ///
+    /// // O pacote chega decifrado, e o cliente sem cifragem não é aceito.
+    /// let received = received.unwrap();
+    /// assert_eq!(received.payload, [1, 2, 3]);
+    /// assert!(!received.header.flags.contains(PacketFlags::ENCRYPTED));
+    /// assert_eq!(plaintext.state(), ConnectionState::Connecting);
+    /// assert_eq!(server.session_count(), 1);
+    /// ```
+    #[cfg(feature = "crypto")]
+    pub fn with_encryption(self) -> Self {
+        self.with_security(SecurityLevel::Encrypted)
+    }
+
+    /// Define a proteção dos pacotes exigida das sessões, com uma troca de chaves no
+    /// aperto de mão se não for [`SecurityLevel::None`].
+    ///
+    /// Disponível com o recurso `crypto`. Com [`SecurityLevel::Signed`], os pacotes da
+    /// aplicação seguem em claro, mas levam a etiqueta HMAC de
+    /// [`Packet::authenticate`](crate::Packet::authenticate), verificada antes de
+    /// qualquer outro processamento; pacotes sem etiqueta ou adulterados são
+    /// descartados. Só são aceitos clientes com o mesmo nível, definido com
+    /// [`Client::with_security`].
+    ///
+    /// ## Parâmetros
+    /// - `security`: Proteção das sessões.
+    ///
+    /// ## Retorno
+    /// - `Self`: O servidor com a nova proteção.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use std::time::{Duration, Instant};
+    /// use packet::connection::{Client, ConnectionState, SecurityLevel, Server, ServerEvent};
+    /// use packet::{Packet, PacketFlags};
+    ///
+    /// let mut server = Server::listen("127.0.0.1:0")
+    ///     .unwrap()
+    ///     .with_security(SecurityLevel::Signed);
+    /// let server_addr = server.local_addr().unwrap();
+    /// let mut client = Client::connect(server_addr, Instant::now())
+    ///     .unwrap()
+    ///     .with_security(SecurityLevel::Signed);
+    /// let mut encrypted = Client::connect_encrypted(server_addr, Instant::now()).unwrap();
+    ///
+    /// let mut received = None;
+    /// for _ in 0..400 {
+    ///     let now = Instant::now();
+    ///     for event in server.poll(now).unwrap() {
+    ///         if let ServerEvent::Packet { packet, .. } = event {
+    ///             received = Some(packet);
+    ///         }
+    ///     }
+    ///     client.poll(now).unwrap();
+    ///     encrypted.poll(now).unwrap();
+    ///     if client.state() == ConnectionState::Connected && received.is_none() {
+    ///         let session_id = client.session_id().unwrap();
+    ///         client.send(Packet::new(1, 1, session_id, vec![1, 2, 3])).unwrap();
+    ///     }
+    ///     if received.is_some() {
+    ///         break;
+    ///     }
+    ///     std::thread::sleep(Duration::from_millis(1));
+    /// }
+    ///
+    /// // O pacote chega verificado e sem a etiqueta; o cliente cifrado não é aceito.
+    /// let received = received.unwrap();
+    /// assert_eq!(received.payload, [1, 2, 3]);
+    /// assert!(!received.header.flags.contains(PacketFlags::AUTHENTICATED));
+    /// assert_eq!(encrypted.state(), ConnectionState::Connecting);
+    /// assert_eq!(server.session_count(), 1);
+    /// ```
+    #[cfg(feature = "crypto")]
+    pub fn with_security(mut self, security: SecurityLevel) -> Self {
+        self.security = security;
+        self
+    }
+
+    /// Passa a comprimir os pacotes das sessões cujos clientes aceitam o algoritmo do
+    /// compressor.
+    ///
+    /// Disponível com o recurso `compression`. O algoritmo é negociado no aperto de
+    /// mão: clientes criados com [`Client::with_compression`] que o anunciam passam a
+    /// trocar pacotes comprimidos nos dois sentidos, e os demais continuam sendo aceitos
+    /// sem compressão.
+    ///
+    /// ## Parâmetros
+    /// - `compressor`: Algoritmo e tamanho mínimo usados nos pacotes enviados pelo
+    ///   servidor; o cliente usa o mesmo algoritmo com o próprio tamanho mínimo.
+    ///
+    /// ## Retorno
+    /// - `Self`: O servidor com a compressão habilitada.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use std::time::{Duration, Instant};
+    /// use packet::compression::{CompressionAlgorithm, Compressor};
+    /// use packet::connection::{Client, ConnectionState, Server, ServerEvent};
+    /// use packet::{Packet, PacketFlags};
+    ///
+    /// let mut server = Server::listen("127.0.0.1:0")
+    ///     .unwrap()
+    ///     .with_compression(Compressor::new(CompressionAlgorithm::Zstd));
+    /// let server_addr = server.local_addr().unwrap();
+    /// let mut client = Client::connect(server_addr, Instant::now())
+    ///     .unwrap()
+    ///     .with_compression(64);
+    /// let mut plain = Client::connect(server_addr, Instant::now()).unwrap();
+    ///
+    /// let mut received = None;
+    /// for _ in 0..400 {
+    ///     let now = Instant::now();
+    ///     for event in server.poll(now).unwrap() {
+    ///         if let ServerEvent::Packet { packet, .. } = event {
+    ///             received = Some(packet);
+    ///         }
+    ///     }
+    ///     client.poll(now).unwrap();
+    ///     plain.poll(now).unwrap();
+    ///     if client.state() == ConnectionState::Connected && received.is_none() {
+    ///         let session_id = client.session_id().unwrap();
+    ///         client.send(Packet::new(1, 1, session_id, vec![7; 1000])).unwrap();
+    ///     }
+    ///     if received.is_some() && plain.state() == ConnectionState::Connected {
+    ///         break;
+    ///     }
+    ///     std::thread::sleep(Duration::from_millis(1));
+    /// }
+    ///
+    /// // O pacote chega descomprimido, e o cliente sem compressão também é aceito.
+    /// let received = received.unwrap();
+    /// assert_eq!(received.payload, vec![7; 1000]);
+    /// assert!(!received.header.flags.contains(PacketFlags::COMPRESSED));
+    /// assert_eq!(plain.state(), ConnectionState::Connected);
+    /// ```
+    #[cfg(feature = "compression")]
+    pub fn with_compression(mut self, compressor: Compressor) -> Self {
+        self.compressor = Some(compressor);
+        self
+    }
+
+    /// Passa a exigir um token de conexão válido, emitido pelo serviço de
+    /// autenticação, no pedido e na resposta do cliente.
+    ///
+    /// Disponível com o recurso `crypto`. A assinatura, a expiração e o endereço do
+    /// token são verificados com [`ConnectToken::verify`] antes de o desafio ser
+    /// enviado e antes de a sessão ser criada; pedidos sem token válido não recebem
+    /// resposta e produzem um [`ServerEvent::Rejected`] com
+    /// [`PacketError::InvalidToken`] ou [`PacketError::TokenExpired`]. O jogador do
+    /// token fica disponível em [`Server::player_id`].
+    ///
+    /// ## Parâmetros
+    /// - `verifying_key`: Chave pública do serviço de autenticação.
+    /// - `server_addr`: Endereço pelo qual os clientes conhecem este servidor, que deve
+    ///   constar dos tokens.
+    ///
+    /// ## Retorno
+    /// - `Self`: O servidor exigindo tokens.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use std::time::{Duration, Instant, SystemTime};
+    /// use ed25519_dalek::SigningKey;
+    /// use packet::PacketError;
+    /// use packet::auth::ConnectToken;
+    /// use packet::connection::{Client, ConnectionState, Server, ServerEvent};
+    ///
+    /// let backend_key = SigningKey::from_bytes(&[7; 32]);
+    /// let server = Server::listen("127.0.0.1:0").unwrap();
+    /// let server_addr = server.local_addr().unwrap();
+    /// let mut server = server.with_connect_tokens(backend_key.verifying_key(), server_addr);
+    ///
+    /// let expires_at = SystemTime::now() + Duration::from_secs(30);
+    /// let token = ConnectToken::new(42, expires_at, &[server_addr]).unwrap();
+    /// let mut client = Client::connect(server_addr, Instant::now())
+    ///     .unwrap()
+    ///     .with_connect_token(token.sign(&backend_key));
+    /// let mut anonymous = Client::connect(server_addr, Instant::now()).unwrap();
+    ///
+    /// let mut rejected = None;
+    /// for _ in 0..400 {
+    ///     let now = Instant::now();
+    ///     for event in server.poll(now).unwrap() {
+    ///         if let ServerEvent::Rejected { error, .. } = event {
+    ///             rejected = Some(error);
+    ///         }
+    ///     }
+    ///     client.poll(now).unwrap();
+    ///     anonymous.poll(now).unwrap();
+    ///     if client.state() == ConnectionState::Connected && rejected.is_some() {
+    ///         break;
+    ///     }
+    ///     std::thread::sleep(Duration::from_millis(1));
+    /// }
+    ///
+    /// let session_id = client.session_id().unwrap();
+    /// assert_eq!(server.player_id(session_id), Some(42));
+    /// assert_eq!(anonymous.state(), ConnectionState::Connecting);
+    /// assert_eq!(
+    ///     rejected,
+    ///     Some(PacketError::InvalidToken { reason: "token ausente" })
+    /// );
+    /// assert_eq!(server.session_count(), 1);
+    /// ```
+    #[cfg(feature = "crypto")]
+    pub fn with_connect_tokens(
+        mut self,
+        verifying_key: VerifyingKey,
+        server_addr: SocketAddr,
+    ) -> Self {
+        self.connect_tokens = Some((verifying_key, server_addr));
+        self
+    }
+
+    /// Define a hora do sistema correspondente à origem do relógio do servidor, usada
+    /// na validade dos tokens de conexão.
+    ///
+    /// Disponível com o recurso `crypto`. Um token é verificado na hora `wall_epoch`
+    /// somada ao tempo decorrido no relógio desde a criação do servidor; sem esta
+    /// chamada, `wall_epoch` é a hora do sistema na criação.
+    ///
+    /// ## Parâmetros
+    /// - `wall_epoch`: Hora do sistema correspondente à criação do servidor.
+    ///
+    /// ## Retorno
+    /// - `Self`: O servidor com a origem definida.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use std::net::SocketAddr;
+    /// use std::time::{Duration, SystemTime};
+    /// use ed25519_dalek::SigningKey;
+    /// use packet::PacketError;
+    /// use packet::auth::ConnectToken;
+    /// use packet::clock::{Clock, MockClock};
+    /// use packet::connection::{Client, Server, ServerEvent};
+    /// use packet::testing::MemoryNetwork;
+    ///
+    /// let backend_key = SigningKey::from_bytes(&[7; 32]);
+    /// let server_addr: SocketAddr = "10.0.0.1:4000".parse().unwrap();
+    /// let network = MemoryNetwork::new();
+    /// let clock = MockClock::new(0);
+    /// let mut server = Server::from_transport_with_clock(network.bind(server_addr), &clock)
+    ///     .unwrap()
+    ///     .with_wall_epoch(SystemTime::UNIX_EPOCH)
+    ///     .with_connect_tokens(backend_key.verifying_key(), server_addr);
+    ///
+    /// // O token vale por 10 s a partir da origem, e o relógio já passou disso.
+    /// let expires_at = SystemTime::UNIX_EPOCH + Duration::from_secs(10);
+    /// let token = ConnectToken::new(42, expires_at, &[server_addr]).unwrap();
+    /// clock.set(11_000);
+    /// let client_addr = "10.0.0.2:5000".parse().unwrap();
+    /// let mut client = Client::from_transport(network.bind(client_addr), server_addr, clock.now())
+    ///     .unwrap()
+    ///     .with_connect_token(token.sign(&backend_key));
+    ///
+    /// client.poll(clock.now()).unwrap();
+    /// assert_eq!(
+    ///     server.poll(clock.now()).unwrap(),
+    ///     [ServerEvent::Rejected { addr: client_addr, error: PacketError::TokenExpired }]
+    /// );
+    /// ```
+    #[cfg(feature = "crypto")]
+    pub fn with_wall_epoch(mut self, wall_epoch: SystemTime) -> Self {
+        self.wall_epoch = wall_epoch;
+        self
+    }
+
+    /// Define os identificadores atribuídos às sessões: `first`, `first + step`, e
+    /// assim por diante, para que servidores que dividem um endereço não os repitam.
+    pub(crate) fn with_session_ids(mut self, first: u64, step: u64) -> Self {
+        self.next_session_id = first;
+        self.session_id_step = step;
+        self
+    }
+
+    /// Retorna o endereço local do servidor.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        Transport::local_addr(&self.transport)
+    }
+
+    /// Retorna o transporte do servidor.
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    /// Retorna o transporte do servidor para alteração, como para
+    /// [`CompatTransport::take_events`](crate::compat::CompatTransport::take_events).
+    pub fn transport_mut(&mut self) -> &mut T {
+        &mut self.transport
+    }
+
+    /// Retorna a quantidade de sessões estabelecidas.
+    pub fn session_count(&self) -> usize {
+        self.sessions.len()
+    }
+
+    /// Retorna a proteção contra inundação, se ativa.
+    pub fn flood_guard(&self) -> Option<&FloodGuard> {
+        self.flood_guard.as_ref()
+    }
+
+    /// Retorna a proteção contra inundação para alteração, como para
+    /// [`FloodGuard::unban`].
+    pub fn flood_guard_mut(&mut self) -> Option<&mut FloodGuard> {
+        self.flood_guard.as_mut()
+    }
+
+    /// Retorna as respostas às consultas de estado, se ativas.
+    pub fn query_responder(&self) -> Option<&QueryResponder> {
+        self.query.as_ref()
+    }
+
+    /// Retorna as respostas às consultas de estado para alteração, como para
+    /// [`QueryResponder::set_status`] ao trocar de mapa.
+    pub fn query_responder_mut(&mut self) -> Option<&mut QueryResponder> {
+        self.query.as_mut()
+    }
+
+    /// Retorna as salas dos jogadores.
+    pub fn lobby(&self) -> &Lobby {
+        &self.lobby
+    }
+
+    /// Retorna as salas dos jogadores para alteração.
+    ///
+    /// As sessões são os jogadores das salas. Os eventos das mudanças chegam no
+    /// próximo [`Server::poll_events`]; com [`Server::poll`], leia-os com
+    /// [`Lobby::take_events`].
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use std::time::{Duration, Instant};
+    /// use packet::Packet;
+    /// use packet::connection::{Client, ClientEvent, NetEvent, Server};
+    /// use packet::lobby::LobbyEvent;
+    ///
+    /// let mut server = Server::listen("127.0.0.1:0").unwrap();
+    /// let server_addr = server.local_addr().unwrap();
+    /// let mut host = Client::connect(server_addr, Instant::now()).unwrap();
+    /// let mut guest = Client::connect(server_addr, Instant::now()).unwrap();
+    /// while host.session_id().is_none() || guest.session_id().is_none() {
+    ///     let now = Instant::now();
+    ///     server.poll(now).unwrap();
+    ///     host.poll(now).unwrap();
+    ///     guest.poll(now).unwrap();
+    ///     std::thread::sleep(Duration::from_millis(1));
+    /// }
+    ///
+    /// let (host_id, guest_id) = (host.session_id().unwrap(), guest.session_id().unwrap());
+    /// let room_id = server.lobby_mut().create_room(host_id, 4).unwrap();
+    /// server.lobby_mut().join(room_id, guest_id).unwrap();
+    ///
+    /// // O servidor repassa a mensagem da sala aos demais membros.
+    /// host.send(Packet::room_message(room_id, 1, host_id, b"gg".to_vec())).unwrap();
+    /// let mut events: Vec<_> = server.poll_events(Instant::now()).unwrap().collect();
+    /// let mut relayed = None;
+    /// while relayed.is_none() {
+    ///     std::thread::sleep(Duration::from_millis(1));
+    ///     events.extend(server.poll_events(Instant::now()).unwrap());
+    ///     relayed = guest.poll(Instant::now()).unwrap().into_iter().find_map(|event| match event {
+    ///         ClientEvent::Packet(packet) => packet.room_id(),
+    ///         _ => None,
+    ///     });
+    /// }
+    /// assert_eq!(relayed, Some(room_id));
+    /// assert!(events.contains(&NetEvent::Lobby(LobbyEvent::PlayerJoined {
+    ///     room_id,
+    ///     session_id: guest_id,
+    /// })));
+    /// ```
+    pub fn lobby_mut(&mut self) -> &mut Lobby {
+        &mut self.lobby
+    }
+
+    /// Retorna o RTT suavizado de uma sessão.
+    ///
+    /// ## Retorno
+    /// - `Option<Duration>`: O RTT, ou `None` se a sessão não existe ou nenhum pong
+    ///   chegou ainda.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use std::time::{Duration, Instant};
+    /// use packet::connection::{Client, Server};
+    ///
+    /// // Pings a cada 20 ms.
+    /// let mut server = Server::listen("127.0.0.1:0")
+    ///     .unwrap()
+    ///     .with_timeout(Duration::from_millis(80));
+    /// let mut client = Client::connect(server.local_addr().unwrap(), Instant::now()).unwrap();
+    ///
+    /// let mut rtt = None;
+    /// for _ in 0..400 {
+    ///     let now = Instant::now();
+    ///     server.poll(now).unwrap();
+    ///     client.poll(now).unwrap();
+    ///     if let Some(session_id) = client.session_id() {
+    ///         rtt = server.rtt(session_id);
+    ///         if rtt.is_some() {
+    ///             assert!(server.jitter(session_id).is_some());
+    ///             assert!(server.last_heard(session_id).unwrap() <= Instant::now());
+    ///             break;
+    ///         }
+    ///     }
+    ///     std::thread::sleep(Duration::from_millis(1));
+    /// }
+    /// assert!(rtt.unwrap() < Duration::from_secs(1));
+    /// assert_eq!(server.rtt(u64::MAX), None);
+    /// ```
+    pub fn rtt(&self, session_id: u64) -> Option<Duration> {
+        self.session(session_id)?.link.rtt.rtt()
+    }
+
+    /// Retorna o jitter estimado de uma sessão, nas mesmas condições de [`Server::rtt`].
+    pub fn jitter(&self, session_id: u64) -> Option<Duration> {
+        self.session(session_id)?.link.rtt.jitter()
+    }
+
+    /// Retorna o maior datagrama confirmado até o cliente de uma sessão, como em
+    /// [`Client::mtu`].
+    pub fn mtu(&self, session_id: u64) -> Option<usize> {
+        Some(self.session(session_id)?.link.mtu.mtu())
+    }
+
+    /// Retorna o instante do último pacote recebido de uma sessão.
+    pub fn last_heard(&self, session_id: u64) -> Option<Instant> {
+        Some(self.session(session_id)?.link.liveness.last_received())
+    }
+
+    /// Retorna o relógio do servidor, gravado nos pongs e estimado pelos clientes com
+    /// [`Client::server_time_now`].
+    ///
+    /// ## Parâmetros
+    /// - `now`: Instante atual.
+    ///
+    /// ## Retorno
+    /// - `Duration`: O tempo desde a criação do servidor.
+    pub fn server_time(&self, now: Instant) -> Duration {
+        now.saturating_duration_since(self.epoch)
+    }
+
+    /// Retorna o jogador autenticado pelo token de conexão de uma sessão.
+    ///
+    /// ## Retorno
+    /// - `Option<u64>`: O `player_id` do token, ou `None` se a sessão não existe ou o
+    ///   servidor não exige tokens.
+    pub fn player_id(&self, session_id: u64) -> Option<u64> {
+        self.session(session_id)?.player_id
+    }
+
+    /// Retorna as estatísticas de todo o servidor.
+    ///
+    /// O tráfego inclui os apertos de mão, os datagramas de endereços desconhecidos e as
+    /// sessões já encerradas; os pings somam os das sessões atuais, e o RTT e o jitter
+    /// são a média delas.
+    ///
+    /// ## Retorno
+    /// - `NetworkStats`: As estatísticas até o último [`Server::poll`].
+    pub fn stats(&self) -> NetworkStats {
+        let links = || self.sessions.values().map(|session| &session.link);
+        NetworkStats {
+            pings_answered: links().map(|link| link.pings_answered).sum(),
+            pings_lost: links().map(|link| link.pings_lost).sum(),
+            rtt: mean(links().filter_map(|link| link.rtt.rtt())),
+            jitter: mean(links().filter_map(|link| link.rtt.jitter())),
+            ..self.stats.snapshot()
+        }
+    }
+
+    /// Retorna as estatísticas de uma sessão, desde a sua criação.
+    ///
+    /// ## Retorno
+    /// - `Option<NetworkStats>`: As estatísticas até o último [`Server::poll`], ou
+    ///   `None` se a sessão não existe.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use std::sync::{Arc, Mutex};
+    /// use std::time::{Duration, Instant};
+    /// use packet::Packet;
+    /// use packet::connection::{Client, Server};
+    ///
+    /// let reports = Arc::new(Mutex::new(Vec::new()));
+    /// let log = Arc::clone(&reports);
+    /// let mut server = Server::listen("127.0.0.1:0")
+    ///     .unwrap()
+    ///     .with_stats_callback(Duration::from_millis(10), move |session_id, stats| {
+    ///         log.lock().unwrap().push((session_id, *stats));
+    ///     });
+    /// let mut client = Client::connect(server.local_addr().unwrap(), Instant::now()).unwrap();
+    ///
+    /// let mut sent = false;
+    /// for _ in 0..400 {
+    ///     let now = Instant::now();
+    ///     server.poll(now).unwrap();
+    ///     client.poll(now).unwrap();
+    ///     if let (Some(session_id), false) = (client.session_id(), sent) {
+    ///         // Acima do MTU inicial, o pacote é fragmentado.
+    ///         client.send(Packet::new(1, 1, session_id, vec![7; 3000])).unwrap();
+    ///         sent = true;
+    ///     }
+    ///     let session_id = client.session_id().unwrap_or(0);
+    ///     if server.session_stats(session_id).is_some_and(|stats| stats.fragments_received >= 3) {
+    ///         break;
+    ///     }
+    ///     std::thread::sleep(Duration::from_millis(1));
+    /// }
+    ///
+    /// let session_id = client.session_id().unwrap();
+    /// let stats = server.session_stats(session_id).unwrap();
+    /// assert!(stats.bytes_received > 3000);
+    /// assert_eq!(client.stats().packets_fragmented, 1);
+    /// assert!(server.stats().packets_received > stats.packets_received);
+    ///
+    /// // A entrega periódica inclui o servidor inteiro e a sessão.
+    /// std::thread::sleep(Duration::from_millis(10));
+    /// server.poll(Instant::now()).unwrap();
+    /// let reports = reports.lock().unwrap();
+    /// assert!(reports.iter().any(|(id, _)| id.is_none()));
+    /// assert!(reports.iter().any(|(id, _)| *id == Some(session_id)));
+    /// ```
+    pub fn session_stats(&self, session_id: u64) -> Option<NetworkStats> {
+        let session = self.session(session_id)?;
+        Some(session.link.stats(session.stats.snapshot()))
+    }
+
+    /// Processa os datagramas recebidos, envia pings e expira sessões ociosas.
+    ///
+    /// ## Parâmetros
+    /// - `now`: Instante atual.
+    ///
+    /// ## Retorno
+    /// - `io::Result<Vec<ServerEvent>>`: Os eventos ocorridos, na ordem, ou a falha de
+    ///   E/S do socket.
+    pub fn poll(&mut self, now: Instant) -> io::Result<Vec<ServerEvent>> {
+        self.poll_dropping(now, &mut Vec::new())
+    }
+
+    /// Conduz os temporizadores sem ler o transporte: expira as sessões caladas e
+    /// envia os pings, as sondas de MTU e as estatísticas devidas.
+    ///
+    /// [`Server::poll`] faz o mesmo depois de processar os datagramas recebidos;
+    /// `update` serve aos laços que separam a recepção do avanço do tempo, como
+    /// simulações em passo fixo.
+    ///
+    /// ## Parâmetros
+    /// - `now`: Instante atual.
+    ///
+    /// ## Retorno
+    /// - `io::Result<Vec<ServerEvent>>`: As sessões expiradas, como
+    ///   [`ServerEvent::TimedOut`], ou a falha de E/S do transporte.
+    pub fn update(&mut self, now: Instant) -> io::Result<Vec<ServerEvent>> {
+        let mut events = Vec::new();
+        self.advance(now, &mut events)?;
+        Ok(events)
+    }
+
+    /// Como [`Server::poll`], com os eventos no formato de [`NetEvent`].
+    ///
+    /// Os avisos sobre os datagramas descartados vêm antes dos demais eventos.
+    ///
+    /// ## Parâmetros
+    /// - `now`: Instante atual.
+    ///
+    /// ## Retorno
+    /// - `io::Result<impl Iterator<Item = NetEvent>>`: Os eventos ocorridos, ou a
+    ///   falha de E/S do socket.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use std::net::UdpSocket;
+    /// use std::time::{Duration, Instant};
+    /// use packet::Packet;
+    /// use packet::connection::{Client, NetEvent, Server};
+    ///
+    /// let mut server = Server::listen("127.0.0.1:0").unwrap();
+    /// let server_addr = server.local_addr().unwrap();
+    /// let mut client = Client::connect(server_addr, Instant::now()).unwrap();
+    ///
+    /// let mut events = Vec::new();
+    /// while client.session_id().is_none() {
+    ///     let now = Instant::now();
+    ///     events.extend(server.poll_events(now).unwrap());
+    ///     client.poll(now).unwrap();
+    ///     std::thread::sleep(Duration::from_millis(1));
+    /// }
+    /// let session_id = client.session_id().unwrap();
+    /// client.send(Packet::new(1, 1, session_id, vec![1]).with_channel_id(2)).unwrap();
+    ///
+    /// // Um datagrama com o checksum corrompido.
+    /// let mut corrupted = Packet::new(1, 2, session_id, vec![2]).to_bytes();
+    /// *corrupted.last_mut().unwrap() ^= 0xFF;
+    /// let intruder = UdpSocket::bind("127.0.0.1:0").unwrap();
+    /// intruder.send_to(&corrupted, server_addr).unwrap();
+    ///
+    /// while events.len() < 3 {
+    ///     events.extend(server.poll_events(Instant::now()).unwrap());
+    /// }
+    /// assert!(matches!(events[0], NetEvent::ClientConnected { session_id: id, .. } if id == session_id));
+    /// assert!(events.iter().any(|event| matches!(
+    ///     event,
+    ///     NetEvent::MessageReceived { channel: 2, packet, .. } if packet.payload == [1]
+    /// )));
+    /// assert!(events.iter().any(|event| matches!(
+    ///     event,
+    ///     NetEvent::ChecksumFailure { addr, .. } if *addr == intruder.local_addr().unwrap()
+    /// )));
+    /// ```
+    pub fn poll_events(&mut self, now: Instant) -> io::Result<impl Iterator<Item = NetEvent>> {
+        let mut dropped = Vec::new();
+        let events = self.poll_dropping(now, &mut dropped)?;
+        Ok(dropped
+            .into_iter()
+            .map(|(addr, error)| NetEvent::dropped(addr, error))
+            .chain(events.into_iter().map(NetEvent::from))
+            .chain(self.lobby.take_events().into_iter().map(NetEvent::Lobby)))
+    }
+
+    /// [`Server::poll`], guardando os datagramas descartados.
+    fn poll_dropping(
+        &mut self,
+        now: Instant,
+        dropped: &mut Vec<(SocketAddr, PacketError)>,
+    ) -> io::Result<Vec<ServerEvent>> {
+        let mut events = Vec::new();
+        while let Some((from, packet)) = receive(&mut self.transport, dropped)? {
+            if !self.admit(from, &packet, now) {
+                continue;
+            }
+            if let Some(packet) = self.inbound_hooks.run(&from, packet) {
+                self.handle(from, packet, now, &mut events)?;
+            }
+        }
+        self.advance(now, &mut events)?;
+        Ok(events)
+    }
+
+    /// Avança os temporizadores até `now`, acrescentando os eventos ocorridos.
+    fn advance(&mut self, now: Instant, events: &mut Vec<ServerEvent>) -> io::Result<()> {
+        self.last_tick = self.last_tick.max(now);
+        self.sessions.retain(|_, session| {
+            let alive = !session.link.liveness.is_dead(now);
+            if !alive {
+                events.push(ServerEvent::TimedOut {
+                    session_id: session.id,
+                });
+            }
+            alive
+        });
+        self.update_lobby(events)?;
+
+        for (addr, session) in &mut self.sessions {
+            let overhead = session.codec.control_overhead();
+            for packet in session
+                .link
+                .poll(now, session.id, overhead)
+                .into_iter()
+                .flatten()
+            {
+                let packet = session.codec.encode_control(packet);
+                let recorders = &mut [&mut self.stats, &mut session.stats];
+                let hooks = &mut self.outbound_hooks;
+                transmit(&mut self.transport, hooks, *addr, &packet, now, recorders)
+                    .map_err(io::Error::from)?;
+            }
+            session.stats.advance(now);
+        }
+        self.stats.advance(now);
+        self.report_stats(now);
+        Ok(())
+    }
+
+    /// Aplica a proteção contra inundação a um pacote recebido, contando os descartes.
+    fn admit(&mut self, from: SocketAddr, packet: &Packet, now: Instant) -> bool {
+        let Some(guard) = &mut self.flood_guard else {
+            return true;
+        };
+        let connected = self.sessions.contains_key(&from);
+        let verdict = guard.check(from, packet.serialized_len(), connected, now);
+        self.stats.record_rejected(verdict);
+        verdict == Verdict::Allowed
+    }
+
+    /// Envia um pacote a uma sessão, com o `player_id` da sessão e cifrado se a sessão é
+    /// cifrada.
+    ///
+    /// ## Parâmetros
+    /// - `session_id`: Sessão de destino.
+    /// - `packet`: Pacote a ser enviado.
+    ///
+    /// ## Retorno
+    /// - `Result<usize, PacketError>`: Quantidade de bytes enviados, ou
+    ///   [`PacketError::Io`] com [`io::ErrorKind::NotConnected`] se a sessão não existe.
+    pub fn send(&mut self, session_id: u64, packet: Packet) -> Result<usize, PacketError> {
+        let (addr, session) = self
+            .sessions
+            .iter_mut()
+            .find(|(_, session)| session.id == session_id)
+            .ok_or_else(|| io::Error::from(io::ErrorKind::NotConnected))?;
+        let packet = session.codec.encode(packet.with_player_id(session_id));
+        let mtu = session.link.mtu.mtu();
+        let recorders = &mut [&mut self.stats, &mut session.stats];
+        send_fragmented(
+            &mut self.transport,
+            &mut self.outbound_hooks,
+            *addr,
+            packet,
+            mtu,
+            self.last_tick,
+            recorders,
+        )
+    }
+
+    /// Envia um pacote aos membros de uma sala, com o `player_id` de cada destinatário,
+    /// como em [`Server::send`].
+    ///
+    /// ## Parâmetros
+    /// - `room_id`: Sala de destino.
+    /// - `packet`: Pacote a ser enviado, como um [`Packet::room_message`].
+    /// - `except`: Sessão que não recebe o pacote, como a do autor da mensagem.
+    ///
+    /// ## Retorno
+    /// - `Result<usize, PacketError>`: Quantidade de bytes enviados a todos os membros,
+    ///   ou [`PacketError::Io`] com [`io::ErrorKind::NotFound`] se a sala não existe.
+    pub fn broadcast_room(
+        &mut self,
+        room_id: u32,
+        packet: &Packet,
+        except: Option<u64>,
+    ) -> Result<usize, PacketError> {
+        let members = self
+            .lobby
+            .room(room_id)
+            .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))?
+            .members()
+            .to_vec();
+        let mut sent = 0;
+        for member in members {
+            if Some(member) != except && self.session_addr(member).is_some() {
+                sent += self.send(member, packet.clone())?;
+            }
+        }
+        Ok(sent)
+    }
+
+    /// Tira das salas as sessões encerradas e repassa as mensagens de sala dos membros.
+    fn update_lobby(&mut self, events: &[ServerEvent]) -> io::Result<()> {
+        for event in events {
+            match event {
+                ServerEvent::Disconnected { session_id, .. }
+                | ServerEvent::TimedOut { session_id } => {
+                    self.lobby.leave(*session_id);
+                }
+                ServerEvent::Packet { session_id, packet } => {
+                    let member = packet
+                        .room_id()
+                        .filter(|&room_id| self.lobby.room_of(*session_id) == Some(room_id));
+                    if let Some(room_id) = member {
+                        self.broadcast_room(room_id, packet, Some(*session_id))?;
+                    }
+                }
+                ServerEvent::Connected { .. } | ServerEvent::Rejected { .. } => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Encerra uma sessão, avisando o cliente.
+    ///
+    /// ## Parâmetros
+    /// - `session_id`: Sessão a encerrar.
+    /// - `reason`: Motivo informado ao cliente.
+    ///
+    /// ## Retorno
+    /// - `Result<bool, PacketError>`: `true` se a sessão existia, ou a falha de envio.
+    pub fn disconnect(
+        &mut self,
+        session_id: u64,
+        reason: DisconnectReason,
+    ) -> Result<bool, PacketError> {
+        let Some(addr) = self.session_addr(session_id) else {
+            return Ok(false);
+        };
+        let session = self
+            .sessions
+            .remove(&addr)
+            .expect("sessão encontrada acima");
+        self.lobby.leave(session_id);
+        let packet = session
+            .codec
+            .encode_control(Packet::disconnect(session_id, reason));
+        for _ in 0..DISCONNECT_REDUNDANCY {
+            transmit(
+                &mut self.transport,
+                &mut self.outbound_hooks,
+                addr,
+                &packet,
+                self.last_tick,
+                &mut [&mut self.stats],
+            )?;
+        }
+        Ok(true)
+    }
+
+    /// Remove um jogador, avisando o cliente com [`DisconnectReason::Kicked`].
+    ///
+    /// ## Parâmetros
+    /// - `session_id`: Sessão a encerrar.
+    /// - `code`: Código da remoção definido pela aplicação, entregue ao cliente.
+    ///
+    /// ## Retorno
+    /// - `Result<bool, PacketError>`: `true` se a sessão existia, ou a falha de envio.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use std::time::{Duration, Instant};
+    /// use packet::connection::{Client, ClientEvent, Server};
+    /// use packet::disconnect::DisconnectReason;
+    ///
+    /// const AFK: u8 = 1;
+    ///
+    /// let mut server = Server::listen("127.0.0.1:0").unwrap();
+    /// let mut client = Client::connect(server.local_addr().unwrap(), Instant::now()).unwrap();
+    /// while client.session_id().is_none() {
+    ///     let now = Instant::now();
+    ///     server.poll(now).unwrap();
+    ///     client.poll(now).unwrap();
+    ///     std::thread::sleep(Duration::from_millis(1));
+    /// }
+    ///
+    /// assert!(server.kick(client.session_id().unwrap(), AFK).unwrap());
+    /// std::thread::sleep(Duration::from_millis(10));
+    /// let events = client.poll(Instant::now()).unwrap();
+    /// assert!(events.contains(&ClientEvent::Disconnected {
+    ///     reason: Some(DisconnectReason::Kicked(AFK)),
+    /// }));
+    /// ```
+    pub fn kick(&mut self, session_id: u64, code: u8) -> Result<bool, PacketError> {
+        self.disconnect(session_id, DisconnectReason::Kicked(code))
+    }
+
+    /// Entrega as estatísticas à função registrada, se é hora.
+    fn report_stats(&mut self, now: Instant) {
+        if !self
+            .reporter
+            .as_mut()
+            .is_some_and(|reporter| reporter.is_due(now))
+        {
+            return;
+        }
+        let global = self.stats();
+        let sessions: Vec<_> = self
+            .sessions
+            .values()
+            .map(|session| (session.id, session.link.stats(session.stats.snapshot())))
+            .collect();
+        let reporter = self.reporter.as_mut().expect("entrega registrada");
+        reporter.report(None, &global);
+        for (session_id, stats) in &sessions {
+            reporter.report(Some(*session_id), stats);
+        }
+    }
+
+    /// Retorna uma sessão pelo identificador.
+    fn session(&self, session_id: u64) -> Option<&Session> {
+        self.sessions
+            .values()
+            .find(|session| session.id == session_id)
+    }
+
+    /// Retorna o endereço de uma sessão.
+    fn session_addr(&self, session_id: u64) -> Option<SocketAddr> {
+        self.sessions
+            .iter()
+            .find(|(_, session)| session.id == session_id)
+            .map(|(addr, _)| *addr)
+    }
+
+    /// Proteção dos pacotes das sessões.
+    fn security(&self) -> SecurityLevel {
+        #[cfg(feature = "crypto")]
+        {
+            self.security
+        }
+        #[cfg(not(feature = "crypto"))]
+        {
+            SecurityLevel::None
+        }
+    }
+
+    /// Negocia os parâmetros da sessão com os anunciados pelo cliente.
+    ///
+    /// Retorna a compressão e as chaves da sessão e os parâmetros a enviar na
+    /// aceitação, ou `None` se o cliente não corresponde à proteção do servidor.
+    fn agree(&self, client: Offer) -> Option<(Codec, Offer)> {
+        if client.security() != self.security() {
+            return None;
+        }
+        #[cfg(feature = "crypto")]
+        let (keys, public_key) = match client.public_key {
+            Some(client_key) => {
+                let exchange = KeyExchange::new();
+                let public_key = exchange.public_key();
+                (Some(exchange.server_keys(&client_key)), Some(public_key))
+            }
+            None => (None, None),
+        };
+        #[cfg(not(feature = "crypto"))]
+        let public_key = None;
+
+        let codec = Codec {
+            replay: ReplayWindow::default(),
+            #[cfg(feature = "crypto")]
+            keys,
+            #[cfg(feature = "crypto")]
+            signed: client.signed,
+            #[cfg(feature = "compression")]
+            compressor: self
+                .compressor
+                .filter(|compressor| client.compression & compressor.algorithm().id() != 0),
+        };
+        let offer = Offer {
+            connect_token: None,
+            public_key,
+            signed: client.signed,
+            compression: codec.compression_id(),
+        };
+        Some((codec, offer))
+    }
+
+    /// Verifica o token de conexão anunciado pelo cliente, se o servidor exige tokens.
+    ///
+    /// A validade do token é a do instante `now` do tick, não a hora do sistema.
+    /// Retorna o jogador autenticado, `None` se os tokens não são exigidos, ou o motivo
+    /// da recusa.
+    fn authorize(&self, client: &Offer, now: Instant) -> Result<Option<u64>, PacketError> {
+        #[cfg(feature = "crypto")]
+        if let Some((verifying_key, server_addr)) = &self.connect_tokens {
+            let connect_token = client.connect_token.ok_or(PacketError::InvalidToken {
+                reason: "token ausente",
+            })?;
+            let token = ConnectToken::verify(
+                &connect_token,
+                verifying_key,
+                self.wall_epoch + now.saturating_duration_since(self.epoch),
+                *server_addr,
+            )?;
+            return Ok(Some(token.player_id));
+        }
+        #[cfg(not(feature = "crypto"))]
+        let _ = (client, now);
+        Ok(None)
+    }
+
+    /// Desafio esperado de um cliente.
+    fn token(&self, from: SocketAddr, salt: u64) -> u64 {
+        self.secret.hash_one((from, salt))
+    }
+
+    /// Trata um pacote recebido.
+    fn handle(
+        &mut self,
+        from: SocketAddr,
+        packet: Packet,
+        now: Instant,
+        events: &mut Vec<ServerEvent>,
+    ) -> io::Result<()> {
+        self.stats.record_received(&packet, now);
+        if let Some(session) = self.sessions.get_mut(&from) {
+            session.stats.record_received(&packet, now);
+        }
+        if packet.header.message_type == Handshake::MESSAGE_TYPE {
+            let Ok(handshake) = packet.decode_typed::<Handshake>() else {
+                return Ok(());
+            };
+            return self.handshake(from, handshake, now, events);
+        }
+        if packet.header.message_type == ServerStatus::MESSAGE_TYPE {
+            let Some(query) = &mut self.query else {
+                return Ok(());
+            };
+            query.set_players(self.sessions.len() as u32);
+            if let Some(reply) = query.respond(from, &packet, now) {
+                let hooks = &mut self.outbound_hooks;
+                transmit(
+                    &mut self.transport,
+                    hooks,
+                    from,
+                    &reply,
+                    now,
+                    &mut [&mut self.stats],
+                )
+                .map_err(io::Error::from)?;
+            }
+            return Ok(());
+        }
+
+        let Some(session) = self.sessions.get_mut(&from) else {
+            return Ok(());
+        };
+        if packet.header.player_id != session.id {
+            return Ok(());
+        }
+        let packet = match packet.is_fragment() {
+            true => session.link.reassemble(packet, now),
+            false => Some(packet),
+        };
+        let Some(packet) = packet.and_then(|packet| session.codec.open(packet)) else {
+            return Ok(());
+        };
+        if let Some(pong) = session.link.record(&packet, now) {
+            let pong = session.codec.encode_control(pong);
+            let recorders = &mut [&mut self.stats, &mut session.stats];
+            let hooks = &mut self.outbound_hooks;
+            transmit(&mut self.transport, hooks, from, &pong, now, recorders)
+                .map_err(io::Error::from)?;
+            return Ok(());
+        }
+
+        match packet.message_type() {
+            Ok(MessageType::Disconnect) => {
+                let session_id = session.id;
+                self.sessions.remove(&from);
+                events.push(ServerEvent::Disconnected {
+                    session_id,
+                    reason: packet.decode_disconnect().ok(),
+                });
+            }
+            Ok(MessageType::Ping | MessageType::Pong) => {}
+            _ => {
+                if let Some(packet) = session.codec.decode(packet) {
+                    events.push(ServerEvent::Packet {
+                        session_id: session.id,
+                        packet,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Trata uma mensagem do aperto de mão.
+    fn handshake(
+        &mut self,
+        from: SocketAddr,
+        handshake: Handshake,
+        now: Instant,
+        events: &mut Vec<ServerEvent>,
+    ) -> io::Result<()> {
+        let reply = match handshake {
+            Handshake::Request { salt, .. } | Handshake::Response { salt, .. }
+                if self.sessions.get(&from).is_some_and(|s| s.salt == salt) =>
+            {
+                // Retransmissão de um cliente já aceito.
+                let session = self.sessions.get_mut(&from).expect("sessão encontrada");
+                session.stats.record_retransmission();
+                self.stats.record_retransmission();
+                let accepted = Handshake::Accepted {
+                    salt,
+                    session_id: session.id,
+                    offer: session.offer,
+                };
+                Packet::from_payload(0, session.id, &accepted)
+            }
+            Handshake::Request { salt, version, .. } if !self.version.is_compatible(version) => {
+                events.push(ServerEvent::Rejected {
+                    addr: from,
+                    error: PacketError::IncompatibleVersion {
+                        local: self.version,
+                        remote: version,
+                    },
+                });
+                let version = self.version;
+                Packet::from_payload(0, 0, &Handshake::Rejected { salt, version })
+            }
+            Handshake::Request { salt, offer, .. } if offer.security() == self.security() => {
+                if let Err(error) = self.authorize(&offer, now) {
+                    events.push(ServerEvent::Rejected { addr: from, error });
+                    return Ok(());
+                }
+                let token = self.token(from, salt);
+                Packet::from_payload(0, 0, &Handshake::Challenge { salt, token })
+            }
+            Handshake::Response {
+                salt,
+                token,
+                version,
+                offer,
+            } if token == self.token(from, salt) && self.version.is_compatible(version) => {
+                let player_id = match self.authorize(&offer, now) {
+                    Ok(player_id) => player_id,
+                    Err(error) => {
+                        events.push(ServerEvent::Rejected { addr: from, error });
+                        return Ok(());
+                    }
+                };
+                let Some((codec, offer)) = self.agree(offer) else {
+                    return Ok(());
+                };
+                let session_id = self.next_session_id;
+                self.next_session_id += self.session_id_step;
+                self.sessions.insert(
+                    from,
+                    Session {
+                        id: session_id,
+                        salt,
+                        offer,
+                        player_id,
+                        codec,
+                        link: Link::new(self.timeout, now, self.epoch),
+                        stats: StatsRecorder::new(),
+                    },
+                );
+                events.push(ServerEvent::Connected {
+                    session_id,
+                    addr: from,
+                });
+                let accepted = Handshake::Accepted {
+                    salt,
+                    session_id,
+                    offer,
+                };
+                Packet::from_payload(0, session_id, &accepted)
+            }
+            _ => return Ok(()),
+        };
+        let mut recorders = vec![&mut self.stats];
+        recorders.extend(
+            self.sessions
+                .get_mut(&from)
+                .map(|session| &mut session.stats),
+        );
+        let hooks = &mut self.outbound_hooks;
+        transmit(
+            &mut self.transport,
+            hooks,
+            from,
+            &reply,
+            now,
+            &mut recorders,
+        )
+        .map(drop)
+        .map_err(io::Error::from)
+    }
+}
+
+/// Cliente que se conecta a um [`Server`].
+///
+/// O transporte padrão é o [`UdpTransport`]; [`Client::from_transport`] aceita
+/// qualquer [`Transport`].
+#[derive(Debug)]
+pub struct Client<T = UdpTransport> {
+    /// Transporte em modo não bloqueante.
+    transport: T,
+    /// Endereço do servidor.
+    server: SocketAddr,
+    /// Estado da conexão.
+    state: ConnectionState,
+    /// Valor aleatório que identifica esta tentativa de conexão.
+    salt: u64,
+    /// Desafio recebido do servidor.
+    token: Option<u64>,
+    /// Sessão atribuída pelo servidor.
+    session_id: Option<u64>,
+    /// Versão do protocolo anunciada ao servidor.
+    version: ProtocolVersion,
+    /// Token de conexão anunciado ao servidor.
+    connect_token: Option<[u8; CONNECT_TOKEN_SIZE]>,
+    /// Instante do início do aperto de mão.
+    started_at: Instant,
+    /// Instante do último envio de uma mensagem do aperto de mão.
+    last_handshake: Option<Instant>,
+    /// Instante do último `poll` ou `update`, usado pelos envios fora deles.
+    last_tick: Instant,
+    /// Pings, RTT e liveness do servidor, reiniciados na aceitação.
+    link: Link,
+    /// Motivo e cópias restantes do pacote de desconexão.
+    disconnecting: Option<(DisconnectReason, u32)>,
+    /// Proteção dos pacotes anunciada ao servidor.
+    #[cfg(feature = "crypto")]
+    security: SecurityLevel,
+    /// Troca de chaves em andamento, nas conexões assinadas ou cifradas.
+    #[cfg(feature = "crypto")]
+    key_exchange: Option<KeyExchange>,
+    /// Tamanho mínimo do corpo comprimido, se a compressão é anunciada ao servidor.
+    #[cfg(feature = "compression")]
+    compression: Option<usize>,
+    /// Compressão e cifragem dos pacotes da aplicação.
+    codec: Codec,
+    /// Tráfego da conexão, incluindo o aperto de mão.
+    stats: StatsRecorder,
+    /// Entrega periódica das estatísticas.
+    reporter: Option<StatsReporter>,
+    /// Ganchos dos pacotes recebidos.
+    inbound_hooks: HookPipeline,
+    /// Ganchos dos pacotes enviados.
+    outbound_hooks: HookPipeline,
+}
+
+impl Client {
+    /// Inicia a conexão com um servidor.
+    ///
+    /// O pedido de conexão é enviado no primeiro [`Client::poll`].
+    ///
+    /// ## Parâmetros
+    /// - `server`: Endereço do servidor.
+    /// - `now`: Instante atual.
+    ///
+    /// ## Retorno
+    /// - `io::Result<Self>`: O cliente em [`ConnectionState::Connecting`], associado a
+    ///   uma porta local qualquer, ou o erro retornado pelo sistema.
+    pub fn connect(server: SocketAddr, now: Instant) -> io::Result<Self> {
+        let local: SocketAddr = match server {
+            SocketAddr::V4(_) => (Ipv4Addr::UNSPECIFIED, 0).into(),
+            SocketAddr::V6(_) => (Ipv6Addr::UNSPECIFIED, 0).into(),
+        };
+        Self::from_transport(UdpTransport::bind(local)?, server, now)
+    }
+
+    /// Inicia uma conexão cifrada com um servidor criado com
+    /// [`Server::with_encryption`].
+    ///
+    /// Disponível com o recurso `crypto`. A conexão é como a de [`Client::connect`],
+    /// com uma troca de chaves no aperto de mão; um servidor sem cifragem não a aceita,
+    /// e ela termina em [`ConnectionState::TimedOut`].
+    ///
+    /// ## Parâmetros
+    /// - `server`: Endereço do servidor.
+    /// - `now`: Instante atual.
+    ///
+    /// ## Retorno
+    /// - `io::Result<Self>`: O cliente em [`ConnectionState::Connecting`], ou o erro
+    ///   retornado pelo sistema.
+    #[cfg(feature = "crypto")]
+    pub fn connect_encrypted(server: SocketAddr, now: Instant) -> io::Result<Self> {
+        Ok(Self::connect(server, now)?.with_security(SecurityLevel::Encrypted))
+    }
+}
+
+impl<T: Transport> Client<T> {
+    /// Inicia a conexão com um servidor sobre um transporte qualquer.
+    ///
+    /// O pedido de conexão é enviado no primeiro [`Client::poll`].
+    ///
+    /// ## Parâmetros
+    /// - `transport`: Transporte do cliente; é colocado em modo não bloqueante.
+    /// - `server`: Endereço do servidor.
+    /// - `now`: Instante atual.
+    ///
+    /// ## Retorno
+    /// - `io::Result<Self>`: O cliente em [`ConnectionState::Connecting`], ou o erro
+    ///   de [`Transport::set_nonblocking`].
+    pub fn from_transport(mut transport: T, server: SocketAddr, now: Instant) -> io::Result<Self> {
+        transport.set_nonblocking(true)?;
+        Ok(Self {
+            transport,
+            server,
+            state: ConnectionState::Connecting,
+            salt: RandomState::new().hash_one(now),
+            token: None,
+            session_id: None,
+            version: ProtocolVersion::default(),
+            connect_token: None,
+            started_at: now,
+            last_handshake: None,
+            last_tick: now,
+            link: Link::new(DEFAULT_CONNECTION_TIMEOUT, now, now),
+            disconnecting: None,
+            #[cfg(feature = "crypto")]
+            security: SecurityLevel::None,
+            #[cfg(feature = "crypto")]
+            key_exchange: None,
+            #[cfg(feature = "compression")]
+            compression: None,
+            codec: Codec::default(),
+            stats: StatsRecorder::new(),
+            reporter: None,
+            inbound_hooks: HookPipeline::new(),
+            outbound_hooks: HookPipeline::new(),
+        })
+    }
+
+    /// Retorna o cliente com a proteção dos pacotes definida, com uma troca de chaves
+    /// no aperto de mão se não for [`SecurityLevel::None`].
+    ///
+    /// Disponível com o recurso `crypto`. Só é aceito por um servidor com o mesmo nível,
+    /// definido com [`Server::with_security`]; caso contrário, a conexão termina em
+    /// [`ConnectionState::TimedOut`]. Deve ser chamado antes do primeiro
+    /// [`Client::poll`].
+    ///
+    /// ## Parâmetros
+    /// - `security`: Proteção da conexão.
+    ///
+    /// ## Retorno
+    /// - `Self`: O cliente com a nova proteção.
+    #[cfg(feature = "crypto")]
+    pub fn with_security(mut self, security: SecurityLevel) -> Self {
+        self.security = security;
+        self.key_exchange = (security != SecurityLevel::None).then(KeyExchange::new);
+        self
+    }
+
+    /// Retorna o cliente anunciando ao servidor os algoritmos de compressão
+    /// disponíveis.
+    ///
+    /// Disponível com o recurso `compression`. Se o servidor foi criado com
+    /// [`Server::with_compression`] e aceita um dos algoritmos, os pacotes da aplicação
+    /// passam a ser comprimidos nos dois sentidos; caso contrário, seguem sem
+    /// compressão. Deve ser chamado antes do primeiro [`Client::poll`].
+    ///
+    /// ## Parâmetros
+    /// - `min_size`: Tamanho mínimo, em bytes, do corpo dos pacotes comprimidos.
+    ///
+    /// ## Retorno
+    /// - `Self`: O cliente com a compressão anunciada.
+    #[cfg(feature = "compression")]
+    pub fn with_compression(mut self, min_size: usize) -> Self {
+        self.compression = Some(min_size);
+        self
+    }
+
+    /// Define a versão do protocolo da aplicação anunciada ao servidor.
+    ///
+    /// Se o servidor a considera incompatível, a conexão termina com um
+    /// [`ClientEvent::Rejected`] em vez de expirar. Deve ser chamado antes do primeiro
+    /// [`Client::poll`].
+    ///
+    /// ## Parâmetros
+    /// - `version`: Versão do cliente; o padrão é `0.0`.
+    ///
+    /// ## Retorno
+    /// - `Self`: O cliente com a nova versão.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use std::time::{Duration, Instant};
+    /// use packet::PacketError;
+    /// use packet::connection::{Client, ClientEvent, ConnectionState, ProtocolVersion, Server};
+    ///
+    /// let mut server = Server::listen("127.0.0.1:0")
+    ///     .unwrap()
+    ///     .with_protocol_version(ProtocolVersion::new(2, 0));
+    /// let mut client = Client::connect(server.local_addr().unwrap(), Instant::now())
+    ///     .unwrap()
+    ///     .with_protocol_version(ProtocolVersion::new(1, 4));
+    ///
+    /// let mut rejection = None;
+    /// for _ in 0..200 {
+    ///     let now = Instant::now();
+    ///     server.poll(now).unwrap();
+    ///     for event in client.poll(now).unwrap() {
+    ///         if let ClientEvent::Rejected { error } = event {
+    ///             rejection = Some(error);
+    ///         }
+    ///     }
+    ///     if rejection.is_some() {
+    ///         break;
+    ///     }
+    ///     std::thread::sleep(Duration::from_millis(5));
+    /// }
+    /// assert_eq!(client.state(), ConnectionState::Disconnected);
+    /// assert_eq!(
+    ///     rejection,
+    ///     Some(PacketError::IncompatibleVersion {
+    ///         local: ProtocolVersion::new(1, 4),
+    ///         remote: ProtocolVersion::new(2, 0),
+    ///     })
+    /// );
+    /// ```
+    pub fn with_protocol_version(mut self, version: ProtocolVersion) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Define o token de conexão emitido pelo serviço de autenticação, enviado no
+    /// pedido e na resposta do aperto de mão.
+    ///
+    /// Exigido por servidores criados com [`Server::with_connect_tokens`]; o cliente
+    /// não interpreta os bytes. Deve ser chamado antes do primeiro [`Client::poll`].
+    ///
+    /// ## Parâmetros
+    /// - `connect_token`: Token recebido do serviço de autenticação.
+    ///
+    /// ## Retorno
+    /// - `Self`: O cliente com o token.
+    pub fn with_connect_token(mut self, connect_token: [u8; CONNECT_TOKEN_SIZE]) -> Self {
+        self.connect_token = Some(connect_token);
+        self
+    }
+
+    /// Registra uma função chamada periodicamente por [`Client::poll`] com as
+    /// estatísticas de [`Client::stats`], para alimentar um gráfico de rede.
+    ///
+    /// ## Parâmetros
+    /// - `interval`: Intervalo mínimo entre as entregas; a primeira acontece no
+    ///   primeiro `poll`.
+    /// - `callback`: Recebe as estatísticas da conexão.
+    ///
+    /// ## Retorno
+    /// - `Self`: O cliente com a entrega registrada.
+    pub fn with_stats_callback(
+        mut self,
+        interval: Duration,
+        mut callback: impl FnMut(&NetworkStats) + Send + 'static,
+    ) -> Self {
+        let callback = move |_: Option<u64>, stats: &NetworkStats| callback(stats);
+        self.reporter = Some(StatsReporter::new(interval, callback));
+        self
+    }
+
+    /// Retorna o estado da conexão.
+    pub fn state(&self) -> ConnectionState {
+        self.state
+    }
+
+    /// Retorna a sessão atribuída pelo servidor, após a conexão.
+    pub fn session_id(&self) -> Option<u64> {
+        self.session_id
+    }
+
+    /// Retorna o RTT suavizado até o servidor.
+    ///
+    /// ## Retorno
+    /// - `Option<Duration>`: O RTT, ou `None` enquanto nenhum pong chegou.
+    pub fn rtt(&self) -> Option<Duration> {
+        self.link.rtt.rtt()
+    }
+
+    /// Retorna o jitter estimado até o servidor, nas mesmas condições de [`Client::rtt`].
+    pub fn jitter(&self) -> Option<Duration> {
+        self.link.rtt.jitter()
+    }
+
+    /// Estima o relógio do servidor, como lido por [`Server::server_time`].
+    ///
+    /// A estimativa vem dos pongs do servidor, por [`TimeSync`]; logo após a conexão, o
+    /// cliente envia uma rajada de pings para sincronizar depressa, repetida
+    /// periodicamente.
+    ///
+    /// ## Parâmetros
+    /// - `now`: Instante atual.
+    ///
+    /// ## Retorno
+    /// - `Option<Duration>`: O relógio do servidor estimado, ou `None` enquanto nenhum
+    ///   pong com o relógio chegou.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use std::time::{Duration, Instant};
+    /// use packet::connection::{Client, Server};
+    ///
+    /// let mut server = Server::listen("127.0.0.1:0").unwrap();
+    /// let mut client = Client::connect(server.local_addr().unwrap(), Instant::now()).unwrap();
+    /// while !client.time_sync().is_synchronized() {
+    ///     let now = Instant::now();
+    ///     server.poll(now).unwrap();
+    ///     client.poll(now).unwrap();
+    ///     std::thread::sleep(Duration::from_millis(5));
+    /// }
+    /// let now = Instant::now();
+    /// let error = client.server_time_now(now).unwrap().abs_diff(server.server_time(now));
+    /// assert!(error < Duration::from_millis(50));
+    /// ```
+    pub fn server_time_now(&self, now: Instant) -> Option<Duration> {
+        self.link.sync.remote_time(now)
+    }
+
+    /// Retorna a sincronização com o relógio do servidor.
+    pub fn time_sync(&self) -> &TimeSync {
+        &self.link.sync
+    }
+
+    /// Acrescenta um gancho ao fim dos ganchos de recepção, como em
+    /// [`Server::add_inbound_hook`]; só os pacotes vindos do servidor passam por eles.
+    ///
+    /// ## Parâmetros
+    /// - `hook`: Função chamada com a origem e a visão de cada pacote recebido.
+    pub fn add_inbound_hook(
+        &mut self,
+        hook: impl FnMut(&SocketAddr, &PacketView<'_>) -> HookDecision + Send + 'static,
+    ) {
+        self.inbound_hooks.add(hook);
+    }
+
+    /// Acrescenta um gancho ao fim dos ganchos de envio, como em
+    /// [`Server::add_outbound_hook`]; um pacote descartado não é enviado, e
+    /// [`Client::send`] retorna `0` bytes.
+    ///
+    /// ## Parâmetros
+    /// - `hook`: Função chamada com o destino e a visão de cada pacote a enviar.
+    pub fn add_outbound_hook(
+        &mut self,
+        hook: impl FnMut(&SocketAddr, &PacketView<'_>) -> HookDecision + Send + 'static,
+    ) {
+        self.outbound_hooks.add(hook);
+    }
+
+    /// Retorna o maior datagrama confirmado até o servidor.
+    ///
+    /// Começa em [`DEFAULT_MTU`](crate::DEFAULT_MTU) e cresce até
+    /// [`MAX_PROBE_MTU`](crate::mtu::MAX_PROBE_MTU) conforme as sondas enviadas após a
+    /// conexão são respondidas. Pacotes maiores são fragmentados por [`Client::send`]
+    /// e remontados pelo servidor.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use std::time::{Duration, Instant};
+    /// use packet::{Packet, DEFAULT_MTU};
+    /// use packet::connection::{Client, ConnectionState, Server, ServerEvent};
+    /// use packet::mtu::MAX_PROBE_MTU;
+    ///
+    /// let mut server = Server::listen("127.0.0.1:0").unwrap();
+    /// let mut client = Client::connect(server.local_addr().unwrap(), Instant::now()).unwrap();
+    /// assert_eq!(client.mtu(), DEFAULT_MTU);
+    ///
+    /// for _ in 0..400 {
+    ///     let now = Instant::now();
+    ///     server.poll(now).unwrap();
+    ///     client.poll(now).unwrap();
+    ///     if client.mtu() == MAX_PROBE_MTU {
+    ///         break;
+    ///     }
+    ///     std::thread::sleep(Duration::from_millis(5));
+    /// }
+    /// // A interface local aceita datagramas do tamanho máximo sondado.
+    /// assert_eq!(client.mtu(), MAX_PROBE_MTU);
+    ///
+    /// // Um pacote maior que o MTU chega remontado.
+    /// let session_id = client.session_id().unwrap();
+    /// client.send(Packet::new(1, 1, session_id, vec![7; 4000])).unwrap();
+    /// let mut received = None;
+    /// while received.is_none() {
+    ///     for event in server.poll(Instant::now()).unwrap() {
+    ///         if let ServerEvent::Packet { packet, .. } = event {
+    ///             received = Some(packet);
+    ///         }
+    ///     }
+    /// }
+    /// assert_eq!(received.unwrap().payload, [7; 4000]);
+    /// ```
+    pub fn mtu(&self) -> usize {
+        self.link.mtu.mtu()
+    }
+
+    /// Retorna o instante do último pacote recebido do servidor, após a conexão.
+    pub fn last_heard(&self) -> Option<Instant> {
+        self.session_id.map(|_| self.link.liveness.last_received())
+    }
+
+    /// Retorna as estatísticas da conexão, desde o início do aperto de mão.
+    ///
+    /// ## Retorno
+    /// - `NetworkStats`: As estatísticas até o último [`Client::poll`]; o RTT, o jitter
+    ///   e os pings valem a partir da conexão.
+    pub fn stats(&self) -> NetworkStats {
+        self.link.stats(self.stats.snapshot())
+    }
+
+    /// Processa os datagramas recebidos e conduz o aperto de mão, os pings e a
+    /// desconexão.
+    ///
+    /// ## Parâmetros
+    /// - `now`: Instante atual.
+    ///
+    /// ## Retorno
+    /// - `io::Result<Vec<ClientEvent>>`: Os eventos ocorridos, na ordem, ou a falha de
+    ///   E/S do socket.
+    pub fn poll(&mut self, now: Instant) -> io::Result<Vec<ClientEvent>> {
+        self.poll_dropping(now, &mut Vec::new())
+    }
+
+    /// Conduz os temporizadores sem ler o transporte: retransmite o aperto de mão,
+    /// envia os pings e a desconexão e detecta o silêncio do servidor.
+    ///
+    /// [`Client::poll`] faz o mesmo depois de processar os datagramas recebidos.
+    ///
+    /// ## Parâmetros
+    /// - `now`: Instante atual.
+    ///
+    /// ## Retorno
+    /// - `io::Result<Vec<ClientEvent>>`: Os eventos ocorridos, como
+    ///   [`ClientEvent::TimedOut`], ou a falha de E/S do transporte.
+    pub fn update(&mut self, now: Instant) -> io::Result<Vec<ClientEvent>> {
+        let mut events = Vec::new();
+        self.advance(now, &mut events)?;
+        Ok(events)
+    }
+
+    /// Como [`Client::poll`], com os eventos no formato de [`NetEvent`].
+    ///
+    /// Os avisos sobre os datagramas do servidor descartados vêm antes dos demais
+    /// eventos.
+    ///
+    /// ## Parâmetros
+    /// - `now`: Instante atual.
+    ///
+    /// ## Retorno
+    /// - `io::Result<impl Iterator<Item = NetEvent>>`: Os eventos ocorridos, ou a
+    ///   falha de E/S do socket.
+    pub fn poll_events(&mut self, now: Instant) -> io::Result<impl Iterator<Item = NetEvent>> {
+        let mut dropped = Vec::new();
+        let events = self.poll_dropping(now, &mut dropped)?;
+        let server = self.server;
+        dropped.retain(|&(from, _)| from == server);
+        let session_id = self.session_id;
+        Ok(dropped
+            .into_iter()
+            .map(|(addr, error)| NetEvent::dropped(addr, error))
+            .chain(
+                events
+                    .into_iter()
+                    .map(move |event| match (event, session_id) {
+                        (ClientEvent::Connected { session_id }, _) => NetEvent::ClientConnected {
+                            session_id,
+                            addr: server,
+                        },
+                        (ClientEvent::Disconnected { reason }, Some(session_id)) => {
+                            NetEvent::ClientDisconnected { session_id, reason }
+                        }
+                        (ClientEvent::TimedOut, Some(session_id)) => NetEvent::ClientDisconnected {
+                            session_id,
+                            reason: Some(DisconnectReason::Timeout),
+                        },
+                        (ClientEvent::Rejected { error }, _) => NetEvent::ConnectionFailed {
+                            addr: server,
+                            error,
+                        },
+                        (ClientEvent::Packet(packet), Some(session_id)) => {
+                            NetEvent::message(session_id, packet)
+                        }
+                        // Sem sessão, só o aperto de mão pode ter expirado.
+                        (_, None) => NetEvent::ConnectionFailed {
+                            addr: server,
+                            error: io::Error::from(io::ErrorKind::TimedOut).into(),
+                        },
+                    }),
+            ))
+    }
+
+    /// [`Client::poll`], guardando os datagramas descartados.
+    fn poll_dropping(
+        &mut self,
+        now: Instant,
+        dropped: &mut Vec<(SocketAddr, PacketError)>,
+    ) -> io::Result<Vec<ClientEvent>> {
+        let mut events = Vec::new();
+        while let Some((from, packet)) = receive(&mut self.transport, dropped)? {
+            if from != self.server {
+                continue;
+            }
+            if let Some(packet) = self.inbound_hooks.run(&from, packet) {
+                self.stats.record_received(&packet, now);
+                self.handle(packet, now, &mut events)?;
+            }
+        }
+        self.advance(now, &mut events)?;
+        Ok(events)
+    }
+
+    /// Avança os temporizadores até `now`, acrescentando os eventos ocorridos.
+    fn advance(&mut self, now: Instant, events: &mut Vec<ClientEvent>) -> io::Result<()> {
+        self.last_tick = self.last_tick.max(now);
+        match self.state {
+            ConnectionState::Connecting => self.drive_handshake(now, events)?,
+            ConnectionState::Connected if self.link.liveness.is_dead(now) => {
+                self.state = ConnectionState::TimedOut;
+                events.push(ClientEvent::TimedOut);
+            }
+            ConnectionState::Connected => {
+                let session_id = self.session_id.expect("sessão atribuída na conexão");
+                let overhead = self.codec.control_overhead();
+                for packet in self
+                    .link
+                    .poll(now, session_id, overhead)
+                    .into_iter()
+                    .flatten()
+                {
+                    let packet = self.codec.encode_control(packet);
+                    self.send_raw(&packet, now)?;
+                }
+            }
+            ConnectionState::Disconnecting => {
+                let (reason, remaining) = self.disconnecting.expect("motivo registrado");
+                let session_id = self.session_id.expect("sessão atribuída na conexão");
+                let packet = self
+                    .codec
+                    .encode_control(Packet::disconnect(session_id, reason));
+                self.send_raw(&packet, now)?;
+                self.disconnecting = Some((reason, remaining - 1));
+                if remaining == 1 {
+                    self.state = ConnectionState::Disconnected;
+                }
+            }
+            ConnectionState::Disconnected | ConnectionState::TimedOut => {}
+        }
+
+        self.stats.advance(now);
+        if self
+            .reporter
+            .as_mut()
+            .is_some_and(|reporter| reporter.is_due(now))
+        {
+            let stats = self.stats();
+            let reporter = self.reporter.as_mut().expect("entrega registrada");
+            reporter.report(None, &stats);
+        }
+        Ok(())
+    }
+
+    /// Envia um pacote ao servidor, com o `player_id` da sessão e comprimido e cifrado
+    /// conforme negociado.
+    ///
+    /// ## Parâmetros
+    /// - `packet`: Pacote a ser enviado.
+    ///
+    /// ## Retorno
+    /// - `Result<usize, PacketError>`: Quantidade de bytes enviados, ou
+    ///   [`PacketError::Io`] com [`io::ErrorKind::NotConnected`] fora de
+    ///   [`ConnectionState::Connected`].
+    pub fn send(&mut self, packet: Packet) -> Result<usize, PacketError> {
+        let session_id = self
+            .session_id
+            .filter(|_| self.state == ConnectionState::Connected)
+            .ok_or_else(|| io::Error::from(io::ErrorKind::NotConnected))?;
+        let packet = self.codec.encode(packet.with_player_id(session_id));
+        let (server, mtu) = (self.server, self.link.mtu.mtu());
+        let recorders = &mut [&mut self.stats];
+        send_fragmented(
+            &mut self.transport,
+            &mut self.outbound_hooks,
+            server,
+            packet,
+            mtu,
+            self.last_tick,
+            recorders,
+        )
+    }
+
+    /// Inicia o encerramento da conexão.
+    ///
+    /// O pacote de desconexão é enviado [`DISCONNECT_REDUNDANCY`] vezes, uma por
+    /// [`Client::poll`], para tolerar perdas; depois o estado passa a
+    /// [`ConnectionState::Disconnected`]. Fora de [`ConnectionState::Connected`], a
+    /// conexão é apenas abandonada.
+    ///
+    /// ## Parâmetros
+    /// - `reason`: Motivo informado ao servidor.
+    pub fn disconnect(&mut self, reason: DisconnectReason) {
+        if self.state == ConnectionState::Connected {
+            self.state = ConnectionState::Disconnecting;
+            self.disconnecting = Some((reason, DISCONNECT_REDUNDANCY));
+        } else if self.state == ConnectionState::Connecting {
+            self.state = ConnectionState::Disconnected;
+        }
+    }
+
+    /// Trata um pacote recebido do servidor.
+    fn handle(
+        &mut self,
+        packet: Packet,
+        now: Instant,
+        events: &mut Vec<ClientEvent>,
+    ) -> io::Result<()> {
+        if self.state == ConnectionState::Connecting {
+            match packet.decode_typed::<Handshake>() {
+                Ok(Handshake::Challenge { salt, token }) if salt == self.salt => {
+                    self.token = Some(token);
+                    self.last_handshake = None;
+                    self.drive_handshake(now, events)?;
+                }
+                Ok(Handshake::Accepted {
+                    salt,
+                    session_id,
+                    offer,
+                }) if salt == self.salt && self.agree(offer) => {
+                    self.state = ConnectionState::Connected;
+                    self.session_id = Some(session_id);
+                    self.link = Link::new(DEFAULT_CONNECTION_TIMEOUT, now, self.started_at);
+                    events.push(ClientEvent::Connected { session_id });
+                }
+                Ok(Handshake::Rejected { salt, version }) if salt == self.salt => {
+                    self.state = ConnectionState::Disconnected;
+                    events.push(ClientEvent::Rejected {
+                        error: PacketError::IncompatibleVersion {
+                            local: self.version,
+                            remote: version,
+                        },
+                    });
+                }
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        if self.state != ConnectionState::Connected
+            || Some(packet.header.player_id) != self.session_id
+        {
+            return Ok(());
+        }
+        let packet = match packet.is_fragment() {
+            true => self.link.reassemble(packet, now),
+            false => Some(packet),
+        };
+        let Some(packet) = packet.and_then(|packet| self.codec.open(packet)) else {
+            return Ok(());
+        };
+        if let Some(pong) = self.link.record(&packet, now) {
+            let pong = self.codec.encode_control(pong);
+            return self.send_raw(&pong, now);
+        }
+
+        match packet.message_type() {
+            Ok(MessageType::Disconnect) => {
+                self.state = ConnectionState::Disconnected;
+                events.push(ClientEvent::Disconnected {
+                    reason: packet.decode_disconnect().ok(),
+                });
+            }
+            Ok(MessageType::Ping | MessageType::Pong | MessageType::Connect) => {}
+            _ => events.extend(self.codec.decode(packet).map(ClientEvent::Packet)),
+        }
+        Ok(())
+    }
+
+    /// Envia, quando é hora, a próxima mensagem do aperto de mão ou desiste dele.
+    fn drive_handshake(&mut self, now: Instant, events: &mut Vec<ClientEvent>) -> io::Result<()> {
+        if now.saturating_duration_since(self.started_at) >= CONNECT_TIMEOUT {
+            self.state = ConnectionState::TimedOut;
+            events.push(ClientEvent::TimedOut);
+            return Ok(());
+        }
+        let due = self
+            .last_handshake
+            .is_none_or(|last| now.saturating_duration_since(last) >= HANDSHAKE_RESEND_INTERVAL);
+        if !due {
+            return Ok(());
+        }
+
+        let (salt, version, offer) = (self.salt, self.version, self.offer());
+        let handshake = match self.token {
+            None => Handshake::Request {
+                salt,
+                version,
+                offer,
+            },
+            Some(token) => Handshake::Response {
+                salt,
+                token,
+                version,
+                offer,
+            },
+        };
+        if self.last_handshake.replace(now).is_some() {
+            self.stats.record_retransmission();
+        }
+        self.send_raw(&Packet::from_payload(0, 0, &handshake), now)
+    }
+
+    /// Parâmetros a anunciar no aperto de mão: o token de conexão, a chave pública e o
+    /// nível de proteção, nas conexões assinadas ou cifradas, e os algoritmos de
+    /// compressão aceitos.
+    fn offer(&self) -> Offer {
+        #[cfg(feature = "crypto")]
+        let (public_key, signed) = (
+            self.key_exchange.as_ref().map(KeyExchange::public_key),
+            self.security == SecurityLevel::Signed,
+        );
+        #[cfg(not(feature = "crypto"))]
+        let (public_key, signed) = (None, false);
+        #[cfg(feature = "compression")]
+        let compression = match self.compression {
+            Some(_) => CompressionAlgorithm::ALL
+                .iter()
+                .fold(0, |mask, algorithm| mask | algorithm.id()),
+            None => 0,
+        };
+        #[cfg(not(feature = "compression"))]
+        let compression = 0;
+        Offer {
+            connect_token: self.connect_token,
+            public_key,
+            signed,
+            compression,
+        }
+    }
+
+    /// Conclui a negociação com os parâmetros da aceitação.
+    ///
+    /// Retorna `false`, recusando a aceitação, se o servidor não corresponde à
+    /// proteção do cliente ou escolheu um algoritmo de compressão não anunciado.
+    fn agree(&mut self, server: Offer) -> bool {
+        #[cfg(feature = "crypto")]
+        if server.security() != self.security {
+            return false;
+        }
+        #[cfg(feature = "compression")]
+        if server.compression != 0 {
+            let algorithm = CompressionAlgorithm::from_id(server.compression);
+            let (Some(min_size), Some(algorithm)) = (self.compression, algorithm) else {
+                return false;
+            };
+            self.codec.compressor = Some(Compressor::new(algorithm).with_min_size(min_size));
+        }
+        #[cfg(not(feature = "compression"))]
+        if server.compression != 0 {
+            return false;
+        }
+
+        #[cfg(feature = "crypto")]
+        if let Some(exchange) = self.key_exchange.take() {
+            let Some(server_key) = server.public_key else {
+                self.key_exchange = Some(exchange);
+                return false;
+            };
+            self.codec.keys = Some(exchange.client_keys(&server_key));
+            self.codec.signed = server.signed;
+            return true;
+        }
+        server.public_key.is_none()
+    }
+
+    /// Envia um pacote de controle ao servidor.
+    fn send_raw(&mut self, packet: &Packet, now: Instant) -> io::Result<()> {
+        transmit(
+            &mut self.transport,
+            &mut self.outbound_hooks,
+            self.server,
+            packet,
+            now,
+            &mut [&mut self.stats],
+        )
+        .map(drop)
+        .map_err(io::Error::from)
+    }
+}
+
+/// Passa um pacote pelos ganchos de saída e envia o que eles deixam em fragmentos que
+/// cabem no MTU, ou inteiro se ele já cabe; retorna `0` se um gancho descartou o pacote.
+fn send_fragmented(
+    transport: &mut impl Transport,
+    hooks: &mut HookPipeline,
+    addr: SocketAddr,
+    packet: Packet,
+    mtu: usize,
+    now: Instant,
+    recorders: &mut [&mut StatsRecorder],
+) -> Result<usize, PacketError> {
+    let Some(packet) = hooks.run(&addr, packet) else {
+        return Ok(0);
+    };
+    let fragments = packet.fragment(mtu.saturating_sub(PacketHeader::SIZE))?;
+    let mut sent = 0;
+    for fragment in &fragments {
+        sent += transport.send_packet(addr, fragment)?;
+    }
+    for recorder in recorders {
+        recorder.record_fragmented(&fragments, now);
+    }
+    Ok(sent)
+}
+
+/// Média de um conjunto de durações, ou `None` se ele é vazio.
+fn mean(durations: impl Iterator<Item = Duration>) -> Option<Duration> {
+    let (total, count) = durations.fold((Duration::ZERO, 0), |(total, count), duration| {
+        (total + duration, count + 1)
+    });
+    (count > 0).then(|| total / count)
+}
+
+/// Passa um pacote pelos ganchos de saída, envia o que eles deixam e o registra nas
+/// estatísticas informadas; retorna `0` se um gancho descartou o pacote.
+fn transmit(
+    transport: &mut impl Transport,
+    hooks: &mut HookPipeline,
+    addr: SocketAddr,
+    packet: &Packet,
+    now: Instant,
+    recorders: &mut [&mut StatsRecorder],
+) -> Result<usize, PacketError> {
+    let hooked;
+    let packet = match hooks.is_empty() {
+        true => packet,
+        false => match hooks.run(&addr, packet.clone()) {
+            Some(packet) => {
+                hooked = packet;
+                &hooked
+            }
+            None => return Ok(0),
+        },
+    };
+    let sent = transport.send_packet(addr, packet)?;
+    for recorder in recorders {
+        recorder.record_sent(packet, now);
+    }
+    Ok(sent)
+}
+
+/// Recebe o próximo pacote válido de um transporte não bloqueante.
+///
+/// Datagramas malformados são descartados; `None` indica que não há mais datagramas.
+fn receive(
+    transport: &mut impl Transport,
+    dropped: &mut Vec<(SocketAddr, PacketError)>,
+) -> io::Result<Option<(SocketAddr, Packet)>> {
+    while let Some((from, packet)) = transport.recv_from()? {
+        match packet {
+            Ok(packet) => return Ok(Some((from, packet))),
+            Err(error) => dropped.push((from, error)),
+        }
+    }
+    Ok(None)
+}
@@ -0,0 +1,169 @@
+//! # Módulo `decoder`
+//!
+//! Decodifica pacotes concatenados em um fluxo de bytes, como uma conexão TCP,
+//! à medida que os bytes chegam.
+//!
+//! Quando o fluxo é corrompido ou a leitura começa no meio de um pacote, o
+//! decodificador pode se ressincronizar procurando os próximos bytes mágicos do
+//! cabeçalho ([`PacketHeader::MAGIC`]) e retomando a decodificação a partir deles.
+
+use crate::config::ParseConfig;
+use crate::view::PacketRef;
+use crate::{Packet, PacketError, PacketHeader};
+
+/// Decodificador incremental de pacotes em um fluxo de bytes.
+///
+/// ## Exemplos
+/// ```rust
+/// use packet::Packet;
+/// use packet::decoder::PacketDecoder;
+///
+/// let first = Packet::new(1, 1, 7, vec![1, 2]);
+/// let second = Packet::new(1, 2, 7, vec![3]);
+///
+/// let mut decoder = PacketDecoder::new();
+/// decoder.set_auto_resync(true);
+///
+/// // Lixo antes do primeiro pacote, como ao conectar no meio do fluxo.
+/// decoder.extend_from_slice(&[0xFF, 0x00, 0x13]);
+/// decoder.extend_from_slice(&first.to_bytes());
+/// decoder.extend_from_slice(&second.to_bytes()[..4]);
+///
+/// assert_eq!(decoder.decode().unwrap(), Some(first));
+/// assert_eq!(decoder.decode().unwrap(), None);
+/// assert_eq!(decoder.skipped_bytes(), 3);
+///
+/// decoder.extend_from_slice(&second.to_bytes()[4..]);
+/// assert_eq!(decoder.decode().unwrap(), Some(second));
+/// ```
+#[derive(Debug, Default)]
+pub struct PacketDecoder {
+    /// Bytes recebidos e ainda não decodificados.
+    buffer: Vec<u8>,
+    /// Ressincroniza automaticamente em caso de erro.
+    auto_resync: bool,
+    /// Total de bytes descartados por ressincronização.
+    skipped: u64,
+    /// Limites dos pacotes aceitos.
+    limits: ParseConfig,
+}
+
+impl PacketDecoder {
+    /// Cria um decodificador vazio, sem ressincronização automática e com os limites de
+    /// [`ParseConfig::default`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ativa ou desativa a ressincronização automática.
+    ///
+    /// Com ela ativa, [`PacketDecoder::decode`] nunca retorna erro: bytes que não
+    /// formam um pacote válido são descartados e contabilizados em
+    /// [`PacketDecoder::skipped_bytes`].
+    ///
+    /// ## Parâmetros
+    /// - `enabled`: `true` para ressincronizar automaticamente.
+    pub fn set_auto_resync(&mut self, enabled: bool) {
+        self.auto_resync = enabled;
+    }
+
+    /// Define os limites dos pacotes aceitos.
+    ///
+    /// ## Parâmetros
+    /// - `limits`: Limites da leitura; o payload declarado é verificado antes de se
+    ///   aguardar o restante do pacote.
+    pub fn set_parse_config(&mut self, limits: ParseConfig) {
+        self.limits = limits;
+    }
+
+    /// Acrescenta bytes recebidos do fluxo.
+    ///
+    /// ## Parâmetros
+    /// - `bytes`: Bytes lidos do transporte.
+    pub fn extend_from_slice(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    /// Decodifica o próximo pacote completo do fluxo.
+    ///
+    /// Além da estrutura, o checksum e os limites de [`ParseConfig`] são verificados,
+    /// para que um cabeçalho corrompido não seja aceito nem bloqueie o fluxo à
+    /// espera de um payload gigantesco.
+    ///
+    /// ## Retorno
+    /// - `Result<Option<Packet>, PacketError>`: O pacote, `None` se faltam bytes, ou o
+    ///   erro encontrado. Após um erro, chame [`PacketDecoder::resync`] para continuar.
+    pub fn decode(&mut self) -> Result<Option<Packet>, PacketError> {
+        loop {
+            match self.try_decode() {
+                Err(_) if self.auto_resync => {
+                    self.resync();
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Descarta bytes até a próxima ocorrência dos bytes mágicos, ignorando a posição atual.
+    ///
+    /// Se nenhuma ocorrência for encontrada, o buffer é descartado, exceto por um
+    /// byte final que pode ser o início dos próximos bytes mágicos.
+    ///
+    /// ## Retorno
+    /// - `usize`: Quantidade de bytes descartados.
+    pub fn resync(&mut self) -> usize {
+        let magic = PacketHeader::MAGIC;
+        let skip = if self.buffer.is_empty() {
+            0
+        } else {
+            self.buffer[1..]
+                .windows(magic.len())
+                .position(|window| window == magic)
+                .map(|position| position + 1)
+                .unwrap_or_else(|| {
+                    let keep = usize::from(self.buffer.last() == Some(&magic[0]));
+                    (self.buffer.len() - keep).max(1)
+                })
+        };
+
+        self.buffer.drain(..skip);
+        self.skipped += skip as u64;
+        skip
+    }
+
+    /// Retorna o total de bytes descartados por ressincronização.
+    pub fn skipped_bytes(&self) -> u64 {
+        self.skipped
+    }
+
+    /// Retorna a quantidade de bytes recebidos e ainda não decodificados.
+    pub fn buffered_len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    fn try_decode(&mut self) -> Result<Option<Packet>, PacketError> {
+        if self.buffer.is_empty() {
+            return Ok(None);
+        }
+
+        let header = match PacketHeader::from_bytes(&self.buffer) {
+            Ok(header) => header,
+            Err(PacketError::TooShort { .. }) => return Ok(None),
+            Err(error) => return Err(error),
+        };
+        self.limits.check_header(&header)?;
+
+        let (packet, rest) = match PacketRef::parse_prefix(&self.buffer) {
+            Ok(parsed) => parsed,
+            Err(PacketError::TooShort { .. }) => return Ok(None),
+            Err(error) => return Err(error),
+        };
+        self.limits.check(&packet.header, packet.payload)?;
+        let packet = packet.to_packet();
+        packet.check_checksum()?;
+
+        let consumed = self.buffer.len() - rest.len();
+        self.buffer.drain(..consumed);
+        Ok(Some(packet))
+    }
+}
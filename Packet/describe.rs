@@ -0,0 +1,97 @@
+//! # Módulo `describe`
+//!
+//! Descrição de pacotes em várias linhas, para ferramentas de inspeção interativa.
+//!
+//! Os campos do cabeçalho são lidos a partir de
+//! [`wire_format_description`](crate::wire::wire_format_description), de forma que a
+//! descrição acompanha o formato na rede quando campos são acrescentados.
+
+use std::fmt::Write;
+
+use crate::wire::{wire_format_description, Endianness};
+use crate::Packet;
+
+/// Quantidade de bytes do payload exibidos por linha do hexdump.
+const HEXDUMP_WIDTH: usize = 16;
+
+impl Packet {
+    /// Descreve o pacote campo a campo, seguido de um hexdump do payload.
+    ///
+    /// Cada campo do cabeçalho aparece com seu valor em decimal e em hexadecimal; o
+    /// campo `flags` lista também os nomes das flags ativas. O hexdump mostra o
+    /// deslocamento, os bytes em hexadecimal e sua representação ASCII.
+    ///
+    /// ## Retorno
+    /// - `String`: A descrição, com uma linha por campo e por 16 bytes de payload.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::Packet;
+    ///
+    /// let packet = Packet::reliable(1, 42, 7, b"hello, world!!!!ping".to_vec());
+    /// let description = packet.describe();
+    ///
+    /// assert!(description.contains("sequence      42 (0x0000002a)"));
+    /// assert!(description.contains("flags         1 (0x0001) [RELIABLE]"));
+    /// assert!(description.contains(
+    ///     "0000  68 65 6c 6c 6f 2c 20 77 6f 72 6c 64 21 21 21 21  hello, world!!!!"
+    /// ));
+    /// assert!(description.ends_with("ping"));
+    /// ```
+    pub fn describe(&self) -> String {
+        let header = self.header.to_bytes();
+        let mut out = String::new();
+
+        for field in wire_format_description() {
+            let raw = &header[field.offset..field.offset + field.width];
+            let mut value = [0u8; 8];
+            let value = match field.endianness {
+                Endianness::Little => {
+                    value[..field.width].copy_from_slice(raw);
+                    u64::from_le_bytes(value)
+                }
+                Endianness::Big => {
+                    value[8 - field.width..].copy_from_slice(raw);
+                    u64::from_be_bytes(value)
+                }
+            };
+
+            let _ = write!(
+                out,
+                "{:<13} {value} (0x{value:0digits$x})",
+                field.name,
+                digits = field.width * 2
+            );
+            if field.name == "flags" {
+                let flags = self.header.flags;
+                let mut names: Vec<String> = flags.names().map(str::to_owned).collect();
+                if flags.unknown_bits() != 0 {
+                    names.push(format!("0x{:04x}", flags.unknown_bits()));
+                }
+                let _ = write!(out, " [{}]", names.join(", "));
+            }
+            out.push('\n');
+        }
+
+        let _ = write!(out, "payload       {} bytes", self.payload.len());
+        for (line, chunk) in self.payload.chunks(HEXDUMP_WIDTH).enumerate() {
+            let _ = write!(out, "\n{:04x} ", line * HEXDUMP_WIDTH);
+            for byte in chunk {
+                let _ = write!(out, " {byte:02x}");
+            }
+            let padding = (HEXDUMP_WIDTH - chunk.len()) * 3;
+            let _ = write!(out, "{:padding$}  ", "");
+            out.extend(chunk.iter().map(|&byte| printable(byte)));
+        }
+        out
+    }
+}
+
+/// Retorna o caractere exibido para `byte` na coluna ASCII do hexdump.
+fn printable(byte: u8) -> char {
+    if byte.is_ascii_graphic() || byte == b' ' {
+        byte as char
+    } else {
+        '.'
+    }
+}
@@ -0,0 +1,107 @@
+//! # Módulo `disconnect`
+//!
+//! Encerramento de conexão com o motivo codificado no payload.
+//!
+//! Um pacote de desconexão criado por [`Packet::disconnect`] leva um
+//! [`DisconnectReason`], para que o outro lado exiba a mensagem adequada e decida se
+//! deve tentar reconectar. Os motivos do protocolo ocupam um byte, exceto
+//! [`DisconnectReason::Kicked`], seguido do código da remoção; um
+//! [`DisconnectReason::Custom`] ocupa dois, o marcador [`CUSTOM_REASON_TAG`] seguido
+//! do código da aplicação.
+//!
+//! ## Exemplos
+//! ```rust
+//! use packet::Packet;
+//! use packet::disconnect::DisconnectReason;
+//!
+//! let packet = Packet::disconnect(7, DisconnectReason::Kicked(3));
+//! let received = Packet::from_bytes(&packet.to_bytes()).unwrap();
+//! assert_eq!(received.decode_disconnect(), Ok(DisconnectReason::Kicked(3)));
+//!
+//! let custom = Packet::disconnect(7, DisconnectReason::Custom(42));
+//! assert_eq!(custom.payload, [0xFF, 42]);
+//! assert_eq!(custom.decode_disconnect(), Ok(DisconnectReason::Custom(42)));
+//! ```
+
+use crate::payload::PacketPayload;
+use crate::{Packet, PacketError};
+
+/// Byte que precede o código de um [`DisconnectReason::Custom`].
+pub const CUSTOM_REASON_TAG: u8 = 0xFF;
+
+/// Motivo do encerramento de uma conexão.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DisconnectReason {
+    /// O jogador saiu do jogo.
+    Quit,
+    /// O outro lado parou de responder.
+    Timeout,
+    /// O jogador foi removido pelo servidor, com um código definido pela aplicação,
+    /// como inatividade ou banimento.
+    Kicked(u8),
+    /// O servidor está sendo desligado.
+    ServerShutdown,
+    /// O outro lado enviou pacotes inválidos.
+    ProtocolError,
+    /// Motivo definido pela aplicação.
+    Custom(u8),
+}
+
+impl PacketPayload for DisconnectReason {
+    /// O byte de [`MessageType::Disconnect`](crate::message::MessageType::Disconnect).
+    const MESSAGE_TYPE: u8 = 4;
+
+    fn encode(&self) -> Vec<u8> {
+        match *self {
+            Self::Quit => vec![0],
+            Self::Timeout => vec![1],
+            Self::Kicked(code) => vec![2, code],
+            Self::ServerShutdown => vec![3],
+            Self::ProtocolError => vec![4],
+            Self::Custom(code) => vec![CUSTOM_REASON_TAG, code],
+        }
+    }
+
+    fn decode(payload: &[u8]) -> Result<Self, PacketError> {
+        match *payload {
+            [0] => Ok(Self::Quit),
+            [1] => Ok(Self::Timeout),
+            [2, code] => Ok(Self::Kicked(code)),
+            [3] => Ok(Self::ServerShutdown),
+            [4] => Ok(Self::ProtocolError),
+            [CUSTOM_REASON_TAG, code] => Ok(Self::Custom(code)),
+            _ => Err(PacketError::InvalidPayload {
+                reason: "motivo de desconexão desconhecido",
+            }),
+        }
+    }
+}
+
+impl Packet {
+    /// Cria um pacote
+    /// [`MessageType::Disconnect`](crate::message::MessageType::Disconnect)
+    /// com o motivo informado.
+    ///
+    /// O pacote tem sequência `0`; use [`Packet::with_sequence`] para numerá-lo.
+    ///
+    /// ## Parâmetros
+    /// - `player_id`: Identificador único do jogador.
+    /// - `reason`: Motivo do encerramento.
+    ///
+    /// ## Retorno
+    /// - `Self`: O pacote com o motivo codificado no payload.
+    pub fn disconnect(player_id: u64, reason: DisconnectReason) -> Self {
+        Self::from_payload(0, player_id, &reason)
+    }
+
+    /// Decodifica o motivo de um pacote
+    /// [`MessageType::Disconnect`](crate::message::MessageType::Disconnect).
+    ///
+    /// ## Retorno
+    /// - `Result<DisconnectReason, PacketError>`: O motivo,
+    ///   [`PacketError::UnexpectedMessageType`] se o pacote não é de desconexão, ou
+    ///   [`PacketError::InvalidPayload`] se o motivo é desconhecido.
+    pub fn decode_disconnect(&self) -> Result<DisconnectReason, PacketError> {
+        self.decode_typed()
+    }
+}
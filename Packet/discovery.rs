@@ -0,0 +1,326 @@
+//! # Módulo `discovery`
+//!
+//! Descoberta de servidores na rede local, disponível com o recurso `serde`.
+//!
+//! Um servidor cria um [`DiscoveryResponder`] com seu [`ServerInfo`] e chama
+//! [`DiscoveryResponder::poll`] no laço do jogo; o responder escuta a porta
+//! [`DISCOVERY_PORT`] e responde a cada sonda com as informações serializadas em
+//! `bincode`. Um cliente chama [`discover`], que envia sondas em broadcast a cada
+//! [`DISCOVERY_PROBE_INTERVAL`] até o tempo limite e retorna os servidores que
+//! responderam, sem repetições. O endereço retornado junta o IP de origem da resposta
+//! à porta de jogo anunciada em [`ServerInfo::port`], de modo que pode ser usado
+//! diretamente para conectar.
+//!
+//! Sondas e respostas são pacotes [`MessageType::Discovery`]: a sonda tem o payload
+//! vazio e uma sequência que varia a cada descoberta, repetida pela resposta para que respostas de
+//! descobertas anteriores sejam descartadas. O campo [`ServerInfo::extra`] leva dados
+//! próprios da aplicação, como o mapa ou o modo de jogo, com qualquer tipo `serde`;
+//! respostas cujo `extra` não é do tipo esperado são ignoradas.
+//!
+//! ## Exemplos
+//! ```rust
+//! use std::thread;
+//! use std::time::Duration;
+//! use serde::{Deserialize, Serialize};
+//! use packet::connection::ProtocolVersion;
+//! use packet::discovery::{discover_at, DiscoveryResponder, ServerInfo};
+//!
+//! #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+//! struct Match {
+//!     map: String,
+//! }
+//!
+//! let info = ServerInfo::new("Sala 1", 4000, ProtocolVersion::new(1, 0))
+//!     .with_players(3, 8)
+//!     .with_extra(Match { map: "deserto".into() });
+//! let mut responder = DiscoveryResponder::bind("127.0.0.1:0", info.clone()).unwrap();
+//! let target = responder.local_addr().unwrap();
+//! thread::spawn(move || loop {
+//!     responder.poll().unwrap();
+//!     thread::sleep(Duration::from_millis(5));
+//! });
+//!
+//! let servers = discover_at::<Match>(target, Duration::from_millis(300)).unwrap();
+//! assert_eq!(servers.len(), 1);
+//! assert_eq!(servers[0].0, "127.0.0.1:4000".parse().unwrap());
+//! assert_eq!(servers[0].1, info);
+//! ```
+
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs};
+use std::time::{Duration, Instant, SystemTime};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::connection::ProtocolVersion;
+use crate::message::MessageType;
+use crate::serialize::Format;
+use crate::transport::UdpTransport;
+use crate::{Packet, PacketError};
+
+/// Porta padrão em que os servidores escutam as sondas de descoberta.
+pub const DISCOVERY_PORT: u16 = 47_800;
+
+/// Intervalo entre as sondas enviadas por [`discover`], para compensar perdas.
+pub const DISCOVERY_PROBE_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Informações anunciadas por um servidor.
+///
+/// `E` é o tipo dos dados próprios da aplicação; `()` quando não há nenhum.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ServerInfo<E = ()> {
+    /// Nome exibido na lista de servidores.
+    pub name: String,
+    /// Porta em que o servidor aceita conexões.
+    pub port: u16,
+    /// Jogadores conectados.
+    pub players: u32,
+    /// Máximo de jogadores aceitos.
+    pub max_players: u32,
+    /// Versão do protocolo da aplicação.
+    pub version: ProtocolVersion,
+    /// Dados próprios da aplicação.
+    pub extra: E,
+}
+
+impl ServerInfo {
+    /// Cria as informações de um servidor vazio, sem dados da aplicação.
+    ///
+    /// ## Parâmetros
+    /// - `name`: Nome exibido na lista de servidores.
+    /// - `port`: Porta em que o servidor aceita conexões.
+    /// - `version`: Versão do protocolo da aplicação.
+    ///
+    /// ## Retorno
+    /// - `Self`: As informações, com zero jogadores de um máximo de zero.
+    pub fn new(name: impl Into<String>, port: u16, version: ProtocolVersion) -> Self {
+        Self {
+            name: name.into(),
+            port,
+            players: 0,
+            max_players: 0,
+            version,
+            extra: (),
+        }
+    }
+}
+
+impl<E> ServerInfo<E> {
+    /// Define a ocupação do servidor.
+    ///
+    /// ## Parâmetros
+    /// - `players`: Jogadores conectados.
+    /// - `max_players`: Máximo de jogadores aceitos.
+    ///
+    /// ## Retorno
+    /// - `Self`: As informações com a nova ocupação.
+    pub fn with_players(mut self, players: u32, max_players: u32) -> Self {
+        self.players = players;
+        self.max_players = max_players;
+        self
+    }
+
+    /// Substitui os dados próprios da aplicação.
+    ///
+    /// ## Parâmetros
+    /// - `extra`: Novos dados, de qualquer tipo `serde`.
+    ///
+    /// ## Retorno
+    /// - `ServerInfo<F>`: As informações com os novos dados.
+    pub fn with_extra<F>(self, extra: F) -> ServerInfo<F> {
+        ServerInfo {
+            name: self.name,
+            port: self.port,
+            players: self.players,
+            max_players: self.max_players,
+            version: self.version,
+            extra,
+        }
+    }
+}
+
+/// Responde às sondas de descoberta com as informações do servidor.
+#[derive(Debug)]
+pub struct DiscoveryResponder<E = ()> {
+    /// Transporte não bloqueante associado à porta de descoberta.
+    transport: UdpTransport,
+    /// Informações anunciadas.
+    info: ServerInfo<E>,
+}
+
+impl<E: Serialize> DiscoveryResponder<E> {
+    /// Cria um responder que escuta [`DISCOVERY_PORT`] em todas as interfaces IPv4.
+    ///
+    /// ## Parâmetros
+    /// - `info`: Informações anunciadas.
+    ///
+    /// ## Retorno
+    /// - `io::Result<Self>`: O responder, ou o erro do sistema ao associar a porta.
+    pub fn new(info: ServerInfo<E>) -> io::Result<Self> {
+        Self::bind((Ipv4Addr::UNSPECIFIED, DISCOVERY_PORT), info)
+    }
+
+    /// Cria um responder associado ao endereço informado.
+    ///
+    /// ## Parâmetros
+    /// - `addr`: Endereço local em que as sondas são recebidas.
+    /// - `info`: Informações anunciadas.
+    ///
+    /// ## Retorno
+    /// - `io::Result<Self>`: O responder, ou o erro do sistema ao associar o endereço.
+    pub fn bind(addr: impl ToSocketAddrs, info: ServerInfo<E>) -> io::Result<Self> {
+        let transport = UdpTransport::bind(addr)?;
+        transport.get_ref().set_nonblocking(true)?;
+        Ok(Self { transport, info })
+    }
+
+    /// Retorna o endereço local do responder.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.transport.local_addr()
+    }
+
+    /// Retorna as informações anunciadas.
+    pub fn info(&self) -> &ServerInfo<E> {
+        &self.info
+    }
+
+    /// Retorna as informações anunciadas para alteração, como a quantidade de
+    /// jogadores; as próximas respostas já levam os novos valores.
+    pub fn info_mut(&mut self) -> &mut ServerInfo<E> {
+        &mut self.info
+    }
+
+    /// Responde a todas as sondas recebidas desde a última chamada, sem bloquear.
+    ///
+    /// Datagramas que não são sondas de descoberta são descartados.
+    ///
+    /// ## Retorno
+    /// - `Result<usize, PacketError>`: A quantidade de sondas respondidas;
+    ///   [`PacketError::Io`] com o erro do sistema, ou
+    ///   [`PacketError::InvalidPayload`] se as informações não puderam ser
+    ///   serializadas.
+    pub fn poll(&mut self) -> Result<usize, PacketError> {
+        let mut answered = 0;
+        loop {
+            let (from, probe) = match self.transport.recv_packet() {
+                Ok(received) => received,
+                Err(PacketError::Io(error)) if error.kind() == io::ErrorKind::WouldBlock => {
+                    return Ok(answered);
+                }
+                Err(error @ PacketError::Io(_)) => return Err(error),
+                Err(_) => continue,
+            };
+            if probe.message_type() != Ok(MessageType::Discovery) || !probe.payload.is_empty() {
+                continue;
+            }
+            let reply = Packet::from_message(
+                MessageType::Discovery.into(),
+                probe.header.sequence,
+                0,
+                Format::Bincode,
+                &self.info,
+            )?;
+            self.transport.send_packet(from, &reply)?;
+            answered += 1;
+        }
+    }
+}
+
+/// Procura servidores na rede local por broadcast na porta [`DISCOVERY_PORT`].
+///
+/// Bloqueia durante todo o tempo limite, reenviando a sonda a cada
+/// [`DISCOVERY_PROBE_INTERVAL`].
+///
+/// ## Parâmetros
+/// - `timeout`: Tempo durante o qual as respostas são aguardadas.
+///
+/// ## Retorno
+/// - `Result<Vec<(SocketAddr, ServerInfo<E>)>, PacketError>`: Os servidores que
+///   responderam, na ordem da primeira resposta e com as informações mais recentes
+///   de cada um, ou [`PacketError::Io`] com o erro do sistema.
+pub fn discover<E: DeserializeOwned>(
+    timeout: Duration,
+) -> Result<Vec<(SocketAddr, ServerInfo<E>)>, PacketError> {
+    discover_at((Ipv4Addr::BROADCAST, DISCOVERY_PORT).into(), timeout)
+}
+
+/// Procura servidores enviando as sondas a um endereço específico.
+///
+/// Como [`discover`], mas o destino pode ser o broadcast de uma sub-rede, um grupo
+/// multicast ou um único servidor.
+///
+/// ## Parâmetros
+/// - `target`: Destino das sondas.
+/// - `timeout`: Tempo durante o qual as respostas são aguardadas.
+///
+/// ## Retorno
+/// - `Result<Vec<(SocketAddr, ServerInfo<E>)>, PacketError>`: Os servidores que
+///   responderam, ou [`PacketError::Io`] com o erro do sistema.
+pub fn discover_at<E: DeserializeOwned>(
+    target: SocketAddr,
+    timeout: Duration,
+) -> Result<Vec<(SocketAddr, ServerInfo<E>)>, PacketError> {
+    let local: IpAddr = match target {
+        SocketAddr::V4(_) => Ipv4Addr::UNSPECIFIED.into(),
+        SocketAddr::V6(_) => Ipv6Addr::UNSPECIFIED.into(),
+    };
+    let mut transport = UdpTransport::bind((local, 0))?;
+    let nonce = probe_nonce();
+    let probe = Packet::new(MessageType::Discovery.into(), nonce, 0, Vec::new());
+
+    let deadline = Instant::now() + timeout;
+    let mut next_probe = Instant::now();
+    let mut servers: Vec<(SocketAddr, ServerInfo<E>)> = Vec::new();
+    loop {
+        let now = Instant::now();
+        if now >= deadline {
+            return Ok(servers);
+        }
+        if now >= next_probe {
+            if target.ip() == IpAddr::V4(Ipv4Addr::BROADCAST) {
+                transport.broadcast(&probe, target.port())?;
+            } else {
+                transport.send_packet(target, &probe)?;
+            }
+            next_probe = now + DISCOVERY_PROBE_INTERVAL;
+        }
+
+        let wait = deadline.min(next_probe).saturating_duration_since(now);
+        transport
+            .get_ref()
+            .set_read_timeout(Some(wait.max(Duration::from_millis(1))))?;
+        let (from, reply) = match transport.recv_packet() {
+            Ok(received) => received,
+            Err(PacketError::Io(error))
+                if matches!(
+                    error.kind(),
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                ) =>
+            {
+                continue;
+            }
+            Err(error @ PacketError::Io(_)) => return Err(error),
+            Err(_) => continue,
+        };
+        if reply.message_type() != Ok(MessageType::Discovery) || reply.header.sequence != nonce {
+            continue;
+        }
+        let Ok(info) = reply.decode_payload::<ServerInfo<E>>() else {
+            continue;
+        };
+        let addr = SocketAddr::new(from.ip(), info.port);
+        match servers.iter_mut().find(|(known, _)| *known == addr) {
+            Some(server) => server.1 = info,
+            None => servers.push((addr, info)),
+        }
+    }
+}
+
+/// Sequência da sonda, derivada do relógio para diferir entre descobertas.
+fn probe_nonce() -> u32 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|elapsed| elapsed.subsec_nanos() ^ elapsed.as_secs() as u32)
+        .unwrap_or(0)
+}
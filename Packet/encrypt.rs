@@ -0,0 +1,334 @@
+//! # Módulo `encrypt`
+//!
+//! Cifragem autenticada de payloads com ChaCha20-Poly1305, disponível com o recurso
+//! `crypto`.
+//!
+//! [`Packet::seal`] cifra o payload e anexa a etiqueta de autenticação de
+//! [`TAG_SIZE`] (16) bytes; [`Packet::open`] verifica e decifra. O cabeçalho segue em
+//! claro, mas é autenticado: os campos de [`Packet::canonical_bytes`] entram como dados
+//! associados, de modo que relays ainda podem decrementar o `ttl` e anexar confirmações,
+//! mas não redirecionar o pacote para outro jogador ou outra sequência.
+//!
+//! O nonce é derivado de `player_id` e `sequence`. Uma mesma chave não deve cifrar dois
+//! payloads diferentes com o mesmo par; troque a chave com [`Packet::rekey`] antes que
+//! a sequência dê a volta.
+//!
+//! As chaves podem ser combinadas sem segredo prévio com [`KeyExchange`], uma troca
+//! X25519 efêmera: cada lado envia sua chave pública e ambos derivam as mesmas
+//! [`SessionKeys`], com uma chave para cada sentido, de modo que os dois lados podem
+//! usar as mesmas sequências sem repetir nonces. É a troca feita pelo aperto de mão de
+//! [`connection`](crate::connection) quando a cifragem está habilitada.
+//!
+//! ## Exemplos
+//! ```rust
+//! use chacha20poly1305::Key;
+//! use packet::Packet;
+//!
+//! let key = Key::from_slice(&[7; 32]);
+//!
+//! let sealed = Packet::new(1, 1, 7, vec![1, 2, 3]).seal(key);
+//! assert_eq!(sealed.payload.len(), 3 + packet::encrypt::TAG_SIZE);
+//! assert_ne!(sealed.payload[..3], [1, 2, 3]);
+//!
+//! let mut received = Packet::from_bytes(&sealed.to_bytes()).unwrap();
+//! received.decrement_ttl().unwrap();
+//! assert_eq!(received.open(key).unwrap().payload, [1, 2, 3]);
+//!
+//! received.header.player_id = 8;
+//! assert!(received.open(key).is_err());
+//! ```
+
+use std::fmt;
+
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use sha2::{Digest, Sha256};
+use x25519_dalek::{EphemeralSecret, PublicKey};
+
+use crate::mac::MAC_KEY_SIZE;
+use crate::{Packet, PacketError, PacketFlags, PacketHeader};
+
+/// Tamanho, em bytes, da etiqueta de autenticação anexada ao payload cifrado.
+pub const TAG_SIZE: usize = 16;
+
+/// Tamanho, em bytes, de uma chave pública de [`KeyExchange`].
+pub const PUBLIC_KEY_SIZE: usize = 32;
+
+/// Lado efêmero de uma troca de chaves X25519.
+///
+/// ## Exemplos
+/// ```rust
+/// use packet::Packet;
+/// use packet::encrypt::KeyExchange;
+///
+/// let client = KeyExchange::new();
+/// let server = KeyExchange::new();
+/// let (client_public, server_public) = (client.public_key(), server.public_key());
+///
+/// let client_keys = client.client_keys(&server_public);
+/// let server_keys = server.server_keys(&client_public);
+///
+/// let sealed = client_keys.seal(Packet::new(1, 1, 7, vec![1, 2, 3]));
+/// assert_eq!(server_keys.open(&sealed).unwrap().payload, [1, 2, 3]);
+/// // Cada sentido tem a sua chave.
+/// assert!(client_keys.open(&sealed).is_err());
+/// ```
+pub struct KeyExchange {
+    /// Segredo efêmero, consumido ao derivar as chaves.
+    secret: EphemeralSecret,
+    /// Chave pública enviada ao outro lado.
+    public: PublicKey,
+}
+
+impl fmt::Debug for KeyExchange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyExchange")
+            .field("public", &self.public.as_bytes())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for KeyExchange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KeyExchange {
+    /// Gera um novo segredo efêmero com o gerador aleatório do sistema.
+    pub fn new() -> Self {
+        let secret = EphemeralSecret::random();
+        let public = PublicKey::from(&secret);
+        Self { secret, public }
+    }
+
+    /// Retorna a chave pública a ser enviada ao outro lado.
+    pub fn public_key(&self) -> [u8; PUBLIC_KEY_SIZE] {
+        self.public.to_bytes()
+    }
+
+    /// Conclui a troca do lado que iniciou a conexão.
+    ///
+    /// ## Parâmetros
+    /// - `server_public`: Chave pública recebida do servidor.
+    ///
+    /// ## Retorno
+    /// - `SessionKeys`: As chaves do cliente: cifra com a chave cliente → servidor e
+    ///   decifra com a chave servidor → cliente.
+    pub fn client_keys(self, server_public: &[u8; PUBLIC_KEY_SIZE]) -> SessionKeys {
+        let client_public = self.public_key();
+        let shared = self.secret.diffie_hellman(&PublicKey::from(*server_public));
+        let derive = |label| derive_key(label, shared.as_bytes(), &client_public, server_public);
+        SessionKeys {
+            send: Key::from(derive(CLIENT_TO_SERVER)),
+            receive: Key::from(derive(SERVER_TO_CLIENT)),
+            mac_send: derive(MAC_CLIENT_TO_SERVER),
+            mac_receive: derive(MAC_SERVER_TO_CLIENT),
+        }
+    }
+
+    /// Conclui a troca do lado que aceitou a conexão.
+    ///
+    /// ## Parâmetros
+    /// - `client_public`: Chave pública recebida do cliente.
+    ///
+    /// ## Retorno
+    /// - `SessionKeys`: As chaves do servidor, complementares às de
+    ///   [`KeyExchange::client_keys`].
+    pub fn server_keys(self, client_public: &[u8; PUBLIC_KEY_SIZE]) -> SessionKeys {
+        let server_public = self.public_key();
+        let shared = self.secret.diffie_hellman(&PublicKey::from(*client_public));
+        let derive = |label| derive_key(label, shared.as_bytes(), client_public, &server_public);
+        SessionKeys {
+            send: Key::from(derive(SERVER_TO_CLIENT)),
+            receive: Key::from(derive(CLIENT_TO_SERVER)),
+            mac_send: derive(MAC_SERVER_TO_CLIENT),
+            mac_receive: derive(MAC_CLIENT_TO_SERVER),
+        }
+    }
+}
+
+/// Rótulo da chave do sentido cliente → servidor.
+const CLIENT_TO_SERVER: &[u8] = b"packet encrypt client->server";
+
+/// Rótulo da chave do sentido servidor → cliente.
+const SERVER_TO_CLIENT: &[u8] = b"packet encrypt server->client";
+
+/// Rótulo da chave de autenticação do sentido cliente → servidor.
+const MAC_CLIENT_TO_SERVER: &[u8] = b"packet mac client->server";
+
+/// Rótulo da chave de autenticação do sentido servidor → cliente.
+const MAC_SERVER_TO_CLIENT: &[u8] = b"packet mac server->client";
+
+/// Deriva a chave de um sentido do segredo compartilhado e das duas chaves públicas.
+fn derive_key(label: &[u8], shared: &[u8; 32], client: &[u8; 32], server: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    for part in [label, shared, client, server] {
+        hasher.update(part);
+    }
+    let mut key = [0; 32];
+    key.copy_from_slice(&hasher.finalize());
+    key
+}
+
+/// Chaves de uma conexão combinadas por [`KeyExchange`].
+#[derive(Clone)]
+pub struct SessionKeys {
+    /// Chave dos pacotes enviados.
+    send: Key,
+    /// Chave dos pacotes recebidos.
+    receive: Key,
+    /// Chave de autenticação dos pacotes enviados.
+    mac_send: [u8; MAC_KEY_SIZE],
+    /// Chave de autenticação dos pacotes recebidos.
+    mac_receive: [u8; MAC_KEY_SIZE],
+}
+
+impl fmt::Debug for SessionKeys {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SessionKeys").finish_non_exhaustive()
+    }
+}
+
+impl SessionKeys {
+    /// Cifra um pacote a ser enviado, como em [`Packet::seal`].
+    pub fn seal(&self, packet: Packet) -> Packet {
+        packet.seal(&self.send)
+    }
+
+    /// Decifra um pacote recebido, como em [`Packet::open`].
+    pub fn open(&self, packet: &Packet) -> Result<Packet, PacketError> {
+        packet.open(&self.receive)
+    }
+
+    /// Autentica um pacote a ser enviado, sem cifrá-lo, como em
+    /// [`Packet::authenticate`].
+    ///
+    /// As chaves de autenticação são derivadas da mesma troca, mas com rótulos
+    /// próprios, independentes das chaves de cifragem.
+    pub fn authenticate(&self, packet: Packet) -> Packet {
+        packet.authenticate(&self.mac_send)
+    }
+
+    /// Verifica um pacote recebido, como em [`Packet::verify_mac`].
+    pub fn verify_mac(&self, packet: &Packet) -> Result<Packet, PacketError> {
+        packet.verify_mac(&self.mac_receive)
+    }
+}
+
+impl Packet {
+    /// Cifra o payload com a chave informada.
+    ///
+    /// ## Parâmetros
+    /// - `key`: Chave simétrica compartilhada com o destinatário.
+    ///
+    /// ## Retorno
+    /// - `Packet`: O pacote com a flag [`PacketFlags::ENCRYPTED`], o payload cifrado
+    ///   acrescido de [`TAG_SIZE`] bytes e `payload_size` e checksum atualizados.
+    pub fn seal(mut self, key: &Key) -> Packet {
+        self.header.flags.insert(PacketFlags::ENCRYPTED);
+        let sealed_len = self.payload.len() + TAG_SIZE;
+        let aad = self.associated_data(sealed_len);
+
+        self.payload = ChaCha20Poly1305::new(key)
+            .encrypt(
+                &self.nonce(),
+                Payload {
+                    msg: &self.payload,
+                    aad: &aad,
+                },
+            )
+            .expect("payload dentro do limite do ChaCha20-Poly1305");
+        self.refresh_header();
+        self
+    }
+
+    /// Verifica e decifra um payload cifrado por [`Packet::seal`].
+    ///
+    /// ## Parâmetros
+    /// - `key`: Chave com que o pacote foi cifrado.
+    ///
+    /// ## Retorno
+    /// - `Result<Packet, PacketError>`: O pacote com o payload em claro, sem a flag
+    ///   [`PacketFlags::ENCRYPTED`] e com os demais campos do cabeçalho preservados, ou
+    ///   [`PacketError::DecryptionFailed`] se o pacote não está cifrado, foi cifrado com
+    ///   outra chave ou foi alterado.
+    pub fn open(&self, key: &Key) -> Result<Packet, PacketError> {
+        if !self.header.flags.contains(PacketFlags::ENCRYPTED) {
+            return Err(PacketError::DecryptionFailed);
+        }
+        let aad = self.associated_data(self.payload.len());
+        let payload = ChaCha20Poly1305::new(key)
+            .decrypt(
+                &self.nonce(),
+                Payload {
+                    msg: &self.payload,
+                    aad: &aad,
+                },
+            )
+            .map_err(|_| PacketError::DecryptionFailed)?;
+
+        let mut opened = Packet {
+            header: self.header.clone(),
+            payload,
+        };
+        opened.header.flags.remove(PacketFlags::ENCRYPTED);
+        opened.refresh_header();
+        Ok(opened)
+    }
+
+    /// Cifra novamente, com uma nova chave, um pacote cifrado pela chave anterior.
+    ///
+    /// Destinado à janela de troca de chaves: pacotes enfileirados sob a chave anterior
+    /// saem sob a atual sem que o chamador manipule o payload em claro. Todos os campos
+    /// do cabeçalho, inclusive `ttl` e confirmações, são preservados.
+    ///
+    /// ## Parâmetros
+    /// - `old_key`: Chave com que o pacote foi cifrado.
+    /// - `new_key`: Chave com que o pacote deve sair.
+    ///
+    /// ## Retorno
+    /// - `Result<Packet, PacketError>`: O pacote cifrado com `new_key`, ou o erro de
+    ///   [`Packet::open`] se ele não pôde ser decifrado com `old_key`.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use chacha20poly1305::Key;
+    /// use packet::{Packet, PacketError};
+    ///
+    /// let old_key = Key::from_slice(&[1; 32]);
+    /// let new_key = Key::from_slice(&[2; 32]);
+    ///
+    /// let queued = Packet::new(1, 5, 7, vec![1, 2, 3]).with_ack(4, 0b1).seal(old_key);
+    /// let rekeyed = queued.clone().rekey(old_key, new_key).unwrap();
+    ///
+    /// assert_eq!(rekeyed.header.ack, 4);
+    /// assert!(rekeyed.open(old_key).is_err());
+    /// assert_eq!(rekeyed.open(new_key).unwrap().payload, [1, 2, 3]);
+    ///
+    /// assert_eq!(queued.rekey(new_key, old_key), Err(PacketError::DecryptionFailed));
+    /// ```
+    pub fn rekey(self, old_key: &Key, new_key: &Key) -> Result<Packet, PacketError> {
+        Ok(self.open(old_key)?.seal(new_key))
+    }
+
+    /// Nonce do pacote:`player_id` seguido de `sequence`, em little-endian.
+    fn nonce(&self) -> Nonce {
+        let mut nonce = [0; 12];
+        nonce[..8].copy_from_slice(&self.header.player_id.to_le_bytes());
+        nonce[8..].copy_from_slice(&self.header.sequence.to_le_bytes());
+        Nonce::from(nonce)
+    }
+
+    /// Cabeçalho canônico do pacote cifrado, com `sealed_len` bytes de payload.
+    fn associated_data(&self, sealed_len: usize) -> Vec<u8> {
+        let mut header = Packet {
+            header: self.header.clone(),
+            payload: Vec::new(),
+        };
+        header.header.payload_size = sealed_len as u32;
+        let bytes = header.canonical_bytes();
+        debug_assert_eq!(bytes.len(), PacketHeader::SIZE);
+        bytes
+    }
+}
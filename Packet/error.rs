@@ -0,0 +1,377 @@
+//! # Módulo `error`
+//!
+//! Define [`PacketError`], o tipo de erro retornado pelas operações fallíveis do crate.
+//!
+//! Erros de E/S são preservados em [`PacketError::Io`] e expostos por
+//! [`std::error::Error::source`], de modo que a causa original continua acessível
+//! após a propagação com `?`.
+//!
+//! As funções sobre fluxos, como [`Packet::from_reader`](crate::Packet::from_reader),
+//! retornam `io::Result` e levam o [`PacketError`] dentro de um
+//! [`io::ErrorKind::InvalidData`]; [`PacketError::from_io_error`] o recupera para que
+//! a variante possa ser comparada.
+//!
+//! ## Exemplos
+//! ```rust
+//! use std::error::Error;
+//! use std::io;
+//! use packet::PacketError;
+//!
+//! let error = PacketError::from(io::Error::new(io::ErrorKind::ConnectionReset, "reset"));
+//! let source = error.source().unwrap().downcast_ref::<io::Error>().unwrap();
+//! assert_eq!(source.kind(), io::ErrorKind::ConnectionReset);
+//!
+//! // A conversão de volta devolve o erro original.
+//! assert_eq!(io::Error::from(error).kind(), io::ErrorKind::ConnectionReset);
+//! ```
+
+use alloc::vec::Vec;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::io;
+#[cfg(feature = "std")]
+use std::sync::Arc;
+
+use crate::compare::HeaderFieldSet;
+use crate::message::ProtocolVersion;
+
+/// Erro de E/S dentro de um [`PacketError`].
+///
+/// O erro é compartilhado para que [`PacketError`] continue clonável, e a
+/// comparação considera apenas o [`io::ErrorKind`].
+#[cfg(feature = "std")]
+#[derive(Debug, Clone)]
+pub struct IoError(Arc<io::Error>);
+
+#[cfg(feature = "std")]
+impl IoError {
+    /// Retorna o tipo do erro de E/S.
+    pub fn kind(&self) -> io::ErrorKind {
+        self.0.kind()
+    }
+
+    /// Retorna o erro de E/S original.
+    pub fn get_ref(&self) -> &io::Error {
+        &self.0
+    }
+}
+
+#[cfg(feature = "std")]
+impl PartialEq for IoError {
+    fn eq(&self, other: &Self) -> bool {
+        self.kind() == other.kind()
+    }
+}
+
+#[cfg(feature = "std")]
+impl Eq for IoError {}
+
+/// Erros produzidos ao montar, serializar ou interpretar pacotes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PacketError {
+    /// A entrada tem menos bytes do que o necessário.
+    TooShort {
+        /// Quantidade de bytes necessária.
+        expected: usize,
+        /// Quantidade de bytes disponível.
+        actual: usize,
+    },
+    /// Os bytes iniciais não correspondem a [`PacketHeader::MAGIC`](crate::PacketHeader::MAGIC).
+    InvalidMagic {
+        /// Bytes encontrados no lugar dos bytes mágicos.
+        found: [u8; 2],
+    },
+    /// O cabeçalho usa uma versão do formato diferente de
+    /// [`PacketHeader::VERSION`](crate::PacketHeader::VERSION).
+    UnsupportedVersion {
+        /// Versão encontrada no cabeçalho.
+        found: u8,
+        /// Versão suportada por este crate.
+        supported: u8,
+    },
+    /// O outro lado do aperto de mão usa uma versão incompatível do protocolo da
+    /// aplicação (veja [`ProtocolVersion`]).
+    IncompatibleVersion {
+        /// Versão local.
+        local: ProtocolVersion,
+        /// Versão anunciada pelo outro lado.
+        remote: ProtocolVersion,
+    },
+    /// O token de conexão não é válido (veja [`ConnectToken`](crate::auth::ConnectToken)).
+    InvalidToken {
+        /// Motivo da recusa, como assinatura inválida ou servidor fora do token.
+        reason: &'static str,
+    },
+    /// O token de conexão já expirou.
+    TokenExpired,
+    /// O tamanho do payload declarado no cabeçalho não corresponde ao recebido.
+    PayloadSizeMismatch {
+        /// Tamanho declarado no cabeçalho.
+        declared: usize,
+        /// Tamanho efetivamente recebido.
+        actual: usize,
+    },
+    /// O tamanho total do pacote não pode ser representado em `usize`.
+    SizeOverflow {
+        /// Tamanho do payload declarado no cabeçalho.
+        payload_size: u32,
+    },
+    /// O pacote é grande demais para a operação solicitada.
+    PacketTooLarge {
+        /// Tamanho do pacote, em bytes.
+        size: usize,
+        /// Limite permitido, em bytes.
+        limit: usize,
+    },
+    /// O payload excede o tamanho máximo permitido.
+    PayloadTooLarge {
+        /// Tamanho do payload, em bytes.
+        size: usize,
+        /// Limite permitido, em bytes.
+        limit: usize,
+    },
+    /// O fragmento declara mais fragmentos que o limite permitido.
+    TooManyFragments {
+        /// Quantidade de fragmentos declarada.
+        count: usize,
+        /// Limite permitido.
+        limit: usize,
+    },
+    /// O lote contém mais pacotes que o limite permitido.
+    BatchTooLarge {
+        /// Limite permitido, em pacotes.
+        limit: usize,
+    },
+    /// O tamanho solicitado para o payload é inválido para a operação.
+    InvalidPayloadLength {
+        /// Tamanho solicitado.
+        requested: usize,
+        /// Tamanho atual do payload.
+        actual: usize,
+    },
+    /// O checksum armazenado não corresponde ao calculado.
+    ChecksumMismatch {
+        /// Checksum armazenado no cabeçalho.
+        expected: u32,
+        /// Checksum calculado sobre os dados recebidos.
+        actual: u32,
+    },
+    /// O `header_checksum` armazenado não corresponde ao calculado sobre o cabeçalho.
+    HeaderChecksumMismatch {
+        /// Checksum armazenado no cabeçalho.
+        expected: u8,
+        /// Checksum calculado sobre os bytes recebidos do cabeçalho.
+        actual: u8,
+    },
+    /// O tipo de mensagem pertence à faixa reservada e não é conhecido.
+    UnknownMessageType(u8),
+    /// O tipo de mensagem do pacote difere do esperado pela operação.
+    UnexpectedMessageType {
+        /// Tipo esperado.
+        expected: u8,
+        /// Tipo encontrado no cabeçalho.
+        found: u8,
+    },
+    /// O payload não pôde ser interpretado como o tipo solicitado.
+    InvalidPayload {
+        /// Descrição do problema encontrado.
+        reason: &'static str,
+    },
+    /// O payload deveria ser texto UTF-8, mas não é.
+    InvalidUtf8 {
+        /// Quantidade de bytes iniciais que formam UTF-8 válido.
+        valid_up_to: usize,
+    },
+    /// O quadro recebido não respeita o formato de enquadramento esperado.
+    MalformedFrame {
+        /// Descrição do problema encontrado.
+        reason: &'static str,
+    },
+    /// A entrada termina no meio de um campo do cabeçalho.
+    TruncatedField {
+        /// Nome do campo, igual ao nome em [`PacketHeader`](crate::PacketHeader).
+        field: &'static str,
+        /// Posição do primeiro byte do campo no cabeçalho.
+        offset: usize,
+    },
+    /// O datagrama recebido foi truncado por não caber no buffer de recepção.
+    Truncated {
+        /// Tamanho do pacote declarado no cabeçalho.
+        declared: usize,
+        /// Tamanho do buffer de recepção.
+        buffer: usize,
+    },
+    /// O pacote interpretado de volta a partir dos seus bytes difere do original
+    /// (veja [`Packet::debug_roundtrip`](crate::Packet::debug_roundtrip)).
+    RoundtripMismatch {
+        /// Campos do cabeçalho que mudaram.
+        fields: HeaderFieldSet,
+        /// Indica se o payload mudou.
+        payload: bool,
+    },
+    /// O payload não pôde ser decifrado: o pacote não está cifrado, foi cifrado com outra
+    /// chave ou foi alterado.
+    DecryptionFailed,
+    /// A etiqueta HMAC do pacote não confere: o pacote não está autenticado, foi
+    /// autenticado com outra chave ou foi alterado.
+    AuthenticationFailed,
+    /// O pacote esgotou seu limite de saltos e deve ser descartado pelo relay.
+    TtlExpired,
+    /// Falha de E/S no transporte subjacente.
+    #[cfg(feature = "std")]
+    Io(IoError),
+}
+
+impl fmt::Display for PacketError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooShort { expected, actual } => {
+                write!(f, "bytes insuficientes: esperado {expected}, recebido {actual}")
+            }
+            Self::InvalidMagic { found } => {
+                write!(f, "bytes mágicos inválidos: {:02x}{:02x}", found[0], found[1])
+            }
+            Self::UnsupportedVersion { found, supported } => write!(
+                f,
+                "versão do formato não suportada: {found} (suportada: {supported})"
+            ),
+            Self::IncompatibleVersion { local, remote } => write!(
+                f,
+                "versão do protocolo incompatível: {remote} (local: {local})"
+            ),
+            Self::InvalidToken { reason } => write!(f, "token de conexão inválido: {reason}"),
+            Self::TokenExpired => write!(f, "token de conexão expirado"),
+            Self::PayloadSizeMismatch { declared, actual } => write!(
+                f,
+                "tamanho do payload não corresponde ao cabeçalho: declarado {declared}, recebido {actual}"
+            ),
+            Self::SizeOverflow { payload_size } => {
+                write!(f, "tamanho do pacote excede usize (payload_size = {payload_size})")
+            }
+            Self::PacketTooLarge { size, limit } => {
+                write!(f, "pacote de {size} bytes excede o limite de {limit} bytes")
+            }
+            Self::PayloadTooLarge { size, limit } => {
+                write!(f, "payload de {size} bytes excede o limite de {limit} bytes")
+            }
+            Self::TooManyFragments { count, limit } => {
+                write!(f, "{count} fragmentos excedem o limite de {limit}")
+            }
+            Self::BatchTooLarge { limit } => {
+                write!(f, "lote excede o limite de {limit} pacotes")
+            }
+            Self::InvalidPayloadLength { requested, actual } => write!(
+                f,
+                "tamanho de payload inválido: solicitado {requested}, atual {actual}"
+            ),
+            Self::ChecksumMismatch { expected, actual } => write!(
+                f,
+                "checksum divergente: armazenado {expected:#010x}, calculado {actual:#010x}"
+            ),
+            Self::HeaderChecksumMismatch { expected, actual } => write!(
+                f,
+                "checksum do cabeçalho divergente: armazenado {expected:#04x}, calculado {actual:#04x}"
+            ),
+            Self::UnknownMessageType(message_type) => {
+                write!(f, "tipo de mensagem desconhecido: {message_type}")
+            }
+            Self::UnexpectedMessageType { expected, found } => {
+                write!(f, "tipo de mensagem inesperado: esperado {expected}, encontrado {found}")
+            }
+            Self::InvalidPayload { reason } => write!(f, "payload inválido: {reason}"),
+            Self::InvalidUtf8 { valid_up_to } => {
+                write!(f, "payload não é UTF-8 válido a partir do byte {valid_up_to}")
+            }
+            Self::MalformedFrame { reason } => write!(f, "quadro malformado: {reason}"),
+            Self::TruncatedField { field, offset } => {
+                write!(f, "campo {field} truncado (deslocamento {offset})")
+            }
+            Self::Truncated { declared, buffer } => write!(
+                f,
+                "datagrama truncado: pacote de {declared} bytes em buffer de {buffer} bytes"
+            ),
+            Self::RoundtripMismatch { fields, payload } => {
+                let mut changed: Vec<&str> = fields.names().collect();
+                if *payload {
+                    changed.push("payload");
+                }
+                write!(
+                    f,
+                    "pacote muda ao ser serializado e lido de volta: {}",
+                    changed.join(", ")
+                )
+            }
+            Self::DecryptionFailed => write!(f, "falha ao decifrar o payload"),
+            Self::AuthenticationFailed => write!(f, "etiqueta de autenticação inválida"),
+            Self::TtlExpired => write!(f, "limite de saltos (ttl) esgotado"),
+            #[cfg(feature = "std")]
+            Self::Io(error) => write!(f, "erro de E/S: {}", error.get_ref()),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl PacketError {
+    /// Recupera o [`PacketError`] carregado por um erro de E/S.
+    ///
+    /// ## Parâmetros
+    /// - `error`: Erro retornado por uma função que converte [`PacketError`] em
+    ///   [`io::Error`].
+    ///
+    /// ## Retorno
+    /// - `Option<&PacketError>`: O erro do pacote, ou `None` se a falha veio do próprio
+    ///   fluxo.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use packet::{Packet, PacketError};
+    ///
+    /// let mut bytes = Packet::new(1, 1, 7, vec![1, 2, 3]).to_bytes();
+    /// *bytes.last_mut().unwrap() ^= 0xFF;
+    ///
+    /// let error = Packet::from_reader(&mut Cursor::new(bytes)).unwrap_err();
+    /// assert!(matches!(
+    ///     PacketError::from_io_error(&error),
+    ///     Some(PacketError::ChecksumMismatch { .. })
+    /// ));
+    /// ```
+    pub fn from_io_error(error: &io::Error) -> Option<&PacketError> {
+        error.get_ref()?.downcast_ref()
+    }
+}
+
+impl core::error::Error for PacketError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            #[cfg(feature = "std")]
+            Self::Io(error) => Some(error.get_ref()),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<io::Error> for PacketError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(IoError(Arc::new(error)))
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<PacketError> for io::Error {
+    /// Converte o erro em [`io::ErrorKind::InvalidData`], para uso com `?` em
+    /// funções que leem ou escrevem pacotes em fluxos.
+    ///
+    /// Um [`PacketError::Io`] devolve o erro de E/S original quando não há outras
+    /// cópias dele, ou um erro do mesmo tipo que o tem como causa.
+    fn from(error: PacketError) -> Self {
+        match error {
+            PacketError::Io(IoError(inner)) => Arc::try_unwrap(inner).unwrap_or_else(|shared| {
+                let kind = shared.kind();
+                io::Error::new(kind, PacketError::Io(IoError(shared)))
+            }),
+            error => io::Error::new(io::ErrorKind::InvalidData, error),
+        }
+    }
+}
@@ -0,0 +1,407 @@
+//! # Módulo `fec`
+//!
+//! Redundância e correção de erros (FEC) para mensagens não confiáveis, que não podem
+//! esperar uma retransmissão.
+//!
+//! Retransmitir custa pelo menos um RTT, tempo demais para as entradas do jogador.
+//! [`Redundancy`] repete em cada pacote as últimas mensagens enviadas no canal, com a
+//! flag [`PacketFlags::REDUNDANT`]: uma mensagem só se perde se todos os pacotes que a
+//! levam se perdem. [`Packet::unwrap_redundant`] separa as mensagens de um pacote
+//! recebido, das mais antigas à do próprio pacote.
+//!
+//! [`FecEncoder`] agrupa as mensagens em sequências consecutivas e, ao completar um
+//! grupo, gera um pacote de paridade com a flag [`PacketFlags::PARITY`]: o XOR do tipo,
+//! do tamanho e do payload de cada mensagem. Com a paridade, o [`FecDecoder`] do outro
+//! lado reconstrói uma mensagem perdida por grupo, sem retransmissão.
+//!
+//! Os dois mecanismos podem ser combinados, e o [`Channel`](crate::channel::Channel)
+//! os aplica aos modos não confiáveis com
+//! [`Channel::with_redundancy`](crate::channel::Channel::with_redundancy) e
+//! [`Channel::with_fec`](crate::channel::Channel::with_fec). As mensagens
+//! reconstruídas ou repetidas recebem o cabeçalho do pacote que as levou, com a própria
+//! sequência e o próprio tipo de mensagem.
+//!
+//! ## Exemplos
+//! ```rust
+//! use packet::Packet;
+//! use packet::fec::{FecDecoder, FecEncoder, Redundancy};
+//!
+//! let mut redundancy = Redundancy::new(2);
+//! let mut encoder = FecEncoder::new(3);
+//! let mut sent = Vec::new();
+//! let mut parity = None;
+//! for sequence in 0..3 {
+//!     let message = Packet::new(4, sequence, 7, vec![sequence as u8; 4]);
+//!     parity = parity.or(encoder.push(&message));
+//!     sent.push(redundancy.wrap(message));
+//! }
+//!
+//! // O terceiro pacote leva também as duas mensagens anteriores.
+//! let messages = sent[2].unwrap_redundant().unwrap();
+//! let sequences: Vec<_> = messages.iter().map(|m| m.header.sequence).collect();
+//! assert_eq!(sequences, [0, 1, 2]);
+//!
+//! // Sem a mensagem 1, a paridade do grupo a reconstrói.
+//! let mut decoder = FecDecoder::new();
+//! decoder.record(&Packet::new(4, 0, 7, vec![0; 4]));
+//! decoder.record(&Packet::new(4, 2, 7, vec![2; 4]));
+//! let recovered = decoder.recover(&parity.unwrap()).unwrap().unwrap();
+//! assert_eq!((recovered.header.sequence, recovered.payload), (1, vec![1; 4]));
+//! ```
+
+use std::collections::VecDeque;
+
+use crate::sequence::{sequence_less_than, SequenceBuffer};
+use crate::{Packet, PacketError, PacketFlags};
+
+/// Quantidade máxima de mensagens anteriores repetidas por [`Redundancy`].
+pub const MAX_REDUNDANCY: usize = u8::MAX as usize;
+
+/// Quantidade máxima de mensagens em um grupo de [`FecEncoder`].
+pub const MAX_FEC_GROUP: usize = u8::MAX as usize;
+
+/// Quantidade padrão de mensagens recentes guardadas por [`FecDecoder`].
+pub const DEFAULT_FEC_WINDOW: usize = 256;
+
+/// Bytes antes do payload de cada mensagem repetida: distância, tipo e tamanho.
+const REDUNDANT_ENTRY_HEADER: usize = 4;
+
+/// Bytes antes do payload de cada mensagem na paridade: tipo e tamanho.
+const PARITY_RECORD_HEADER: usize = 5;
+
+/// Mensagem lembrada para ser repetida.
+#[derive(Debug, Clone)]
+struct Recent {
+    /// Sequência da mensagem.
+    sequence: u32,
+    /// Tipo da mensagem.
+    message_type: u8,
+    /// Payload da mensagem.
+    payload: Vec<u8>,
+}
+
+/// Repetição das últimas mensagens enviadas em cada pacote.
+///
+/// O payload de um pacote com a flag [`PacketFlags::REDUNDANT`] começa com a quantidade
+/// de mensagens repetidas, seguida de cada uma, da mais antiga à mais nova: a distância
+/// da sua sequência à do pacote, o tipo, o tamanho em `u16` little-endian e o payload;
+/// o restante é o payload do próprio pacote. Mensagens maiores que `u16::MAX` bytes não
+/// são repetidas.
+#[derive(Debug, Clone)]
+pub struct Redundancy {
+    /// Quantidade de mensagens anteriores repetidas.
+    depth: usize,
+    /// Últimas mensagens enviadas, da mais antiga à mais nova.
+    recent: VecDeque<Recent>,
+}
+
+impl Redundancy {
+    /// Cria a repetição sem mensagens anteriores.
+    ///
+    /// ## Parâmetros
+    /// - `depth`: Quantidade de mensagens anteriores repetidas em cada pacote; no
+    ///   máximo [`MAX_REDUNDANCY`]. Cada uma acrescenta ao pacote o seu payload e 4
+    ///   bytes.
+    ///
+    /// ## Retorno
+    /// - `Self`: A repetição.
+    pub fn new(depth: usize) -> Self {
+        Self {
+            depth: depth.min(MAX_REDUNDANCY),
+            recent: VecDeque::new(),
+        }
+    }
+
+    /// Retorna a quantidade de mensagens anteriores repetidas.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Acrescenta ao pacote as mensagens anteriores e o lembra para os próximos.
+    ///
+    /// ## Parâmetros
+    /// - `packet`: Pacote a ser enviado, já com a sequência do canal.
+    ///
+    /// ## Retorno
+    /// - `Packet`: O pacote com a flag [`PacketFlags::REDUNDANT`] e as mensagens
+    ///   anteriores no início do payload, ou o próprio pacote se `depth` é `0`.
+    pub fn wrap(&mut self, mut packet: Packet) -> Packet {
+        if self.depth == 0 {
+            return packet;
+        }
+        let sequence = packet.header.sequence;
+        let entries: Vec<_> = self
+            .recent
+            .iter()
+            .filter(|recent| sequence_less_than(recent.sequence, sequence))
+            .filter(|recent| sequence.wrapping_sub(recent.sequence) <= u8::MAX as u32)
+            .collect();
+
+        let mut payload = vec![entries.len() as u8];
+        for recent in &entries {
+            payload.push(sequence.wrapping_sub(recent.sequence) as u8);
+            payload.push(recent.message_type);
+            payload.extend_from_slice(&(recent.payload.len() as u16).to_le_bytes());
+            payload.extend_from_slice(&recent.payload);
+        }
+        payload.extend_from_slice(&packet.payload);
+
+        if packet.payload.len() <= u16::MAX as usize {
+            if self.recent.len() == self.depth {
+                self.recent.pop_front();
+            }
+            self.recent.push_back(Recent {
+                sequence,
+                message_type: packet.header.message_type,
+                payload: packet.payload.clone(),
+            });
+        }
+        packet.header.flags.insert(PacketFlags::REDUNDANT);
+        packet.payload = payload;
+        packet.refresh_header();
+        packet
+    }
+}
+
+impl Packet {
+    /// Separa as mensagens de um pacote montado por [`Redundancy::wrap`].
+    ///
+    /// ## Retorno
+    /// - `Result<Vec<Packet>, PacketError>`: As mensagens repetidas, da mais antiga à
+    ///   mais nova, seguidas do próprio pacote, todas sem a flag
+    ///   [`PacketFlags::REDUNDANT`]; só o próprio pacote se ele não tem a flag; ou
+    ///   [`PacketError::InvalidPayload`] se as mensagens estão truncadas.
+    pub fn unwrap_redundant(&self) -> Result<Vec<Packet>, PacketError> {
+        if !self.header.flags.contains(PacketFlags::REDUNDANT) {
+            return Ok(vec![self.clone()]);
+        }
+        let invalid = PacketError::InvalidPayload {
+            reason: "mensagens repetidas truncadas",
+        };
+        let (&count, mut rest) = self.payload.split_first().ok_or(invalid.clone())?;
+
+        let mut messages = Vec::with_capacity(count as usize + 1);
+        for _ in 0..count {
+            if rest.len() < REDUNDANT_ENTRY_HEADER {
+                return Err(invalid);
+            }
+            let back = rest[0];
+            let message_type = rest[1];
+            let len = u16::from_le_bytes([rest[2], rest[3]]) as usize;
+            let payload = rest[REDUNDANT_ENTRY_HEADER..]
+                .get(..len)
+                .ok_or(invalid.clone())?;
+            let sequence = self.header.sequence.wrapping_sub(u32::from(back));
+            messages.push(self.carried(sequence, message_type, payload.to_vec()));
+            rest = &rest[REDUNDANT_ENTRY_HEADER + len..];
+        }
+        let (sequence, message_type) = (self.header.sequence, self.header.message_type);
+        messages.push(self.carried(sequence, message_type, rest.to_vec()));
+        Ok(messages)
+    }
+
+    /// Monta uma mensagem levada por este pacote, com o seu cabeçalho sem as flags de
+    /// redundância e paridade.
+    fn carried(&self, sequence: u32, message_type: u8, payload: Vec<u8>) -> Packet {
+        let mut message = Packet {
+            header: self.header.clone(),
+            payload,
+        };
+        message.header.sequence = sequence;
+        message.header.message_type = message_type;
+        message.header.flags.remove(PacketFlags::REDUNDANT);
+        message.header.flags.remove(PacketFlags::PARITY);
+        message.refresh_header();
+        message
+    }
+}
+
+/// Acumula o XOR do registro de uma mensagem na paridade.
+fn xor_record(parity: &mut Vec<u8>, message_type: u8, payload: &[u8]) {
+    let len = (payload.len() as u32).to_le_bytes();
+    let record = [message_type]
+        .into_iter()
+        .chain(len)
+        .chain(payload.iter().copied());
+    let size = PARITY_RECORD_HEADER + payload.len();
+    if parity.len() < size {
+        parity.resize(size, 0);
+    }
+    for (byte, value) in parity.iter_mut().zip(record) {
+        *byte ^= value;
+    }
+}
+
+/// Geração dos pacotes de paridade de grupos de mensagens consecutivas.
+///
+/// O pacote de paridade tem o cabeçalho da última mensagem do grupo, com a sequência da
+/// primeira e a flag [`PacketFlags::PARITY`]; o payload é a quantidade de mensagens do
+/// grupo seguida do XOR dos seus registros: tipo, tamanho em `u32` little-endian e
+/// payload, completados com zeros até o maior.
+#[derive(Debug, Clone)]
+pub struct FecEncoder {
+    /// Quantidade de mensagens por grupo.
+    group_size: usize,
+    /// Sequência da primeira mensagem do grupo em andamento.
+    first: u32,
+    /// Mensagens do grupo em andamento.
+    count: usize,
+    /// XOR dos registros do grupo em andamento.
+    parity: Vec<u8>,
+}
+
+impl FecEncoder {
+    /// Cria o gerador sem grupo em andamento.
+    ///
+    /// ## Parâmetros
+    /// - `group_size`: Mensagens por grupo, de `2` a [`MAX_FEC_GROUP`]. Grupos menores
+    ///   toleram mais perdas, ao custo de mais pacotes de paridade.
+    ///
+    /// ## Retorno
+    /// - `Self`: O gerador.
+    pub fn new(group_size: usize) -> Self {
+        Self {
+            group_size: group_size.clamp(2, MAX_FEC_GROUP),
+            first: 0,
+            count: 0,
+            parity: Vec::new(),
+        }
+    }
+
+    /// Retorna a quantidade de mensagens por grupo.
+    pub fn group_size(&self) -> usize {
+        self.group_size
+    }
+
+    /// Acrescenta uma mensagem enviada ao grupo em andamento.
+    ///
+    /// Uma mensagem fora da sequência do grupo descarta o grupo e inicia outro.
+    ///
+    /// ## Parâmetros
+    /// - `message`: Mensagem enviada, antes de [`Redundancy::wrap`].
+    ///
+    /// ## Retorno
+    /// - `Option<Packet>`: O pacote de paridade, a ser enviado depois da mensagem, se
+    ///   ela completou o grupo.
+    pub fn push(&mut self, message: &Packet) -> Option<Packet> {
+        let sequence = message.header.sequence;
+        if self.count > 0 && sequence != self.first.wrapping_add(self.count as u32) {
+            self.count = 0;
+            self.parity.clear();
+        }
+        if self.count == 0 {
+            self.first = sequence;
+        }
+        xor_record(
+            &mut self.parity,
+            message.header.message_type,
+            &message.payload,
+        );
+        self.count += 1;
+        if self.count < self.group_size {
+            return None;
+        }
+
+        let mut payload = vec![self.count as u8];
+        payload.append(&mut self.parity);
+        self.count = 0;
+        let mut parity = message.carried(self.first, message.header.message_type, payload);
+        parity.header.flags.insert(PacketFlags::PARITY);
+        parity.refresh_header();
+        Some(parity)
+    }
+}
+
+/// Reconstrução das mensagens perdidas a partir dos pacotes de paridade.
+#[derive(Debug, Clone)]
+pub struct FecDecoder {
+    /// Tipo e payload das mensagens recentes recebidas ou reconstruídas.
+    received: SequenceBuffer<(u8, Vec<u8>)>,
+}
+
+impl Default for FecDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FecDecoder {
+    /// Cria o reconstrutor, lembrando as últimas [`DEFAULT_FEC_WINDOW`] mensagens.
+    pub fn new() -> Self {
+        Self::with_window(DEFAULT_FEC_WINDOW)
+    }
+
+    /// Cria o reconstrutor lembrando as últimas `window` mensagens.
+    ///
+    /// ## Parâmetros
+    /// - `window`: Quantidade de sequências lembradas; deve cobrir pelo menos um grupo
+    ///   e o atraso da paridade.
+    ///
+    /// ## Retorno
+    /// - `Self`: O reconstrutor.
+    pub fn with_window(window: usize) -> Self {
+        Self {
+            received: SequenceBuffer::new(window),
+        }
+    }
+
+    /// Lembra uma mensagem recebida, para reconstruir as outras do seu grupo.
+    ///
+    /// ## Parâmetros
+    /// - `message`: Mensagem recebida, depois de [`Packet::unwrap_redundant`].
+    pub fn record(&mut self, message: &Packet) {
+        self.received.insert(
+            message.header.sequence,
+            (message.header.message_type, message.payload.clone()),
+        );
+    }
+
+    /// Reconstrói a mensagem perdida do grupo de um pacote de paridade.
+    ///
+    /// ## Parâmetros
+    /// - `parity`: Pacote de paridade recebido.
+    ///
+    /// ## Retorno
+    /// - `Result<Option<Packet>, PacketError>`: A mensagem reconstruída, também lembrada
+    ///   pelo reconstrutor, se só ela faltava no grupo; `None` se não falta nenhuma ou
+    ///   falta mais de uma; ou [`PacketError::InvalidPayload`] se o pacote não é uma
+    ///   paridade válida.
+    pub fn recover(&mut self, parity: &Packet) -> Result<Option<Packet>, PacketError> {
+        let invalid = PacketError::InvalidPayload {
+            reason: "paridade malformada",
+        };
+        if !parity.header.flags.contains(PacketFlags::PARITY) {
+            return Err(invalid);
+        }
+        let (&count, xor) = parity.payload.split_first().ok_or(invalid.clone())?;
+        let first = parity.header.sequence;
+        let mut missing = (0..u32::from(count))
+            .map(|offset| first.wrapping_add(offset))
+            .filter(|sequence| !self.received.exists(*sequence));
+        let (Some(sequence), None) = (missing.next(), missing.next()) else {
+            return Ok(None);
+        };
+
+        let mut record = xor.to_vec();
+        for offset in 0..u32::from(count) {
+            if let Some((message_type, payload)) = self.received.get(first.wrapping_add(offset)) {
+                if record.len() < PARITY_RECORD_HEADER + payload.len() {
+                    return Err(invalid);
+                }
+                xor_record(&mut record, *message_type, payload);
+            }
+        }
+        if record.len() < PARITY_RECORD_HEADER {
+            return Err(invalid);
+        }
+        let len = u32::from_le_bytes([record[1], record[2], record[3], record[4]]) as usize;
+        let payload = record[PARITY_RECORD_HEADER..]
+            .get(..len)
+            .ok_or(invalid)?
+            .to_vec();
+
+        let recovered = parity.carried(sequence, record[0], payload);
+        self.record(&recovered);
+        Ok(Some(recovered))
+    }
+}
@@ -0,0 +1,162 @@
+//! # Módulo `flags`
+//!
+//! Define [`PacketFlags`], o campo de bits do cabeçalho que marca opções do pacote.
+
+use core::ops::{BitAnd, BitOr, BitOrAssign};
+
+/// Conjunto de flags de um pacote, serializado em dois bytes (little-endian).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct PacketFlags(u16);
+
+impl PacketFlags {
+    /// O pacote deve ser confirmado e retransmitido até ser entregue.
+    pub const RELIABLE: Self = Self(1 << 0);
+    /// O payload é um fragmento de uma mensagem maior.
+    pub const FRAGMENT: Self = Self(1 << 1);
+    /// Os campos `ack` e `ack_bits` do cabeçalho carregam confirmações válidas.
+    pub const ACK: Self = Self(1 << 2);
+    /// O checksum foi calculado com CRC-32 em vez da soma de bytes
+    /// (veja [`ChecksumAlgorithm`](crate::checksum::ChecksumAlgorithm)).
+    pub const CRC32: Self = Self(1 << 3);
+    /// O checksum cobre também o cabeçalho
+    /// (veja [`ChecksumScope`](crate::checksum::ChecksumScope)).
+    pub const HEADER_CHECKSUM: Self = Self(1 << 4);
+    /// O checksum não foi calculado e não deve ser verificado
+    /// (veja [`ChecksumScope::None`](crate::checksum::ChecksumScope::None)).
+    pub const NO_CHECKSUM: Self = Self(1 << 5);
+    /// O payload começa com uma área de opções TLV
+    /// (veja [`options`](crate::options)).
+    pub const OPTIONS: Self = Self(1 << 6);
+    /// Junto de [`PacketFlags::CRC32`], o CRC-32 usa a variante de Castagnoli
+    /// (veja [`Crc32Variant`](crate::checksum::Crc32Variant)).
+    pub const CRC32C: Self = Self(1 << 7);
+    /// Os últimos bytes do payload são uma assinatura Ed25519 (veja o módulo `sign`,
+    /// disponível com o recurso `crypto`).
+    pub const SIGNED: Self = Self(1 << 8);
+    /// Os dados da aplicação estão comprimidos
+    /// (veja [`compression`](crate::compression)).
+    pub const COMPRESSED: Self = Self(1 << 9);
+    /// O payload está cifrado e termina com uma etiqueta de autenticação (veja o módulo
+    /// `encrypt`, disponível com o recurso `crypto`).
+    pub const ENCRYPTED: Self = Self(1 << 10);
+    /// O checksum foi calculado com xxHash32
+    /// (veja [`ChecksumAlgorithm::XxHash32`](crate::checksum::ChecksumAlgorithm::XxHash32)).
+    pub const XXHASH32: Self = Self(1 << 11);
+    /// O payload termina com uma etiqueta HMAC-SHA256 truncada (veja o módulo `mac`,
+    /// disponível com o recurso `crypto`).
+    pub const AUTHENTICATED: Self = Self(1 << 12);
+    /// O payload leva também as mensagens anteriores do canal (veja
+    /// [`Redundancy`](crate::fec::Redundancy)).
+    pub const REDUNDANT: Self = Self(1 << 13);
+    /// O pacote é a paridade XOR de um grupo de mensagens do canal (veja
+    /// [`FecEncoder`](crate::fec::FecEncoder)).
+    pub const PARITY: Self = Self(1 << 14);
+
+    /// Flags conhecidas e seus nomes, na ordem dos bits.
+    const NAMED: [(&'static str, Self); 15] = [
+        ("RELIABLE", Self::RELIABLE),
+        ("FRAGMENT", Self::FRAGMENT),
+        ("ACK", Self::ACK),
+        ("CRC32", Self::CRC32),
+        ("HEADER_CHECKSUM", Self::HEADER_CHECKSUM),
+        ("NO_CHECKSUM", Self::NO_CHECKSUM),
+        ("OPTIONS", Self::OPTIONS),
+        ("CRC32C", Self::CRC32C),
+        ("SIGNED", Self::SIGNED),
+        ("COMPRESSED", Self::COMPRESSED),
+        ("ENCRYPTED", Self::ENCRYPTED),
+        ("XXHASH32", Self::XXHASH32),
+        ("AUTHENTICATED", Self::AUTHENTICATED),
+        ("REDUNDANT", Self::REDUNDANT),
+        ("PARITY", Self::PARITY),
+    ];
+
+    /// Retorna um conjunto sem nenhuma flag.
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Constrói o conjunto a partir do campo do cabeçalho, preservando bits desconhecidos.
+    ///
+    /// ## Parâmetros
+    /// - `bits`: Campo de flags lido do cabeçalho.
+    ///
+    /// ## Retorno
+    /// - `Self`: O conjunto de flags correspondente.
+    pub const fn from_bits_retain(bits: u16) -> Self {
+        Self(bits)
+    }
+
+    /// Retorna o valor que representa o conjunto no cabeçalho.
+    pub const fn bits(self) -> u16 {
+        self.0
+    }
+
+    /// Indica se nenhuma flag está ativa.
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Indica se todas as flags de `other` estão ativas.
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Ativa as flags de `other`.
+    pub fn insert(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+
+    /// Desativa as flags de `other`.
+    pub fn remove(&mut self, other: Self) {
+        self.0 &= !other.0;
+    }
+
+    /// Retorna os nomes das flags conhecidas que estão ativas.
+    ///
+    /// ## Retorno
+    /// - `impl Iterator<Item = &'static str>`: Os nomes das constantes, na ordem dos
+    ///   bits. Bits desconhecidos são ignorados; veja [`PacketFlags::unknown_bits`].
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::PacketFlags;
+    ///
+    /// let flags = PacketFlags::ACK | PacketFlags::RELIABLE;
+    /// assert_eq!(flags.names().collect::<Vec<_>>(), ["RELIABLE", "ACK"]);
+    /// ```
+    pub fn names(self) -> impl Iterator<Item = &'static str> {
+        Self::NAMED
+            .into_iter()
+            .filter(move |(_, flag)| self.contains(*flag))
+            .map(|(name, _)| name)
+    }
+
+    /// Retorna os bits ativos que não correspondem a nenhuma flag conhecida.
+    pub fn unknown_bits(self) -> u16 {
+        let known = Self::NAMED.iter().fold(0, |bits, (_, flag)| bits | flag.0);
+        self.0 & !known
+    }
+}
+
+impl BitOr for PacketFlags {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for PacketFlags {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl BitAnd for PacketFlags {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        Self(self.0 & rhs.0)
+    }
+}
@@ -0,0 +1,325 @@
+//! # Módulo `fragment`
+//!
+//! Divisão de pacotes maiores que o MTU em fragmentos e sua remontagem.
+//!
+//! Cada fragmento é um pacote com o mesmo cabeçalho do original, acrescido da flag
+//! [`PacketFlags::FRAGMENT`]. O payload do fragmento começa com um cabeçalho de
+//! [`FRAGMENT_HEADER_SIZE`] bytes, seguido de um trecho do payload original:
+//!
+//! | Offset | Tamanho | Campo                                    |
+//! |--------|---------|------------------------------------------|
+//! | `0`    | `2`     | Índice do fragmento (little-endian)      |
+//! | `2`    | `2`     | Quantidade de fragmentos (little-endian) |
+//!
+//! Todos os fragmentos de um pacote compartilham a sequência do original, que
+//! identifica o grupo na remontagem por um [`Reassembler`].
+//!
+//! ## Exemplos
+//! ```rust
+//! use packet::{Packet, PacketFlags};
+//!
+//! let packet = Packet::new(1, 9, 7, vec![0xAB; 10]);
+//! let fragments = packet.fragment(8).unwrap();
+//!
+//! assert_eq!(fragments.len(), 3);
+//! assert!(fragments.iter().all(|f| f.header.flags.contains(PacketFlags::FRAGMENT)));
+//! assert!(fragments.iter().all(|f| f.header.sequence == 9 && f.verify_checksum()));
+//! assert_eq!(fragments[2].payload, [2, 0, 3, 0, 0xAB, 0xAB]);
+//! ```
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::config::ParseConfig;
+use crate::{instrument, Packet, PacketError, PacketFlags, PacketHeader};
+
+/// Tamanho, em bytes, do cabeçalho no início do payload de cada fragmento.
+pub const FRAGMENT_HEADER_SIZE: usize = 4;
+
+/// Posição de um fragmento no pacote original, lida por [`Packet::fragment_info`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FragmentInfo {
+    /// Índice do fragmento, a partir de `0`.
+    pub index: u16,
+    /// Quantidade de fragmentos do pacote original.
+    pub count: u16,
+    /// Sequência do pacote original, compartilhada por todos os seus fragmentos.
+    pub group_sequence: u32,
+}
+
+impl Packet {
+    /// Divide o pacote em fragmentos cujo payload não excede `max_payload` bytes.
+    ///
+    /// Um pacote que já cabe no limite é retornado sem alterações, como único elemento.
+    ///
+    /// ## Parâmetros
+    /// - `max_payload`: Tamanho máximo do payload de cada fragmento, incluindo o
+    ///   cabeçalho de fragmento.
+    ///
+    /// ## Retorno
+    /// - `Result<Vec<Packet>, PacketError>`: Os fragmentos, em ordem de índice.
+    ///   [`PacketError::InvalidPayloadLength`] se `max_payload` não comporta o cabeçalho
+    ///   de fragmento e ao menos um byte, ou [`PacketError::PayloadTooLarge`] se seriam
+    ///   necessários mais de `u16::MAX` fragmentos.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::Packet;
+    ///
+    /// let small = Packet::new(1, 1, 7, vec![1, 2, 3]);
+    /// assert_eq!(small.fragment(100).unwrap(), vec![small.clone()]);
+    ///
+    /// assert!(Packet::new(1, 1, 7, vec![0; 10]).fragment(4).is_err());
+    /// ```
+    pub fn fragment(&self, max_payload: usize) -> Result<Vec<Packet>, PacketError> {
+        if self.payload.len() <= max_payload {
+            return Ok(vec![self.clone()]);
+        }
+        if max_payload <= FRAGMENT_HEADER_SIZE {
+            return Err(PacketError::InvalidPayloadLength {
+                requested: max_payload,
+                actual: self.payload.len(),
+            });
+        }
+
+        let chunk_size = max_payload - FRAGMENT_HEADER_SIZE;
+        let count = self.payload.len().div_ceil(chunk_size);
+        let count = u16::try_from(count).map_err(|_| PacketError::PayloadTooLarge {
+            size: self.payload.len(),
+            limit: u16::MAX as usize * chunk_size,
+        })?;
+
+        let fragments = self
+            .payload
+            .chunks(chunk_size)
+            .enumerate()
+            .map(|(index, chunk)| {
+                let mut payload = Vec::with_capacity(FRAGMENT_HEADER_SIZE + chunk.len());
+                payload.extend_from_slice(&(index as u16).to_le_bytes());
+                payload.extend_from_slice(&count.to_le_bytes());
+                payload.extend_from_slice(chunk);
+
+                let mut fragment = Packet {
+                    header: self.header.clone(),
+                    payload,
+                };
+                fragment.header.flags.insert(PacketFlags::FRAGMENT);
+                fragment.refresh_header();
+                fragment
+            })
+            .collect();
+        Ok(fragments)
+    }
+
+    /// Indica se o pacote é um fragmento, pela flag [`PacketFlags::FRAGMENT`].
+    pub fn is_fragment(&self) -> bool {
+        self.header.flags.contains(PacketFlags::FRAGMENT)
+    }
+
+    /// Lê a posição do fragmento no pacote original.
+    ///
+    /// ## Retorno
+    /// - `Option<FragmentInfo>`: O índice, a quantidade e a sequência do grupo, ou
+    ///   `None` se o pacote não é um fragmento ou se seu payload não comporta o
+    ///   cabeçalho de fragmento.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::Packet;
+    /// use packet::fragment::FragmentInfo;
+    ///
+    /// let packet = Packet::new(1, 9, 7, vec![0; 10]);
+    /// assert!(!packet.is_fragment());
+    /// assert_eq!(packet.fragment_info(), None);
+    ///
+    /// let fragments = packet.fragment(8).unwrap();
+    /// assert!(fragments[1].is_fragment());
+    /// assert_eq!(
+    ///     fragments[1].fragment_info(),
+    ///     Some(FragmentInfo { index: 1, count: 3, group_sequence: 9 })
+    /// );
+    /// ```
+    pub fn fragment_info(&self) -> Option<FragmentInfo> {
+        if !self.is_fragment() {
+            return None;
+        }
+        let header = self.payload.get(..FRAGMENT_HEADER_SIZE)?;
+        Some(FragmentInfo {
+            index: u16::from_le_bytes([header[0], header[1]]),
+            count: u16::from_le_bytes([header[2], header[3]]),
+            group_sequence: self.header.sequence,
+        })
+    }
+}
+
+/// Fragmentos recebidos de um mesmo pacote original.
+#[derive(Debug)]
+struct FragmentGroup {
+    /// Cabeçalho do primeiro fragmento recebido.
+    header: PacketHeader,
+    /// Trechos do payload original, por índice.
+    chunks: Vec<Option<Vec<u8>>>,
+    /// Quantidade de trechos já recebidos.
+    received: usize,
+    /// Instante em que o primeiro fragmento foi recebido.
+    started_at: Instant,
+}
+
+/// Remonta pacotes divididos por [`Packet::fragment`].
+///
+/// Os fragmentos são agrupados pelo jogador e pela sequência do original e podem
+/// chegar em qualquer ordem. Grupos incompletos ocupam memória até serem descartados
+/// por [`Reassembler::expire`], que deve ser chamado periodicamente.
+///
+/// ## Exemplos
+/// ```rust
+/// use std::time::{Duration, Instant};
+/// use packet::Packet;
+/// use packet::fragment::Reassembler;
+///
+/// let packet = Packet::new(1, 9, 7, (0..10).collect());
+/// let mut fragments = packet.fragment(8).unwrap();
+/// fragments.reverse();
+///
+/// let mut reassembler = Reassembler::new(Duration::from_secs(1));
+/// let now = Instant::now();
+/// assert_eq!(reassembler.insert(fragments[0].clone(), now), Ok(None));
+/// assert_eq!(reassembler.insert(fragments[1].clone(), now), Ok(None));
+/// assert_eq!(reassembler.insert(fragments[2].clone(), now), Ok(Some(packet)));
+/// assert_eq!(reassembler.pending_groups(), 0);
+///
+/// // Um grupo que não se completa dentro do tempo limite é descartado.
+/// reassembler.insert(fragments[0].clone(), now).unwrap();
+/// assert_eq!(reassembler.expire(now + Duration::from_secs(1)), 1);
+/// assert_eq!(reassembler.pending_groups(), 0);
+/// ```
+#[derive(Debug)]
+pub struct Reassembler {
+    /// Tempo máximo para que um grupo se complete.
+    timeout: Duration,
+    /// Grupos incompletos, indexados por jogador e sequência.
+    groups: HashMap<(u64, u32), FragmentGroup>,
+    /// Limites dos fragmentos aceitos.
+    limits: ParseConfig,
+}
+
+impl Reassembler {
+    /// Cria um remontador sem grupos pendentes, com os limites de
+    /// [`ParseConfig::default`].
+    ///
+    /// ## Parâmetros
+    /// - `timeout`: Tempo, a partir do primeiro fragmento, após o qual um grupo
+    ///   incompleto é descartado.
+    ///
+    /// ## Retorno
+    /// - `Self`: Um remontador vazio.
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            groups: HashMap::new(),
+            limits: ParseConfig::default(),
+        }
+    }
+
+    /// Define os limites dos fragmentos aceitos.
+    ///
+    /// Cada fragmento é verificado por [`ParseConfig::check`], o que limita um grupo a
+    /// [`ParseConfig::max_fragments`] trechos de até [`ParseConfig::max_payload`] bytes.
+    ///
+    /// ## Parâmetros
+    /// - `limits`: Limites da leitura.
+    ///
+    /// ## Retorno
+    /// - `Self`: O remontador com os novos limites.
+    pub fn with_parse_config(mut self, limits: ParseConfig) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Acrescenta um pacote recebido, retornando o original quando o grupo se completa.
+    ///
+    /// Pacotes que não são fragmentos são retornados sem alterações, e fragmentos
+    /// repetidos são ignorados.
+    ///
+    /// ## Parâmetros
+    /// - `packet`: Pacote recebido.
+    /// - `now`: Instante da recepção.
+    ///
+    /// ## Retorno
+    /// - `Result<Option<Packet>, PacketError>`: O pacote original, com o payload
+    ///   remontado e sem a flag [`PacketFlags::FRAGMENT`], ou `None` se faltam
+    ///   fragmentos. [`PacketError::InvalidPayload`] se o cabeçalho de fragmento é
+    ///   inválido ou diverge do grupo, ou o erro de [`ParseConfig::check`] se o
+    ///   fragmento excede os limites.
+    pub fn insert(&mut self, packet: Packet, now: Instant) -> Result<Option<Packet>, PacketError> {
+        if !packet.is_fragment() {
+            return Ok(Some(packet));
+        }
+        self.limits.check(&packet.header, &packet.payload)?;
+        let info = packet.fragment_info().ok_or(PacketError::InvalidPayload {
+            reason: "fragmento sem cabeçalho de fragmento",
+        })?;
+        if info.index >= info.count {
+            return Err(PacketError::InvalidPayload {
+                reason: "índice de fragmento fora da quantidade",
+            });
+        }
+
+        let key = (packet.header.player_id, info.group_sequence);
+        let group = self.groups.entry(key).or_insert_with(|| FragmentGroup {
+            header: packet.header.clone(),
+            chunks: vec![None; info.count as usize],
+            received: 0,
+            started_at: now,
+        });
+        if group.chunks.len() != info.count as usize {
+            return Err(PacketError::InvalidPayload {
+                reason: "quantidade de fragmentos diverge do grupo",
+            });
+        }
+
+        let chunk = &mut group.chunks[info.index as usize];
+        if chunk.is_none() {
+            *chunk = Some(packet.payload[FRAGMENT_HEADER_SIZE..].to_vec());
+            group.received += 1;
+        }
+        if group.received < group.chunks.len() {
+            return Ok(None);
+        }
+
+        let group = self.groups.remove(&key).expect("grupo inserido acima");
+        let fragment_count = group.chunks.len();
+        let mut original = Packet {
+            header: group.header,
+            payload: group.chunks.into_iter().flatten().flatten().collect(),
+        };
+        original.header.flags.remove(PacketFlags::FRAGMENT);
+        original.refresh_header();
+        instrument::reassembled(&original.header, fragment_count);
+        Ok(Some(original))
+    }
+
+    /// Descarta os grupos incompletos cujo tempo limite expirou.
+    ///
+    /// ## Parâmetros
+    /// - `now`: Instante atual.
+    ///
+    /// ## Retorno
+    /// - `usize`: Quantidade de grupos descartados.
+    pub fn expire(&mut self, now: Instant) -> usize {
+        let before = self.groups.len();
+        self.groups.retain(|_, group| {
+            let alive = now.saturating_duration_since(group.started_at) < self.timeout;
+            if !alive {
+                instrument::reassembly_expired(&group.header, group.received, group.chunks.len());
+            }
+            alive
+        });
+        before - self.groups.len()
+    }
+
+    /// Retorna a quantidade de grupos incompletos.
+    pub fn pending_groups(&self) -> usize {
+        self.groups.len()
+    }
+}
@@ -0,0 +1,300 @@
+//! # Módulo `framing`
+//!
+//! Estratégias de enquadramento que delimitam pacotes sobre diferentes transportes:
+//!
+//! - [`LengthPrefixFramer`]: prefixo de tamanho, para fluxos como TCP.
+//! - [`WireFormatFramer`]: prefixo de tamanho com a ordem de bytes de um
+//!   [`WireFormat`], para fluxos de outras implementações.
+//! - [`DatagramFramer`]: um pacote por datagrama, para UDP.
+//! - [`CobsFramer`]: COBS com delimitador zero, para linhas seriais.
+//!
+//! Os enquadramentos com prefixo de tamanho aplicam um [`ParseConfig`]: um prefixo que
+//! declara um pacote maior que o permitido é rejeitado assim que é lido, sem esperar
+//! nem acumular o restante do quadro. Como o fim do quadro é desconhecido, o buffer não
+//! é consumido e o fluxo deve ser encerrado.
+//!
+//! ## Exemplos
+//! ```rust
+//! use packet::Packet;
+//! use packet::framing::{Framer, LengthPrefixFramer};
+//!
+//! let framer = LengthPrefixFramer::new();
+//! let packet = Packet::new(1, 1, 7, vec![1, 2, 3]);
+//!
+//! let mut stream = Vec::new();
+//! framer.encode(&packet, &mut stream);
+//!
+//! // Um fluxo incompleto ainda não produz pacote.
+//! let mut partial = stream[..5].to_vec();
+//! assert_eq!(framer.decode(&mut partial).unwrap(), None);
+//!
+//! assert_eq!(framer.decode(&mut stream).unwrap(), Some(packet));
+//! assert!(stream.is_empty());
+//! ```
+
+use crate::cobs::{cobs_decode, cobs_encode, COBS_DELIMITER};
+use crate::config::ParseConfig;
+use crate::wire::WireFormat;
+use crate::{Packet, PacketError, PacketHeader};
+
+/// Estratégia de delimitação de pacotes em um meio de transporte.
+pub trait Framer {
+    /// Acrescenta o pacote enquadrado ao final de `out`.
+    ///
+    /// ## Parâmetros
+    /// - `packet`: Pacote a ser enquadrado.
+    /// - `out`: Buffer de saída.
+    fn encode(&self, packet: &Packet, out: &mut Vec<u8>);
+
+    /// Extrai o próximo pacote do início de `buf`, removendo os bytes consumidos.
+    ///
+    /// ## Parâmetros
+    /// - `buf`: Bytes recebidos e ainda não processados.
+    ///
+    /// ## Retorno
+    /// - `Result<Option<Packet>, PacketError>`: O pacote decodificado, `None` se ainda
+    ///   faltam bytes para completar um quadro, ou o erro encontrado no quadro.
+    fn decode(&self, buf: &mut Vec<u8>) -> Result<Option<Packet>, PacketError>;
+}
+
+/// Enquadra cada pacote com um prefixo de 4 bytes (little-endian) contendo seu tamanho.
+///
+/// ## Exemplos
+/// ```rust
+/// use packet::{Packet, PacketError, PacketHeader};
+/// use packet::config::ParseConfig;
+/// use packet::framing::{Framer, LengthPrefixFramer};
+///
+/// let limits = ParseConfig {
+///     max_payload: 16,
+///     ..ParseConfig::default()
+/// };
+/// let framer = LengthPrefixFramer::new().with_parse_config(limits);
+///
+/// // O prefixo de um quadro de 4 GiB é rejeitado antes de chegar o restante.
+/// let mut stream = u32::MAX.to_le_bytes().to_vec();
+/// assert_eq!(
+///     framer.decode(&mut stream),
+///     Err(PacketError::PacketTooLarge {
+///         size: u32::MAX as usize,
+///         limit: PacketHeader::SIZE + 16,
+///     })
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LengthPrefixFramer {
+    /// Limites dos pacotes aceitos.
+    limits: ParseConfig,
+}
+
+impl LengthPrefixFramer {
+    /// Tamanho do prefixo, em bytes.
+    pub const PREFIX_SIZE: usize = 4;
+
+    /// Cria o enquadramento com os limites de [`ParseConfig::default`].
+    ///
+    /// ## Retorno
+    /// - `Self`: O enquadramento.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Define os limites dos pacotes aceitos.
+    ///
+    /// ## Parâmetros
+    /// - `limits`: Limites da leitura; o prefixo não pode declarar mais que
+    ///   [`PacketHeader::SIZE`] somado a [`ParseConfig::max_payload`] bytes.
+    ///
+    /// ## Retorno
+    /// - `Self`: O enquadramento com os novos limites.
+    pub fn with_parse_config(mut self, limits: ParseConfig) -> Self {
+        self.limits = limits;
+        self
+    }
+}
+
+/// Verifica o tamanho declarado em um prefixo e retorna o fim do quadro no buffer.
+///
+/// ## Retorno
+/// - `Result<usize, PacketError>`: A posição logo após o quadro, ou
+///   [`PacketError::PacketTooLarge`] se `len` excede o maior pacote permitido por
+///   `limits`.
+fn frame_end(len: usize, limits: &ParseConfig) -> Result<usize, PacketError> {
+    let limit = PacketHeader::SIZE.saturating_add(limits.max_payload);
+    LengthPrefixFramer::PREFIX_SIZE
+        .checked_add(len)
+        .filter(|_| len <= limit)
+        .ok_or(PacketError::PacketTooLarge { size: len, limit })
+}
+
+impl Framer for LengthPrefixFramer {
+    fn encode(&self, packet: &Packet, out: &mut Vec<u8>) {
+        out.extend(&(packet.serialized_len() as u32).to_le_bytes());
+        packet.to_bytes_into(out);
+    }
+
+    fn decode(&self, buf: &mut Vec<u8>) -> Result<Option<Packet>, PacketError> {
+        if buf.len() < Self::PREFIX_SIZE {
+            return Ok(None);
+        }
+
+        let len = u32::from_le_bytes(buf[..Self::PREFIX_SIZE].try_into().unwrap()) as usize;
+        let end = frame_end(len, &self.limits)?;
+        if buf.len() < end {
+            return Ok(None);
+        }
+
+        let result = Packet::from_bytes_with_limits(&buf[Self::PREFIX_SIZE..end], &self.limits);
+        buf.drain(..end);
+        result.map(Some)
+    }
+}
+
+/// Enquadra cada pacote como o [`LengthPrefixFramer`], com o prefixo e o pacote em um
+/// formato da rede e os mesmos limites.
+///
+/// ## Exemplos
+/// ```rust
+/// use packet::Packet;
+/// use packet::framing::{Framer, WireFormatFramer};
+/// use packet::wire::WireFormat;
+///
+/// let framer = WireFormatFramer::new(WireFormat::NETWORK);
+/// let packet = Packet::new(1, 1, 7, vec![1, 2, 3]);
+///
+/// let mut stream = Vec::new();
+/// framer.encode(&packet, &mut stream);
+/// assert_eq!(stream[..4], (packet.serialized_len() as u32).to_be_bytes());
+/// assert_eq!(framer.decode(&mut stream).unwrap(), Some(packet));
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WireFormatFramer {
+    /// Formato do prefixo e dos pacotes.
+    format: WireFormat,
+    /// Limites dos pacotes aceitos.
+    limits: ParseConfig,
+}
+
+impl WireFormatFramer {
+    /// Cria o enquadramento em um formato.
+    ///
+    /// ## Parâmetros
+    /// - `format`: Formato do prefixo e dos pacotes; com [`WireFormat::STANDARD`], os
+    ///   quadros são os do [`LengthPrefixFramer`].
+    ///
+    /// ## Retorno
+    /// - `Self`: O enquadramento, com os limites de [`ParseConfig::default`].
+    pub fn new(format: WireFormat) -> Self {
+        Self {
+            format,
+            limits: ParseConfig::default(),
+        }
+    }
+
+    /// Define os limites dos pacotes aceitos, como em
+    /// [`LengthPrefixFramer::with_parse_config`].
+    ///
+    /// ## Parâmetros
+    /// - `limits`: Limites da leitura.
+    ///
+    /// ## Retorno
+    /// - `Self`: O enquadramento com os novos limites.
+    pub fn with_parse_config(mut self, limits: ParseConfig) -> Self {
+        self.limits = limits;
+        self
+    }
+}
+
+impl Framer for WireFormatFramer {
+    fn encode(&self, packet: &Packet, out: &mut Vec<u8>) {
+        let len = packet.serialized_len() as u32;
+        out.extend(&self.format.endianness.u32_bytes(len));
+        out.extend(packet.to_bytes_with_format(self.format));
+    }
+
+    fn decode(&self, buf: &mut Vec<u8>) -> Result<Option<Packet>, PacketError> {
+        let prefix = LengthPrefixFramer::PREFIX_SIZE;
+        if buf.len() < prefix {
+            return Ok(None);
+        }
+
+        let len = self
+            .format
+            .endianness
+            .read_u32(buf[..prefix].try_into().unwrap()) as usize;
+        let end = frame_end(len, &self.limits)?;
+        if buf.len() < end {
+            return Ok(None);
+        }
+
+        let result =
+            Packet::from_bytes_with_format(&buf[prefix..end], self.format).and_then(|packet| {
+                self.limits
+                    .check(&packet.header, &packet.payload)
+                    .map(|()| packet)
+            });
+        buf.drain(..end);
+        result.map(Some)
+    }
+}
+
+/// Trata cada buffer como exatamente um pacote, como em um datagrama UDP.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DatagramFramer;
+
+impl Framer for DatagramFramer {
+    fn encode(&self, packet: &Packet, out: &mut Vec<u8>) {
+        packet.to_bytes_into(out);
+    }
+
+    fn decode(&self, buf: &mut Vec<u8>) -> Result<Option<Packet>, PacketError> {
+        if buf.is_empty() {
+            return Ok(None);
+        }
+
+        let result = Packet::from_bytes(buf);
+        buf.clear();
+        result.map(Some)
+    }
+}
+
+/// Codifica cada pacote com [`cobs_encode`], terminando-o com um byte zero
+/// delimitador, o que permite recuperar os limites dos pacotes em um fluxo de
+/// bytes serial.
+///
+/// ## Exemplos
+/// ```rust
+/// use packet::Packet;
+/// use packet::framing::{CobsFramer, Framer};
+///
+/// let framer = CobsFramer;
+/// let first = Packet::new(1, 1, 7, vec![0, 1, 0]);
+/// let second = Packet::new(1, 2, 7, vec![]);
+///
+/// let mut stream = Vec::new();
+/// framer.encode(&first, &mut stream);
+/// framer.encode(&second, &mut stream);
+/// assert_eq!(stream.iter().filter(|&&b| b == 0).count(), 2);
+///
+/// assert_eq!(framer.decode(&mut stream).unwrap(), Some(first));
+/// assert_eq!(framer.decode(&mut stream).unwrap(), Some(second));
+/// assert_eq!(framer.decode(&mut stream).unwrap(), None);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CobsFramer;
+
+impl Framer for CobsFramer {
+    fn encode(&self, packet: &Packet, out: &mut Vec<u8>) {
+        out.extend(cobs_encode(&packet.to_bytes()));
+    }
+
+    fn decode(&self, buf: &mut Vec<u8>) -> Result<Option<Packet>, PacketError> {
+        let Some(end) = buf.iter().position(|&b| b == COBS_DELIMITER) else {
+            return Ok(None);
+        };
+
+        let result = cobs_decode(&buf[..=end]).and_then(|bytes| Packet::from_bytes(&bytes));
+        buf.drain(..=end);
+        result.map(Some)
+    }
+}
@@ -0,0 +1,22 @@
+//! Alvo de *fuzzing* da leitura de lotes.
+//!
+//! Interpreta os bytes como um datagrama de [`unpack_datagram_with_limits`] e
+//! verifica que o resultado respeita os limites padrão.
+//!
+//! Execute com `cargo fuzz run batch`.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use packet::batch::unpack_datagram_with_limits;
+use packet::config::ParseConfig;
+
+fuzz_target!(|data: &[u8]| {
+    let limits = ParseConfig::default();
+    if let Ok(packets) = unpack_datagram_with_limits(data, &limits) {
+        assert!(packets.len() <= limits.max_batch);
+        assert!(packets
+            .iter()
+            .all(|packet| packet.payload.len() <= limits.max_payload));
+    }
+});
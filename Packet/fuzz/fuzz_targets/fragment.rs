@@ -0,0 +1,38 @@
+//! Alvo de *fuzzing* da remontagem de fragmentos.
+//!
+//! Interpreta os bytes como pacotes concatenados e entrega os fragmentos a um
+//! [`Reassembler`] com limites reduzidos, verificando que nenhum pacote remontado
+//! excede a memória que os limites permitem.
+//!
+//! Execute com `cargo fuzz run fragment`.
+
+#![no_main]
+
+use std::time::{Duration, Instant};
+
+use libfuzzer_sys::fuzz_target;
+use packet::config::ParseConfig;
+use packet::fragment::Reassembler;
+use packet::view::PacketIter;
+
+/// Limites reduzidos, para que o *fuzzer* alcance grupos completos.
+const LIMITS: ParseConfig = ParseConfig {
+    max_payload: 64,
+    max_fragments: 8,
+    max_batch: 8,
+};
+
+fuzz_target!(|data: &[u8]| {
+    let mut reassembler = Reassembler::new(Duration::from_secs(1)).with_parse_config(LIMITS);
+    let now = Instant::now();
+
+    for packet in PacketIter::new(data).flatten() {
+        let packet = packet.to_packet();
+        if !packet.is_fragment() {
+            continue;
+        }
+        if let Ok(Some(packet)) = reassembler.insert(packet, now) {
+            assert!(packet.payload.len() <= LIMITS.max_fragments * LIMITS.max_payload);
+        }
+    }
+});
@@ -0,0 +1,22 @@
+//! Alvo de *fuzzing* da leitura do cabeçalho.
+//!
+//! Interpreta os bytes como [`PacketHeader`] e verifica que um cabeçalho aceito pelos
+//! limites padrão declara um pacote representável.
+//!
+//! Execute com `cargo fuzz run header`.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use packet::config::ParseConfig;
+use packet::PacketHeader;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(header) = PacketHeader::from_bytes(data) else {
+        return;
+    };
+    let _ = header.validate();
+    if ParseConfig::default().check_header(&header).is_ok() {
+        assert!(header.packet_len().is_ok());
+    }
+});
@@ -0,0 +1,34 @@
+//! Alvo de *fuzzing* da leitura de pacotes completos.
+//!
+//! Compara [`Packet::from_bytes_with_limits`] e [`PacketRef::from_bytes_with_limits`]
+//! e alimenta um [`PacketDecoder`] com os mesmos bytes, verificando que nenhum pacote
+//! aceito excede os limites padrão.
+//!
+//! Execute com `cargo fuzz run packet`.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use packet::config::ParseConfig;
+use packet::decoder::PacketDecoder;
+use packet::view::PacketRef;
+use packet::Packet;
+
+fuzz_target!(|data: &[u8]| {
+    let limits = ParseConfig::default();
+
+    let owned = Packet::from_bytes_with_limits(data, &limits);
+    let borrowed = PacketRef::from_bytes_with_limits(data, &limits);
+    assert_eq!(owned.is_ok(), borrowed.is_ok());
+    if let (Ok(owned), Ok(borrowed)) = (owned, borrowed) {
+        assert!(owned.payload.len() <= limits.max_payload);
+        assert_eq!(owned, borrowed.to_packet());
+    }
+
+    let mut decoder = PacketDecoder::new();
+    decoder.set_auto_resync(true);
+    decoder.extend_from_slice(data);
+    while let Ok(Some(packet)) = decoder.decode() {
+        assert!(packet.payload.len() <= limits.max_payload);
+    }
+});
@@ -0,0 +1,59 @@
+//! # Módulo `fuzzing`
+//!
+//! Implementação de [`arbitrary::Arbitrary`] para [`Packet`], disponível com o
+//! recurso `arbitrary`.
+//!
+//! Os pacotes gerados são sempre estruturalmente válidos (`payload_size` e checksum
+//! consistentes com o payload), permitindo que alvos de *fuzzing* explorem a lógica
+//! de camadas superiores, como confiabilidade e remontagem, em vez de pararem na
+//! validação do cabeçalho. Flags de confiabilidade, fragmento e confirmação, além do
+//! algoritmo e do escopo do checksum, são sorteados para exercitar caminhos diferentes.
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+use crate::checksum::{ChecksumAlgorithm, ChecksumScope, Crc32Variant};
+use crate::payload::ContentType;
+use crate::{Packet, PacketFlags, MAX_PAYLOAD_SIZE};
+
+impl<'a> Arbitrary<'a> for Packet {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let message_type = u.arbitrary()?;
+        let sequence = u.arbitrary()?;
+        let player_id = u.arbitrary()?;
+        let priority = u.arbitrary()?;
+
+        let len = u.int_in_range(0..=u.len().min(MAX_PAYLOAD_SIZE))?;
+        let payload = u.bytes(len)?.to_vec();
+
+        let mut packet = if u.arbitrary()? {
+            Packet::reliable(message_type, sequence, player_id, payload)
+        } else {
+            Packet::new(message_type, sequence, player_id, payload)
+        };
+        packet = packet
+            .with_priority(priority)
+            .with_channel_id(u.arbitrary()?)
+            .with_content_type(ContentType::from(u.arbitrary::<u8>()?))
+            .with_ttl(u.arbitrary()?);
+
+        if u.arbitrary()? {
+            packet.header.flags.insert(PacketFlags::FRAGMENT);
+        }
+        if u.arbitrary()? {
+            packet = packet.with_ack(u.arbitrary()?, u.arbitrary()?);
+        }
+        if u.arbitrary()? {
+            let variant = if u.arbitrary()? {
+                Crc32Variant::Castagnoli
+            } else {
+                Crc32Variant::Ieee
+            };
+            packet = packet.with_checksum_algorithm(ChecksumAlgorithm::Crc32(variant));
+        }
+        if u.arbitrary()? {
+            packet = packet.with_checksum_scope(ChecksumScope::HeaderAndPayload);
+        }
+
+        Ok(packet)
+    }
+}
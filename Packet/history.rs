@@ -0,0 +1,92 @@
+//! # Módulo `history`
+//!
+//! Histórico dos pacotes mais recentes, para diagnóstico após um erro ou uma
+//! desconexão.
+//!
+//! Apenas os cabeçalhos são guardados, o que limita a memória usada a
+//! `capacidade * size_of::<PacketHeader>()` independentemente do tamanho dos payloads.
+
+use std::collections::VecDeque;
+
+use crate::{Packet, PacketHeader};
+
+/// Buffer circular com os cabeçalhos dos últimos pacotes registrados.
+///
+/// O espaço é alocado uma única vez na criação; ao atingir a capacidade, cada novo
+/// registro sobrescreve o mais antigo.
+///
+/// ## Exemplos
+/// ```rust
+/// use packet::Packet;
+/// use packet::history::PacketHistory;
+///
+/// let mut history = PacketHistory::new(2);
+/// for sequence in 1..=3 {
+///     history.push(&Packet::new(1, sequence, 7, vec![0; 100]));
+/// }
+///
+/// let sequences: Vec<_> = history.recent().map(|h| h.sequence).collect();
+/// assert_eq!(sequences, [2, 3]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct PacketHistory {
+    /// Cabeçalhos registrados, do mais antigo ao mais recente.
+    headers: VecDeque<PacketHeader>,
+    /// Quantidade máxima de cabeçalhos guardados.
+    capacity: usize,
+}
+
+impl PacketHistory {
+    /// Cria um histórico vazio.
+    ///
+    /// ## Parâmetros
+    /// - `capacity`: Quantidade de pacotes guardados; `0` desativa o registro.
+    ///
+    /// ## Retorno
+    /// - `Self`: Um histórico com o espaço já alocado.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            headers: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Registra o cabeçalho de um pacote, descartando o mais antigo se necessário.
+    ///
+    /// ## Parâmetros
+    /// - `packet`: Pacote enviado ou recebido.
+    pub fn push(&mut self, packet: &Packet) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.headers.len() == self.capacity {
+            self.headers.pop_front();
+        }
+        self.headers.push_back(packet.header.clone());
+    }
+
+    /// Retorna os cabeçalhos guardados, do mais antigo ao mais recente.
+    pub fn recent(&self) -> impl Iterator<Item = &PacketHeader> {
+        self.headers.iter()
+    }
+
+    /// Retorna a quantidade de cabeçalhos guardados.
+    pub fn len(&self) -> usize {
+        self.headers.len()
+    }
+
+    /// Indica se nenhum pacote foi registrado.
+    pub fn is_empty(&self) -> bool {
+        self.headers.is_empty()
+    }
+
+    /// Retorna a quantidade máxima de cabeçalhos guardados.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Descarta todos os cabeçalhos guardados, mantendo o espaço alocado.
+    pub fn clear(&mut self) {
+        self.headers.clear();
+    }
+}
@@ -0,0 +1,159 @@
+//! # Módulo `hook`
+//!
+//! Ganchos de inspeção dos pacotes que entram e saem de um
+//! [`Server`](crate::connection::Server) ou [`Client`](crate::connection::Client),
+//! para registro, heurísticas contra trapaça e filtros próprios do jogo sem alterar a
+//! biblioteca.
+//!
+//! Cada gancho recebe o endereço do outro lado e uma [`PacketView`] do pacote e
+//! responde com uma [`HookDecision`]: deixar o pacote seguir, substituí-lo ou
+//! descartá-lo. Um [`HookPipeline`] chama os ganchos na ordem em que foram
+//! registrados; cada gancho vê o pacote deixado pelo anterior, e o primeiro descarte
+//! encerra a cadeia.
+//!
+//! ## Exemplos
+//! ```rust
+//! use std::net::SocketAddr;
+//! use packet::Packet;
+//! use packet::hook::{HookDecision, HookPipeline};
+//!
+//! let mut pipeline = HookPipeline::new();
+//! // Descarta os pacotes de um tipo que o jogo não aceita.
+//! pipeline.add(|_, view| match view.header.message_type {
+//!     9 => HookDecision::Drop,
+//!     _ => HookDecision::Continue,
+//! });
+//! // Limita o payload a 4 bytes.
+//! pipeline.add(|_, view| {
+//!     if view.payload.len() <= 4 {
+//!         return HookDecision::Continue;
+//!     }
+//!     let mut trimmed = view.to_packet();
+//!     trimmed.replace_payload(view.payload[..4].to_vec());
+//!     HookDecision::Replace(trimmed)
+//! });
+//!
+//! let addr: SocketAddr = "127.0.0.1:4000".parse().unwrap();
+//! assert_eq!(pipeline.run(&addr, Packet::new(9, 1, 7, vec![1])), None);
+//! let trimmed = pipeline.run(&addr, Packet::new(1, 2, 7, vec![1, 2, 3, 4, 5])).unwrap();
+//! assert_eq!(trimmed.payload, [1, 2, 3, 4]);
+//! ```
+
+use std::fmt;
+use std::net::SocketAddr;
+
+use crate::view::PacketView;
+use crate::Packet;
+
+/// Resposta de um gancho a um pacote.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HookDecision {
+    /// O pacote segue como está para o próximo gancho.
+    Continue,
+    /// O pacote é substituído pelo informado, que segue para o próximo gancho.
+    Replace(Packet),
+    /// O pacote é descartado, sem chamar os ganchos seguintes.
+    Drop,
+}
+
+/// Gancho registrado em um [`HookPipeline`].
+type Hook = Box<dyn FnMut(&SocketAddr, &PacketView<'_>) -> HookDecision + Send>;
+
+/// Sequência ordenada de ganchos aplicada a cada pacote.
+#[derive(Default)]
+pub struct HookPipeline {
+    /// Ganchos na ordem de registro.
+    hooks: Vec<Hook>,
+}
+
+impl fmt::Debug for HookPipeline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HookPipeline")
+            .field("hooks", &self.hooks.len())
+            .finish()
+    }
+}
+
+impl HookPipeline {
+    /// Cria uma sequência sem ganchos.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Acrescenta um gancho ao fim da sequência.
+    ///
+    /// ## Parâmetros
+    /// - `hook`: Função chamada com o endereço do outro lado e a visão de cada pacote.
+    pub fn add(
+        &mut self,
+        hook: impl FnMut(&SocketAddr, &PacketView<'_>) -> HookDecision + Send + 'static,
+    ) {
+        self.hooks.push(Box::new(hook));
+    }
+
+    /// Retorna a quantidade de ganchos.
+    pub fn len(&self) -> usize {
+        self.hooks.len()
+    }
+
+    /// Indica se a sequência não tem ganchos.
+    pub fn is_empty(&self) -> bool {
+        self.hooks.is_empty()
+    }
+
+    /// Passa um pacote pelos ganchos, na ordem de registro.
+    ///
+    /// O pacote só é serializado se há ganchos, e de novo a cada substituição. Um
+    /// pacote que não pode ser lido de volta, como uma substituição com o payload
+    /// editado diretamente em vez de por [`Packet::replace_payload`], é descartado: os
+    /// ganchos seguintes não poderiam inspecioná-lo, e o último não o entrega.
+    ///
+    /// ## Parâmetros
+    /// - `addr`: Endereço do outro lado: a origem dos pacotes recebidos ou o destino
+    ///   dos enviados.
+    /// - `packet`: Pacote a inspecionar.
+    ///
+    /// ## Retorno
+    /// - `Option<Packet>`: O pacote deixado pelo último gancho, ou `None` se um gancho
+    ///   o descartou ou o pacote não pôde ser lido por um deles.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use std::net::SocketAddr;
+    /// use packet::Packet;
+    /// use packet::hook::{HookDecision, HookPipeline};
+    ///
+    /// let mut pipeline = HookPipeline::new();
+    /// // Acrescenta um byte sem atualizar o cabeçalho.
+    /// pipeline.add(|_, view| {
+    ///     let mut stale = view.to_packet();
+    ///     stale.payload.push(0);
+    ///     HookDecision::Replace(stale)
+    /// });
+    /// let addr: SocketAddr = "127.0.0.1:4000".parse().unwrap();
+    /// assert_eq!(pipeline.run(&addr, Packet::new(1, 1, 7, vec![1])), None);
+    ///
+    /// // Um filtro depois da substituição também não é contornado.
+    /// pipeline.add(|_, _| HookDecision::Drop);
+    /// assert_eq!(pipeline.run(&addr, Packet::new(1, 2, 7, vec![1])), None);
+    /// ```
+    pub fn run(&mut self, addr: &SocketAddr, mut packet: Packet) -> Option<Packet> {
+        if self.hooks.is_empty() {
+            return Some(packet);
+        }
+        let mut bytes = packet.to_bytes();
+        for hook in &mut self.hooks {
+            let view = PacketView::from_bytes(&bytes).ok()?;
+            match hook(addr, &view) {
+                HookDecision::Continue => {}
+                HookDecision::Replace(replacement) => {
+                    bytes = replacement.to_bytes();
+                    PacketView::from_bytes(&bytes).ok()?;
+                    packet = replacement;
+                }
+                HookDecision::Drop => return None,
+            }
+        }
+        Some(packet)
+    }
+}
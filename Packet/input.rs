@@ -0,0 +1,235 @@
+//! # Módulo `input`
+//!
+//! Codificação compacta da entrada do jogador ([`MessageType::INPUT`]), a mensagem
+//! mais frequente de um jogo.
+//!
+//! [`InputFrame`] implementa [`PacketPayload`] e é gravado com [`bits`](crate::bits):
+//! o tick ocupa 32 bits, os botões 16 e cada eixo analógico um bit indicando se está
+//! fora do repouso, seguido de 8 bits com o valor. Com os eixos em repouso, o payload
+//! tem 7 bytes, contra os 10 de uma codificação byte a byte.
+//!
+//! [`InputHistory`] junta as entradas mais recentes em um pacote
+//! [`MessageType::INPUT_HISTORY`], de modo que cada entrada viaja em vários pacotes e
+//! a perda de um deles não a perde; o
+//! [`InputBuffer`](crate::prediction::InputBuffer) do cliente monta esses pacotes.
+//!
+//! ## Exemplos
+//! ```rust
+//! use packet::Packet;
+//! use packet::input::InputFrame;
+//!
+//! let frame = InputFrame {
+//!     tick: 1200,
+//!     buttons: InputFrame::button(0) | InputFrame::button(3),
+//!     axes: [0, 0, -128, 127],
+//! };
+//! let packet = Packet::input_frame(7, 1, &frame);
+//! assert_eq!(packet.payload.len(), 9);
+//!
+//! let received = Packet::from_bytes(&packet.to_bytes()).unwrap();
+//! assert_eq!(received.decode_input_frame(), Ok(frame));
+//! assert!(frame.is_pressed(3));
+//! ```
+
+use crate::bits::{BitReader, BitWriter};
+use crate::message::MessageType;
+use crate::payload::PacketPayload;
+use crate::{Packet, PacketError};
+
+/// Quantidade de eixos analógicos de um [`InputFrame`].
+pub const AXIS_COUNT: usize = 4;
+
+/// Quantidade máxima de entradas em um [`InputHistory`].
+pub const MAX_HISTORY_FRAMES: usize = u8::MAX as usize;
+
+/// Estado da entrada do jogador em um tick da simulação.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct InputFrame {
+    /// Tick do cliente ao qual a entrada se aplica.
+    pub tick: u32,
+    /// Botões pressionados; o bit `i` corresponde ao botão `i`.
+    pub buttons: u16,
+    /// Eixos analógicos, como os dois eixos de cada alavanca; `0` é o repouso.
+    pub axes: [i8; AXIS_COUNT],
+}
+
+impl InputFrame {
+    /// Retorna a máscara do botão `index`, para compor [`InputFrame::buttons`].
+    ///
+    /// ## Pânico
+    /// Se `index` é maior ou igual a `16`.
+    pub const fn button(index: u32) -> u16 {
+        1 << index
+    }
+
+    /// Indica se o botão `index` está pressionado.
+    ///
+    /// ## Pânico
+    /// Se `index` é maior ou igual a `16`.
+    pub fn is_pressed(&self, index: u32) -> bool {
+        self.buttons & Self::button(index) != 0
+    }
+}
+
+impl InputFrame {
+    /// Grava a entrada no formato compacto.
+    fn write(&self, writer: &mut BitWriter) {
+        writer.write_bits(self.tick as u64, 32);
+        writer.write_bits(self.buttons as u64, 16);
+        for axis in self.axes {
+            writer.write_bool(axis != 0);
+            if axis != 0 {
+                writer.write_bits(axis as u8 as u64, 8);
+            }
+        }
+    }
+
+    /// Lê uma entrada gravada por [`InputFrame::write`].
+    fn read(reader: &mut BitReader<'_>) -> Result<Self, PacketError> {
+        let tick = reader.read_bits(32)? as u32;
+        let buttons = reader.read_bits(16)? as u16;
+        let mut axes = [0; AXIS_COUNT];
+        for axis in &mut axes {
+            if reader.read_bool()? {
+                *axis = reader.read_bits(8)? as u8 as i8;
+            }
+        }
+        Ok(Self {
+            tick,
+            buttons,
+            axes,
+        })
+    }
+}
+
+impl PacketPayload for InputFrame {
+    /// O byte de [`MessageType::INPUT`].
+    const MESSAGE_TYPE: u8 = MessageType::CUSTOM_START;
+
+    fn encode(&self) -> Vec<u8> {
+        let mut writer = BitWriter::new();
+        self.write(&mut writer);
+        writer.finish()
+    }
+
+    fn decode(payload: &[u8]) -> Result<Self, PacketError> {
+        let mut reader = BitReader::new(payload);
+        let frame = Self::read(&mut reader)?;
+        reject_trailing(&reader, "bytes excedentes após a entrada do jogador")?;
+        Ok(frame)
+    }
+}
+
+/// Entradas recentes do jogador, enviadas juntas em um pacote
+/// [`MessageType::INPUT_HISTORY`] para que a perda de um pacote não perca entradas.
+///
+/// O payload começa com a quantidade de entradas em 8 bits, seguida de cada
+/// [`InputFrame`] no formato compacto, da mais antiga para a mais recente.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct InputHistory {
+    /// Entradas, da mais antiga para a mais recente.
+    pub frames: Vec<InputFrame>,
+}
+
+impl PacketPayload for InputHistory {
+    /// O byte de [`MessageType::INPUT_HISTORY`].
+    const MESSAGE_TYPE: u8 = MessageType::CUSTOM_START + 3;
+
+    /// Grava no máximo [`MAX_HISTORY_FRAMES`] entradas, descartando as mais antigas.
+    fn encode(&self) -> Vec<u8> {
+        let skip = self.frames.len().saturating_sub(MAX_HISTORY_FRAMES);
+        let mut writer = BitWriter::new();
+        writer.write_bits((self.frames.len() - skip) as u64, 8);
+        for frame in &self.frames[skip..] {
+            frame.write(&mut writer);
+        }
+        writer.finish()
+    }
+
+    fn decode(payload: &[u8]) -> Result<Self, PacketError> {
+        let mut reader = BitReader::new(payload);
+        let count = reader.read_bits(8)? as usize;
+        let frames = (0..count)
+            .map(|_| InputFrame::read(&mut reader))
+            .collect::<Result<_, _>>()?;
+        reject_trailing(&reader, "bytes excedentes após o histórico de entradas")?;
+        Ok(Self { frames })
+    }
+}
+
+/// Rejeita um payload com ao menos um byte inteiro além do último valor lido.
+fn reject_trailing(reader: &BitReader<'_>, reason: &'static str) -> Result<(), PacketError> {
+    if reader.remaining_bits() >= 8 {
+        return Err(PacketError::InvalidPayload { reason });
+    }
+    Ok(())
+}
+
+impl Packet {
+    /// Cria um pacote [`MessageType::INPUT`] com a entrada do jogador.
+    ///
+    /// ## Parâmetros
+    /// - `player_id`: Identificador único do jogador.
+    /// - `sequence`: Sequência do pacote.
+    /// - `frame`: Entrada a ser codificada.
+    ///
+    /// ## Retorno
+    /// - `Self`: O pacote com a entrada codificada no payload.
+    pub fn input_frame(player_id: u64, sequence: u32, frame: &InputFrame) -> Self {
+        Self::from_payload(sequence, player_id, frame)
+    }
+
+    /// Decodifica a entrada do jogador de um pacote [`MessageType::INPUT`].
+    ///
+    /// ## Retorno
+    /// - `Result<InputFrame, PacketError>`: A entrada,
+    ///   [`PacketError::UnexpectedMessageType`] se o pacote não é de entrada, ou
+    ///   [`PacketError::InvalidPayload`] se o payload não é uma entrada válida.
+    pub fn decode_input_frame(&self) -> Result<InputFrame, PacketError> {
+        self.decode_typed()
+    }
+
+    /// Cria um pacote [`MessageType::INPUT_HISTORY`] com as entradas recentes do
+    /// jogador.
+    ///
+    /// ## Parâmetros
+    /// - `player_id`: Identificador único do jogador.
+    /// - `sequence`: Sequência do pacote.
+    /// - `frames`: Entradas, da mais antiga para a mais recente; apenas as
+    ///   [`MAX_HISTORY_FRAMES`] mais recentes são gravadas.
+    ///
+    /// ## Retorno
+    /// - `Self`: O pacote com as entradas codificadas no payload.
+    pub fn input_history(player_id: u64, sequence: u32, frames: &[InputFrame]) -> Self {
+        let history = InputHistory {
+            frames: frames.to_vec(),
+        };
+        Self::from_payload(sequence, player_id, &history)
+    }
+
+    /// Decodifica as entradas de um pacote [`MessageType::INPUT_HISTORY`].
+    ///
+    /// ## Retorno
+    /// - `Result<Vec<InputFrame>, PacketError>`: As entradas, da mais antiga para a
+    ///   mais recente; [`PacketError::UnexpectedMessageType`] se o pacote não é um
+    ///   histórico de entradas, ou [`PacketError::InvalidPayload`] se o payload não é
+    ///   um histórico válido.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::Packet;
+    /// use packet::input::InputFrame;
+    ///
+    /// let frames: Vec<_> = (10..13)
+    ///     .map(|tick| InputFrame { tick, ..InputFrame::default() })
+    ///     .collect();
+    /// let packet = Packet::input_history(7, 1, &frames);
+    ///
+    /// let received = Packet::from_bytes(&packet.to_bytes()).unwrap();
+    /// assert_eq!(received.decode_input_history(), Ok(frames));
+    /// ```
+    pub fn decode_input_history(&self) -> Result<Vec<InputFrame>, PacketError> {
+        self.decode_typed::<InputHistory>()
+            .map(|history| history.frames)
+    }
+}
@@ -0,0 +1,74 @@
+//! # Módulo `instrument`
+//!
+//! Eventos `tracing` emitidos pelo crate com o recurso `tracing`.
+//!
+//! Os eventos carregam `player_id`, `sequence` e `message_type` como campos
+//! estruturados. Sem o recurso, as funções deste módulo são vazias e desaparecem na
+//! compilação.
+
+use crate::{PacketError, PacketHeader};
+
+/// Registra o resultado da interpretação de um pacote recebido: `debug` em caso de
+/// sucesso e `warn` em caso de falha.
+pub(crate) fn parsed(result: Result<&PacketHeader, &PacketError>) {
+    #[cfg(feature = "tracing")]
+    match result {
+        Ok(header) => tracing::debug!(
+            player_id = header.player_id,
+            sequence = header.sequence,
+            message_type = header.message_type,
+            "pacote interpretado"
+        ),
+        Err(error) => tracing::warn!(%error, "falha ao interpretar pacote"),
+    }
+    #[cfg(not(feature = "tracing"))]
+    let _ = result;
+}
+
+/// Registra, em nível `debug`, um pacote remontado a partir de `fragment_count`
+/// fragmentos.
+#[cfg(feature = "std")]
+pub(crate) fn reassembled(header: &PacketHeader, fragment_count: usize) {
+    #[cfg(feature = "tracing")]
+    tracing::debug!(
+        player_id = header.player_id,
+        sequence = header.sequence,
+        message_type = header.message_type,
+        fragment_count,
+        "pacote remontado"
+    );
+    #[cfg(not(feature = "tracing"))]
+    let _ = (header, fragment_count);
+}
+
+/// Registra, em nível `debug`, um grupo de fragmentos descartado por tempo limite
+/// com `received` de `fragment_count` fragmentos.
+#[cfg(feature = "std")]
+pub(crate) fn reassembly_expired(header: &PacketHeader, received: usize, fragment_count: usize) {
+    #[cfg(feature = "tracing")]
+    tracing::debug!(
+        player_id = header.player_id,
+        sequence = header.sequence,
+        message_type = header.message_type,
+        received,
+        fragment_count,
+        "grupo de fragmentos expirado"
+    );
+    #[cfg(not(feature = "tracing"))]
+    let _ = (header, received, fragment_count);
+}
+
+/// Registra, em nível `warn`, um checksum que não corresponde ao calculado.
+pub(crate) fn checksum_mismatch(header: &PacketHeader, actual: u32) {
+    #[cfg(feature = "tracing")]
+    tracing::warn!(
+        player_id = header.player_id,
+        sequence = header.sequence,
+        message_type = header.message_type,
+        expected = header.checksum,
+        actual,
+        "checksum divergente"
+    );
+    #[cfg(not(feature = "tracing"))]
+    let _ = (header, actual);
+}
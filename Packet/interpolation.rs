@@ -0,0 +1,254 @@
+//! # Módulo `interpolation`
+//!
+//! Buffer de interpolação para suavizar o movimento das entidades remotas no cliente.
+//!
+//! O cliente guarda os estados recebidos do servidor com o instante em que foram
+//! produzidos e desenha as entidades um pouco no passado, interpolando entre os dois
+//! estados que cercam esse instante. O atraso precisa cobrir a variação na chegada
+//! dos pacotes: [`SnapshotBuffer::tune`] o ajusta pelo jitter de um
+//! [`RttEstimator`], somando ao atraso mínimo [`DEFAULT_JITTER_MULTIPLIER`] vezes o
+//! jitter estimado.
+//!
+//! ## Exemplos
+//! ```rust
+//! use std::time::Duration;
+//! use packet::interpolation::SnapshotBuffer;
+//!
+//! let mut buffer = SnapshotBuffer::new(32, Duration::from_millis(100));
+//! buffer.push(Duration::from_millis(0), 0.0f32);
+//! buffer.push(Duration::from_millis(50), 10.0);
+//! buffer.push(Duration::from_millis(100), 20.0);
+//!
+//! // Aos 175 ms de relógio, o cliente desenha o estado de 75 ms.
+//! let render_time = buffer.render_time(Duration::from_millis(175));
+//! assert_eq!(render_time, Duration::from_millis(75));
+//! assert_eq!(buffer.sample(render_time), Some(15.0));
+//! ```
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use crate::rtt::RttEstimator;
+
+/// Quantas vezes o jitter estimado é somado ao atraso mínimo por
+/// [`SnapshotBuffer::tune`].
+pub const DEFAULT_JITTER_MULTIPLIER: u32 = 2;
+
+/// Estado que pode ser interpolado linearmente.
+pub trait Interpolate {
+    /// Retorna o estado a uma fração `t` do caminho entre `self` e `other`.
+    ///
+    /// ## Parâmetros
+    /// - `other`: Estado seguinte.
+    /// - `t`: Fração entre `0.0` (`self`) e `1.0` (`other`).
+    fn interpolate(&self, other: &Self, t: f32) -> Self;
+}
+
+impl Interpolate for f32 {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl Interpolate for f64 {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        self + (other - self) * f64::from(t)
+    }
+}
+
+impl<T: Interpolate, const N: usize> Interpolate for [T; N] {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        std::array::from_fn(|i| self[i].interpolate(&other[i], t))
+    }
+}
+
+/// Estados com carimbo de tempo, amostrados com um atraso de interpolação.
+#[derive(Debug, Clone)]
+pub struct SnapshotBuffer<T> {
+    /// Estados em ordem crescente de tempo.
+    states: VecDeque<(Duration, T)>,
+    /// Quantidade máxima de estados guardados.
+    capacity: usize,
+    /// Atraso abaixo do qual [`SnapshotBuffer::tune`] não desce.
+    min_delay: Duration,
+    /// Atraso atual entre o relógio e o instante desenhado.
+    delay: Duration,
+}
+
+impl<T: Clone> SnapshotBuffer<T> {
+    /// Cria um buffer vazio.
+    ///
+    /// ## Parâmetros
+    /// - `capacity`: Quantidade máxima de estados; os mais antigos são descartados.
+    /// - `min_delay`: Atraso inicial e mínimo, em geral de dois a três intervalos
+    ///   entre os snapshots do servidor.
+    ///
+    /// ## Retorno
+    /// - `Self`: Um buffer sem estados, com atraso `min_delay`.
+    pub fn new(capacity: usize, min_delay: Duration) -> Self {
+        Self {
+            states: VecDeque::with_capacity(capacity.max(2)),
+            capacity: capacity.max(2),
+            min_delay,
+            delay: min_delay,
+        }
+    }
+
+    /// Acrescenta um estado recebido.
+    ///
+    /// Estados fora de ordem são inseridos na posição do seu tempo; um estado com o
+    /// mesmo tempo de outro o substitui.
+    ///
+    /// ## Parâmetros
+    /// - `time`: Instante em que o estado foi produzido, no relógio do servidor.
+    /// - `state`: Estado da entidade.
+    pub fn push(&mut self, time: Duration, state: T) {
+        match self.states.binary_search_by_key(&time, |(t, _)| *t) {
+            Ok(index) => self.states[index].1 = state,
+            Err(index) => {
+                self.states.insert(index, (time, state));
+                if self.states.len() > self.capacity {
+                    self.states.pop_front();
+                }
+            }
+        }
+    }
+
+    /// Retorna o atraso atual de interpolação.
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+
+    /// Define o atraso de interpolação, sem respeitar o mínimo.
+    pub fn set_delay(&mut self, delay: Duration) {
+        self.delay = delay;
+    }
+
+    /// Ajusta o atraso pelo jitter observado.
+    ///
+    /// O novo atraso é o mínimo mais [`DEFAULT_JITTER_MULTIPLIER`] vezes o jitter do
+    /// estimador; sem amostras, volta ao mínimo.
+    ///
+    /// ## Parâmetros
+    /// - `estimator`: Estimador de RTT da conexão com o servidor.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use std::time::Duration;
+    /// use packet::interpolation::SnapshotBuffer;
+    /// use packet::rtt::RttEstimator;
+    ///
+    /// let mut buffer = SnapshotBuffer::<f32>::new(32, Duration::from_millis(100));
+    /// let mut estimator = RttEstimator::new();
+    /// estimator.record(Duration::from_millis(80));
+    ///
+    /// buffer.tune(&estimator);
+    /// assert_eq!(buffer.delay(), Duration::from_millis(180));
+    /// ```
+    pub fn tune(&mut self, estimator: &RttEstimator) {
+        let jitter = estimator.jitter().unwrap_or_default();
+        self.delay = self.min_delay + jitter * DEFAULT_JITTER_MULTIPLIER;
+    }
+
+    /// Retorna o instante a ser desenhado.
+    ///
+    /// ## Parâmetros
+    /// - `now`: Instante atual, no relógio do servidor estimado pelo cliente.
+    ///
+    /// ## Retorno
+    /// - `Duration`: `now` menos o atraso, ou zero.
+    pub fn render_time(&self, now: Duration) -> Duration {
+        now.saturating_sub(self.delay)
+    }
+
+    /// Amostra o estado em um instante, com [`Interpolate`].
+    ///
+    /// ## Parâmetros
+    /// - `render_time`: Instante a ser desenhado, em geral de
+    ///   [`SnapshotBuffer::render_time`].
+    ///
+    /// ## Retorno
+    /// - `Option<T>`: O estado interpolado; o primeiro ou o último estado se o instante
+    ///   está fora do intervalo guardado, sem extrapolação; ou `None` sem estados.
+    pub fn sample(&self, render_time: Duration) -> Option<T>
+    where
+        T: Interpolate,
+    {
+        self.sample_with(render_time, T::interpolate)
+    }
+
+    /// Amostra o estado em um instante com uma função de interpolação própria, para
+    /// estados que não implementam [`Interpolate`] ou que precisam de outra curva, como
+    /// rotações.
+    ///
+    /// ## Parâmetros
+    /// - `render_time`: Instante a ser desenhado.
+    /// - `lerp`: Recebe os estados anterior e seguinte e a fração entre eles, de `0.0`
+    ///   a `1.0`.
+    ///
+    /// ## Retorno
+    /// - `Option<T>`: Como em [`SnapshotBuffer::sample`].
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use std::time::Duration;
+    /// use packet::interpolation::SnapshotBuffer;
+    ///
+    /// // Ângulos em graus, pelo menor caminho.
+    /// let mut buffer = SnapshotBuffer::new(8, Duration::ZERO);
+    /// buffer.push(Duration::from_millis(0), 350.0f32);
+    /// buffer.push(Duration::from_millis(100), 10.0);
+    ///
+    /// let angle = buffer.sample_with(Duration::from_millis(50), |a, b, t| {
+    ///     let delta = (b - a + 540.0) % 360.0 - 180.0;
+    ///     (a + delta * t).rem_euclid(360.0)
+    /// });
+    /// assert_eq!(angle, Some(0.0));
+    /// ```
+    pub fn sample_with(
+        &self,
+        render_time: Duration,
+        lerp: impl FnOnce(&T, &T, f32) -> T,
+    ) -> Option<T> {
+        let next = self
+            .states
+            .partition_point(|(time, _)| *time <= render_time);
+        if next == 0 {
+            return self.states.front().map(|(_, state)| state.clone());
+        }
+        if next == self.states.len() {
+            return self.states.back().map(|(_, state)| state.clone());
+        }
+        let (from_time, from) = &self.states[next - 1];
+        let (to_time, to) = &self.states[next];
+        let t = (render_time - *from_time).as_secs_f32() / (*to_time - *from_time).as_secs_f32();
+        Some(lerp(from, to, t))
+    }
+
+    /// Descarta os estados que não serão mais usados a partir de um instante,
+    /// preservando o último anterior a ele.
+    ///
+    /// ## Parâmetros
+    /// - `render_time`: Instante desenhado mais antigo que ainda pode ser amostrado.
+    pub fn discard_before(&mut self, render_time: Duration) {
+        let next = self
+            .states
+            .partition_point(|(time, _)| *time <= render_time);
+        self.states.drain(..next.saturating_sub(1));
+    }
+
+    /// Retorna a quantidade de estados guardados.
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    /// Indica se o buffer não tem estados.
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+
+    /// Retorna o tempo do estado mais recente.
+    pub fn latest_time(&self) -> Option<Duration> {
+        self.states.back().map(|(time, _)| *time)
+    }
+}
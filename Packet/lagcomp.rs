@@ -0,0 +1,229 @@
+//! # Módulo `lagcomp`
+//!
+//! Compensação de latência no servidor: histórico dos estados do mundo para validar
+//! acertos no instante em que o cliente os viu.
+//!
+//! O cliente desenha as entidades remotas no passado, com o atraso de interpolação de
+//! um [`SnapshotBuffer`](crate::interpolation::SnapshotBuffer), e leva no disparo o
+//! instante que desenhava, no relógio do servidor estimado por
+//! [`Client::server_time_now`](crate::connection::Client::server_time_now). O
+//! servidor grava o estado de cada tick em um [`HistoryBuffer`], com o relógio de
+//! [`Server::server_time`](crate::connection::Server::server_time), e valida o acerto
+//! contra o estado de [`HistoryBuffer::rewind_to`], interpolado entre os dois ticks
+//! que cercam o instante do cliente. O estado atual não é alterado: a volta ao
+//! presente é só continuar usando [`HistoryBuffer::latest`].
+//!
+//! O histórico é limitado pelo tempo, de [`DEFAULT_HISTORY`], e pela quantidade de
+//! estados, de [`DEFAULT_MAX_STATES`]; instantes mais antigos que o histórico não
+//! podem ser revistos, o que também limita quanto um cliente pode voltar no tempo.
+//!
+//! ## Exemplos
+//! ```rust
+//! use std::time::Duration;
+//! use packet::lagcomp::HistoryBuffer;
+//!
+//! let mut history = HistoryBuffer::new().with_history(Duration::from_millis(500));
+//! for tick in 0..=30u32 {
+//!     // A entidade anda 1 unidade por tick de 50 ms.
+//!     history.record(Duration::from_millis(u64::from(tick) * 50), [tick as f32, 0.0]);
+//! }
+//!
+//! // O cliente viu o mundo aos 1.425 ms: a entidade estava entre os ticks 28 e 29.
+//! assert_eq!(history.rewind_to(Duration::from_millis(1_425)), Some([28.5, 0.0]));
+//!
+//! // Antes do histórico, o acerto não pode ser validado.
+//! assert_eq!(history.rewind_to(Duration::from_millis(500)), None);
+//! assert_eq!(history.latest(), Some(&[30.0, 0.0]));
+//! ```
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use crate::interpolation::Interpolate;
+
+/// Duração padrão do histórico.
+pub const DEFAULT_HISTORY: Duration = Duration::from_secs(1);
+
+/// Quantidade máxima padrão de estados guardados.
+pub const DEFAULT_MAX_STATES: usize = 256;
+
+/// Estados do mundo por tick, para rever o passado recente.
+#[derive(Debug, Clone)]
+pub struct HistoryBuffer<T> {
+    /// Estados em ordem crescente de tempo.
+    states: VecDeque<(Duration, T)>,
+    /// Tempo guardado antes do estado mais recente.
+    history: Duration,
+    /// Quantidade máxima de estados guardados.
+    max_states: usize,
+}
+
+impl<T> Default for HistoryBuffer<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> HistoryBuffer<T> {
+    /// Cria um histórico vazio, com [`DEFAULT_HISTORY`] e [`DEFAULT_MAX_STATES`].
+    pub fn new() -> Self {
+        Self {
+            states: VecDeque::new(),
+            history: DEFAULT_HISTORY,
+            max_states: DEFAULT_MAX_STATES,
+        }
+    }
+
+    /// Define quanto tempo antes do estado mais recente é guardado.
+    ///
+    /// ## Parâmetros
+    /// - `history`: Duração do histórico, em geral o maior RTT aceito somado ao atraso
+    ///   de interpolação dos clientes.
+    ///
+    /// ## Retorno
+    /// - `Self`: O histórico com a nova duração.
+    pub fn with_history(mut self, history: Duration) -> Self {
+        self.history = history;
+        self.trim();
+        self
+    }
+
+    /// Limita a quantidade de estados guardados, e com ela a memória usada.
+    ///
+    /// ## Parâmetros
+    /// - `max_states`: Limite de estados; no mínimo `2`. Ao ser atingido, os mais
+    ///   antigos são descartados, mesmo dentro da duração do histórico.
+    ///
+    /// ## Retorno
+    /// - `Self`: O histórico com o novo limite.
+    pub fn with_max_states(mut self, max_states: usize) -> Self {
+        self.max_states = max_states.max(2);
+        self.trim();
+        self
+    }
+
+    /// Grava o estado de um tick.
+    ///
+    /// Estados fora de ordem são inseridos na posição do seu tempo; um estado com o
+    /// mesmo tempo de outro o substitui. Os estados que saem do histórico são
+    /// descartados.
+    ///
+    /// ## Parâmetros
+    /// - `time`: Instante do tick, no relógio do servidor.
+    /// - `state`: Estado do mundo ou da entidade nesse tick.
+    pub fn record(&mut self, time: Duration, state: T) {
+        match self.states.binary_search_by_key(&time, |(t, _)| *t) {
+            Ok(index) => self.states[index].1 = state,
+            Err(index) => self.states.insert(index, (time, state)),
+        }
+        self.trim();
+    }
+
+    /// Retorna o estado mais recente, o do presente.
+    pub fn latest(&self) -> Option<&T> {
+        self.states.back().map(|(_, state)| state)
+    }
+
+    /// Retorna o tempo do estado mais antigo, até onde é possível voltar.
+    pub fn oldest_time(&self) -> Option<Duration> {
+        self.states.front().map(|(time, _)| *time)
+    }
+
+    /// Retorna o tempo do estado mais recente.
+    pub fn latest_time(&self) -> Option<Duration> {
+        self.states.back().map(|(time, _)| *time)
+    }
+
+    /// Retorna a quantidade de estados guardados.
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    /// Indica se o histórico não tem estados.
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+
+    /// Descarta todos os estados.
+    pub fn clear(&mut self) {
+        self.states.clear();
+    }
+
+    /// Descarta os estados além da duração e da quantidade máximas.
+    fn trim(&mut self) {
+        if let Some(latest) = self.latest_time() {
+            let cutoff = latest.saturating_sub(self.history);
+            let expired = self.states.partition_point(|(time, _)| *time < cutoff);
+            self.states.drain(..expired);
+        }
+        let excess = self.states.len().saturating_sub(self.max_states);
+        self.states.drain(..excess);
+    }
+}
+
+impl<T: Clone> HistoryBuffer<T> {
+    /// Retorna o estado em um instante passado, com [`Interpolate`].
+    ///
+    /// ## Parâmetros
+    /// - `timestamp`: Instante visto pelo cliente, no relógio do servidor.
+    ///
+    /// ## Retorno
+    /// - `Option<T>`: O estado interpolado entre os ticks que cercam o instante; o
+    ///   estado mais recente se o instante é posterior a ele; ou `None` se o instante
+    ///   é anterior ao histórico ou o histórico está vazio.
+    pub fn rewind_to(&self, timestamp: Duration) -> Option<T>
+    where
+        T: Interpolate,
+    {
+        self.rewind_to_with(timestamp, T::interpolate)
+    }
+
+    /// Retorna o estado em um instante passado com uma função de interpolação própria,
+    /// para estados que não implementam [`Interpolate`], como um mapa de entidades.
+    ///
+    /// ## Parâmetros
+    /// - `timestamp`: Instante visto pelo cliente, no relógio do servidor.
+    /// - `lerp`: Recebe os estados anterior e seguinte e a fração entre eles, de `0.0`
+    ///   a `1.0`.
+    ///
+    /// ## Retorno
+    /// - `Option<T>`: Como em [`HistoryBuffer::rewind_to`].
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use std::collections::BTreeMap;
+    /// use std::time::Duration;
+    /// use packet::lagcomp::HistoryBuffer;
+    ///
+    /// let mut history = HistoryBuffer::new();
+    /// history.record(Duration::from_millis(0), BTreeMap::from([(1u64, 0.0f32), (2, 5.0)]));
+    /// history.record(Duration::from_millis(100), BTreeMap::from([(1u64, 10.0f32)]));
+    ///
+    /// // Só as entidades presentes nos dois ticks são interpoladas.
+    /// let world = history
+    ///     .rewind_to_with(Duration::from_millis(50), |from, to, t| {
+    ///         from.iter()
+    ///             .filter_map(|(id, a)| to.get(id).map(|b| (*id, a + (b - a) * t)))
+    ///             .collect()
+    ///     })
+    ///     .unwrap();
+    /// assert_eq!(world, BTreeMap::from([(1, 5.0)]));
+    /// ```
+    pub fn rewind_to_with(
+        &self,
+        timestamp: Duration,
+        lerp: impl FnOnce(&T, &T, f32) -> T,
+    ) -> Option<T> {
+        if timestamp < self.oldest_time()? {
+            return None;
+        }
+        let next = self.states.partition_point(|(time, _)| *time <= timestamp);
+        if next == self.states.len() {
+            return self.latest().cloned();
+        }
+        let (from_time, from) = &self.states[next - 1];
+        let (to_time, to) = &self.states[next];
+        let t = (timestamp - *from_time).as_secs_f32() / (*to_time - *from_time).as_secs_f32();
+        Some(lerp(from, to, t))
+    }
+}
@@ -0,0 +1,156 @@
+//! # Módulo `liveness`
+//!
+//! Detecção de conexões mortas a partir do tempo desde o último pacote recebido.
+//!
+//! [`Liveness`] considera o outro lado morto quando nenhum pacote chega dentro do
+//! tempo limite. Para que uma conexão ociosa continue viva, cada lado usa um
+//! [`Heartbeat`], que produz um [`MessageType::Ping`] quando nada foi enviado durante
+//! o intervalo; o intervalo deve ser bem menor que o tempo limite do outro lado, para
+//! tolerar a perda de alguns pings.
+//!
+//! ## Exemplos
+//! ```rust
+//! use std::time::{Duration, Instant};
+//! use packet::liveness::{Heartbeat, Liveness};
+//! use packet::message::PacketKind;
+//!
+//! let start = Instant::now();
+//! let mut client = Heartbeat::new(Duration::from_millis(250), start);
+//! let mut server = Liveness::new(Duration::from_secs(1), start);
+//!
+//! // O cliente está ocioso, mas seus pings mantêm a conexão viva no servidor.
+//! for ms in (0..=3000).step_by(50) {
+//!     let now = start + Duration::from_millis(ms);
+//!     if let Some(ping) = client.poll(now, ms as u32, 7) {
+//!         assert_eq!(ping.kind(), PacketKind::Control);
+//!         server.record_received(now);
+//!     }
+//!     assert!(!server.is_dead(now));
+//! }
+//!
+//! // Sem pings, a conexão é considerada morta após o tempo limite.
+//! assert!(server.is_dead(start + Duration::from_millis(4001)));
+//! ```
+
+use std::time::{Duration, Instant};
+
+use crate::message::MessageType;
+use crate::Packet;
+
+/// Rastreia o tempo desde o último pacote recebido de um par.
+#[derive(Debug, Clone)]
+pub struct Liveness {
+    /// Tempo sem pacotes após o qual o par é considerado morto.
+    timeout: Duration,
+    /// Instante do último pacote recebido, ou da criação do rastreador.
+    last_received: Instant,
+}
+
+impl Liveness {
+    /// Cria um rastreador.
+    ///
+    /// ## Parâmetros
+    /// - `timeout`: Tempo sem pacotes após o qual o par é considerado morto.
+    /// - `now`: Instante inicial, normalmente o do estabelecimento da conexão.
+    ///
+    /// ## Retorno
+    /// - `Self`: Um rastreador que conta o tempo a partir de `now`.
+    pub fn new(timeout: Duration, now: Instant) -> Self {
+        Self {
+            timeout,
+            last_received: now,
+        }
+    }
+
+    /// Registra a chegada de um pacote do par.
+    ///
+    /// ## Parâmetros
+    /// - `now`: Instante da chegada.
+    pub fn record_received(&mut self, now: Instant) {
+        self.last_received = self.last_received.max(now);
+    }
+
+    /// Retorna o instante do último pacote recebido, ou o da criação do rastreador se
+    /// nenhum chegou.
+    pub fn last_received(&self) -> Instant {
+        self.last_received
+    }
+
+    /// Retorna o tempo desde o último pacote recebido.
+    ///
+    /// ## Parâmetros
+    /// - `now`: Instante atual.
+    pub fn time_since_last(&self, now: Instant) -> Duration {
+        now.saturating_duration_since(self.last_received)
+    }
+
+    /// Indica se nenhum pacote chegou dentro do tempo limite.
+    ///
+    /// ## Parâmetros
+    /// - `now`: Instante atual.
+    ///
+    /// ## Retorno
+    /// - `bool`: `true` se o tempo desde o último pacote excede o tempo limite.
+    pub fn is_dead(&self, now: Instant) -> bool {
+        self.time_since_last(now) > self.timeout
+    }
+}
+
+/// Produz pings quando a conexão fica ociosa no sentido de envio.
+#[derive(Debug, Clone)]
+pub struct Heartbeat {
+    /// Tempo sem envios após o qual um ping é produzido.
+    interval: Duration,
+    /// Instante do último envio.
+    last_sent: Instant,
+}
+
+impl Heartbeat {
+    /// Cria um gerador de pings.
+    ///
+    /// ## Parâmetros
+    /// - `interval`: Tempo sem envios após o qual um ping é produzido.
+    /// - `now`: Instante inicial.
+    ///
+    /// ## Retorno
+    /// - `Self`: Um gerador que produz o primeiro ping após `interval`.
+    pub fn new(interval: Duration, now: Instant) -> Self {
+        Self {
+            interval,
+            last_sent: now,
+        }
+    }
+
+    /// Registra o envio de um pacote, adiando o próximo ping.
+    ///
+    /// ## Parâmetros
+    /// - `now`: Instante do envio.
+    pub fn record_sent(&mut self, now: Instant) {
+        self.last_sent = self.last_sent.max(now);
+    }
+
+    /// Produz um ping se nada foi enviado durante o intervalo.
+    ///
+    /// O ping produzido é registrado como envio.
+    ///
+    /// ## Parâmetros
+    /// - `now`: Instante atual.
+    /// - `sequence`: Sequência do ping.
+    /// - `player_id`: Identificador do jogador remetente.
+    ///
+    /// ## Retorno
+    /// - `Option<Packet>`: Um pacote [`MessageType::Ping`] sem payload, ou `None` se
+    ///   ainda não é hora.
+    pub fn poll(&mut self, now: Instant, sequence: u32, player_id: u64) -> Option<Packet> {
+        if now.saturating_duration_since(self.last_sent) < self.interval {
+            return None;
+        }
+        self.last_sent = now;
+        Some(Packet::new(
+            MessageType::Ping.into(),
+            sequence,
+            player_id,
+            Vec::new(),
+        ))
+    }
+}
@@ -0,0 +1,314 @@
+//! # Módulo `lobby`
+//!
+//! Salas de jogadores sobre as sessões do [`Server`](crate::connection::Server).
+//!
+//! Um [`Lobby`] guarda as salas, cada uma com um limite de membros e um anfitrião.
+//! Um jogador está em no máximo uma sala. Quando o anfitrião sai ou se desconecta, o
+//! membro mais antigo assume; quando o último membro sai, a sala é fechada. Cada
+//! mudança produz um [`LobbyEvent`].
+//!
+//! As mensagens de uma sala são pacotes [`MessageType::ROOM`] com o identificador da
+//! sala no `group_id` do cabeçalho, criados por [`Packet::room_message`]. O servidor
+//! repassa a mensagem de um membro aos demais membros da sala e a entrega também à
+//! aplicação; as de quem não é membro da sala só são entregues à aplicação.
+//!
+//! O servidor mantém um [`Lobby`] próprio, acessível por
+//! [`Server::lobby_mut`](crate::connection::Server::lobby_mut), que remove da sala os
+//! jogadores desconectados e cujos eventos chegam por
+//! [`Server::poll_events`](crate::connection::Server::poll_events) como
+//! [`NetEvent::Lobby`](crate::connection::NetEvent::Lobby).
+//!
+//! ## Exemplos
+//! ```rust
+//! use packet::lobby::{Lobby, LobbyError, LobbyEvent};
+//!
+//! let mut lobby = Lobby::new();
+//! let room_id = lobby.create_room(1, 2).unwrap();
+//! lobby.join(room_id, 2).unwrap();
+//! assert_eq!(lobby.join(room_id, 3), Err(LobbyError::RoomFull));
+//!
+//! // O anfitrião sai e o membro mais antigo assume.
+//! assert_eq!(lobby.leave(1), Some(room_id));
+//! assert_eq!(lobby.room(room_id).unwrap().host(), 2);
+//!
+//! let events = lobby.take_events();
+//! assert_eq!(events.last(), Some(&LobbyEvent::HostChanged { room_id, host: 2 }));
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use crate::message::MessageType;
+use crate::Packet;
+
+/// Motivo da recusa de uma operação do [`Lobby`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LobbyError {
+    /// A sala não existe.
+    RoomNotFound,
+    /// A sala atingiu o limite de membros.
+    RoomFull,
+    /// O jogador já está em uma sala.
+    AlreadyInRoom,
+}
+
+impl fmt::Display for LobbyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::RoomNotFound => write!(f, "sala inexistente"),
+            Self::RoomFull => write!(f, "sala cheia"),
+            Self::AlreadyInRoom => write!(f, "jogador já está em uma sala"),
+        }
+    }
+}
+
+impl std::error::Error for LobbyError {}
+
+/// Mudança em uma sala do [`Lobby`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LobbyEvent {
+    /// Uma sala foi criada, com o anfitrião como único membro.
+    RoomCreated {
+        /// Sala criada.
+        room_id: u32,
+        /// Anfitrião da sala.
+        host: u64,
+    },
+    /// Um jogador entrou em uma sala.
+    PlayerJoined {
+        /// Sala.
+        room_id: u32,
+        /// Jogador.
+        session_id: u64,
+    },
+    /// Um jogador saiu de uma sala, por vontade própria ou por desconexão.
+    PlayerLeft {
+        /// Sala.
+        room_id: u32,
+        /// Jogador.
+        session_id: u64,
+    },
+    /// O anfitrião saiu e outro membro assumiu.
+    HostChanged {
+        /// Sala.
+        room_id: u32,
+        /// Novo anfitrião.
+        host: u64,
+    },
+    /// O último membro saiu e a sala foi fechada.
+    RoomClosed {
+        /// Sala fechada.
+        room_id: u32,
+    },
+}
+
+/// Sala de jogadores.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Room {
+    /// Identificador da sala, gravado no `group_id` das suas mensagens.
+    id: u32,
+    /// Anfitrião da sala.
+    host: u64,
+    /// Limite de membros.
+    capacity: usize,
+    /// Membros, na ordem em que entraram.
+    members: Vec<u64>,
+}
+
+impl Room {
+    /// Retorna o identificador da sala.
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// Retorna o anfitrião da sala.
+    pub fn host(&self) -> u64 {
+        self.host
+    }
+
+    /// Retorna o limite de membros.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Retorna os membros, na ordem em que entraram.
+    pub fn members(&self) -> &[u64] {
+        &self.members
+    }
+
+    /// Indica se a sala atingiu o limite de membros.
+    pub fn is_full(&self) -> bool {
+        self.members.len() >= self.capacity
+    }
+}
+
+/// Conjunto de salas e a sala de cada jogador.
+#[derive(Debug, Clone)]
+pub struct Lobby {
+    /// Salas, pelo identificador.
+    rooms: BTreeMap<u32, Room>,
+    /// Sala de cada jogador.
+    membership: HashMap<u64, u32>,
+    /// Identificador da próxima sala.
+    next_room_id: u32,
+    /// Eventos ainda não entregues.
+    events: Vec<LobbyEvent>,
+}
+
+impl Default for Lobby {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Lobby {
+    /// Cria um lobby sem salas.
+    pub fn new() -> Self {
+        Self {
+            rooms: BTreeMap::new(),
+            membership: HashMap::new(),
+            next_room_id: 1,
+            events: Vec::new(),
+        }
+    }
+
+    /// Cria uma sala com o anfitrião como único membro.
+    ///
+    /// ## Parâmetros
+    /// - `host`: Sessão do anfitrião.
+    /// - `capacity`: Limite de membros, incluindo o anfitrião; no mínimo `1`.
+    ///
+    /// ## Retorno
+    /// - `Result<u32, LobbyError>`: O identificador da sala, nunca `0`, ou
+    ///   [`LobbyError::AlreadyInRoom`] se o anfitrião já está em uma sala.
+    pub fn create_room(&mut self, host: u64, capacity: usize) -> Result<u32, LobbyError> {
+        if self.membership.contains_key(&host) {
+            return Err(LobbyError::AlreadyInRoom);
+        }
+        let room_id = self.next_room_id;
+        self.next_room_id = self.next_room_id.checked_add(1).unwrap_or(1);
+        self.rooms.insert(
+            room_id,
+            Room {
+                id: room_id,
+                host,
+                capacity: capacity.max(1),
+                members: vec![host],
+            },
+        );
+        self.membership.insert(host, room_id);
+        self.events.push(LobbyEvent::RoomCreated { room_id, host });
+        Ok(room_id)
+    }
+
+    /// Coloca um jogador em uma sala.
+    ///
+    /// ## Parâmetros
+    /// - `room_id`: Sala.
+    /// - `session_id`: Sessão do jogador.
+    ///
+    /// ## Retorno
+    /// - `Result<(), LobbyError>`: `Ok` se o jogador entrou; [`LobbyError::AlreadyInRoom`]
+    ///   se ele já está em uma sala, [`LobbyError::RoomNotFound`] ou
+    ///   [`LobbyError::RoomFull`].
+    pub fn join(&mut self, room_id: u32, session_id: u64) -> Result<(), LobbyError> {
+        if self.membership.contains_key(&session_id) {
+            return Err(LobbyError::AlreadyInRoom);
+        }
+        let room = self
+            .rooms
+            .get_mut(&room_id)
+            .ok_or(LobbyError::RoomNotFound)?;
+        if room.is_full() {
+            return Err(LobbyError::RoomFull);
+        }
+        room.members.push(session_id);
+        self.membership.insert(session_id, room_id);
+        self.events.push(LobbyEvent::PlayerJoined {
+            room_id,
+            session_id,
+        });
+        Ok(())
+    }
+
+    /// Tira um jogador da sua sala.
+    ///
+    /// Se o jogador era o anfitrião, o membro mais antigo assume; se era o último
+    /// membro, a sala é fechada.
+    ///
+    /// ## Parâmetros
+    /// - `session_id`: Sessão do jogador.
+    ///
+    /// ## Retorno
+    /// - `Option<u32>`: A sala de onde o jogador saiu, ou `None` se ele não estava em
+    ///   uma sala.
+    pub fn leave(&mut self, session_id: u64) -> Option<u32> {
+        let room_id = self.membership.remove(&session_id)?;
+        let room = self.rooms.get_mut(&room_id).expect("sala do membro");
+        room.members.retain(|&member| member != session_id);
+        self.events.push(LobbyEvent::PlayerLeft {
+            room_id,
+            session_id,
+        });
+        match room.members.first() {
+            None => {
+                self.rooms.remove(&room_id);
+                self.events.push(LobbyEvent::RoomClosed { room_id });
+            }
+            Some(&oldest) if room.host == session_id => {
+                room.host = oldest;
+                self.events.push(LobbyEvent::HostChanged {
+                    room_id,
+                    host: oldest,
+                });
+            }
+            Some(_) => {}
+        }
+        Some(room_id)
+    }
+
+    /// Retorna uma sala.
+    pub fn room(&self, room_id: u32) -> Option<&Room> {
+        self.rooms.get(&room_id)
+    }
+
+    /// Retorna a sala de um jogador.
+    pub fn room_of(&self, session_id: u64) -> Option<u32> {
+        self.membership.get(&session_id).copied()
+    }
+
+    /// Retorna as salas, em ordem de identificador.
+    pub fn rooms(&self) -> impl Iterator<Item = &Room> {
+        self.rooms.values()
+    }
+
+    /// Retorna e descarta os eventos ocorridos desde a última chamada, na ordem.
+    pub fn take_events(&mut self) -> Vec<LobbyEvent> {
+        std::mem::take(&mut self.events)
+    }
+}
+
+impl Packet {
+    /// Cria um pacote [`MessageType::ROOM`] para os membros de uma sala.
+    ///
+    /// ## Parâmetros
+    /// - `room_id`: Sala de destino, gravada no `group_id`.
+    /// - `sequence`: Sequência do pacote.
+    /// - `player_id`: Identificador único do jogador.
+    /// - `payload`: Mensagem da aplicação.
+    ///
+    /// ## Retorno
+    /// - `Self`: O pacote da sala.
+    pub fn room_message(room_id: u32, sequence: u32, player_id: u64, payload: Vec<u8>) -> Self {
+        Self::new(MessageType::ROOM.into(), sequence, player_id, payload).with_group_id(room_id)
+    }
+
+    /// Retorna a sala de destino de um pacote [`MessageType::ROOM`].
+    ///
+    /// ## Retorno
+    /// - `Option<u32>`: O `group_id` do pacote, ou `None` se ele não é uma mensagem de
+    ///   sala.
+    pub fn room_id(&self) -> Option<u32> {
+        (self.header.message_type == u8::from(MessageType::ROOM)).then_some(self.header.group_id)
+    }
+}
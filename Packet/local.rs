@@ -0,0 +1,103 @@
+//! # Módulo `local`
+//!
+//! Transporte de pacotes dentro do processo, disponível com o recurso `tokio`.
+//!
+//! [`LocalTransport`] oferece a mesma interface de envio e recepção de
+//! [`PacketSocket`](crate::socket::PacketSocket) sobre canais `tokio::sync::mpsc`, sem
+//! abrir sockets: útil para testes de integração determinísticos e para jogos locais
+//! em tela dividida. Os pacotes trafegam serializados e são verificados ao chegar,
+//! exercitando o mesmo caminho de um socket real.
+//!
+//! ## Exemplos
+//! ```rust,no_run
+//! use packet::Packet;
+//! use packet::local::LocalTransport;
+//!
+//! # async fn run() -> std::io::Result<()> {
+//! let (client, mut server) = LocalTransport::pair();
+//!
+//! client.send(&Packet::new(1, 1, 7, vec![1, 2, 3]))?;
+//! let received = server.recv().await?;
+//! assert_eq!(received.payload, [1, 2, 3]);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::io;
+
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+use crate::{Packet, PacketError};
+
+/// Uma extremidade de um par de transportes locais.
+///
+/// Como em UDP, o envio nunca bloqueia: os pacotes ficam enfileirados até que a
+/// outra extremidade os receba.
+#[derive(Debug)]
+pub struct LocalTransport {
+    /// Canal para a outra extremidade.
+    sender: UnboundedSender<Vec<u8>>,
+    /// Canal vindo da outra extremidade.
+    receiver: UnboundedReceiver<Vec<u8>>,
+}
+
+impl LocalTransport {
+    /// Cria duas extremidades conectadas entre si.
+    ///
+    /// ## Retorno
+    /// - `(Self, Self)`: As extremidades; o que uma envia, a outra recebe.
+    pub fn pair() -> (Self, Self) {
+        let (to_second, from_first) = unbounded_channel();
+        let (to_first, from_second) = unbounded_channel();
+        (
+            Self {
+                sender: to_second,
+                receiver: from_second,
+            },
+            Self {
+                sender: to_first,
+                receiver: from_first,
+            },
+        )
+    }
+
+    /// Envia um pacote à outra extremidade.
+    ///
+    /// ## Parâmetros
+    /// - `packet`: Pacote a ser enviado.
+    ///
+    /// ## Retorno
+    /// - `io::Result<usize>`: Quantidade de bytes enviados, ou
+    ///   [`io::ErrorKind::BrokenPipe`] se a outra extremidade foi descartada.
+    pub fn send(&self, packet: &Packet) -> io::Result<usize> {
+        let bytes = packet.to_bytes();
+        let len = bytes.len();
+        self.sender
+            .send(bytes)
+            .map_err(|_| io::Error::from(io::ErrorKind::BrokenPipe))?;
+        Ok(len)
+    }
+
+    /// Aguarda o próximo pacote da outra extremidade, verificando seu checksum.
+    ///
+    /// ## Retorno
+    /// - `io::Result<Packet>`: O pacote recebido,
+    ///   [`io::ErrorKind::ConnectionAborted`] se a outra extremidade foi descartada e
+    ///   não há mais pacotes, ou [`io::ErrorKind::InvalidData`] com o [`PacketError`]
+    ///   de um pacote inválido.
+    pub async fn recv(&mut self) -> io::Result<Packet> {
+        let bytes = self
+            .receiver
+            .recv()
+            .await
+            .ok_or_else(|| io::Error::from(io::ErrorKind::ConnectionAborted))?;
+        Ok(parse(&bytes)?)
+    }
+}
+
+/// Interpreta um pacote recebido e verifica seu checksum.
+fn parse(bytes: &[u8]) -> Result<Packet, PacketError> {
+    let packet = Packet::from_bytes(bytes)?;
+    packet.check_checksum()?;
+    Ok(packet)
+}
@@ -0,0 +1,134 @@
+//! # Módulo `mac`
+//!
+//! Autenticação de pacotes com HMAC-SHA256 e chave compartilhada, disponível com o
+//! recurso `crypto`.
+//!
+//! Para jogos que só precisam impedir a adulteração, sem esconder o conteúdo, a
+//! autenticação custa menos que a cifragem do módulo `encrypt`: o payload segue em
+//! claro e ganha uma etiqueta de [`MAC_SIZE`] (16) bytes, o HMAC-SHA256 (RFC 2104)
+//! truncado. Ao contrário das assinaturas do módulo `sign`, os dois lados conhecem a
+//! mesma chave, combinada no aperto de mão por
+//! [`KeyExchange`](crate::encrypt::KeyExchange).
+//!
+//! A etiqueta cobre [`Packet::canonical_bytes`] do pacote com a flag
+//! [`PacketFlags::AUTHENTICATED`] e o `payload_size` final, como os dados associados
+//! da cifragem, seguido do payload; relays ainda podem decrementar o `ttl` e anexar
+//! confirmações sem invalidá-la.
+//!
+//! ## Exemplos
+//! ```rust
+//! use packet::{Packet, PacketError};
+//!
+//! let key = [7; 32];
+//!
+//! let tagged = Packet::new(1, 1, 7, vec![1, 2, 3]).authenticate(&key);
+//! assert_eq!(tagged.payload.len(), 3 + packet::mac::MAC_SIZE);
+//! assert_eq!(tagged.payload[..3], [1, 2, 3]);
+//!
+//! let mut received = Packet::from_bytes(&tagged.to_bytes()).unwrap();
+//! received.decrement_ttl().unwrap();
+//! assert_eq!(received.verify_mac(&key).unwrap().payload, [1, 2, 3]);
+//!
+//! received.payload[0] = 9;
+//! assert_eq!(received.verify_mac(&key), Err(PacketError::AuthenticationFailed));
+//! ```
+
+use sha2::{Digest, Sha256};
+
+use crate::{Packet, PacketError, PacketFlags};
+
+/// Tamanho, em bytes, da etiqueta anexada ao payload.
+pub const MAC_SIZE: usize = 16;
+
+/// Tamanho, em bytes, da chave do HMAC.
+pub const MAC_KEY_SIZE: usize = 32;
+
+/// Tamanho do bloco do SHA-256, em bytes.
+const BLOCK_SIZE: usize = 64;
+
+/// Calcula o HMAC-SHA256 da concatenação de `parts`.
+fn hmac_sha256(key: &[u8; MAC_KEY_SIZE], parts: &[&[u8]]) -> [u8; 32] {
+    let mut block = [0; BLOCK_SIZE];
+    block[..MAC_KEY_SIZE].copy_from_slice(key);
+
+    let mut inner = Sha256::new();
+    inner.update(block.map(|byte| byte ^ 0x36));
+    for part in parts {
+        inner.update(part);
+    }
+    let mut outer = Sha256::new();
+    outer.update(block.map(|byte| byte ^ 0x5c));
+    outer.update(inner.finalize());
+
+    let mut digest = [0; 32];
+    digest.copy_from_slice(&outer.finalize());
+    digest
+}
+
+/// Compara duas etiquetas sem encerrar na primeira diferença.
+fn tags_match(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+impl Packet {
+    /// Autentica o pacote, anexando a etiqueta HMAC ao fim do payload.
+    ///
+    /// ## Parâmetros
+    /// - `key`: Chave compartilhada com o destinatário.
+    ///
+    /// ## Retorno
+    /// - `Packet`: O pacote com a flag [`PacketFlags::AUTHENTICATED`], o payload
+    ///   acrescido de [`MAC_SIZE`] bytes e `payload_size` e checksum atualizados.
+    pub fn authenticate(mut self, key: &[u8; MAC_KEY_SIZE]) -> Packet {
+        self.header.flags.insert(PacketFlags::AUTHENTICATED);
+        let tag = self.mac(key, &self.payload, self.payload.len() + MAC_SIZE);
+        self.payload.extend_from_slice(&tag[..MAC_SIZE]);
+        self.refresh_header();
+        self
+    }
+
+    /// Verifica a etiqueta de um pacote autenticado por [`Packet::authenticate`].
+    ///
+    /// ## Parâmetros
+    /// - `key`: Chave com que o pacote foi autenticado.
+    ///
+    /// ## Retorno
+    /// - `Result<Packet, PacketError>`: O pacote sem a etiqueta e sem a flag
+    ///   [`PacketFlags::AUTHENTICATED`], com os demais campos do cabeçalho preservados,
+    ///   ou [`PacketError::AuthenticationFailed`] se o pacote não está autenticado, foi
+    ///   autenticado com outra chave ou foi alterado.
+    pub fn verify_mac(&self, key: &[u8; MAC_KEY_SIZE]) -> Result<Packet, PacketError> {
+        if !self.header.flags.contains(PacketFlags::AUTHENTICATED) {
+            return Err(PacketError::AuthenticationFailed);
+        }
+        let split = self
+            .payload
+            .len()
+            .checked_sub(MAC_SIZE)
+            .ok_or(PacketError::AuthenticationFailed)?;
+        let (payload, tag) = self.payload.split_at(split);
+        let expected = self.mac(key, payload, self.payload.len());
+        if !tags_match(tag, &expected[..MAC_SIZE]) {
+            return Err(PacketError::AuthenticationFailed);
+        }
+
+        let mut verified = Packet {
+            header: self.header.clone(),
+            payload: payload.to_vec(),
+        };
+        verified.header.flags.remove(PacketFlags::AUTHENTICATED);
+        verified.refresh_header();
+        Ok(verified)
+    }
+
+    /// Calcula o HMAC do cabeçalho canônico, com `tagged_len` bytes de payload, e de
+    /// `payload`.
+    fn mac(&self, key: &[u8; MAC_KEY_SIZE], payload: &[u8], tagged_len: usize) -> [u8; 32] {
+        let mut header = Packet {
+            header: self.header.clone(),
+            payload: Vec::new(),
+        };
+        header.header.payload_size = tagged_len as u32;
+        hmac_sha256(key, &[&header.canonical_bytes(), payload])
+    }
+}
@@ -0,0 +1,442 @@
+//! # Módulo `message`
+//!
+//! Define os tipos de mensagem conhecidos pelo protocolo ([`MessageType`]) e a
+//! classificação grosseira de pacotes ([`PacketKind`]) usada em logs e métricas.
+//!
+//! ## Mapeamento de tipos
+//! | Byte          | [`MessageType`]              | [`PacketKind`] |
+//! |---------------|------------------------------|----------------|
+//! | `1`           | [`MessageType::Data`]        | `Data`         |
+//! | `2`           | [`MessageType::Ack`]         | `Ack`          |
+//! | `3`           | [`MessageType::Connect`]     | `Control`      |
+//! | `4`           | [`MessageType::Disconnect`]  | `Control`      |
+//! | `5`           | [`MessageType::Ping`]        | `Control`      |
+//! | `6`           | [`MessageType::Pong`]        | `Control`      |
+//! | `7`           | [`MessageType::Discovery`]   | `Control`      |
+//! | `8`           | [`MessageType::Query`]       | `Control`      |
+//! | `0x80..=0xFF` | [`MessageType::Custom`]      | `Data`         |
+//!
+//! Dentro da faixa da aplicação, os primeiros valores são convencionados para
+//! mensagens comuns de jogos; os três primeiros são usados pelos atalhos de
+//! [`PacketBuilder`](crate::builder::PacketBuilder):
+//!
+//! | Byte   | Constante                      | Conteúdo                                 |
+//! |--------|--------------------------------|------------------------------------------|
+//! | `0x80` | [`MessageType::INPUT`]         | Entrada do jogador                       |
+//! | `0x81` | [`MessageType::STATE`]         | Estado do jogo                           |
+//! | `0x82` | [`MessageType::CHAT`]          | Texto UTF-8 ([`Packet::chat_text`])      |
+//! | `0x83` | [`MessageType::INPUT_HISTORY`] | Entradas recentes, com redundância       |
+//! | `0x84` | [`MessageType::ROOM`]          | Mensagem aos membros de uma sala         |
+//! | `0x85` | [`MessageType::STREAM`]        | Quadro de voz ou vídeo                   |
+//!
+//! Os demais valores são reservados e classificados como `Data`. Pacotes com a flag
+//! [`PacketFlags::FRAGMENT`](crate::PacketFlags::FRAGMENT) são sempre `Fragment`,
+//! independentemente do tipo.
+//!
+//! [`MessageRegistry`] associa tipos, inclusive os da faixa da aplicação, a funções
+//! que tratam os pacotes recebidos.
+
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{Packet, PacketError, PacketFlags};
+
+/// Tipos de mensagem definidos pelo protocolo, serializados como um único byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MessageType {
+    /// Dados da aplicação.
+    Data,
+    /// Confirmação de recebimento.
+    Ack,
+    /// Pedido de conexão.
+    Connect,
+    /// Encerramento de conexão.
+    Disconnect,
+    /// Pedido de medição de latência.
+    Ping,
+    /// Resposta a um [`MessageType::Ping`].
+    Pong,
+    /// Sonda de descoberta de servidores na rede local e sua resposta.
+    Discovery,
+    /// Consulta ao estado de um servidor, sem conexão, e sua resposta.
+    Query,
+    /// Tipo definido pela aplicação, na faixa `0x80..=0xFF`.
+    Custom(u8),
+}
+
+impl MessageType {
+    /// Primeiro byte da faixa reservada a tipos definidos pela aplicação.
+    pub const CUSTOM_START: u8 = 0x80;
+
+    /// Entrada do jogador, enviada do cliente ao servidor.
+    pub const INPUT: Self = Self::Custom(Self::CUSTOM_START);
+    /// Estado do jogo, enviado do servidor aos clientes.
+    pub const STATE: Self = Self::Custom(Self::CUSTOM_START + 1);
+    /// Mensagem de chat em texto UTF-8.
+    pub const CHAT: Self = Self::Custom(Self::CUSTOM_START + 2);
+    /// Entradas recentes do jogador, repetidas em cada pacote para resistir a perdas.
+    pub const INPUT_HISTORY: Self = Self::Custom(Self::CUSTOM_START + 3);
+    /// Mensagem aos membros da sala indicada no `group_id`, repassada pelo servidor.
+    pub const ROOM: Self = Self::Custom(Self::CUSTOM_START + 4);
+    /// Quadro de voz ou vídeo carimbado por um
+    /// [`StreamChannel`](crate::streaming::StreamChannel).
+    pub const STREAM: Self = Self::Custom(Self::CUSTOM_START + 5);
+
+    /// Classifica o tipo de mensagem em uma categoria de [`PacketKind`].
+    pub fn kind(self) -> PacketKind {
+        match self {
+            Self::Data | Self::Custom(_) => PacketKind::Data,
+            Self::Ack => PacketKind::Ack,
+            Self::Connect
+            | Self::Disconnect
+            | Self::Ping
+            | Self::Pong
+            | Self::Discovery
+            | Self::Query => PacketKind::Control,
+        }
+    }
+}
+
+impl TryFrom<u8> for MessageType {
+    type Error = u8;
+
+    /// Converte o byte do cabeçalho no tipo correspondente, devolvendo o próprio byte
+    /// quando ele pertence à faixa reservada.
+    fn try_from(value: u8) -> Result<Self, u8> {
+        match value {
+            1 => Ok(Self::Data),
+            2 => Ok(Self::Ack),
+            3 => Ok(Self::Connect),
+            4 => Ok(Self::Disconnect),
+            5 => Ok(Self::Ping),
+            6 => Ok(Self::Pong),
+            7 => Ok(Self::Discovery),
+            8 => Ok(Self::Query),
+            Self::CUSTOM_START..=u8::MAX => Ok(Self::Custom(value)),
+            _ => Err(value),
+        }
+    }
+}
+
+impl From<MessageType> for u8 {
+    fn from(message_type: MessageType) -> u8 {
+        match message_type {
+            MessageType::Data => 1,
+            MessageType::Ack => 2,
+            MessageType::Connect => 3,
+            MessageType::Disconnect => 4,
+            MessageType::Ping => 5,
+            MessageType::Pong => 6,
+            MessageType::Discovery => 7,
+            MessageType::Query => 8,
+            MessageType::Custom(value) => value,
+        }
+    }
+}
+
+/// Nomes dos tipos de mensagem conhecidos, usados em logs e ferramentas.
+///
+/// Inclui os tipos do protocolo e os tipos convencionados da faixa da aplicação
+/// ([`MessageType::INPUT`], [`MessageType::STATE`], [`MessageType::CHAT`],
+/// [`MessageType::INPUT_HISTORY`], [`MessageType::ROOM`] e [`MessageType::STREAM`]).
+pub const MESSAGE_TYPE_NAMES: &[(u8, &str)] = &[
+    (1, "data"),
+    (2, "ack"),
+    (3, "connect"),
+    (4, "disconnect"),
+    (5, "ping"),
+    (6, "pong"),
+    (7, "discovery"),
+    (8, "query"),
+    (MessageType::CUSTOM_START, "input"),
+    (MessageType::CUSTOM_START + 1, "state"),
+    (MessageType::CUSTOM_START + 2, "chat"),
+    (MessageType::CUSTOM_START + 3, "input_history"),
+    (MessageType::CUSTOM_START + 4, "room"),
+    (MessageType::CUSTOM_START + 5, "stream"),
+];
+
+/// Retorna o nome legível de um tipo de mensagem.
+///
+/// ## Parâmetros
+/// - `message_type`: Byte do tipo de mensagem.
+///
+/// ## Retorno
+/// - `&'static str`: O nome em [`MESSAGE_TYPE_NAMES`], `"custom"` para os demais valores
+///   da faixa da aplicação ou `"unknown"` para a faixa reservada.
+///
+/// ## Exemplos
+/// A tabela é mantida em sincronia com [`MessageType`]:
+/// ```rust
+/// use packet::message::{message_type_from_name, message_type_name, MessageType};
+///
+/// assert_eq!(message_type_name(5), "ping");
+/// assert_eq!(message_type_name(0x90), "custom");
+/// assert_eq!(message_type_name(0x10), "unknown");
+/// assert_eq!(message_type_from_name("chat"), Some(MessageType::CHAT.into()));
+///
+/// for byte in 0..=u8::MAX {
+///     let name = message_type_name(byte);
+///     match MessageType::try_from(byte) {
+///         Ok(MessageType::Custom(_)) if name == "custom" => {}
+///         Ok(_) => assert_eq!(message_type_from_name(name), Some(byte)),
+///         Err(_) => assert_eq!(name, "unknown"),
+///     }
+/// }
+/// ```
+pub fn message_type_name(message_type: u8) -> &'static str {
+    if let Some(&(_, name)) = MESSAGE_TYPE_NAMES
+        .iter()
+        .find(|&&(value, _)| value == message_type)
+    {
+        return name;
+    }
+    match MessageType::try_from(message_type) {
+        Ok(_) => "custom",
+        Err(_) => "unknown",
+    }
+}
+
+/// Retorna o tipo de mensagem correspondente a um nome de [`MESSAGE_TYPE_NAMES`].
+///
+/// ## Parâmetros
+/// - `name`: Nome do tipo, como retornado por [`message_type_name`].
+///
+/// ## Retorno
+/// - `Option<u8>`: O byte do tipo, ou `None` se o nome não é conhecido.
+pub fn message_type_from_name(name: &str) -> Option<u8> {
+    MESSAGE_TYPE_NAMES
+        .iter()
+        .find(|&&(_, known)| known == name)
+        .map(|&(value, _)| value)
+}
+
+/// Função que trata os pacotes de um tipo registrado em um [`MessageRegistry`].
+type MessageHandler = Box<dyn FnMut(&Packet) + Send>;
+
+/// Tabela de funções que tratam os pacotes recebidos, indexada pelo tipo de mensagem.
+///
+/// ## Exemplos
+/// ```rust
+/// use std::sync::{Arc, Mutex};
+/// use packet::{Packet, PacketError};
+/// use packet::message::{MessageRegistry, MessageType};
+///
+/// const SPAWN: MessageType = MessageType::Custom(0x90);
+///
+/// let spawned = Arc::new(Mutex::new(Vec::new()));
+/// let mut registry = MessageRegistry::new();
+/// let log = Arc::clone(&spawned);
+/// registry.register(SPAWN, move |packet| log.lock().unwrap().push(packet.header.player_id));
+///
+/// assert_eq!(registry.dispatch(&Packet::new(SPAWN.into(), 1, 7, vec![])), Ok(true));
+/// assert_eq!(registry.dispatch(&Packet::new(MessageType::Ping.into(), 2, 7, vec![])), Ok(false));
+/// assert_eq!(*spawned.lock().unwrap(), [7]);
+///
+/// let reserved = Packet::new(0x10, 3, 7, vec![]);
+/// assert_eq!(registry.dispatch(&reserved), Err(PacketError::UnknownMessageType(0x10)));
+/// ```
+#[derive(Default)]
+pub struct MessageRegistry {
+    /// Funções registradas, pelo byte do tipo.
+    handlers: BTreeMap<u8, MessageHandler>,
+}
+
+impl fmt::Debug for MessageRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let registered: Vec<_> = self.handlers.keys().collect();
+        f.debug_struct("MessageRegistry")
+            .field("registered", &registered)
+            .finish()
+    }
+}
+
+impl MessageRegistry {
+    /// Cria uma tabela vazia.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Associa uma função a um tipo de mensagem, substituindo a anterior.
+    ///
+    /// ## Parâmetros
+    /// - `message_type`: Tipo tratado pela função.
+    /// - `handler`: Função chamada com cada pacote do tipo.
+    ///
+    /// ## Retorno
+    /// - `bool`: `true` se o tipo já tinha uma função, agora substituída.
+    pub fn register(
+        &mut self,
+        message_type: MessageType,
+        handler: impl FnMut(&Packet) + Send + 'static,
+    ) -> bool {
+        self.handlers
+            .insert(message_type.into(), Box::new(handler))
+            .is_some()
+    }
+
+    /// Remove a função associada a um tipo de mensagem.
+    ///
+    /// ## Retorno
+    /// - `bool`: `true` se o tipo tinha uma função.
+    pub fn unregister(&mut self, message_type: MessageType) -> bool {
+        self.handlers.remove(&message_type.into()).is_some()
+    }
+
+    /// Indica se o tipo de mensagem tem uma função associada.
+    pub fn is_registered(&self, message_type: MessageType) -> bool {
+        self.handlers.contains_key(&message_type.into())
+    }
+
+    /// Entrega um pacote à função do seu tipo.
+    ///
+    /// ## Parâmetros
+    /// - `packet`: Pacote recebido.
+    ///
+    /// ## Retorno
+    /// - `Result<bool, PacketError>`: `true` se uma função tratou o pacote, `false` se o
+    ///   tipo não tem função associada, ou [`PacketError::UnknownMessageType`] se o tipo
+    ///   pertence à faixa reservada.
+    pub fn dispatch(&mut self, packet: &Packet) -> Result<bool, PacketError> {
+        let message_type = u8::from(packet.message_type()?);
+        match self.handlers.get_mut(&message_type) {
+            Some(handler) => {
+                handler(packet);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+}
+
+/// Categoria estável de um pacote, usada para agregar métricas e logs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PacketKind {
+    /// Mensagens de controle de conexão.
+    Control,
+    /// Dados da aplicação.
+    Data,
+    /// Confirmações de recebimento.
+    Ack,
+    /// Fragmento de uma mensagem maior.
+    Fragment,
+}
+
+/// Versão do protocolo da aplicação, negociada no aperto de mão.
+///
+/// Independe de [`PacketHeader::VERSION`](crate::PacketHeader::VERSION), que versiona o
+/// formato do cabeçalho: identifica as mensagens trocadas pela aplicação, e duas
+/// versões são compatíveis quando têm o mesmo `major`.
+///
+/// ## Exemplos
+/// ```rust
+/// use packet::message::ProtocolVersion;
+///
+/// let server = ProtocolVersion::new(2, 3);
+/// assert!(server.is_compatible(ProtocolVersion::new(2, 0)));
+/// assert!(!server.is_compatible(ProtocolVersion::new(1, 9)));
+/// assert_eq!(server.to_string(), "2.3");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProtocolVersion {
+    /// Versão principal; muda quando a aplicação deixa de entender a anterior.
+    pub major: u16,
+    /// Versão secundária; muda em extensões compatíveis.
+    pub minor: u16,
+}
+
+impl ProtocolVersion {
+    /// Cria uma versão.
+    pub const fn new(major: u16, minor: u16) -> Self {
+        Self { major, minor }
+    }
+
+    /// Indica se as duas versões podem se conectar, isto é, se têm o mesmo `major`.
+    pub fn is_compatible(self, other: Self) -> bool {
+        self.major == other.major
+    }
+}
+
+impl fmt::Display for ProtocolVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+impl Packet {
+    /// Retorna o tipo de mensagem do pacote.
+    ///
+    /// ## Retorno
+    /// - `Result<MessageType, PacketError>`: O tipo, ou [`PacketError::UnknownMessageType`]
+    ///   se o byte do cabeçalho pertence à faixa reservada.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::Packet;
+    /// use packet::message::MessageType;
+    ///
+    /// let ping = Packet::new(MessageType::Ping.into(), 1, 7, vec![]);
+    /// assert_eq!(ping.message_type(), Ok(MessageType::Ping));
+    /// assert_eq!(ping.header.message_type, 5);
+    /// ```
+    pub fn message_type(&self) -> Result<MessageType, PacketError> {
+        MessageType::try_from(self.header.message_type).map_err(PacketError::UnknownMessageType)
+    }
+
+    /// Classifica o pacote conforme o mapeamento descrito no módulo [`message`](crate::message).
+    ///
+    /// ## Retorno
+    /// - `PacketKind`: A categoria do pacote.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::Packet;
+    /// use packet::message::{MessageType, PacketKind};
+    ///
+    /// let ping = Packet::new(MessageType::Ping.into(), 1, 7, vec![]);
+    /// assert_eq!(ping.kind(), PacketKind::Control);
+    /// assert_eq!(Packet::new(0x90, 1, 7, vec![1]).kind(), PacketKind::Data);
+    /// ```
+    pub fn kind(&self) -> PacketKind {
+        if self.header.flags.contains(PacketFlags::FRAGMENT) {
+            return PacketKind::Fragment;
+        }
+        MessageType::try_from(self.header.message_type)
+            .map(MessageType::kind)
+            .unwrap_or(PacketKind::Data)
+    }
+
+    /// Interpreta o payload de um pacote [`MessageType::CHAT`] como texto.
+    ///
+    /// ## Retorno
+    /// - `Result<&str, PacketError>`: O texto, [`PacketError::UnexpectedMessageType`] se o
+    ///   pacote não é de chat, ou [`PacketError::InvalidUtf8`] se o payload não é UTF-8 válido.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::{Packet, PacketError};
+    /// use packet::builder::PacketBuilder;
+    /// use packet::message::MessageType;
+    ///
+    /// let chat = PacketBuilder::chat(7, 1, "olá").seal();
+    /// assert_eq!(chat.chat_text(), Ok("olá"));
+    ///
+    /// let corrupted = Packet::new(MessageType::CHAT.into(), 2, 7, vec![b'o', 0xFF]);
+    /// assert_eq!(corrupted.chat_text(), Err(PacketError::InvalidUtf8 { valid_up_to: 1 }));
+    /// ```
+    pub fn chat_text(&self) -> Result<&str, PacketError> {
+        let chat = u8::from(MessageType::CHAT);
+        if self.header.message_type != chat {
+            return Err(PacketError::UnexpectedMessageType {
+                expected: chat,
+                found: self.header.message_type,
+            });
+        }
+        core::str::from_utf8(&self.payload).map_err(|error| PacketError::InvalidUtf8 {
+            valid_up_to: error.valid_up_to(),
+        })
+    }
+}
@@ -0,0 +1,115 @@
+//! # Módulo `monotonic`
+//!
+//! Diagnóstico da ordem de chegada das sequências de cada jogador.
+//!
+//! [`MonotonicTracker`] guarda apenas a maior sequência vista por `player_id` e
+//! classifica cada pacote recebido em relação a ela. É mais leve que as camadas de
+//! confiabilidade e ordenação e não altera a entrega: serve para registrar em log e
+//! medir a qualidade da rede de cada jogador.
+
+use std::collections::HashMap;
+
+use crate::sequence::sequence_less_than;
+use crate::Packet;
+
+/// Classificação de uma sequência recebida.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SequenceStatus {
+    /// A sequência é a seguinte à maior vista, ou a primeira do jogador.
+    New,
+    /// A sequência é igual à maior vista.
+    Duplicate,
+    /// A sequência é anterior à maior vista: chegou fora de ordem ou foi repetida.
+    Reordered,
+    /// A sequência está à frente da maior vista; contém a quantidade de sequências
+    /// puladas, possivelmente perdidas.
+    Ahead(u32),
+}
+
+/// Rastreador da maior sequência vista por jogador.
+///
+/// As sequências são comparadas com aritmética circular, como em
+/// [`sequence`](crate::sequence). Como só a maior sequência é guardada, uma sequência
+/// antiga repetida é classificada como [`SequenceStatus::Reordered`], e não como
+/// duplicada.
+///
+/// ## Exemplos
+/// ```rust
+/// use packet::Packet;
+/// use packet::monotonic::{MonotonicTracker, SequenceStatus};
+///
+/// let mut tracker = MonotonicTracker::new();
+/// let from = |player_id, sequence| Packet::new(1, sequence, player_id, vec![]);
+///
+/// assert_eq!(tracker.check(&from(7, u32::MAX)), SequenceStatus::New);
+/// assert_eq!(tracker.check(&from(7, 0)), SequenceStatus::New);
+/// assert_eq!(tracker.check(&from(7, 0)), SequenceStatus::Duplicate);
+/// assert_eq!(tracker.check(&from(7, 3)), SequenceStatus::Ahead(2));
+/// assert_eq!(tracker.check(&from(7, 1)), SequenceStatus::Reordered);
+///
+/// // Cada jogador tem sua própria sequência.
+/// assert_eq!(tracker.check(&from(8, 1)), SequenceStatus::New);
+/// assert_eq!(tracker.highest(7), Some(3));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MonotonicTracker {
+    /// Maior sequência vista de cada jogador.
+    highest: HashMap<u64, u32>,
+}
+
+impl MonotonicTracker {
+    /// Cria um rastreador sem jogadores.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Classifica a sequência do pacote e a registra se for a maior do jogador.
+    ///
+    /// ## Parâmetros
+    /// - `packet`: Pacote recebido.
+    ///
+    /// ## Retorno
+    /// - `SequenceStatus`: A classificação em relação à maior sequência vista do
+    ///   `player_id` do pacote.
+    pub fn check(&mut self, packet: &Packet) -> SequenceStatus {
+        let sequence = packet.header.sequence;
+        let Some(highest) = self.highest.get_mut(&packet.header.player_id) else {
+            self.highest.insert(packet.header.player_id, sequence);
+            return SequenceStatus::New;
+        };
+
+        if sequence == *highest {
+            return SequenceStatus::Duplicate;
+        }
+        if sequence_less_than(sequence, *highest) {
+            return SequenceStatus::Reordered;
+        }
+
+        let gap = sequence.wrapping_sub(*highest) - 1;
+        *highest = sequence;
+        if gap == 0 {
+            SequenceStatus::New
+        } else {
+            SequenceStatus::Ahead(gap)
+        }
+    }
+
+    /// Retorna a maior sequência vista do jogador.
+    ///
+    /// ## Parâmetros
+    /// - `player_id`: Identificador do jogador.
+    ///
+    /// ## Retorno
+    /// - `Option<u32>`: A sequência, ou `None` se nenhum pacote do jogador foi verificado.
+    pub fn highest(&self, player_id: u64) -> Option<u32> {
+        self.highest.get(&player_id).copied()
+    }
+
+    /// Esquece um jogador, por exemplo ao fim da sua conexão.
+    ///
+    /// ## Parâmetros
+    /// - `player_id`: Identificador do jogador.
+    pub fn remove(&mut self, player_id: u64) {
+        self.highest.remove(&player_id);
+    }
+}
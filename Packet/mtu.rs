@@ -0,0 +1,176 @@
+//! # Módulo `mtu`
+//!
+//! Descoberta do MTU do caminho por sondas de tamanho crescente.
+//!
+//! [`MtuDiscovery`] faz uma busca binária entre um tamanho seguro, considerado
+//! confirmado desde o início, e um máximo: cada sonda é um datagrama preenchido até o
+//! tamanho testado, e o maior tamanho confirmado pelo outro lado passa a ser o MTU.
+//! Uma sonda sem resposta é repetida até [`MAX_PROBE_ATTEMPTS`] vezes antes de o
+//! tamanho ser considerado grande demais, para que uma perda isolada não reduza o
+//! resultado. O módulo não envia nada: o chamador monta e envia as sondas e repassa as
+//! confirmações. As conexões de [`connection`](crate::connection) usam pings
+//! preenchidos como sondas.
+//!
+//! ## Exemplos
+//! ```rust
+//! use std::time::Instant;
+//! use packet::mtu::MtuDiscovery;
+//!
+//! // O caminho simulado aceita datagramas de até 1400 bytes.
+//! let mut discovery = MtuDiscovery::new(1200, 1472);
+//! let mut now = Instant::now();
+//! let mut sequence = 0;
+//! while !discovery.is_complete() {
+//!     if let Some(size) = discovery.poll(now, sequence) {
+//!         if size <= 1400 {
+//!             discovery.acknowledge(sequence);
+//!         }
+//!         sequence += 1;
+//!     }
+//!     now += discovery.timeout();
+//! }
+//! assert_eq!(discovery.mtu(), 1400);
+//! ```
+
+use std::time::{Duration, Instant};
+
+/// Maior datagrama sondado por padrão: um quadro Ethernet de 1500 bytes menos os
+/// cabeçalhos IPv4 e UDP.
+pub const MAX_PROBE_MTU: usize = 1472;
+
+/// Tempo padrão de espera pela confirmação de uma sonda.
+pub const DEFAULT_PROBE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Quantidade de sondas sem resposta de um mesmo tamanho após a qual ele é
+/// considerado grande demais.
+pub const MAX_PROBE_ATTEMPTS: u32 = 3;
+
+/// Sonda enviada e ainda sem resposta.
+#[derive(Debug, Clone, Copy)]
+struct Probe {
+    /// Sequência do pacote da sonda.
+    sequence: u32,
+    /// Tamanho do datagrama testado.
+    size: usize,
+    /// Instante do envio.
+    sent_at: Instant,
+}
+
+/// Busca binária do maior datagrama que atravessa o caminho.
+#[derive(Debug, Clone)]
+pub struct MtuDiscovery {
+    /// Maior tamanho confirmado.
+    confirmed: usize,
+    /// Menor tamanho considerado grande demais; o máximo mais um no início.
+    limit: usize,
+    /// Sonda em andamento.
+    probe: Option<Probe>,
+    /// Sondas sem resposta do tamanho atual.
+    attempts: u32,
+    /// Tempo de espera pela confirmação de cada sonda.
+    timeout: Duration,
+}
+
+impl MtuDiscovery {
+    /// Cria uma busca entre um tamanho seguro e um máximo, com
+    /// [`DEFAULT_PROBE_TIMEOUT`].
+    ///
+    /// ## Parâmetros
+    /// - `min`: Tamanho de datagrama considerado confirmado, como
+    ///   [`DEFAULT_MTU`](crate::DEFAULT_MTU).
+    /// - `max`: Maior tamanho sondado, como [`MAX_PROBE_MTU`].
+    ///
+    /// ## Retorno
+    /// - `Self`: A busca, com [`MtuDiscovery::mtu`] igual a `min`.
+    pub fn new(min: usize, max: usize) -> Self {
+        Self {
+            confirmed: min,
+            limit: max.max(min) + 1,
+            probe: None,
+            attempts: 0,
+            timeout: DEFAULT_PROBE_TIMEOUT,
+        }
+    }
+
+    /// Define o tempo de espera pela confirmação de cada sonda.
+    ///
+    /// ## Parâmetros
+    /// - `timeout`: Novo tempo de espera, em geral algumas vezes o RTT.
+    ///
+    /// ## Retorno
+    /// - `Self`: A busca com o novo tempo de espera.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Retorna o tempo de espera pela confirmação de cada sonda.
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Retorna o maior tamanho de datagrama confirmado.
+    pub fn mtu(&self) -> usize {
+        self.confirmed
+    }
+
+    /// Indica se a busca terminou.
+    pub fn is_complete(&self) -> bool {
+        self.limit - self.confirmed <= 1
+    }
+
+    /// Retorna o tamanho da próxima sonda, se é hora de enviá-la.
+    ///
+    /// Uma nova sonda só é produzida quando a anterior foi confirmada ou expirou.
+    ///
+    /// ## Parâmetros
+    /// - `now`: Instante atual.
+    /// - `sequence`: Sequência do pacote que levará a sonda, usada por
+    ///   [`MtuDiscovery::acknowledge`].
+    ///
+    /// ## Retorno
+    /// - `Option<usize>`: O tamanho total do datagrama a enviar, ou `None`.
+    pub fn poll(&mut self, now: Instant, sequence: u32) -> Option<usize> {
+        if let Some(probe) = self.probe {
+            if now.saturating_duration_since(probe.sent_at) < self.timeout {
+                return None;
+            }
+            self.probe = None;
+            self.attempts += 1;
+            if self.attempts >= MAX_PROBE_ATTEMPTS {
+                self.limit = probe.size;
+                self.attempts = 0;
+            }
+        }
+        if self.is_complete() {
+            return None;
+        }
+
+        let size = self.confirmed + (self.limit - self.confirmed) / 2;
+        self.probe = Some(Probe {
+            sequence,
+            size,
+            sent_at: now,
+        });
+        Some(size)
+    }
+
+    /// Registra a confirmação de uma sonda.
+    ///
+    /// ## Parâmetros
+    /// - `sequence`: Sequência do pacote confirmado.
+    ///
+    /// ## Retorno
+    /// - `bool`: `true` se a sequência é a da sonda em andamento.
+    pub fn acknowledge(&mut self, sequence: u32) -> bool {
+        match self.probe {
+            Some(probe) if probe.sequence == sequence => {
+                self.confirmed = probe.size;
+                self.probe = None;
+                self.attempts = 0;
+                true
+            }
+            _ => false,
+        }
+    }
+}
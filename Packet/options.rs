@@ -0,0 +1,127 @@
+//! # Módulo `options`
+//!
+//! Opções TLV (tipo, tamanho, valor) entre o cabeçalho fixo e os dados do pacote.
+//!
+//! Metadados opcionais não aumentam o cabeçalho de todos os pacotes: apenas pacotes
+//! com a flag [`PacketFlags::OPTIONS`] carregam a área de opções, no início do
+//! payload, coberta pelo checksum como o restante do payload:
+//!
+//! | Offset  | Tamanho       | Campo                                            |
+//! |---------|---------------|--------------------------------------------------|
+//! | `0`     | `2`           | Tamanho das opções, `options_len` (little-endian) |
+//! | `2`     | `options_len` | Opções, cada uma com tipo (`u8`), tamanho (`u8`) e valor |
+//! | `2 + options_len` | restante | Dados da aplicação ([`Packet::body`])     |
+//!
+//! Fragmentos não expõem opções: elas pertencem ao payload original e voltam a ser
+//! legíveis após a remontagem.
+//!
+//! ## Exemplos
+//! ```rust
+//! use packet::{Packet, PacketFlags};
+//! use packet::builder::PacketBuilder;
+//!
+//! let mut builder = PacketBuilder::new(1, 1, 7);
+//! builder.add_option(1, &[0x2A]).unwrap();
+//! builder.add_option(9, b"trace-id").unwrap();
+//! builder.extend([10u8, 20, 30]);
+//! let packet = builder.seal();
+//!
+//! assert!(packet.header.flags.contains(PacketFlags::OPTIONS));
+//! let options: Vec<_> = packet.options().collect();
+//! assert_eq!(options, [(1, &[0x2A][..]), (9, &b"trace-id"[..])]);
+//! assert_eq!(packet.body(), [10, 20, 30]);
+//!
+//! // Pacotes sem a flag não têm opções, e os dados ocupam todo o payload.
+//! let plain = Packet::new(1, 1, 7, vec![10, 20, 30]);
+//! assert_eq!(plain.options().count(), 0);
+//! assert_eq!(plain.body(), plain.payload);
+//! ```
+
+use crate::builder::PacketBuilder;
+use crate::{Packet, PacketError, PacketFlags};
+
+/// Tamanho, em bytes, do campo `options_len` no início do payload.
+pub const OPTIONS_LENGTH_SIZE: usize = 2;
+
+/// Tamanho máximo do valor de uma opção, em bytes.
+pub const MAX_OPTION_VALUE_SIZE: usize = u8::MAX as usize;
+
+impl Packet {
+    /// Retorna a área de opções e os dados da aplicação, se o pacote tem opções.
+    fn split_options(&self) -> Option<(&[u8], &[u8])> {
+        let flags = self.header.flags;
+        if !flags.contains(PacketFlags::OPTIONS) || flags.contains(PacketFlags::FRAGMENT) {
+            return None;
+        }
+
+        let len = self.payload.get(..OPTIONS_LENGTH_SIZE)?;
+        let len = u16::from_le_bytes([len[0], len[1]]) as usize;
+        let rest = &self.payload[OPTIONS_LENGTH_SIZE..];
+        (len <= rest.len()).then(|| rest.split_at(len))
+    }
+
+    /// Percorre as opções do pacote.
+    ///
+    /// ## Retorno
+    /// - `impl Iterator<Item = (u8, &[u8])>`: O tipo e o valor de cada opção, na ordem
+    ///   em que foram acrescentadas. Vazio sem a flag [`PacketFlags::OPTIONS`]; uma
+    ///   opção que ultrapassa a área de opções encerra a iteração.
+    pub fn options(&self) -> impl Iterator<Item = (u8, &[u8])> {
+        let mut area = self.split_options().map_or(&[][..], |(options, _)| options);
+        std::iter::from_fn(move || {
+            let (&option_type, rest) = area.split_first()?;
+            let (&len, rest) = rest.split_first()?;
+            let value = rest.get(..len as usize)?;
+            area = &rest[len as usize..];
+            Some((option_type, value))
+        })
+    }
+
+    /// Retorna os dados da aplicação, após a área de opções.
+    ///
+    /// ## Retorno
+    /// - `&[u8]`: O payload sem a área de opções; o payload inteiro se o pacote não tem
+    ///   opções ou se a área de opções está malformada.
+    pub fn body(&self) -> &[u8] {
+        self.split_options().map_or(&self.payload, |(_, body)| body)
+    }
+}
+
+impl PacketBuilder {
+    /// Acrescenta uma opção TLV ao pacote.
+    ///
+    /// Ao selar, a área de opções é gravada antes do payload montado e a flag
+    /// [`PacketFlags::OPTIONS`] é ativada.
+    ///
+    /// ## Parâmetros
+    /// - `option_type`: Tipo da opção, definido pela aplicação; os tipos a partir de
+    ///   `0xF0` são reservados ao crate, como
+    ///   [`UNCOMPRESSED_LEN_OPTION`](crate::compression::UNCOMPRESSED_LEN_OPTION) e
+    ///   [`TIMESTAMP_OPTION`](crate::clock::TIMESTAMP_OPTION).
+    /// - `value`: Valor da opção.
+    ///
+    /// ## Retorno
+    /// - `Result<(), PacketError>`: [`PacketError::PayloadTooLarge`] se o valor excede
+    ///   [`MAX_OPTION_VALUE_SIZE`] ou se a área de opções excederia `u16::MAX` bytes;
+    ///   nesse caso nenhuma opção é acrescentada.
+    pub fn add_option(&mut self, option_type: u8, value: &[u8]) -> Result<(), PacketError> {
+        if value.len() > MAX_OPTION_VALUE_SIZE {
+            return Err(PacketError::PayloadTooLarge {
+                size: value.len(),
+                limit: MAX_OPTION_VALUE_SIZE,
+            });
+        }
+        let size = self.options.len() + 2 + value.len();
+        if size > u16::MAX as usize {
+            return Err(PacketError::PayloadTooLarge {
+                size,
+                limit: u16::MAX as usize,
+            });
+        }
+
+        self.options.push(option_type);
+        self.options.push(value.len() as u8);
+        self.options.extend_from_slice(value);
+        Ok(())
+    }
+}
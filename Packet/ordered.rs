@@ -0,0 +1,343 @@
+//! # Módulo `ordered`
+//!
+//! Entrega ordenada de pacotes por canal lógico.
+//!
+//! [`OrderedChannel`] retém pacotes que chegam fora de ordem até que as sequências
+//! anteriores cheguem. [`ChannelMux`] mantém um [`OrderedChannel`] independente para
+//! cada `channel_id` do cabeçalho, de forma que a perda de um pacote em um canal não
+//! atrasa a entrega nos demais.
+//!
+//! As sequências são comparadas com aritmética circular: um pacote até `2^31`
+//! sequências à frente da próxima esperada é considerado futuro, e os demais são
+//! considerados antigos (duplicados) e descartados.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::config::ProtocolConfig;
+use crate::sequence::sequence_less_than;
+use crate::Packet;
+
+/// Distância máxima à frente de `next` considerada futura pela ordem circular.
+const FUTURE_WINDOW: u32 = 1 << 31;
+
+/// Função notificada com as sequências ausentes detectadas por um [`OrderedChannel`].
+type GapCallback = Box<dyn FnMut(&[u32]) + Send>;
+
+/// Canal que entrega pacotes na ordem das sequências.
+///
+/// ## Exemplos
+/// ```rust
+/// use packet::Packet;
+/// use packet::ordered::OrderedChannel;
+///
+/// let mut channel = OrderedChannel::new(1);
+///
+/// assert!(channel.receive(Packet::new(1, 3, 7, vec![3])).is_empty());
+/// assert_eq!(channel.missing(), vec![1, 2]);
+///
+/// assert_eq!(channel.receive(Packet::new(1, 1, 7, vec![1])).len(), 1);
+/// let delivered = channel.receive(Packet::new(1, 2, 7, vec![2]));
+/// let sequences: Vec<_> = delivered.iter().map(|p| p.header.sequence).collect();
+/// assert_eq!(sequences, vec![2, 3]);
+/// assert_eq!(channel.next_sequence(), 4);
+/// ```
+pub struct OrderedChannel {
+    /// Próxima sequência a ser entregue.
+    next: u32,
+    /// Pacotes recebidos à frente de `next`, indexados pela sequência.
+    pending: BTreeMap<u32, Packet>,
+    /// Notificado quando novas lacunas são detectadas.
+    on_gap: Option<GapCallback>,
+    /// Sequências anteriores a esta já foram notificadas como lacunas.
+    reported_through: u32,
+    /// Pacotes a esta distância ou mais à frente de `next` são descartados.
+    window: u32,
+}
+
+impl fmt::Debug for OrderedChannel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OrderedChannel")
+            .field("next", &self.next)
+            .field("pending", &self.pending)
+            .field("on_gap", &self.on_gap.is_some())
+            .field("window", &self.window)
+            .finish()
+    }
+}
+
+impl OrderedChannel {
+    /// Cria um canal vazio.
+    ///
+    /// ## Parâmetros
+    /// - `next_sequence`: Sequência do primeiro pacote a ser entregue.
+    ///
+    /// ## Retorno
+    /// - `Self`: Um canal sem pacotes retidos.
+    pub fn new(next_sequence: u32) -> Self {
+        Self {
+            next: next_sequence,
+            pending: BTreeMap::new(),
+            on_gap: None,
+            reported_through: next_sequence,
+            window: FUTURE_WINDOW,
+        }
+    }
+
+    /// Cria um canal vazio que retém no máximo
+    /// [`ProtocolConfig::reliability_window`] sequências à frente da próxima esperada.
+    ///
+    /// ## Parâmetros
+    /// - `next_sequence`: Sequência do primeiro pacote a ser entregue.
+    /// - `config`: Configuração do protocolo.
+    ///
+    /// ## Retorno
+    /// - `Self`: Um canal sem pacotes retidos.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::Packet;
+    /// use packet::config::ProtocolConfig;
+    /// use packet::ordered::OrderedChannel;
+    ///
+    /// let config = ProtocolConfig { reliability_window: 4, ..ProtocolConfig::default() };
+    /// let mut channel = OrderedChannel::with_config(1, &config);
+    ///
+    /// // A sequência 5 está fora da janela de 1 a 4 e é descartada.
+    /// channel.receive(Packet::new(1, 4, 7, vec![]));
+    /// channel.receive(Packet::new(1, 5, 7, vec![]));
+    /// assert_eq!(channel.missing(), vec![1, 2, 3]);
+    /// ```
+    pub fn with_config(next_sequence: u32, config: &ProtocolConfig) -> Self {
+        Self {
+            window: config.reliability_window.min(FUTURE_WINDOW),
+            ..Self::new(next_sequence)
+        }
+    }
+
+    /// Registra uma função chamada quando [`OrderedChannel::receive`] detecta sequências ausentes.
+    ///
+    /// Cada lacuna é notificada uma única vez, com as sequências que passaram a faltar
+    /// desde a notificação anterior. Quando todas as lacunas são preenchidas, o canal
+    /// volta ao estado inicial e uma nova perda gera uma nova notificação.
+    ///
+    /// ## Parâmetros
+    /// - `callback`: Recebe as sequências recém-detectadas como ausentes, em ordem.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use std::sync::{Arc, Mutex};
+    /// use packet::Packet;
+    /// use packet::ordered::OrderedChannel;
+    ///
+    /// let gaps = Arc::new(Mutex::new(Vec::new()));
+    /// let mut channel = OrderedChannel::new(1);
+    /// let log = Arc::clone(&gaps);
+    /// channel.set_on_gap(move |missing| log.lock().unwrap().push(missing.to_vec()));
+    ///
+    /// channel.receive(Packet::new(1, 3, 7, vec![]));
+    /// channel.receive(Packet::new(1, 4, 7, vec![]));
+    /// channel.receive(Packet::new(1, 6, 7, vec![]));
+    /// assert_eq!(*gaps.lock().unwrap(), vec![vec![1, 2], vec![5]]);
+    ///
+    /// // Preenchidas as lacunas, uma nova perda é notificada novamente.
+    /// for sequence in [1, 2, 5, 8] {
+    ///     channel.receive(Packet::new(1, sequence, 7, vec![]));
+    /// }
+    /// assert_eq!(gaps.lock().unwrap().last(), Some(&vec![7]));
+    /// assert_eq!(gaps.lock().unwrap().len(), 3);
+    /// ```
+    pub fn set_on_gap(&mut self, callback: impl FnMut(&[u32]) + Send + 'static) {
+        self.on_gap = Some(Box::new(callback));
+    }
+
+    /// Recebe um pacote e retorna os pacotes que passam a poder ser entregues.
+    ///
+    /// Pacotes antigos, duplicados ou além da janela do canal são descartados.
+    ///
+    /// ## Parâmetros
+    /// - `packet`: Pacote recebido.
+    ///
+    /// ## Retorno
+    /// - `Vec<Packet>`: Pacotes prontos para entrega, em ordem de sequência; vazio se
+    ///   o pacote foi retido ou descartado.
+    pub fn receive(&mut self, packet: Packet) -> Vec<Packet> {
+        let sequence = packet.header.sequence;
+        if sequence_less_than(sequence, self.next)
+            || sequence.wrapping_sub(self.next) >= self.window
+        {
+            return Vec::new();
+        }
+        self.pending.entry(sequence).or_insert(packet);
+
+        let mut delivered = Vec::new();
+        while let Some(packet) = self.pending.remove(&self.next) {
+            delivered.push(packet);
+            self.next = self.next.wrapping_add(1);
+        }
+
+        self.report_gaps();
+        delivered
+    }
+
+    /// Notifica as lacunas ainda não notificadas e reinicia o controle quando não há lacunas.
+    fn report_gaps(&mut self) {
+        let next = self.next;
+        let Some(furthest) = self
+            .pending
+            .keys()
+            .copied()
+            .max_by_key(|sequence| sequence.wrapping_sub(next))
+        else {
+            self.reported_through = next;
+            return;
+        };
+
+        let reported_through = self.reported_through;
+        self.reported_through = furthest.wrapping_add(1);
+        if sequence_less_than(furthest, reported_through) {
+            return;
+        }
+
+        let new_gaps: Vec<u32> = self
+            .missing()
+            .into_iter()
+            .filter(|&sequence| !sequence_less_than(sequence, reported_through))
+            .collect();
+        if new_gaps.is_empty() {
+            return;
+        }
+        if let Some(on_gap) = self.on_gap.as_mut() {
+            on_gap(&new_gaps);
+        }
+    }
+
+    /// Retorna as sequências que faltam para entregar os pacotes retidos.
+    ///
+    /// ## Retorno
+    /// - `Vec<u32>`: Sequências entre a próxima esperada e o pacote retido mais
+    ///   adiantado que ainda não chegaram, em ordem.
+    pub fn missing(&self) -> Vec<u32> {
+        let Some(furthest) = self
+            .pending
+            .keys()
+            .map(|&sequence| sequence.wrapping_sub(self.next))
+            .max()
+        else {
+            return Vec::new();
+        };
+
+        (0..furthest)
+            .map(|offset| self.next.wrapping_add(offset))
+            .filter(|sequence| !self.pending.contains_key(sequence))
+            .collect()
+    }
+
+    /// Retorna a próxima sequência a ser entregue.
+    pub fn next_sequence(&self) -> u32 {
+        self.next
+    }
+
+    /// Retorna a quantidade de pacotes retidos à espera de sequências anteriores.
+    pub fn buffered_len(&self) -> usize {
+        self.pending.len()
+    }
+}
+
+/// Demultiplexador que mantém um [`OrderedChannel`] por `channel_id`.
+///
+/// Os canais são criados na primeira vez em que um pacote do canal é recebido.
+///
+/// ## Exemplos
+/// ```rust
+/// use packet::Packet;
+/// use packet::ordered::ChannelMux;
+///
+/// let mut mux = ChannelMux::new(1);
+///
+/// // A perda da sequência 1 no canal 0 não bloqueia o canal 1.
+/// assert!(mux.receive(Packet::new(1, 2, 7, vec![]).with_channel_id(0)).is_empty());
+/// assert_eq!(mux.receive(Packet::new(1, 1, 7, vec![]).with_channel_id(1)).len(), 1);
+///
+/// assert_eq!(mux.missing(0), vec![1]);
+/// assert!(mux.missing(1).is_empty());
+/// ```
+#[derive(Debug)]
+pub struct ChannelMux {
+    /// Sequência inicial dos canais criados sob demanda.
+    initial_sequence: u32,
+    /// Janela dos canais criados sob demanda.
+    window: u32,
+    /// Canais já utilizados, indexados pelo `channel_id`.
+    channels: BTreeMap<u8, OrderedChannel>,
+}
+
+impl ChannelMux {
+    /// Cria um demultiplexador sem canais.
+    ///
+    /// ## Parâmetros
+    /// - `initial_sequence`: Sequência do primeiro pacote de cada canal.
+    ///
+    /// ## Retorno
+    /// - `Self`: Um demultiplexador vazio.
+    pub fn new(initial_sequence: u32) -> Self {
+        Self {
+            initial_sequence,
+            window: FUTURE_WINDOW,
+            channels: BTreeMap::new(),
+        }
+    }
+
+    /// Cria um demultiplexador cujos canais são criados com
+    /// [`OrderedChannel::with_config`].
+    ///
+    /// ## Parâmetros
+    /// - `initial_sequence`: Sequência do primeiro pacote de cada canal.
+    /// - `config`: Configuração do protocolo.
+    ///
+    /// ## Retorno
+    /// - `Self`: Um demultiplexador vazio.
+    pub fn with_config(initial_sequence: u32, config: &ProtocolConfig) -> Self {
+        Self {
+            window: config.reliability_window.min(FUTURE_WINDOW),
+            ..Self::new(initial_sequence)
+        }
+    }
+
+    /// Encaminha um pacote ao canal indicado em seu cabeçalho.
+    ///
+    /// ## Parâmetros
+    /// - `packet`: Pacote recebido.
+    ///
+    /// ## Retorno
+    /// - `Vec<Packet>`: Pacotes do canal que passam a poder ser entregues, em ordem.
+    pub fn receive(&mut self, packet: Packet) -> Vec<Packet> {
+        let (initial_sequence, window) = (self.initial_sequence, self.window);
+        self.channels
+            .entry(packet.header.channel_id)
+            .or_insert_with(|| OrderedChannel {
+                window,
+                ..OrderedChannel::new(initial_sequence)
+            })
+            .receive(packet)
+    }
+
+    /// Retorna as sequências que faltam em um canal.
+    ///
+    /// ## Parâmetros
+    /// - `channel_id`: Canal consultado.
+    ///
+    /// ## Retorno
+    /// - `Vec<u32>`: As sequências ausentes; vazio se o canal ainda não foi usado.
+    pub fn missing(&self, channel_id: u8) -> Vec<u32> {
+        self.channels
+            .get(&channel_id)
+            .map(OrderedChannel::missing)
+            .unwrap_or_default()
+    }
+
+    /// Retorna o canal com o identificador informado, se já foi usado.
+    pub fn channel(&self, channel_id: u8) -> Option<&OrderedChannel> {
+        self.channels.get(&channel_id)
+    }
+}
@@ -7,6 +7,116 @@
 //! - [`PacketHeader`]: Representa o cabeçalho de um pacote.
 //! - [`Packet`]: Representa um pacote completo, incluindo o cabeçalho e o payload.
 //!
+//! ## Submódulos
+//! - `async_transport`: Transporte UDP assíncrono sobre `tokio` (recurso `tokio`).
+//! - [`auth`]: Tokens de conexão assinados emitidos por um serviço de autenticação.
+//! - [`batch`]: Agrupamento de vários pacotes em um único datagrama.
+//! - [`bits`]: Escrita e leitura de valores com largura arbitrária em bits.
+//! - [`broadcast`]: Envio de um mesmo pacote a todos os membros de um grupo.
+//! - [`builder`]: Montagem incremental de pacotes com [`builder::PacketBuilder`].
+//! - [`capture`]: Gravação dos pacotes de uma sessão em arquivo e repetição da gravação.
+//! - [`channel`]: Canais lógicos com garantias de entrega configuráveis.
+//! - [`checksum`]: Algoritmos de checksum selecionáveis pelas flags do cabeçalho.
+//! - [`clock`]: Fontes de tempo injetáveis e carimbo de tempo dos pacotes.
+//! - [`cobs`]: Codificação COBS para transportes seriais.
+//! - `codec`: Integração com `tokio_util::codec` e fluxos assíncronos (recurso `tokio`).
+//! - [`compare`]: Comparação de pacotes que desconsidera campos do cabeçalho.
+//! - [`compat`]: Leitura do cabeçalho original e resposta a cada par no formato que ele usa.
+//! - [`compression`]: Marcação de payloads comprimidos e razão de compressão.
+//! - [`config`]: Parâmetros do protocolo ajustáveis por ambiente e limites da leitura.
+//! - [`congestion`]: Controle de congestionamento e orçamento de banda por conexão.
+//! - [`connection`]: Conexões com aperto de mão por desafio e resposta.
+//! - [`decoder`]: Decodificação de fluxos de bytes com ressincronização.
+//! - [`describe`]: Descrição legível de pacotes para ferramentas de inspeção.
+//! - [`disconnect`]: Encerramento de conexão com motivo.
+//! - `discovery`: Descoberta de servidores na rede local por broadcast (recurso `serde`).
+//! - `encrypt`: Cifragem autenticada de payloads com ChaCha20-Poly1305 (recurso `crypto`).
+//! - [`error`]: Tipo de erro [`PacketError`] retornado pelas operações fallíveis.
+//! - [`fec`]: Redundância e paridade XOR para recuperar mensagens não confiáveis perdidas.
+//! - [`fragment`]: Divisão de pacotes maiores que o MTU em fragmentos e remontagem.
+//! - [`framing`]: Estratégias de enquadramento de pacotes por tipo de transporte.
+//! - [`flags`]: Campo de bits [`PacketFlags`] com as opções do pacote.
+//! - [`history`]: Histórico dos cabeçalhos dos pacotes mais recentes.
+//! - [`hook`]: Ganchos de inspeção, alteração e descarte dos pacotes recebidos e enviados.
+//! - [`input`]: Codificação compacta da entrada do jogador.
+//! - [`interpolation`]: Buffer de interpolação dos estados das entidades remotas.
+//! - [`lagcomp`]: Histórico dos estados do mundo para a compensação de latência no servidor.
+//! - [`liveness`]: Detecção de conexões mortas e pings de manutenção.
+//! - [`lobby`]: Salas de jogadores com limite de membros e troca de anfitrião.
+//! - `local`: Transporte de pacotes dentro do processo sobre canais (recurso `tokio`).
+//! - `mac`: Autenticação de pacotes com HMAC-SHA256 e chave compartilhada (recurso `crypto`).
+//! - [`message`]: Tipos de mensagem, classificação de pacotes e registro de funções por tipo.
+//! - [`monotonic`]: Classificação da ordem de chegada das sequências por jogador.
+//! - [`mtu`]: Descoberta do MTU do caminho por sondas de tamanho crescente.
+//! - [`options`]: Opções TLV entre o cabeçalho e os dados do pacote.
+//! - [`ordered`]: Entrega ordenada por canal lógico.
+//! - [`payload`]: Payloads tipados associados ao tipo de mensagem e à codificação.
+//! - [`pool`]: Buffers de tamanho fixo reaproveitados entre envios e recepções.
+//! - [`prediction`]: Buffer de entradas locais para predição e reconciliação no cliente.
+//! - [`query`]: Consulta sem conexão ao estado de um servidor, com respostas limitadas por origem.
+//! - [`queue`]: Filas de recepção limitada e de envio com rodízio entre jogadores.
+//! - [`ratelimit`]: Limitação da taxa de recepção por origem e banimento temporário contra inundação.
+//! - [`reliability`]: Rastreamento e retransmissão de pacotes confiáveis.
+//! - [`replication`]: Replicação do estado do jogo por snapshots completos e deltas.
+//! - [`ring`]: Montagem de pacotes em um buffer circular pré-alocado.
+//! - [`routing`]: Tabela de jogadores do servidor, com migração de endereço e detecção de `player_id` forjado.
+//! - [`rtt`]: Estatísticas de tempo de ida e volta.
+//! - [`runtime`]: Servidor dividido entre uma thread de leitura e trabalhadores, com envio de qualquer thread.
+//! - [`sequence`]: Armazenamento indexado pela sequência, comparação circular e janela anti-repetição.
+//! - `serialize`: Mensagens `serde` como payloads tipados (recurso `serde`).
+//! - `sign`: Assinaturas Ed25519 de pacotes (recurso `crypto`).
+//! - [`simulator`]: Simulação de latência, perda, duplicação e reordenação para testes.
+//! - [`snapshot`]: Divisão de snapshots de estado em pacotes limitados pelo MTU.
+//! - [`socket`]: Envio e recepção de pacotes sobre UDP.
+//! - [`stats`]: Estatísticas de tráfego, perda, RTT e banda por conexão.
+//! - [`stream`]: Leitura e escrita de pacotes em fluxos `Read`/`Write`.
+//! - [`streaming`]: Canais de voz e vídeo com quadros carimbados e buffer de jitter.
+//! - [`testing`]: Corrupção de pacotes serializados para testes do receptor.
+//! - [`throughput`]: Taxa de transferência em uma janela deslizante.
+//! - [`timesync`]: Sincronização do relógio do cliente com o do servidor pelos pings.
+//! - [`transport`]: Transporte UDP com buffer de recepção próprio e erros tipados.
+//! - [`view`]: Leitura de pacotes sem cópia do payload.
+//! - `websocket`: Transporte de pacotes sobre WebSocket, inclusive no navegador (recurso `websocket`).
+//! - [`wire`]: Descrição do formato do cabeçalho na rede.
+//!
+//! ## Recursos opcionais
+//! - `std` (padrão): habilita os módulos de E/S, transporte e conexão e os demais que
+//!   dependem da biblioteca padrão, além de [`PacketError::Io`]. Sem ele, o crate é
+//!   `#![no_std]` com `alloc` e contém só o formato na rede: [`Packet`],
+//!   [`PacketHeader`] e os módulos [`checksum`], [`compare`], [`error`], [`flags`],
+//!   [`message`], [`view`] e [`wire`], o que permite compartilhar o formato entre um
+//!   servidor nativo e clientes `wasm32-unknown-unknown` ou embarcados. Os demais
+//!   recursos dependem de `std`.
+//! - `arbitrary`: implementa `arbitrary::Arbitrary` para [`Packet`], gerando pacotes
+//!   estruturalmente válidos para *fuzzing*.
+//! - `tracing`: emite eventos `tracing` ao interpretar pacotes (`debug`) e em falhas
+//!   de interpretação ou de checksum (`warn`), com `player_id`, `sequence` e
+//!   `message_type` como campos.
+//! - `tokio`: habilita o módulo `codec`, que lê e escreve pacotes como `Stream`/`Sink`
+//!   sobre um `tokio::net::TcpStream`, o módulo `async_transport`, com envio e recepção
+//!   assíncronos e um `Stream` de pacotes sobre um `tokio::net::UdpSocket`, e o módulo
+//!   `local`, com pares de transportes sobre `tokio::sync::mpsc` para testes sem
+//!   sockets.
+//! - `serde`: implementa `Serialize` e `Deserialize` para [`config::ProtocolConfig`] e
+//!   [`checksum::ChecksumAlgorithm`], permitindo ler a configuração de arquivos TOML ou
+//!   JSON, e habilita o módulo `serialize`, que envia e recebe mensagens `serde`
+//!   codificadas com `bincode`, e o módulo `discovery`, com que os servidores anunciam
+//!   nome, ocupação e versão na rede local e os clientes os descobrem por broadcast.
+//! - `postcard`: acrescenta `postcard` aos formatos do módulo `serialize`.
+//! - `crypto`: habilita o módulo `sign`, que assina e verifica pacotes com Ed25519
+//!   (`ed25519-dalek`), ao custo de 64 bytes por pacote, e o módulo `encrypt`, que
+//!   cifra payloads com ChaCha20-Poly1305 (`chacha20poly1305`), ao custo de 16 bytes,
+//!   e combina as chaves com X25519 (`x25519-dalek` e `sha2`), e o módulo `mac`, que
+//!   autentica pacotes sem cifrá-los com HMAC-SHA256, também ao custo de 16 bytes;
+//!   habilita também as conexões assinadas e cifradas de [`connection`] e a emissão e
+//!   verificação dos tokens de [`auth`], exigidos pelo servidor no aperto de mão.
+//! - `compression`: habilita em [`compression`] a compressão de payloads com LZ4
+//!   (`lz4_flex`) e zstd (`zstd`) acima de um tamanho mínimo, e a negociação do
+//!   algoritmo no aperto de mão de [`connection`].
+//! - `websocket`: habilita o módulo `websocket`, com um [`transport::Transport`] que
+//!   envia cada pacote como uma mensagem binária WebSocket, sobre `tungstenite` fora
+//!   do navegador e sobre `web-sys` em `wasm32`.
+//!
 //! ## Exemplos
 //! ### Criação de um novo pacote
 //! ```rust
@@ -29,36 +139,348 @@
 //! assert_eq!(packet.payload, decoded.payload);
 //! ```
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::collections::{HashMap, HashSet};
+
+use checksum::{ChecksumAlgorithm, ChecksumScope};
+use wire::WireFormat;
+
+#[cfg(feature = "tokio")]
+pub mod async_transport;
+#[cfg(feature = "std")]
+pub mod auth;
+#[cfg(feature = "std")]
+pub mod batch;
+#[cfg(feature = "std")]
+pub mod bits;
+#[cfg(feature = "std")]
+pub mod broadcast;
+#[cfg(feature = "std")]
+pub mod builder;
+#[cfg(feature = "std")]
+pub mod capture;
+#[cfg(feature = "std")]
+pub mod channel;
+pub mod checksum;
+#[cfg(feature = "std")]
+pub mod clock;
+#[cfg(feature = "std")]
+pub mod cobs;
+#[cfg(feature = "tokio")]
+pub mod codec;
+pub mod compare;
+#[cfg(feature = "std")]
+pub mod compat;
+#[cfg(feature = "std")]
+pub mod compression;
+#[cfg(feature = "std")]
+pub mod config;
+#[cfg(feature = "std")]
+pub mod congestion;
+#[cfg(feature = "std")]
+pub mod connection;
+#[cfg(feature = "std")]
+pub mod decoder;
+#[cfg(feature = "std")]
+pub mod describe;
+#[cfg(feature = "std")]
+pub mod disconnect;
+#[cfg(feature = "serde")]
+pub mod discovery;
+#[cfg(feature = "crypto")]
+pub mod encrypt;
+pub mod error;
+#[cfg(feature = "std")]
+pub mod fec;
+pub mod flags;
+#[cfg(feature = "std")]
+pub mod fragment;
+#[cfg(feature = "std")]
+pub mod framing;
+#[cfg(feature = "arbitrary")]
+mod fuzzing;
+#[cfg(feature = "std")]
+pub mod history;
+#[cfg(feature = "std")]
+pub mod hook;
+#[cfg(feature = "std")]
+pub mod input;
+mod instrument;
+#[cfg(feature = "std")]
+pub mod interpolation;
+#[cfg(feature = "std")]
+pub mod lagcomp;
+#[cfg(feature = "std")]
+pub mod liveness;
+#[cfg(feature = "std")]
+pub mod lobby;
+#[cfg(feature = "tokio")]
+pub mod local;
+#[cfg(feature = "crypto")]
+pub mod mac;
+pub mod message;
+#[cfg(feature = "std")]
+pub mod monotonic;
+#[cfg(feature = "std")]
+pub mod mtu;
+#[cfg(feature = "std")]
+pub mod options;
+#[cfg(feature = "std")]
+pub mod ordered;
+#[cfg(feature = "std")]
+pub mod payload;
+#[cfg(feature = "std")]
+pub mod pool;
+#[cfg(feature = "std")]
+pub mod prediction;
+#[cfg(feature = "std")]
+pub mod query;
+#[cfg(feature = "std")]
+pub mod queue;
+#[cfg(feature = "std")]
+pub mod ratelimit;
+#[cfg(feature = "std")]
+pub mod reliability;
+#[cfg(feature = "std")]
+pub mod replication;
+#[cfg(feature = "std")]
+pub mod ring;
+#[cfg(feature = "std")]
+pub mod routing;
+#[cfg(feature = "std")]
+pub mod rtt;
+#[cfg(feature = "std")]
+pub mod runtime;
+#[cfg(feature = "std")]
+pub mod sequence;
+#[cfg(feature = "serde")]
+pub mod serialize;
+#[cfg(feature = "crypto")]
+pub mod sign;
+#[cfg(feature = "std")]
+pub mod simulator;
+#[cfg(feature = "std")]
+pub mod snapshot;
+#[cfg(feature = "std")]
+pub mod socket;
+#[cfg(feature = "std")]
+pub mod stats;
+#[cfg(feature = "std")]
+pub mod stream;
+#[cfg(feature = "std")]
+pub mod streaming;
+#[cfg(feature = "std")]
+pub mod testing;
+#[cfg(feature = "std")]
+pub mod throughput;
+#[cfg(feature = "std")]
+pub mod timesync;
+#[cfg(feature = "std")]
+pub mod transport;
+pub mod view;
+#[cfg(feature = "websocket")]
+pub mod websocket;
+pub mod wire;
+
+pub use error::PacketError;
+pub use flags::PacketFlags;
+
+/// MTU padrão, em bytes, usado quando nenhum valor é configurado.
+///
+/// O valor é conservador para caber em praticamente qualquer caminho de rede
+/// sem fragmentação no nível IP.
+pub const DEFAULT_MTU: usize = 1200;
+
+/// Maior datagrama UDP que pode ser enviado sobre IPv4, em bytes.
+pub const MAX_DATAGRAM_SIZE: usize = 65_507;
+
+/// Maior payload aceito em um único pacote, em bytes.
+///
+/// Corresponde ao espaço que sobra em [`MAX_DATAGRAM_SIZE`] depois do cabeçalho.
+pub const MAX_PAYLOAD_SIZE: usize = MAX_DATAGRAM_SIZE - PacketHeader::SIZE;
+
+/// Retorna o tamanho de buffer de recepção que comporta qualquer pacote válido.
+///
+/// Um buffer menor faz com que `recv_from` trunque datagramas grandes sem aviso;
+/// [`socket::PacketSocket::recv_from`] detecta esse caso e retorna
+/// [`PacketError::Truncated`].
+///
+/// ## Retorno
+/// - `usize`: [`PacketHeader::SIZE`] somado a [`MAX_PAYLOAD_SIZE`]. Nenhuma flag
+///   acrescenta dados após o payload.
+pub const fn recommended_recv_buffer_size() -> usize {
+    PacketHeader::SIZE + MAX_PAYLOAD_SIZE
+}
+
 /// Representa o cabeçalho de um pacote.
 ///
 /// O cabeçalho contém informações básicas sobre o pacote, como tipo de mensagem,
-/// sequência, ID do jogador, tamanho do payload e checksum.
-#[derive(Debug)]
-struct PacketHeader {
+/// flags, prioridade, canal, codificação do payload, limite de saltos, sequência, ID do jogador, grupo, confirmações, tamanho do payload e checksum.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PacketHeader {
     /// Tipo de mensagem.
-    message_type: u8,
+    pub message_type: u8,
+    /// Flags de opções do pacote.
+    pub flags: PacketFlags,
+    /// Prioridade do pacote; valores maiores são mais importantes.
+    pub priority: u8,
+    /// Canal lógico do pacote; a ordem de entrega é garantida por canal.
+    pub channel_id: u8,
+    /// Codificação do payload (veja [`payload::ContentType`]); `0` indica bytes brutos.
+    pub content_type: u8,
+    /// Quantidade de saltos restantes entre relays (veja [`Packet::decrement_ttl`]).
+    pub ttl: u8,
     /// Sequência do pacote.
-    sequence: u32,
+    pub sequence: u32,
     /// Identificador único do jogador.
-    player_id: u64,
+    pub player_id: u64,
+    /// Grupo (sala) ao qual o pacote se destina; `0` indica nenhum grupo.
+    pub group_id: u32,
+    /// Sequência mais recente recebida do outro lado; válida com a flag [`PacketFlags::ACK`].
+    pub ack: u32,
+    /// Bit `i` indica o recebimento da sequência `ack - 1 - i`; válido com a flag
+    /// [`PacketFlags::ACK`].
+    pub ack_bits: u32,
     /// Tamanho do payload em bytes.
-    payload_size: u32,
+    pub payload_size: u32,
+    /// CRC-8 dos bytes do cabeçalho que o precedem (veja
+    /// [`PacketHeader::expected_header_checksum`]), verificado por
+    /// [`PacketHeader::from_bytes`] independentemente do payload.
+    pub header_checksum: u8,
     /// Checksum para controle de integridade.
-    checksum: u32,
+    pub checksum: u32,
 }
 
 impl PacketHeader {
+    /// Tamanho do cabeçalho serializado, em bytes.
+    pub const SIZE: usize = Self::CHECKSUM_OFFSET + 4;
+
+    /// Limite de saltos atribuído aos pacotes criados por [`Packet::new`].
+    pub const DEFAULT_TTL: u8 = 64;
+
+    /// Bytes que iniciam todo cabeçalho serializado, usados para descartar
+    /// rapidamente dados que não são pacotes e para ressincronizar fluxos.
+    pub const MAGIC: [u8; 2] = *b"RN";
+
+    /// Versão do formato na rede, gravada logo após os bytes mágicos; cabeçalhos de
+    /// outra versão são recusados com [`PacketError::UnsupportedVersion`] antes de
+    /// qualquer outro campo ser lido.
+    pub const VERSION: u8 = 1;
+
+    /// Posição dos bytes mágicos no cabeçalho serializado.
+    pub const MAGIC_OFFSET: usize = 0;
+    /// Posição do byte de versão no cabeçalho serializado.
+    pub const VERSION_OFFSET: usize = Self::MAGIC_OFFSET + Self::MAGIC.len();
+    /// Posição do campo `message_type` no cabeçalho serializado.
+    pub const MESSAGE_TYPE_OFFSET: usize = Self::VERSION_OFFSET + 1;
+    /// Posição do campo `flags` no cabeçalho serializado.
+    pub const FLAGS_OFFSET: usize = Self::MESSAGE_TYPE_OFFSET + 1;
+    /// Posição do campo `priority` no cabeçalho serializado.
+    pub const PRIORITY_OFFSET: usize = Self::FLAGS_OFFSET + 2;
+    /// Posição do campo `channel_id` no cabeçalho serializado.
+    pub const CHANNEL_ID_OFFSET: usize = Self::PRIORITY_OFFSET + 1;
+    /// Posição do campo `content_type` no cabeçalho serializado.
+    pub const CONTENT_TYPE_OFFSET: usize = Self::CHANNEL_ID_OFFSET + 1;
+    /// Posição do campo `ttl` no cabeçalho serializado.
+    pub const TTL_OFFSET: usize = Self::CONTENT_TYPE_OFFSET + 1;
+    /// Posição do campo `sequence` no cabeçalho serializado.
+    pub const SEQUENCE_OFFSET: usize = Self::TTL_OFFSET + 1;
+    /// Posição do campo `player_id` no cabeçalho serializado.
+    pub const PLAYER_ID_OFFSET: usize = Self::SEQUENCE_OFFSET + 4;
+    /// Posição do campo `group_id` no cabeçalho serializado.
+    pub const GROUP_ID_OFFSET: usize = Self::PLAYER_ID_OFFSET + 8;
+    /// Posição do campo `ack` no cabeçalho serializado.
+    pub const ACK_OFFSET: usize = Self::GROUP_ID_OFFSET + 4;
+    /// Posição do campo `ack_bits` no cabeçalho serializado.
+    pub const ACK_BITS_OFFSET: usize = Self::ACK_OFFSET + 4;
+    /// Posição do campo `payload_size` no cabeçalho serializado.
+    pub const PAYLOAD_SIZE_OFFSET: usize = Self::ACK_BITS_OFFSET + 4;
+    /// Posição do campo `header_checksum` no cabeçalho serializado; os bytes
+    /// anteriores são os cobertos por ele.
+    pub const HEADER_CHECKSUM_OFFSET: usize = Self::PAYLOAD_SIZE_OFFSET + 4;
+    /// Posição do campo `checksum` no cabeçalho serializado.
+    pub const CHECKSUM_OFFSET: usize = Self::HEADER_CHECKSUM_OFFSET + 1;
+
     /// Serializa o cabeçalho para um vetor de bytes.
     ///
     /// ## Retorno
     /// - `Vec<u8>`: Um vetor de bytes representando o cabeçalho.
     pub fn to_bytes(&self) -> Vec<u8> {
-        let mut buffer = Vec::new();
-        buffer.push(self.message_type);
-        buffer.extend(&self.sequence.to_le_bytes());
-        buffer.extend(&self.player_id.to_le_bytes());
-        buffer.extend(&self.payload_size.to_le_bytes());
-        buffer.extend(&self.checksum.to_le_bytes());
+        self.to_array().to_vec()
+    }
+
+    /// Serializa o cabeçalho no início de um buffer existente, sem alocação.
+    ///
+    /// ## Parâmetros
+    /// - `buffer`: Buffer de destino, com ao menos [`PacketHeader::SIZE`] bytes; os
+    ///   bytes seguintes não são alterados.
+    ///
+    /// ## Retorno
+    /// - `usize`: Quantidade de bytes escritos, sempre [`PacketHeader::SIZE`].
+    ///
+    /// ## Pânico
+    /// Entra em pânico se o buffer tem menos de [`PacketHeader::SIZE`] bytes.
+    pub fn write_into(&self, buffer: &mut [u8]) -> usize {
+        buffer[..Self::SIZE].copy_from_slice(&self.to_array());
+        Self::SIZE
+    }
+
+    /// Serializa o cabeçalho para um array de tamanho fixo, sem alocação.
+    ///
+    /// ## Retorno
+    /// - `[u8; PacketHeader::SIZE]`: Os mesmos bytes de [`PacketHeader::to_bytes`].
+    pub fn to_array(&self) -> [u8; Self::SIZE] {
+        let mut buffer = [0; Self::SIZE];
+        buffer[Self::MAGIC_OFFSET..Self::VERSION_OFFSET].copy_from_slice(&Self::MAGIC);
+        buffer[Self::VERSION_OFFSET] = Self::VERSION;
+        buffer[Self::MESSAGE_TYPE_OFFSET] = self.message_type;
+        write_field(
+            &mut buffer,
+            Self::FLAGS_OFFSET,
+            &self.flags.bits().to_le_bytes(),
+        );
+        buffer[Self::PRIORITY_OFFSET] = self.priority;
+        buffer[Self::CHANNEL_ID_OFFSET] = self.channel_id;
+        buffer[Self::CONTENT_TYPE_OFFSET] = self.content_type;
+        buffer[Self::TTL_OFFSET] = self.ttl;
+        write_field(
+            &mut buffer,
+            Self::SEQUENCE_OFFSET,
+            &self.sequence.to_le_bytes(),
+        );
+        write_field(
+            &mut buffer,
+            Self::PLAYER_ID_OFFSET,
+            &self.player_id.to_le_bytes(),
+        );
+        write_field(
+            &mut buffer,
+            Self::GROUP_ID_OFFSET,
+            &self.group_id.to_le_bytes(),
+        );
+        write_field(&mut buffer, Self::ACK_OFFSET, &self.ack.to_le_bytes());
+        write_field(
+            &mut buffer,
+            Self::ACK_BITS_OFFSET,
+            &self.ack_bits.to_le_bytes(),
+        );
+        write_field(
+            &mut buffer,
+            Self::PAYLOAD_SIZE_OFFSET,
+            &self.payload_size.to_le_bytes(),
+        );
+        buffer[Self::HEADER_CHECKSUM_OFFSET] = self.header_checksum;
+        write_field(
+            &mut buffer,
+            Self::CHECKSUM_OFFSET,
+            &self.checksum.to_le_bytes(),
+        );
         buffer
     }
 
@@ -68,35 +490,312 @@ impl PacketHeader {
     /// - `bytes`: Fatia de bytes representando o cabeçalho.
     ///
     /// ## Retorno
-    /// - `Result<Self, String>`: Retorna o cabeçalho em caso de sucesso ou uma mensagem de erro.
-    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
-        if bytes.len() < 21 {
-            return Err("Bytes insuficientes para um cabeçalho".into());
+    /// - `Result<Self, PacketError>`: Retorna o cabeçalho em caso de sucesso ou o erro encontrado.
+    ///   Os bytes mágicos e a versão são verificados antes de qualquer outro campo, e o
+    ///   campo `header_checksum` em seguida, resultando em
+    ///   [`PacketError::HeaderChecksumMismatch`] se algum byte do cabeçalho foi
+    ///   corrompido.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::{Packet, PacketError, PacketHeader};
+    ///
+    /// let mut bytes = Packet::new(1, 1, 7, vec![1, 2, 3]).to_bytes();
+    /// assert!(PacketHeader::from_bytes(&bytes[..PacketHeader::SIZE]).is_ok());
+    ///
+    /// // Um relay que só lê o cabeçalho detecta a corrupção sem receber o payload.
+    /// bytes[PacketHeader::PLAYER_ID_OFFSET] ^= 0x01;
+    /// assert!(matches!(
+    ///     PacketHeader::from_bytes(&bytes[..PacketHeader::SIZE]),
+    ///     Err(PacketError::HeaderChecksumMismatch { .. })
+    /// ));
+    ///
+    /// // Um cabeçalho de outra versão do formato é recusado antes de ser lido.
+    /// bytes[PacketHeader::VERSION_OFFSET] = 9;
+    /// assert_eq!(
+    ///     PacketHeader::from_bytes(&bytes),
+    ///     Err(PacketError::UnsupportedVersion {
+    ///         found: 9,
+    ///         supported: PacketHeader::VERSION,
+    ///     })
+    /// );
+    /// ```
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, PacketError> {
+        let magic_len = Self::MAGIC.len();
+        if bytes.len() < magic_len {
+            return Err(PacketError::TooShort {
+                expected: Self::SIZE,
+                actual: bytes.len(),
+            });
+        }
+        if bytes[..magic_len] != Self::MAGIC {
+            return Err(PacketError::InvalidMagic {
+                found: [bytes[0], bytes[1]],
+            });
+        }
+        match bytes.get(Self::VERSION_OFFSET) {
+            Some(&Self::VERSION) => {}
+            Some(&found) => {
+                return Err(PacketError::UnsupportedVersion {
+                    found,
+                    supported: Self::VERSION,
+                })
+            }
+            None => {
+                return Err(PacketError::TooShort {
+                    expected: Self::SIZE,
+                    actual: bytes.len(),
+                })
+            }
         }
 
-        let message_type = bytes[0];
-        let sequence = u32::from_le_bytes(bytes[1..5].try_into().unwrap());
-        let player_id = u64::from_le_bytes(bytes[5..13].try_into().unwrap());
-        let payload_size = u32::from_le_bytes(bytes[13..17].try_into().unwrap());
-        let checksum = u32::from_le_bytes(bytes[17..21].try_into().unwrap());
+        if bytes.len() < Self::SIZE {
+            return Err(PacketError::TooShort {
+                expected: Self::SIZE,
+                actual: bytes.len(),
+            });
+        }
+
+        let header_checksum = bytes[Self::HEADER_CHECKSUM_OFFSET];
+        let actual = checksum::crc8(&bytes[..Self::HEADER_CHECKSUM_OFFSET]);
+        if header_checksum != actual {
+            return Err(PacketError::HeaderChecksumMismatch {
+                expected: header_checksum,
+                actual,
+            });
+        }
 
         Ok(Self {
-            message_type,
-            sequence,
-            player_id,
-            payload_size,
-            checksum,
+            message_type: bytes[Self::MESSAGE_TYPE_OFFSET],
+            flags: PacketFlags::from_bits_retain(u16::from_le_bytes(read_field(
+                bytes,
+                "flags",
+                Self::FLAGS_OFFSET,
+            )?)),
+            priority: bytes[Self::PRIORITY_OFFSET],
+            channel_id: bytes[Self::CHANNEL_ID_OFFSET],
+            content_type: bytes[Self::CONTENT_TYPE_OFFSET],
+            ttl: bytes[Self::TTL_OFFSET],
+            sequence: u32::from_le_bytes(read_field(bytes, "sequence", Self::SEQUENCE_OFFSET)?),
+            player_id: u64::from_le_bytes(read_field(bytes, "player_id", Self::PLAYER_ID_OFFSET)?),
+            group_id: u32::from_le_bytes(read_field(bytes, "group_id", Self::GROUP_ID_OFFSET)?),
+            ack: u32::from_le_bytes(read_field(bytes, "ack", Self::ACK_OFFSET)?),
+            ack_bits: u32::from_le_bytes(read_field(bytes, "ack_bits", Self::ACK_BITS_OFFSET)?),
+            payload_size: u32::from_le_bytes(read_field(
+                bytes,
+                "payload_size",
+                Self::PAYLOAD_SIZE_OFFSET,
+            )?),
+            header_checksum,
+            checksum: u32::from_le_bytes(read_field(bytes, "checksum", Self::CHECKSUM_OFFSET)?),
         })
     }
+
+    /// Sobrescreve o checksum de um pacote já serializado, sem decodificá-lo.
+    ///
+    /// Útil para intermediários que alteram um campo diretamente no buffer e só
+    /// precisam corrigir o checksum, evitando desserializar e serializar de novo.
+    ///
+    /// ## Parâmetros
+    /// - `buf`: Bytes do pacote, começando pelo cabeçalho.
+    /// - `new_checksum`: Checksum a ser gravado, na mesma ordem de bytes de [`PacketHeader::to_bytes`].
+    ///
+    /// ## Retorno
+    /// - `Result<(), PacketError>`: [`PacketError::TooShort`] se `buf` não contém um cabeçalho completo.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::{Packet, PacketHeader};
+    ///
+    /// let packet = Packet::new(1, 1, 7, vec![1, 2, 3]);
+    /// let mut bytes = packet.to_bytes();
+    ///
+    /// // Edita o payload no próprio buffer e corrige o checksum em seguida.
+    /// bytes[PacketHeader::SIZE] = 9;
+    /// let edited = Packet::new(1, 1, 7, vec![9, 2, 3]);
+    /// PacketHeader::patch_checksum_in_buffer(&mut bytes, edited.header.checksum).unwrap();
+    ///
+    /// assert_eq!(bytes, edited.to_bytes());
+    /// assert!(Packet::from_bytes(&bytes).unwrap().verify_checksum());
+    /// ```
+    pub fn patch_checksum_in_buffer(buf: &mut [u8], new_checksum: u32) -> Result<(), PacketError> {
+        if buf.len() < Self::SIZE {
+            return Err(PacketError::TooShort {
+                expected: Self::SIZE,
+                actual: buf.len(),
+            });
+        }
+        write_field(buf, Self::CHECKSUM_OFFSET, &new_checksum.to_le_bytes());
+        Ok(())
+    }
+
+    /// Retorna o algoritmo de checksum indicado pelas flags do cabeçalho.
+    pub fn checksum_algorithm(&self) -> ChecksumAlgorithm {
+        ChecksumAlgorithm::from_flags(self.flags)
+    }
+
+    /// Retorna o escopo do checksum indicado pelas flags do cabeçalho.
+    pub fn checksum_scope(&self) -> ChecksumScope {
+        ChecksumScope::from_flags(self.flags)
+    }
+
+    /// Calcula o checksum que este cabeçalho deveria ter para o payload informado,
+    /// de acordo com o algoritmo e o escopo indicados pelas flags.
+    ///
+    /// ## Parâmetros
+    /// - `payload`: Bytes do payload do pacote.
+    ///
+    /// ## Retorno
+    /// - `u32`: O checksum esperado; o valor atual do campo `checksum` é ignorado.
+    pub fn expected_checksum(&self, payload: &[u8]) -> u32 {
+        self.checksum_with(payload, self.checksum_scope(), self.checksum_algorithm())
+    }
+
+    /// Calcula o valor que o campo `header_checksum` deveria ter: o CRC-8
+    /// ([`checksum::crc8`]) dos bytes serializados que o precedem.
+    ///
+    /// Cobre todos os campos de roteamento e nenhum byte do payload, como o checksum
+    /// do cabeçalho IP. O campo `checksum` fica de fora, de modo que o checksum do
+    /// payload pode ser recalculado sem alterá-lo.
+    ///
+    /// ## Retorno
+    /// - `u8`: O checksum esperado; o valor atual do campo `header_checksum` é ignorado.
+    pub fn expected_header_checksum(&self) -> u8 {
+        checksum::crc8(&self.to_array()[..Self::HEADER_CHECKSUM_OFFSET])
+    }
+
+    /// Calcula o checksum com o escopo e o algoritmo informados, ignorando as flags.
+    ///
+    /// Com [`ChecksumScope::HeaderAndPayload`], o cabeçalho entra no cálculo tal como
+    /// está, inclusive as flags, com o campo `checksum` zerado.
+    fn checksum_with(
+        &self,
+        payload: &[u8],
+        scope: ChecksumScope,
+        algorithm: ChecksumAlgorithm,
+    ) -> u32 {
+        match scope {
+            ChecksumScope::Payload => algorithm.compute(payload),
+            ChecksumScope::HeaderAndPayload => {
+                let mut header = self.to_array();
+                header[Self::CHECKSUM_OFFSET..Self::SIZE].fill(0);
+                algorithm.compute_parts(&[&header, payload])
+            }
+            ChecksumScope::None => 0,
+        }
+    }
+
+    /// Recalcula os campos `header_checksum` e `checksum` para o payload e o escopo
+    /// informados.
+    ///
+    /// O escopo é gravado nas flags antes do cálculo, de modo que o cabeçalho passa a
+    /// descrever corretamente como verificá-lo. Todas as operações que criam ou
+    /// editam pacotes recalculam o checksum por meio deste método.
+    ///
+    /// ## Parâmetros
+    /// - `payload`: Bytes do payload do pacote; `payload_size` já deve corresponder a ele.
+    /// - `scope`: Parte do pacote coberta pelo checksum.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::Packet;
+    /// use packet::checksum::ChecksumScope;
+    ///
+    /// let mut packet = Packet::new(1, 1, 7, vec![1, 2, 3]);
+    /// packet.header.sequence = 2;
+    /// packet.header.recompute(&packet.payload, ChecksumScope::HeaderAndPayload);
+    ///
+    /// assert_eq!(packet.checksum_scope(), ChecksumScope::HeaderAndPayload);
+    /// assert!(packet.verify_checksum());
+    /// ```
+    pub fn recompute(&mut self, payload: &[u8], scope: ChecksumScope) {
+        scope.apply_to(&mut self.flags);
+        self.header_checksum = self.expected_header_checksum();
+        self.checksum = self.expected_checksum(payload);
+    }
+
+    /// Verifica a coerência do cabeçalho sem consultar o payload.
+    ///
+    /// Permite que intermediários que só leem o cabeçalho descartem pacotes
+    /// obviamente inválidos antes de ler um payload possivelmente enorme do socket.
+    /// Os bytes mágicos não fazem parte da estrutura e são verificados por
+    /// [`PacketHeader::from_bytes`]; o checksum depende do payload e não é verificado.
+    ///
+    /// ## Retorno
+    /// - `Result<(), PacketError>`: [`PacketError::PayloadTooLarge`] se `payload_size`
+    ///   excede [`MAX_PAYLOAD_SIZE`], ou [`PacketError::UnknownMessageType`] se o tipo
+    ///   pertence à faixa reservada.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::{Packet, PacketError, PacketHeader, MAX_PAYLOAD_SIZE};
+    ///
+    /// let bytes = Packet::new(1, 1, 7, vec![1, 2, 3]).to_bytes();
+    /// let mut header = PacketHeader::from_bytes(&bytes[..PacketHeader::SIZE]).unwrap();
+    /// assert!(header.validate().is_ok());
+    ///
+    /// header.payload_size = u32::MAX;
+    /// assert!(matches!(header.validate(), Err(PacketError::PayloadTooLarge { .. })));
+    /// ```
+    pub fn validate(&self) -> Result<(), PacketError> {
+        let payload_size = self.payload_size as usize;
+        if payload_size > MAX_PAYLOAD_SIZE {
+            return Err(PacketError::PayloadTooLarge {
+                size: payload_size,
+                limit: MAX_PAYLOAD_SIZE,
+            });
+        }
+        message::MessageType::try_from(self.message_type)
+            .map_err(PacketError::UnknownMessageType)?;
+        Ok(())
+    }
+
+    /// Calcula o tamanho total do pacote descrito por este cabeçalho.
+    ///
+    /// A soma é feita com aritmética verificada, de modo que um `payload_size`
+    /// próximo de `u32::MAX` produz um erro em alvos de 32 bits em vez de
+    /// causar pânico ou um valor incorreto.
+    ///
+    /// ## Retorno
+    /// - `Result<usize, PacketError>`: Tamanho do cabeçalho somado ao payload declarado,
+    ///   ou [`PacketError::SizeOverflow`] caso não caiba em `usize`.
+    pub fn packet_len(&self) -> Result<usize, PacketError> {
+        let overflow = PacketError::SizeOverflow {
+            payload_size: self.payload_size,
+        };
+        let payload_size = usize::try_from(self.payload_size).map_err(|_| overflow.clone())?;
+        Self::SIZE.checked_add(payload_size).ok_or(overflow)
+    }
+}
+
+/// Lê os `N` bytes do campo `field` a partir de `offset`.
+///
+/// Retorna [`PacketError::TruncatedField`] se `bytes` termina antes do fim do campo;
+/// [`PacketHeader::from_bytes`] verifica o tamanho total antes, de forma que o erro
+/// só aparece se essa verificação deixar de cobrir algum campo.
+fn read_field<const N: usize>(
+    bytes: &[u8],
+    field: &'static str,
+    offset: usize,
+) -> Result<[u8; N], PacketError> {
+    bytes
+        .get(offset..offset + N)
+        .and_then(|raw| raw.try_into().ok())
+        .ok_or(PacketError::TruncatedField { field, offset })
+}
+
+/// Grava os bytes de um campo a partir de `offset`.
+fn write_field(buffer: &mut [u8], offset: usize, value: &[u8]) {
+    buffer[offset..offset + value.len()].copy_from_slice(value);
 }
 
 /// Representa um pacote contendo um cabeçalho e um payload.
-#[derive(Debug)]
-struct Packet {
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Packet {
     /// Cabeçalho do pacote.
-    header: PacketHeader,
+    pub header: PacketHeader,
     /// Dados do pacote.
-    payload: Vec<u8>,
+    pub payload: Vec<u8>,
 }
 
 impl Packet {
@@ -105,31 +804,348 @@ impl Packet {
     /// ## Retorno
     /// - `Vec<u8>`: Um vetor de bytes representando o pacote completo.
     pub fn to_bytes(&self) -> Vec<u8> {
-        let mut buffer = self.header.to_bytes();
-        buffer.extend(&self.payload);
+        let mut buffer = Vec::with_capacity(self.serialized_len());
+        self.to_bytes_into(&mut buffer);
         buffer
     }
 
+    /// Acrescenta o pacote serializado ao final de um buffer existente.
+    ///
+    /// Reutilizar o mesmo buffer entre envios evita uma alocação por pacote.
+    ///
+    /// ## Parâmetros
+    /// - `buffer`: Buffer de destino; o conteúdo anterior é preservado.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::Packet;
+    ///
+    /// let packet = Packet::new(1, 1, 7, vec![1, 2, 3]);
+    /// let mut buffer = Vec::with_capacity(packet.serialized_len());
+    /// for _ in 0..3 {
+    ///     buffer.clear();
+    ///     packet.to_bytes_into(&mut buffer);
+    ///     assert_eq!(buffer, packet.to_bytes());
+    /// }
+    /// ```
+    pub fn to_bytes_into(&self, buffer: &mut Vec<u8>) {
+        buffer.reserve(self.serialized_len());
+        buffer.extend_from_slice(&self.header.to_array());
+        buffer.extend_from_slice(&self.payload);
+    }
+
+    /// Serializa o pacote no início de um buffer existente, como um array na pilha
+    /// ou um [`PooledBuffer`](pool::PooledBuffer), sem alocação.
+    ///
+    /// ## Parâmetros
+    /// - `buffer`: Buffer de destino, com ao menos [`Packet::serialized_len`] bytes; os
+    ///   bytes seguintes não são alterados.
+    ///
+    /// ## Retorno
+    /// - `usize`: Quantidade de bytes escritos, igual a [`Packet::serialized_len`].
+    ///
+    /// ## Pânico
+    /// Entra em pânico se o buffer é menor que o pacote serializado.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::Packet;
+    ///
+    /// let packet = Packet::new(1, 1, 7, vec![1, 2, 3]);
+    /// let mut buffer = [0u8; 1200];
+    /// let len = packet.write_into(&mut buffer);
+    /// assert_eq!(&buffer[..len], packet.to_bytes());
+    /// ```
+    pub fn write_into(&self, buffer: &mut [u8]) -> usize {
+        let len = self.serialized_len();
+        assert!(
+            buffer.len() >= len,
+            "buffer de {} bytes para um pacote de {len} bytes",
+            buffer.len()
+        );
+        let offset = self.header.write_into(buffer);
+        buffer[offset..len].copy_from_slice(&self.payload);
+        len
+    }
+
+    /// Serializa o pacote em um formato da rede, como a ordem de bytes de rede.
+    ///
+    /// ## Parâmetros
+    /// - `format`: Formato dos bytes; com [`WireFormat::STANDARD`], os bytes são os de
+    ///   [`Packet::to_bytes`].
+    ///
+    /// ## Retorno
+    /// - `Vec<u8>`: O pacote serializado, com os checksums calculados sobre os bytes
+    ///   no formato informado.
+    pub fn to_bytes_with_format(&self, format: WireFormat) -> Vec<u8> {
+        let mut bytes = self.to_bytes();
+        WireFormat::convert(&mut bytes, WireFormat::STANDARD, format);
+        bytes
+    }
+
+    /// Reconstrói um pacote serializado por [`Packet::to_bytes_with_format`].
+    ///
+    /// ## Parâmetros
+    /// - `bytes`: Fatia de bytes representando o pacote completo.
+    /// - `format`: Formato em que o pacote foi serializado.
+    ///
+    /// ## Retorno
+    /// - `Result<Self, PacketError>`: O pacote ou o erro de [`Packet::from_bytes`]; o
+    ///   pacote em memória é o mesmo em qualquer formato.
+    pub fn from_bytes_with_format(bytes: &[u8], format: WireFormat) -> Result<Self, PacketError> {
+        if format == WireFormat::STANDARD {
+            return Self::from_bytes(bytes);
+        }
+        let mut standard = bytes.to_vec();
+        WireFormat::convert(&mut standard, format, WireFormat::STANDARD);
+        Self::from_bytes(&standard)
+    }
+
+    /// Serializa o pacote em um [`Cow`] com os bytes próprios.
+    ///
+    /// Existe para simetria com [`PacketRef::as_bytes_cow`](view::PacketRef::as_bytes_cow),
+    /// que empresta os bytes do buffer de recepção: código que encaminha pacotes pode
+    /// tratar os dois casos da mesma forma e só serializar quando necessário.
+    ///
+    /// ## Retorno
+    /// - `Cow<'static, [u8]>`: Sempre [`Cow::Owned`] com os bytes de [`Packet::to_bytes`].
+    pub fn as_bytes_cow(&self) -> Cow<'static, [u8]> {
+        Cow::Owned(self.to_bytes())
+    }
+
     /// Reconstrói o pacote a partir de um vetor de bytes.
     ///
+    /// O buffer deve conter exatamente um pacote; para buffers com vários pacotes
+    /// concatenados, use [`Packet::from_bytes_prefix`].
+    ///
+    /// ## Parâmetros
+    /// - `bytes`: Fatia de bytes representando o pacote completo.
+    ///
+    /// ## Retorno
+    /// - `Result<Self, PacketError>`: Retorna o pacote em caso de sucesso ou o erro encontrado.
+    ///
+    /// ## Exemplos
+    /// Um `payload_size` absurdo resulta em erro, nunca em pânico:
+    /// ```rust
+    /// use packet::{Packet, PacketHeader};
+    ///
+    /// let mut bytes = Packet::new(1, 1, 1, Vec::new()).to_bytes();
+    /// let offset = PacketHeader::PAYLOAD_SIZE_OFFSET;
+    /// bytes[offset..offset + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+    ///
+    /// assert!(Packet::from_bytes(&bytes).is_err());
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            name = "packet.parse",
+            skip_all,
+            fields(len = bytes.len())
+        )
+    )]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, PacketError> {
+        let result = view::PacketRef::parse_exact(bytes);
+        instrument::parsed(result.as_ref().map(|packet| &packet.header));
+        result.map(|packet| packet.to_packet())
+    }
+
+    /// Reconstrói o pacote no início do buffer e retorna os bytes não consumidos.
+    ///
+    /// Ao contrário de [`Packet::from_bytes`], que exige que o buffer contenha
+    /// exatamente um pacote, bytes após o fim do pacote são aceitos e devolvidos, o que
+    /// permite percorrer pacotes concatenados em um mesmo buffer.
+    ///
+    /// ## Parâmetros
+    /// - `bytes`: Buffer que começa com um pacote serializado.
+    ///
+    /// ## Retorno
+    /// - `Result<(Self, &[u8]), PacketError>`: O pacote e os bytes que o seguem, ou
+    ///   [`PacketError::TooShort`] se o buffer termina antes do fim do pacote.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::Packet;
+    ///
+    /// let first = Packet::new(1, 1, 7, vec![1, 2]);
+    /// let second = Packet::new(1, 2, 7, vec![3]);
+    /// let mut buffer = first.to_bytes();
+    /// buffer.extend(second.to_bytes());
+    ///
+    /// assert!(Packet::from_bytes(&buffer).is_err());
+    ///
+    /// let (packet, rest) = Packet::from_bytes_prefix(&buffer).unwrap();
+    /// assert_eq!(packet, first);
+    /// let (packet, rest) = Packet::from_bytes_prefix(rest).unwrap();
+    /// assert_eq!(packet, second);
+    /// assert!(rest.is_empty());
+    /// ```
+    pub fn from_bytes_prefix(bytes: &[u8]) -> Result<(Self, &[u8]), PacketError> {
+        let (packet, rest) = view::PacketRef::parse_prefix(bytes)?;
+        Ok((packet.to_packet(), rest))
+    }
+
+    /// Reconstrói um pacote confiando no tamanho real do buffer em vez do `payload_size`
+    /// declarado.
+    ///
+    /// **Atenção: este modo mascara corrupção.** Quando o `payload_size` não confere,
+    /// o cabeçalho é corrigido e o checksum é recalculado sobre os bytes recebidos, de
+    /// modo que um pacote truncado ou com lixo ao final é aceito como válido. Use apenas
+    /// para interoperar com remetentes legados sabidamente defeituosos, nunca em
+    /// tráfego comum; para todo o resto, use [`Packet::from_bytes`].
+    ///
+    /// Buffers sem um cabeçalho completo, com bytes mágicos inválidos ou com o
+    /// `header_checksum` divergente continuam sendo rejeitados. Quando o tamanho
+    /// confere, o pacote é retornado sem alterações, inclusive o checksum.
+    ///
+    /// O limite de [`MAX_PAYLOAD_SIZE`] continua valendo tanto para o `payload_size`
+    /// declarado quanto para os bytes recebidos, e é verificado antes de copiar o payload.
+    ///
     /// ## Parâmetros
     /// - `bytes`: Fatia de bytes representando o pacote completo.
     ///
     /// ## Retorno
-    /// - `Result<Self, String>`: Retorna o pacote em caso de sucesso ou uma mensagem de erro.
-    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
-        if bytes.len() < 21 {
-            return Err("Bytes insuficientes para um pacote".into());
+    /// - `Result<Self, PacketError>`: O pacote, possivelmente com `payload_size` e checksum
+    ///   corrigidos, o erro encontrado no cabeçalho ou [`PacketError::PayloadTooLarge`] se
+    ///   o payload declarado ou recebido excede [`MAX_PAYLOAD_SIZE`].
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::{Packet, PacketError, PacketHeader, MAX_PAYLOAD_SIZE};
+    /// use packet::checksum::crc8;
+    ///
+    /// // Um remetente defeituoso declara 10 bytes de payload, mas envia 3.
+    /// let mut bytes = Packet::new(1, 1, 7, vec![1, 2, 3]).to_bytes();
+    /// let offset = PacketHeader::PAYLOAD_SIZE_OFFSET;
+    /// bytes[offset..offset + 4].copy_from_slice(&10u32.to_le_bytes());
+    /// let offset = PacketHeader::HEADER_CHECKSUM_OFFSET;
+    /// bytes[offset] = crc8(&bytes[..offset]);
+    ///
+    /// assert!(Packet::from_bytes(&bytes).is_err());
+    /// let repaired = Packet::from_bytes_lenient(&bytes).unwrap();
+    /// assert_eq!(repaired.header.payload_size, 3);
+    /// assert!(repaired.verify_checksum());
+    ///
+    /// // Um `payload_size` acima do limite é rejeitado, mesmo que o buffer seja curto.
+    /// let offset = PacketHeader::PAYLOAD_SIZE_OFFSET;
+    /// bytes[offset..offset + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+    /// let offset = PacketHeader::HEADER_CHECKSUM_OFFSET;
+    /// bytes[offset] = crc8(&bytes[..offset]);
+    ///
+    /// assert!(matches!(
+    ///     Packet::from_bytes_lenient(&bytes),
+    ///     Err(PacketError::PayloadTooLarge { limit: MAX_PAYLOAD_SIZE, .. })
+    /// ));
+    /// ```
+    pub fn from_bytes_lenient(bytes: &[u8]) -> Result<Self, PacketError> {
+        let header = PacketHeader::from_bytes(bytes)?;
+        let payload = &bytes[PacketHeader::SIZE..];
+        let size = (header.payload_size as usize).max(payload.len());
+        if size > MAX_PAYLOAD_SIZE {
+            return Err(PacketError::PayloadTooLarge {
+                size,
+                limit: MAX_PAYLOAD_SIZE,
+            });
         }
 
-        let header = PacketHeader::from_bytes(&bytes[..21])?;
-        let payload = bytes[21..].to_vec();
+        let mut packet = Self {
+            header,
+            payload: payload.to_vec(),
+        };
+        if packet.header.payload_size as usize != packet.payload.len() {
+            packet.refresh_header();
+        }
+        Ok(packet)
+    }
 
-        if payload.len() != header.payload_size as usize {
-            return Err("Tamanho do payload não corresponde ao especificado no cabeçalho".into());
+    /// Interpreta separadamente o cabeçalho e o payload, recuperando o máximo possível
+    /// de um pacote danificado.
+    ///
+    /// Destinado a ferramentas de diagnóstico: o cabeçalho é retornado sempre que os
+    /// bytes mágicos e o `header_checksum` conferem e há ao menos [`PacketHeader::SIZE`]
+    /// bytes, mesmo que o payload não corresponda ao `payload_size` declarado. O
+    /// checksum do payload não é verificado.
+    ///
+    /// ## Parâmetros
+    /// - `bytes`: Fatia de bytes de um pacote possivelmente corrompido.
+    ///
+    /// ## Retorno
+    /// - `(Option<PacketHeader>, Result<Vec<u8>, PacketError>)`: O cabeçalho, se pôde
+    ///   ser lido, e o payload ou o erro que impediu sua leitura com as mesmas regras
+    ///   de [`Packet::from_bytes`].
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::{Packet, PacketError};
+    ///
+    /// let bytes = Packet::new(1, 9, 7, vec![1, 2, 3]).to_bytes();
+    /// let (header, payload) = Packet::from_bytes_partial(&bytes[..bytes.len() - 1]);
+    ///
+    /// assert_eq!(header.unwrap().sequence, 9);
+    /// assert!(matches!(payload, Err(PacketError::PayloadSizeMismatch { declared: 3, actual: 2 })));
+    ///
+    /// let (header, payload) = Packet::from_bytes_partial(&bytes[..4]);
+    /// assert!(header.is_none() && payload.is_err());
+    /// ```
+    pub fn from_bytes_partial(
+        bytes: &[u8],
+    ) -> (Option<PacketHeader>, Result<Vec<u8>, PacketError>) {
+        match PacketHeader::from_bytes(bytes) {
+            Ok(header) => (
+                Some(header),
+                view::PacketRef::parse_exact(bytes).map(|packet| packet.payload.to_vec()),
+            ),
+            Err(error) => (None, Err(error)),
         }
+    }
 
-        Ok(Self { header, payload })
+    /// Retorna o tamanho do pacote serializado, em bytes, sem serializá-lo.
+    ///
+    /// ## Retorno
+    /// - `usize`: Tamanho do cabeçalho somado ao tamanho do payload.
+    pub fn serialized_len(&self) -> usize {
+        PacketHeader::SIZE + self.payload.len()
+    }
+
+    /// Calcula o checksum do pacote com um escopo e um algoritmo arbitrários,
+    /// independentemente dos indicados pelas flags.
+    ///
+    /// Destinado a ferramentas de análise: ao testar combinações até reproduzir o
+    /// checksum armazenado, é possível descobrir qual foi usada por outra implementação.
+    ///
+    /// ## Parâmetros
+    /// - `scope`: Parte do pacote coberta pelo cálculo.
+    /// - `algorithm`: Algoritmo usado; [`ChecksumAlgorithm::Adaptive`] é resolvido pelo
+    ///   tamanho dos dados cobertos.
+    ///
+    /// ## Retorno
+    /// - `u32`: O checksum calculado; o pacote não é alterado.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::Packet;
+    /// use packet::checksum::{ChecksumAlgorithm, ChecksumScope};
+    ///
+    /// let captured = Packet::new(1, 1, 7, vec![1, 2, 3])
+    ///     .with_checksum_algorithm(ChecksumAlgorithm::CRC32)
+    ///     .with_checksum_scope(ChecksumScope::HeaderAndPayload);
+    ///
+    /// let candidates = [
+    ///     (ChecksumScope::Payload, ChecksumAlgorithm::ByteSum),
+    ///     (ChecksumScope::Payload, ChecksumAlgorithm::CRC32),
+    ///     (ChecksumScope::HeaderAndPayload, ChecksumAlgorithm::ByteSum),
+    ///     (ChecksumScope::HeaderAndPayload, ChecksumAlgorithm::CRC32),
+    /// ];
+    /// let found: Vec<_> = candidates
+    ///     .into_iter()
+    ///     .filter(|&(scope, algorithm)| {
+    ///         captured.compute_checksum(scope, algorithm) == captured.header.checksum
+    ///     })
+    ///     .collect();
+    /// assert_eq!(found, [(ChecksumScope::HeaderAndPayload, ChecksumAlgorithm::CRC32)]);
+    /// ```
+    pub fn compute_checksum(&self, scope: ChecksumScope, algorithm: ChecksumAlgorithm) -> u32 {
+        self.header.checksum_with(&self.payload, scope, algorithm)
     }
 
     /// Calcula o checksum de um payload.
@@ -138,9 +1154,11 @@ impl Packet {
     /// - `payload`: Referência para os bytes do payload.
     ///
     /// ## Retorno
-    /// - `u32`: Soma dos valores dos bytes do payload.
+    /// - `u32`: Soma dos bytes do payload pelo algoritmo padrão
+    ///   ([`ChecksumAlgorithm::ByteSum`]); nunca é `0` para um payload vazio
+    ///   (veja [`checksum::BYTE_SUM_SEED`]).
     pub fn calculate_checksum(payload: &[u8]) -> u32 {
-        payload.iter().map(|&b| b as u32).sum()
+        ChecksumAlgorithm::ByteSum.compute(payload)
     }
 
     /// Cria um novo pacote com base nos parâmetros fornecidos.
@@ -153,18 +1171,822 @@ impl Packet {
     ///
     /// ## Retorno
     /// - `Self`: Um novo pacote.
+    ///
+    /// ## Pânico
+    /// Entra em pânico se o payload tiver mais de `u32::MAX` bytes. Para dados não
+    /// confiáveis, use [`Packet::try_new`].
     pub fn new(message_type: u8, sequence: u32, player_id: u64, payload: Vec<u8>) -> Self {
-        let payload_size = payload.len() as u32;
-        let checksum = Self::calculate_checksum(&payload);
+        let payload_size = u32::try_from(payload.len()).expect("payload excede u32::MAX bytes");
 
-        let header = PacketHeader {
+        let mut header = PacketHeader {
             message_type,
+            flags: PacketFlags::empty(),
+            priority: 0,
+            channel_id: 0,
+            content_type: 0,
+            ttl: PacketHeader::DEFAULT_TTL,
             sequence,
             player_id,
+            group_id: 0,
+            ack: 0,
+            ack_bits: 0,
             payload_size,
-            checksum,
+            header_checksum: 0,
+            checksum: 0,
         };
+        header.recompute(&payload, ChecksumScope::Payload);
 
         Self { header, payload }
     }
+
+    /// Cria um novo pacote cujo payload é a concatenação de várias fatias.
+    ///
+    /// O tamanho total é calculado antes da cópia, de forma que o payload é alocado uma
+    /// única vez, sem concatenações intermediárias.
+    ///
+    /// ## Parâmetros
+    /// - `message_type`: Tipo da mensagem.
+    /// - `sequence`: Sequência do pacote.
+    /// - `player_id`: Identificador único do jogador.
+    /// - `slices`: Partes do payload, na ordem em que são copiadas.
+    ///
+    /// ## Retorno
+    /// - `Self`: Um novo pacote, igual ao criado por [`Packet::new`] com as fatias concatenadas.
+    ///
+    /// ## Pânico
+    /// Entra em pânico se o payload tiver mais de `u32::MAX` bytes, como [`Packet::new`].
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::Packet;
+    ///
+    /// let prefix = [0xFF, 0x01];
+    /// let body = b"corpo".to_vec();
+    /// let packet = Packet::from_slices(1, 1, 7, &[&prefix, &body, &[]]);
+    ///
+    /// assert_eq!(packet, Packet::new(1, 1, 7, [&prefix[..], &body].concat()));
+    /// assert_eq!(packet.payload.capacity(), prefix.len() + body.len());
+    /// ```
+    pub fn from_slices(message_type: u8, sequence: u32, player_id: u64, slices: &[&[u8]]) -> Self {
+        let len = slices.iter().map(|slice| slice.len()).sum();
+        let mut payload = Vec::with_capacity(len);
+        for slice in slices {
+            payload.extend_from_slice(slice);
+        }
+        Self::new(message_type, sequence, player_id, payload)
+    }
+
+    /// Cria um novo pacote validando os parâmetros, para dados vindos de fontes não confiáveis.
+    ///
+    /// ## Parâmetros
+    /// - `message_type`: Tipo da mensagem; deve ser um [`MessageType`](message::MessageType) conhecido.
+    /// - `sequence`: Sequência do pacote.
+    /// - `player_id`: Identificador único do jogador.
+    /// - `payload`: Dados do pacote.
+    ///
+    /// ## Retorno
+    /// - `Result<Self, PacketError>`: O pacote, [`PacketError::PayloadTooLarge`] se o
+    ///   payload exceder [`MAX_PAYLOAD_SIZE`], ou [`PacketError::UnknownMessageType`] se o
+    ///   tipo pertencer à faixa reservada.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::{Packet, PacketError, MAX_PAYLOAD_SIZE};
+    ///
+    /// assert!(Packet::try_new(1, 1, 7, vec![1, 2, 3]).is_ok());
+    /// assert_eq!(
+    ///     Packet::try_new(0x10, 1, 7, vec![]),
+    ///     Err(PacketError::UnknownMessageType(0x10))
+    /// );
+    /// assert!(Packet::try_new(1, 1, 7, vec![0; MAX_PAYLOAD_SIZE + 1]).is_err());
+    /// ```
+    pub fn try_new(
+        message_type: u8,
+        sequence: u32,
+        player_id: u64,
+        payload: Vec<u8>,
+    ) -> Result<Self, PacketError> {
+        if payload.len() > MAX_PAYLOAD_SIZE {
+            return Err(PacketError::PayloadTooLarge {
+                size: payload.len(),
+                limit: MAX_PAYLOAD_SIZE,
+            });
+        }
+        message::MessageType::try_from(message_type).map_err(PacketError::UnknownMessageType)?;
+
+        Ok(Self::new(message_type, sequence, player_id, payload))
+    }
+
+    /// Cria um pacote com um checksum arbitrário, independente do payload.
+    ///
+    /// **Destinado a testes.** Permite montar pacotes com checksum deliberadamente
+    /// incorreto para exercitar os caminhos de rejeição do receptor. Código de
+    /// produção deve usar [`Packet::new`].
+    ///
+    /// ## Parâmetros
+    /// - `message_type`: Tipo da mensagem.
+    /// - `sequence`: Sequência do pacote.
+    /// - `player_id`: Identificador único do jogador.
+    /// - `payload`: Dados do pacote.
+    /// - `checksum`: Valor gravado no cabeçalho no lugar do checksum calculado.
+    ///
+    /// ## Retorno
+    /// - `Self`: Um novo pacote com o checksum informado.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::Packet;
+    ///
+    /// let corrupted = Packet::new_with_raw_checksum(1, 1, 7, vec![1, 2, 3], 0xDEAD_BEEF);
+    /// let received = Packet::from_bytes(&corrupted.to_bytes()).unwrap();
+    ///
+    /// assert!(!received.verify_checksum());
+    /// ```
+    pub fn new_with_raw_checksum(
+        message_type: u8,
+        sequence: u32,
+        player_id: u64,
+        payload: Vec<u8>,
+        checksum: u32,
+    ) -> Self {
+        let mut packet = Self::new(message_type, sequence, player_id, payload);
+        packet.header.checksum = checksum;
+        packet
+    }
+
+    /// Verifica se o checksum do cabeçalho corresponde ao payload.
+    ///
+    /// O checksum é recalculado com o algoritmo e o escopo indicados pelas flags do
+    /// cabeçalho (veja [`Packet::checksum_algorithm`] e [`Packet::checksum_scope`]).
+    ///
+    /// ## Retorno
+    /// - `bool`: `true` se o checksum armazenado é igual ao calculado.
+    pub fn verify_checksum(&self) -> bool {
+        self.check_checksum().is_ok()
+    }
+
+    /// Verifica o checksum, retornando um erro descritivo em caso de divergência.
+    ///
+    /// ## Retorno
+    /// - `Result<(), PacketError>`: [`PacketError::ChecksumMismatch`] se o checksum
+    ///   armazenado difere do calculado.
+    pub fn check_checksum(&self) -> Result<(), PacketError> {
+        let actual = self.header.expected_checksum(&self.payload);
+        if self.header.checksum != actual {
+            instrument::checksum_mismatch(&self.header, actual);
+            return Err(PacketError::ChecksumMismatch {
+                expected: self.header.checksum,
+                actual,
+            });
+        }
+        Ok(())
+    }
+
+    /// Verifica o checksum tentando cada um dos algoritmos informados.
+    ///
+    /// Destinado a migrações de algoritmo: durante a transição, o receptor aceita tanto
+    /// remetentes antigos quanto novos, independentemente do algoritmo indicado pelas
+    /// flags. O escopo continua sendo o indicado pelas flags. Terminada a migração,
+    /// volte a usar [`Packet::verify_checksum`].
+    ///
+    /// ## Parâmetros
+    /// - `algos`: Algoritmos aceitos.
+    ///
+    /// ## Retorno
+    /// - `bool`: `true` se o checksum armazenado corresponde a algum dos algoritmos.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::Packet;
+    /// use packet::checksum::ChecksumAlgorithm;
+    ///
+    /// let accepted = [ChecksumAlgorithm::ByteSum, ChecksumAlgorithm::CRC32];
+    /// let old_sender = Packet::new(1, 1, 7, vec![1, 2, 3]);
+    /// let mut new_sender = old_sender.clone().with_checksum_algorithm(ChecksumAlgorithm::CRC32);
+    /// // Um remetente que calcula CRC-32 sem marcar a flag ainda é aceito.
+    /// new_sender.header.flags = old_sender.header.flags;
+    ///
+    /// assert!(old_sender.verify_checksum_any(&accepted));
+    /// assert!(new_sender.verify_checksum_any(&accepted));
+    /// assert!(!new_sender.verify_checksum());
+    /// assert!(!new_sender.verify_checksum_any(&[ChecksumAlgorithm::ByteSum]));
+    /// ```
+    pub fn verify_checksum_any(&self, algos: &[ChecksumAlgorithm]) -> bool {
+        let scope = self.checksum_scope();
+        algos
+            .iter()
+            .any(|&algorithm| self.compute_checksum(scope, algorithm) == self.header.checksum)
+    }
+
+    /// Cria um novo pacote confiável, que deve ser confirmado pelo destino e
+    /// retransmitido até a confirmação.
+    ///
+    /// ## Parâmetros
+    /// - `message_type`: Tipo da mensagem.
+    /// - `sequence`: Sequência do pacote.
+    /// - `player_id`: Identificador único do jogador.
+    /// - `payload`: Dados do pacote.
+    ///
+    /// ## Retorno
+    /// - `Self`: Um novo pacote com a flag [`PacketFlags::RELIABLE`].
+    pub fn reliable(message_type: u8, sequence: u32, player_id: u64, payload: Vec<u8>) -> Self {
+        let mut packet = Self::new(message_type, sequence, player_id, payload);
+        packet.header.flags.insert(PacketFlags::RELIABLE);
+        packet.header_edited();
+        packet
+    }
+
+    /// Acrescenta bytes ao fim do payload, atualizando `payload_size` e o checksum.
+    ///
+    /// ## Parâmetros
+    /// - `extra`: Bytes a serem acrescentados.
+    ///
+    /// ## Retorno
+    /// - `Result<(), PacketError>`: [`PacketError::PayloadTooLarge`] se o novo payload
+    ///   excederia [`MAX_PAYLOAD_SIZE`]; nesse caso o pacote não é alterado.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::Packet;
+    ///
+    /// let mut packet = Packet::new(1, 1, 7, vec![1, 2]);
+    /// packet.append_payload(&[3, 4]).unwrap();
+    ///
+    /// assert_eq!(packet, Packet::new(1, 1, 7, vec![1, 2, 3, 4]));
+    /// assert!(packet.verify_checksum());
+    /// ```
+    pub fn append_payload(&mut self, extra: &[u8]) -> Result<(), PacketError> {
+        let size = self.payload.len() + extra.len();
+        if size > MAX_PAYLOAD_SIZE {
+            return Err(PacketError::PayloadTooLarge {
+                size,
+                limit: MAX_PAYLOAD_SIZE,
+            });
+        }
+
+        self.payload.extend_from_slice(extra);
+        self.refresh_header();
+        Ok(())
+    }
+
+    /// Encurta o payload para `new_len` bytes, atualizando `payload_size` e o checksum.
+    ///
+    /// ## Parâmetros
+    /// - `new_len`: Novo tamanho do payload.
+    ///
+    /// ## Retorno
+    /// - `Result<(), PacketError>`: [`PacketError::InvalidPayloadLength`] se `new_len`
+    ///   excede o tamanho atual; nesse caso o pacote não é alterado.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::Packet;
+    ///
+    /// let mut packet = Packet::new(1, 1, 7, vec![1, 2, 0, 0]);
+    /// packet.truncate_payload(2).unwrap();
+    /// assert_eq!(packet, Packet::new(1, 1, 7, vec![1, 2]));
+    ///
+    /// assert!(packet.truncate_payload(3).is_err());
+    /// ```
+    pub fn truncate_payload(&mut self, new_len: usize) -> Result<(), PacketError> {
+        if new_len > self.payload.len() {
+            return Err(PacketError::InvalidPayloadLength {
+                requested: new_len,
+                actual: self.payload.len(),
+            });
+        }
+
+        self.payload.truncate(new_len);
+        self.refresh_header();
+        Ok(())
+    }
+
+    /// Substitui o payload, atualizando `payload_size` e o checksum, e devolve o anterior.
+    ///
+    /// Permite reaproveitar um mesmo pacote em vários envios e devolver o buffer
+    /// antigo a um *pool* sem novas alocações.
+    ///
+    /// ## Parâmetros
+    /// - `new`: Novo payload.
+    ///
+    /// ## Retorno
+    /// - `Vec<u8>`: O payload anterior, com sua capacidade preservada.
+    ///
+    /// ## Pânico
+    /// Entra em pânico se o novo payload tiver mais de `u32::MAX` bytes, como
+    /// [`Packet::new`].
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::Packet;
+    ///
+    /// let mut packet = Packet::new(1, 1, 7, vec![1, 2, 3]);
+    /// let mut old = packet.replace_payload(vec![4, 5]);
+    /// assert_eq!(old, [1, 2, 3]);
+    /// assert_eq!(packet, Packet::new(1, 1, 7, vec![4, 5]));
+    ///
+    /// // O buffer devolvido pode ser reutilizado no próximo envio.
+    /// old.clear();
+    /// old.extend_from_slice(&[6]);
+    /// packet.replace_payload(old);
+    /// assert_eq!(packet.header.payload_size, 1);
+    /// assert!(packet.verify_checksum());
+    /// ```
+    pub fn replace_payload(&mut self, new: Vec<u8>) -> Vec<u8> {
+        assert!(
+            u32::try_from(new.len()).is_ok(),
+            "payload excede u32::MAX bytes"
+        );
+
+        let old = core::mem::replace(&mut self.payload, new);
+        self.refresh_header();
+        old
+    }
+
+    /// Recalcula `payload_size` e o checksum após uma alteração no payload.
+    fn refresh_header(&mut self) {
+        self.header.payload_size = self.payload.len() as u32;
+        self.recompute_checksum();
+    }
+
+    /// Recalcula `header_checksum` após a edição de um campo do cabeçalho, e também o
+    /// checksum quando o cabeçalho faz parte do seu escopo.
+    fn header_edited(&mut self) {
+        if self.checksum_scope() == ChecksumScope::HeaderAndPayload {
+            self.recompute_checksum();
+        } else {
+            self.header.header_checksum = self.header.expected_header_checksum();
+        }
+    }
+
+    /// Recalcula o checksum mantendo o algoritmo e o escopo atuais.
+    ///
+    /// Deve ser chamado após editar diretamente os campos públicos do pacote.
+    pub fn recompute_checksum(&mut self) {
+        let scope = self.checksum_scope();
+        self.header.recompute(&self.payload, scope);
+    }
+
+    /// Retorna o algoritmo de checksum usado pelo pacote, lido das flags do cabeçalho.
+    ///
+    /// ## Retorno
+    /// - `ChecksumAlgorithm`: [`ChecksumAlgorithm::XxHash32`] se a flag
+    ///   [`PacketFlags::XXHASH32`] está ativa; senão [`ChecksumAlgorithm::Crc32`] se a
+    ///   flag [`PacketFlags::CRC32`] está ativa, na variante indicada por
+    ///   [`PacketFlags::CRC32C`]; caso contrário, [`ChecksumAlgorithm::ByteSum`].
+    pub fn checksum_algorithm(&self) -> ChecksumAlgorithm {
+        self.header.checksum_algorithm()
+    }
+
+    /// Retorna o escopo do checksum usado pelo pacote, lido das flags do cabeçalho.
+    pub fn checksum_scope(&self) -> ChecksumScope {
+        self.header.checksum_scope()
+    }
+
+    /// Retorna o pacote usando o escopo de checksum informado, recalculando o checksum.
+    ///
+    /// ## Parâmetros
+    /// - `scope`: Parte do pacote coberta pelo checksum.
+    ///
+    /// ## Retorno
+    /// - `Self`: O pacote com as flags e o checksum atualizados.
+    pub fn with_checksum_scope(mut self, scope: ChecksumScope) -> Self {
+        self.header.recompute(&self.payload, scope);
+        self
+    }
+
+    /// Retorna o pacote usando o algoritmo de checksum informado, recalculando o checksum.
+    ///
+    /// Com [`ChecksumAlgorithm::Adaptive`], o algoritmo é escolhido pelo tamanho atual
+    /// do payload e mantido em edições posteriores do payload.
+    ///
+    /// ## Parâmetros
+    /// - `algorithm`: Algoritmo a ser usado.
+    ///
+    /// ## Retorno
+    /// - `Self`: O pacote com as flags e o checksum atualizados.
+    pub fn with_checksum_algorithm(mut self, algorithm: ChecksumAlgorithm) -> Self {
+        let algorithm = algorithm.resolve(self.payload.len());
+        algorithm.apply_to(&mut self.header.flags);
+        self.refresh_header();
+        self
+    }
+
+    /// Retorna o pacote com a sequência informada.
+    ///
+    /// Com o escopo padrão ([`ChecksumScope::Payload`]) o checksum é reaproveitado sem
+    /// recálculo, o que torna barato derivar várias cópias de um pacote com payload
+    /// grande; com [`ChecksumScope::HeaderAndPayload`] ele é recalculado.
+    ///
+    /// ## Parâmetros
+    /// - `sequence`: Nova sequência.
+    ///
+    /// ## Retorno
+    /// - `Self`: O pacote com o campo `sequence` atualizado.
+    pub fn with_sequence(mut self, sequence: u32) -> Self {
+        self.header.sequence = sequence;
+        self.header_edited();
+        self
+    }
+
+    /// Retorna o pacote destinado ao jogador informado.
+    ///
+    /// Assim como em [`Packet::with_sequence`], o checksum só é recalculado quando o
+    /// escopo inclui o cabeçalho.
+    ///
+    /// ## Parâmetros
+    /// - `player_id`: Identificador do jogador.
+    ///
+    /// ## Retorno
+    /// - `Self`: O pacote com o campo `player_id` atualizado.
+    ///
+    /// ## Exemplos
+    /// Um mesmo payload enviado a vários jogadores:
+    /// ```rust
+    /// use packet::Packet;
+    ///
+    /// let template = Packet::new(0x81, 1, 0, vec![0xAB; 4096]);
+    /// let copies: Vec<_> = [7, 8, 9]
+    ///     .into_iter()
+    ///     .map(|player_id| template.clone().with_player_id(player_id))
+    ///     .collect();
+    ///
+    /// assert!(copies.iter().all(|copy| copy.header.checksum == template.header.checksum));
+    /// assert!(copies.iter().all(Packet::verify_checksum));
+    /// assert_eq!(copies[2].header.player_id, 9);
+    /// ```
+    pub fn with_player_id(mut self, player_id: u64) -> Self {
+        self.header.player_id = player_id;
+        self.header_edited();
+        self
+    }
+
+    /// Retorna o pacote com o limite de saltos informado.
+    ///
+    /// ## Parâmetros
+    /// - `ttl`: Quantidade de relays que podem encaminhar o pacote.
+    ///
+    /// ## Retorno
+    /// - `Self`: O pacote com o campo `ttl` atualizado.
+    pub fn with_ttl(mut self, ttl: u8) -> Self {
+        self.header.ttl = ttl;
+        self.header_edited();
+        self
+    }
+
+    /// Decrementa o limite de saltos antes do encaminhamento por um relay.
+    ///
+    /// Quando o limite chega a zero, o pacote não deve ser encaminhado; isso impede
+    /// que pacotes circulem indefinidamente em topologias com ciclos. O checksum é
+    /// recalculado se o escopo incluir o cabeçalho.
+    ///
+    /// ## Retorno
+    /// - `Result<(), PacketError>`: [`PacketError::TtlExpired`] se o limite chegou ou já
+    ///   estava em zero; nesse caso o pacote deve ser descartado.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::{Packet, PacketError};
+    ///
+    /// let mut packet = Packet::new(1, 1, 7, vec![1, 2, 3]).with_ttl(2);
+    /// assert_eq!(packet.decrement_ttl(), Ok(()));
+    /// assert_eq!(packet.header.ttl, 1);
+    /// assert_eq!(packet.decrement_ttl(), Err(PacketError::TtlExpired));
+    /// assert_eq!(packet.header.ttl, 0);
+    /// assert!(packet.verify_checksum());
+    /// ```
+    pub fn decrement_ttl(&mut self) -> Result<(), PacketError> {
+        self.header.ttl = self.header.ttl.saturating_sub(1);
+        self.header_edited();
+        if self.header.ttl == 0 {
+            return Err(PacketError::TtlExpired);
+        }
+        Ok(())
+    }
+
+    /// Retorna o pacote com a prioridade informada.
+    ///
+    /// ## Parâmetros
+    /// - `priority`: Nova prioridade; valores maiores são mais importantes.
+    ///
+    /// ## Retorno
+    /// - `Self`: O pacote com o campo `priority` atualizado.
+    pub fn with_priority(mut self, priority: u8) -> Self {
+        self.header.priority = priority;
+        self.header_edited();
+        self
+    }
+
+    /// Retorna o pacote no canal lógico informado.
+    ///
+    /// ## Parâmetros
+    /// - `channel_id`: Canal do pacote; cada canal tem sua própria ordem de entrega
+    ///   (veja [`ordered::ChannelMux`] e [`channel::ChannelSet`]).
+    ///
+    /// ## Retorno
+    /// - `Self`: O pacote com o campo `channel_id` atualizado.
+    pub fn with_channel_id(mut self, channel_id: u8) -> Self {
+        self.header.channel_id = channel_id;
+        self.header_edited();
+        self
+    }
+
+    /// Retorna o pacote destinado ao grupo informado.
+    ///
+    /// ## Parâmetros
+    /// - `group_id`: Grupo (sala) de destino; `0` indica nenhum grupo.
+    ///
+    /// ## Retorno
+    /// - `Self`: O pacote com o campo `group_id` atualizado.
+    pub fn with_group_id(mut self, group_id: u32) -> Self {
+        self.header.group_id = group_id;
+        self.header_edited();
+        self
+    }
+
+    /// Anexa ao pacote a confirmação dos pacotes recebidos do outro lado, evitando
+    /// o envio de pacotes de confirmação separados.
+    ///
+    /// O checksum só é recalculado quando o escopo inclui o cabeçalho
+    /// ([`ChecksumScope::HeaderAndPayload`]).
+    ///
+    /// ## Parâmetros
+    /// - `latest`: Sequência mais recente recebida.
+    /// - `bits`: Bit `i` indica o recebimento da sequência `latest - 1 - i`.
+    ///
+    /// ## Retorno
+    /// - `Self`: O pacote com os campos de confirmação e a flag [`PacketFlags::ACK`].
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::Packet;
+    ///
+    /// let packet = Packet::new(1, 10, 7, vec![1]).with_ack(41, 0b101);
+    /// let received = Packet::from_bytes(&packet.to_bytes()).unwrap();
+    ///
+    /// assert_eq!(received.ack(), Some((41, 0b101)));
+    /// assert_eq!(received.acked_sequences().collect::<Vec<_>>(), [41, 40, 38]);
+    /// assert_eq!(Packet::new(1, 11, 7, vec![]).ack(), None);
+    /// ```
+    pub fn with_ack(mut self, latest: u32, bits: u32) -> Self {
+        self.header.ack = latest;
+        self.header.ack_bits = bits;
+        self.header.flags.insert(PacketFlags::ACK);
+        self.header_edited();
+        self
+    }
+
+    /// Retorna a confirmação anexada ao pacote.
+    ///
+    /// ## Retorno
+    /// - `Option<(u32, u32)>`: A sequência mais recente e o campo de bits, ou `None` se o
+    ///   pacote não carrega a flag [`PacketFlags::ACK`].
+    pub fn ack(&self) -> Option<(u32, u32)> {
+        self.header
+            .flags
+            .contains(PacketFlags::ACK)
+            .then_some((self.header.ack, self.header.ack_bits))
+    }
+
+    /// Retorna todas as sequências confirmadas por este pacote, da mais recente para a mais antiga.
+    pub fn acked_sequences(&self) -> impl Iterator<Item = u32> {
+        self.ack().into_iter().flat_map(|(latest, bits)| {
+            let previous = (0..32)
+                .filter(move |i| bits & (1 << i) != 0)
+                .map(move |i| latest.wrapping_sub(1 + i));
+            core::iter::once(latest).chain(previous)
+        })
+    }
+
+    /// Indica se o pacote exige entrega confiável.
+    pub fn is_reliable(&self) -> bool {
+        self.header.flags.contains(PacketFlags::RELIABLE)
+    }
+
+    /// Indica se o pacote é degenerado: tipo, sequência e jogador zerados e payload
+    /// vazio ou só de zeros, como o resultado de interpretar uma sequência de zeros.
+    ///
+    /// Sequências de zeros, comuns em preenchimentos e em fluxos à espera de
+    /// ressincronização, já são rejeitadas por [`Packet::from_bytes`] pelos bytes
+    /// mágicos e pelo `header_checksum`. O predicado serve para pacotes construídos ou
+    /// convertidos por outros caminhos, como implementações antigas sem bytes mágicos.
+    ///
+    /// ## Retorno
+    /// - `bool`: `true` se o pacote não carrega nenhuma informação.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::{Packet, PacketError, PacketHeader};
+    ///
+    /// let zeros = [0u8; PacketHeader::SIZE];
+    /// assert!(matches!(Packet::from_bytes(&zeros), Err(PacketError::InvalidMagic { .. })));
+    ///
+    /// assert!(Packet::new(0, 0, 0, vec![0; 4]).is_degenerate());
+    /// assert!(!Packet::new(0, 0, 0, vec![1]).is_degenerate());
+    /// assert!(!Packet::new(1, 0, 0, vec![]).is_degenerate());
+    /// ```
+    pub fn is_degenerate(&self) -> bool {
+        self.header.message_type == 0
+            && self.header.sequence == 0
+            && self.header.player_id == 0
+            && self.payload.iter().all(|&byte| byte == 0)
+    }
+
+    /// Ativa flags do pacote, mantendo o checksum coerente.
+    ///
+    /// O checksum é recalculado quando o escopo passa a incluir ou já inclui o
+    /// cabeçalho, ou quando a flag altera o algoritmo ou o escopo do checksum
+    /// ([`ChecksumAlgorithm::FLAGS`] e [`ChecksumScope::FLAGS`]).
+    ///
+    /// Nenhuma combinação de flags é proibida; cabe ao chamador não ativar flags que
+    /// contradigam o conteúdo do payload, como [`PacketFlags::FRAGMENT`] em uma
+    /// mensagem completa.
+    ///
+    /// ## Parâmetros
+    /// - `flag`: Flags a serem ativadas.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::{Packet, PacketFlags};
+    /// use packet::checksum::{ChecksumAlgorithm, ChecksumScope};
+    ///
+    /// let mut packet = Packet::new(1, 1, 7, vec![1, 2, 3])
+    ///     .with_checksum_scope(ChecksumScope::HeaderAndPayload);
+    /// packet.set_flag(PacketFlags::RELIABLE);
+    ///
+    /// assert!(packet.is_reliable());
+    /// assert!(packet.verify_checksum());
+    ///
+    /// // Trocar o algoritmo recalcula o checksum com o novo algoritmo.
+    /// packet.set_flag(PacketFlags::XXHASH32);
+    /// assert_eq!(packet.checksum_algorithm(), ChecksumAlgorithm::XxHash32);
+    /// assert!(packet.verify_checksum());
+    ///
+    /// packet.clear_flag(PacketFlags::XXHASH32);
+    /// assert_eq!(packet.checksum_algorithm(), ChecksumAlgorithm::ByteSum);
+    /// assert!(packet.verify_checksum());
+    /// ```
+    pub fn set_flag(&mut self, flag: PacketFlags) {
+        self.header.flags.insert(flag);
+        self.flags_edited(flag);
+    }
+
+    /// Desativa flags do pacote, mantendo o checksum coerente como em [`Packet::set_flag`].
+    ///
+    /// ## Parâmetros
+    /// - `flag`: Flags a serem desativadas.
+    pub fn clear_flag(&mut self, flag: PacketFlags) {
+        self.header.flags.remove(flag);
+        self.flags_edited(flag);
+    }
+
+    /// Recalcula o checksum após a alteração das flags em `changed`, se necessário.
+    fn flags_edited(&mut self, changed: PacketFlags) {
+        if (changed & (ChecksumAlgorithm::FLAGS | ChecksumScope::FLAGS)).is_empty() {
+            self.header_edited();
+        } else {
+            self.recompute_checksum();
+        }
+    }
+
+    /// Byte usado por [`Packet::redacted`] no lugar de cada byte do payload.
+    pub const REDACTED_BYTE: u8 = 0xAA;
+
+    /// Retorna uma cópia do pacote segura para registro em log, com o payload
+    /// substituído por [`Packet::REDACTED_BYTE`].
+    ///
+    /// O cabeçalho é mantido intacto, inclusive o checksum do payload original,
+    /// de modo que o tamanho e os metadados continuam visíveis no log. Por isso a
+    /// cópia não passa em [`Packet::verify_checksum`] e não deve ser transmitida.
+    ///
+    /// ## Retorno
+    /// - `Packet`: A cópia com o payload ocultado.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::Packet;
+    ///
+    /// let packet = Packet::new(3, 1, 7, b"token=secreto".to_vec());
+    /// let redacted = packet.redacted();
+    ///
+    /// assert_eq!(redacted.header, packet.header);
+    /// assert_eq!(redacted.payload, vec![Packet::REDACTED_BYTE; 13]);
+    /// ```
+    pub fn redacted(&self) -> Packet {
+        Packet {
+            header: self.header.clone(),
+            payload: alloc::vec![Self::REDACTED_BYTE; self.payload.len()],
+        }
+    }
+
+    /// Retorna uma cópia do pacote com o `player_id` substituído por um pseudônimo.
+    ///
+    /// Pseudônimos são alocados em ordem (`1`, `2`, ...) na primeira vez em que um
+    /// jogador aparece e reutilizados pelo mapeamento nos pacotes seguintes, de modo
+    /// que a exportação preserva quem enviou o quê sem revelar os identificadores reais.
+    /// Um novo pseudônimo é o sucessor do maior já presente no mapeamento, de modo que
+    /// um mapeamento pré-preenchido ou esparso nunca recebe repetições; se o maior é
+    /// [`u64::MAX`], é o menor valor a partir de `1` ainda livre. O payload, os tamanhos
+    /// e os demais campos são mantidos, e o checksum é recalculado.
+    ///
+    /// ## Parâmetros
+    /// - `mapping`: Pseudônimos já alocados, indexados pelo `player_id` real; deve ser
+    ///   o mesmo para todos os pacotes de uma exportação.
+    ///
+    /// ## Retorno
+    /// - `Packet`: A cópia anonimizada.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use std::collections::HashMap;
+    /// use packet::Packet;
+    ///
+    /// let mut mapping = HashMap::new();
+    /// let a = Packet::new(1, 1, 9001, vec![1]).anonymize(&mut mapping);
+    /// let b = Packet::new(1, 2, 4242, vec![2]).anonymize(&mut mapping);
+    /// let c = Packet::new(1, 3, 9001, vec![3]).anonymize(&mut mapping);
+    ///
+    /// assert_eq!([a.header.player_id, b.header.player_id, c.header.player_id], [1, 2, 1]);
+    /// assert!(c.verify_checksum());
+    ///
+    /// // Um mapeamento pré-preenchido continua a partir do maior pseudônimo.
+    /// let mut mapping = HashMap::from([(7, 1), (8, 5)]);
+    /// let d = Packet::new(1, 4, 9001, vec![4]).anonymize(&mut mapping);
+    /// let e = Packet::new(1, 5, 4242, vec![5]).anonymize(&mut mapping);
+    ///
+    /// assert_eq!([d.header.player_id, e.header.player_id], [6, 7]);
+    ///
+    /// // Sem sucessor para o maior pseudônimo, o menor livre é usado.
+    /// let mut mapping = HashMap::from([(7, 1), (8, u64::MAX)]);
+    /// let f = Packet::new(1, 6, 9001, vec![6]).anonymize(&mut mapping);
+    ///
+    /// assert_eq!(f.header.player_id, 2);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn anonymize(&self, mapping: &mut HashMap<u64, u64>) -> Packet {
+        let pseudonym = match mapping.get(&self.header.player_id) {
+            Some(&pseudonym) => pseudonym,
+            None => {
+                let next_pseudonym = match mapping.values().max() {
+                    None => 1,
+                    Some(max) => max.checked_add(1).unwrap_or_else(|| {
+                        let taken: HashSet<u64> = mapping.values().copied().collect();
+                        (1..)
+                            .find(|pseudonym| !taken.contains(pseudonym))
+                            .expect("um mapeamento em memória não ocupa todos os pseudônimos")
+                    }),
+                };
+                mapping.insert(self.header.player_id, next_pseudonym);
+                next_pseudonym
+            }
+        };
+
+        let mut packet = self.clone();
+        packet.header.player_id = pseudonym;
+        packet.recompute_checksum();
+        packet
+    }
+
+    /// Serializa o pacote com os campos que variam a cada transmissão normalizados,
+    /// produzindo uma representação estável para calcular hashes ou assinaturas.
+    ///
+    /// Dois pacotes que diferem apenas nesses campos produzem os mesmos bytes, de modo
+    /// que um relay pode decrementar o `ttl` ou anexar confirmações sem invalidar uma
+    /// assinatura calculada sobre eles. Os campos normalizados são exatamente:
+    ///
+    /// - `ttl`, gravado como `0`;
+    /// - `ack` e `ack_bits`, gravados como `0`, e a flag [`PacketFlags::ACK`], removida;
+    /// - `header_checksum` e `checksum`, gravados como `0`, pois dependem dos campos acima.
+    ///
+    /// Todos os demais campos e o payload são serializados sem alterações.
+    ///
+    /// ## Retorno
+    /// - `Vec<u8>`: Os bytes canônicos; não correspondem a um pacote válido na rede.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::Packet;
+    ///
+    /// let sent = Packet::new(1, 1, 7, vec![1, 2, 3]);
+    /// let mut relayed = sent.clone().with_ack(41, 0b1);
+    /// relayed.decrement_ttl().unwrap();
+    ///
+    /// assert_ne!(relayed.to_bytes(), sent.to_bytes());
+    /// assert_eq!(relayed.canonical_bytes(), sent.canonical_bytes());
+    /// assert_ne!(sent.with_sequence(2).canonical_bytes(), relayed.canonical_bytes());
+    /// ```
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        let mut header = self.header.clone();
+        header.ttl = 0;
+        header.ack = 0;
+        header.ack_bits = 0;
+        header.flags.remove(PacketFlags::ACK);
+        header.header_checksum = 0;
+        header.checksum = 0;
+
+        let mut bytes = Vec::with_capacity(self.serialized_len());
+        bytes.extend_from_slice(&header.to_array());
+        bytes.extend_from_slice(&self.payload);
+        bytes
+    }
 }
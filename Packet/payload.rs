@@ -0,0 +1,281 @@
+//! # Módulo `payload`
+//!
+//! Liga tipos de payload da aplicação ao tipo de mensagem do cabeçalho.
+//!
+//! Um tipo que implementa [`PacketPayload`] declara o `message_type` que o
+//! identifica na rede. [`Packet::decode_typed`] só decodifica o payload quando o
+//! tipo do pacote corresponde, impedindo, por exemplo, que um pacote de entrada do
+//! jogador seja interpretado como uma mensagem de chat.
+//!
+//! ## Exemplos
+//! A conversão com `try_into` é obtida implementando [`TryFrom<Packet>`] sobre
+//! [`Packet::decode_typed`]:
+//! ```rust
+//! use packet::{Packet, PacketError};
+//! use packet::payload::PacketPayload;
+//!
+//! #[derive(Debug, PartialEq)]
+//! struct PlayerInput {
+//!     buttons: u8,
+//! }
+//!
+//! impl PacketPayload for PlayerInput {
+//!     const MESSAGE_TYPE: u8 = 0x80;
+//!
+//!     fn encode(&self) -> Vec<u8> {
+//!         vec![self.buttons]
+//!     }
+//!
+//!     fn decode(payload: &[u8]) -> Result<Self, PacketError> {
+//!         match payload {
+//!             [buttons] => Ok(Self { buttons: *buttons }),
+//!             _ => Err(PacketError::InvalidPayload { reason: "entrada deve ter 1 byte" }),
+//!         }
+//!     }
+//! }
+//!
+//! impl TryFrom<Packet> for PlayerInput {
+//!     type Error = PacketError;
+//!
+//!     fn try_from(packet: Packet) -> Result<Self, PacketError> {
+//!         packet.decode_typed()
+//!     }
+//! }
+//!
+//! let packet = Packet::from_payload(1, 7, &PlayerInput { buttons: 0b11 });
+//! let input: PlayerInput = packet.try_into().unwrap();
+//! assert_eq!(input, PlayerInput { buttons: 0b11 });
+//!
+//! let chat = Packet::new(0x81, 2, 7, b"oi".to_vec());
+//! assert_eq!(
+//!     chat.decode_typed::<PlayerInput>(),
+//!     Err(PacketError::UnexpectedMessageType { expected: 0x80, found: 0x81 })
+//! );
+//! ```
+//!
+//! ## Codificação do payload
+//! O campo `content_type` do cabeçalho ([`ContentType`]) indica como o payload foi
+//! codificado, independentemente do tipo de mensagem. Um tipo que aceita mais de uma
+//! codificação sobrescreve [`PacketPayload::decode_as`] para escolher o decodificador:
+//! ```rust
+//! use packet::{Packet, PacketError};
+//! use packet::payload::{ContentType, PacketPayload};
+//!
+//! #[derive(Debug, PartialEq)]
+//! struct Score(u32);
+//!
+//! impl PacketPayload for Score {
+//!     const MESSAGE_TYPE: u8 = 0x81;
+//!
+//!     fn encode(&self) -> Vec<u8> {
+//!         self.0.to_le_bytes().to_vec()
+//!     }
+//!
+//!     fn decode(payload: &[u8]) -> Result<Self, PacketError> {
+//!         let bytes = payload.try_into().map_err(|_| PacketError::InvalidPayload {
+//!             reason: "placar deve ter 4 bytes",
+//!         })?;
+//!         Ok(Self(u32::from_le_bytes(bytes)))
+//!     }
+//!
+//!     fn decode_as(content_type: ContentType, payload: &[u8]) -> Result<Self, PacketError> {
+//!         match content_type {
+//!             ContentType::Raw => Self::decode(payload),
+//!             ContentType::Json => std::str::from_utf8(payload)
+//!                 .ok()
+//!                 .and_then(|text| text.trim().parse().ok())
+//!                 .map(Self)
+//!                 .ok_or(PacketError::InvalidPayload { reason: "JSON inválido" }),
+//!             _ => Err(PacketError::InvalidPayload { reason: "codificação não suportada" }),
+//!         }
+//!     }
+//! }
+//!
+//! let json = Packet::new(0x81, 1, 7, b"42".to_vec()).with_content_type(ContentType::Json);
+//! assert_eq!(json.decode_typed::<Score>(), Ok(Score(42)));
+//!
+//! let raw = Packet::from_payload(2, 7, &Score(42));
+//! assert_eq!(raw.content_type(), ContentType::Raw);
+//! assert_eq!(raw.decode_typed::<Score>(), Ok(Score(42)));
+//! ```
+
+use crate::{Packet, PacketError};
+
+/// Quantidade máxima de bytes do início do payload considerada por
+/// [`Packet::payload_entropy_estimate`].
+pub const ENTROPY_SAMPLE_SIZE: usize = 4096;
+
+/// Codificação do payload, indicada pelo campo `content_type` do cabeçalho.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ContentType {
+    /// Bytes em formato próprio da aplicação, sem codificação genérica.
+    #[default]
+    Raw,
+    /// Texto JSON em UTF-8.
+    Json,
+    /// Dados serializados com `bincode`.
+    Bincode,
+    /// Dados serializados com `postcard`.
+    Postcard,
+    /// Codificação definida pela aplicação, com qualquer byte não listado acima.
+    Custom(u8),
+}
+
+impl From<u8> for ContentType {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => Self::Raw,
+            1 => Self::Json,
+            2 => Self::Bincode,
+            3 => Self::Postcard,
+            _ => Self::Custom(value),
+        }
+    }
+}
+
+impl From<ContentType> for u8 {
+    fn from(content_type: ContentType) -> u8 {
+        match content_type {
+            ContentType::Raw => 0,
+            ContentType::Json => 1,
+            ContentType::Bincode => 2,
+            ContentType::Postcard => 3,
+            ContentType::Custom(value) => value,
+        }
+    }
+}
+
+/// Payload da aplicação associado a um tipo de mensagem.
+pub trait PacketPayload: Sized {
+    /// Tipo de mensagem que identifica este payload no cabeçalho.
+    const MESSAGE_TYPE: u8;
+
+    /// Codificação produzida por [`PacketPayload::encode`], gravada no cabeçalho por
+    /// [`Packet::from_payload`].
+    const CONTENT_TYPE: ContentType = ContentType::Raw;
+
+    /// Serializa o payload.
+    fn encode(&self) -> Vec<u8>;
+
+    /// Interpreta os bytes do payload.
+    ///
+    /// ## Parâmetros
+    /// - `payload`: Bytes do payload do pacote.
+    ///
+    /// ## Retorno
+    /// - `Result<Self, PacketError>`: O payload decodificado ou, normalmente,
+    ///   [`PacketError::InvalidPayload`].
+    fn decode(payload: &[u8]) -> Result<Self, PacketError>;
+
+    /// Interpreta os bytes do payload de acordo com a codificação indicada no cabeçalho.
+    ///
+    /// A implementação padrão ignora a codificação e chama [`PacketPayload::decode`].
+    ///
+    /// ## Parâmetros
+    /// - `content_type`: Codificação indicada pelo pacote.
+    /// - `payload`: Bytes do payload do pacote.
+    ///
+    /// ## Retorno
+    /// - `Result<Self, PacketError>`: O payload decodificado.
+    fn decode_as(content_type: ContentType, payload: &[u8]) -> Result<Self, PacketError> {
+        let _ = content_type;
+        Self::decode(payload)
+    }
+}
+
+impl Packet {
+    /// Cria um pacote a partir de um payload tipado, usando o tipo de mensagem dele.
+    ///
+    /// ## Parâmetros
+    /// - `sequence`: Sequência do pacote.
+    /// - `player_id`: Identificador único do jogador.
+    /// - `payload`: Payload a ser serializado.
+    ///
+    /// ## Retorno
+    /// - `Self`: Um novo pacote com `message_type` igual a [`PacketPayload::MESSAGE_TYPE`]
+    ///   e `content_type` igual a [`PacketPayload::CONTENT_TYPE`].
+    pub fn from_payload<T: PacketPayload>(sequence: u32, player_id: u64, payload: &T) -> Self {
+        Self::new(T::MESSAGE_TYPE, sequence, player_id, payload.encode())
+            .with_content_type(T::CONTENT_TYPE)
+    }
+
+    /// Decodifica o payload como `T`, verificando antes o tipo de mensagem.
+    ///
+    /// ## Retorno
+    /// - `Result<T, PacketError>`: O payload decodificado,
+    ///   [`PacketError::UnexpectedMessageType`] se o tipo do pacote não é o de `T`, ou o
+    ///   erro retornado por [`PacketPayload::decode_as`] com a codificação do pacote.
+    pub fn decode_typed<T: PacketPayload>(&self) -> Result<T, PacketError> {
+        if self.header.message_type != T::MESSAGE_TYPE {
+            return Err(PacketError::UnexpectedMessageType {
+                expected: T::MESSAGE_TYPE,
+                found: self.header.message_type,
+            });
+        }
+        T::decode_as(self.content_type(), &self.payload)
+    }
+
+    /// Estima a entropia de Shannon do payload, em bits por byte.
+    ///
+    /// É uma heurística, não um valor exato: apenas os primeiros
+    /// [`ENTROPY_SAMPLE_SIZE`] bytes são considerados, e amostras pequenas tendem a
+    /// subestimar a entropia. Serve para decidir se vale a pena comprimir: dados já
+    /// comprimidos ou cifrados ficam próximos de `8.0`, e texto costuma ficar abaixo
+    /// de `5.0`.
+    ///
+    /// ## Retorno
+    /// - `f32`: Valor entre `0.0` (payload vazio ou com um único valor de byte) e `8.0`.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::Packet;
+    ///
+    /// let repeated = Packet::new(1, 1, 7, vec![0; 1000]);
+    /// assert_eq!(repeated.payload_entropy_estimate(), 0.0);
+    ///
+    /// let uniform: Vec<u8> = (0..4096).map(|i| i as u8).collect();
+    /// let uniform = Packet::new(1, 2, 7, uniform);
+    /// assert!(uniform.payload_entropy_estimate() > 7.9);
+    /// ```
+    pub fn payload_entropy_estimate(&self) -> f32 {
+        let sample = &self.payload[..self.payload.len().min(ENTROPY_SAMPLE_SIZE)];
+        if sample.is_empty() {
+            return 0.0;
+        }
+
+        let mut counts = [0u32; 256];
+        for &byte in sample {
+            counts[byte as usize] += 1;
+        }
+
+        let len = sample.len() as f32;
+        counts
+            .iter()
+            .filter(|&&count| count > 0)
+            .map(|&count| {
+                let probability = count as f32 / len;
+                -probability * probability.log2()
+            })
+            .sum()
+    }
+
+    /// Retorna a codificação do payload indicada no cabeçalho.
+    pub fn content_type(&self) -> ContentType {
+        ContentType::from(self.header.content_type)
+    }
+
+    /// Retorna o pacote com a codificação do payload informada.
+    ///
+    /// O checksum só é recalculado quando o escopo inclui o cabeçalho.
+    ///
+    /// ## Parâmetros
+    /// - `content_type`: Codificação do payload.
+    ///
+    /// ## Retorno
+    /// - `Self`: O pacote com o campo `content_type` atualizado.
+    pub fn with_content_type(mut self, content_type: ContentType) -> Self {
+        self.header.content_type = content_type.into();
+        self.header_edited();
+        self
+    }
+}
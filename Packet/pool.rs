@@ -0,0 +1,218 @@
+//! # Módulo `pool`
+//!
+//! Buffers de capacidade fixa reaproveitados entre envios e recepções.
+//!
+//! [`BufferPool`] mantém buffers alocados uma única vez; [`BufferPool::get`] entrega
+//! um [`PooledBuffer`], que volta ao pool ao ser descartado. Com
+//! [`Packet::write_into`](crate::Packet::write_into) e
+//! [`Packet::to_pooled`](crate::Packet::to_pooled), a serialização escreve
+//! diretamente no buffer, e na recepção o datagrama pode ser lido nele e
+//! interpretado com [`PacketRef`](crate::view::PacketRef), sem copiar o payload.
+//! O pool é compartilhado entre threads; clones de [`BufferPool`] usam os mesmos
+//! buffers.
+//!
+//! ## Exemplos
+//! ```rust
+//! use std::net::UdpSocket;
+//! use packet::Packet;
+//! use packet::pool::BufferPool;
+//! use packet::view::PacketRef;
+//!
+//! let pool = BufferPool::new(1200, 8);
+//! let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+//! let client = UdpSocket::bind("127.0.0.1:0").unwrap();
+//!
+//! let packet = Packet::new(1, 1, 7, vec![1, 2, 3]);
+//! let outgoing = packet.to_pooled(&pool).unwrap();
+//! client.send_to(&outgoing, server.local_addr().unwrap()).unwrap();
+//! drop(outgoing);
+//!
+//! // O mesmo buffer é reaproveitado na recepção.
+//! let mut incoming = pool.get();
+//! let (len, _) = server.recv_from(incoming.as_full_mut()).unwrap();
+//! incoming.set_len(len);
+//! assert_eq!(PacketRef::from_bytes(&incoming).unwrap().payload, [1, 2, 3]);
+//!
+//! drop(incoming);
+//! assert_eq!(pool.available(), 1);
+//! ```
+
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::{Packet, PacketError};
+
+/// Conjunto compartilhado de buffers de mesma capacidade.
+#[derive(Debug, Clone)]
+pub struct BufferPool {
+    /// Estado compartilhado com os buffers entregues.
+    shared: Arc<Shared>,
+}
+
+/// Estado de um [`BufferPool`].
+#[derive(Debug)]
+struct Shared {
+    /// Capacidade de cada buffer, em bytes.
+    buffer_capacity: usize,
+    /// Quantidade máxima de buffers guardados.
+    max_pooled: usize,
+    /// Buffers livres.
+    free: Mutex<Vec<Box<[u8]>>>,
+}
+
+impl Shared {
+    /// Trava a lista de buffers livres; nenhuma operação sob a trava entra em pânico.
+    fn free(&self) -> std::sync::MutexGuard<'_, Vec<Box<[u8]>>> {
+        self.free.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl BufferPool {
+    /// Cria um pool vazio; os buffers são alocados sob demanda e reaproveitados.
+    ///
+    /// ## Parâmetros
+    /// - `buffer_capacity`: Capacidade de cada buffer, em geral o MTU ou
+    ///   [`recommended_recv_buffer_size`](crate::recommended_recv_buffer_size).
+    /// - `max_pooled`: Quantidade máxima de buffers livres guardados; os excedentes
+    ///   são liberados ao voltar ao pool.
+    ///
+    /// ## Retorno
+    /// - `Self`: O pool, sem buffers.
+    pub fn new(buffer_capacity: usize, max_pooled: usize) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                buffer_capacity,
+                max_pooled,
+                free: Mutex::new(Vec::with_capacity(max_pooled)),
+            }),
+        }
+    }
+
+    /// Cria um pool com `count` buffers já alocados.
+    ///
+    /// ## Parâmetros
+    /// - `buffer_capacity`: Capacidade de cada buffer.
+    /// - `count`: Quantidade de buffers alocados agora, que é também o máximo
+    ///   guardado.
+    ///
+    /// ## Retorno
+    /// - `Self`: O pool, com `count` buffers livres.
+    pub fn preallocated(buffer_capacity: usize, count: usize) -> Self {
+        let pool = Self::new(buffer_capacity, count);
+        pool.shared
+            .free()
+            .extend((0..count).map(|_| vec![0; buffer_capacity].into_boxed_slice()));
+        pool
+    }
+
+    /// Retorna a capacidade de cada buffer.
+    pub fn buffer_capacity(&self) -> usize {
+        self.shared.buffer_capacity
+    }
+
+    /// Retorna a quantidade de buffers livres.
+    pub fn available(&self) -> usize {
+        self.shared.free().len()
+    }
+
+    /// Entrega um buffer vazio, reaproveitado ou recém-alocado se não há livres.
+    ///
+    /// ## Retorno
+    /// - `PooledBuffer`: Um buffer de tamanho zero com a capacidade do pool.
+    pub fn get(&self) -> PooledBuffer {
+        let buffer = self.shared.free().pop();
+        PooledBuffer {
+            buffer: buffer
+                .unwrap_or_else(|| vec![0; self.shared.buffer_capacity].into_boxed_slice()),
+            len: 0,
+            pool: Arc::clone(&self.shared),
+        }
+    }
+}
+
+/// Buffer de capacidade fixa emprestado de um [`BufferPool`], ao qual volta ao ser
+/// descartado.
+///
+/// Funciona como uma fatia dos bytes preenchidos, de tamanho `len()`;
+/// [`PooledBuffer::as_full_mut`] dá acesso a toda a capacidade, para escrever antes de
+/// ajustar o tamanho com [`PooledBuffer::set_len`].
+#[derive(Debug)]
+pub struct PooledBuffer {
+    /// Memória do buffer.
+    buffer: Box<[u8]>,
+    /// Quantidade de bytes preenchidos.
+    len: usize,
+    /// Pool de origem.
+    pool: Arc<Shared>,
+}
+
+impl PooledBuffer {
+    /// Retorna a capacidade do buffer.
+    pub fn capacity(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Define quantos bytes do início do buffer estão preenchidos.
+    ///
+    /// ## Pânico
+    /// Entra em pânico se `len` excede a capacidade.
+    pub fn set_len(&mut self, len: usize) {
+        assert!(
+            len <= self.capacity(),
+            "tamanho além da capacidade do buffer"
+        );
+        self.len = len;
+    }
+
+    /// Retorna toda a capacidade do buffer, independentemente do tamanho preenchido.
+    pub fn as_full_mut(&mut self) -> &mut [u8] {
+        &mut self.buffer
+    }
+}
+
+impl Deref for PooledBuffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.buffer[..self.len]
+    }
+}
+
+impl DerefMut for PooledBuffer {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        &mut self.buffer[..self.len]
+    }
+}
+
+impl Drop for PooledBuffer {
+    fn drop(&mut self) {
+        let mut free = self.pool.free();
+        if free.len() < self.pool.max_pooled {
+            free.push(std::mem::take(&mut self.buffer));
+        }
+    }
+}
+
+impl Packet {
+    /// Serializa o pacote em um buffer de um [`BufferPool`].
+    ///
+    /// ## Parâmetros
+    /// - `pool`: Pool de onde o buffer é obtido.
+    ///
+    /// ## Retorno
+    /// - `Result<PooledBuffer, PacketError>`: O buffer com o pacote serializado, ou
+    ///   [`PacketError::PacketTooLarge`] se o pacote não cabe na capacidade do pool.
+    pub fn to_pooled(&self, pool: &BufferPool) -> Result<PooledBuffer, PacketError> {
+        let size = self.serialized_len();
+        if size > pool.buffer_capacity() {
+            return Err(PacketError::PacketTooLarge {
+                size,
+                limit: pool.buffer_capacity(),
+            });
+        }
+        let mut buffer = pool.get();
+        let len = self.write_into(buffer.as_full_mut());
+        buffer.set_len(len);
+        Ok(buffer)
+    }
+}
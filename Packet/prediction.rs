@@ -0,0 +1,226 @@
+//! # Módulo `prediction`
+//!
+//! Predição no cliente: armazenamento das entradas locais e reconciliação com o
+//! estado autoritativo do servidor.
+//!
+//! O cliente aplica cada entrada imediatamente ao seu estado local, sem esperar o
+//! servidor, e a guarda em um [`InputBuffer`] com o tick a que se refere. Cada pacote
+//! enviado leva as [`DEFAULT_INPUT_REDUNDANCY`] entradas mais recentes, de modo que
+//! uma entrada só se perde se todos os pacotes que a levam se perdem. Quando chega o
+//! estado autoritativo de um tick, [`InputBuffer::reconcile`] descarta as entradas
+//! já processadas pelo servidor e reaplica as seguintes sobre esse estado com a
+//! função de simulação do jogo, produzindo o novo estado previsto.
+//!
+//! ## Exemplos
+//! ```rust
+//! use packet::input::InputFrame;
+//! use packet::prediction::InputBuffer;
+//!
+//! // A simulação move o jogador uma unidade por tick com o botão 0 pressionado.
+//! fn simulate(position: &mut i32, input: &InputFrame) {
+//!     if input.is_pressed(0) {
+//!         *position += 1;
+//!     }
+//! }
+//!
+//! let mut buffer = InputBuffer::new(64);
+//! let mut predicted = 0;
+//! for tick in 1..=5 {
+//!     let input = InputFrame { buttons: InputFrame::button(0), ..InputFrame::default() };
+//!     simulate(&mut predicted, &input);
+//!     buffer.push(tick, input);
+//! }
+//! assert_eq!(predicted, 5);
+//!
+//! // Cada pacote leva as três entradas mais recentes.
+//! let packet = buffer.to_packet(7, 5);
+//! let ticks: Vec<_> = packet.decode_input_history().unwrap().iter().map(|f| f.tick).collect();
+//! assert_eq!(ticks, [3, 4, 5]);
+//!
+//! // O servidor processou até o tick 3, mas um obstáculo deixou o jogador em 2.
+//! predicted = buffer.reconcile(3, 2, simulate);
+//! assert_eq!(predicted, 4);
+//! assert_eq!(buffer.len(), 2);
+//! ```
+
+use std::collections::VecDeque;
+
+use crate::input::InputFrame;
+use crate::sequence::sequence_greater_than;
+use crate::Packet;
+
+/// Quantidade padrão de entradas recentes repetidas em cada pacote.
+pub const DEFAULT_INPUT_REDUNDANCY: usize = 3;
+
+/// Entradas locais ainda não confirmadas pelo servidor, indexadas pelo tick.
+///
+/// Os ticks são comparados de forma circular, como as sequências, e devem ser
+/// registrados em ordem crescente.
+#[derive(Debug, Clone)]
+pub struct InputBuffer<I = InputFrame> {
+    /// Entradas, da mais antiga para a mais recente.
+    inputs: VecDeque<(u32, I)>,
+    /// Quantidade máxima de entradas guardadas.
+    capacity: usize,
+    /// Quantidade de entradas recentes repetidas em cada pacote.
+    redundancy: usize,
+}
+
+impl<I> InputBuffer<I> {
+    /// Cria um buffer vazio, com [`DEFAULT_INPUT_REDUNDANCY`].
+    ///
+    /// ## Parâmetros
+    /// - `capacity`: Quantidade máxima de entradas guardadas; a mais antiga é
+    ///   descartada quando o buffer está cheio. Deve cobrir os ticks de um RTT.
+    ///
+    /// ## Retorno
+    /// - `Self`: O buffer.
+    ///
+    /// ## Pânico
+    /// Se `capacity` é zero.
+    pub fn new(capacity: usize) -> Self {
+        assert!(
+            capacity > 0,
+            "a capacidade do buffer de entradas deve ser positiva"
+        );
+        Self {
+            inputs: VecDeque::with_capacity(capacity),
+            capacity,
+            redundancy: DEFAULT_INPUT_REDUNDANCY,
+        }
+    }
+
+    /// Define quantas entradas recentes são repetidas em cada pacote.
+    ///
+    /// ## Parâmetros
+    /// - `redundancy`: Quantidade de entradas; `1` desativa a redundância.
+    ///
+    /// ## Retorno
+    /// - `Self`: O buffer com a nova redundância.
+    pub fn with_redundancy(mut self, redundancy: usize) -> Self {
+        self.redundancy = redundancy.max(1);
+        self
+    }
+
+    /// Retorna a quantidade de entradas guardadas.
+    pub fn len(&self) -> usize {
+        self.inputs.len()
+    }
+
+    /// Indica se não há entradas guardadas.
+    pub fn is_empty(&self) -> bool {
+        self.inputs.is_empty()
+    }
+
+    /// Retorna o tick da entrada mais recente, se houver.
+    pub fn newest_tick(&self) -> Option<u32> {
+        self.inputs.back().map(|&(tick, _)| tick)
+    }
+
+    /// Registra a entrada local de um tick.
+    ///
+    /// ## Parâmetros
+    /// - `tick`: Tick a que a entrada se aplica, posterior ao de todas as entradas
+    ///   guardadas.
+    /// - `input`: Entrada aplicada localmente.
+    ///
+    /// ## Retorno
+    /// - `bool`: `true` se a entrada foi guardada; `false` se `tick` não é posterior
+    ///   ao da entrada mais recente.
+    pub fn push(&mut self, tick: u32, input: I) -> bool {
+        if self
+            .newest_tick()
+            .is_some_and(|newest| !sequence_greater_than(tick, newest))
+        {
+            return false;
+        }
+        if self.inputs.len() == self.capacity {
+            self.inputs.pop_front();
+        }
+        self.inputs.push_back((tick, input));
+        true
+    }
+
+    /// Retorna a entrada de um tick, se ainda está guardada.
+    pub fn get(&self, tick: u32) -> Option<&I> {
+        self.inputs
+            .iter()
+            .find(|&&(stored, _)| stored == tick)
+            .map(|(_, input)| input)
+    }
+
+    /// Retorna as entradas a repetir no próximo pacote.
+    ///
+    /// ## Retorno
+    /// - `impl Iterator<Item = (u32, &I)>`: Até a redundância configurada de entradas
+    ///   mais recentes, com seus ticks, da mais antiga para a mais recente.
+    pub fn recent(&self) -> impl Iterator<Item = (u32, &I)> {
+        let skip = self.inputs.len().saturating_sub(self.redundancy);
+        self.inputs
+            .iter()
+            .skip(skip)
+            .map(|(tick, input)| (*tick, input))
+    }
+
+    /// Descarta as entradas até um tick, inclusive, já processadas pelo servidor.
+    ///
+    /// ## Parâmetros
+    /// - `tick`: Último tick processado pelo servidor.
+    pub fn acknowledge(&mut self, tick: u32) {
+        while self
+            .inputs
+            .front()
+            .is_some_and(|&(stored, _)| !sequence_greater_than(stored, tick))
+        {
+            self.inputs.pop_front();
+        }
+    }
+
+    /// Reconcilia a predição com o estado autoritativo de um tick.
+    ///
+    /// Descarta as entradas até `tick`, inclusive, e reaplica as seguintes, em ordem,
+    /// sobre `state`. As entradas reaplicadas continuam guardadas até serem
+    /// confirmadas por um estado posterior.
+    ///
+    /// ## Parâmetros
+    /// - `tick`: Tick do estado autoritativo.
+    /// - `state`: Estado autoritativo recebido do servidor.
+    /// - `simulate`: Função de simulação do jogo, que avança o estado em um tick com
+    ///   a entrada informada.
+    ///
+    /// ## Retorno
+    /// - `S`: O estado previsto para o tick da entrada mais recente.
+    pub fn reconcile<S>(
+        &mut self,
+        tick: u32,
+        mut state: S,
+        mut simulate: impl FnMut(&mut S, &I),
+    ) -> S {
+        self.acknowledge(tick);
+        for (_, input) in &self.inputs {
+            simulate(&mut state, input);
+        }
+        state
+    }
+}
+
+impl InputBuffer<InputFrame> {
+    /// Cria o pacote [`MessageType::INPUT_HISTORY`](crate::message::MessageType::INPUT_HISTORY)
+    /// com as entradas de [`InputBuffer::recent`].
+    ///
+    /// O tick de cada entrada enviada é o tick com que ela foi registrada.
+    ///
+    /// ## Parâmetros
+    /// - `player_id`: Identificador único do jogador.
+    /// - `sequence`: Sequência do pacote.
+    ///
+    /// ## Retorno
+    /// - `Packet`: O pacote com as entradas recentes.
+    pub fn to_packet(&self, player_id: u64, sequence: u32) -> Packet {
+        let frames: Vec<_> = self
+            .recent()
+            .map(|(tick, frame)| InputFrame { tick, ..*frame })
+            .collect();
+        Packet::input_history(player_id, sequence, &frames)
+    }
+}
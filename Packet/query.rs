@@ -0,0 +1,381 @@
+//! # Módulo `query`
+//!
+//! Consulta ao estado de um servidor sem conexão, para listas de servidores e
+//! ferramentas de monitoramento.
+//!
+//! A consulta é um único pacote [`MessageType::Query`] com o payload vazio e uma
+//! sequência que varia a cada chamada; a resposta repete a sequência e leva o
+//! [`ServerStatus`]: jogadores conectados, mapa, versão e pares chave/valor próprios da
+//! aplicação. Nenhum dos lados guarda estado de sessão, e a resposta sempre cabe em um
+//! único datagrama sem fragmentação, de no máximo [`DEFAULT_MTU`] bytes; um estado
+//! maior que [`MAX_STATUS_SIZE`] é recusado ao ser definido.
+//!
+//! Um cliente chama [`query_server`], que reenvia a consulta a cada
+//! [`QUERY_RETRY_INTERVAL`] até a resposta ou o tempo limite. No servidor, um
+//! [`QueryResponder`] responde às consultas limitando as respostas por origem, com
+//! [`DEFAULT_QUERY_LIMIT`], para que o servidor não sirva de amplificador; o
+//! [`Server`](crate::connection::Server) usa um quando recebe
+//! [`Server::with_query_status`](crate::connection::Server::with_query_status).
+//!
+//! ## Exemplos
+//! ```rust
+//! use std::time::{Duration, Instant};
+//! use packet::Packet;
+//! use packet::connection::ProtocolVersion;
+//! use packet::message::MessageType;
+//! use packet::query::{QueryResponder, ServerStatus};
+//!
+//! let status = ServerStatus::new("deserto", ProtocolVersion::new(1, 2))
+//!     .with_players(3, 16)
+//!     .with_value("modo", "captura");
+//! let mut responder = QueryResponder::new(status.clone()).unwrap();
+//!
+//! let from = "127.0.0.1:5000".parse().unwrap();
+//! let query = Packet::new(MessageType::Query.into(), 42, 0, Vec::new());
+//! let reply = responder.respond(from, &query, Instant::now()).unwrap();
+//! assert_eq!(reply.header.sequence, 42);
+//! assert_eq!(reply.decode_typed::<ServerStatus>().unwrap(), status);
+//! ```
+
+use std::collections::hash_map::RandomState;
+use std::collections::BTreeMap;
+use std::hash::BuildHasher;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::{Duration, Instant};
+
+use crate::message::{MessageType, ProtocolVersion};
+use crate::payload::PacketPayload;
+use crate::ratelimit::{FloodGuard, RateLimit, Verdict, DEFAULT_BAN_DURATION};
+use crate::transport::UdpTransport;
+use crate::{Packet, PacketError, PacketHeader, DEFAULT_MTU};
+
+/// Tamanho máximo, em bytes, do [`ServerStatus`] codificado, para que a resposta caiba
+/// em um datagrama de [`DEFAULT_MTU`] bytes.
+pub const MAX_STATUS_SIZE: usize = DEFAULT_MTU - PacketHeader::SIZE;
+
+/// Intervalo entre as consultas reenviadas por [`query_server`], para compensar perdas.
+pub const QUERY_RETRY_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Limite padrão de respostas de um [`QueryResponder`] a cada origem, medido nos bytes
+/// das respostas.
+pub const DEFAULT_QUERY_LIMIT: RateLimit = RateLimit::new(5, 8 * 1024);
+
+/// Estado de um servidor, respondido às consultas.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ServerStatus {
+    /// Jogadores conectados.
+    pub players: u32,
+    /// Máximo de jogadores aceitos.
+    pub max_players: u32,
+    /// Mapa em jogo.
+    pub map: String,
+    /// Versão do protocolo da aplicação.
+    pub version: ProtocolVersion,
+    /// Pares chave/valor próprios da aplicação, como o modo de jogo.
+    pub values: BTreeMap<String, String>,
+}
+
+impl ServerStatus {
+    /// Cria o estado de um servidor vazio.
+    ///
+    /// ## Parâmetros
+    /// - `map`: Mapa em jogo.
+    /// - `version`: Versão do protocolo da aplicação.
+    ///
+    /// ## Retorno
+    /// - `Self`: O estado, com zero jogadores de um máximo de zero e sem pares.
+    pub fn new(map: impl Into<String>, version: ProtocolVersion) -> Self {
+        Self {
+            map: map.into(),
+            version,
+            ..Self::default()
+        }
+    }
+
+    /// Define a ocupação do servidor.
+    ///
+    /// ## Parâmetros
+    /// - `players`: Jogadores conectados.
+    /// - `max_players`: Máximo de jogadores aceitos.
+    ///
+    /// ## Retorno
+    /// - `Self`: O estado com a nova ocupação.
+    pub fn with_players(mut self, players: u32, max_players: u32) -> Self {
+        self.players = players;
+        self.max_players = max_players;
+        self
+    }
+
+    /// Acrescenta ou substitui um par chave/valor.
+    ///
+    /// ## Parâmetros
+    /// - `key`: Chave do par.
+    /// - `value`: Valor associado.
+    ///
+    /// ## Retorno
+    /// - `Self`: O estado com o par.
+    pub fn with_value(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.values.insert(key.into(), value.into());
+        self
+    }
+
+    /// Retorna o tamanho do estado codificado, em bytes.
+    pub fn encoded_len(&self) -> usize {
+        let strings = self
+            .values
+            .iter()
+            .map(|(key, value)| 4 + key.len() + value.len());
+        14 + self.map.len() + 2 + strings.sum::<usize>()
+    }
+
+    /// Verifica se a resposta com este estado cabe em um único datagrama.
+    ///
+    /// ## Retorno
+    /// - `Result<(), PacketError>`: `Ok(())`, ou [`PacketError::PayloadTooLarge`] se o
+    ///   estado codificado excede [`MAX_STATUS_SIZE`].
+    pub fn validate(&self) -> Result<(), PacketError> {
+        let size = self.encoded_len();
+        if size > MAX_STATUS_SIZE {
+            return Err(PacketError::PayloadTooLarge {
+                size,
+                limit: MAX_STATUS_SIZE,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Grava um texto precedido do seu tamanho em 16 bits.
+fn put_str(payload: &mut Vec<u8>, text: &str) {
+    payload.extend_from_slice(&(text.len() as u16).to_le_bytes());
+    payload.extend_from_slice(text.as_bytes());
+}
+
+/// Lê um texto gravado por [`put_str`], avançando `rest`.
+fn take_str(rest: &mut &[u8]) -> Option<String> {
+    let len = u16::from_le_bytes(rest.get(..2)?.try_into().ok()?) as usize;
+    let text = rest.get(2..2 + len)?;
+    *rest = &rest[2 + len..];
+    String::from_utf8(text.to_vec()).ok()
+}
+
+impl PacketPayload for ServerStatus {
+    const MESSAGE_TYPE: u8 = 8;
+
+    /// Grava `players` e `max_players` em 32 bits, a versão em dois campos de 16 bits,
+    /// o mapa e a quantidade de pares, seguida de cada chave e valor; os textos são
+    /// precedidos do tamanho em 16 bits, e os inteiros em little-endian.
+    fn encode(&self) -> Vec<u8> {
+        let mut payload = Vec::with_capacity(self.encoded_len());
+        payload.extend_from_slice(&self.players.to_le_bytes());
+        payload.extend_from_slice(&self.max_players.to_le_bytes());
+        payload.extend_from_slice(&self.version.major.to_le_bytes());
+        payload.extend_from_slice(&self.version.minor.to_le_bytes());
+        put_str(&mut payload, &self.map);
+        payload.extend_from_slice(&(self.values.len() as u16).to_le_bytes());
+        for (key, value) in &self.values {
+            put_str(&mut payload, key);
+            put_str(&mut payload, value);
+        }
+        payload
+    }
+
+    fn decode(payload: &[u8]) -> Result<Self, PacketError> {
+        let invalid = PacketError::InvalidPayload {
+            reason: "estado de servidor malformado",
+        };
+        let word = |offset: usize| -> Option<u32> {
+            Some(u32::from_le_bytes(
+                payload.get(offset..offset + 4)?.try_into().ok()?,
+            ))
+        };
+        let half = |offset: usize| -> Option<u16> {
+            Some(u16::from_le_bytes(
+                payload.get(offset..offset + 2)?.try_into().ok()?,
+            ))
+        };
+        let decoded = (|| {
+            let mut status = Self {
+                players: word(0)?,
+                max_players: word(4)?,
+                version: ProtocolVersion::new(half(8)?, half(10)?),
+                ..Self::default()
+            };
+            let mut rest = &payload[12..];
+            status.map = take_str(&mut rest)?;
+            let count = u16::from_le_bytes(rest.get(..2)?.try_into().ok()?);
+            rest = &rest[2..];
+            for _ in 0..count {
+                let key = take_str(&mut rest)?;
+                let value = take_str(&mut rest)?;
+                status.values.insert(key, value);
+            }
+            rest.is_empty().then_some(status)
+        })();
+        decoded.ok_or(invalid)
+    }
+}
+
+/// Responde às consultas de estado, limitando as respostas por origem.
+#[derive(Debug, Clone)]
+pub struct QueryResponder {
+    /// Estado respondido.
+    status: ServerStatus,
+    /// Limitação das respostas por origem, sem banimentos.
+    guard: FloodGuard,
+}
+
+impl QueryResponder {
+    /// Cria um responder com [`DEFAULT_QUERY_LIMIT`].
+    ///
+    /// ## Parâmetros
+    /// - `status`: Estado respondido.
+    ///
+    /// ## Retorno
+    /// - `Result<Self, PacketError>`: O responder, ou [`PacketError::PayloadTooLarge`]
+    ///   se o estado não cabe em um único datagrama.
+    pub fn new(status: ServerStatus) -> Result<Self, PacketError> {
+        status.validate()?;
+        let guard = FloodGuard::new()
+            .with_unconnected_limit(DEFAULT_QUERY_LIMIT)
+            .with_bans(0, DEFAULT_BAN_DURATION);
+        Ok(Self { status, guard })
+    }
+
+    /// Define o limite de respostas a cada origem.
+    ///
+    /// ## Parâmetros
+    /// - `limit`: Respostas e bytes de resposta por segundo a cada origem.
+    ///
+    /// ## Retorno
+    /// - `Self`: O responder com o novo limite.
+    pub fn with_limit(mut self, limit: RateLimit) -> Self {
+        self.guard = self.guard.with_unconnected_limit(limit);
+        self
+    }
+
+    /// Retorna o estado respondido.
+    pub fn status(&self) -> &ServerStatus {
+        &self.status
+    }
+
+    /// Substitui o estado respondido; as próximas respostas já levam o novo estado.
+    ///
+    /// ## Parâmetros
+    /// - `status`: Novo estado.
+    ///
+    /// ## Retorno
+    /// - `Result<(), PacketError>`: `Ok(())`, ou [`PacketError::PayloadTooLarge`] se o
+    ///   estado não cabe em um único datagrama; nesse caso o anterior é mantido.
+    pub fn set_status(&mut self, status: ServerStatus) -> Result<(), PacketError> {
+        status.validate()?;
+        self.status = status;
+        Ok(())
+    }
+
+    /// Atualiza só a quantidade de jogadores conectados, sem mudar o tamanho do estado.
+    pub(crate) fn set_players(&mut self, players: u32) {
+        self.status.players = players;
+    }
+
+    /// Monta a resposta a um pacote recebido, se ele é uma consulta.
+    ///
+    /// ## Parâmetros
+    /// - `from`: Origem do pacote.
+    /// - `query`: Pacote recebido.
+    /// - `now`: Instante da recepção.
+    ///
+    /// ## Retorno
+    /// - `Option<Packet>`: A resposta, com a sequência da consulta, ou `None` se o
+    ///   pacote não é uma consulta ou a origem excedeu o limite de respostas.
+    pub fn respond(&mut self, from: SocketAddr, query: &Packet, now: Instant) -> Option<Packet> {
+        if query.message_type() != Ok(MessageType::Query) || !query.payload.is_empty() {
+            return None;
+        }
+        let reply = Packet::from_payload(query.header.sequence, 0, &self.status);
+        let verdict = self.guard.check(from, reply.serialized_len(), false, now);
+        (verdict == Verdict::Allowed).then_some(reply)
+    }
+}
+
+/// Consulta o estado de um servidor.
+///
+/// Bloqueia até a resposta ou o tempo limite, reenviando a consulta a cada
+/// [`QUERY_RETRY_INTERVAL`]. Respostas de outros endereços ou de consultas anteriores
+/// são descartadas.
+///
+/// ## Parâmetros
+/// - `addr`: Endereço do servidor.
+/// - `timeout`: Tempo máximo de espera pela resposta.
+///
+/// ## Retorno
+/// - `Result<ServerStatus, PacketError>`: O estado do servidor, ou [`PacketError::Io`]
+///   com [`io::ErrorKind::TimedOut`] se não houve resposta, ou com o erro do sistema.
+///
+/// ## Exemplos
+/// ```rust
+/// use std::time::{Duration, Instant};
+/// use packet::connection::{ProtocolVersion, Server};
+/// use packet::query::{query_server, ServerStatus};
+///
+/// let status = ServerStatus::new("porto", ProtocolVersion::new(1, 0)).with_players(0, 8);
+/// let mut server = Server::listen("127.0.0.1:0")
+///     .unwrap()
+///     .with_query_status(status.clone())
+///     .unwrap();
+/// let addr = server.local_addr().unwrap();
+/// let handle = std::thread::spawn(move || query_server(addr, Duration::from_secs(2)));
+///
+/// while !handle.is_finished() {
+///     server.poll(Instant::now()).unwrap();
+///     std::thread::sleep(Duration::from_millis(1));
+/// }
+/// assert_eq!(handle.join().unwrap().unwrap(), status);
+/// ```
+pub fn query_server(addr: SocketAddr, timeout: Duration) -> Result<ServerStatus, PacketError> {
+    let local: IpAddr = match addr {
+        SocketAddr::V4(_) => Ipv4Addr::UNSPECIFIED.into(),
+        SocketAddr::V6(_) => Ipv6Addr::UNSPECIFIED.into(),
+    };
+    let mut transport = UdpTransport::bind((local, 0))?;
+    let nonce = RandomState::new().hash_one(Instant::now()) as u32;
+    let query = Packet::new(MessageType::Query.into(), nonce, 0, Vec::new());
+
+    let deadline = Instant::now() + timeout;
+    let mut next_query = Instant::now();
+    loop {
+        let now = Instant::now();
+        if now >= deadline {
+            return Err(io::Error::from(io::ErrorKind::TimedOut).into());
+        }
+        if now >= next_query {
+            transport.send_packet(addr, &query)?;
+            next_query = now + QUERY_RETRY_INTERVAL;
+        }
+
+        let wait = deadline.min(next_query).saturating_duration_since(now);
+        transport
+            .get_ref()
+            .set_read_timeout(Some(wait.max(Duration::from_millis(1))))?;
+        let (from, reply) = match transport.recv_packet() {
+            Ok(received) => received,
+            Err(PacketError::Io(error))
+                if matches!(
+                    error.kind(),
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                ) =>
+            {
+                continue;
+            }
+            Err(error @ PacketError::Io(_)) => return Err(error),
+            Err(_) => continue,
+        };
+        if from != addr || reply.header.sequence != nonce {
+            continue;
+        }
+        if let Ok(status) = reply.decode_typed::<ServerStatus>() {
+            return Ok(status);
+        }
+    }
+}
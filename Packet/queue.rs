@@ -0,0 +1,514 @@
+//! # Módulo `queue`
+//!
+//! Fila de recepção com capacidade limitada, usada como mecanismo de contenção
+//! quando o servidor não consegue processar os pacotes na velocidade em que chegam,
+//! fila de envio com escalonamento justo entre jogadores e escalonador de envio por
+//! prioridade entre canais, limitado ao orçamento de banda de cada tick.
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use crate::Packet;
+
+/// Política aplicada quando um pacote chega a uma fila cheia.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DropPolicy {
+    /// Descarta o pacote mais antigo da fila.
+    DropOldest,
+    /// Descarta o pacote que acabou de chegar.
+    DropNewest,
+    /// Descarta o pacote de menor `priority`; em caso de empate, o pacote que chegou
+    /// é descartado antes dos que já estavam na fila.
+    DropLowestPriority,
+}
+
+/// Fila FIFO de pacotes com capacidade máxima e política de descarte.
+///
+/// ## Exemplos
+/// ```rust
+/// use packet::Packet;
+/// use packet::queue::{BoundedQueue, DropPolicy};
+///
+/// let mut queue = BoundedQueue::new(2, DropPolicy::DropLowestPriority);
+/// queue.push(Packet::new(1, 1, 7, vec![]).with_priority(5));
+/// queue.push(Packet::new(1, 2, 7, vec![]).with_priority(1));
+///
+/// let dropped = queue.push(Packet::new(1, 3, 7, vec![]).with_priority(3)).unwrap();
+/// assert_eq!(dropped.header.sequence, 2);
+/// assert_eq!(queue.dropped(), 1);
+///
+/// assert_eq!(queue.pop().unwrap().header.sequence, 1);
+/// assert_eq!(queue.pop().unwrap().header.sequence, 3);
+/// ```
+#[derive(Debug)]
+pub struct BoundedQueue {
+    /// Quantidade máxima de pacotes na fila.
+    capacity: usize,
+    /// Política aplicada quando a fila está cheia.
+    policy: DropPolicy,
+    /// Pacotes na ordem de chegada.
+    packets: VecDeque<Packet>,
+    /// Total de pacotes descartados desde a criação.
+    dropped: u64,
+}
+
+impl BoundedQueue {
+    /// Cria uma fila vazia.
+    ///
+    /// ## Parâmetros
+    /// - `capacity`: Quantidade máxima de pacotes na fila.
+    /// - `policy`: Política aplicada quando a fila está cheia.
+    ///
+    /// ## Retorno
+    /// - `Self`: Uma fila vazia.
+    pub fn new(capacity: usize, policy: DropPolicy) -> Self {
+        Self {
+            capacity,
+            policy,
+            packets: VecDeque::with_capacity(capacity),
+            dropped: 0,
+        }
+    }
+
+    /// Insere um pacote no fim da fila, aplicando a política de descarte se ela estiver cheia.
+    ///
+    /// ## Parâmetros
+    /// - `packet`: Pacote recebido.
+    ///
+    /// ## Retorno
+    /// - `Option<Packet>`: O pacote descartado, se algum foi descartado.
+    pub fn push(&mut self, packet: Packet) -> Option<Packet> {
+        if self.packets.len() < self.capacity {
+            self.packets.push_back(packet);
+            return None;
+        }
+
+        self.dropped += 1;
+        let dropped = match self.policy {
+            DropPolicy::DropNewest => packet,
+            DropPolicy::DropOldest => self.evict(0, packet),
+            DropPolicy::DropLowestPriority => {
+                let lowest = self
+                    .packets
+                    .iter()
+                    .enumerate()
+                    .min_by_key(|(_, queued)| queued.header.priority)
+                    .map(|(index, queued)| (index, queued.header.priority));
+
+                match lowest {
+                    Some((index, priority)) if priority < packet.header.priority => {
+                        self.evict(index, packet)
+                    }
+                    _ => packet,
+                }
+            }
+        };
+        Some(dropped)
+    }
+
+    /// Remove o pacote mais antigo da fila.
+    ///
+    /// ## Retorno
+    /// - `Option<Packet>`: O pacote removido, ou `None` se a fila está vazia.
+    pub fn pop(&mut self) -> Option<Packet> {
+        self.packets.pop_front()
+    }
+
+    /// Retorna a quantidade de pacotes na fila.
+    pub fn len(&self) -> usize {
+        self.packets.len()
+    }
+
+    /// Indica se a fila está vazia.
+    pub fn is_empty(&self) -> bool {
+        self.packets.is_empty()
+    }
+
+    /// Retorna a capacidade máxima da fila.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Retorna o total de pacotes descartados desde a criação da fila.
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Remove o pacote na posição `index` e insere `packet` no fim da fila.
+    fn evict(&mut self, index: usize, packet: Packet) -> Packet {
+        let Some(evicted) = self.packets.remove(index) else {
+            return packet;
+        };
+        self.packets.push_back(packet);
+        evicted
+    }
+}
+
+/// Fila de envio que alterna entre jogadores, impedindo que um jogador com muitos
+/// pacotes atrase os demais.
+///
+/// Cada `player_id` tem sua própria subfila FIFO, e [`FairQueue::pop`] atende os
+/// jogadores com pacotes pendentes em rodízio. O peso de um jogador
+/// ([`FairQueue::set_weight`]) define quantos pacotes ele envia em cada vez; por
+/// padrão, todos têm peso `1`.
+///
+/// ## Exemplos
+/// ```rust
+/// use packet::Packet;
+/// use packet::queue::FairQueue;
+///
+/// let mut queue = FairQueue::new();
+/// for sequence in 1..=3 {
+///     queue.push(Packet::new(1, sequence, 7, vec![]));
+/// }
+/// queue.push(Packet::new(1, 10, 8, vec![]));
+///
+/// let order: Vec<_> = std::iter::from_fn(|| queue.pop())
+///     .map(|p| (p.header.player_id, p.header.sequence))
+///     .collect();
+/// assert_eq!(order, [(7, 1), (8, 10), (7, 2), (7, 3)]);
+/// ```
+///
+/// Com pesos, um jogador envia proporcionalmente mais:
+/// ```rust
+/// use packet::Packet;
+/// use packet::queue::FairQueue;
+///
+/// let mut queue = FairQueue::new();
+/// queue.set_weight(7, 2);
+/// for sequence in 1..=4 {
+///     queue.push(Packet::new(1, sequence, 7, vec![]));
+///     queue.push(Packet::new(1, sequence, 8, vec![]));
+/// }
+///
+/// let players: Vec<_> = std::iter::from_fn(|| queue.pop())
+///     .take(6)
+///     .map(|p| p.header.player_id)
+///     .collect();
+/// assert_eq!(players, [7, 7, 8, 7, 7, 8]);
+/// ```
+#[derive(Debug, Default)]
+pub struct FairQueue {
+    /// Subfilas não vazias, indexadas pelo `player_id`.
+    queues: HashMap<u64, VecDeque<Packet>>,
+    /// Jogadores com pacotes pendentes, na ordem do rodízio; o primeiro está sendo atendido.
+    active: VecDeque<u64>,
+    /// Pesos diferentes de `1`.
+    weights: HashMap<u64, u32>,
+    /// Pacotes já enviados pelo jogador atendido na vez atual.
+    served: u32,
+    /// Total de pacotes em todas as subfilas.
+    len: usize,
+}
+
+impl FairQueue {
+    /// Cria uma fila vazia.
+    ///
+    /// ## Retorno
+    /// - `Self`: Uma fila sem jogadores.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Define quantos pacotes o jogador envia em cada vez do rodízio.
+    ///
+    /// ## Parâmetros
+    /// - `player_id`: Jogador afetado.
+    /// - `weight`: Pacotes por vez; `0` é tratado como `1`.
+    pub fn set_weight(&mut self, player_id: u64, weight: u32) {
+        if weight <= 1 {
+            self.weights.remove(&player_id);
+        } else {
+            self.weights.insert(player_id, weight);
+        }
+    }
+
+    /// Insere um pacote no fim da subfila do jogador indicado em seu cabeçalho.
+    ///
+    /// ## Parâmetros
+    /// - `packet`: Pacote a ser enviado.
+    pub fn push(&mut self, packet: Packet) {
+        let player_id = packet.header.player_id;
+        let queue = self.queues.entry(player_id).or_default();
+        if queue.is_empty() {
+            self.active.push_back(player_id);
+        }
+        queue.push_back(packet);
+        self.len += 1;
+    }
+
+    /// Remove o próximo pacote na ordem do rodízio.
+    ///
+    /// ## Retorno
+    /// - `Option<Packet>`: O pacote removido, ou `None` se a fila está vazia.
+    pub fn pop(&mut self) -> Option<Packet> {
+        let player_id = *self.active.front()?;
+        let queue = self.queues.get_mut(&player_id)?;
+        let packet = queue.pop_front()?;
+        self.len -= 1;
+        self.served += 1;
+
+        if queue.is_empty() {
+            self.queues.remove(&player_id);
+            self.active.pop_front();
+            self.served = 0;
+        } else if self.served >= self.weight(player_id) {
+            self.active.rotate_left(1);
+            self.served = 0;
+        }
+        Some(packet)
+    }
+
+    /// Retorna a quantidade de pacotes pendentes de um jogador.
+    pub fn player_len(&self, player_id: u64) -> usize {
+        self.queues.get(&player_id).map_or(0, VecDeque::len)
+    }
+
+    /// Retorna a quantidade total de pacotes na fila.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Indica se a fila está vazia.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Retorna o peso do jogador.
+    fn weight(&self, player_id: u64) -> u32 {
+        self.weights.get(&player_id).copied().unwrap_or(1)
+    }
+}
+
+/// Ticks seguidos que um canal com pacotes pendentes passa sem enviar antes de ser
+/// atendido à frente dos demais, por padrão.
+pub const DEFAULT_STARVATION_TICKS: u32 = 8;
+
+/// Subfila de um canal no [`PriorityScheduler`].
+#[derive(Debug, Default)]
+struct ChannelQueue {
+    /// Pacotes do canal, na ordem de chegada.
+    packets: VecDeque<Packet>,
+    /// Ticks seguidos com pacotes pendentes e nenhum enviado.
+    waiting: u32,
+    /// Indica se o canal enviou algum pacote no tick atual.
+    served: bool,
+}
+
+/// Escalonador de envio que atende os canais em ordem de prioridade, dentro do
+/// orçamento de bytes de cada tick.
+///
+/// Cada `channel_id` tem sua própria subfila FIFO, cuja prioridade é o `priority` do
+/// pacote à frente dela, gravado por
+/// [`Channel::with_priority`](crate::channel::Channel::with_priority) ou por
+/// [`Packet::with_priority`]. [`PriorityScheduler::tick`] libera primeiro os pacotes
+/// dos canais de maior prioridade, enquanto houver orçamento; em caso de empate, o
+/// canal de menor identificador vai antes. Um pacote é liberado sempre que
+/// ainda há orçamento, e o excesso é descontado do tick seguinte, como em
+/// [`BandwidthBudget`](crate::congestion::BandwidthBudget).
+///
+/// Para que os canais de baixa prioridade não fiquem parados indefinidamente, um canal
+/// que passa [`DEFAULT_STARVATION_TICKS`] ticks seguidos com pacotes pendentes e sem
+/// enviar nenhum é atendido, com um pacote, antes dos demais no tick seguinte.
+///
+/// ## Exemplos
+/// ```rust
+/// use std::time::{Duration, Instant};
+/// use packet::Packet;
+/// use packet::channel::{ChannelSet, DeliveryMode};
+/// use packet::queue::PriorityScheduler;
+///
+/// const VOICE: u8 = 0;
+/// const BULK: u8 = 1;
+///
+/// let mut channels = ChannelSet::new(Duration::from_millis(100))
+///     .with_channel_priority(VOICE, DeliveryMode::Unreliable, 200)
+///     .with_channel(BULK, DeliveryMode::ReliableOrdered);
+/// let mut scheduler = PriorityScheduler::new();
+/// let now = Instant::now();
+///
+/// for _ in 0..3 {
+///     let packet = Packet::new(1, 0, 7, vec![0; 100]);
+///     scheduler.push(channels.send(BULK, packet, now).unwrap());
+/// }
+/// let voice = channels.send(VOICE, Packet::new(1, 0, 7, vec![0; 100]), now).unwrap();
+/// let size = voice.serialized_len();
+/// scheduler.push(voice);
+///
+/// // O orçamento do tick comporta dois pacotes: a voz passa à frente dos dados.
+/// let sent: Vec<_> = scheduler.tick(2 * size).iter().map(|p| p.header.channel_id).collect();
+/// assert_eq!(sent, [VOICE, BULK]);
+/// assert_eq!(scheduler.channel_len(BULK), 2);
+/// ```
+///
+/// Com a proteção contra inanição, um canal de baixa prioridade ainda envia:
+/// ```rust
+/// use packet::Packet;
+/// use packet::queue::PriorityScheduler;
+///
+/// let mut scheduler = PriorityScheduler::new().with_starvation_limit(2);
+/// scheduler.push(Packet::new(1, 0, 7, vec![0; 100]).with_channel_id(1));
+///
+/// let mut order = Vec::new();
+/// for sequence in 0..3 {
+///     let voice = Packet::new(1, sequence, 7, vec![0; 100]).with_priority(200);
+///     let size = voice.serialized_len();
+///     scheduler.push(voice);
+///     order.push(scheduler.tick(size)[0].header.channel_id);
+/// }
+/// // Depois de dois ticks sem enviar, o canal 1 é atendido antes da voz.
+/// assert_eq!(order, [0, 0, 1]);
+/// ```
+#[derive(Debug)]
+pub struct PriorityScheduler {
+    /// Subfilas não vazias, indexadas pelo `channel_id`.
+    queues: BTreeMap<u8, ChannelQueue>,
+    /// Ticks sem enviar que tornam um canal prioritário; `0` desativa a proteção.
+    starvation_ticks: u32,
+    /// Bytes liberados além do orçamento, descontados do próximo tick.
+    debt: usize,
+    /// Total de pacotes em todas as subfilas.
+    len: usize,
+}
+
+impl Default for PriorityScheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PriorityScheduler {
+    /// Cria um escalonador vazio, com a proteção de [`DEFAULT_STARVATION_TICKS`].
+    pub fn new() -> Self {
+        Self {
+            queues: BTreeMap::new(),
+            starvation_ticks: DEFAULT_STARVATION_TICKS,
+            debt: 0,
+            len: 0,
+        }
+    }
+
+    /// Define após quantos ticks sem enviar um canal é atendido antes dos demais.
+    ///
+    /// ## Parâmetros
+    /// - `ticks`: Ticks seguidos sem enviar; `0` desativa a proteção e atende os canais
+    ///   estritamente por prioridade.
+    ///
+    /// ## Retorno
+    /// - `Self`: O escalonador com a nova proteção.
+    pub fn with_starvation_limit(mut self, ticks: u32) -> Self {
+        self.starvation_ticks = ticks;
+        self
+    }
+
+    /// Insere um pacote no fim da subfila do canal indicado em seu cabeçalho.
+    ///
+    /// ## Parâmetros
+    /// - `packet`: Pacote a ser enviado.
+    pub fn push(&mut self, packet: Packet) {
+        self.queues
+            .entry(packet.header.channel_id)
+            .or_default()
+            .packets
+            .push_back(packet);
+        self.len += 1;
+    }
+
+    /// Libera os pacotes do tick, dentro do orçamento.
+    ///
+    /// Primeiro, cada canal que atingiu o limite de inanição envia um pacote, do que
+    /// espera há mais tempo ao mais recente; depois, os canais são atendidos por
+    /// prioridade até o orçamento acabar.
+    ///
+    /// ## Parâmetros
+    /// - `budget`: Orçamento do tick, em bytes serializados, como a taxa de
+    ///   [`RateController::send_rate`](crate::congestion::RateController::send_rate)
+    ///   multiplicada pela duração do tick.
+    ///
+    /// ## Retorno
+    /// - `Vec<Packet>`: Os pacotes a serem enviados agora, na ordem de envio.
+    pub fn tick(&mut self, budget: usize) -> Vec<Packet> {
+        let mut available = budget as i64 - self.debt as i64;
+        let mut released = Vec::new();
+
+        if self.starvation_ticks > 0 {
+            let mut starved: Vec<(u32, u8)> = self
+                .queues
+                .iter()
+                .filter(|(_, queue)| queue.waiting >= self.starvation_ticks)
+                .map(|(&channel_id, queue)| (queue.waiting, channel_id))
+                .collect();
+            starved.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+            for (_, channel_id) in starved {
+                if available <= 0 {
+                    break;
+                }
+                let packet = self.pop_from(channel_id);
+                available -= packet.serialized_len() as i64;
+                released.push(packet);
+            }
+        }
+
+        while available > 0 {
+            let Some(channel_id) = self.highest_priority() else {
+                break;
+            };
+            let packet = self.pop_from(channel_id);
+            available -= packet.serialized_len() as i64;
+            released.push(packet);
+        }
+
+        self.debt = available.min(0).unsigned_abs() as usize;
+        self.queues.retain(|_, queue| !queue.packets.is_empty());
+        for queue in self.queues.values_mut() {
+            queue.waiting = if queue.served {
+                0
+            } else {
+                queue.waiting.saturating_add(1)
+            };
+            queue.served = false;
+        }
+        released
+    }
+
+    /// Retorna a quantidade de pacotes pendentes de um canal.
+    pub fn channel_len(&self, channel_id: u8) -> usize {
+        self.queues
+            .get(&channel_id)
+            .map_or(0, |queue| queue.packets.len())
+    }
+
+    /// Retorna a quantidade total de pacotes pendentes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Indica se não há pacotes pendentes.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Retorna o canal com pacotes pendentes de maior prioridade, o de menor
+    /// identificador em caso de empate.
+    fn highest_priority(&self) -> Option<u8> {
+        let mut best: Option<(u8, u8)> = None;
+        for (&channel_id, queue) in &self.queues {
+            let Some(front) = queue.packets.front() else {
+                continue;
+            };
+            let priority = front.header.priority;
+            if best.is_none_or(|(_, best_priority)| priority > best_priority) {
+                best = Some((channel_id, priority));
+            }
+        }
+        best.map(|(channel_id, _)| channel_id)
+    }
+
+    /// Remove o pacote à frente da subfila de um canal com pacotes pendentes.
+    fn pop_from(&mut self, channel_id: u8) -> Packet {
+        let queue = self.queues.get_mut(&channel_id).expect("canal com pacotes");
+        let packet = queue.packets.pop_front().expect("canal com pacotes");
+        queue.served = true;
+        self.len -= 1;
+        packet
+    }
+}
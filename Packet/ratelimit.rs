@@ -0,0 +1,331 @@
+//! # Módulo `ratelimit`
+//!
+//! Proteção contra inundação na recepção: limitação da taxa por endereço de origem e
+//! banimento temporário de quem a excede repetidamente.
+//!
+//! [`FloodGuard`] mantém, para cada origem, um balde de fichas em pacotes e outro em
+//! bytes, repostos continuamente até a rajada de um segundo do [`RateLimit`]. Origens
+//! sem sessão estabelecida recebem um limite mais estrito que as conectadas, já que o
+//! aperto de mão troca poucos pacotes. Cada vez que uma origem passa a exceder o
+//! limite conta como uma infração; ao atingir o limite de infrações, a origem é
+//! banida por um tempo, e todos os seus pacotes são descartados até lá. As origens
+//! ociosas são esquecidas, com suas infrações, depois de [`SOURCE_IDLE_TIMEOUT`].
+//!
+//! O [`Server`](crate::connection::Server) aplica a proteção antes de interpretar o
+//! pacote, inclusive antes do aperto de mão, com
+//! [`Server::with_flood_protection`](crate::connection::Server::with_flood_protection),
+//! e conta os descartes nas suas [`NetworkStats`](crate::stats::NetworkStats).
+//!
+//! ## Exemplos
+//! ```rust
+//! use std::net::SocketAddr;
+//! use std::time::{Duration, Instant};
+//!
+//! use packet::ratelimit::{FloodGuard, RateLimit, Verdict};
+//!
+//! let mut guard = FloodGuard::new()
+//!     .with_unconnected_limit(RateLimit::new(2, 1_000))
+//!     .with_bans(2, Duration::from_secs(30));
+//! let attacker: SocketAddr = "203.0.113.7:4000".parse().unwrap();
+//! let now = Instant::now();
+//!
+//! assert_eq!(guard.check(attacker, 100, false, now), Verdict::Allowed);
+//! assert_eq!(guard.check(attacker, 100, false, now), Verdict::Allowed);
+//! assert_eq!(guard.check(attacker, 100, false, now), Verdict::Limited);
+//!
+//! // Depois de repor as fichas, a origem volta a exceder o limite: segunda infração.
+//! let later = now + Duration::from_secs(1);
+//! for _ in 0..2 {
+//!     guard.check(attacker, 100, false, later);
+//! }
+//! assert_eq!(guard.check(attacker, 100, false, later), Verdict::Banned { new: true });
+//! assert!(guard.is_banned(attacker, later + Duration::from_secs(10)));
+//! ```
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+
+/// Limite padrão das origens com sessão estabelecida.
+pub const DEFAULT_CONNECTED_LIMIT: RateLimit = RateLimit::new(500, 512 * 1024);
+
+/// Limite padrão das origens sem sessão, que só trocam o aperto de mão.
+pub const DEFAULT_UNCONNECTED_LIMIT: RateLimit = RateLimit::new(10, 4 * 1024);
+
+/// Quantidade padrão de infrações que bane uma origem.
+pub const DEFAULT_BAN_THRESHOLD: u32 = 3;
+
+/// Duração padrão de um banimento.
+pub const DEFAULT_BAN_DURATION: Duration = Duration::from_secs(30);
+
+/// Tempo sem pacotes após o qual uma origem não banida é esquecida.
+pub const SOURCE_IDLE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Taxa máxima de recepção de uma origem, com rajada de um segundo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RateLimit {
+    /// Pacotes por segundo.
+    pub packets_per_second: u32,
+    /// Bytes serializados por segundo.
+    pub bytes_per_second: u32,
+}
+
+impl RateLimit {
+    /// Cria um limite.
+    ///
+    /// ## Parâmetros
+    /// - `packets_per_second`: Pacotes por segundo.
+    /// - `bytes_per_second`: Bytes serializados por segundo.
+    ///
+    /// ## Retorno
+    /// - `Self`: O limite.
+    pub const fn new(packets_per_second: u32, bytes_per_second: u32) -> Self {
+        Self {
+            packets_per_second,
+            bytes_per_second,
+        }
+    }
+}
+
+/// Decisão de [`FloodGuard::check`] sobre um pacote recebido.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Verdict {
+    /// O pacote cabe no limite da origem.
+    Allowed,
+    /// O pacote excede o limite da origem e deve ser descartado.
+    Limited,
+    /// A origem está banida e o pacote deve ser descartado.
+    Banned {
+        /// Indica se o banimento começou com este pacote.
+        new: bool,
+    },
+}
+
+/// Fichas disponíveis de uma origem.
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    /// Pacotes disponíveis.
+    packets: f64,
+    /// Bytes disponíveis.
+    bytes: f64,
+    /// Instante da última reposição.
+    last_refill: Instant,
+}
+
+impl Bucket {
+    /// Cria um balde cheio.
+    fn full(limit: RateLimit, now: Instant) -> Self {
+        Self {
+            packets: f64::from(limit.packets_per_second),
+            bytes: f64::from(limit.bytes_per_second),
+            last_refill: now,
+        }
+    }
+
+    /// Repõe as fichas até `now` e consome as de um pacote, se houver.
+    fn take(&mut self, limit: RateLimit, len: usize, now: Instant) -> bool {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.last_refill = now;
+        let packets = f64::from(limit.packets_per_second);
+        let bytes = f64::from(limit.bytes_per_second);
+        self.packets = (self.packets + elapsed * packets).min(packets);
+        self.bytes = (self.bytes + elapsed * bytes).min(bytes);
+
+        let len = len as f64;
+        if self.packets < 1.0 || self.bytes < len {
+            return false;
+        }
+        self.packets -= 1.0;
+        self.bytes -= len;
+        true
+    }
+}
+
+/// Estado de uma origem.
+#[derive(Debug, Clone, Copy)]
+struct Source {
+    /// Fichas da origem.
+    bucket: Bucket,
+    /// Indica se o último pacote excedeu o limite.
+    limited: bool,
+    /// Infrações desde o último banimento.
+    offenses: u32,
+    /// Fim do banimento em vigor.
+    banned_until: Option<Instant>,
+    /// Instante do último pacote.
+    last_seen: Instant,
+}
+
+/// Limitação da taxa de recepção por origem, com banimentos temporários.
+#[derive(Debug, Clone)]
+pub struct FloodGuard {
+    /// Limite das origens com sessão.
+    connected: RateLimit,
+    /// Limite das origens sem sessão.
+    unconnected: RateLimit,
+    /// Infrações que banem uma origem; `0` desativa os banimentos.
+    ban_threshold: u32,
+    /// Duração dos banimentos.
+    ban_duration: Duration,
+    /// Origens conhecidas.
+    sources: HashMap<SocketAddr, Source>,
+    /// Instante da última remoção das origens ociosas.
+    last_prune: Option<Instant>,
+}
+
+impl Default for FloodGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FloodGuard {
+    /// Cria a proteção com [`DEFAULT_CONNECTED_LIMIT`], [`DEFAULT_UNCONNECTED_LIMIT`],
+    /// [`DEFAULT_BAN_THRESHOLD`] e [`DEFAULT_BAN_DURATION`].
+    pub fn new() -> Self {
+        Self {
+            connected: DEFAULT_CONNECTED_LIMIT,
+            unconnected: DEFAULT_UNCONNECTED_LIMIT,
+            ban_threshold: DEFAULT_BAN_THRESHOLD,
+            ban_duration: DEFAULT_BAN_DURATION,
+            sources: HashMap::new(),
+            last_prune: None,
+        }
+    }
+
+    /// Define o limite das origens com sessão estabelecida.
+    ///
+    /// ## Parâmetros
+    /// - `limit`: Novo limite.
+    ///
+    /// ## Retorno
+    /// - `Self`: A proteção com o novo limite.
+    pub fn with_connected_limit(mut self, limit: RateLimit) -> Self {
+        self.connected = limit;
+        self
+    }
+
+    /// Define o limite das origens sem sessão.
+    ///
+    /// ## Parâmetros
+    /// - `limit`: Novo limite.
+    ///
+    /// ## Retorno
+    /// - `Self`: A proteção com o novo limite.
+    pub fn with_unconnected_limit(mut self, limit: RateLimit) -> Self {
+        self.unconnected = limit;
+        self
+    }
+
+    /// Define quando e por quanto tempo uma origem é banida.
+    ///
+    /// ## Parâmetros
+    /// - `threshold`: Infrações que banem a origem; `0` desativa os banimentos.
+    /// - `duration`: Duração de cada banimento.
+    ///
+    /// ## Retorno
+    /// - `Self`: A proteção com os novos banimentos.
+    pub fn with_bans(mut self, threshold: u32, duration: Duration) -> Self {
+        self.ban_threshold = threshold;
+        self.ban_duration = duration;
+        self
+    }
+
+    /// Decide se um pacote recebido é aceito, consumindo as fichas da origem.
+    ///
+    /// ## Parâmetros
+    /// - `from`: Origem do pacote.
+    /// - `len`: Tamanho serializado do pacote, em bytes.
+    /// - `connected`: Indica se a origem tem sessão estabelecida.
+    /// - `now`: Instante da recepção.
+    ///
+    /// ## Retorno
+    /// - `Verdict`: A decisão sobre o pacote.
+    pub fn check(
+        &mut self,
+        from: SocketAddr,
+        len: usize,
+        connected: bool,
+        now: Instant,
+    ) -> Verdict {
+        self.prune(now);
+        let limit = if connected {
+            self.connected
+        } else {
+            self.unconnected
+        };
+        let source = self.sources.entry(from).or_insert_with(|| Source {
+            bucket: Bucket::full(limit, now),
+            limited: false,
+            offenses: 0,
+            banned_until: None,
+            last_seen: now,
+        });
+        source.last_seen = now;
+
+        if let Some(until) = source.banned_until {
+            if now < until {
+                return Verdict::Banned { new: false };
+            }
+            source.banned_until = None;
+        }
+        if source.bucket.take(limit, len, now) {
+            source.limited = false;
+            return Verdict::Allowed;
+        }
+        if source.limited {
+            return Verdict::Limited;
+        }
+
+        source.limited = true;
+        source.offenses += 1;
+        if self.ban_threshold == 0 || source.offenses < self.ban_threshold {
+            return Verdict::Limited;
+        }
+        source.offenses = 0;
+        source.limited = false;
+        source.banned_until = Some(now + self.ban_duration);
+        Verdict::Banned { new: true }
+    }
+
+    /// Indica se uma origem está banida.
+    pub fn is_banned(&self, addr: SocketAddr, now: Instant) -> bool {
+        self.sources
+            .get(&addr)
+            .and_then(|source| source.banned_until)
+            .is_some_and(|until| now < until)
+    }
+
+    /// Encerra o banimento e esquece as infrações de uma origem.
+    ///
+    /// ## Retorno
+    /// - `bool`: `true` se a origem estava banida.
+    pub fn unban(&mut self, addr: SocketAddr) -> bool {
+        self.sources
+            .remove(&addr)
+            .is_some_and(|source| source.banned_until.is_some())
+    }
+
+    /// Retorna a quantidade de origens acompanhadas.
+    pub fn tracked_sources(&self) -> usize {
+        self.sources.len()
+    }
+
+    /// Esquece as origens ociosas não banidas, no máximo a cada
+    /// [`SOURCE_IDLE_TIMEOUT`].
+    fn prune(&mut self, now: Instant) {
+        if self
+            .last_prune
+            .is_some_and(|last| now.saturating_duration_since(last) < SOURCE_IDLE_TIMEOUT)
+        {
+            return;
+        }
+        self.last_prune = Some(now);
+        self.sources.retain(|_, source| {
+            source.banned_until.is_some_and(|until| now < until)
+                || now.saturating_duration_since(source.last_seen) < SOURCE_IDLE_TIMEOUT
+        });
+    }
+}
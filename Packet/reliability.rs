@@ -0,0 +1,401 @@
+//! # Módulo `reliability`
+//!
+//! Rastreia pacotes confiáveis enviados até que sejam confirmados, retransmitindo
+//! os que não forem confirmados dentro do tempo limite.
+//!
+//! Apenas pacotes com a flag [`PacketFlags::RELIABLE`](crate::PacketFlags::RELIABLE)
+//! são rastreados; os demais passam direto, o que permite misturar tráfego confiável
+//! e não confiável na mesma conexão.
+//!
+//! [`ReliableEndpoint`] reúne os dois lados de uma conexão: confirma os pacotes
+//! recebidos, retransmite os enviados e descarta duplicados. As sequências são
+//! comparadas na ordem circular de [`sequence`](crate::sequence), de modo que a volta
+//! de `u32::MAX` para `0` não interrompe confirmações nem retransmissões.
+
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use crate::config::ProtocolConfig;
+use crate::sequence::{sequence_greater_than, SequenceBuffer};
+use crate::Packet;
+
+/// Quantidade de sequências recebidas lembradas por [`ReliableEndpoint::new`], igual à
+/// janela padrão de [`ProtocolConfig`].
+pub const DEFAULT_RECEIVE_WINDOW: usize = 1024;
+
+/// Pacote confiável aguardando confirmação.
+#[derive(Debug)]
+struct PendingPacket {
+    /// Cópia do pacote enviado.
+    packet: Packet,
+    /// Instante do último envio.
+    last_sent: Instant,
+}
+
+/// Canal que retransmite pacotes confiáveis até receber a confirmação.
+///
+/// ## Exemplos
+/// ```rust
+/// use std::time::{Duration, Instant};
+/// use packet::Packet;
+/// use packet::reliability::ReliableChannel;
+///
+/// let mut channel = ReliableChannel::new(Duration::from_millis(100));
+/// let now = Instant::now();
+///
+/// channel.send(Packet::reliable(1, 1, 7, vec![1]), now);
+/// channel.send(Packet::new(1, 2, 7, vec![2]), now);
+/// assert_eq!(channel.pending_count(), 1);
+///
+/// let resent = channel.resend_due(now + Duration::from_millis(100));
+/// assert_eq!(resent[0].header.sequence, 1);
+///
+/// assert!(channel.acknowledge(1));
+/// assert_eq!(channel.pending_count(), 0);
+/// ```
+#[derive(Debug)]
+pub struct ReliableChannel {
+    /// Tempo sem confirmação após o qual um pacote é retransmitido.
+    resend_timeout: Duration,
+    /// Pacotes aguardando confirmação, indexados pela sequência.
+    pending: BTreeMap<u32, PendingPacket>,
+}
+
+impl ReliableChannel {
+    /// Cria um novo canal.
+    ///
+    /// ## Parâmetros
+    /// - `resend_timeout`: Tempo sem confirmação após o qual um pacote é retransmitido.
+    ///
+    /// ## Retorno
+    /// - `Self`: Um canal sem pacotes pendentes.
+    pub fn new(resend_timeout: Duration) -> Self {
+        Self {
+            resend_timeout,
+            pending: BTreeMap::new(),
+        }
+    }
+
+    /// Cria um novo canal com o tempo de retransmissão da configuração.
+    ///
+    /// ## Parâmetros
+    /// - `config`: Configuração do protocolo; usa
+    ///   [`ProtocolConfig::resend_timeout`].
+    ///
+    /// ## Retorno
+    /// - `Self`: Um canal sem pacotes pendentes.
+    pub fn with_config(config: &ProtocolConfig) -> Self {
+        Self::new(config.resend_timeout())
+    }
+
+    /// Registra o envio de um pacote.
+    ///
+    /// Pacotes confiáveis passam a ser rastreados até a confirmação; pacotes não
+    /// confiáveis são devolvidos sem nenhum registro.
+    ///
+    /// ## Parâmetros
+    /// - `packet`: Pacote a ser enviado.
+    /// - `now`: Instante do envio.
+    ///
+    /// ## Retorno
+    /// - `Packet`: O mesmo pacote, pronto para ser transmitido.
+    pub fn send(&mut self, packet: Packet, now: Instant) -> Packet {
+        if packet.is_reliable() {
+            self.pending.insert(
+                packet.header.sequence,
+                PendingPacket {
+                    packet: packet.clone(),
+                    last_sent: now,
+                },
+            );
+        }
+        packet
+    }
+
+    /// Marca um pacote como confirmado, encerrando seu rastreamento.
+    ///
+    /// ## Parâmetros
+    /// - `sequence`: Sequência do pacote confirmado.
+    ///
+    /// ## Retorno
+    /// - `bool`: `true` se o pacote estava pendente.
+    pub fn acknowledge(&mut self, sequence: u32) -> bool {
+        self.pending.remove(&sequence).is_some()
+    }
+
+    /// Marca como confirmadas todas as sequências indicadas pela confirmação anexada
+    /// a um pacote recebido (veja [`Packet::with_ack`]).
+    ///
+    /// ## Parâmetros
+    /// - `packet`: Pacote recebido do outro lado.
+    ///
+    /// ## Retorno
+    /// - `usize`: Quantidade de pacotes pendentes que foram confirmados.
+    pub fn acknowledge_packet(&mut self, packet: &Packet) -> usize {
+        packet
+            .acked_sequences()
+            .filter(|&sequence| self.acknowledge(sequence))
+            .count()
+    }
+
+    /// Retorna os pacotes cuja confirmação expirou, renovando o instante de envio.
+    ///
+    /// ## Parâmetros
+    /// - `now`: Instante atual.
+    ///
+    /// ## Retorno
+    /// - `Vec<Packet>`: Pacotes a serem retransmitidos, em ordem circular de sequência,
+    ///   do mais antigo ao mais recente, mesmo quando as sequências dão a volta.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use std::time::{Duration, Instant};
+    /// use packet::Packet;
+    /// use packet::reliability::ReliableChannel;
+    ///
+    /// let mut channel = ReliableChannel::new(Duration::from_millis(100));
+    /// let now = Instant::now();
+    /// for sequence in [u32::MAX - 1, u32::MAX, 0, 1] {
+    ///     channel.send(Packet::reliable(1, sequence, 7, vec![]), now);
+    /// }
+    ///
+    /// let resent = channel.resend_due(now + Duration::from_millis(100));
+    /// let sequences: Vec<_> = resent.iter().map(|p| p.header.sequence).collect();
+    /// assert_eq!(sequences, [u32::MAX - 1, u32::MAX, 0, 1]);
+    /// ```
+    pub fn resend_due(&mut self, now: Instant) -> Vec<Packet> {
+        let mut due = Vec::new();
+        for pending in self.pending.values_mut() {
+            if now.saturating_duration_since(pending.last_sent) >= self.resend_timeout {
+                pending.last_sent = now;
+                due.push(pending.packet.clone());
+            }
+        }
+        let newest = self.pending.keys().copied().reduce(|newest, sequence| {
+            if sequence_greater_than(sequence, newest) {
+                sequence
+            } else {
+                newest
+            }
+        });
+        if let Some(newest) = newest {
+            let oldest = newest.wrapping_add(1);
+            due.sort_by_key(|packet| packet.header.sequence.wrapping_sub(oldest));
+        }
+        due
+    }
+
+    /// Retorna a quantidade de pacotes aguardando confirmação.
+    pub fn pending_count(&self) -> usize {
+        self.pending.len()
+    }
+}
+
+/// Ponta de uma conexão com entrega confiável: confirma o que recebe, retransmite o
+/// que não foi confirmado e entrega cada pacote à aplicação uma única vez.
+///
+/// Combina um [`ReliableChannel`], para os pacotes enviados, com um
+/// [`SequenceBuffer`] das sequências recebidas, que alimenta a confirmação anexada a
+/// cada pacote enviado ([`Packet::with_ack`]) e descarta duplicados. As sequências são
+/// atribuídas pelo chamador e devem ser únicas por conexão.
+///
+/// ## Exemplos
+/// ```rust
+/// use std::time::{Duration, Instant};
+/// use packet::Packet;
+/// use packet::reliability::ReliableEndpoint;
+///
+/// let mut client = ReliableEndpoint::new(Duration::from_millis(100));
+/// let mut server = ReliableEndpoint::new(Duration::from_millis(100));
+/// let now = Instant::now();
+///
+/// let sent = client.send(Packet::reliable(1, 1, 7, vec![1]), now);
+/// assert!(server.receive(sent.clone()).is_some());
+/// // Uma cópia duplicada não é entregue de novo.
+/// assert!(server.receive(sent).is_none());
+///
+/// // A resposta do servidor confirma a sequência 1.
+/// let reply = server.send(Packet::new(1, 1, 0, vec![2]), now);
+/// assert_eq!(reply.ack(), Some((1, 0)));
+/// client.receive(reply).unwrap();
+/// assert_eq!(client.pending_count(), 0);
+/// assert!(client.resend_due(now + Duration::from_secs(1)).is_empty());
+/// ```
+#[derive(Debug)]
+pub struct ReliableEndpoint {
+    /// Pacotes enviados aguardando confirmação.
+    channel: ReliableChannel,
+    /// Sequências recebidas do outro lado.
+    received: SequenceBuffer<()>,
+}
+
+impl ReliableEndpoint {
+    /// Cria uma nova ponta que lembra [`DEFAULT_RECEIVE_WINDOW`] sequências recebidas.
+    ///
+    /// ## Parâmetros
+    /// - `resend_timeout`: Tempo sem confirmação após o qual um pacote é retransmitido.
+    ///
+    /// ## Retorno
+    /// - `Self`: Uma ponta sem pacotes enviados nem recebidos.
+    pub fn new(resend_timeout: Duration) -> Self {
+        Self {
+            channel: ReliableChannel::new(resend_timeout),
+            received: SequenceBuffer::new(DEFAULT_RECEIVE_WINDOW),
+        }
+    }
+
+    /// Cria uma nova ponta com o tempo de retransmissão e a janela da configuração.
+    ///
+    /// ## Parâmetros
+    /// - `config`: Configuração do protocolo; usa [`ProtocolConfig::resend_timeout`] e
+    ///   [`ProtocolConfig::reliability_window`] como quantidade de sequências recebidas
+    ///   lembradas.
+    ///
+    /// ## Retorno
+    /// - `Self`: Uma ponta sem pacotes enviados nem recebidos.
+    pub fn with_config(config: &ProtocolConfig) -> Self {
+        Self {
+            channel: ReliableChannel::with_config(config),
+            received: SequenceBuffer::new(config.reliability_window as usize),
+        }
+    }
+
+    /// Prepara um pacote para envio, anexando a confirmação do que foi recebido.
+    ///
+    /// Pacotes confiáveis passam a ser rastreados até a confirmação, como em
+    /// [`ReliableChannel::send`].
+    ///
+    /// ## Parâmetros
+    /// - `packet`: Pacote a ser enviado.
+    /// - `now`: Instante do envio.
+    ///
+    /// ## Retorno
+    /// - `Packet`: O pacote pronto para ser transmitido, com a flag
+    ///   [`PacketFlags::ACK`](crate::PacketFlags::ACK) se algo já foi recebido.
+    pub fn send(&mut self, packet: Packet, now: Instant) -> Packet {
+        let packet = self.attach_ack(packet);
+        self.channel.send(packet, now)
+    }
+
+    /// Processa um pacote recebido.
+    ///
+    /// A confirmação anexada ao pacote encerra o rastreamento dos pacotes confirmados.
+    /// O pacote é entregue se sua sequência ainda não foi recebida.
+    ///
+    /// ## Parâmetros
+    /// - `packet`: Pacote recebido do outro lado.
+    ///
+    /// ## Retorno
+    /// - `Option<Packet>`: O pacote, ou `None` se é um duplicado ou é mais antigo que a
+    ///   janela de sequências lembradas.
+    pub fn receive(&mut self, packet: Packet) -> Option<Packet> {
+        self.channel.acknowledge_packet(&packet);
+
+        let sequence = packet.header.sequence;
+        if self.received.exists(sequence) || !self.received.insert(sequence, ()) {
+            return None;
+        }
+        Some(packet)
+    }
+
+    /// Retorna os pacotes cuja confirmação expirou, com a confirmação atualizada.
+    ///
+    /// ## Parâmetros
+    /// - `now`: Instante atual.
+    ///
+    /// ## Retorno
+    /// - `Vec<Packet>`: Pacotes a serem retransmitidos, em ordem de sequência.
+    pub fn resend_due(&mut self, now: Instant) -> Vec<Packet> {
+        self.channel
+            .resend_due(now)
+            .into_iter()
+            .map(|packet| self.attach_ack(packet))
+            .collect()
+    }
+
+    /// Retorna a confirmação a ser anexada ao próximo pacote enviado.
+    ///
+    /// ## Retorno
+    /// - `Option<(u32, u32)>`: A sequência mais recente recebida e o campo de bits das
+    ///   32 anteriores, no formato de [`Packet::with_ack`], ou `None` se nada foi
+    ///   recebido.
+    pub fn ack(&self) -> Option<(u32, u32)> {
+        let latest = self.received.newest()?;
+        let bits = (0..32)
+            .filter(|&i| self.received.exists(latest.wrapping_sub(1 + i)))
+            .fold(0, |bits, i| bits | 1 << i);
+        Some((latest, bits))
+    }
+
+    /// Retorna a quantidade de pacotes aguardando confirmação.
+    pub fn pending_count(&self) -> usize {
+        self.channel.pending_count()
+    }
+
+    /// Anexa a confirmação atual ao pacote, se algo já foi recebido.
+    fn attach_ack(&self, packet: Packet) -> Packet {
+        match self.ack() {
+            Some((latest, bits)) => packet.with_ack(latest, bits),
+            None => packet,
+        }
+    }
+}
+
+/// Separa pacotes confiáveis dos não confiáveis, pela flag
+/// [`PacketFlags::RELIABLE`](crate::PacketFlags::RELIABLE).
+///
+/// Os confiáveis seguem para o [`ReliableChannel`] e para a entrega ordenada; os não
+/// confiáveis podem ser aplicados imediatamente.
+///
+/// ## Parâmetros
+/// - `packets`: Pacotes recebidos.
+///
+/// ## Retorno
+/// - `(Vec<Packet>, Vec<Packet>)`: Os pacotes confiáveis e os não confiáveis, nessa
+///   ordem, preservando a ordem relativa de cada grupo.
+///
+/// ## Exemplos
+/// ```rust
+/// use packet::Packet;
+/// use packet::reliability::partition_reliability;
+///
+/// let packets = vec![
+///     Packet::new(1, 1, 7, vec![]),
+///     Packet::reliable(1, 2, 7, vec![]),
+///     Packet::new(1, 3, 7, vec![]),
+/// ];
+/// let (reliable, unreliable) = partition_reliability(packets);
+///
+/// assert_eq!(reliable.iter().map(|p| p.header.sequence).collect::<Vec<_>>(), [2]);
+/// assert_eq!(unreliable.iter().map(|p| p.header.sequence).collect::<Vec<_>>(), [1, 3]);
+/// ```
+pub fn partition_reliability(packets: Vec<Packet>) -> (Vec<Packet>, Vec<Packet>) {
+    packets.into_iter().partition(Packet::is_reliable)
+}
+
+/// Remove os pacotes não confiáveis de `packets`, mantendo os confiáveis no mesmo vetor.
+///
+/// Variante de [`partition_reliability`] que reaproveita a alocação de `packets`.
+///
+/// ## Parâmetros
+/// - `packets`: Pacotes recebidos; ao final, contém apenas os confiáveis.
+///
+/// ## Retorno
+/// - `Vec<Packet>`: Os pacotes não confiáveis removidos, na ordem original.
+///
+/// ## Exemplos
+/// ```rust
+/// use packet::Packet;
+/// use packet::reliability::extract_unreliable;
+///
+/// let mut packets = vec![Packet::reliable(1, 1, 7, vec![]), Packet::new(1, 2, 7, vec![])];
+/// let unreliable = extract_unreliable(&mut packets);
+///
+/// assert_eq!(packets.len(), 1);
+/// assert_eq!(unreliable[0].header.sequence, 2);
+/// ```
+pub fn extract_unreliable(packets: &mut Vec<Packet>) -> Vec<Packet> {
+    packets
+        .extract_if(.., |packet| !packet.is_reliable())
+        .collect()
+}
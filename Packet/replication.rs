@@ -0,0 +1,578 @@
+//! # Módulo `replication`
+//!
+//! Replicação do estado do jogo por snapshots completos e deltas.
+//!
+//! A aplicação registra os componentes do estado em um [`Schema`], cada um com largura
+//! fixa em bytes, e monta a cada tick um [`WorldSnapshot`] com o valor dos componentes
+//! de cada entidade. O [`Replicator`] do servidor guarda, por cliente, o último
+//! snapshot confirmado e envia apenas a diferença em relação a ele: para cada
+//! entidade alterada, uma máscara de bits dos componentes que mudaram seguida dos
+//! seus bytes, além da lista de entidades removidas. Sem confirmação, ou quando a
+//! última confirmada tem mais de [`DEFAULT_MAX_UNACKED`] snapshots, o snapshot segue
+//! completo. No cliente, a [`Replica`] reconstrói cada snapshot a partir do que
+//! serviu de base.
+//!
+//! A sequência do pacote identifica o snapshot e é por ela que o cliente confirma o
+//! recebimento, pelo meio que a aplicação preferir, como o campo `ack` dos seus
+//! próprios pacotes; o servidor a repassa a [`Replicator::ack`].
+//!
+//! O payload começa com um byte que indica o tipo do snapshot (`0` completo, `1`
+//! delta, seguido da sequência da base em `u32`), seguido da quantidade de entidades
+//! alteradas e, para cada uma, do identificador, da máscara e dos componentes, e por
+//! fim da quantidade e dos identificadores das entidades removidas. Inteiros são
+//! little-endian.
+//!
+//! ## Exemplos
+//! ```rust
+//! use packet::replication::{Replica, Replicator, Schema, WorldSnapshot};
+//!
+//! let mut schema = Schema::new();
+//! let position = schema.register(8);
+//! let health = schema.register(2);
+//!
+//! let mut server = Replicator::new(schema.clone(), 0x90);
+//! let mut client = Replica::new(schema.clone());
+//!
+//! let mut world = WorldSnapshot::new(&schema);
+//! world.set(1, position, &[0; 8]);
+//! world.set(1, health, &100u16.to_le_bytes());
+//!
+//! // Sem confirmação, o primeiro snapshot segue completo.
+//! let full = server.encode(7, &world);
+//! client.apply(&full).unwrap();
+//! server.ack(7, full.header.sequence);
+//!
+//! // Depois dela, só a vida da entidade 1 é enviada.
+//! world.set(1, health, &90u16.to_le_bytes());
+//! let delta = server.encode(7, &world);
+//! assert!(delta.payload.len() < full.payload.len());
+//!
+//! let received = client.apply(&delta).unwrap().unwrap();
+//! assert_eq!(received.get(1, health), Some(&90u16.to_le_bytes()[..]));
+//! assert_eq!(received, &world);
+//! ```
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use crate::sequence::sequence_greater_than;
+use crate::{Packet, PacketError};
+
+/// Quantidade padrão de snapshots enviados sem confirmação antes de voltar ao snapshot
+/// completo, e de snapshots guardados pela [`Replica`] como base de deltas.
+pub const DEFAULT_MAX_UNACKED: usize = 32;
+
+/// Tipo de snapshot completo no primeiro byte do payload.
+const FULL: u8 = 0;
+/// Tipo de snapshot delta no primeiro byte do payload.
+const DELTA: u8 = 1;
+
+/// Identificador de um componente registrado em um [`Schema`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ComponentId(usize);
+
+impl ComponentId {
+    /// Retorna a posição do componente no [`Schema`], que é também o seu bit na
+    /// máscara.
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+/// Componentes do estado de cada entidade, na ordem de registro.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Schema {
+    /// Largura de cada componente, em bytes.
+    widths: Vec<usize>,
+    /// Posição de cada componente no estado da entidade.
+    offsets: Vec<usize>,
+    /// Soma das larguras.
+    state_size: usize,
+}
+
+impl Schema {
+    /// Cria um esquema sem componentes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registra um componente de largura fixa.
+    ///
+    /// ## Parâmetros
+    /// - `width`: Largura do componente, em bytes.
+    ///
+    /// ## Retorno
+    /// - `ComponentId`: O identificador do componente, usado em [`WorldSnapshot::set`].
+    pub fn register(&mut self, width: usize) -> ComponentId {
+        let id = ComponentId(self.widths.len());
+        self.widths.push(width);
+        self.offsets.push(self.state_size);
+        self.state_size += width;
+        id
+    }
+
+    /// Retorna a quantidade de componentes registrados.
+    pub fn len(&self) -> usize {
+        self.widths.len()
+    }
+
+    /// Indica se nenhum componente foi registrado.
+    pub fn is_empty(&self) -> bool {
+        self.widths.is_empty()
+    }
+
+    /// Retorna a largura de um componente, em bytes.
+    pub fn width(&self, component: ComponentId) -> usize {
+        self.widths[component.0]
+    }
+
+    /// Retorna o tamanho do estado completo de uma entidade, em bytes.
+    pub fn state_size(&self) -> usize {
+        self.state_size
+    }
+
+    /// Tamanho da máscara de componentes alterados, em bytes.
+    fn mask_size(&self) -> usize {
+        self.widths.len().div_ceil(8)
+    }
+
+    /// Intervalo do componente no estado da entidade.
+    fn range(&self, index: usize) -> std::ops::Range<usize> {
+        self.offsets[index]..self.offsets[index] + self.widths[index]
+    }
+}
+
+/// Estado de todas as entidades em um tick.
+///
+/// Entidades são criadas no primeiro [`WorldSnapshot::set`], com os demais componentes
+/// zerados.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorldSnapshot {
+    /// Componentes das entidades.
+    schema: Schema,
+    /// Estado de cada entidade, com os componentes concatenados.
+    entities: BTreeMap<u32, Box<[u8]>>,
+}
+
+impl WorldSnapshot {
+    /// Cria um snapshot sem entidades.
+    ///
+    /// ## Parâmetros
+    /// - `schema`: Componentes das entidades.
+    pub fn new(schema: &Schema) -> Self {
+        Self {
+            schema: schema.clone(),
+            entities: BTreeMap::new(),
+        }
+    }
+
+    /// Retorna os componentes das entidades.
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    /// Define o valor de um componente de uma entidade.
+    ///
+    /// ## Parâmetros
+    /// - `entity`: Identificador da entidade.
+    /// - `component`: Componente registrado no [`Schema`] do snapshot.
+    /// - `bytes`: Novo valor, com a largura do componente.
+    ///
+    /// ## Pânico
+    /// Entra em pânico se o componente não pertence ao esquema ou se `bytes` não tem a
+    /// sua largura.
+    pub fn set(&mut self, entity: u32, component: ComponentId, bytes: &[u8]) {
+        assert_eq!(
+            bytes.len(),
+            self.schema.width(component),
+            "largura do componente"
+        );
+        let range = self.schema.range(component.0);
+        let size = self.schema.state_size;
+        let state = self
+            .entities
+            .entry(entity)
+            .or_insert_with(|| vec![0; size].into_boxed_slice());
+        state[range].copy_from_slice(bytes);
+    }
+
+    /// Retorna o valor de um componente de uma entidade.
+    ///
+    /// ## Retorno
+    /// - `Option<&[u8]>`: Os bytes do componente, ou `None` se a entidade não existe.
+    pub fn get(&self, entity: u32, component: ComponentId) -> Option<&[u8]> {
+        let range = self.schema.range(component.0);
+        self.entities.get(&entity).map(|state| &state[range])
+    }
+
+    /// Remove uma entidade.
+    ///
+    /// ## Retorno
+    /// - `bool`: `true` se a entidade existia.
+    pub fn remove(&mut self, entity: u32) -> bool {
+        self.entities.remove(&entity).is_some()
+    }
+
+    /// Indica se a entidade existe.
+    pub fn contains(&self, entity: u32) -> bool {
+        self.entities.contains_key(&entity)
+    }
+
+    /// Retorna os identificadores das entidades, em ordem crescente.
+    pub fn entities(&self) -> impl Iterator<Item = u32> + '_ {
+        self.entities.keys().copied()
+    }
+
+    /// Retorna a quantidade de entidades.
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    /// Indica se o snapshot não tem entidades.
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    /// Serializa a diferença em relação a `base`, ou o snapshot completo sem base.
+    fn encode(&self, base: Option<(u32, &WorldSnapshot)>) -> Vec<u8> {
+        let schema = &self.schema;
+        let mut payload = Vec::new();
+        match base {
+            Some((sequence, _)) => {
+                payload.push(DELTA);
+                payload.extend_from_slice(&sequence.to_le_bytes());
+            }
+            None => payload.push(FULL),
+        }
+
+        let count_at = payload.len();
+        payload.extend_from_slice(&0u32.to_le_bytes());
+        let mut changed = 0u32;
+        let mut mask = vec![0u8; schema.mask_size()];
+        for (&entity, state) in &self.entities {
+            let previous = base.and_then(|(_, base)| base.entities.get(&entity));
+            mask.fill(0);
+            for index in 0..schema.len() {
+                let range = schema.range(index);
+                if previous.is_none_or(|previous| previous[range.clone()] != state[range]) {
+                    mask[index / 8] |= 1 << (index % 8);
+                }
+            }
+            if previous.is_some() && mask.iter().all(|&byte| byte == 0) {
+                continue;
+            }
+            changed += 1;
+            payload.extend_from_slice(&entity.to_le_bytes());
+            payload.extend_from_slice(&mask);
+            for index in (0..schema.len()).filter(|i| mask[i / 8] & (1 << (i % 8)) != 0) {
+                payload.extend_from_slice(&state[schema.range(index)]);
+            }
+        }
+        payload[count_at..count_at + 4].copy_from_slice(&changed.to_le_bytes());
+
+        let removed: Vec<u32> = base
+            .map(|(_, base)| base.entities().filter(|e| !self.contains(*e)).collect())
+            .unwrap_or_default();
+        payload.extend_from_slice(&(removed.len() as u32).to_le_bytes());
+        for entity in removed {
+            payload.extend_from_slice(&entity.to_le_bytes());
+        }
+        payload
+    }
+}
+
+/// Snapshots enviados a um cliente e o último confirmado.
+#[derive(Debug, Clone)]
+struct Baseline {
+    /// Sequência do próximo snapshot.
+    next_sequence: u32,
+    /// Último snapshot confirmado, base dos deltas.
+    acked: Option<(u32, WorldSnapshot)>,
+    /// Snapshots enviados após o confirmado, do mais antigo ao mais recente.
+    sent: VecDeque<(u32, WorldSnapshot)>,
+}
+
+/// Lado do servidor: produz os snapshots de cada cliente como deltas do último
+/// confirmado.
+#[derive(Debug, Clone)]
+pub struct Replicator {
+    /// Componentes das entidades.
+    schema: Schema,
+    /// Tipo da mensagem dos pacotes produzidos.
+    message_type: u8,
+    /// Distância máxima, em snapshots, entre a base de um delta e o snapshot enviado.
+    max_unacked: usize,
+    /// Estado de cada cliente, pelo `player_id`.
+    clients: HashMap<u64, Baseline>,
+}
+
+impl Replicator {
+    /// Cria um replicador sem clientes, com [`DEFAULT_MAX_UNACKED`].
+    ///
+    /// ## Parâmetros
+    /// - `schema`: Componentes das entidades.
+    /// - `message_type`: Tipo da mensagem dos pacotes produzidos.
+    pub fn new(schema: Schema, message_type: u8) -> Self {
+        Self {
+            schema,
+            message_type,
+            max_unacked: DEFAULT_MAX_UNACKED,
+            clients: HashMap::new(),
+        }
+    }
+
+    /// Define após quantos snapshots sem confirmação o replicador volta ao snapshot
+    /// completo.
+    ///
+    /// O valor não deve superar o histórico da [`Replica`] do cliente, ou ele pode
+    /// receber deltas de uma base que já descartou.
+    ///
+    /// ## Parâmetros
+    /// - `max_unacked`: Distância máxima entre a base de um delta e o snapshot enviado.
+    ///
+    /// ## Retorno
+    /// - `Self`: O replicador com o novo limite.
+    pub fn with_max_unacked(mut self, max_unacked: usize) -> Self {
+        self.max_unacked = max_unacked.max(1);
+        self
+    }
+
+    /// Retorna os componentes das entidades.
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    /// Monta o próximo snapshot de um cliente.
+    ///
+    /// É um delta do último snapshot confirmado pelo cliente, ou o snapshot completo se
+    /// não há confirmação ou se ela é mais antiga que o limite de
+    /// [`Replicator::with_max_unacked`].
+    ///
+    /// ## Parâmetros
+    /// - `player_id`: Cliente de destino; é registrado no primeiro envio.
+    /// - `snapshot`: Estado atual.
+    ///
+    /// ## Retorno
+    /// - `Packet`: O pacote, com a sequência do snapshot.
+    ///
+    /// ## Pânico
+    /// Entra em pânico se o snapshot foi criado com outro [`Schema`].
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::replication::{Replicator, Schema, WorldSnapshot};
+    ///
+    /// let mut schema = Schema::new();
+    /// let position = schema.register(4);
+    /// let mut server = Replicator::new(schema.clone(), 0x90).with_max_unacked(2);
+    ///
+    /// let mut world = WorldSnapshot::new(&schema);
+    /// world.set(1, position, &[1; 4]);
+    /// let first = server.encode(7, &world);
+    /// server.ack(7, first.header.sequence);
+    ///
+    /// // Deltas do snapshot confirmado enquanto ele está a até 2 snapshots.
+    /// assert_eq!(server.encode(7, &world).payload[0], 1);
+    /// assert_eq!(server.encode(7, &world).payload[0], 1);
+    /// // Sem novas confirmações, volta ao snapshot completo.
+    /// assert_eq!(server.encode(7, &world).payload[0], 0);
+    /// ```
+    pub fn encode(&mut self, player_id: u64, snapshot: &WorldSnapshot) -> Packet {
+        assert_eq!(snapshot.schema, self.schema, "esquema do snapshot");
+        let client = self.clients.entry(player_id).or_insert_with(|| Baseline {
+            next_sequence: 0,
+            acked: None,
+            sent: VecDeque::new(),
+        });
+        let sequence = client.next_sequence;
+        client.next_sequence = sequence.wrapping_add(1);
+
+        let base = client
+            .acked
+            .as_ref()
+            .filter(|(acked, _)| sequence.wrapping_sub(*acked) as usize <= self.max_unacked)
+            .map(|(acked, base)| (*acked, base));
+        let payload = snapshot.encode(base);
+
+        client.sent.push_back((sequence, snapshot.clone()));
+        if client.sent.len() > self.max_unacked {
+            client.sent.pop_front();
+        }
+        Packet::new(self.message_type, sequence, player_id, payload)
+    }
+
+    /// Registra a confirmação de um snapshot, que passa a ser a base dos deltas
+    /// seguintes do cliente.
+    ///
+    /// ## Parâmetros
+    /// - `player_id`: Cliente que confirmou.
+    /// - `sequence`: Sequência do pacote confirmado.
+    ///
+    /// ## Retorno
+    /// - `bool`: `true` se a base mudou; confirmações repetidas, antigas ou de
+    ///   snapshots fora do histórico são ignoradas.
+    pub fn ack(&mut self, player_id: u64, sequence: u32) -> bool {
+        let Some(client) = self.clients.get_mut(&player_id) else {
+            return false;
+        };
+        let Some(position) = client.sent.iter().position(|(sent, _)| *sent == sequence) else {
+            return false;
+        };
+        if client
+            .acked
+            .as_ref()
+            .is_some_and(|(acked, _)| !sequence_greater_than(sequence, *acked))
+        {
+            return false;
+        }
+        client.acked = client.sent.drain(..=position).next_back();
+        true
+    }
+
+    /// Esquece um cliente; o próximo snapshot enviado a ele será completo.
+    ///
+    /// ## Retorno
+    /// - `bool`: `true` se o cliente existia.
+    pub fn remove_client(&mut self, player_id: u64) -> bool {
+        self.clients.remove(&player_id).is_some()
+    }
+}
+
+/// Lado do cliente: reconstrói os snapshots recebidos de um [`Replicator`].
+#[derive(Debug, Clone)]
+pub struct Replica {
+    /// Componentes das entidades.
+    schema: Schema,
+    /// Quantidade máxima de snapshots guardados.
+    capacity: usize,
+    /// Snapshots recebidos, do mais antigo ao mais recente.
+    history: VecDeque<(u32, WorldSnapshot)>,
+}
+
+impl Replica {
+    /// Cria uma réplica vazia que guarda [`DEFAULT_MAX_UNACKED`] snapshots.
+    ///
+    /// ## Parâmetros
+    /// - `schema`: Componentes das entidades, iguais aos do servidor.
+    pub fn new(schema: Schema) -> Self {
+        Self::with_capacity(schema, DEFAULT_MAX_UNACKED)
+    }
+
+    /// Cria uma réplica vazia com um histórico explícito.
+    ///
+    /// ## Parâmetros
+    /// - `schema`: Componentes das entidades, iguais aos do servidor.
+    /// - `capacity`: Quantidade de snapshots guardados como base de deltas; deve ser ao
+    ///   menos o limite de [`Replicator::with_max_unacked`].
+    pub fn with_capacity(schema: Schema, capacity: usize) -> Self {
+        Self {
+            schema,
+            capacity: capacity.max(1),
+            history: VecDeque::new(),
+        }
+    }
+
+    /// Retorna o snapshot mais recente e sua sequência.
+    pub fn latest(&self) -> Option<(u32, &WorldSnapshot)> {
+        self.history
+            .back()
+            .map(|(sequence, snapshot)| (*sequence, snapshot))
+    }
+
+    /// Aplica um pacote produzido por [`Replicator::encode`].
+    ///
+    /// ## Parâmetros
+    /// - `packet`: Pacote recebido.
+    ///
+    /// ## Retorno
+    /// - `Result<Option<&WorldSnapshot>, PacketError>`: O snapshot reconstruído, cuja
+    ///   sequência deve ser confirmada ao servidor; `None` se ele não é mais recente que
+    ///   [`Replica::latest`]; ou [`PacketError::InvalidPayload`] se o payload é
+    ///   malformado ou a base do delta não está no histórico.
+    pub fn apply(&mut self, packet: &Packet) -> Result<Option<&WorldSnapshot>, PacketError> {
+        let sequence = packet.header.sequence;
+        if self
+            .latest()
+            .is_some_and(|(latest, _)| !sequence_greater_than(sequence, latest))
+        {
+            return Ok(None);
+        }
+
+        let mut reader = Reader(&packet.payload);
+        let snapshot = match reader.u8()? {
+            FULL => self.decode(WorldSnapshot::new(&self.schema), &mut reader)?,
+            DELTA => {
+                let base = reader.u32()?;
+                let Some((_, base)) = self.history.iter().find(|(s, _)| *s == base) else {
+                    return Err(invalid("base do delta fora do histórico"));
+                };
+                self.decode(base.clone(), &mut reader)?
+            }
+            _ => return Err(invalid("tipo de snapshot desconhecido")),
+        };
+        if !reader.0.is_empty() {
+            return Err(invalid("bytes após o snapshot"));
+        }
+
+        if self.history.len() == self.capacity {
+            self.history.pop_front();
+        }
+        self.history.push_back((sequence, snapshot));
+        Ok(self.history.back().map(|(_, snapshot)| snapshot))
+    }
+
+    /// Aplica as entidades alteradas e removidas sobre a base.
+    fn decode(
+        &self,
+        mut snapshot: WorldSnapshot,
+        reader: &mut Reader<'_>,
+    ) -> Result<WorldSnapshot, PacketError> {
+        let schema = &self.schema;
+        for _ in 0..reader.u32()? {
+            let entity = reader.u32()?;
+            let mask = reader.take(schema.mask_size())?;
+            let state = snapshot
+                .entities
+                .entry(entity)
+                .or_insert_with(|| vec![0; schema.state_size].into_boxed_slice());
+            for index in (0..schema.len()).filter(|i| mask[i / 8] & (1 << (i % 8)) != 0) {
+                let range = schema.range(index);
+                state[range.clone()].copy_from_slice(reader.take(range.len())?);
+            }
+        }
+        for _ in 0..reader.u32()? {
+            snapshot.remove(reader.u32()?);
+        }
+        Ok(snapshot)
+    }
+}
+
+/// Erro de payload malformado.
+fn invalid(reason: &'static str) -> PacketError {
+    PacketError::InvalidPayload { reason }
+}
+
+/// Leitura sequencial do payload.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    /// Consome os próximos `len` bytes.
+    fn take(&mut self, len: usize) -> Result<&'a [u8], PacketError> {
+        if self.0.len() < len {
+            return Err(invalid("snapshot truncado"));
+        }
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    /// Consome um byte.
+    fn u8(&mut self) -> Result<u8, PacketError> {
+        Ok(self.take(1)?[0])
+    }
+
+    /// Consome um `u32` little-endian.
+    fn u32(&mut self) -> Result<u32, PacketError> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+}
@@ -0,0 +1,246 @@
+//! # Módulo `ring`
+//!
+//! Montagem de pacotes em um único buffer circular pré-alocado, sem alocações por
+//! pacote.
+//!
+//! [`RingAllocator`] reserva regiões contíguas de um buffer alocado uma única vez, na
+//! criação. Cada pacote é montado no próprio buffer: o payload é escrito diretamente
+//! na região e o cabeçalho é gravado à sua frente ao finalizar. Depois de enviado, o
+//! pacote é liberado e o espaço volta a ser usado quando a escrita dá a volta no
+//! buffer. Um tick inteiro de montagem de pacotes não faz nenhuma alocação no heap.
+//!
+//! ## Restrições de tempo de vida
+//! - Uma [`RingRegion`] empresta o alocador de forma exclusiva: só uma região é
+//!   montada por vez, e nenhum pacote pode ser lido enquanto ela existir.
+//! - Pacotes finalizados são identificados por um [`RingSlot`], que não empresta o
+//!   alocador. Os bytes e a [`PacketRef`] obtidos de um slot emprestam o alocador de
+//!   forma compartilhada e devem ser descartados antes de montar o próximo pacote.
+//! - Os slots são liberados na ordem em que foram criados; o espaço de um pacote só é
+//!   reaproveitado após a liberação dele e de todos os anteriores.
+//! - Uma região descartada sem [`RingRegion::finish`] não ocupa espaço.
+//!
+//! ## Exemplos
+//! ```rust
+//! use packet::PacketHeader;
+//! use packet::ring::RingAllocator;
+//!
+//! let mut ring = RingAllocator::new(4 * (PacketHeader::SIZE + 8));
+//!
+//! for tick in 0..10u32 {
+//!     let mut region = ring.allocate(8).expect("espaço no anel");
+//!     region.payload_mut().copy_from_slice(&(tick as u64).to_le_bytes());
+//!     let slot = region.finish(1, tick, 7);
+//!
+//!     let packet = ring.packet(&slot);
+//!     assert!(packet.verify_checksum());
+//!     assert_eq!(packet.payload, (tick as u64).to_le_bytes());
+//!     // ...envia `ring.bytes(&slot)` pelo socket...
+//!
+//!     ring.release(slot).unwrap();
+//! }
+//! assert!(ring.is_empty());
+//! ```
+
+use crate::checksum::ChecksumScope;
+use crate::view::PacketRef;
+use crate::{Packet, PacketHeader};
+
+/// Buffer circular do qual são reservadas as regiões dos pacotes.
+#[derive(Debug)]
+pub struct RingAllocator {
+    /// Memória reservada na criação.
+    buffer: Box<[u8]>,
+    /// Início do espaço livre para a próxima região.
+    head: usize,
+    /// Início do pacote vivo mais antigo.
+    tail: usize,
+    /// Fim dos dados antes da volta, quando a escrita já voltou ao início do buffer.
+    wrapped_at: Option<usize>,
+    /// Quantidade de pacotes finalizados e ainda não liberados.
+    live: usize,
+}
+
+/// Pacote finalizado em um [`RingAllocator`].
+///
+/// Não é [`Clone`], para que cada pacote seja liberado uma única vez.
+#[derive(Debug, PartialEq, Eq)]
+pub struct RingSlot {
+    /// Posição do pacote no buffer.
+    start: usize,
+    /// Tamanho do pacote serializado.
+    len: usize,
+}
+
+/// Região reservada para a montagem de um pacote.
+#[derive(Debug)]
+pub struct RingRegion<'a> {
+    /// Alocador de onde a região foi reservada.
+    ring: &'a mut RingAllocator,
+    /// Posição da região no buffer.
+    start: usize,
+    /// Tamanho do pacote serializado.
+    len: usize,
+}
+
+impl RingAllocator {
+    /// Cria um anel com a capacidade informada, alocando-a de uma só vez.
+    ///
+    /// ## Parâmetros
+    /// - `capacity`: Tamanho do buffer, em bytes; cada pacote ocupa
+    ///   [`PacketHeader::SIZE`] bytes além do payload.
+    ///
+    /// ## Retorno
+    /// - `Self`: Um anel vazio.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            buffer: vec![0; capacity].into_boxed_slice(),
+            head: 0,
+            tail: 0,
+            wrapped_at: None,
+            live: 0,
+        }
+    }
+
+    /// Retorna o tamanho do buffer, em bytes.
+    pub fn capacity(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Indica se não há pacotes vivos.
+    pub fn is_empty(&self) -> bool {
+        self.live == 0
+    }
+
+    /// Reserva uma região para um pacote com `payload_len` bytes de payload.
+    ///
+    /// A região é contígua: se não couber antes do fim do buffer, é reservada no
+    /// início, desde que os pacotes de lá já tenham sido liberados.
+    ///
+    /// ## Parâmetros
+    /// - `payload_len`: Tamanho do payload.
+    ///
+    /// ## Retorno
+    /// - `Option<RingRegion<'_>>`: A região, com o payload zerado, ou `None` se não há
+    ///   espaço contíguo suficiente até que pacotes mais antigos sejam liberados.
+    pub fn allocate(&mut self, payload_len: usize) -> Option<RingRegion<'_>> {
+        let len = PacketHeader::SIZE.checked_add(payload_len)?;
+        u32::try_from(payload_len).ok()?;
+
+        let start = match self.wrapped_at {
+            None if self.capacity() - self.head >= len => self.head,
+            None if self.tail >= len => 0,
+            Some(_) if self.tail - self.head >= len => self.head,
+            _ => return None,
+        };
+
+        self.buffer[start + PacketHeader::SIZE..start + len].fill(0);
+        Some(RingRegion {
+            ring: self,
+            start,
+            len,
+        })
+    }
+
+    /// Retorna os bytes serializados de um pacote finalizado, prontos para envio.
+    pub fn bytes(&self, slot: &RingSlot) -> &[u8] {
+        &self.buffer[slot.start..slot.start + slot.len]
+    }
+
+    /// Retorna uma visão sem cópia de um pacote finalizado.
+    pub fn packet(&self, slot: &RingSlot) -> PacketRef<'_> {
+        PacketRef::from_bytes(self.bytes(slot)).expect("slot gravado por RingRegion::finish")
+    }
+
+    /// Libera o pacote mais antigo, tornando seu espaço reutilizável.
+    ///
+    /// ## Parâmetros
+    /// - `slot`: Pacote a liberar; deve ser o mais antigo ainda vivo.
+    ///
+    /// ## Retorno
+    /// - `Result<(), RingSlot>`: O próprio slot de volta se ele não é o mais antigo;
+    ///   nesse caso nada é liberado.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::PacketHeader;
+    /// use packet::ring::RingAllocator;
+    ///
+    /// let mut ring = RingAllocator::new(3 * PacketHeader::SIZE);
+    /// let first = ring.allocate(0).unwrap().finish(1, 1, 7);
+    /// let second = ring.allocate(0).unwrap().finish(1, 2, 7);
+    /// let third = ring.allocate(0).unwrap().finish(1, 3, 7);
+    /// assert!(ring.allocate(0).is_none());
+    ///
+    /// // Fora de ordem, o slot é devolvido.
+    /// let second = ring.release(second).unwrap_err();
+    ///
+    /// // Liberado o mais antigo, a escrita dá a volta e reaproveita seu espaço.
+    /// ring.release(first).unwrap();
+    /// let fourth = ring.allocate(0).unwrap().finish(1, 4, 7);
+    /// assert_eq!(ring.packet(&fourth).header.sequence, 4);
+    /// assert_eq!(ring.packet(&third).header.sequence, 3);
+    ///
+    /// for slot in [second, third, fourth] {
+    ///     ring.release(slot).unwrap();
+    /// }
+    /// assert!(ring.is_empty());
+    /// ```
+    pub fn release(&mut self, slot: RingSlot) -> Result<(), RingSlot> {
+        if self.live == 0 || slot.start != self.tail {
+            return Err(slot);
+        }
+
+        self.tail = slot.start + slot.len;
+        self.live -= 1;
+        if self.live == 0 {
+            self.head = 0;
+            self.tail = 0;
+            self.wrapped_at = None;
+        } else if self.wrapped_at == Some(self.tail) {
+            // Os pacotes antes da volta acabaram; o mais antigo está no início.
+            self.tail = 0;
+            self.wrapped_at = None;
+        }
+        Ok(())
+    }
+}
+
+impl RingRegion<'_> {
+    /// Retorna o payload da região, para ser preenchido no próprio buffer.
+    pub fn payload_mut(&mut self) -> &mut [u8] {
+        &mut self.ring.buffer[self.start + PacketHeader::SIZE..self.start + self.len]
+    }
+
+    /// Grava o cabeçalho à frente do payload e ocupa definitivamente a região.
+    ///
+    /// O cabeçalho é o de [`Packet::new`], com o checksum calculado sobre o payload
+    /// escrito.
+    ///
+    /// ## Parâmetros
+    /// - `message_type`: Tipo da mensagem.
+    /// - `sequence`: Sequência do pacote.
+    /// - `player_id`: Identificador único do jogador.
+    ///
+    /// ## Retorno
+    /// - `RingSlot`: O identificador do pacote no anel.
+    pub fn finish(self, message_type: u8, sequence: u32, player_id: u64) -> RingSlot {
+        let Self { ring, start, len } = self;
+        let (header_bytes, rest) = ring.buffer[start..start + len].split_at_mut(PacketHeader::SIZE);
+
+        // Um `Vec` vazio não aloca; só o cabeçalho de `Packet::new` é aproveitado.
+        let mut header = Packet::new(message_type, sequence, player_id, Vec::new()).header;
+        header.payload_size = rest.len() as u32;
+        header.recompute(rest, ChecksumScope::Payload);
+        header_bytes.copy_from_slice(&header.to_array());
+
+        if ring.wrapped_at.is_none() && start == 0 && ring.live > 0 {
+            ring.wrapped_at = Some(ring.head);
+        }
+        if ring.live == 0 {
+            ring.tail = start;
+        }
+        ring.head = start + len;
+        ring.live += 1;
+        RingSlot { start, len }
+    }
+}
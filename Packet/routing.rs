@@ -0,0 +1,366 @@
+//! # Módulo `routing`
+//!
+//! Tabela de jogadores do servidor, que associa cada `player_id` do cabeçalho ao
+//! endereço do jogador e ao estado da sua conexão.
+//!
+//! Cada pacote recebido é classificado por [`PlayerTable::route`] a partir da origem e
+//! do `player_id`:
+//!
+//! - da origem associada ao jogador, o pacote é dele ([`Route::Known`]);
+//! - de uma origem nova, com o endereço antigo do jogador em silêncio há mais que o
+//!   tempo de migração, o jogador mudou de endereço, como quando o NAT troca a porta
+//!   do mapeamento, e a tabela passa a usar a nova origem ([`Route::Migrated`]);
+//! - de uma origem associada a outro jogador, ou de uma origem nova enquanto o
+//!   endereço do jogador está ativo, o `player_id` foi forjado ([`Route::Spoofed`]);
+//! - de um jogador que não está na tabela, o pacote é de um desconhecido
+//!   ([`Route::Unknown`]).
+//!
+//! A migração automática aceita qualquer origem depois do silêncio do jogador; se os
+//! pacotes são autenticados, como nas sessões cifradas, desative-a com
+//! [`PlayerTable::with_migration_timeout`] e chame [`PlayerTable::migrate`] só depois
+//! de verificar o pacote.
+//!
+//! ## Exemplos
+//! ```rust
+//! use std::net::SocketAddr;
+//! use std::time::{Duration, Instant};
+//!
+//! use packet::routing::{PlayerTable, Route};
+//! use packet::Packet;
+//!
+//! let alice: SocketAddr = "10.0.0.1:4000".parse().unwrap();
+//! let spoofer: SocketAddr = "10.0.0.9:4000".parse().unwrap();
+//! let now = Instant::now();
+//!
+//! let mut players = PlayerTable::new();
+//! players.insert(1, alice, "alice", now);
+//!
+//! let packet = Packet::new(1, 0, 1, vec![]);
+//! assert_eq!(players.route(alice, &packet.header, now), Route::Known(1));
+//! assert_eq!(
+//!     players.route(spoofer, &packet.header, now),
+//!     Route::Spoofed { claimed: 1, owner: None },
+//! );
+//!
+//! // Depois de um silêncio longo, o NAT de Alice trocou a porta.
+//! let rebound: SocketAddr = "10.0.0.1:4711".parse().unwrap();
+//! let later = now + Duration::from_secs(5);
+//! assert_eq!(
+//!     players.route(rebound, &packet.header, later),
+//!     Route::Migrated { player_id: 1, previous: alice },
+//! );
+//! assert_eq!(players.addr_of(1), Some(rebound));
+//! ```
+
+use std::collections::HashMap;
+use std::io;
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+
+use crate::transport::Transport;
+use crate::{Packet, PacketError, PacketHeader};
+
+/// Tempo padrão de silêncio do endereço de um jogador após o qual outra origem pode
+/// assumir seu `player_id`.
+pub const DEFAULT_MIGRATION_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Classificação de um pacote recebido por [`PlayerTable::route`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Route {
+    /// O pacote veio do endereço do jogador.
+    Known(u64),
+    /// O jogador passou a usar a origem do pacote.
+    Migrated {
+        /// Jogador que mudou de endereço.
+        player_id: u64,
+        /// Endereço usado até então.
+        previous: SocketAddr,
+    },
+    /// O `player_id` do pacote não corresponde à origem.
+    Spoofed {
+        /// Jogador declarado no cabeçalho.
+        claimed: u64,
+        /// Jogador associado à origem, se houver.
+        owner: Option<u64>,
+    },
+    /// O jogador declarado não está na tabela.
+    Unknown,
+}
+
+/// Endereço e conexão de um jogador da tabela.
+#[derive(Debug, Clone)]
+struct PlayerEntry<C> {
+    /// Endereço atual do jogador.
+    addr: SocketAddr,
+    /// Estado da conexão associado pela aplicação.
+    connection: C,
+    /// Instante do último pacote recebido do endereço atual.
+    last_seen: Instant,
+}
+
+/// Associação entre `player_id`, endereço e conexão dos jogadores do servidor.
+///
+/// `C` é o estado da conexão guardado para cada jogador, como o canal confiável ou o
+/// identificador da sessão.
+#[derive(Debug, Clone)]
+pub struct PlayerTable<C = ()> {
+    /// Jogadores, pelo `player_id`.
+    players: HashMap<u64, PlayerEntry<C>>,
+    /// `player_id` de cada endereço associado.
+    addrs: HashMap<SocketAddr, u64>,
+    /// Silêncio após o qual outra origem assume o `player_id`, se a migração
+    /// automática está ativa.
+    migration_timeout: Option<Duration>,
+}
+
+impl<C> Default for PlayerTable<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C> PlayerTable<C> {
+    /// Cria uma tabela vazia, com [`DEFAULT_MIGRATION_TIMEOUT`].
+    pub fn new() -> Self {
+        Self {
+            players: HashMap::new(),
+            addrs: HashMap::new(),
+            migration_timeout: Some(DEFAULT_MIGRATION_TIMEOUT),
+        }
+    }
+
+    /// Define o silêncio após o qual outra origem assume o `player_id` de um jogador.
+    ///
+    /// ## Parâmetros
+    /// - `timeout`: Tempo sem pacotes do endereço do jogador; `None` desativa a
+    ///   migração automática, restando [`PlayerTable::migrate`].
+    ///
+    /// ## Retorno
+    /// - `Self`: A tabela com o novo tempo de migração.
+    pub fn with_migration_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.migration_timeout = timeout;
+        self
+    }
+
+    /// Retorna a quantidade de jogadores.
+    pub fn len(&self) -> usize {
+        self.players.len()
+    }
+
+    /// Indica se não há jogadores.
+    pub fn is_empty(&self) -> bool {
+        self.players.is_empty()
+    }
+
+    /// Indica se o jogador está na tabela.
+    pub fn contains(&self, player_id: u64) -> bool {
+        self.players.contains_key(&player_id)
+    }
+
+    /// Adiciona um jogador, substituindo o registro anterior do mesmo `player_id`.
+    ///
+    /// Outro jogador associado a `addr` é removido da tabela.
+    ///
+    /// ## Parâmetros
+    /// - `player_id`: Identificador do jogador.
+    /// - `addr`: Endereço do jogador.
+    /// - `connection`: Estado da conexão do jogador.
+    /// - `now`: Instante atual, contado como o último pacote do jogador.
+    ///
+    /// ## Retorno
+    /// - `Option<(SocketAddr, C)>`: O endereço e a conexão anteriores do jogador, se
+    ///   ele já estava na tabela.
+    pub fn insert(
+        &mut self,
+        player_id: u64,
+        addr: SocketAddr,
+        connection: C,
+        now: Instant,
+    ) -> Option<(SocketAddr, C)> {
+        let previous = self.remove(player_id);
+        if let Some(owner) = self.addrs.get(&addr).copied() {
+            self.remove(owner);
+        }
+        self.addrs.insert(addr, player_id);
+        self.players.insert(
+            player_id,
+            PlayerEntry {
+                addr,
+                connection,
+                last_seen: now,
+            },
+        );
+        previous
+    }
+
+    /// Remove um jogador.
+    ///
+    /// ## Retorno
+    /// - `Option<(SocketAddr, C)>`: O endereço e a conexão do jogador, se ele estava na
+    ///   tabela.
+    pub fn remove(&mut self, player_id: u64) -> Option<(SocketAddr, C)> {
+        let entry = self.players.remove(&player_id)?;
+        self.addrs.remove(&entry.addr);
+        Some((entry.addr, entry.connection))
+    }
+
+    /// Retorna o endereço atual de um jogador.
+    pub fn addr_of(&self, player_id: u64) -> Option<SocketAddr> {
+        self.players.get(&player_id).map(|entry| entry.addr)
+    }
+
+    /// Retorna o jogador associado a um endereço.
+    pub fn player_at(&self, addr: SocketAddr) -> Option<u64> {
+        self.addrs.get(&addr).copied()
+    }
+
+    /// Retorna a conexão de um jogador.
+    pub fn connection(&self, player_id: u64) -> Option<&C> {
+        self.players.get(&player_id).map(|entry| &entry.connection)
+    }
+
+    /// Retorna a conexão de um jogador para alteração.
+    pub fn connection_mut(&mut self, player_id: u64) -> Option<&mut C> {
+        self.players
+            .get_mut(&player_id)
+            .map(|entry| &mut entry.connection)
+    }
+
+    /// Retorna os jogadores, com o endereço e a conexão, em ordem arbitrária.
+    pub fn iter(&self) -> impl Iterator<Item = (u64, SocketAddr, &C)> {
+        self.players
+            .iter()
+            .map(|(&player_id, entry)| (player_id, entry.addr, &entry.connection))
+    }
+
+    /// Classifica um pacote recebido pela origem e pelo `player_id` do cabeçalho.
+    ///
+    /// Um pacote [`Route::Known`] ou [`Route::Migrated`] renova o último pacote do
+    /// jogador; nos demais casos a tabela não muda.
+    ///
+    /// ## Parâmetros
+    /// - `from`: Origem do pacote.
+    /// - `header`: Cabeçalho do pacote.
+    /// - `now`: Instante da recepção.
+    ///
+    /// ## Retorno
+    /// - `Route`: A classificação do pacote.
+    pub fn route(&mut self, from: SocketAddr, header: &PacketHeader, now: Instant) -> Route {
+        let claimed = header.player_id;
+        if let Some(owner) = self.player_at(from) {
+            if owner != claimed {
+                return Route::Spoofed {
+                    claimed,
+                    owner: Some(owner),
+                };
+            }
+            if let Some(entry) = self.players.get_mut(&owner) {
+                entry.last_seen = now;
+            }
+            return Route::Known(owner);
+        }
+
+        let Some(entry) = self.players.get(&claimed) else {
+            return Route::Unknown;
+        };
+        let silent = now.saturating_duration_since(entry.last_seen);
+        match self.migration_timeout {
+            Some(timeout) if silent >= timeout => {
+                let previous = self.migrate(claimed, from).expect("jogador verificado");
+                if let Some(entry) = self.players.get_mut(&claimed) {
+                    entry.last_seen = now;
+                }
+                Route::Migrated {
+                    player_id: claimed,
+                    previous,
+                }
+            }
+            _ => Route::Spoofed {
+                claimed,
+                owner: None,
+            },
+        }
+    }
+
+    /// Associa um jogador a um novo endereço, como depois de autenticar um pacote
+    /// vindo de uma origem nova.
+    ///
+    /// Outro jogador associado a `addr` é removido da tabela.
+    ///
+    /// ## Parâmetros
+    /// - `player_id`: Identificador do jogador.
+    /// - `addr`: Novo endereço do jogador.
+    ///
+    /// ## Retorno
+    /// - `Option<SocketAddr>`: O endereço anterior, ou `None` se o jogador não está na
+    ///   tabela.
+    pub fn migrate(&mut self, player_id: u64, addr: SocketAddr) -> Option<SocketAddr> {
+        let previous = self.addr_of(player_id)?;
+        if previous == addr {
+            return Some(previous);
+        }
+        if let Some(owner) = self.player_at(addr) {
+            self.remove(owner);
+        }
+        self.addrs.remove(&previous);
+        self.addrs.insert(addr, player_id);
+        if let Some(entry) = self.players.get_mut(&player_id) {
+            entry.addr = addr;
+        }
+        Some(previous)
+    }
+
+    /// Envia um pacote ao endereço atual de um jogador.
+    ///
+    /// O pacote é enviado como está, sem alterar o `player_id` do cabeçalho.
+    ///
+    /// ## Parâmetros
+    /// - `transport`: Transporte usado no envio.
+    /// - `player_id`: Jogador de destino.
+    /// - `packet`: Pacote a ser enviado.
+    ///
+    /// ## Retorno
+    /// - `Result<usize, PacketError>`: Quantidade de bytes enviados, ou
+    ///   [`PacketError::Io`] com [`io::ErrorKind::NotConnected`] se o jogador não está
+    ///   na tabela.
+    pub fn send_to_player(
+        &self,
+        transport: &mut impl Transport,
+        player_id: u64,
+        packet: &Packet,
+    ) -> Result<usize, PacketError> {
+        let addr = self
+            .addr_of(player_id)
+            .ok_or_else(|| io::Error::from(io::ErrorKind::NotConnected))?;
+        transport.send_packet(addr, packet)
+    }
+
+    /// Envia um pacote a todos os jogadores, exceto um, como ao repassar aos demais o
+    /// pacote recebido de um jogador.
+    ///
+    /// Como em [`BufferedSender::send_batch`](crate::batch::BufferedSender::send_batch),
+    /// o envio para no primeiro erro.
+    ///
+    /// ## Parâmetros
+    /// - `transport`: Transporte usado no envio.
+    /// - `player_id`: Jogador que não recebe o pacote.
+    /// - `packet`: Pacote a ser enviado.
+    ///
+    /// ## Retorno
+    /// - `Result<usize, PacketError>`: Quantidade de bytes enviados a todos os
+    ///   jogadores, ou o erro do transporte.
+    pub fn broadcast_except(
+        &self,
+        transport: &mut impl Transport,
+        player_id: u64,
+        packet: &Packet,
+    ) -> Result<usize, PacketError> {
+        let mut sent = 0;
+        for (&id, entry) in &self.players {
+            if id != player_id {
+                sent += transport.send_packet(entry.addr, packet)?;
+            }
+        }
+        Ok(sent)
+    }
+}
@@ -0,0 +1,203 @@
+//! # Módulo `rtt`
+//!
+//! Estatísticas de tempo de ida e volta (RTT) para monitoramento da conexão.
+//!
+//! [`RttHistogram`] acumula amostras em faixas de largura fixa, de forma que o
+//! custo de cada registro é constante e a memória usada não depende da quantidade
+//! de amostras. [`RttEstimator`] mantém o RTT suavizado e o jitter usados pela
+//! conexão, no estilo da RFC 6298.
+
+use std::time::Duration;
+
+/// Histograma de amostras de RTT com faixas de largura fixa.
+///
+/// Amostras maiores que a última faixa são contadas nela. Os percentis são
+/// aproximados pelo limite superior da faixa em que caem, limitados ao maior valor
+/// observado; mínimo, máximo e média são exatos.
+///
+/// ## Exemplos
+/// ```rust
+/// use std::time::Duration;
+/// use packet::rtt::RttHistogram;
+///
+/// let mut histogram = RttHistogram::new(Duration::from_millis(1), 500);
+/// for ms in 1..=100 {
+///     histogram.record(Duration::from_millis(ms));
+/// }
+///
+/// assert_eq!(histogram.min(), Some(Duration::from_millis(1)));
+/// assert_eq!(histogram.max(), Some(Duration::from_millis(100)));
+/// assert_eq!(histogram.percentile(0.99), Some(Duration::from_millis(100)));
+/// assert_eq!(histogram.percentile(0.5), Some(Duration::from_millis(51)));
+/// ```
+#[derive(Debug, Clone)]
+pub struct RttHistogram {
+    /// Largura de cada faixa.
+    bucket_width: Duration,
+    /// Quantidade de amostras por faixa.
+    buckets: Vec<u64>,
+    /// Total de amostras registradas.
+    count: u64,
+    /// Soma de todas as amostras.
+    sum: Duration,
+    /// Menor amostra registrada.
+    min: Option<Duration>,
+    /// Maior amostra registrada.
+    max: Option<Duration>,
+}
+
+impl RttHistogram {
+    /// Cria um histograma vazio.
+    ///
+    /// ## Parâmetros
+    /// - `bucket_width`: Largura de cada faixa; determina a resolução dos percentis.
+    /// - `bucket_count`: Quantidade de faixas; a cobertura é `bucket_width * bucket_count`.
+    ///
+    /// ## Retorno
+    /// - `Self`: Um histograma sem amostras.
+    pub fn new(bucket_width: Duration, bucket_count: usize) -> Self {
+        Self {
+            bucket_width: bucket_width.max(Duration::from_nanos(1)),
+            buckets: vec![0; bucket_count.max(1)],
+            count: 0,
+            sum: Duration::ZERO,
+            min: None,
+            max: None,
+        }
+    }
+
+    /// Registra uma amostra de RTT.
+    ///
+    /// ## Parâmetros
+    /// - `rtt`: Tempo de ida e volta medido.
+    pub fn record(&mut self, rtt: Duration) {
+        let index = (rtt.as_nanos() / self.bucket_width.as_nanos()) as usize;
+        let last = self.buckets.len() - 1;
+        self.buckets[index.min(last)] += 1;
+
+        self.count += 1;
+        self.sum += rtt;
+        self.min = Some(self.min.map_or(rtt, |min| min.min(rtt)));
+        self.max = Some(self.max.map_or(rtt, |max| max.max(rtt)));
+    }
+
+    /// Retorna o valor abaixo do qual está a fração `quantile` das amostras.
+    ///
+    /// ## Parâmetros
+    /// - `quantile`: Fração entre `0.0` e `1.0`, por exemplo `0.99` para o p99.
+    ///
+    /// ## Retorno
+    /// - `Option<Duration>`: O percentil aproximado, ou `None` se não há amostras.
+    pub fn percentile(&self, quantile: f64) -> Option<Duration> {
+        let max = self.max?;
+        let target = ((quantile.clamp(0.0, 1.0) * self.count as f64).ceil() as u64).max(1);
+
+        let mut seen = 0;
+        for (index, &bucket) in self.buckets.iter().enumerate() {
+            seen += bucket;
+            if seen >= target {
+                let upper = self.bucket_width * (index as u32 + 1);
+                return Some(upper.min(max));
+            }
+        }
+        Some(max)
+    }
+
+    /// Retorna a menor amostra registrada.
+    pub fn min(&self) -> Option<Duration> {
+        self.min
+    }
+
+    /// Retorna a maior amostra registrada.
+    pub fn max(&self) -> Option<Duration> {
+        self.max
+    }
+
+    /// Retorna a média das amostras registradas.
+    pub fn mean(&self) -> Option<Duration> {
+        if self.count == 0 {
+            return None;
+        }
+        Some(Duration::from_nanos(
+            (self.sum.as_nanos() / self.count as u128) as u64,
+        ))
+    }
+
+    /// Retorna a quantidade de amostras registradas.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Descarta todas as amostras.
+    pub fn clear(&mut self) {
+        self.buckets.iter_mut().for_each(|bucket| *bucket = 0);
+        self.count = 0;
+        self.sum = Duration::ZERO;
+        self.min = None;
+        self.max = None;
+    }
+}
+
+/// Estimativa suavizada do RTT e da sua variação (jitter).
+///
+/// Segue a RFC 6298: a primeira amostra define o RTT e metade dela o jitter; as
+/// seguintes entram com peso `1/8` no RTT e `1/4` no jitter, que acompanha o desvio
+/// absoluto entre cada amostra e o RTT suavizado.
+///
+/// ## Exemplos
+/// ```rust
+/// use std::time::Duration;
+/// use packet::rtt::RttEstimator;
+///
+/// let mut estimator = RttEstimator::new();
+/// assert_eq!(estimator.rtt(), None);
+///
+/// estimator.record(Duration::from_millis(80));
+/// assert_eq!(estimator.rtt(), Some(Duration::from_millis(80)));
+/// assert_eq!(estimator.jitter(), Some(Duration::from_millis(40)));
+///
+/// estimator.record(Duration::from_millis(160));
+/// assert_eq!(estimator.rtt(), Some(Duration::from_millis(90)));
+/// assert_eq!(estimator.jitter(), Some(Duration::from_millis(50)));
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RttEstimator {
+    /// RTT suavizado; `None` antes da primeira amostra.
+    smoothed: Option<Duration>,
+    /// Variação média das amostras em torno do RTT suavizado.
+    variation: Duration,
+}
+
+impl RttEstimator {
+    /// Cria um estimador sem amostras.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Acrescenta uma amostra de RTT à estimativa.
+    ///
+    /// ## Parâmetros
+    /// - `sample`: Tempo de ida e volta medido.
+    pub fn record(&mut self, sample: Duration) {
+        match self.smoothed {
+            None => {
+                self.smoothed = Some(sample);
+                self.variation = sample / 2;
+            }
+            Some(smoothed) => {
+                self.variation = (self.variation * 3 + smoothed.abs_diff(sample)) / 4;
+                self.smoothed = Some((smoothed * 7 + sample) / 8);
+            }
+        }
+    }
+
+    /// Retorna o RTT suavizado, ou `None` antes da primeira amostra.
+    pub fn rtt(&self) -> Option<Duration> {
+        self.smoothed
+    }
+
+    /// Retorna o jitter estimado, ou `None` antes da primeira amostra.
+    pub fn jitter(&self) -> Option<Duration> {
+        self.smoothed.map(|_| self.variation)
+    }
+}
@@ -0,0 +1,627 @@
+//! # Módulo `runtime`
+//!
+//! Servidor dividido entre várias threads, para os milhares de clientes que um único
+//! laço de recepção não acompanha.
+//!
+//! Um [`ServerRuntime`] lê o socket em uma thread própria e distribui os datagramas
+//! entre os trabalhadores pelo endereço de origem. Cada trabalhador conduz seu próprio
+//! [`Server`] sobre um [`ShardTransport`], dono exclusivo das sessões da sua parte:
+//! como a origem de uma sessão não muda, todos os seus pacotes chegam ao mesmo
+//! trabalhador, e nenhum mapa de conexões é compartilhado entre threads. Os
+//! identificadores de sessão são intercalados entre os trabalhadores (o trabalhador
+//! `i` de `n` atribui `i + 1`, `i + 1 + n`, ...), então o trabalhador de uma sessão
+//! sai do próprio identificador, sem consulta a nenhuma tabela.
+//!
+//! Os envios de outras threads passam por um [`ServerHandle`], que é `Send + Sync`,
+//! barato de clonar e encaminha cada pedido ao trabalhador da sessão. Os eventos de
+//! todos os trabalhadores chegam juntos por [`ServerRuntime::recv_event`].
+//!
+//! O socket é lido por uma única thread: a leitura é barata perto do processamento
+//! das sessões, que é o que se divide.
+//!
+//! Os trabalhadores tiram o instante de cada `poll` de um [`Clock`]: o do sistema, ou o
+//! informado a [`ServerRuntime::start_with_clock`], que com um
+//! [`MockClock`](crate::clock::MockClock) faz os tempos limite e os pings das sessões
+//! avançarem só com o relógio.
+//!
+//! ## Exemplos
+//! ```rust
+//! use std::thread;
+//! use std::time::{Duration, Instant};
+//! use packet::Packet;
+//! use packet::connection::{Client, ClientEvent, ServerEvent};
+//! use packet::runtime::{RuntimeConfig, ServerRuntime};
+//! use packet::transport::UdpTransport;
+//!
+//! let transport = UdpTransport::bind("127.0.0.1:0").unwrap();
+//! let runtime = ServerRuntime::start(transport, RuntimeConfig::new().with_workers(2)).unwrap();
+//! let mut client = Client::connect(runtime.local_addr(), Instant::now()).unwrap();
+//!
+//! let session_id = loop {
+//!     client.poll(Instant::now()).unwrap();
+//!     if let Some(ServerEvent::Connected { session_id, .. }) =
+//!         runtime.recv_event(Duration::from_millis(5))
+//!     {
+//!         break session_id;
+//!     }
+//! };
+//!
+//! // Um sistema do jogo em outra thread envia ao jogador.
+//! let handle = runtime.handle();
+//! thread::spawn(move || {
+//!     handle.send_to_player(session_id, Packet::new(1, 1, 0, b"oi".to_vec())).unwrap()
+//! })
+//! .join()
+//! .unwrap();
+//!
+//! let mut received = None;
+//! while received.is_none() {
+//!     for event in client.poll(Instant::now()).unwrap() {
+//!         if let ClientEvent::Packet(packet) = event {
+//!             received = Some(packet.payload);
+//!         }
+//!     }
+//! }
+//! assert_eq!(received.unwrap(), b"oi");
+//! runtime.shutdown().unwrap();
+//! ```
+
+use std::collections::VecDeque;
+use std::hash::{BuildHasher, RandomState};
+use std::io;
+use std::net::{SocketAddr, UdpSocket};
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::clock::{Clock, SystemClock};
+use crate::connection::{Server, ServerEvent};
+use crate::disconnect::DisconnectReason;
+use crate::transport::{Transport, UdpTransport};
+use crate::{Packet, PacketError};
+
+/// Intervalo máximo entre dois `poll` de um trabalhador sem datagramas, que mantém
+/// os pings e os tempos limite das sessões em dia.
+pub const DEFAULT_TICK: Duration = Duration::from_millis(10);
+
+/// Configuração de um [`ServerRuntime`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RuntimeConfig {
+    /// Quantidade de trabalhadores.
+    workers: NonZeroUsize,
+    /// Intervalo máximo entre dois `poll` de um trabalhador.
+    tick: Duration,
+}
+
+impl Default for RuntimeConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RuntimeConfig {
+    /// Cria a configuração com um trabalhador por núcleo disponível e [`DEFAULT_TICK`].
+    pub fn new() -> Self {
+        Self {
+            workers: thread::available_parallelism().unwrap_or(NonZeroUsize::MIN),
+            tick: DEFAULT_TICK,
+        }
+    }
+
+    /// Define a quantidade de trabalhadores.
+    ///
+    /// ## Parâmetros
+    /// - `workers`: Quantidade de threads que conduzem as sessões.
+    ///
+    /// ## Retorno
+    /// - `Self`: A configuração alterada.
+    ///
+    /// ## Pânico
+    /// Entra em pânico se `workers` é zero.
+    pub fn with_workers(mut self, workers: usize) -> Self {
+        self.workers = NonZeroUsize::new(workers).expect("o runtime precisa de um trabalhador");
+        self
+    }
+
+    /// Define o intervalo máximo entre dois `poll` de um trabalhador, e também o
+    /// tempo máximo até a thread de leitura perceber o encerramento.
+    ///
+    /// ## Parâmetros
+    /// - `tick`: Intervalo; valores menores que 1 ms são tratados como 1 ms.
+    ///
+    /// ## Retorno
+    /// - `Self`: A configuração alterada.
+    pub fn with_tick(mut self, tick: Duration) -> Self {
+        self.tick = tick.max(Duration::from_millis(1));
+        self
+    }
+
+    /// Retorna a quantidade de trabalhadores.
+    pub fn workers(&self) -> usize {
+        self.workers.get()
+    }
+
+    /// Retorna o intervalo máximo entre dois `poll` de um trabalhador.
+    pub fn tick(&self) -> Duration {
+        self.tick
+    }
+}
+
+/// Datagrama recebido pela thread de leitura, já interpretado.
+type Datagram = (SocketAddr, Result<Packet, PacketError>);
+
+/// Transporte de um trabalhador: recebe os datagramas que a thread de leitura lhe
+/// entrega e envia pelo socket compartilhado.
+#[derive(Debug)]
+pub struct ShardTransport {
+    /// Socket compartilhado com a thread de leitura e os demais trabalhadores.
+    socket: Arc<UdpSocket>,
+    /// Datagramas entregues e ainda não lidos pelo servidor.
+    inbox: VecDeque<Datagram>,
+}
+
+impl Transport for ShardTransport {
+    fn send_packet(&mut self, addr: SocketAddr, packet: &Packet) -> Result<usize, PacketError> {
+        Ok(self.socket.send_to(&packet.to_bytes(), addr)?)
+    }
+
+    fn recv_packet(&mut self) -> Result<(SocketAddr, Packet), PacketError> {
+        let (from, packet) = self
+            .inbox
+            .pop_front()
+            .ok_or_else(|| io::Error::from(io::ErrorKind::WouldBlock))?;
+        Ok((from, packet?))
+    }
+
+    fn recv_from(&mut self) -> io::Result<Option<Datagram>> {
+        Ok(self.inbox.pop_front())
+    }
+
+    fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.socket.local_addr()
+    }
+
+    /// Não tem efeito: a recepção nunca bloqueia.
+    fn set_nonblocking(&mut self, _nonblocking: bool) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Pedido a um trabalhador.
+#[derive(Debug)]
+enum Job {
+    /// Datagrama de uma origem da parte do trabalhador.
+    Datagram(Datagram),
+    /// Envio a uma sessão.
+    Send { session_id: u64, packet: Packet },
+    /// Encerramento de uma sessão.
+    Disconnect {
+        session_id: u64,
+        reason: DisconnectReason,
+    },
+}
+
+/// Função que configura o servidor de cada trabalhador.
+type Configure = dyn Fn(Server<ShardTransport>) -> Server<ShardTransport> + Send + Sync;
+
+/// Relógio compartilhado pelos trabalhadores.
+type SharedClock = Arc<dyn Clock + Send + Sync>;
+
+/// Acesso de qualquer thread às sessões de um [`ServerRuntime`].
+///
+/// Os pedidos são encaminhados ao trabalhador da sessão e executados por ele, em
+/// ordem, antes do seu próximo `poll`; os pedidos a sessões que não existem e as
+/// falhas de envio são ignorados.
+///
+/// ## Exemplos
+/// ```rust
+/// use packet::runtime::ServerHandle;
+///
+/// fn shared<T: Send + Sync + Clone>() {}
+/// shared::<ServerHandle>();
+/// ```
+#[derive(Debug, Clone)]
+pub struct ServerHandle {
+    /// Fila de cada trabalhador.
+    workers: Arc<[Sender<Job>]>,
+    /// Sessões de cada trabalhador, atualizadas a cada `poll`.
+    sessions: Arc<[AtomicUsize]>,
+}
+
+impl ServerHandle {
+    /// Retorna o trabalhador de uma sessão.
+    fn worker(&self, session_id: u64) -> &Sender<Job> {
+        let index = session_id.saturating_sub(1) % self.workers.len() as u64;
+        &self.workers[index as usize]
+    }
+
+    /// Encaminha um pedido ao trabalhador de uma sessão.
+    fn dispatch(&self, session_id: u64, job: Job) -> Result<(), PacketError> {
+        self.worker(session_id)
+            .send(job)
+            .map_err(|_| PacketError::from(io::Error::from(io::ErrorKind::NotConnected)))
+    }
+
+    /// Envia um pacote a uma sessão, como em [`Server::send`].
+    ///
+    /// ## Parâmetros
+    /// - `session_id`: Sessão de destino.
+    /// - `packet`: Pacote a ser enviado.
+    ///
+    /// ## Retorno
+    /// - `Result<(), PacketError>`: `Ok` se o pedido foi entregue ao trabalhador, ou
+    ///   [`PacketError::Io`] com [`io::ErrorKind::NotConnected`] se o runtime foi
+    ///   encerrado.
+    pub fn send_to_player(&self, session_id: u64, packet: Packet) -> Result<(), PacketError> {
+        self.dispatch(session_id, Job::Send { session_id, packet })
+    }
+
+    /// Encerra uma sessão, como em [`Server::disconnect`].
+    ///
+    /// ## Parâmetros
+    /// - `session_id`: Sessão a encerrar.
+    /// - `reason`: Motivo informado ao cliente.
+    ///
+    /// ## Retorno
+    /// - `Result<(), PacketError>`: Como em [`ServerHandle::send_to_player`].
+    pub fn disconnect(&self, session_id: u64, reason: DisconnectReason) -> Result<(), PacketError> {
+        self.dispatch(session_id, Job::Disconnect { session_id, reason })
+    }
+
+    /// Retorna a quantidade de sessões estabelecidas em todos os trabalhadores, como
+    /// estava no último `poll` de cada um.
+    pub fn session_count(&self) -> usize {
+        self.sessions
+            .iter()
+            .map(|count| count.load(Ordering::Relaxed))
+            .sum()
+    }
+}
+
+/// Servidor conduzido por uma thread de leitura e um conjunto de trabalhadores.
+///
+/// Descartar o runtime o encerra como [`ServerRuntime::shutdown`], ignorando os erros.
+#[derive(Debug)]
+pub struct ServerRuntime {
+    /// Endereço local do socket.
+    local_addr: SocketAddr,
+    /// Acesso às sessões.
+    handle: ServerHandle,
+    /// Eventos de todos os trabalhadores.
+    events: Receiver<ServerEvent>,
+    /// Indica que as threads devem parar.
+    stopping: Arc<AtomicBool>,
+    /// Thread de leitura do socket.
+    reader: Option<JoinHandle<io::Result<()>>>,
+    /// Threads dos trabalhadores.
+    workers: Vec<JoinHandle<io::Result<()>>>,
+}
+
+impl ServerRuntime {
+    /// Inicia o runtime com servidores na configuração padrão.
+    ///
+    /// ## Parâmetros
+    /// - `transport`: Transporte já associado ao endereço do servidor; seus limites de
+    ///   [`UdpTransport::parse_config`] valem para todos os trabalhadores.
+    /// - `config`: Quantidade de trabalhadores e intervalo entre os `poll`.
+    ///
+    /// ## Retorno
+    /// - `io::Result<Self>`: O runtime já em execução, ou o erro do sistema ao
+    ///   configurar o socket ou criar as threads.
+    pub fn start(transport: UdpTransport, config: RuntimeConfig) -> io::Result<Self> {
+        Self::start_with(transport, config, |server| server)
+    }
+
+    /// Inicia o runtime, configurando o servidor de cada trabalhador.
+    ///
+    /// ## Parâmetros
+    /// - `transport`: Como em [`ServerRuntime::start`].
+    /// - `config`: Como em [`ServerRuntime::start`].
+    /// - `configure`: Função chamada na thread de cada trabalhador com o seu servidor,
+    ///   para definir tempo limite, ganchos, proteção contra inundação e os demais
+    ///   `with_*` de [`Server`].
+    ///
+    /// ## Retorno
+    /// - `io::Result<Self>`: Como em [`ServerRuntime::start`].
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use std::time::Duration;
+    /// use packet::runtime::{RuntimeConfig, ServerRuntime};
+    /// use packet::transport::UdpTransport;
+    ///
+    /// let transport = UdpTransport::bind("127.0.0.1:0").unwrap();
+    /// let config = RuntimeConfig::new().with_workers(4);
+    /// let runtime = ServerRuntime::start_with(transport, config, |server| {
+    ///     server.with_timeout(Duration::from_secs(5))
+    /// })
+    /// .unwrap();
+    /// assert_eq!(runtime.workers(), 4);
+    /// assert_eq!(runtime.handle().session_count(), 0);
+    /// ```
+    pub fn start_with(
+        transport: UdpTransport,
+        config: RuntimeConfig,
+        configure: impl Fn(Server<ShardTransport>) -> Server<ShardTransport> + Send + Sync + 'static,
+    ) -> io::Result<Self> {
+        Self::start_with_clock(transport, config, Arc::new(SystemClock), configure)
+    }
+
+    /// Inicia o runtime como [`ServerRuntime::start_with`], com os trabalhadores
+    /// conduzidos por um relógio.
+    ///
+    /// Cada servidor é criado por [`Server::from_transport_with_clock`] e recebe
+    /// [`Clock::now`] em cada `poll`. O intervalo de [`RuntimeConfig::tick`] e a espera
+    /// pelos datagramas continuam no tempo real.
+    ///
+    /// ## Parâmetros
+    /// - `transport`: Como em [`ServerRuntime::start`].
+    /// - `config`: Como em [`ServerRuntime::start`].
+    /// - `clock`: Relógio lido pelos trabalhadores.
+    /// - `configure`: Como em [`ServerRuntime::start_with`].
+    ///
+    /// ## Retorno
+    /// - `io::Result<Self>`: Como em [`ServerRuntime::start`].
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    /// use packet::clock::{Clock, MockClock};
+    /// use packet::connection::{Client, ServerEvent};
+    /// use packet::runtime::{RuntimeConfig, ServerRuntime};
+    /// use packet::transport::UdpTransport;
+    ///
+    /// let clock = Arc::new(MockClock::new(0));
+    /// let transport = UdpTransport::bind("127.0.0.1:0").unwrap();
+    /// let config = RuntimeConfig::new().with_workers(2);
+    /// let runtime =
+    ///     ServerRuntime::start_with_clock(transport, config, clock.clone(), |server| server)
+    ///         .unwrap();
+    /// let mut client = Client::connect(runtime.local_addr(), clock.now()).unwrap();
+    /// let mut session_id = None;
+    /// // Até o primeiro ping do cliente ser respondido.
+    /// while session_id.is_none() || client.rtt().is_none() {
+    ///     client.poll(clock.now()).unwrap();
+    ///     if let Some(ServerEvent::Connected { session_id: id, .. }) =
+    ///         runtime.recv_event(Duration::from_millis(5))
+    ///     {
+    ///         session_id = Some(id);
+    ///     }
+    /// }
+    /// let session_id = session_id.unwrap();
+    ///
+    /// // O cliente se cala; o tempo real passa, mas a sessão só expira quando o
+    /// // relógio avança.
+    /// assert_eq!(runtime.recv_event(Duration::from_millis(200)), None);
+    /// clock.advance(60_000);
+    /// assert_eq!(
+    ///     runtime.recv_event(Duration::from_secs(5)),
+    ///     Some(ServerEvent::TimedOut { session_id })
+    /// );
+    /// ```
+    pub fn start_with_clock(
+        transport: UdpTransport,
+        config: RuntimeConfig,
+        clock: Arc<dyn Clock + Send + Sync>,
+        configure: impl Fn(Server<ShardTransport>) -> Server<ShardTransport> + Send + Sync + 'static,
+    ) -> io::Result<Self> {
+        let local_addr = transport.local_addr()?;
+        transport.get_ref().set_nonblocking(false)?;
+        transport.get_ref().set_read_timeout(Some(config.tick))?;
+        let socket = Arc::new(transport.get_ref().try_clone()?);
+        let configure: Arc<Configure> = Arc::new(configure);
+        let stopping = Arc::new(AtomicBool::new(false));
+        let sessions: Arc<[AtomicUsize]> =
+            (0..config.workers()).map(|_| AtomicUsize::new(0)).collect();
+        let (events_tx, events) = mpsc::channel();
+
+        let mut senders = Vec::with_capacity(config.workers());
+        let mut workers = Vec::with_capacity(config.workers());
+        for index in 0..config.workers() {
+            let (jobs_tx, jobs) = mpsc::channel();
+            senders.push(jobs_tx);
+            let worker = Worker {
+                index,
+                jobs,
+                events: events_tx.clone(),
+                sessions: Arc::clone(&sessions),
+                tick: config.tick,
+                clock: Arc::clone(&clock),
+                stopping: Arc::clone(&stopping),
+            };
+            let transport = ShardTransport {
+                socket: Arc::clone(&socket),
+                inbox: VecDeque::new(),
+            };
+            let configure = Arc::clone(&configure);
+            let spawned = thread::Builder::new()
+                .name(format!("packet-worker-{index}"))
+                .spawn(move || worker.run(transport, config.workers(), &*configure));
+            workers.push(spawned?);
+        }
+
+        let handle = ServerHandle {
+            workers: senders.into(),
+            sessions,
+        };
+        let reader = Reader {
+            transport,
+            workers: Arc::clone(&handle.workers),
+            stopping: Arc::clone(&stopping),
+            hasher: RandomState::new(),
+        };
+        let reader = thread::Builder::new()
+            .name("packet-reader".into())
+            .spawn(move || reader.run())?;
+
+        Ok(Self {
+            local_addr,
+            handle,
+            events,
+            stopping,
+            reader: Some(reader),
+            workers,
+        })
+    }
+
+    /// Retorna o endereço local do servidor.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Retorna a quantidade de trabalhadores.
+    pub fn workers(&self) -> usize {
+        self.handle.workers.len()
+    }
+
+    /// Retorna um acesso às sessões que pode ser levado a outras threads.
+    pub fn handle(&self) -> ServerHandle {
+        self.handle.clone()
+    }
+
+    /// Aguarda o próximo evento de qualquer trabalhador.
+    ///
+    /// ## Parâmetros
+    /// - `timeout`: Tempo máximo de espera.
+    ///
+    /// ## Retorno
+    /// - `Option<ServerEvent>`: O evento, ou `None` se nenhum chegou a tempo.
+    pub fn recv_event(&self, timeout: Duration) -> Option<ServerEvent> {
+        self.events.recv_timeout(timeout).ok()
+    }
+
+    /// Retorna os eventos já produzidos pelos trabalhadores, sem esperar.
+    pub fn events(&self) -> Vec<ServerEvent> {
+        self.events.try_iter().collect()
+    }
+
+    /// Encerra a leitura e os trabalhadores e aguarda as threads.
+    ///
+    /// As sessões não são avisadas; use [`ServerHandle::disconnect`] antes para
+    /// encerrá-las com um motivo.
+    ///
+    /// ## Retorno
+    /// - `io::Result<()>`: `Ok`, ou o primeiro erro de E/S que parou uma das threads.
+    pub fn shutdown(mut self) -> io::Result<()> {
+        self.stop()
+    }
+
+    /// Sinaliza o encerramento e aguarda as threads.
+    fn stop(&mut self) -> io::Result<()> {
+        self.stopping.store(true, Ordering::Relaxed);
+        let mut result = Ok(());
+        let reader = self.reader.take().into_iter();
+        for thread in reader.chain(self.workers.drain(..)) {
+            let finished = thread
+                .join()
+                .unwrap_or_else(|_| Err(io::Error::other("thread do runtime em pânico")));
+            result = result.and(finished);
+        }
+        result
+    }
+}
+
+impl Drop for ServerRuntime {
+    fn drop(&mut self) {
+        let _ = self.stop();
+    }
+}
+
+/// Thread de leitura do socket.
+struct Reader {
+    /// Transporte com tempo limite de leitura.
+    transport: UdpTransport,
+    /// Filas dos trabalhadores.
+    workers: Arc<[Sender<Job>]>,
+    /// Indica que a thread deve parar.
+    stopping: Arc<AtomicBool>,
+    /// Distribuição das origens entre os trabalhadores.
+    hasher: RandomState,
+}
+
+impl Reader {
+    /// Lê os datagramas e os entrega ao trabalhador da origem até o encerramento.
+    fn run(mut self) -> io::Result<()> {
+        let workers = Arc::clone(&self.workers);
+        while !self.stopping.load(Ordering::Relaxed) {
+            let datagram = match self.transport.recv_from() {
+                Ok(Some(datagram)) => datagram,
+                Ok(None) => continue,
+                Err(error) if error.kind() == io::ErrorKind::TimedOut => continue,
+                Err(error) if error.kind() == io::ErrorKind::ConnectionReset => continue,
+                Err(error) => return Err(error),
+            };
+            let index = self.hasher.hash_one(datagram.0) % workers.len() as u64;
+            // Um trabalhador que parou por erro de E/S perde os datagramas da sua parte.
+            let _ = workers[index as usize].send(Job::Datagram(datagram));
+        }
+        Ok(())
+    }
+}
+
+/// Thread de um trabalhador.
+struct Worker {
+    /// Posição do trabalhador.
+    index: usize,
+    /// Pedidos recebidos.
+    jobs: Receiver<Job>,
+    /// Eventos do servidor.
+    events: Sender<ServerEvent>,
+    /// Sessões de cada trabalhador.
+    sessions: Arc<[AtomicUsize]>,
+    /// Intervalo máximo entre dois `poll`.
+    tick: Duration,
+    /// Relógio dos instantes de cada `poll`.
+    clock: SharedClock,
+    /// Indica que a thread deve parar.
+    stopping: Arc<AtomicBool>,
+}
+
+impl Worker {
+    /// Conduz o servidor da parte até as filas serem fechadas.
+    fn run(
+        self,
+        transport: ShardTransport,
+        workers: usize,
+        configure: &Configure,
+    ) -> io::Result<()> {
+        let server = Server::from_transport_with_clock(transport, &&*self.clock)?
+            .with_session_ids(self.index as u64 + 1, workers as u64);
+        let mut server = configure(server);
+        while !self.stopping.load(Ordering::Relaxed) {
+            match self.jobs.recv_timeout(self.tick) {
+                Ok(job) => {
+                    apply(&mut server, job);
+                    for job in self.jobs.try_iter() {
+                        apply(&mut server, job);
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+            for event in server.poll(self.clock.now())? {
+                // O runtime foi descartado; os eventos não têm mais destino.
+                let _ = self.events.send(event);
+            }
+            self.sessions[self.index].store(server.session_count(), Ordering::Relaxed);
+        }
+        Ok(())
+    }
+}
+
+/// Executa um pedido no servidor de um trabalhador.
+fn apply(server: &mut Server<ShardTransport>, job: Job) {
+    match job {
+        Job::Datagram(datagram) => server.transport_mut().inbox.push_back(datagram),
+        Job::Send { session_id, packet } => {
+            let _ = server.send(session_id, packet);
+        }
+        Job::Disconnect { session_id, reason } => {
+            let _ = server.disconnect(session_id, reason);
+        }
+    }
+}
@@ -0,0 +1,274 @@
+//! # Módulo `sequence`
+//!
+//! Armazenamento de dados por sequência em um vetor de tamanho fixo.
+//!
+//! [`SequenceBuffer`] é a estrutura usada por confiabilidade, descarte de duplicados
+//! e ordenação: cada sequência ocupa a posição `sequence % capacidade`, e a sequência
+//! guardada junto do valor distingue uma entrada atual de uma antiga que ocupava a
+//! mesma posição antes de as sequências darem a volta.
+//!
+//! As sequências `u32` dão a volta em sessões longas, então nunca são comparadas com
+//! `<` e `>`: [`sequence_greater_than`] e [`sequence_less_than`] as comparam na ordem
+//! circular, em que uma sequência é posterior às `u32::MAX / 2` que a antecedem.
+//! [`ReplayWindow`] usa essa ordem para descartar pacotes repetidos ou antigos demais.
+
+/// Vetor de tamanho fixo indexado pela sequência.
+///
+/// As sequências são comparadas com aritmética circular, como em
+/// [`ordered`](crate::ordered): inserções mais antigas que a janela de `capacity`
+/// sequências terminando na mais recente são recusadas.
+///
+/// ## Exemplos
+/// ```rust
+/// use packet::sequence::SequenceBuffer;
+///
+/// let mut buffer = SequenceBuffer::new(4);
+/// assert!(buffer.insert(u32::MAX, "a"));
+/// assert!(buffer.insert(1, "b"));
+/// assert_eq!(buffer.get(u32::MAX), Some(&"a"));
+///
+/// // A sequência 5 ocupa a posição da 1, que deixa de existir.
+/// assert!(buffer.insert(5, "c"));
+/// assert!(!buffer.exists(1));
+/// assert!(!buffer.exists(u32::MAX));
+/// assert_eq!(buffer.get(5), Some(&"c"));
+///
+/// // Sequências fora da janela são recusadas.
+/// assert!(!buffer.insert(1, "d"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct SequenceBuffer<T> {
+    /// Entradas com a sequência que as ocupa.
+    slots: Vec<Option<(u32, T)>>,
+    /// Sequência mais recente inserida; `None` antes da primeira inserção.
+    newest: Option<u32>,
+}
+
+impl<T> SequenceBuffer<T> {
+    /// Cria um buffer vazio.
+    ///
+    /// ## Parâmetros
+    /// - `capacity`: Quantidade de sequências mantidas; no mínimo 1.
+    ///
+    /// ## Retorno
+    /// - `Self`: Um buffer com todas as posições vazias.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            slots: std::iter::repeat_with(|| None)
+                .take(capacity.max(1))
+                .collect(),
+            newest: None,
+        }
+    }
+
+    /// Retorna a quantidade de sequências mantidas.
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Retorna a sequência mais recente inserida.
+    pub fn newest(&self) -> Option<u32> {
+        self.newest
+    }
+
+    /// Guarda um valor para a sequência, substituindo o que ocupava sua posição.
+    ///
+    /// Ao avançar a sequência mais recente, as posições das sequências puladas são
+    /// esvaziadas.
+    ///
+    /// ## Parâmetros
+    /// - `sequence`: Sequência do valor.
+    /// - `value`: Valor guardado.
+    ///
+    /// ## Retorno
+    /// - `bool`: `false` se a sequência é mais antiga que a janela do buffer e o valor
+    ///   foi descartado.
+    pub fn insert(&mut self, sequence: u32, value: T) -> bool {
+        let capacity = self.slots.len() as u32;
+        match self.newest {
+            Some(newest) if sequence_less_than(sequence, newest) => {
+                if newest.wrapping_sub(sequence) >= capacity {
+                    return false;
+                }
+            }
+            Some(newest) => {
+                let skipped = sequence.wrapping_sub(newest).min(capacity);
+                for offset in 1..skipped {
+                    let index = self.index(sequence.wrapping_sub(offset));
+                    self.slots[index] = None;
+                }
+                self.newest = Some(sequence);
+            }
+            None => self.newest = Some(sequence),
+        }
+
+        let index = self.index(sequence);
+        self.slots[index] = Some((sequence, value));
+        true
+    }
+
+    /// Retorna o valor guardado para a sequência.
+    ///
+    /// ## Parâmetros
+    /// - `sequence`: Sequência procurada.
+    ///
+    /// ## Retorno
+    /// - `Option<&T>`: O valor, ou `None` se a posição está vazia ou ocupada por outra
+    ///   sequência.
+    pub fn get(&self, sequence: u32) -> Option<&T> {
+        match &self.slots[self.index(sequence)] {
+            Some((stored, value)) if *stored == sequence => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Retorna uma referência mutável ao valor guardado para a sequência.
+    pub fn get_mut(&mut self, sequence: u32) -> Option<&mut T> {
+        let index = self.index(sequence);
+        match &mut self.slots[index] {
+            Some((stored, value)) if *stored == sequence => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Indica se há um valor guardado para a sequência.
+    pub fn exists(&self, sequence: u32) -> bool {
+        self.get(sequence).is_some()
+    }
+
+    /// Remove e retorna o valor guardado para a sequência.
+    pub fn remove(&mut self, sequence: u32) -> Option<T> {
+        let index = self.index(sequence);
+        match &self.slots[index] {
+            Some((stored, _)) if *stored == sequence => {
+                self.slots[index].take().map(|(_, value)| value)
+            }
+            _ => None,
+        }
+    }
+
+    /// Posição da sequência em `slots`.
+    fn index(&self, sequence: u32) -> usize {
+        sequence as usize % self.slots.len()
+    }
+}
+
+/// Quantidade de sequências lembradas por uma [`ReplayWindow`] padrão.
+pub const DEFAULT_REPLAY_WINDOW_SIZE: usize = 256;
+
+/// Classificação de uma sequência por [`ReplayWindow::check`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReplayStatus {
+    /// A sequência ainda não foi vista e foi registrada.
+    Accepted,
+    /// A sequência já foi vista.
+    Duplicate,
+    /// A sequência é mais antiga que a janela e não pode mais ser verificada.
+    Stale,
+}
+
+/// Janela deslizante das sequências recebidas, para descartar repetições.
+///
+/// A janela termina na sequência mais recente aceita e cobre as `size` anteriores:
+/// sequências dentro dela são aceitas uma única vez, mesmo fora de ordem, e
+/// sequências anteriores a ela são recusadas, o que impede que um pacote capturado
+/// seja reproduzido mais tarde. As comparações usam a ordem circular, de modo que a
+/// volta de `u32::MAX` para `0` é apenas mais um avanço.
+///
+/// ## Exemplos
+/// ```rust
+/// use packet::sequence::{ReplayStatus, ReplayWindow};
+///
+/// let mut window = ReplayWindow::new(64);
+/// assert_eq!(window.check(u32::MAX - 1), ReplayStatus::Accepted);
+/// assert_eq!(window.check(1), ReplayStatus::Accepted);
+/// // Fora de ordem, mas dentro da janela.
+/// assert_eq!(window.check(u32::MAX), ReplayStatus::Accepted);
+/// assert_eq!(window.check(u32::MAX), ReplayStatus::Duplicate);
+///
+/// assert_eq!(window.check(100), ReplayStatus::Accepted);
+/// assert_eq!(window.check(2), ReplayStatus::Stale);
+/// assert_eq!(window.newest(), Some(100));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ReplayWindow {
+    /// Sequências aceitas dentro da janela.
+    received: SequenceBuffer<()>,
+}
+
+impl ReplayWindow {
+    /// Cria uma janela sem sequências aceitas.
+    ///
+    /// ## Parâmetros
+    /// - `size`: Quantidade de sequências lembradas; no mínimo 1.
+    ///
+    /// ## Retorno
+    /// - `Self`: A janela.
+    pub fn new(size: usize) -> Self {
+        Self {
+            received: SequenceBuffer::new(size),
+        }
+    }
+
+    /// Retorna a quantidade de sequências lembradas.
+    pub fn size(&self) -> usize {
+        self.received.capacity()
+    }
+
+    /// Retorna a sequência mais recente aceita.
+    pub fn newest(&self) -> Option<u32> {
+        self.received.newest()
+    }
+
+    /// Classifica a sequência e a registra se ainda não foi vista.
+    ///
+    /// ## Parâmetros
+    /// - `sequence`: Sequência do pacote recebido.
+    ///
+    /// ## Retorno
+    /// - `ReplayStatus`: [`ReplayStatus::Accepted`] se o pacote deve ser processado.
+    pub fn check(&mut self, sequence: u32) -> ReplayStatus {
+        if self.received.exists(sequence) {
+            ReplayStatus::Duplicate
+        } else if self.received.insert(sequence, ()) {
+            ReplayStatus::Accepted
+        } else {
+            ReplayStatus::Stale
+        }
+    }
+}
+
+impl Default for ReplayWindow {
+    /// Retorna uma janela de [`DEFAULT_REPLAY_WINDOW_SIZE`] sequências.
+    fn default() -> Self {
+        Self::new(DEFAULT_REPLAY_WINDOW_SIZE)
+    }
+}
+
+/// Indica se a sequência `a` é posterior a `b` na ordem circular das sequências.
+///
+/// ## Parâmetros
+/// - `a`: Sequência comparada.
+/// - `b`: Sequência de referência.
+///
+/// ## Retorno
+/// - `bool`: `true` se `a` está até `u32::MAX / 2 + 1` sequências à frente de `b`.
+///
+/// ## Exemplos
+/// ```rust
+/// use packet::sequence::sequence_greater_than;
+///
+/// assert!(sequence_greater_than(2, 1));
+/// assert!(sequence_greater_than(0, u32::MAX));
+/// assert!(!sequence_greater_than(u32::MAX, 0));
+/// assert!(!sequence_greater_than(7, 7));
+/// ```
+pub fn sequence_greater_than(a: u32, b: u32) -> bool {
+    sequence_less_than(b, a)
+}
+
+/// Indica se a sequência `a` é anterior a `b` na ordem circular das sequências, o
+/// inverso de [`sequence_greater_than`].
+pub fn sequence_less_than(a: u32, b: u32) -> bool {
+    a.wrapping_sub(b) > u32::MAX / 2
+}
@@ -0,0 +1,128 @@
+//! # Módulo `serialize`
+//!
+//! Payloads tipados serializados com `serde`, disponível com o recurso `serde`.
+//!
+//! [`Packet::from_message`] serializa qualquer valor [`Serialize`] no formato
+//! escolhido na construção e grava o formato no campo `content_type` do cabeçalho;
+//! [`Packet::decode_payload`] lê o campo e desserializa com o mesmo formato, de modo
+//! que o receptor não precisa saber de antemão como o remetente codificou a
+//! mensagem. `bincode` está sempre disponível com o recurso; `postcard`, mais
+//! compacto, exige também o recurso `postcard`.
+//!
+//! ## Exemplos
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use packet::Packet;
+//! use packet::payload::ContentType;
+//! use packet::serialize::Format;
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Chat {
+//!     from: u64,
+//!     text: String,
+//! }
+//!
+//! let chat = Chat { from: 7, text: "gg".into() };
+//! let packet = Packet::from_message(0x81, 1, 7, Format::Bincode, &chat).unwrap();
+//! assert_eq!(packet.content_type(), ContentType::Bincode);
+//!
+//! let received = Packet::from_bytes(&packet.to_bytes()).unwrap();
+//! assert_eq!(received.decode_payload::<Chat>(), Ok(chat));
+//! ```
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::payload::ContentType;
+use crate::{Packet, PacketError};
+
+/// Formato de serialização usado por [`Packet::from_message`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Format {
+    /// `bincode`, com a configuração padrão do crate.
+    #[default]
+    Bincode,
+    /// `postcard`, com inteiros de tamanho variável; exige o recurso `postcard`.
+    #[cfg(feature = "postcard")]
+    Postcard,
+}
+
+impl From<Format> for ContentType {
+    fn from(format: Format) -> Self {
+        match format {
+            Format::Bincode => ContentType::Bincode,
+            #[cfg(feature = "postcard")]
+            Format::Postcard => ContentType::Postcard,
+        }
+    }
+}
+
+impl Packet {
+    /// Cria um pacote cujo payload é uma mensagem serializada com `serde`.
+    ///
+    /// ## Parâmetros
+    /// - `message_type`: Tipo da mensagem.
+    /// - `sequence`: Sequência do pacote.
+    /// - `player_id`: Identificador único do jogador.
+    /// - `format`: Formato de serialização, gravado no `content_type` do cabeçalho.
+    /// - `message`: Valor a ser serializado.
+    ///
+    /// ## Retorno
+    /// - `Result<Self, PacketError>`: O pacote, ou [`PacketError::InvalidPayload`] se o
+    ///   valor não pôde ser serializado no formato.
+    pub fn from_message<T: Serialize + ?Sized>(
+        message_type: u8,
+        sequence: u32,
+        player_id: u64,
+        format: Format,
+        message: &T,
+    ) -> Result<Self, PacketError> {
+        let payload = match format {
+            Format::Bincode => bincode::serialize(message).ok(),
+            #[cfg(feature = "postcard")]
+            Format::Postcard => postcard::to_allocvec(message).ok(),
+        }
+        .ok_or(PacketError::InvalidPayload {
+            reason: "mensagem não serializável no formato escolhido",
+        })?;
+        Ok(Self::new(message_type, sequence, player_id, payload).with_content_type(format.into()))
+    }
+
+    /// Desserializa o payload com o formato indicado pelo `content_type` do cabeçalho.
+    ///
+    /// ## Retorno
+    /// - `Result<T, PacketError>`: A mensagem, ou [`PacketError::InvalidPayload`] se o
+    ///   `content_type` não é um formato `serde` disponível ou se o payload não é um `T`
+    ///   válido nesse formato.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::{Packet, PacketError};
+    /// use packet::serialize::Format;
+    ///
+    /// let packet = Packet::from_message(0x81, 1, 7, Format::Bincode, &(1u8, 2u16)).unwrap();
+    /// assert_eq!(packet.decode_payload::<(u8, u16)>(), Ok((1, 2)));
+    /// assert!(packet.decode_payload::<(u64, u64)>().is_err());
+    ///
+    /// let raw = Packet::new(0x81, 1, 7, vec![1, 2, 0]);
+    /// assert!(matches!(
+    ///     raw.decode_payload::<(u8, u16)>(),
+    ///     Err(PacketError::InvalidPayload { .. })
+    /// ));
+    /// ```
+    pub fn decode_payload<T: DeserializeOwned>(&self) -> Result<T, PacketError> {
+        let message = match self.content_type() {
+            ContentType::Bincode => bincode::deserialize(&self.payload).ok(),
+            #[cfg(feature = "postcard")]
+            ContentType::Postcard => postcard::from_bytes(&self.payload).ok(),
+            _ => {
+                return Err(PacketError::InvalidPayload {
+                    reason: "content_type não é um formato serde disponível",
+                })
+            }
+        };
+        message.ok_or(PacketError::InvalidPayload {
+            reason: "payload inválido para o tipo no formato indicado",
+        })
+    }
+}
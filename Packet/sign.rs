@@ -0,0 +1,107 @@
+//! # Módulo `sign`
+//!
+//! Assinaturas Ed25519 de pacotes, disponível com o recurso `crypto`.
+//!
+//! Diferentemente de uma autenticação com segredo compartilhado, quem verifica precisa
+//! apenas da chave pública de quem assina: é o modelo adequado para tráfego de um
+//! servidor autoritativo ou de *broadcast*, em que nenhum cliente deve ser capaz de
+//! forjar pacotes do servidor.
+//!
+//! A assinatura cobre [`Packet::canonical_bytes`] do pacote ainda sem a assinatura e
+//! com a flag [`PacketFlags::SIGNED`] já ativa, e é anexada ao fim do payload. Cada
+//! pacote assinado fica [`SIGNATURE_SIZE`] (64) bytes maior; relays podem decrementar
+//! o `ttl` e anexar confirmações sem invalidá-la.
+//!
+//! ## Exemplos
+//! ```rust
+//! use ed25519_dalek::SigningKey;
+//! use packet::Packet;
+//!
+//! let signing_key = SigningKey::from_bytes(&[7; 32]);
+//! let verifying_key = signing_key.verifying_key();
+//!
+//! let signed = Packet::new(1, 1, 7, vec![1, 2, 3]).sign(&signing_key);
+//! assert_eq!(signed.payload.len(), 3 + packet::sign::SIGNATURE_SIZE);
+//!
+//! let mut received = Packet::from_bytes(&signed.to_bytes()).unwrap();
+//! received.decrement_ttl().unwrap();
+//! assert!(received.verify(&verifying_key));
+//! assert_eq!(received.unsigned_payload(), Some(&[1, 2, 3][..]));
+//!
+//! received.payload[0] = 9;
+//! assert!(!received.verify(&verifying_key));
+//! ```
+
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
+
+use crate::{Packet, PacketFlags};
+
+/// Tamanho, em bytes, da assinatura anexada ao payload.
+pub const SIGNATURE_SIZE: usize = Signature::BYTE_SIZE;
+
+impl Packet {
+    /// Assina o pacote, anexando a assinatura Ed25519 ao fim do payload.
+    ///
+    /// Alterações posteriores no payload ou em campos fora dos normalizados por
+    /// [`Packet::canonical_bytes`] invalidam a assinatura.
+    ///
+    /// ## Parâmetros
+    /// - `signing_key`: Chave privada de quem assina.
+    ///
+    /// ## Retorno
+    /// - `Packet`: O pacote com a flag [`PacketFlags::SIGNED`], o payload acrescido de
+    ///   [`SIGNATURE_SIZE`] bytes e `payload_size` e checksum atualizados.
+    pub fn sign(mut self, signing_key: &SigningKey) -> Packet {
+        self.header.flags.insert(PacketFlags::SIGNED);
+        self.refresh_header();
+
+        let signature = signing_key.sign(&self.canonical_bytes());
+        self.payload.extend_from_slice(&signature.to_bytes());
+        self.refresh_header();
+        self
+    }
+
+    /// Verifica a assinatura Ed25519 anexada ao payload.
+    ///
+    /// ## Parâmetros
+    /// - `verifying_key`: Chave pública de quem assinou.
+    ///
+    /// ## Retorno
+    /// - `bool`: `true` se o pacote tem a flag [`PacketFlags::SIGNED`] e a assinatura é
+    ///   válida para `verifying_key`.
+    pub fn verify(&self, verifying_key: &VerifyingKey) -> bool {
+        let Some((payload, signature)) = self.split_signature() else {
+            return false;
+        };
+        let signature = Signature::from_bytes(signature);
+
+        let mut unsigned = Packet {
+            header: self.header.clone(),
+            payload: payload.to_vec(),
+        };
+        unsigned.header.payload_size = payload.len() as u32;
+        verifying_key
+            .verify_strict(&unsigned.canonical_bytes(), &signature)
+            .is_ok()
+    }
+
+    /// Retorna o payload sem a assinatura anexada.
+    ///
+    /// ## Retorno
+    /// - `Option<&[u8]>`: Os dados assinados, ou `None` se o pacote não tem a flag
+    ///   [`PacketFlags::SIGNED`] ou é curto demais para conter uma assinatura. A
+    ///   assinatura não é verificada; use [`Packet::verify`].
+    pub fn unsigned_payload(&self) -> Option<&[u8]> {
+        self.split_signature().map(|(payload, _)| payload)
+    }
+
+    /// Separa o payload assinado da assinatura.
+    fn split_signature(&self) -> Option<(&[u8], &[u8; SIGNATURE_SIZE])> {
+        if !self.header.flags.contains(PacketFlags::SIGNED) {
+            return None;
+        }
+        let split = self.payload.len().checked_sub(SIGNATURE_SIZE)?;
+        let (payload, signature) = self.payload.split_at(split);
+        Some((payload, signature.try_into().ok()?))
+    }
+}
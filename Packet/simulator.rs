@@ -0,0 +1,382 @@
+//! # Módulo `simulator`
+//!
+//! Simulação de condições de rede ruins para testes.
+//!
+//! [`SimulatedTransport`] envolve outro [`Transport`] e aplica aos pacotes enviados as
+//! condições de um [`NetworkConditions`]: latência com jitter, perda, duplicação,
+//! reordenação e limite de banda. Os pacotes atrasados ficam retidos e são repassados
+//! ao transporte envolvido quando vencem, a cada envio, recepção ou
+//! [`SimulatedTransport::flush`]. Só o sentido de envio é afetado; para simular os dois
+//! sentidos, envolva o transporte de cada lado.
+//!
+//! Os sorteios vêm de um gerador próprio iniciado com uma semente, e o tempo de um
+//! [`Clock`]: com um [`MockClock`](crate::clock::MockClock), a mesma semente e a mesma
+//! sequência de chamadas produzem sempre as mesmas entregas, nos mesmos instantes.
+//!
+//! ## Exemplos
+//! ```rust
+//! use packet::Packet;
+//! use packet::clock::MockClock;
+//! use packet::simulator::{NetworkConditions, SimulatedTransport};
+//! use packet::transport::{Transport, UdpTransport};
+//!
+//! let mut server = UdpTransport::bind("127.0.0.1:0").unwrap();
+//! let server_addr = server.local_addr().unwrap();
+//! let clock = MockClock::new(0);
+//! let conditions = NetworkConditions {
+//!     latency_ms: 50,
+//!     ..NetworkConditions::default()
+//! };
+//! let socket = UdpTransport::bind("127.0.0.1:0").unwrap();
+//! let mut client = SimulatedTransport::new(socket, conditions, 42).with_clock(&clock);
+//!
+//! client.send_packet(server_addr, &Packet::new(1, 1, 7, vec![1, 2, 3])).unwrap();
+//! assert_eq!(client.pending_count(), 1);
+//!
+//! // O pacote só chega ao socket depois da latência.
+//! clock.advance(50);
+//! client.flush().unwrap();
+//! assert_eq!(client.pending_count(), 0);
+//! let (_, received) = server.recv_packet().unwrap();
+//! assert_eq!(received.payload, [1, 2, 3]);
+//! ```
+
+use std::collections::BTreeMap;
+use std::f64::consts::TAU;
+use std::io;
+use std::net::SocketAddr;
+
+use crate::clock::{Clock, SystemClock};
+use crate::transport::Transport;
+use crate::{Packet, PacketError};
+
+/// Distribuição do jitter somado à latência de cada pacote.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum JitterDistribution {
+    /// Uniforme entre `-jitter_ms` e `+jitter_ms`.
+    #[default]
+    Uniform,
+    /// Normal com média zero e desvio padrão `jitter_ms`.
+    Normal,
+}
+
+/// Condições de rede aplicadas por um [`SimulatedTransport`].
+///
+/// O padrão é uma rede perfeita: sem atraso, perda, duplicação, reordenação nem limite
+/// de banda. As probabilidades vão de `0.0` a `1.0`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetworkConditions {
+    /// Atraso médio de cada pacote, em milissegundos.
+    pub latency_ms: u32,
+    /// Amplitude do jitter, em milissegundos; o atraso resultante nunca é negativo.
+    pub jitter_ms: u32,
+    /// Distribuição do jitter.
+    pub jitter_distribution: JitterDistribution,
+    /// Probabilidade de um pacote ser perdido.
+    pub loss: f64,
+    /// Probabilidade de um pacote ser entregue duas vezes.
+    pub duplication: f64,
+    /// Probabilidade de um pacote ser retido por mais [`NetworkConditions::reorder_delay_ms`],
+    /// chegando depois de pacotes enviados após ele.
+    pub reorder: f64,
+    /// Atraso adicional dos pacotes reordenados, em milissegundos.
+    pub reorder_delay_ms: u32,
+    /// Banda do enlace, em bytes por segundo; pacotes que excedem a banda esperam na
+    /// fila do enlace. `0` desativa o limite.
+    pub bandwidth: u32,
+}
+
+impl Default for NetworkConditions {
+    fn default() -> Self {
+        Self {
+            latency_ms: 0,
+            jitter_ms: 0,
+            jitter_distribution: JitterDistribution::default(),
+            loss: 0.0,
+            duplication: 0.0,
+            reorder: 0.0,
+            reorder_delay_ms: 0,
+            bandwidth: 0,
+        }
+    }
+}
+
+/// Transporte que aplica [`NetworkConditions`] aos pacotes enviados por outro
+/// [`Transport`].
+///
+/// ## Exemplos
+/// ```rust
+/// use std::net::SocketAddr;
+/// use packet::{Packet, PacketError};
+/// use packet::clock::MockClock;
+/// use packet::simulator::{NetworkConditions, SimulatedTransport};
+/// use packet::transport::Transport;
+///
+/// /// Transporte que apenas registra as sequências repassadas.
+/// #[derive(Default)]
+/// struct Recorder(Vec<u32>);
+///
+/// impl Transport for Recorder {
+///     fn send_packet(&mut self, _: SocketAddr, packet: &Packet) -> Result<usize, PacketError> {
+///         self.0.push(packet.header.sequence);
+///         Ok(packet.serialized_len())
+///     }
+///
+///     fn recv_packet(&mut self) -> Result<(SocketAddr, Packet), PacketError> {
+///         Err(std::io::Error::from(std::io::ErrorKind::WouldBlock).into())
+///     }
+/// }
+///
+/// let run = |seed| {
+///     let clock = MockClock::new(0);
+///     let conditions = NetworkConditions {
+///         latency_ms: 30,
+///         jitter_ms: 20,
+///         loss: 0.2,
+///         duplication: 0.1,
+///         ..NetworkConditions::default()
+///     };
+///     let mut transport =
+///         SimulatedTransport::new(Recorder::default(), conditions, seed).with_clock(&clock);
+///     let addr: SocketAddr = "127.0.0.1:4000".parse().unwrap();
+///     for sequence in 0..100 {
+///         transport.send_packet(addr, &Packet::new(1, sequence, 7, vec![0; 16])).unwrap();
+///         clock.advance(5);
+///     }
+///     clock.advance(100);
+///     transport.flush().unwrap();
+///     transport.into_inner().0
+/// };
+///
+/// // A mesma semente reproduz as mesmas perdas, duplicações e ordem de chegada.
+/// assert_eq!(run(7), run(7));
+/// assert_ne!(run(7), run(8));
+/// ```
+#[derive(Debug)]
+pub struct SimulatedTransport<T, C = SystemClock> {
+    /// Transporte que recebe os pacotes quando vencem.
+    inner: T,
+    /// Fonte de tempo.
+    clock: C,
+    /// Condições aplicadas aos pacotes enviados.
+    conditions: NetworkConditions,
+    /// Estado do gerador pseudoaleatório.
+    state: u64,
+    /// Última leitura do relógio, para acumular o tempo decorrido sem dar a volta.
+    last_millis: Option<u32>,
+    /// Milissegundos decorridos desde a primeira leitura do relógio.
+    elapsed: u64,
+    /// Instante em que o enlace termina de transmitir os pacotes já enviados.
+    link_free_at: u64,
+    /// Ordem de envio, que desempata pacotes com o mesmo instante de entrega.
+    next_order: u64,
+    /// Pacotes retidos, pelo instante de entrega e pela ordem de envio.
+    pending: BTreeMap<(u64, u64), (SocketAddr, Packet)>,
+}
+
+impl<T: Transport> SimulatedTransport<T> {
+    /// Envolve um transporte, medindo o tempo com [`SystemClock`].
+    ///
+    /// ## Parâmetros
+    /// - `inner`: Transporte que recebe os pacotes quando vencem.
+    /// - `conditions`: Condições aplicadas aos pacotes enviados.
+    /// - `seed`: Semente do gerador pseudoaleatório.
+    ///
+    /// ## Retorno
+    /// - `Self`: O transporte simulado, sem pacotes retidos.
+    pub fn new(inner: T, conditions: NetworkConditions, seed: u64) -> Self {
+        Self {
+            inner,
+            clock: SystemClock,
+            conditions,
+            state: seed,
+            last_millis: None,
+            elapsed: 0,
+            link_free_at: 0,
+            next_order: 0,
+            pending: BTreeMap::new(),
+        }
+    }
+}
+
+impl<T: Transport, C: Clock> SimulatedTransport<T, C> {
+    /// Substitui a fonte de tempo, por exemplo por uma
+    /// [`MockClock`](crate::clock::MockClock) emprestada.
+    ///
+    /// ## Parâmetros
+    /// - `clock`: Nova fonte de tempo; sua primeira leitura passa a ser o instante atual
+    ///   dos pacotes retidos.
+    ///
+    /// ## Retorno
+    /// - `SimulatedTransport<T, D>`: O transporte com o novo relógio.
+    pub fn with_clock<D: Clock>(self, clock: D) -> SimulatedTransport<T, D> {
+        SimulatedTransport {
+            inner: self.inner,
+            clock,
+            conditions: self.conditions,
+            state: self.state,
+            last_millis: None,
+            elapsed: self.elapsed,
+            link_free_at: self.link_free_at,
+            next_order: self.next_order,
+            pending: self.pending,
+        }
+    }
+
+    /// Retorna as condições aplicadas aos pacotes enviados.
+    pub fn conditions(&self) -> &NetworkConditions {
+        &self.conditions
+    }
+
+    /// Substitui as condições, valendo para os próximos envios.
+    ///
+    /// ## Parâmetros
+    /// - `conditions`: Novas condições; pacotes já retidos mantêm seu instante de entrega.
+    pub fn set_conditions(&mut self, conditions: NetworkConditions) {
+        self.conditions = conditions;
+    }
+
+    /// Retorna o transporte envolvido.
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Retorna o transporte envolvido, para configurá-lo.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Retorna o transporte envolvido, descartando os pacotes retidos.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Retorna a quantidade de pacotes retidos aguardando o instante de entrega.
+    pub fn pending_count(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Repassa ao transporte envolvido os pacotes cujo instante de entrega chegou.
+    ///
+    /// ## Retorno
+    /// - `Result<(), PacketError>`: O primeiro erro de envio do transporte envolvido;
+    ///   o pacote que falhou é descartado, e os demais continuam retidos.
+    pub fn flush(&mut self) -> Result<(), PacketError> {
+        let now = self.now();
+        self.release(now)
+    }
+
+    /// Lê o relógio e retorna os milissegundos decorridos desde a primeira leitura.
+    fn now(&mut self) -> u64 {
+        let millis = self.clock.now_millis();
+        if let Some(last) = self.last_millis {
+            self.elapsed += u64::from(millis.wrapping_sub(last));
+        }
+        self.last_millis = Some(millis);
+        self.elapsed
+    }
+
+    /// Repassa os pacotes que vencem até `now`, na ordem de entrega.
+    fn release(&mut self, now: u64) -> Result<(), PacketError> {
+        while let Some(entry) = self.pending.first_entry() {
+            if entry.key().0 > now {
+                break;
+            }
+            let (addr, packet) = entry.remove();
+            self.inner.send_packet(addr, &packet)?;
+        }
+        Ok(())
+    }
+
+    /// Sorteia o instante de entrega de um pacote de `len` bytes enviado em `now`.
+    fn delivery_time(&mut self, now: u64, len: usize) -> u64 {
+        let sent = match u64::from(self.conditions.bandwidth) {
+            0 => now,
+            bandwidth => {
+                let start = self.link_free_at.max(now);
+                self.link_free_at = start + (len as u64 * 1000).div_ceil(bandwidth);
+                self.link_free_at
+            }
+        };
+
+        let jitter = f64::from(self.conditions.jitter_ms);
+        let offset = match self.conditions.jitter_distribution {
+            JitterDistribution::Uniform => (self.next_f64() * 2.0 - 1.0) * jitter,
+            JitterDistribution::Normal => {
+                let radius = (-2.0 * (1.0 - self.next_f64()).ln()).sqrt();
+                radius * (TAU * self.next_f64()).cos() * jitter
+            }
+        };
+        let mut delay = (f64::from(self.conditions.latency_ms) + offset)
+            .max(0.0)
+            .round() as u64;
+        if self.chance(self.conditions.reorder) {
+            delay += u64::from(self.conditions.reorder_delay_ms);
+        }
+        sent + delay
+    }
+
+    /// Sorteia um evento com a probabilidade informada.
+    fn chance(&mut self, probability: f64) -> bool {
+        probability > 0.0 && self.next_f64() < probability
+    }
+
+    /// Sorteia um valor uniforme em `[0, 1)`.
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Gerador SplitMix64, válido para qualquer semente.
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+impl<T: Transport, C: Clock> Transport for SimulatedTransport<T, C> {
+    /// Aplica as condições ao pacote e o retém até seu instante de entrega.
+    ///
+    /// Pacotes perdidos também são contados como enviados, como em UDP.
+    fn send_packet(&mut self, addr: SocketAddr, packet: &Packet) -> Result<usize, PacketError> {
+        let now = self.now();
+        let len = packet.serialized_len();
+        if !self.chance(self.conditions.loss) {
+            let copies = if self.chance(self.conditions.duplication) {
+                2
+            } else {
+                1
+            };
+            for _ in 0..copies {
+                let at = self.delivery_time(now, len);
+                self.pending
+                    .insert((at, self.next_order), (addr, packet.clone()));
+                self.next_order += 1;
+            }
+        }
+        self.release(now)?;
+        Ok(len)
+    }
+
+    /// Repassa os pacotes vencidos e recebe do transporte envolvido.
+    fn recv_packet(&mut self) -> Result<(SocketAddr, Packet), PacketError> {
+        self.flush()?;
+        self.inner.recv_packet()
+    }
+
+    /// Repassa os pacotes vencidos e recebe do transporte envolvido.
+    fn recv_from(&mut self) -> io::Result<Option<(SocketAddr, Result<Packet, PacketError>)>> {
+        self.flush()?;
+        self.inner.recv_from()
+    }
+
+    fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.inner.local_addr()
+    }
+
+    fn set_nonblocking(&mut self, nonblocking: bool) -> io::Result<()> {
+        self.inner.set_nonblocking(nonblocking)
+    }
+}
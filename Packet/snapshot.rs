@@ -0,0 +1,149 @@
+//! # Módulo `snapshot`
+//!
+//! Montagem de snapshots de estado em pacotes que respeitam um limite de payload.
+//!
+//! O servidor serializa cada entidade separadamente e as acrescenta ao snapshot
+//! até que o próximo blob não caiba; nesse ponto o pacote atual é fechado e um
+//! novo é iniciado com a sequência seguinte. Os blobs são concatenados sem
+//! prefixo, portanto devem ser autodelimitados.
+//!
+//! ## Exemplos
+//! ```rust
+//! use packet::snapshot::SnapshotAssembler;
+//!
+//! let mut assembler = SnapshotAssembler::new(0x80, 1, 7, 8);
+//! let mut packets = Vec::new();
+//!
+//! for entity in [[1u8; 3], [2; 3], [3; 3]] {
+//!     if let Some(full) = assembler.add(&entity).unwrap() {
+//!         packets.push(full);
+//!     }
+//! }
+//! packets.push(assembler.finish());
+//!
+//! assert_eq!(packets.len(), 2);
+//! assert_eq!(packets[0].payload, [1, 1, 1, 2, 2, 2]);
+//! assert_eq!(packets[1].payload, [3, 3, 3]);
+//! assert_eq!(packets[1].header.sequence, 2);
+//! ```
+
+use crate::{Packet, PacketError, PacketHeader};
+
+/// Acumula blobs de entidades e os divide em pacotes de tamanho limitado.
+#[derive(Debug, Clone)]
+pub struct SnapshotAssembler {
+    /// Tipo da mensagem dos pacotes produzidos.
+    message_type: u8,
+    /// Sequência do próximo pacote a ser fechado.
+    next_sequence: u32,
+    /// Identificador único do jogador.
+    player_id: u64,
+    /// Tamanho máximo do payload de cada pacote.
+    max_payload: usize,
+    /// Payload do pacote em montagem.
+    current: Vec<u8>,
+}
+
+impl SnapshotAssembler {
+    /// Cria um montador com um limite de payload explícito.
+    ///
+    /// ## Parâmetros
+    /// - `message_type`: Tipo da mensagem dos pacotes produzidos.
+    /// - `first_sequence`: Sequência do primeiro pacote; as seguintes são consecutivas.
+    /// - `player_id`: Identificador único do jogador.
+    /// - `max_payload`: Tamanho máximo do payload de cada pacote, em bytes.
+    ///
+    /// ## Retorno
+    /// - `Self`: Um montador sem entidades.
+    pub fn new(message_type: u8, first_sequence: u32, player_id: u64, max_payload: usize) -> Self {
+        Self {
+            message_type,
+            next_sequence: first_sequence,
+            player_id,
+            max_payload,
+            current: Vec::new(),
+        }
+    }
+
+    /// Cria um montador cujos pacotes serializados cabem no MTU informado.
+    ///
+    /// ## Parâmetros
+    /// - `message_type`: Tipo da mensagem dos pacotes produzidos.
+    /// - `first_sequence`: Sequência do primeiro pacote; as seguintes são consecutivas.
+    /// - `player_id`: Identificador único do jogador.
+    /// - `mtu`: Tamanho máximo de cada pacote serializado, incluindo o cabeçalho.
+    ///
+    /// ## Retorno
+    /// - `Self`: Um montador sem entidades.
+    pub fn for_mtu(message_type: u8, first_sequence: u32, player_id: u64, mtu: usize) -> Self {
+        let max_payload = mtu.saturating_sub(PacketHeader::SIZE);
+        Self::new(message_type, first_sequence, player_id, max_payload)
+    }
+
+    /// Acrescenta uma entidade ao pacote atual, se couber.
+    ///
+    /// ## Parâmetros
+    /// - `entity_bytes`: Entidade serializada.
+    ///
+    /// ## Retorno
+    /// - `bool`: `false` se a entidade excederia o limite de payload; nesse caso nada é
+    ///   acrescentado e o chamador deve chamar [`SnapshotAssembler::finish`] antes de tentar
+    ///   novamente.
+    pub fn try_add(&mut self, entity_bytes: &[u8]) -> bool {
+        if self.current.len() + entity_bytes.len() > self.max_payload {
+            return false;
+        }
+        self.current.extend_from_slice(entity_bytes);
+        true
+    }
+
+    /// Acrescenta uma entidade, fechando o pacote atual quando ela não couber nele.
+    ///
+    /// ## Parâmetros
+    /// - `entity_bytes`: Entidade serializada.
+    ///
+    /// ## Retorno
+    /// - `Result<Option<Packet>, PacketError>`: O pacote fechado para dar lugar à entidade,
+    ///   se houver, ou [`PacketError::PayloadTooLarge`] se a entidade sozinha excede o limite.
+    pub fn add(&mut self, entity_bytes: &[u8]) -> Result<Option<Packet>, PacketError> {
+        if entity_bytes.len() > self.max_payload {
+            return Err(PacketError::PayloadTooLarge {
+                size: entity_bytes.len(),
+                limit: self.max_payload,
+            });
+        }
+        if self.try_add(entity_bytes) {
+            return Ok(None);
+        }
+
+        let full = self.finish();
+        self.current.extend_from_slice(entity_bytes);
+        Ok(Some(full))
+    }
+
+    /// Fecha o pacote atual, mesmo que vazio, e inicia o próximo.
+    ///
+    /// ## Retorno
+    /// - `Packet`: O pacote com as entidades acumuladas desde o último fechamento.
+    pub fn finish(&mut self) -> Packet {
+        let payload = std::mem::take(&mut self.current);
+        let packet = Packet::new(
+            self.message_type,
+            self.next_sequence,
+            self.player_id,
+            payload,
+        );
+        self.next_sequence = self.next_sequence.wrapping_add(1);
+        packet
+    }
+
+    /// Indica se nenhuma entidade foi acrescentada desde o último fechamento.
+    pub fn is_empty(&self) -> bool {
+        self.current.is_empty()
+    }
+
+    /// Retorna quantos bytes de payload ainda cabem no pacote atual.
+    pub fn remaining(&self) -> usize {
+        self.max_payload - self.current.len()
+    }
+}
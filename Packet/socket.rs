@@ -0,0 +1,189 @@
+//! # Módulo `socket`
+//!
+//! Envio e recepção de pacotes sobre um [`UdpSocket`].
+//!
+//! O sistema operacional descarta sem aviso os bytes de um datagrama que não cabem
+//! no buffer de recepção. [`PacketSocket::recv_from`] compara o tamanho declarado no
+//! cabeçalho com o recebido e reporta o truncamento em vez de interpretar um pacote
+//! cortado; use [`recommended_recv_buffer_size`](crate::recommended_recv_buffer_size)
+//! para dimensionar o buffer.
+
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+
+use crate::view::PacketRef;
+use crate::{instrument, Packet, PacketError, PacketHeader};
+
+/// Socket UDP que envia e recebe pacotes.
+///
+/// ## Exemplos
+/// ```rust
+/// use packet::{recommended_recv_buffer_size, Packet, PacketError};
+/// use packet::socket::PacketSocket;
+///
+/// let server = PacketSocket::bind("127.0.0.1:0").unwrap();
+/// let client = PacketSocket::bind("127.0.0.1:0").unwrap();
+/// let server_addr = server.local_addr().unwrap();
+///
+/// let packet = Packet::new(1, 1, 7, vec![0xAB; 300]);
+/// client.send_to(&packet, server_addr).unwrap();
+/// let mut buffer = vec![0; recommended_recv_buffer_size()];
+/// let (received, from) = server.recv_from(&mut buffer).unwrap();
+/// assert_eq!(received, packet);
+/// assert_eq!(from, client.local_addr().unwrap());
+///
+/// // Um buffer pequeno demais resulta em erro, não em um pacote cortado.
+/// client.send_to(&packet, server_addr).unwrap();
+/// let mut small = [0u8; 100];
+/// let error = server.recv_from(&mut small).unwrap_err();
+/// let error = error.get_ref().unwrap().downcast_ref::<PacketError>().unwrap();
+/// assert!(matches!(error, PacketError::Truncated { buffer: 100, .. }));
+/// ```
+#[derive(Debug)]
+pub struct PacketSocket {
+    /// Socket subjacente.
+    socket: UdpSocket,
+}
+
+impl PacketSocket {
+    /// Cria um socket associado ao endereço informado.
+    ///
+    /// ## Parâmetros
+    /// - `addr`: Endereço local; use a porta `0` para uma porta qualquer.
+    ///
+    /// ## Retorno
+    /// - `io::Result<Self>`: O socket ou o erro retornado pelo sistema.
+    pub fn bind(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        UdpSocket::bind(addr).map(Self::from_socket)
+    }
+
+    /// Envolve um socket já configurado.
+    ///
+    /// ## Parâmetros
+    /// - `socket`: Socket UDP associado a um endereço local.
+    ///
+    /// ## Retorno
+    /// - `Self`: O socket de pacotes.
+    pub fn from_socket(socket: UdpSocket) -> Self {
+        Self { socket }
+    }
+
+    /// Retorna o socket subjacente, para configurações como tempo limite de leitura.
+    pub fn get_ref(&self) -> &UdpSocket {
+        &self.socket
+    }
+
+    /// Retorna o endereço local do socket.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.socket.local_addr()
+    }
+
+    /// Envia um pacote em um único datagrama.
+    ///
+    /// ## Parâmetros
+    /// - `packet`: Pacote a ser enviado.
+    /// - `addr`: Endereço de destino.
+    ///
+    /// ## Retorno
+    /// - `io::Result<usize>`: Quantidade de bytes enviados.
+    pub fn send_to(&self, packet: &Packet, addr: impl ToSocketAddrs) -> io::Result<usize> {
+        self.socket.send_to(&packet.to_bytes(), addr)
+    }
+
+    /// Recebe um datagrama e o interpreta como um pacote, verificando seu checksum.
+    ///
+    /// ## Parâmetros
+    /// - `buf`: Buffer de recepção; veja
+    ///   [`recommended_recv_buffer_size`](crate::recommended_recv_buffer_size).
+    ///
+    /// ## Retorno
+    /// - `io::Result<(Packet, SocketAddr)>`: O pacote e o endereço de origem. Um
+    ///   datagrama maior que o buffer resulta em [`io::ErrorKind::InvalidData`] com
+    ///   [`PacketError::Truncated`], e um pacote inválido com o [`PacketError`]
+    ///   correspondente.
+    pub fn recv_from(&self, buf: &mut [u8]) -> io::Result<(Packet, SocketAddr)> {
+        let (len, from) = self.socket.recv_from(buf)?;
+        Ok((parse_datagram(&buf[..len], buf.len())?.to_packet(), from))
+    }
+
+    /// Conecta o socket a um único endereço remoto.
+    ///
+    /// Com o socket conectado, [`PacketSocket::send`] e [`PacketSocket::recv`] dispensam
+    /// o endereço a cada pacote, datagramas de outros endereços são descartados pelo
+    /// sistema e erros ICMP, como porta inacessível, são reportados nas operações
+    /// seguintes. É a forma indicada para o lado cliente de um jogo cliente-servidor.
+    ///
+    /// ## Parâmetros
+    /// - `addr`: Endereço remoto.
+    ///
+    /// ## Retorno
+    /// - `io::Result<()>`: O erro retornado pelo sistema, se houver.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::{recommended_recv_buffer_size, Packet};
+    /// use packet::socket::PacketSocket;
+    ///
+    /// let server = PacketSocket::bind("127.0.0.1:0").unwrap();
+    /// let client = PacketSocket::bind("127.0.0.1:0").unwrap();
+    /// client.connect(server.local_addr().unwrap()).unwrap();
+    ///
+    /// let packet = Packet::new(1, 1, 7, vec![1, 2, 3]);
+    /// client.send(&packet).unwrap();
+    ///
+    /// let mut buffer = vec![0; recommended_recv_buffer_size()];
+    /// let (received, from) = server.recv_from(&mut buffer).unwrap();
+    /// server.send_to(&received, from).unwrap();
+    /// assert_eq!(client.recv(&mut buffer).unwrap(), packet);
+    /// ```
+    pub fn connect(&self, addr: impl ToSocketAddrs) -> io::Result<()> {
+        self.socket.connect(addr)
+    }
+
+    /// Envia um pacote ao endereço conectado por [`PacketSocket::connect`].
+    ///
+    /// ## Parâmetros
+    /// - `packet`: Pacote a ser enviado.
+    ///
+    /// ## Retorno
+    /// - `io::Result<usize>`: Quantidade de bytes enviados.
+    pub fn send(&self, packet: &Packet) -> io::Result<usize> {
+        self.socket.send(&packet.to_bytes())
+    }
+
+    /// Recebe um pacote do endereço conectado por [`PacketSocket::connect`].
+    ///
+    /// Truncamento e pacotes inválidos são tratados como em [`PacketSocket::recv_from`].
+    ///
+    /// ## Parâmetros
+    /// - `buf`: Buffer de recepção.
+    ///
+    /// ## Retorno
+    /// - `io::Result<Packet>`: O pacote recebido.
+    pub fn recv(&self, buf: &mut [u8]) -> io::Result<Packet> {
+        let len = self.socket.recv(buf)?;
+        Ok(parse_datagram(&buf[..len], buf.len())?.to_packet())
+    }
+}
+
+/// Interpreta, sem copiar, um datagrama recebido em um buffer de `buffer_len` bytes.
+pub(crate) fn parse_datagram(
+    datagram: &[u8],
+    buffer_len: usize,
+) -> Result<PacketRef<'_>, PacketError> {
+    if datagram.len() == buffer_len {
+        let declared = PacketHeader::from_bytes(datagram)?.packet_len()?;
+        if declared > buffer_len {
+            return Err(PacketError::Truncated {
+                declared,
+                buffer: buffer_len,
+            });
+        }
+    }
+
+    let result = PacketRef::parse_exact(datagram);
+    instrument::parsed(result.as_ref().map(|packet| &packet.header));
+    let packet = result?;
+    packet.check_checksum()?;
+    Ok(packet)
+}
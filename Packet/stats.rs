@@ -0,0 +1,277 @@
+//! # Módulo `stats`
+//!
+//! Estatísticas de tráfego por conexão, para gráficos de rede em tempo real.
+//!
+//! [`StatsRecorder`] conta os pacotes e bytes enviados e recebidos, as
+//! retransmissões, os fragmentos e os descartes da proteção contra inundação, e mede a banda em cada sentido com um
+//! [`ThroughputMeter`] de janela [`DEFAULT_STATS_WINDOW`]. [`StatsRecorder::snapshot`]
+//! produz um [`NetworkStats`], ao qual a conexão acrescenta RTT, jitter e perda. As
+//! conexões de [`connection`](crate::connection) mantêm um registro por sessão e um
+//! do servidor inteiro, consultados com `stats` e entregues periodicamente a uma
+//! função registrada com `with_stats_callback`.
+//!
+//! ## Exemplos
+//! ```rust
+//! use std::time::{Duration, Instant};
+//! use packet::{Packet, PacketHeader};
+//! use packet::stats::StatsRecorder;
+//!
+//! let mut recorder = StatsRecorder::new();
+//! let start = Instant::now();
+//! let packet = Packet::new(1, 1, 7, vec![0; 125 - PacketHeader::SIZE]);
+//!
+//! // Um pacote de 125 bytes a cada 100 ms: 10 kbit/s.
+//! for step in 0..10 {
+//!     recorder.record_sent(&packet, start + Duration::from_millis(100) * step);
+//! }
+//! let stats = recorder.snapshot();
+//! assert_eq!(stats.packets_sent, 10);
+//! assert_eq!(stats.bytes_sent, 1250);
+//! assert_eq!(stats.send_rate, 10_000.0);
+//!
+//! // Um pacote dividido conta uma vez como fragmentado e uma vez por fragmento.
+//! let fragments = packet.fragment(40).unwrap();
+//! recorder.record_fragmented(&fragments, start + Duration::from_millis(900));
+//! let stats = recorder.snapshot();
+//! assert_eq!(stats.packets_fragmented, 1);
+//! assert_eq!(stats.fragments_sent, fragments.len() as u64);
+//! ```
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::ratelimit::Verdict;
+use crate::throughput::ThroughputMeter;
+use crate::Packet;
+
+/// Janela sobre a qual a banda é medida.
+pub const DEFAULT_STATS_WINDOW: Duration = Duration::from_secs(1);
+
+/// Quantidade de faixas de tempo na janela de banda.
+const WINDOW_BUCKETS: u32 = 10;
+
+/// Estatísticas de uma conexão ou do servidor em um instante.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct NetworkStats {
+    /// Pacotes enviados, incluindo os de controle e cada fragmento.
+    pub packets_sent: u64,
+    /// Pacotes recebidos, incluindo os de controle e cada fragmento.
+    pub packets_received: u64,
+    /// Bytes serializados enviados.
+    pub bytes_sent: u64,
+    /// Bytes serializados recebidos.
+    pub bytes_received: u64,
+    /// Pings de manutenção respondidos pelo outro lado.
+    pub pings_answered: u64,
+    /// Pings de manutenção sem resposta até o envio do seguinte.
+    pub pings_lost: u64,
+    /// Mensagens reenviadas por falta de resposta.
+    pub retransmissions: u64,
+    /// Pacotes da aplicação divididos em fragmentos antes do envio.
+    pub packets_fragmented: u64,
+    /// Fragmentos enviados.
+    pub fragments_sent: u64,
+    /// Fragmentos recebidos.
+    pub fragments_received: u64,
+    /// RTT suavizado, se já medido.
+    pub rtt: Option<Duration>,
+    /// Jitter estimado, se já medido.
+    pub jitter: Option<Duration>,
+    /// Banda de envio na janela recente, em bits por segundo.
+    pub send_rate: f64,
+    /// Banda de recepção na janela recente, em bits por segundo.
+    pub receive_rate: f64,
+    /// Pacotes descartados por exceder o limite de taxa da origem.
+    pub packets_rate_limited: u64,
+    /// Pacotes descartados por virem de uma origem banida.
+    pub packets_from_banned: u64,
+    /// Banimentos temporários aplicados.
+    pub bans: u64,
+}
+
+impl NetworkStats {
+    /// Calcula a perda de pacotes pela proporção de pings sem resposta.
+    ///
+    /// ## Retorno
+    /// - `f64`: Percentual de perda, de `0.0` a `100.0`; `0.0` sem pings concluídos.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::stats::NetworkStats;
+    ///
+    /// let stats = NetworkStats { pings_answered: 19, pings_lost: 1, ..NetworkStats::default() };
+    /// assert_eq!(stats.packet_loss(), 5.0);
+    /// ```
+    pub fn packet_loss(&self) -> f64 {
+        let total = self.pings_answered + self.pings_lost;
+        if total == 0 {
+            return 0.0;
+        }
+        self.pings_lost as f64 * 100.0 / total as f64
+    }
+}
+
+/// Contadores e medidores de banda de uma conexão.
+#[derive(Debug, Clone)]
+pub struct StatsRecorder {
+    /// Contadores acumulados; RTT, jitter e pings ficam a cargo da conexão.
+    totals: NetworkStats,
+    /// Banda de envio.
+    sent: ThroughputMeter,
+    /// Banda de recepção.
+    received: ThroughputMeter,
+}
+
+impl Default for StatsRecorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StatsRecorder {
+    /// Cria um registro zerado, com janela de banda [`DEFAULT_STATS_WINDOW`].
+    pub fn new() -> Self {
+        let bucket_width = DEFAULT_STATS_WINDOW / WINDOW_BUCKETS;
+        Self {
+            totals: NetworkStats::default(),
+            sent: ThroughputMeter::new(bucket_width, WINDOW_BUCKETS as usize),
+            received: ThroughputMeter::new(bucket_width, WINDOW_BUCKETS as usize),
+        }
+    }
+
+    /// Registra um pacote enviado.
+    ///
+    /// ## Parâmetros
+    /// - `packet`: Pacote enviado; fragmentos são contados também como tal.
+    /// - `now`: Instante do envio.
+    pub fn record_sent(&mut self, packet: &Packet, now: Instant) {
+        let size = packet.serialized_len();
+        self.totals.packets_sent += 1;
+        self.totals.bytes_sent += size as u64;
+        self.totals.fragments_sent += u64::from(packet.is_fragment());
+        self.sent.record(size, now);
+    }
+
+    /// Registra um pacote recebido.
+    ///
+    /// ## Parâmetros
+    /// - `packet`: Pacote recebido; fragmentos são contados também como tal.
+    /// - `now`: Instante da recepção.
+    pub fn record_received(&mut self, packet: &Packet, now: Instant) {
+        let size = packet.serialized_len();
+        self.totals.packets_received += 1;
+        self.totals.bytes_received += size as u64;
+        self.totals.fragments_received += u64::from(packet.is_fragment());
+        self.received.record(size, now);
+    }
+
+    /// Registra o envio dos pacotes produzidos por
+    /// [`Packet::fragment`](crate::Packet::fragment).
+    ///
+    /// ## Parâmetros
+    /// - `fragments`: Pacotes enviados; mais de um indica um pacote fragmentado.
+    /// - `now`: Instante do envio.
+    pub fn record_fragmented(&mut self, fragments: &[Packet], now: Instant) {
+        self.totals.packets_fragmented += u64::from(fragments.len() > 1);
+        for fragment in fragments {
+            self.record_sent(fragment, now);
+        }
+    }
+
+    /// Registra uma mensagem reenviada por falta de resposta, além do envio em si.
+    pub fn record_retransmission(&mut self) {
+        self.totals.retransmissions += 1;
+    }
+
+    /// Registra um pacote descartado pela proteção contra inundação.
+    ///
+    /// ## Parâmetros
+    /// - `verdict`: Decisão de [`FloodGuard::check`](crate::ratelimit::FloodGuard::check)
+    ///   sobre o pacote; [`Verdict::Allowed`] não é contado.
+    pub fn record_rejected(&mut self, verdict: Verdict) {
+        match verdict {
+            Verdict::Allowed => {}
+            Verdict::Limited => self.totals.packets_rate_limited += 1,
+            Verdict::Banned { new } => {
+                self.totals.packets_from_banned += 1;
+                self.totals.bans += u64::from(new);
+            }
+        }
+    }
+
+    /// Avança as janelas de banda até `now`, para que períodos sem tráfego reduzam as
+    /// taxas.
+    ///
+    /// ## Parâmetros
+    /// - `now`: Instante atual.
+    pub fn advance(&mut self, now: Instant) {
+        self.sent.record(0, now);
+        self.received.record(0, now);
+    }
+
+    /// Retorna os contadores e as taxas até o último registro ou
+    /// [`StatsRecorder::advance`].
+    ///
+    /// ## Retorno
+    /// - `NetworkStats`: As estatísticas, sem RTT, jitter e pings.
+    pub fn snapshot(&self) -> NetworkStats {
+        NetworkStats {
+            send_rate: self.sent.bits_per_second(DEFAULT_STATS_WINDOW),
+            receive_rate: self.received.bits_per_second(DEFAULT_STATS_WINDOW),
+            ..self.totals
+        }
+    }
+}
+
+/// Função notificada com as estatísticas periódicas: `None` para o servidor inteiro
+/// ou a sessão, e as estatísticas.
+type StatsCallback = Box<dyn FnMut(Option<u64>, &NetworkStats) + Send>;
+
+/// Entrega periódica de estatísticas a uma função registrada.
+pub(crate) struct StatsReporter {
+    /// Intervalo entre as entregas.
+    interval: Duration,
+    /// Instante da última entrega.
+    last: Option<Instant>,
+    /// Função notificada.
+    callback: StatsCallback,
+}
+
+impl fmt::Debug for StatsReporter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StatsReporter")
+            .field("interval", &self.interval)
+            .field("last", &self.last)
+            .finish()
+    }
+}
+
+impl StatsReporter {
+    /// Cria a entrega; a primeira acontece no primeiro [`StatsReporter::is_due`].
+    pub(crate) fn new(
+        interval: Duration,
+        callback: impl FnMut(Option<u64>, &NetworkStats) + Send + 'static,
+    ) -> Self {
+        Self {
+            interval,
+            last: None,
+            callback: Box::new(callback),
+        }
+    }
+
+    /// Indica se é hora de uma entrega, marcando-a como feita.
+    pub(crate) fn is_due(&mut self, now: Instant) -> bool {
+        let due = self
+            .last
+            .is_none_or(|last| now.saturating_duration_since(last) >= self.interval);
+        if due {
+            self.last = Some(now);
+        }
+        due
+    }
+
+    /// Entrega um conjunto de estatísticas.
+    pub(crate) fn report(&mut self, session_id: Option<u64>, stats: &NetworkStats) {
+        (self.callback)(session_id, stats);
+    }
+}
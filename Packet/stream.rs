@@ -0,0 +1,164 @@
+//! # Módulo `stream`
+//!
+//! Leitura e escrita de pacotes diretamente em fluxos [`Read`] e [`Write`], como
+//! conexões TCP e arquivos.
+//!
+//! ## Exemplos
+//! ```rust
+//! use std::io::Cursor;
+//! use packet::Packet;
+//!
+//! let mut capture = Vec::new();
+//! Packet::new(1, 1, 7, vec![1, 2]).to_writer(&mut capture).unwrap();
+//! Packet::new(1, 2, 7, vec![3]).to_writer(&mut capture).unwrap();
+//!
+//! let mut reader = Cursor::new(capture);
+//! let mut sequences = Vec::new();
+//! while let Some(packet) = Packet::from_reader(&mut reader).unwrap() {
+//!     sequences.push(packet.header.sequence);
+//! }
+//! assert_eq!(sequences, [1, 2]);
+//! ```
+
+use std::io::{self, Read, Write};
+
+use crate::config::ParseConfig;
+use crate::{Packet, PacketHeader};
+
+impl Packet {
+    /// Lê exatamente um pacote de um fluxo, verificando seu checksum.
+    ///
+    /// O cabeçalho é lido primeiro e o payload declarado em seguida; nenhum byte além
+    /// do pacote é consumido. Um `payload_size` acima de
+    /// [`MAX_PAYLOAD_SIZE`](crate::MAX_PAYLOAD_SIZE) é rejeitado antes de qualquer
+    /// alocação.
+    ///
+    /// ## Parâmetros
+    /// - `r`: Fluxo de leitura. Use um `BufReader` para evitar muitas chamadas de sistema.
+    ///
+    /// ## Retorno
+    /// - `io::Result<Option<Packet>>`: O pacote lido, ou `None` se o fluxo terminou
+    ///   exatamente entre dois pacotes. Um fim de fluxo no meio de um pacote resulta em
+    ///   [`io::ErrorKind::UnexpectedEof`], e um pacote inválido em
+    ///   [`io::ErrorKind::InvalidData`] com o [`PacketError`](crate::PacketError) correspondente.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use std::io::{Cursor, ErrorKind};
+    /// use packet::Packet;
+    ///
+    /// let bytes = Packet::new(1, 1, 7, vec![1, 2, 3]).to_bytes();
+    ///
+    /// let mut truncated = Cursor::new(&bytes[..bytes.len() - 1]);
+    /// let error = Packet::from_reader(&mut truncated).unwrap_err();
+    /// assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+    ///
+    /// let mut empty = Cursor::new(Vec::new());
+    /// assert!(Packet::from_reader(&mut empty).unwrap().is_none());
+    /// ```
+    pub fn from_reader<R: Read>(r: &mut R) -> io::Result<Option<Packet>> {
+        read_packet(r, None)
+    }
+
+    /// Lê exatamente um pacote de um fluxo como [`Packet::from_reader`], aplicando os
+    /// limites.
+    ///
+    /// O `payload_size` é verificado antes da alocação do payload, e a quantidade de
+    /// fragmentos depois da leitura.
+    ///
+    /// ## Parâmetros
+    /// - `r`: Fluxo de leitura.
+    /// - `limits`: Limites da leitura.
+    ///
+    /// ## Retorno
+    /// - `io::Result<Option<Packet>>`: O resultado de [`Packet::from_reader`]; os erros
+    ///   de [`ParseConfig::check`] chegam em [`io::ErrorKind::InvalidData`].
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use std::io::{Cursor, ErrorKind};
+    /// use packet::Packet;
+    /// use packet::config::ParseConfig;
+    ///
+    /// let limits = ParseConfig {
+    ///     max_payload: 2,
+    ///     ..ParseConfig::default()
+    /// };
+    ///
+    /// let mut reader = Cursor::new(Packet::new(1, 1, 7, vec![1, 2, 3]).to_bytes());
+    /// let error = Packet::from_reader_with_limits(&mut reader, &limits).unwrap_err();
+    /// assert_eq!(error.kind(), ErrorKind::InvalidData);
+    /// ```
+    pub fn from_reader_with_limits<R: Read>(
+        r: &mut R,
+        limits: &ParseConfig,
+    ) -> io::Result<Option<Packet>> {
+        read_packet(r, Some(limits))
+    }
+
+    /// Escreve o pacote em um fluxo sem montar um vetor intermediário.
+    ///
+    /// O cabeçalho é serializado na pilha e escrito antes do payload, em duas
+    /// chamadas a [`Write::write_all`]. Para transportes como TCP, envolva o fluxo em
+    /// um `BufWriter` para que as duas escritas resultem em um único envio.
+    ///
+    /// ## Parâmetros
+    /// - `w`: Fluxo de escrita.
+    ///
+    /// ## Retorno
+    /// - `io::Result<()>`: O erro retornado pelo fluxo, se houver.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::Packet;
+    ///
+    /// let packet = Packet::new(1, 1, 7, vec![1, 2, 3]);
+    /// let mut output = Vec::new();
+    /// packet.to_writer(&mut output).unwrap();
+    ///
+    /// assert_eq!(output, packet.to_bytes());
+    /// ```
+    pub fn to_writer<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(&self.header.to_array())?;
+        w.write_all(&self.payload)
+    }
+}
+
+/// Lê um pacote com os limites informados; sem eles, só o payload é limitado, a
+/// [`MAX_PAYLOAD_SIZE`](crate::MAX_PAYLOAD_SIZE).
+fn read_packet<R: Read>(r: &mut R, limits: Option<&ParseConfig>) -> io::Result<Option<Packet>> {
+    let mut header = [0u8; PacketHeader::SIZE];
+    if !read_exact_or_eof(r, &mut header)? {
+        return Ok(None);
+    }
+
+    let header = PacketHeader::from_bytes(&header)?;
+    let default = ParseConfig::default();
+    limits.unwrap_or(&default).check_header(&header)?;
+
+    let mut payload = vec![0; header.payload_size as usize];
+    r.read_exact(&mut payload)?;
+
+    let packet = Packet { header, payload };
+    if let Some(limits) = limits {
+        limits.check(&packet.header, &packet.payload)?;
+    }
+    packet.check_checksum()?;
+    Ok(Some(packet))
+}
+
+/// Preenche `buf` por completo, retornando `false` se o fluxo terminou antes do
+/// primeiro byte.
+fn read_exact_or_eof<R: Read>(r: &mut R, buf: &mut [u8]) -> io::Result<bool> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match r.read(&mut buf[filled..]) {
+            Ok(0) if filled == 0 => return Ok(false),
+            Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+            Ok(read) => filled += read,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+            Err(error) => return Err(error),
+        }
+    }
+    Ok(true)
+}
@@ -0,0 +1,358 @@
+//! # Módulo `streaming`
+//!
+//! Canais de voz e vídeo: quadros pequenos, sem retransmissão, tocados no ritmo em que
+//! foram gravados.
+//!
+//! Um [`StreamChannel`] carimba cada quadro com a sequência do canal, no cabeçalho, e
+//! com o instante da gravação, em milissegundos desde o primeiro quadro, nos
+//! [`STREAM_HEADER_SIZE`] primeiros bytes do payload; os quadros são pacotes
+//! [`MessageType::STREAM`] comuns e seguem pelos mesmos transportes dos outros canais.
+//! Quadros maiores que o tamanho do canal são recusados, para que nenhum precise de
+//! fragmentação.
+//!
+//! Na recepção, os quadros passam por um buffer de jitter: cada quadro é tocado depois
+//! do atraso alvo, contado a partir do instante em que chegaria pelo caminho mais
+//! rápido já visto, e [`StreamChannel::poll`] os devolve em ordem de sequência quando
+//! chega a hora. Um quadro que não chegou até a hora do seguinte é dado como perdido e
+//! devolvido como [`StreamPlayout::Missing`], para que o decodificador o disfarce; um
+//! que chega depois disso é descartado e contado em [`StreamStats::late`]. Um atraso
+//! alvo maior tolera mais variação na rede ao custo de mais latência.
+//!
+//! ## Exemplos
+//! ```rust
+//! use std::time::{Duration, Instant};
+//! use packet::streaming::{StreamChannel, StreamPlayout};
+//!
+//! let new_channel = || StreamChannel::new(4, 160).with_target_delay(Duration::from_millis(40));
+//! let (mut sender, mut receiver) = (new_channel(), new_channel());
+//! let start = Instant::now();
+//! let at = |ms| start + Duration::from_millis(ms);
+//!
+//! // Quadros de 20 ms; o segundo se atrasa.
+//! let frames: Vec<_> = (0..4u64).map(|i| sender.send(&[i as u8; 160], at(i * 20)).unwrap()).collect();
+//! receiver.receive(&frames[0], at(5));
+//! receiver.receive(&frames[2], at(50));
+//! receiver.receive(&frames[3], at(66));
+//!
+//! // Nada é tocado antes do atraso alvo.
+//! assert!(receiver.poll(at(44)).is_empty());
+//! let played = receiver.poll(at(90));
+//! assert!(matches!(&played[0], StreamPlayout::Frame(frame) if frame.data == [0; 160]));
+//! assert_eq!(played[1], StreamPlayout::Missing { sequence: 1 });
+//! assert!(matches!(&played[2], StreamPlayout::Frame(frame) if frame.sequence == 2));
+//! assert_eq!(played.len(), 3);
+//!
+//! // O quadro perdido chega tarde demais.
+//! receiver.receive(&frames[1], at(95));
+//! let stats = receiver.stats();
+//! assert_eq!((stats.played, stats.lost, stats.late), (2, 1, 1));
+//! ```
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::message::MessageType;
+use crate::sequence::sequence_less_than;
+use crate::{Packet, PacketError};
+
+/// Bytes do carimbo de tempo no início do payload de cada quadro.
+pub const STREAM_HEADER_SIZE: usize = 4;
+
+/// Atraso alvo padrão do buffer de jitter.
+pub const DEFAULT_TARGET_DELAY: Duration = Duration::from_millis(60);
+
+/// Quantidade padrão de quadros retidos pelo buffer de jitter.
+pub const DEFAULT_STREAM_CAPACITY: usize = 64;
+
+/// Quadro recebido, pronto para ser tocado.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamFrame {
+    /// Sequência do quadro no canal.
+    pub sequence: u32,
+    /// Instante da gravação, em milissegundos desde o primeiro quadro do remetente.
+    pub timestamp: u32,
+    /// Conteúdo do quadro, sem o carimbo de tempo.
+    pub data: Vec<u8>,
+}
+
+/// Saída do buffer de jitter, na ordem de sequência.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StreamPlayout {
+    /// Quadro a ser tocado.
+    Frame(StreamFrame),
+    /// Quadro que não chegou a tempo, a ser disfarçado pelo decodificador.
+    Missing {
+        /// Sequência do quadro perdido.
+        sequence: u32,
+    },
+}
+
+/// Contadores de um [`StreamChannel`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StreamStats {
+    /// Quadros enviados.
+    pub sent: u64,
+    /// Quadros recebidos, inclusive os descartados.
+    pub received: u64,
+    /// Quadros tocados.
+    pub played: u64,
+    /// Quadros que não chegaram até a hora de serem tocados.
+    pub lost: u64,
+    /// Quadros que chegaram depois da hora e foram descartados.
+    pub late: u64,
+    /// Quadros recebidos mais de uma vez.
+    pub duplicates: u64,
+    /// Quadros descartados porque o buffer estava cheio.
+    pub overflows: u64,
+}
+
+/// Canal de voz ou vídeo com quadros carimbados e buffer de jitter.
+#[derive(Debug, Clone)]
+pub struct StreamChannel {
+    /// Identificador do canal no cabeçalho.
+    id: u8,
+    /// Tamanho máximo de um quadro, sem o carimbo de tempo.
+    frame_size: usize,
+    /// Atraso entre a chegada esperada de um quadro e o instante em que é tocado.
+    target_delay: Duration,
+    /// Quantidade máxima de quadros retidos.
+    capacity: usize,
+    /// Prioridade gravada nos quadros enviados; `None` mantém a padrão.
+    priority: Option<u8>,
+    /// Sequência do próximo quadro enviado.
+    next_sequence: u32,
+    /// Instante do primeiro quadro enviado, origem dos carimbos de tempo.
+    epoch: Option<Instant>,
+    /// Sequência do próximo quadro a ser tocado; `None` antes do primeiro recebido.
+    next_play: Option<u32>,
+    /// Instante de chegada e carimbo do quadro que chegou mais adiantado.
+    anchor: Option<(Instant, u32)>,
+    /// Quadros retidos, a partir de `next_play`.
+    slots: VecDeque<Option<StreamFrame>>,
+    /// Contadores do canal.
+    stats: StreamStats,
+}
+
+impl StreamChannel {
+    /// Cria um canal com [`DEFAULT_TARGET_DELAY`] e [`DEFAULT_STREAM_CAPACITY`].
+    ///
+    /// ## Parâmetros
+    /// - `id`: Identificador do canal, gravado no `channel_id` dos quadros enviados.
+    /// - `frame_size`: Tamanho máximo de um quadro, em bytes, como os 160 bytes de 20 ms
+    ///   de voz a 64 kbit/s.
+    ///
+    /// ## Retorno
+    /// - `Self`: O canal, cuja primeira sequência é `0`.
+    pub fn new(id: u8, frame_size: usize) -> Self {
+        Self {
+            id,
+            frame_size,
+            target_delay: DEFAULT_TARGET_DELAY,
+            capacity: DEFAULT_STREAM_CAPACITY,
+            priority: None,
+            next_sequence: 0,
+            epoch: None,
+            next_play: None,
+            anchor: None,
+            slots: VecDeque::new(),
+            stats: StreamStats::default(),
+        }
+    }
+
+    /// Define o atraso alvo do buffer de jitter.
+    ///
+    /// ## Parâmetros
+    /// - `target_delay`: Tempo que cada quadro espera depois da chegada esperada.
+    ///
+    /// ## Retorno
+    /// - `Self`: O canal com o novo atraso.
+    pub fn with_target_delay(mut self, target_delay: Duration) -> Self {
+        self.target_delay = target_delay;
+        self
+    }
+
+    /// Limita a quantidade de quadros retidos pelo buffer de jitter.
+    ///
+    /// ## Parâmetros
+    /// - `capacity`: Limite de quadros; no mínimo `1`. Um quadro adiantado além do
+    ///   limite faz os mais antigos serem descartados.
+    ///
+    /// ## Retorno
+    /// - `Self`: O canal com o novo limite.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    /// Define a prioridade do canal, gravada no `priority` de cada quadro enviado.
+    ///
+    /// ## Parâmetros
+    /// - `priority`: Prioridade; maior é enviada antes.
+    ///
+    /// ## Retorno
+    /// - `Self`: O canal com a prioridade.
+    pub fn with_priority(mut self, priority: u8) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    /// Retorna o identificador do canal.
+    pub fn id(&self) -> u8 {
+        self.id
+    }
+
+    /// Retorna o tamanho máximo de um quadro.
+    pub fn frame_size(&self) -> usize {
+        self.frame_size
+    }
+
+    /// Retorna o atraso alvo do buffer de jitter.
+    pub fn target_delay(&self) -> Duration {
+        self.target_delay
+    }
+
+    /// Retorna a quantidade de quadros retidos à espera da hora de serem tocados.
+    pub fn buffered(&self) -> usize {
+        self.slots.iter().flatten().count()
+    }
+
+    /// Retorna os contadores do canal.
+    pub fn stats(&self) -> StreamStats {
+        self.stats
+    }
+
+    /// Prepara um quadro para envio.
+    ///
+    /// ## Parâmetros
+    /// - `frame`: Conteúdo do quadro.
+    /// - `now`: Instante da gravação do quadro.
+    ///
+    /// ## Retorno
+    /// - `Result<Packet, PacketError>`: O pacote, com o `channel_id`, a próxima sequência
+    ///   e a prioridade do canal, ou [`PacketError::PayloadTooLarge`] se o quadro excede
+    ///   o tamanho do canal.
+    pub fn send(&mut self, frame: &[u8], now: Instant) -> Result<Packet, PacketError> {
+        if frame.len() > self.frame_size {
+            return Err(PacketError::PayloadTooLarge {
+                size: frame.len(),
+                limit: self.frame_size,
+            });
+        }
+        let epoch = *self.epoch.get_or_insert(now);
+        let timestamp = now.saturating_duration_since(epoch).as_millis() as u32;
+        let mut payload = Vec::with_capacity(STREAM_HEADER_SIZE + frame.len());
+        payload.extend_from_slice(&timestamp.to_le_bytes());
+        payload.extend_from_slice(frame);
+
+        let sequence = self.next_sequence;
+        self.next_sequence = sequence.wrapping_add(1);
+        self.stats.sent += 1;
+        let packet =
+            Packet::new(MessageType::STREAM.into(), sequence, 0, payload).with_channel_id(self.id);
+        Ok(match self.priority {
+            Some(priority) => packet.with_priority(priority),
+            None => packet,
+        })
+    }
+
+    /// Guarda um quadro recebido no buffer de jitter.
+    ///
+    /// Quadros sem o carimbo de tempo são ignorados.
+    ///
+    /// ## Parâmetros
+    /// - `packet`: Pacote recebido; deve pertencer a este canal.
+    /// - `now`: Instante da chegada.
+    pub fn receive(&mut self, packet: &Packet, now: Instant) {
+        let Some(stamp) = packet.payload.get(..STREAM_HEADER_SIZE) else {
+            return;
+        };
+        let frame = StreamFrame {
+            sequence: packet.header.sequence,
+            timestamp: u32::from_le_bytes(stamp.try_into().expect("4 bytes")),
+            data: packet.payload[STREAM_HEADER_SIZE..].to_vec(),
+        };
+        self.stats.received += 1;
+
+        let next_play = *self.next_play.get_or_insert(frame.sequence);
+        if sequence_less_than(frame.sequence, next_play) {
+            self.stats.late += 1;
+            return;
+        }
+        let expected = self.expected_arrival(frame.timestamp);
+        if expected.is_none_or(|expected| now < expected) {
+            self.anchor = Some((now, frame.timestamp));
+        }
+
+        let offset = frame.sequence.wrapping_sub(next_play) as usize;
+        let overflow = (offset + 1).saturating_sub(self.capacity);
+        for _ in 0..overflow {
+            match self.slots.front() {
+                Some(Some(_)) => self.stats.overflows += 1,
+                _ => self.stats.lost += 1,
+            }
+            self.advance();
+        }
+        let offset = offset - overflow;
+        if self.slots.len() <= offset {
+            self.slots.resize(offset + 1, None);
+        }
+        match &mut self.slots[offset] {
+            Some(_) => self.stats.duplicates += 1,
+            slot => *slot = Some(frame),
+        }
+    }
+
+    /// Retorna, em ordem de sequência, os quadros cuja hora de serem tocados chegou e os
+    /// perdidos antes deles.
+    ///
+    /// ## Parâmetros
+    /// - `now`: Instante atual.
+    ///
+    /// ## Retorno
+    /// - `Vec<StreamPlayout>`: Os quadros a tocar; vazio se nenhum está na hora.
+    pub fn poll(&mut self, now: Instant) -> Vec<StreamPlayout> {
+        let mut playout = Vec::new();
+        while let Some(index) = self.slots.iter().position(Option::is_some) {
+            let timestamp = self.slots[index]
+                .as_ref()
+                .map_or(0, |frame| frame.timestamp);
+            if self
+                .expected_arrival(timestamp)
+                .is_some_and(|expected| now < expected + self.target_delay)
+            {
+                break;
+            }
+            for _ in 0..index {
+                let sequence = self.advance();
+                self.stats.lost += 1;
+                playout.push(StreamPlayout::Missing { sequence });
+            }
+            if let Some(Some(frame)) = self.slots.pop_front() {
+                self.stats.played += 1;
+                playout.push(StreamPlayout::Frame(frame));
+            }
+            self.next_play = self.next_play.map(|sequence| sequence.wrapping_add(1));
+        }
+        playout
+    }
+
+    /// Instante em que o quadro com o carimbo informado chegaria pelo caminho mais
+    /// rápido já visto.
+    fn expected_arrival(&self, timestamp: u32) -> Option<Instant> {
+        let (arrival, anchor) = self.anchor?;
+        let ahead = timestamp.wrapping_sub(anchor) as i32;
+        let offset = Duration::from_millis(u64::from(ahead.unsigned_abs()));
+        Some(match ahead {
+            0.. => arrival + offset,
+            _ => arrival.checked_sub(offset).unwrap_or(arrival),
+        })
+    }
+
+    /// Descarta a primeira posição do buffer, retornando a sequência dela.
+    fn advance(&mut self) -> u32 {
+        self.slots.pop_front();
+        let sequence = self.next_play.unwrap_or(0);
+        self.next_play = Some(sequence.wrapping_add(1));
+        sequence
+    }
+}
@@ -0,0 +1,210 @@
+//! # Módulo `testing`
+//!
+//! Utilitários para testes de tratamento de erros do receptor e para conduzir a pilha
+//! sem rede.
+//!
+//! As funções corrompem bytes serializados sem atualizar o checksum, produzindo
+//! entradas que [`Packet::verify_checksum`] deve rejeitar. Nenhum gerador de números
+//! aleatórios é imposto: o chamador fornece a fonte de aleatoriedade, o que permite
+//! testes determinísticos com uma semente fixa.
+//!
+//! Uma [`MemoryNetwork`] entrega os datagramas em memória, na ordem de envio, entre
+//! [`MemoryTransport`]s de endereços fictícios. Com os instantes de `poll` e `update`
+//! tirados de um [`MockClock`](crate::clock::MockClock), servidor e cliente
+//! avançam só quando o teste manda, sem sockets nem tempo real.
+//!
+//! ## Exemplos
+//! ```rust
+//! use packet::Packet;
+//! use packet::testing::corrupt_payload;
+//!
+//! let packet = Packet::new(1, 1, 7, vec![1, 2, 3, 4]);
+//! let mut state = 0x2545_F491_4F6C_DD1Du64;
+//! let mut rng = || {
+//!     state ^= state << 13;
+//!     state ^= state >> 7;
+//!     state ^= state << 17;
+//!     state
+//! };
+//!
+//! for _ in 0..10 {
+//!     let bytes = corrupt_payload(&packet, &mut rng);
+//!     let received = Packet::from_bytes(&bytes).unwrap();
+//!     assert!(!received.verify_checksum());
+//! }
+//! ```
+
+use std::collections::{HashMap, VecDeque};
+use std::io;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::socket::parse_datagram;
+use crate::transport::Transport;
+use crate::{Packet, PacketError, PacketHeader};
+
+/// Inverte todos os bits de um byte.
+///
+/// ## Parâmetros
+/// - `bytes`: Bytes a corromper.
+/// - `index`: Posição do byte invertido.
+///
+/// ## Pânico
+/// Se `index` está fora de `bytes`.
+///
+/// ## Exemplos
+/// ```rust
+/// use packet::testing::corrupt_byte;
+///
+/// let mut bytes = [0x0F, 0x00];
+/// corrupt_byte(&mut bytes, 0);
+/// assert_eq!(bytes, [0xF0, 0x00]);
+/// ```
+pub fn corrupt_byte(bytes: &mut [u8], index: usize) {
+    bytes[index] = !bytes[index];
+}
+
+/// Serializa o pacote e altera um byte sorteado do payload, mantendo o checksum.
+///
+/// O byte sorteado recebe um valor diferente do original, de forma que o pacote
+/// resultante falha na verificação do checksum, exceto com
+/// [`ChecksumScope::None`](crate::checksum::ChecksumScope::None).
+///
+/// ## Parâmetros
+/// - `packet`: Pacote a corromper.
+/// - `rng`: Fonte de números aleatórios, como `rand::random::<u64>`.
+///
+/// ## Retorno
+/// - `Vec<u8>`: Os bytes do pacote com um byte do payload alterado.
+///
+/// ## Pânico
+/// Se o payload do pacote está vazio.
+pub fn corrupt_payload(packet: &Packet, mut rng: impl FnMut() -> u64) -> Vec<u8> {
+    assert!(
+        !packet.payload.is_empty(),
+        "o pacote não tem payload a corromper"
+    );
+
+    let mut bytes = packet.to_bytes();
+    let index = PacketHeader::SIZE + (rng() % packet.payload.len() as u64) as usize;
+    let mask = 1 + (rng() % 255) as u8;
+    bytes[index] ^= mask;
+    bytes
+}
+
+/// Filas de recepção de cada endereço associado.
+type Mailboxes = HashMap<SocketAddr, VecDeque<(SocketAddr, Vec<u8>)>>;
+
+/// Rede em memória que entrega cada datagrama ao endereço de destino, sem perdas e
+/// na ordem de envio.
+///
+/// ## Exemplos
+/// ```rust
+/// use std::net::SocketAddr;
+/// use packet::Packet;
+/// use packet::testing::MemoryNetwork;
+/// use packet::transport::Transport;
+///
+/// let network = MemoryNetwork::new();
+/// let a: SocketAddr = "10.0.0.1:1000".parse().unwrap();
+/// let b: SocketAddr = "10.0.0.2:2000".parse().unwrap();
+/// let mut left = network.bind(a);
+/// let mut right = network.bind(b);
+///
+/// left.send_packet(b, &Packet::new(1, 1, 7, vec![1, 2])).unwrap();
+/// assert_eq!(network.in_flight(), 1);
+/// let (from, packet) = right.recv_packet().unwrap();
+/// assert_eq!((from, packet.payload.as_slice()), (a, &[1, 2][..]));
+/// assert!(right.recv_from().unwrap().is_none());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MemoryNetwork {
+    /// Filas compartilhadas pelos transportes da rede.
+    mailboxes: Arc<Mutex<Mailboxes>>,
+}
+
+impl MemoryNetwork {
+    /// Cria uma rede sem endereços.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Associa um transporte a um endereço da rede.
+    ///
+    /// ## Parâmetros
+    /// - `addr`: Endereço fictício do transporte; associá-lo de novo descarta os
+    ///   datagramas ainda não lidos.
+    ///
+    /// ## Retorno
+    /// - `MemoryTransport`: O transporte.
+    pub fn bind(&self, addr: SocketAddr) -> MemoryTransport {
+        self.lock().insert(addr, VecDeque::new());
+        MemoryTransport {
+            addr,
+            network: self.clone(),
+        }
+    }
+
+    /// Retorna a quantidade de datagramas enviados e ainda não lidos.
+    pub fn in_flight(&self) -> usize {
+        self.lock().values().map(VecDeque::len).sum()
+    }
+
+    /// Bloqueia as filas, ignorando o envenenamento por pânico de outra thread.
+    fn lock(&self) -> std::sync::MutexGuard<'_, Mailboxes> {
+        self.mailboxes
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Transporte de uma [`MemoryNetwork`].
+///
+/// Os datagramas a endereços não associados são descartados, como no UDP, e os
+/// recebidos passam pelas mesmas verificações do [`UdpTransport`](crate::transport::UdpTransport).
+#[derive(Debug, Clone)]
+pub struct MemoryTransport {
+    /// Endereço do transporte na rede.
+    addr: SocketAddr,
+    /// Rede do transporte.
+    network: MemoryNetwork,
+}
+
+impl Transport for MemoryTransport {
+    fn send_packet(&mut self, addr: SocketAddr, packet: &Packet) -> Result<usize, PacketError> {
+        let bytes = packet.to_bytes();
+        let len = bytes.len();
+        if let Some(mailbox) = self.network.lock().get_mut(&addr) {
+            mailbox.push_back((self.addr, bytes));
+        }
+        Ok(len)
+    }
+
+    fn recv_packet(&mut self) -> Result<(SocketAddr, Packet), PacketError> {
+        let (from, packet) = self
+            .recv_from()?
+            .ok_or_else(|| io::Error::from(io::ErrorKind::WouldBlock))?;
+        Ok((from, packet?))
+    }
+
+    fn recv_from(&mut self) -> io::Result<Option<(SocketAddr, Result<Packet, PacketError>)>> {
+        let received = self
+            .network
+            .lock()
+            .get_mut(&self.addr)
+            .and_then(VecDeque::pop_front);
+        Ok(received.map(|(from, bytes)| {
+            let packet = parse_datagram(&bytes, usize::MAX).map(|packet| packet.to_packet());
+            (from, packet)
+        }))
+    }
+
+    fn local_addr(&self) -> io::Result<SocketAddr> {
+        Ok(self.addr)
+    }
+
+    /// Não tem efeito: a recepção nunca bloqueia.
+    fn set_nonblocking(&mut self, _nonblocking: bool) -> io::Result<()> {
+        Ok(())
+    }
+}
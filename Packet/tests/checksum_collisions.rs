@@ -0,0 +1,133 @@
+//! Taxa de colisão dos algoritmos de checksum sob corrupções típicas.
+//!
+//! Gera payloads pseudoaleatórios de forma determinística, aplica três tipos de
+//! corrupção e conta quantas passam despercebidas por cada algoritmo:
+//!
+//! - troca de um único byte por outro valor;
+//! - troca de posição entre dois bytes diferentes;
+//! - alteração aleatória de vários bytes.
+//!
+//! CRC-32 detecta qualquer erro em um único byte e, nas amostras geradas, todas as
+//! trocas de posição, assim como xxHash32 nas mesmas amostras; a soma de bytes não detecta nenhuma troca de posição, o que
+//! justifica o uso de CRC-32 onde a integridade importa. Execute com
+//! `cargo test --test checksum_collisions -- --nocapture` para ver as taxas medidas.
+
+use packet::checksum::{ChecksumAlgorithm, Crc32Variant};
+
+/// Quantidade de payloads gerados por tipo de corrupção.
+const TRIALS: usize = 20_000;
+
+/// Tamanho máximo dos payloads gerados.
+const MAX_LEN: usize = 512;
+
+const ALGORITHMS: [(&str, ChecksumAlgorithm); 4] = [
+    ("byte_sum", ChecksumAlgorithm::ByteSum),
+    ("crc32", ChecksumAlgorithm::Crc32(Crc32Variant::Ieee)),
+    ("crc32c", ChecksumAlgorithm::Crc32(Crc32Variant::Castagnoli)),
+    ("xxhash32", ChecksumAlgorithm::XxHash32),
+];
+
+/// Gerador xorshift64*, suficiente para produzir entradas reproduzíveis.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+
+    fn payload(&mut self, min_len: usize) -> Vec<u8> {
+        let len = min_len + self.below(MAX_LEN - min_len);
+        (0..len).map(|_| self.next() as u8).collect()
+    }
+}
+
+/// Troca um byte por um valor diferente.
+fn corrupt_single_byte(rng: &mut Rng, payload: &mut [u8]) -> bool {
+    let index = rng.below(payload.len());
+    payload[index] ^= 1 + rng.below(255) as u8;
+    true
+}
+
+/// Troca de posição dois bytes; retorna `false` se eles eram iguais e nada mudou.
+fn corrupt_swap(rng: &mut Rng, payload: &mut [u8]) -> bool {
+    let first = rng.below(payload.len());
+    let second = rng.below(payload.len());
+    payload.swap(first, second);
+    payload[first] != payload[second]
+}
+
+/// Sorteia novos valores para entre dois e oito bytes.
+fn corrupt_random(rng: &mut Rng, payload: &mut [u8]) -> bool {
+    let original = payload.to_vec();
+    for _ in 0..2 + rng.below(7) {
+        let index = rng.below(payload.len());
+        payload[index] = rng.next() as u8;
+    }
+    payload != original.as_slice()
+}
+
+/// Retorna, para cada algoritmo, quantas corrupções passaram despercebidas e o total.
+fn undetected(
+    seed: u64,
+    corrupt: fn(&mut Rng, &mut [u8]) -> bool,
+) -> ([usize; ALGORITHMS.len()], usize) {
+    let mut rng = Rng(seed);
+    let mut missed = [0; ALGORITHMS.len()];
+    let mut total = 0;
+
+    while total < TRIALS {
+        let original = rng.payload(2);
+        let mut corrupted = original.clone();
+        if !corrupt(&mut rng, &mut corrupted) {
+            continue;
+        }
+        total += 1;
+
+        for (count, (_, algorithm)) in missed.iter_mut().zip(ALGORITHMS) {
+            if algorithm.compute(&original) == algorithm.compute(&corrupted) {
+                *count += 1;
+            }
+        }
+    }
+    (missed, total)
+}
+
+fn report(corruption: &str, missed: [usize; ALGORITHMS.len()], total: usize) {
+    for (count, (name, _)) in missed.iter().zip(ALGORITHMS) {
+        println!(
+            "{corruption}/{name}: {count} de {total} não detectadas ({:.2}%)",
+            *count as f64 * 100.0 / total as f64
+        );
+    }
+}
+
+#[test]
+fn single_byte_corruption_is_always_detected() {
+    let (missed, total) = undetected(0x5EED_0001, corrupt_single_byte);
+    report("single_byte", missed, total);
+    assert_eq!(missed, [0, 0, 0, 0]);
+}
+
+#[test]
+fn byte_swaps_escape_byte_sum_but_not_crc32() {
+    let (missed, total) = undetected(0x5EED_0002, corrupt_swap);
+    report("swap", missed, total);
+    assert_eq!(missed[0], total);
+    assert_eq!(missed[1..], [0, 0, 0]);
+}
+
+#[test]
+fn random_corruption_rarely_escapes_crc32() {
+    let (missed, total) = undetected(0x5EED_0003, corrupt_random);
+    report("random", missed, total);
+    // Com 2^32 valores possíveis, nenhuma colisão é esperada nesta quantidade de amostras.
+    assert_eq!(missed[1..], [0, 0, 0]);
+    assert!(missed[0] <= total / 100);
+}
@@ -0,0 +1,132 @@
+//! Troca completa entre cliente e servidor sobre UDP em loopback: conexão, envio
+//! de pacotes confiáveis com perda simulada, confirmações e entrega em ordem.
+
+use std::collections::HashSet;
+use std::net::{SocketAddr, UdpSocket};
+use std::time::{Duration, Instant};
+
+use packet::message::MessageType;
+use packet::ordered::OrderedChannel;
+use packet::reliability::ReliableChannel;
+use packet::Packet;
+
+const CLIENT_ID: u64 = 7;
+const MESSAGES: u32 = 20;
+const RESEND_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Extremidade de teste: um socket em loopback com tempo limite de leitura.
+struct Endpoint {
+    socket: UdpSocket,
+}
+
+impl Endpoint {
+    fn bind() -> Self {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        socket
+            .set_read_timeout(Some(Duration::from_secs(2)))
+            .unwrap();
+        Self { socket }
+    }
+
+    fn addr(&self) -> SocketAddr {
+        self.socket.local_addr().unwrap()
+    }
+
+    fn send(&self, packet: &Packet, to: SocketAddr) {
+        self.socket.send_to(&packet.to_bytes(), to).unwrap();
+    }
+
+    fn recv(&self) -> (Packet, SocketAddr) {
+        let mut buffer = [0u8; 1500];
+        let (len, from) = self.socket.recv_from(&mut buffer).unwrap();
+        let packet = Packet::from_bytes(&buffer[..len]).unwrap();
+        assert!(packet.verify_checksum());
+        (packet, from)
+    }
+}
+
+/// Descarta a primeira transmissão de cada quarta sequência, de forma determinística.
+#[derive(Default)]
+struct LossyLink {
+    transmitted: HashSet<u32>,
+}
+
+impl LossyLink {
+    fn delivers(&mut self, packet: &Packet) -> bool {
+        let sequence = packet.header.sequence;
+        let first_transmission = self.transmitted.insert(sequence);
+        !(first_transmission && sequence.is_multiple_of(4))
+    }
+}
+
+#[test]
+fn reliable_packets_are_delivered_in_order_despite_loss() {
+    let server = Endpoint::bind();
+    let client = Endpoint::bind();
+
+    // Conexão.
+    let connect = Packet::new(MessageType::Connect.into(), 0, CLIENT_ID, Vec::new());
+    client.send(&connect, server.addr());
+    let (request, client_addr) = server.recv();
+    assert_eq!(request.header.message_type, u8::from(MessageType::Connect));
+    assert_eq!(request.header.player_id, CLIENT_ID);
+    server.send(&request, client_addr);
+    let (accepted, _) = client.recv();
+    assert_eq!(accepted.header.message_type, u8::from(MessageType::Connect));
+
+    // Envio confiável.
+    let mut reliable = ReliableChannel::new(RESEND_TIMEOUT);
+    let mut link = LossyLink::default();
+    let mut ordered = OrderedChannel::new(1);
+    let mut delivered = Vec::new();
+
+    let mut now = Instant::now();
+    let mut outgoing: Vec<Packet> = (1..=MESSAGES)
+        .map(|sequence| {
+            let payload = format!("mensagem {sequence}").into_bytes();
+            let packet = Packet::reliable(MessageType::Data.into(), sequence, CLIENT_ID, payload);
+            reliable.send(packet, now)
+        })
+        .collect();
+
+    for _round in 0..10 {
+        if outgoing.is_empty() {
+            break;
+        }
+
+        let mut in_flight = 0;
+        for packet in &outgoing {
+            if link.delivers(packet) {
+                client.send(packet, server.addr());
+                in_flight += 1;
+            }
+        }
+
+        // O servidor confirma cada pacote recebido e entrega os que estão em ordem.
+        for _ in 0..in_flight {
+            let (packet, from) = server.recv();
+            let ack = Packet::new(MessageType::Ack.into(), 0, 0, Vec::new())
+                .with_ack(packet.header.sequence, 0);
+            server.send(&ack, from);
+            delivered.extend(ordered.receive(packet));
+        }
+
+        for _ in 0..in_flight {
+            let (ack, _) = client.recv();
+            reliable.acknowledge_packet(&ack);
+        }
+
+        now += RESEND_TIMEOUT;
+        outgoing = reliable.resend_due(now);
+    }
+
+    assert_eq!(reliable.pending_count(), 0);
+    assert_eq!(ordered.buffered_len(), 0);
+
+    let sequences: Vec<u32> = delivered.iter().map(|p| p.header.sequence).collect();
+    assert_eq!(sequences, (1..=MESSAGES).collect::<Vec<_>>());
+    for packet in &delivered {
+        let expected = format!("mensagem {}", packet.header.sequence);
+        assert_eq!(packet.payload, expected.into_bytes());
+    }
+}
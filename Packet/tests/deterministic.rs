@@ -0,0 +1,341 @@
+//! Servidor e cliente conduzidos por um `MockClock` sobre uma `MemoryNetwork`: o
+//! aperto de mão, a troca de pacotes e a expiração da sessão acontecem nos mesmos
+//! ticks a cada execução, sem sockets nem tempo real. Os temporizadores avançados só
+//! por `update` mudam de estado no tick exato previsto pelas constantes. O
+//! `ServerRuntime`, que lê um socket de verdade em suas threads, e o `BufferedSender`
+//! também só veem o tempo passar pelo relógio injetado.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use packet::batch::{unpack_batch, BufferedSender};
+use packet::clock::{Clock, MockClock};
+use packet::connection::{
+    Client, ClientEvent, ConnectionState, Server, ServerEvent, CONNECT_TIMEOUT,
+    DISCONNECT_REDUNDANCY,
+};
+use packet::disconnect::DisconnectReason;
+use packet::message::ProtocolVersion;
+use packet::runtime::{RuntimeConfig, ServerRuntime};
+use packet::testing::{MemoryNetwork, MemoryTransport};
+use packet::transport::UdpTransport;
+use packet::{Packet, PacketError};
+
+const TICK_MS: u32 = 10;
+const TIMEOUT: Duration = Duration::from_millis(500);
+
+fn server_addr() -> SocketAddr {
+    "10.0.0.1:4000".parse().unwrap()
+}
+
+fn client_addr() -> SocketAddr {
+    "10.0.0.2:5000".parse().unwrap()
+}
+
+/// Servidor com [`TIMEOUT`] e cliente sobre a mesma rede, ambos no relógio `clock`.
+fn pair(
+    network: &MemoryNetwork,
+    clock: &MockClock,
+) -> (Server<MemoryTransport>, Client<MemoryTransport>) {
+    let server = Server::from_transport_with_clock(network.bind(server_addr()), clock)
+        .unwrap()
+        .with_timeout(TIMEOUT);
+    let client =
+        Client::from_transport(network.bind(client_addr()), server_addr(), clock.now()).unwrap();
+    (server, client)
+}
+
+/// Conduz o aperto de mão com `poll`; retorna a sessão com o relógio no tick seguinte
+/// ao da confirmação no cliente.
+fn connect(
+    server: &mut Server<MemoryTransport>,
+    client: &mut Client<MemoryTransport>,
+    clock: &MockClock,
+) -> u64 {
+    while client.state() == ConnectionState::Connecting {
+        assert!(clock.now_millis() < 1_000, "aperto de mão não concluído");
+        client.poll(clock.now()).unwrap();
+        server.poll(clock.now()).unwrap();
+        clock.advance(TICK_MS);
+    }
+    client.session_id().unwrap()
+}
+
+/// Evento de um dos lados, com o instante do tick em que ocorreu.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Traced {
+    Server(u32, ServerEvent),
+    Client(u32, ClientEvent),
+}
+
+/// Conecta, troca um pacote e deixa a sessão expirar; retorna todos os eventos.
+fn run() -> Vec<Traced> {
+    let network = MemoryNetwork::new();
+    let clock = MockClock::new(0);
+    let (mut server, mut client) = pair(&network, &clock);
+
+    let mut trace = Vec::new();
+    let tick = |server: &mut Server<_>, client: Option<&mut Client<_>>, trace: &mut Vec<_>| {
+        let now = clock.now();
+        let ms = clock.now_millis();
+        if let Some(client) = client {
+            let events = client.poll(now).unwrap();
+            trace.extend(events.into_iter().map(|event| Traced::Client(ms, event)));
+        }
+        let events = server.poll(now).unwrap();
+        trace.extend(events.into_iter().map(|event| Traced::Server(ms, event)));
+        clock.advance(TICK_MS);
+    };
+
+    while client.state() == ConnectionState::Connecting {
+        assert!(clock.now_millis() < 1_000, "aperto de mão não concluído");
+        tick(&mut server, Some(&mut client), &mut trace);
+    }
+    let session_id = client.session_id().unwrap();
+    client
+        .send(Packet::new(1, 1, session_id, vec![1, 2, 3]))
+        .unwrap();
+    tick(&mut server, Some(&mut client), &mut trace);
+
+    // O cliente para de responder; só o servidor segue avançando.
+    while server.session_count() > 0 {
+        assert!(clock.now_millis() < 5_000, "sessão não expirou");
+        tick(&mut server, None, &mut trace);
+    }
+    trace
+}
+
+#[test]
+fn the_whole_stack_is_driven_by_the_injected_clock() {
+    let trace = run();
+    assert_eq!(trace, run(), "execuções com o mesmo relógio divergiram");
+
+    let connected = trace
+        .iter()
+        .find_map(|event| match event {
+            Traced::Server(ms, ServerEvent::Connected { session_id, .. }) => {
+                Some((*ms, *session_id))
+            }
+            _ => None,
+        })
+        .unwrap();
+    let (connected_at, session_id) = connected;
+    assert!(trace.contains(&Traced::Client(
+        connected_at + TICK_MS,
+        ClientEvent::Connected { session_id }
+    )));
+
+    let received = trace.iter().find_map(|event| match event {
+        Traced::Server(ms, ServerEvent::Packet { packet, .. }) => Some((*ms, &packet.payload)),
+        _ => None,
+    });
+    let (received_at, payload) = received.unwrap();
+    assert_eq!(payload, &[1, 2, 3]);
+
+    // A última notícia do cliente chegou no tick do pacote; a sessão expira no
+    // primeiro tick em que o tempo limite foi ultrapassado.
+    let expired_at = received_at + TIMEOUT.as_millis() as u32 + TICK_MS;
+    assert_eq!(
+        trace.last(),
+        Some(&Traced::Server(
+            expired_at,
+            ServerEvent::TimedOut { session_id }
+        ))
+    );
+}
+
+#[test]
+fn server_update_expires_a_silent_session_on_the_exact_tick() {
+    let network = MemoryNetwork::new();
+    let clock = MockClock::new(0);
+    let (mut server, mut client) = pair(&network, &clock);
+    let session_id = connect(&mut server, &mut client, &clock);
+
+    // O último pacote do cliente foi lido no tick anterior; sem novos `poll`, só o
+    // tempo limite estrito decide a expiração.
+    let last_heard = server.last_heard(session_id).unwrap();
+    let expires_at = last_heard + TIMEOUT + Duration::from_millis(TICK_MS.into());
+    loop {
+        let now = clock.now();
+        let events = server.update(now).unwrap();
+        if now < expires_at {
+            assert!(
+                events.is_empty(),
+                "evento antes do tempo limite: {events:?}"
+            );
+            assert_eq!(server.session_count(), 1);
+        } else {
+            assert_eq!(now, expires_at);
+            assert_eq!(events, [ServerEvent::TimedOut { session_id }]);
+            assert_eq!(server.session_count(), 0);
+            break;
+        }
+        clock.advance(TICK_MS);
+    }
+}
+
+#[test]
+fn client_update_sends_every_disconnect_copy_before_disconnecting() {
+    let network = MemoryNetwork::new();
+    let clock = MockClock::new(0);
+    let (mut server, mut client) = pair(&network, &clock);
+    let session_id = connect(&mut server, &mut client, &clock);
+
+    let in_flight = network.in_flight();
+    client.disconnect(DisconnectReason::Quit);
+    for remaining in (0..DISCONNECT_REDUNDANCY).rev() {
+        assert_eq!(client.state(), ConnectionState::Disconnecting);
+        assert!(client.update(clock.now()).unwrap().is_empty());
+        clock.advance(TICK_MS);
+        let expected = if remaining == 0 {
+            ConnectionState::Disconnected
+        } else {
+            ConnectionState::Disconnecting
+        };
+        assert_eq!(client.state(), expected);
+    }
+    assert_eq!(
+        network.in_flight(),
+        in_flight + DISCONNECT_REDUNDANCY as usize
+    );
+
+    // A primeira cópia encerra a sessão; as demais chegam a uma sessão inexistente.
+    let events = server.poll(clock.now()).unwrap();
+    assert_eq!(
+        events,
+        [ServerEvent::Disconnected {
+            session_id,
+            reason: Some(DisconnectReason::Quit),
+        }]
+    );
+    assert!(client.update(clock.now()).unwrap().is_empty());
+    assert_eq!(client.state(), ConnectionState::Disconnected);
+}
+
+#[test]
+fn client_update_times_out_the_handshake_on_the_exact_tick() {
+    // Nenhum servidor no endereço: os pedidos de conexão se perdem.
+    let network = MemoryNetwork::new();
+    let clock = MockClock::new(0);
+    let mut client =
+        Client::from_transport(network.bind(client_addr()), server_addr(), clock.now()).unwrap();
+
+    let timeout_ms = CONNECT_TIMEOUT.as_millis() as u32;
+    while clock.now_millis() < timeout_ms {
+        assert!(client.update(clock.now()).unwrap().is_empty());
+        assert_eq!(client.state(), ConnectionState::Connecting);
+        clock.advance(TICK_MS);
+    }
+    assert_eq!(client.update(clock.now()).unwrap(), [ClientEvent::TimedOut]);
+    assert_eq!(client.state(), ConnectionState::TimedOut);
+    assert!(client.update(clock.now()).unwrap().is_empty());
+}
+
+#[test]
+fn an_incompatible_client_is_rejected_one_tick_after_asking() {
+    let network = MemoryNetwork::new();
+    let clock = MockClock::new(0);
+    let (server, client) = pair(&network, &clock);
+    let mut server = server.with_protocol_version(ProtocolVersion::new(2, 0));
+    let mut client = client.with_protocol_version(ProtocolVersion::new(1, 0));
+
+    // Tick 0: o pedido sai no `update` do cliente e é recusado no `poll` do servidor.
+    assert!(client.update(clock.now()).unwrap().is_empty());
+    let events = server.poll(clock.now()).unwrap();
+    let error = PacketError::IncompatibleVersion {
+        local: ProtocolVersion::new(2, 0),
+        remote: ProtocolVersion::new(1, 0),
+    };
+    assert_eq!(
+        events,
+        [ServerEvent::Rejected {
+            addr: client_addr(),
+            error: error.clone(),
+        }]
+    );
+    assert_eq!(client.state(), ConnectionState::Connecting);
+
+    // Tick 1: a recusa chega ao cliente, que não volta a pedir.
+    clock.advance(TICK_MS);
+    let events = client.poll(clock.now()).unwrap();
+    assert!(matches!(
+        events.as_slice(),
+        [ClientEvent::Rejected {
+            error: PacketError::IncompatibleVersion { .. }
+        }]
+    ));
+    assert_eq!(client.state(), ConnectionState::Disconnected);
+    clock.advance(TICK_MS * 20);
+    assert!(client.update(clock.now()).unwrap().is_empty());
+    assert_eq!(network.in_flight(), 0);
+}
+
+#[test]
+fn server_runtime_sessions_expire_only_when_the_injected_clock_advances() {
+    let clock = Arc::new(MockClock::new(0));
+    let transport = UdpTransport::bind("127.0.0.1:0").unwrap();
+    let config = RuntimeConfig::new()
+        .with_workers(2)
+        .with_tick(Duration::from_millis(1));
+    let runtime = ServerRuntime::start_with_clock(transport, config, clock.clone(), |server| {
+        server.with_timeout(TIMEOUT)
+    })
+    .unwrap();
+    let mut client = Client::connect(runtime.local_addr(), clock.now()).unwrap();
+    let mut session_id = None;
+    // Até o primeiro ping do cliente ser respondido: um ping que chegasse depois do
+    // avanço do relógio renovaria a sessão.
+    while session_id.is_none() || client.rtt().is_none() {
+        client.poll(clock.now()).unwrap();
+        if let Some(ServerEvent::Connected { session_id: id, .. }) =
+            runtime.recv_event(Duration::from_millis(5))
+        {
+            session_id = Some(id);
+        }
+    }
+    let session_id = session_id.unwrap();
+
+    // O cliente se cala: o dobro do tempo limite passa no tempo real, não no relógio.
+    assert_eq!(runtime.recv_event(2 * TIMEOUT), None);
+    assert_eq!(runtime.handle().session_count(), 1);
+
+    clock.advance(2 * TIMEOUT.as_millis() as u32);
+    assert_eq!(
+        runtime.recv_event(Duration::from_secs(5)),
+        Some(ServerEvent::TimedOut { session_id })
+    );
+    runtime.shutdown().unwrap();
+}
+
+#[test]
+fn buffered_sender_windows_are_measured_on_the_injected_clock() {
+    let clock = MockClock::new(0);
+    let window = Duration::from_millis(TICK_MS.into());
+    let mut sender = BufferedSender::new(window, 1024, 1200);
+
+    sender
+        .enqueue_at(Packet::new(1, 1, 7, vec![1]), clock.now())
+        .unwrap();
+    clock.advance(TICK_MS - 1);
+    sender
+        .enqueue_at(Packet::new(1, 2, 7, vec![2]), clock.now())
+        .unwrap();
+    assert_eq!(sender.flush_due(clock.now()), None);
+
+    // Expira no tick exato da janela do primeiro pacote, por mais atrasado que esteja.
+    clock.advance(1);
+    let datagram = sender.flush_due(clock.now()).unwrap();
+    assert_eq!(unpack_batch(&datagram).unwrap().len(), 2);
+
+    // O próximo lote conta a janela do seu próprio primeiro pacote.
+    clock.advance(10 * TICK_MS);
+    sender
+        .enqueue_at(Packet::new(1, 3, 7, vec![3]), clock.now())
+        .unwrap();
+    assert_eq!(sender.flush_due(clock.now()), None);
+    clock.advance(TICK_MS);
+    let datagram = sender.flush_due(clock.now()).unwrap();
+    assert_eq!(unpack_batch(&datagram).unwrap().len(), 1);
+    assert!(sender.is_empty());
+}
@@ -0,0 +1,92 @@
+//! Pacotes comprimidos enviados por um par com a sessão correta: sem compressão
+//! negociada eles são descartados, e o tamanho original declarado não passa do limite
+//! do payload antes de chegar ao descompressor.
+#![cfg(feature = "compression")]
+
+use std::net::SocketAddr;
+
+use packet::builder::PacketBuilder;
+use packet::clock::{Clock, MockClock};
+use packet::compression::{CompressionAlgorithm, Compressor, COMPRESSION_ALGORITHM_OPTION};
+use packet::connection::{Client, ConnectionState, Server, ServerEvent};
+use packet::testing::{MemoryNetwork, MemoryTransport};
+use packet::transport::Transport;
+use packet::Packet;
+
+const TICK_MS: u32 = 10;
+
+/// Conduz o aperto de mão com `poll`; retorna a sessão do cliente.
+fn connect(
+    server: &mut Server<MemoryTransport>,
+    client: &mut Client<MemoryTransport>,
+    clock: &MockClock,
+) -> u64 {
+    while client.state() == ConnectionState::Connecting {
+        assert!(clock.now_millis() < 1_000, "aperto de mão não concluído");
+        client.poll(clock.now()).unwrap();
+        server.poll(clock.now()).unwrap();
+        clock.advance(TICK_MS);
+    }
+    client.session_id().unwrap()
+}
+
+/// Pacote de poucos bytes que declara [`u32::MAX`] bytes originais comprimidos com LZ4.
+fn bomb(session_id: u64, sequence: u32) -> Packet {
+    let mut builder = PacketBuilder::new(1, sequence, session_id);
+    builder.mark_compressed(u32::MAX).unwrap();
+    let lz4 = CompressionAlgorithm::Lz4.id();
+    builder
+        .add_option(COMPRESSION_ALGORITHM_OPTION, &[lz4])
+        .unwrap();
+    builder.extend([255, 0]);
+    builder.seal()
+}
+
+/// Envia a bomba em nome do cliente e depois um pacote legítimo, que deve ser o único
+/// entregue.
+fn assert_only_the_genuine_packet_arrives(compression: Option<CompressionAlgorithm>) {
+    let server_addr: SocketAddr = "10.0.0.1:4000".parse().unwrap();
+    let client_addr: SocketAddr = "10.0.0.2:5000".parse().unwrap();
+    let network = MemoryNetwork::new();
+    let clock = MockClock::new(0);
+    let client_transport = network.bind(client_addr);
+    let mut as_client = client_transport.clone();
+
+    let mut server = Server::from_transport_with_clock(network.bind(server_addr), &clock).unwrap();
+    let mut client = Client::from_transport(client_transport, server_addr, clock.now()).unwrap();
+    if let Some(algorithm) = compression {
+        server = server.with_compression(Compressor::new(algorithm));
+        client = client.with_compression(0);
+    }
+    let session_id = connect(&mut server, &mut client, &clock);
+
+    as_client
+        .send_packet(server_addr, &bomb(session_id, 1))
+        .unwrap();
+    let events = server.poll(clock.now()).unwrap();
+    assert!(events.is_empty(), "{compression:?}: {events:?}");
+
+    client
+        .send(Packet::new(1, 2, session_id, vec![7; 1000]))
+        .unwrap();
+    let events = server.poll(clock.now()).unwrap();
+    assert!(
+        matches!(
+            &events[..],
+            [ServerEvent::Packet { packet, .. }] if packet.payload == [7; 1000]
+        ),
+        "{compression:?}: {events:?}"
+    );
+}
+
+#[test]
+fn compressed_packets_are_dropped_without_negotiated_compression() {
+    assert_only_the_genuine_packet_arrives(None);
+}
+
+#[test]
+fn a_declared_size_above_the_payload_limit_is_never_decompressed() {
+    for algorithm in CompressionAlgorithm::ALL {
+        assert_only_the_genuine_packet_arrives(Some(algorithm));
+    }
+}
@@ -0,0 +1,178 @@
+//! Pings, pongs e desconexões forjados com o endereço e a sessão corretos contra
+//! conexões assinadas e cifradas: sem a etiqueta das chaves da sessão, eles não
+//! encerram a conexão nem a mantêm viva, enquanto os legítimos seguem funcionando.
+#![cfg(feature = "crypto")]
+
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use packet::clock::{Clock, MockClock};
+use packet::connection::{
+    Client, ConnectionState, SecurityLevel, Server, ServerEvent, DEFAULT_CONNECTION_TIMEOUT,
+};
+use packet::disconnect::DisconnectReason;
+use packet::message::MessageType;
+use packet::testing::{MemoryNetwork, MemoryTransport};
+use packet::transport::Transport;
+use packet::Packet;
+
+const TICK_MS: u32 = 10;
+const TIMEOUT: Duration = Duration::from_millis(500);
+const LEVELS: [SecurityLevel; 2] = [SecurityLevel::Signed, SecurityLevel::Encrypted];
+
+/// Servidor e cliente conectados, com cópias dos transportes que enviam em nome de
+/// cada um.
+struct Connected {
+    clock: MockClock,
+    server: Server<MemoryTransport>,
+    client: Client<MemoryTransport>,
+    /// Envia com o endereço do cliente.
+    as_client: MemoryTransport,
+    /// Envia com o endereço do servidor.
+    as_server: MemoryTransport,
+    session_id: u64,
+}
+
+impl Connected {
+    fn new(security: SecurityLevel) -> Self {
+        let server_addr: SocketAddr = "10.0.0.1:4000".parse().unwrap();
+        let client_addr: SocketAddr = "10.0.0.2:5000".parse().unwrap();
+        let network = MemoryNetwork::new();
+        let clock = MockClock::new(0);
+        let (server_transport, client_transport) =
+            (network.bind(server_addr), network.bind(client_addr));
+        let (as_server, as_client) = (server_transport.clone(), client_transport.clone());
+
+        let mut server = Server::from_transport_with_clock(server_transport, &clock)
+            .unwrap()
+            .with_security(security)
+            .with_timeout(TIMEOUT);
+        let mut client = Client::from_transport(client_transport, server_addr, clock.now())
+            .unwrap()
+            .with_security(security);
+        while client.state() == ConnectionState::Connecting {
+            assert!(clock.now_millis() < 1_000, "aperto de mão não concluído");
+            client.poll(clock.now()).unwrap();
+            server.poll(clock.now()).unwrap();
+            clock.advance(TICK_MS);
+        }
+        let session_id = client.session_id().unwrap();
+        Self {
+            clock,
+            server,
+            client,
+            as_client,
+            as_server,
+            session_id,
+        }
+    }
+
+    fn forge_to_server(&mut self, packet: &Packet) {
+        let server_addr = self.as_server.local_addr().unwrap();
+        self.as_client.send_packet(server_addr, packet).unwrap();
+    }
+
+    fn forge_to_client(&mut self, packet: &Packet) {
+        let client_addr = self.as_client.local_addr().unwrap();
+        self.as_server.send_packet(client_addr, packet).unwrap();
+    }
+}
+
+#[test]
+fn forged_disconnects_do_not_end_the_session() {
+    for security in LEVELS {
+        let mut link = Connected::new(security);
+        let session_id = link.session_id;
+
+        link.forge_to_server(&Packet::disconnect(session_id, DisconnectReason::Quit));
+        let events = link.server.poll(link.clock.now()).unwrap();
+        assert!(events.is_empty(), "{security:?}: {events:?}");
+        assert_eq!(link.server.session_count(), 1, "{security:?}");
+
+        let reason = DisconnectReason::ServerShutdown;
+        link.forge_to_client(&Packet::disconnect(session_id, reason));
+        let events = link.client.poll(link.clock.now()).unwrap();
+        assert!(events.is_empty(), "{security:?}: {events:?}");
+        assert_eq!(
+            link.client.state(),
+            ConnectionState::Connected,
+            "{security:?}"
+        );
+
+        // A desconexão legítima, autenticada pelo cliente, encerra a sessão.
+        link.client.disconnect(DisconnectReason::Quit);
+        link.client.update(link.clock.now()).unwrap();
+        let events = link.server.poll(link.clock.now()).unwrap();
+        assert_eq!(
+            events,
+            [ServerEvent::Disconnected {
+                session_id,
+                reason: Some(DisconnectReason::Quit),
+            }],
+            "{security:?}"
+        );
+    }
+}
+
+#[test]
+fn forged_pongs_and_pings_do_not_keep_a_silent_session_alive() {
+    for security in LEVELS {
+        let mut link = Connected::new(security);
+        let session_id = link.session_id;
+        let last_heard = link.server.last_heard(session_id).unwrap();
+        let expires_at = last_heard + TIMEOUT + Duration::from_millis(TICK_MS.into());
+        let rtt = link.server.rtt(session_id);
+
+        // O cliente se cala; só chegam pongs e pings forjados em seu nome.
+        let mut sequence = 0;
+        loop {
+            let now = link.clock.now();
+            for message_type in [MessageType::Pong, MessageType::Ping] {
+                let forged = Packet::new(message_type.into(), sequence, session_id, vec![]);
+                link.forge_to_server(&forged);
+            }
+            sequence += 1;
+            let events = link.server.poll(now).unwrap();
+            assert_eq!(
+                link.server.last_heard(session_id),
+                Some(last_heard).filter(|_| now < expires_at)
+            );
+            if now < expires_at {
+                assert!(events.is_empty(), "{security:?}: {events:?}");
+                assert_eq!(link.server.rtt(session_id), rtt, "{security:?}");
+            } else {
+                assert_eq!(now, expires_at, "{security:?}");
+                assert_eq!(
+                    events,
+                    [ServerEvent::TimedOut { session_id }],
+                    "{security:?}"
+                );
+                break;
+            }
+            link.clock.advance(TICK_MS);
+        }
+    }
+}
+
+#[test]
+fn authenticated_pings_keep_both_sides_alive_and_measure_rtt() {
+    for security in LEVELS {
+        let mut link = Connected::new(security);
+        let timeout_ms = DEFAULT_CONNECTION_TIMEOUT.as_millis() as u32;
+        let until = link.clock.now_millis() + 2 * timeout_ms;
+        while link.clock.now_millis() < until {
+            let now = link.clock.now();
+            assert!(link.client.poll(now).unwrap().is_empty(), "{security:?}");
+            assert!(link.server.poll(now).unwrap().is_empty(), "{security:?}");
+            link.clock.advance(TICK_MS);
+        }
+        assert_eq!(
+            link.client.state(),
+            ConnectionState::Connected,
+            "{security:?}"
+        );
+        assert_eq!(link.server.session_count(), 1, "{security:?}");
+        assert!(link.client.rtt().is_some(), "{security:?}");
+        assert!(link.server.rtt(link.session_id).is_some(), "{security:?}");
+    }
+}
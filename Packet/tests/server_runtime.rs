@@ -0,0 +1,100 @@
+//! Vários clientes conectados a um `ServerRuntime` com quatro trabalhadores: sessões
+//! distintas, pacotes entregues à sessão de origem e envios de várias threads ao
+//! mesmo tempo.
+
+use std::collections::HashMap;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use packet::connection::{Client, ClientEvent, ServerEvent};
+use packet::runtime::{RuntimeConfig, ServerRuntime};
+use packet::transport::UdpTransport;
+use packet::Packet;
+
+const CLIENTS: usize = 8;
+const DEADLINE: Duration = Duration::from_secs(5);
+
+#[test]
+fn sessions_are_spread_across_workers_and_reachable_from_any_thread() {
+    let transport = UdpTransport::bind("127.0.0.1:0").unwrap();
+    let runtime = ServerRuntime::start(transport, RuntimeConfig::new().with_workers(4)).unwrap();
+    let mut clients: Vec<_> = (0..CLIENTS)
+        .map(|_| Client::connect(runtime.local_addr(), Instant::now()).unwrap())
+        .collect();
+
+    let deadline = Instant::now() + DEADLINE;
+    let mut sessions = HashMap::new();
+    while sessions.len() < CLIENTS || clients.iter().any(|client| client.session_id().is_none()) {
+        assert!(Instant::now() < deadline, "clientes sem sessão");
+        for client in &mut clients {
+            client.poll(Instant::now()).unwrap();
+        }
+        while let Some(event) = runtime.recv_event(Duration::from_millis(1)) {
+            if let ServerEvent::Connected { session_id, addr } = event {
+                sessions.insert(session_id, addr);
+            }
+        }
+    }
+    for client in &clients {
+        assert!(sessions.contains_key(&client.session_id().unwrap()));
+    }
+    while runtime.handle().session_count() < CLIENTS {
+        assert!(Instant::now() < deadline, "sessões não contadas");
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    // Cada cliente envia seu índice; o evento chega com a sessão do cliente.
+    for (index, client) in clients.iter_mut().enumerate() {
+        let session_id = client.session_id().unwrap();
+        client
+            .send(Packet::new(1, 1, session_id, vec![index as u8]))
+            .unwrap();
+    }
+    let mut received = HashMap::new();
+    while received.len() < CLIENTS {
+        assert!(Instant::now() < deadline, "pacotes não entregues");
+        if let Some(ServerEvent::Packet { session_id, packet }) =
+            runtime.recv_event(Duration::from_millis(10))
+        {
+            received.insert(session_id, packet.payload[0]);
+        }
+    }
+    for (index, client) in clients.iter().enumerate() {
+        assert_eq!(received[&client.session_id().unwrap()], index as u8);
+    }
+
+    // Uma thread por cliente responde pelo mesmo acesso ao servidor.
+    let senders: Vec<_> = clients
+        .iter()
+        .map(|client| {
+            let handle = runtime.handle();
+            let session_id = client.session_id().unwrap();
+            thread::spawn(move || {
+                let payload = session_id.to_le_bytes().to_vec();
+                handle
+                    .send_to_player(session_id, Packet::new(1, 1, 0, payload))
+                    .unwrap();
+            })
+        })
+        .collect();
+    for sender in senders {
+        sender.join().unwrap();
+    }
+
+    for client in &mut clients {
+        let session_id = client.session_id().unwrap();
+        let payload = loop {
+            assert!(Instant::now() < deadline, "resposta não entregue");
+            let events = client.poll(Instant::now()).unwrap();
+            if let Some(payload) = events.into_iter().find_map(|event| match event {
+                ClientEvent::Packet(packet) => Some(packet.payload),
+                _ => None,
+            }) {
+                break payload;
+            }
+            thread::sleep(Duration::from_millis(1));
+        };
+        assert_eq!(payload, session_id.to_le_bytes());
+    }
+    runtime.shutdown().unwrap();
+}
@@ -0,0 +1,170 @@
+//! Vetores de referência do formato na rede: pacotes fixos serializados byte a byte.
+//!
+//! O formato serializado é o contrato do crate com implementações em outras
+//! linguagens. Qualquer mudança na ordem dos campos, na ordem dos bytes ou no tamanho
+//! do cabeçalho quebra estes testes; se a mudança for intencional, atualize os
+//! vetores junto com [`packet::wire::wire_format_description`].
+//!
+//! O esquema e os vetores em `tests/vectors/` são os arquivos usados por outras
+//! implementações; com `UPDATE_WIRE_VECTORS=1`, o teste os regrava em vez de
+//! compará-los.
+
+use std::path::Path;
+
+use packet::wire::{header_schema_json, test_vectors, test_vectors_json, WireFormat};
+use packet::{Packet, PacketHeader};
+
+const HEADER_SCHEMA: &str = include_str!("vectors/header_schema.json");
+const WIRE_VECTORS: &str = include_str!("vectors/wire_vectors.json");
+
+/// Verifica a serialização e a leitura de volta de um vetor de referência.
+fn assert_golden(packet: &Packet, golden: &[u8]) {
+    assert_eq!(packet.to_bytes(), golden);
+    assert_eq!(&Packet::from_bytes(golden).unwrap(), packet);
+}
+
+#[test]
+fn empty_payload() {
+    let packet = Packet::new(1, 42, 0x0102_0304_0506_0708, vec![]);
+
+    #[rustfmt::skip]
+    let golden = [
+        0x52, 0x4E,                                     // magic "RN"
+        0x01,                                           // version
+        0x01,                                           // message_type
+        0x00, 0x00,                                     // flags
+        0x00,                                           // priority
+        0x00,                                           // channel_id
+        0x00,                                           // content_type
+        0x40,                                           // ttl
+        0x2A, 0x00, 0x00, 0x00,                         // sequence
+        0x08, 0x07, 0x06, 0x05, 0x04, 0x03, 0x02, 0x01, // player_id
+        0x00, 0x00, 0x00, 0x00,                         // group_id
+        0x00, 0x00, 0x00, 0x00,                         // ack
+        0x00, 0x00, 0x00, 0x00,                         // ack_bits
+        0x00, 0x00, 0x00, 0x00,                         // payload_size
+        0xAF,                                           // header_checksum
+        0x00, 0x00, 0xED, 0x5E,                         // checksum
+    ];
+    assert_eq!(golden.len(), PacketHeader::SIZE);
+    assert_golden(&packet, &golden);
+}
+
+#[test]
+fn multi_byte_payload() {
+    let packet = Packet::reliable(
+        0x80,
+        0xA1B2_C3D4,
+        7,
+        vec![0xDE, 0xAD, 0xBE, 0xEF, 0x00, 0x7F],
+    )
+    .with_priority(3)
+    .with_channel_id(2)
+    .with_group_id(0x0000_0100)
+    .with_ack(41, 0xFFFF_0001);
+
+    #[rustfmt::skip]
+    let golden = [
+        0x52, 0x4E,                                     // magic "RN"
+        0x01,                                           // version
+        0x80,                                           // message_type
+        0x05, 0x00,                                     // flags: RELIABLE | ACK
+        0x03,                                           // priority
+        0x02,                                           // channel_id
+        0x00,                                           // content_type
+        0x40,                                           // ttl
+        0xD4, 0xC3, 0xB2, 0xA1,                         // sequence
+        0x07, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // player_id
+        0x00, 0x01, 0x00, 0x00,                         // group_id
+        0x29, 0x00, 0x00, 0x00,                         // ack
+        0x01, 0x00, 0xFF, 0xFF,                         // ack_bits
+        0x06, 0x00, 0x00, 0x00,                         // payload_size
+        0x0A,                                           // header_checksum
+        0xBD, 0x03, 0xED, 0x5E,                         // checksum
+        0xDE, 0xAD, 0xBE, 0xEF, 0x00, 0x7F,             // payload
+    ];
+    assert_golden(&packet, &golden);
+}
+
+#[test]
+fn network_byte_order() {
+    let packet = Packet::reliable(
+        0x80,
+        0xA1B2_C3D4,
+        7,
+        vec![0xDE, 0xAD, 0xBE, 0xEF, 0x00, 0x7F],
+    )
+    .with_priority(3)
+    .with_channel_id(2)
+    .with_group_id(0x0000_0100)
+    .with_ack(41, 0xFFFF_0001);
+
+    #[rustfmt::skip]
+    let golden = [
+        0x52, 0x4E,                                     // magic "RN"
+        0x01,                                           // version
+        0x80,                                           // message_type
+        0x00, 0x05,                                     // flags: RELIABLE | ACK
+        0x03,                                           // priority
+        0x02,                                           // channel_id
+        0x00,                                           // content_type
+        0x40,                                           // ttl
+        0xA1, 0xB2, 0xC3, 0xD4,                         // sequence
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x07, // player_id
+        0x00, 0x00, 0x01, 0x00,                         // group_id
+        0x00, 0x00, 0x00, 0x29,                         // ack
+        0xFF, 0xFF, 0x00, 0x01,                         // ack_bits
+        0x00, 0x00, 0x00, 0x06,                         // payload_size
+        0x48,                                           // header_checksum
+        0x5E, 0xED, 0x03, 0xBD,                         // checksum
+        0xDE, 0xAD, 0xBE, 0xEF, 0x00, 0x7F,             // payload
+    ];
+    assert_eq!(packet.to_bytes_with_format(WireFormat::NETWORK), golden);
+    assert_eq!(
+        Packet::from_bytes_with_format(&golden, WireFormat::NETWORK).unwrap(),
+        packet
+    );
+}
+
+/// Compara um arquivo de referência com o texto gerado, ou o regrava.
+fn assert_golden_file(name: &str, stored: &str, generated: &str) {
+    if std::env::var_os("UPDATE_WIRE_VECTORS").is_some() {
+        let path = Path::new(file!()).with_file_name("vectors").join(name);
+        std::fs::write(path, generated).unwrap();
+        return;
+    }
+    assert_eq!(
+        stored, generated,
+        "tests/vectors/{name} desatualizado; rode com UPDATE_WIRE_VECTORS=1"
+    );
+}
+
+#[test]
+fn exported_schema_and_vectors_match_the_code() {
+    assert_golden_file(
+        "header_schema.json",
+        HEADER_SCHEMA,
+        &header_schema_json(WireFormat::STANDARD),
+    );
+    let vectors = test_vectors();
+    assert_golden_file(
+        "wire_vectors.json",
+        WIRE_VECTORS,
+        &test_vectors_json(&vectors),
+    );
+
+    for vector in &vectors {
+        let bytes = vector.expected_bytes();
+        assert_eq!(
+            Packet::from_bytes_with_format(&bytes, vector.format).as_ref(),
+            Ok(&vector.packet),
+            "vetor {}",
+            vector.name
+        );
+    }
+    // O primeiro vetor é o mesmo de `empty_payload`.
+    assert_eq!(
+        vectors[0].expected_bytes(),
+        Packet::new(1, 42, 0x0102_0304_0506_0708, vec![]).to_bytes()
+    );
+}
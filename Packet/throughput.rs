@@ -0,0 +1,123 @@
+//! # Módulo `throughput`
+//!
+//! Medição da taxa de envio ou recepção em uma janela deslizante, para gráficos de
+//! banda em tempo real.
+//!
+//! [`ThroughputMeter`] acumula bytes em um anel de faixas de tempo de largura fixa,
+//! de forma que a memória usada não depende da quantidade de pacotes registrados.
+
+use std::time::{Duration, Instant};
+
+use crate::Packet;
+
+/// Medidor de taxa de transferência em uma janela deslizante.
+///
+/// A taxa é calculada até a faixa do registro mais recente; para que períodos sem
+/// tráfego reduzam a taxa, chame [`ThroughputMeter::record`] com `0` bytes
+/// periodicamente.
+///
+/// ## Exemplos
+/// ```rust
+/// use std::time::{Duration, Instant};
+/// use packet::{Packet, PacketHeader};
+/// use packet::throughput::ThroughputMeter;
+///
+/// let mut meter = ThroughputMeter::new(Duration::from_millis(100), 10);
+/// let start = Instant::now();
+/// let packet = Packet::new(1, 1, 7, vec![0; 1250 - PacketHeader::SIZE]);
+///
+/// // Um pacote de 1250 bytes a cada 100 ms: 100 kbit/s.
+/// for step in 0..10 {
+///     meter.record_packet(&packet, start + Duration::from_millis(100) * step);
+/// }
+/// assert_eq!(meter.bits_per_second(Duration::from_secs(1)), 100_000.0);
+///
+/// // Após meio segundo sem tráfego, metade da janela está vazia.
+/// meter.record(0, start + Duration::from_millis(1400));
+/// assert_eq!(meter.bits_per_second(Duration::from_secs(1)), 50_000.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ThroughputMeter {
+    /// Largura de cada faixa de tempo.
+    bucket_width: Duration,
+    /// Bytes registrados em cada faixa, em anel.
+    buckets: Vec<u64>,
+    /// Posição da faixa mais recente em `buckets`.
+    head: usize,
+    /// Início da faixa mais recente; `None` antes do primeiro registro.
+    head_start: Option<Instant>,
+}
+
+impl ThroughputMeter {
+    /// Cria um medidor sem registros.
+    ///
+    /// ## Parâmetros
+    /// - `bucket_width`: Largura de cada faixa; determina a resolução da janela.
+    /// - `bucket_count`: Quantidade de faixas; a maior janela medida é
+    ///   `bucket_width * bucket_count`.
+    ///
+    /// ## Retorno
+    /// - `Self`: Um medidor com o espaço já alocado.
+    pub fn new(bucket_width: Duration, bucket_count: usize) -> Self {
+        Self {
+            bucket_width: bucket_width.max(Duration::from_nanos(1)),
+            buckets: vec![0; bucket_count.max(1)],
+            head: 0,
+            head_start: None,
+        }
+    }
+
+    /// Registra bytes enviados ou recebidos.
+    ///
+    /// Registros com instante anterior ao da faixa mais recente são contados nela.
+    ///
+    /// ## Parâmetros
+    /// - `bytes`: Quantidade de bytes transferidos.
+    /// - `now`: Instante da transferência.
+    pub fn record(&mut self, bytes: usize, now: Instant) {
+        let head_start = *self.head_start.get_or_insert(now);
+        let elapsed = now.saturating_duration_since(head_start).as_nanos();
+        let steps = elapsed / self.bucket_width.as_nanos();
+
+        if steps > 0 {
+            for _ in 0..steps.min(self.buckets.len() as u128) {
+                self.head = (self.head + 1) % self.buckets.len();
+                self.buckets[self.head] = 0;
+            }
+            let advanced = self.bucket_width.as_nanos() * steps;
+            self.head_start = Some(head_start + Duration::from_nanos(advanced as u64));
+        }
+        self.buckets[self.head] += bytes as u64;
+    }
+
+    /// Registra um pacote pelo seu tamanho serializado.
+    ///
+    /// ## Parâmetros
+    /// - `packet`: Pacote enviado ou recebido.
+    /// - `now`: Instante da transferência.
+    pub fn record_packet(&mut self, packet: &Packet, now: Instant) {
+        self.record(packet.serialized_len(), now);
+    }
+
+    /// Calcula a taxa média nas faixas mais recentes que cobrem a janela.
+    ///
+    /// ## Parâmetros
+    /// - `window`: Duração da janela; arredondada para cima até um múltiplo da largura
+    ///   das faixas e limitada à cobertura do medidor.
+    ///
+    /// ## Retorno
+    /// - `f64`: Bits por segundo na janela.
+    pub fn bits_per_second(&self, window: Duration) -> f64 {
+        let width = self.bucket_width.as_nanos();
+        let count = window
+            .as_nanos()
+            .div_ceil(width)
+            .clamp(1, self.buckets.len() as u128) as usize;
+
+        let bytes: u64 = (0..count)
+            .map(|back| self.buckets[(self.head + self.buckets.len() - back) % self.buckets.len()])
+            .sum();
+        let seconds = (width * count as u128) as f64 / 1e9;
+        bytes as f64 * 8.0 / seconds
+    }
+}
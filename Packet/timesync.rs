@@ -0,0 +1,347 @@
+//! # Módulo `timesync`
+//!
+//! Sincronização do relógio do cliente com o do servidor, no estilo do NTP, sobre os
+//! pings e pongs da conexão.
+//!
+//! Quem responde a um [`MessageType::Ping`] grava no [`MessageType::Pong`] o seu
+//! relógio, em microssegundos, com [`Packet::pong`]. Quem enviou o ping conhece o
+//! instante do envio e o da chegada do pong; supondo o caminho simétrico, o relógio
+//! remoto foi lido no meio do RTT, e a diferença entre os dois relógios é estimada
+//! por [`TimeSync::record`].
+//!
+//! [`TimeSync`] guarda as amostras mais recentes e descarta como atípicas as de RTT
+//! alto, em que a assimetria da fila distorce a estimativa: só a metade de menor RTT
+//! é usada. Com amostras espalhadas por pelo menos [`MIN_DRIFT_SPAN`], a deriva entre
+//! os relógios é estimada por regressão linear; antes disso, vale a mediana das
+//! diferenças. A ressincronização é periódica: a cada [`DEFAULT_RESYNC_INTERVAL`],
+//! [`TimeSync::poll`] pede uma rajada de [`SYNC_BURST`] pings, que renova a janela
+//! mais depressa que os pings de manutenção.
+//!
+//! O [`Client`](crate::connection::Client) mede o relógio do servidor por todos os
+//! pongs e o expõe em
+//! [`Client::server_time_now`](crate::connection::Client::server_time_now); o relógio
+//! do servidor é o tempo desde a sua criação, lido com
+//! [`Server::server_time`](crate::connection::Server::server_time).
+//!
+//! ## Exemplos
+//! ```rust
+//! use std::time::{Duration, Instant};
+//!
+//! use packet::timesync::TimeSync;
+//!
+//! // O relógio remoto está 5 s à frente do local, e o RTT é de 40 ms.
+//! let base = Instant::now();
+//! let remote = |local: Duration| local + Duration::from_secs(5);
+//! let mut sync = TimeSync::new();
+//! for i in 0..6 {
+//!     let sent = Duration::from_millis(i * 500);
+//!     let read = remote(sent + Duration::from_millis(20));
+//!     sync.record(base + sent, base + sent + Duration::from_millis(40), read);
+//! }
+//! // Um pong preso na fila de ida chega com o relógio adiantado: é descartado.
+//! let sent = Duration::from_millis(3_000);
+//! let read = remote(sent + Duration::from_millis(380));
+//! sync.record(base + sent, base + sent + Duration::from_millis(400), read);
+//!
+//! assert!(sync.is_synchronized());
+//! let estimate = sync.remote_time(base + Duration::from_secs(10)).unwrap();
+//! let error = estimate.abs_diff(Duration::from_secs(15));
+//! assert!(error < Duration::from_millis(1));
+//! ```
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::message::MessageType;
+use crate::Packet;
+
+/// Quantidade padrão de amostras guardadas.
+pub const DEFAULT_SAMPLE_WINDOW: usize = 16;
+
+/// Amostras a partir das quais a estimativa é considerada sincronizada.
+pub const MIN_SYNC_SAMPLES: usize = 4;
+
+/// Intervalo padrão entre as rajadas de ressincronização.
+pub const DEFAULT_RESYNC_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Pings de cada rajada de sincronização.
+pub const SYNC_BURST: usize = 4;
+
+/// Intervalo entre os pings de uma rajada.
+pub const SYNC_PING_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Espalhamento mínimo das amostras para estimar a deriva.
+pub const MIN_DRIFT_SPAN: Duration = Duration::from_secs(1);
+
+/// Maior deriva aceita entre os relógios, em partes por milhão.
+pub const MAX_DRIFT_PPM: f64 = 1_000.0;
+
+/// Tamanho do relógio gravado no payload de um pong.
+pub const PONG_TIME_SIZE: usize = 8;
+
+/// Medida de um ping respondido.
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    /// Meio do RTT, em segundos desde a origem local.
+    local: f64,
+    /// Relógio remoto menos o local, em segundos.
+    offset: f64,
+    /// RTT da medida.
+    rtt: Duration,
+}
+
+/// Diferença entre os relógios, em função do relógio local.
+#[derive(Debug, Clone, Copy)]
+struct Estimate {
+    /// Instante local de referência, em segundos desde a origem local.
+    reference: f64,
+    /// Diferença no instante de referência, em segundos.
+    offset: f64,
+    /// Variação da diferença por segundo local.
+    drift: f64,
+}
+
+/// Estimativa do relógio remoto a partir dos pings respondidos.
+#[derive(Debug, Clone)]
+pub struct TimeSync {
+    /// Instante local a partir do qual as amostras são medidas.
+    origin: Option<Instant>,
+    /// Amostras mais recentes, da mais antiga à mais nova.
+    samples: VecDeque<Sample>,
+    /// Limite de amostras guardadas.
+    window: usize,
+    /// Estimativa atual.
+    estimate: Option<Estimate>,
+    /// Intervalo entre as rajadas de ressincronização.
+    resync_interval: Duration,
+    /// Início da última rajada.
+    burst_started: Option<Instant>,
+    /// Pings ainda a enviar na rajada atual.
+    burst_remaining: usize,
+    /// Instante do último ping da rajada.
+    last_burst_ping: Option<Instant>,
+}
+
+impl Default for TimeSync {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TimeSync {
+    /// Cria uma estimativa sem amostras, com [`DEFAULT_SAMPLE_WINDOW`] e
+    /// [`DEFAULT_RESYNC_INTERVAL`].
+    pub fn new() -> Self {
+        Self {
+            origin: None,
+            samples: VecDeque::new(),
+            window: DEFAULT_SAMPLE_WINDOW,
+            estimate: None,
+            resync_interval: DEFAULT_RESYNC_INTERVAL,
+            burst_started: None,
+            burst_remaining: 0,
+            last_burst_ping: None,
+        }
+    }
+
+    /// Define quantas amostras são guardadas.
+    ///
+    /// ## Parâmetros
+    /// - `window`: Limite de amostras; no mínimo `1`.
+    ///
+    /// ## Retorno
+    /// - `Self`: A estimativa com a nova janela.
+    pub fn with_window(mut self, window: usize) -> Self {
+        self.window = window.max(1);
+        self
+    }
+
+    /// Define o intervalo entre as rajadas de ressincronização.
+    ///
+    /// ## Parâmetros
+    /// - `interval`: Novo intervalo, contado do início de cada rajada.
+    ///
+    /// ## Retorno
+    /// - `Self`: A estimativa com o novo intervalo.
+    pub fn with_resync_interval(mut self, interval: Duration) -> Self {
+        self.resync_interval = interval;
+        self
+    }
+
+    /// Indica se um ping de sincronização deve ser enviado agora.
+    ///
+    /// A primeira chamada e as seguintes a cada intervalo de ressincronização começam
+    /// uma rajada de [`SYNC_BURST`] pings, espaçados por [`SYNC_PING_INTERVAL`]. Cada
+    /// resposta `true` conta como um ping enviado.
+    ///
+    /// ## Parâmetros
+    /// - `now`: Instante atual.
+    ///
+    /// ## Retorno
+    /// - `bool`: `true` se o ping deve ser enviado.
+    pub fn poll(&mut self, now: Instant) -> bool {
+        if self
+            .burst_started
+            .is_none_or(|started| now.saturating_duration_since(started) >= self.resync_interval)
+        {
+            self.burst_started = Some(now);
+            self.burst_remaining = SYNC_BURST;
+            self.last_burst_ping = None;
+        }
+        let due = self
+            .last_burst_ping
+            .is_none_or(|last| now.saturating_duration_since(last) >= SYNC_PING_INTERVAL);
+        if self.burst_remaining == 0 || !due {
+            return false;
+        }
+        self.burst_remaining -= 1;
+        self.last_burst_ping = Some(now);
+        true
+    }
+
+    /// Registra um ping respondido e atualiza a estimativa.
+    ///
+    /// ## Parâmetros
+    /// - `sent`: Instante local do envio do ping.
+    /// - `received`: Instante local da chegada do pong.
+    /// - `remote_time`: Relógio remoto gravado no pong.
+    pub fn record(&mut self, sent: Instant, received: Instant, remote_time: Duration) {
+        let origin = *self.origin.get_or_insert(sent);
+        let rtt = received.saturating_duration_since(sent);
+        let local = sent.saturating_duration_since(origin).as_secs_f64() + rtt.as_secs_f64() / 2.0;
+        if self.samples.len() == self.window {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(Sample {
+            local,
+            offset: remote_time.as_secs_f64() - local,
+            rtt,
+        });
+        self.estimate = Some(self.fit());
+    }
+
+    /// Retorna a quantidade de amostras guardadas.
+    pub fn sample_count(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Indica se há pelo menos [`MIN_SYNC_SAMPLES`] amostras, ou a janela inteira se
+    /// ela é menor.
+    pub fn is_synchronized(&self) -> bool {
+        self.samples.len() >= MIN_SYNC_SAMPLES.min(self.window)
+    }
+
+    /// Retorna a deriva estimada do relógio remoto em relação ao local.
+    ///
+    /// ## Retorno
+    /// - `Option<f64>`: A deriva em partes por milhão, positiva se o relógio remoto
+    ///   anda mais depressa, ou `None` sem amostras.
+    pub fn drift_ppm(&self) -> Option<f64> {
+        Some(self.estimate?.drift * 1_000_000.0)
+    }
+
+    /// Estima o relógio remoto em um instante local.
+    ///
+    /// ## Parâmetros
+    /// - `now`: Instante local.
+    ///
+    /// ## Retorno
+    /// - `Option<Duration>`: O relógio remoto estimado, ou `None` sem amostras.
+    pub fn remote_time(&self, now: Instant) -> Option<Duration> {
+        let estimate = self.estimate?;
+        let origin = self.origin?;
+        let local = if now >= origin {
+            now.duration_since(origin).as_secs_f64()
+        } else {
+            -origin.duration_since(now).as_secs_f64()
+        };
+        let offset = estimate.offset + estimate.drift * (local - estimate.reference);
+        Some(Duration::from_secs_f64((local + offset).max(0.0)))
+    }
+
+    /// Estima a diferença entre os relógios pela metade de menor RTT das amostras.
+    fn fit(&self) -> Estimate {
+        let mut kept: Vec<Sample> = self.samples.iter().copied().collect();
+        kept.sort_by_key(|sample| sample.rtt);
+        kept.truncate(kept.len().div_ceil(2));
+
+        let count = kept.len() as f64;
+        let reference = kept.iter().map(|sample| sample.local).sum::<f64>() / count;
+        let (min, max) = kept
+            .iter()
+            .fold((f64::MAX, f64::MIN), |(min, max), sample| {
+                (min.min(sample.local), max.max(sample.local))
+            });
+        if kept.len() >= 2 && max - min >= MIN_DRIFT_SPAN.as_secs_f64() {
+            let mean = kept.iter().map(|sample| sample.offset).sum::<f64>() / count;
+            let (covariance, variance) = kept.iter().fold((0.0, 0.0), |(cov, var), sample| {
+                let dx = sample.local - reference;
+                (cov + dx * (sample.offset - mean), var + dx * dx)
+            });
+            let limit = MAX_DRIFT_PPM / 1_000_000.0;
+            return Estimate {
+                reference,
+                offset: mean,
+                drift: (covariance / variance).clamp(-limit, limit),
+            };
+        }
+
+        let mut offsets: Vec<f64> = kept.iter().map(|sample| sample.offset).collect();
+        offsets.sort_by(f64::total_cmp);
+        let middle = offsets.len() / 2;
+        let offset = if offsets.len().is_multiple_of(2) {
+            (offsets[middle - 1] + offsets[middle]) / 2.0
+        } else {
+            offsets[middle]
+        };
+        Estimate {
+            reference,
+            offset,
+            drift: 0.0,
+        }
+    }
+}
+
+impl Packet {
+    /// Cria o pong de um ping, com o relógio de quem responde.
+    ///
+    /// ## Parâmetros
+    /// - `sequence`: Sequência do ping respondido.
+    /// - `player_id`: Identificador único do jogador.
+    /// - `time`: Relógio de quem responde, gravado em microssegundos.
+    ///
+    /// ## Retorno
+    /// - `Self`: O pacote [`MessageType::Pong`].
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use std::time::Duration;
+    /// use packet::Packet;
+    ///
+    /// let pong = Packet::pong(7, 42, Duration::from_micros(1_500));
+    /// assert_eq!(pong.pong_time(), Some(Duration::from_micros(1_500)));
+    /// assert_eq!(Packet::new(1, 7, 42, Vec::new()).pong_time(), None);
+    /// ```
+    pub fn pong(sequence: u32, player_id: u64, time: Duration) -> Self {
+        let micros = u64::try_from(time.as_micros()).unwrap_or(u64::MAX);
+        Self::new(
+            MessageType::Pong.into(),
+            sequence,
+            player_id,
+            micros.to_le_bytes().to_vec(),
+        )
+    }
+
+    /// Retorna o relógio gravado em um pong.
+    ///
+    /// ## Retorno
+    /// - `Option<Duration>`: O relógio de quem respondeu, ou `None` se o pacote não é
+    ///   um pong ou não leva o relógio.
+    pub fn pong_time(&self) -> Option<Duration> {
+        if !matches!(self.message_type(), Ok(MessageType::Pong)) {
+            return None;
+        }
+        let bytes: [u8; PONG_TIME_SIZE] = self.payload.as_slice().try_into().ok()?;
+        Some(Duration::from_micros(u64::from_le_bytes(bytes)))
+    }
+}
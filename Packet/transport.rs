@@ -0,0 +1,402 @@
+//! # Módulo `transport`
+//!
+//! Transporte UDP que cuida da serialização e do buffer de recepção.
+//!
+//! [`UdpTransport`] é a forma mais direta de trocar pacotes: diferentemente de
+//! [`PacketSocket`](crate::socket::PacketSocket), mantém internamente um buffer de
+//! [`recommended_recv_buffer_size`](crate::recommended_recv_buffer_size) bytes e reporta
+//! todas as falhas como [`PacketError`], de modo que um datagrama malformado chega ao
+//! chamador como o erro correspondente, e não como um [`io::Error`] genérico ou um
+//! pacote descartado em silêncio.
+//!
+//! A trait [`Transport`] abstrai o envio e a recepção de pacotes endereçados, de modo
+//! que o código do jogo pode trocar o socket real por um transporte simulado, como o
+//! [`SimulatedTransport`](crate::simulator::SimulatedTransport), nos testes. O
+//! [`Server`](crate::connection::Server) e o [`Client`](crate::connection::Client) de
+//! [`connection`](crate::connection) aceitam qualquer transporte que a implemente,
+//! inclusive os da aplicação.
+//! Com o recurso `websocket`, `WebSocketTransport` também implementa a trait, para
+//! clientes no navegador.
+//!
+//! Para descoberta de servidores na rede local, [`UdpTransport::broadcast`] envia a
+//! todos os hosts da rede e [`UdpTransport::join_multicast`],
+//! [`UdpTransport::leave_multicast`] e [`UdpTransport::send_multicast`] usam grupos
+//! multicast; as opções de socket necessárias são configuradas internamente.
+//!
+//! ## Exemplos
+//! ```rust
+//! use packet::{Packet, PacketError};
+//! use packet::transport::UdpTransport;
+//!
+//! let mut server = UdpTransport::bind("127.0.0.1:0").unwrap();
+//! let client = UdpTransport::bind("127.0.0.1:0").unwrap();
+//! let server_addr = server.local_addr().unwrap();
+//!
+//! let packet = Packet::new(1, 1, 7, vec![1, 2, 3]);
+//! client.send_packet(server_addr, &packet).unwrap();
+//! let (from, received) = server.recv_packet().unwrap();
+//! assert_eq!(from, client.local_addr().unwrap());
+//! assert_eq!(received, packet);
+//!
+//! // Um datagrama que não é um pacote resulta no erro de interpretação.
+//! client.get_ref().send_to(b"lixo", server_addr).unwrap();
+//! assert!(matches!(
+//!     server.recv_packet(),
+//!     Err(PacketError::InvalidMagic { .. })
+//! ));
+//! ```
+
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs, UdpSocket};
+
+use crate::config::ParseConfig;
+use crate::socket::parse_datagram;
+use crate::view::PacketView;
+use crate::{recommended_recv_buffer_size, Packet, PacketError};
+
+#[cfg(feature = "websocket")]
+pub use crate::websocket::WebSocketTransport;
+
+/// Envio e recepção de pacotes endereçados.
+pub trait Transport {
+    /// Envia um pacote ao endereço informado.
+    ///
+    /// ## Retorno
+    /// - `Result<usize, PacketError>`: Quantidade de bytes enviados ou o erro do envio.
+    fn send_packet(&mut self, addr: SocketAddr, packet: &Packet) -> Result<usize, PacketError>;
+
+    /// Recebe o próximo pacote e seu endereço de origem.
+    ///
+    /// ## Retorno
+    /// - `Result<(SocketAddr, Packet), PacketError>`: A origem e o pacote, ou o erro da
+    ///   recepção; transportes não bloqueantes retornam [`PacketError::Io`] com
+    ///   [`io::ErrorKind::WouldBlock`] quando não há pacotes.
+    fn recv_packet(&mut self) -> Result<(SocketAddr, Packet), PacketError>;
+
+    /// Recebe o próximo datagrama sem bloquear, separando a falha da recepção da
+    /// falha de interpretação.
+    ///
+    /// É a operação usada pelas conexões de [`connection`](crate::connection), que
+    /// consultam o transporte a cada `poll`. A implementação padrão usa
+    /// [`Transport::recv_packet`], que não informa a origem de um datagrama inválido:
+    /// nela, a origem dos erros de interpretação é `0.0.0.0:0`.
+    ///
+    /// ## Retorno
+    /// - `io::Result<Option<(SocketAddr, Result<Packet, PacketError>)>>`: A origem e o
+    ///   pacote ou o erro de interpretação do datagrama; `None` se não há datagramas;
+    ///   ou a falha da recepção.
+    fn recv_from(&mut self) -> io::Result<Option<(SocketAddr, Result<Packet, PacketError>)>> {
+        match self.recv_packet() {
+            Ok((from, packet)) => Ok(Some((from, Ok(packet)))),
+            Err(PacketError::Io(error)) if error.kind() == io::ErrorKind::WouldBlock => Ok(None),
+            Err(error @ PacketError::Io(_)) => Err(error.into()),
+            Err(error) => Ok(Some(((Ipv4Addr::UNSPECIFIED, 0).into(), Err(error)))),
+        }
+    }
+
+    /// Retorna o endereço local do transporte.
+    ///
+    /// ## Retorno
+    /// - `io::Result<SocketAddr>`: O endereço; a implementação padrão retorna
+    ///   [`io::ErrorKind::Unsupported`], para transportes sem endereço próprio.
+    fn local_addr(&self) -> io::Result<SocketAddr> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    /// Define se a recepção bloqueia à espera de pacotes.
+    ///
+    /// ## Retorno
+    /// - `io::Result<()>`: `Ok` ou o erro do sistema; a implementação padrão não faz
+    ///   nada, para transportes que nunca bloqueiam.
+    fn set_nonblocking(&mut self, nonblocking: bool) -> io::Result<()> {
+        let _ = nonblocking;
+        Ok(())
+    }
+}
+
+/// Socket UDP com buffer de recepção próprio.
+#[derive(Debug)]
+pub struct UdpTransport {
+    /// Socket subjacente.
+    socket: UdpSocket,
+    /// Buffer reutilizado a cada recepção.
+    buffer: Vec<u8>,
+    /// Limites dos pacotes recebidos.
+    limits: ParseConfig,
+}
+
+impl UdpTransport {
+    /// Cria um transporte associado ao endereço informado.
+    ///
+    /// ## Parâmetros
+    /// - `addr`: Endereço local; use a porta `0` para uma porta qualquer.
+    ///
+    /// ## Retorno
+    /// - `io::Result<Self>`: O transporte ou o erro retornado pelo sistema.
+    pub fn bind(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        UdpSocket::bind(addr).map(Self::from_socket)
+    }
+
+    /// Envolve um socket já configurado.
+    ///
+    /// ## Parâmetros
+    /// - `socket`: Socket UDP associado a um endereço local.
+    ///
+    /// ## Retorno
+    /// - `Self`: O transporte.
+    pub fn from_socket(socket: UdpSocket) -> Self {
+        Self {
+            socket,
+            buffer: vec![0; recommended_recv_buffer_size()],
+            limits: ParseConfig::default(),
+        }
+    }
+
+    /// Define os limites dos pacotes recebidos.
+    ///
+    /// ## Parâmetros
+    /// - `limits`: Limites da leitura, aplicados por [`ParseConfig::check`] a cada
+    ///   datagrama.
+    ///
+    /// ## Retorno
+    /// - `Self`: O transporte com os novos limites.
+    pub fn with_parse_config(mut self, limits: ParseConfig) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Retorna os limites dos pacotes recebidos; [`ParseConfig::default`] se nada foi
+    /// configurado.
+    pub fn parse_config(&self) -> &ParseConfig {
+        &self.limits
+    }
+
+    /// Retorna o socket subjacente, para configurações como tempo limite de leitura.
+    pub fn get_ref(&self) -> &UdpSocket {
+        &self.socket
+    }
+
+    /// Retorna o endereço local do transporte.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.socket.local_addr()
+    }
+
+    /// Serializa e envia um pacote em um único datagrama.
+    ///
+    /// ## Parâmetros
+    /// - `addr`: Endereço de destino.
+    /// - `packet`: Pacote a ser enviado.
+    ///
+    /// ## Retorno
+    /// - `Result<usize, PacketError>`: Quantidade de bytes enviados ou
+    ///   [`PacketError::Io`] com o erro do sistema.
+    pub fn send_packet(
+        &self,
+        addr: impl ToSocketAddrs,
+        packet: &Packet,
+    ) -> Result<usize, PacketError> {
+        Ok(self.socket.send_to(&packet.to_bytes(), addr)?)
+    }
+
+    /// Envia um pacote a todos os hosts da rede local.
+    ///
+    /// A opção `SO_BROADCAST` é habilitada no socket antes do primeiro envio. Em um
+    /// socket IPv6, que não tem broadcast, o pacote vai para o grupo multicast de
+    /// todos os nós do enlace (`ff02::1`). Se o sistema não permite habilitar a opção,
+    /// o envio é tentado mesmo assim e o erro do envio, se houver, é o retornado.
+    ///
+    /// ## Parâmetros
+    /// - `packet`: Pacote a ser enviado.
+    /// - `port`: Porta de destino nos hosts da rede.
+    ///
+    /// ## Retorno
+    /// - `Result<usize, PacketError>`: Quantidade de bytes enviados ou
+    ///   [`PacketError::Io`] com o erro do sistema.
+    pub fn broadcast(&self, packet: &Packet, port: u16) -> Result<usize, PacketError> {
+        let group: IpAddr = if self.socket.local_addr()?.is_ipv6() {
+            Ipv6Addr::new(0xff02, 0, 0, 0, 0, 0, 0, 1).into()
+        } else {
+            if !self.socket.broadcast().unwrap_or(false) {
+                // Sem a opção o envio costuma falhar com permissão negada, que é um
+                // erro mais útil ao chamador que o da configuração.
+                let _ = self.socket.set_broadcast(true);
+            }
+            Ipv4Addr::BROADCAST.into()
+        };
+        self.send_packet(SocketAddr::new(group, port), packet)
+    }
+
+    /// Passa a receber os pacotes enviados a um grupo multicast.
+    ///
+    /// O grupo é associado à interface padrão escolhida pelo sistema. O transporte
+    /// deve estar associado à porta usada pelo grupo, em geral com o endereço não
+    /// especificado (`0.0.0.0` ou `::`).
+    ///
+    /// ## Parâmetros
+    /// - `group`: Endereço do grupo, da mesma família do socket.
+    ///
+    /// ## Retorno
+    /// - `Result<(), PacketError>`: `Ok` ao entrar no grupo; [`PacketError::Io`] com
+    ///   [`io::ErrorKind::InvalidInput`] se `group` não é multicast, ou com o erro do
+    ///   sistema se a plataforma não oferece multicast.
+    ///
+    /// ## Exemplos
+    /// ```rust,no_run
+    /// use packet::Packet;
+    /// use packet::transport::UdpTransport;
+    ///
+    /// let group = "239.255.0.1".parse().unwrap();
+    /// let mut listener = UdpTransport::bind("0.0.0.0:4100").unwrap();
+    /// listener.join_multicast(group).unwrap();
+    ///
+    /// let sender = UdpTransport::bind("0.0.0.0:0").unwrap();
+    /// let packet = Packet::new(1, 1, 7, vec![1, 2, 3]);
+    /// sender.send_multicast(&packet, (group, 4100).into()).unwrap();
+    ///
+    /// let (_, received) = listener.recv_packet().unwrap();
+    /// assert_eq!(received, packet);
+    /// listener.leave_multicast(group).unwrap();
+    /// ```
+    pub fn join_multicast(&self, group: IpAddr) -> Result<(), PacketError> {
+        match multicast_group(group)? {
+            IpAddr::V4(group) => self
+                .socket
+                .join_multicast_v4(&group, &Ipv4Addr::UNSPECIFIED)?,
+            IpAddr::V6(group) => self.socket.join_multicast_v6(&group, 0)?,
+        }
+        Ok(())
+    }
+
+    /// Deixa um grupo em que o transporte entrou com [`UdpTransport::join_multicast`].
+    ///
+    /// ## Parâmetros
+    /// - `group`: Endereço do grupo.
+    ///
+    /// ## Retorno
+    /// - `Result<(), PacketError>`: `Ok` ao sair do grupo, ou os erros de
+    ///   [`UdpTransport::join_multicast`].
+    pub fn leave_multicast(&self, group: IpAddr) -> Result<(), PacketError> {
+        match multicast_group(group)? {
+            IpAddr::V4(group) => self
+                .socket
+                .leave_multicast_v4(&group, &Ipv4Addr::UNSPECIFIED)?,
+            IpAddr::V6(group) => self.socket.leave_multicast_v6(&group, 0)?,
+        }
+        Ok(())
+    }
+
+    /// Envia um pacote a um grupo multicast.
+    ///
+    /// Não é preciso estar no grupo para enviar. Os pacotes enviados também são
+    /// entregues aos transportes do próprio host que estão no grupo, conforme o
+    /// padrão do sistema.
+    ///
+    /// ## Parâmetros
+    /// - `packet`: Pacote a ser enviado.
+    /// - `group`: Endereço do grupo e porta de destino.
+    ///
+    /// ## Retorno
+    /// - `Result<usize, PacketError>`: Quantidade de bytes enviados;
+    ///   [`PacketError::Io`] com [`io::ErrorKind::InvalidInput`] se o endereço não é
+    ///   multicast, ou com o erro do sistema.
+    pub fn send_multicast(&self, packet: &Packet, group: SocketAddr) -> Result<usize, PacketError> {
+        multicast_group(group.ip())?;
+        self.send_packet(group, packet)
+    }
+
+    /// Recebe o próximo datagrama e o interpreta como um pacote, verificando seu checksum.
+    ///
+    /// ## Retorno
+    /// - `Result<(SocketAddr, Packet), PacketError>`: O endereço de origem e o pacote;
+    ///   [`PacketError::Io`] se a recepção falhou, ou o erro de interpretação, de
+    ///   checksum ou de truncamento do datagrama recebido. Após um erro de
+    ///   interpretação o transporte continua utilizável.
+    pub fn recv_packet(&mut self) -> Result<(SocketAddr, Packet), PacketError> {
+        let (from, packet) = self.recv_view()?;
+        Ok((from, packet.to_packet()))
+    }
+
+    /// Recebe o próximo datagrama como uma visão emprestada do buffer interno, sem
+    /// nenhuma alocação.
+    ///
+    /// As verificações e os erros são os de [`UdpTransport::recv_packet`]. A visão
+    /// empresta o transporte até ser descartada; use [`PacketView::to_packet`] para
+    /// guardar o pacote além disso.
+    ///
+    /// ## Retorno
+    /// - `Result<(SocketAddr, PacketView<'_>), PacketError>`: O endereço de origem e a
+    ///   visão do pacote, ou o erro encontrado.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::Packet;
+    /// use packet::transport::UdpTransport;
+    ///
+    /// let mut server = UdpTransport::bind("127.0.0.1:0").unwrap();
+    /// let client = UdpTransport::bind("127.0.0.1:0").unwrap();
+    ///
+    /// let packet = Packet::new(1, 1, 7, vec![1, 2, 3]);
+    /// client.send_packet(server.local_addr().unwrap(), &packet).unwrap();
+    ///
+    /// let (_, view) = server.recv_view().unwrap();
+    /// assert_eq!(view.header.sequence, 1);
+    /// assert_eq!(view.payload, [1, 2, 3]);
+    /// assert_eq!(view.as_bytes(), packet.to_bytes());
+    /// ```
+    pub fn recv_view(&mut self) -> Result<(SocketAddr, PacketView<'_>), PacketError> {
+        let (from, packet) = self.recv_datagram()?;
+        Ok((from, packet?))
+    }
+
+    /// Recebe o próximo datagrama, separando a falha da recepção da falha de
+    /// interpretação, para que o chamador saiba a origem de um datagrama inválido.
+    pub(crate) fn recv_datagram(
+        &mut self,
+    ) -> io::Result<(SocketAddr, Result<PacketView<'_>, PacketError>)> {
+        let (len, from) = self.socket.recv_from(&mut self.buffer)?;
+        let limits = &self.limits;
+        let packet = parse_datagram(&self.buffer[..len], self.buffer.len()).and_then(|packet| {
+            limits.check(&packet.header, packet.payload)?;
+            Ok(packet)
+        });
+        Ok((from, packet))
+    }
+}
+
+impl Transport for UdpTransport {
+    fn send_packet(&mut self, addr: SocketAddr, packet: &Packet) -> Result<usize, PacketError> {
+        UdpTransport::send_packet(self, addr, packet)
+    }
+
+    fn recv_packet(&mut self) -> Result<(SocketAddr, Packet), PacketError> {
+        UdpTransport::recv_packet(self)
+    }
+
+    fn recv_from(&mut self) -> io::Result<Option<(SocketAddr, Result<Packet, PacketError>)>> {
+        match self.recv_datagram() {
+            Ok((from, packet)) => Ok(Some((from, packet.map(|view| view.to_packet())))),
+            Err(error) if error.kind() == io::ErrorKind::WouldBlock => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+
+    fn local_addr(&self) -> io::Result<SocketAddr> {
+        UdpTransport::local_addr(self)
+    }
+
+    fn set_nonblocking(&mut self, nonblocking: bool) -> io::Result<()> {
+        self.socket.set_nonblocking(nonblocking)
+    }
+}
+
+/// Valida que o endereço é de um grupo multicast.
+fn multicast_group(group: IpAddr) -> io::Result<IpAddr> {
+    if group.is_multicast() {
+        Ok(group)
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "o endereço não é de um grupo multicast",
+        ))
+    }
+}
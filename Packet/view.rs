@@ -0,0 +1,287 @@
+//! # Módulo `view`
+//!
+//! Leitura de pacotes sem cópia: [`PacketRef`] (também exportado como
+//! [`PacketView`]) interpreta o cabeçalho e empresta o payload diretamente do buffer
+//! de entrada.
+//!
+//! É o caminho de recepção da biblioteca: [`Packet::from_bytes`] e os sockets validam o
+//! datagrama em uma visão e só copiam o payload, com [`PacketRef::to_packet`], depois
+//! que ele passou por todas as verificações. [`UdpTransport::recv_view`] entrega a
+//! visão diretamente, sem nenhuma alocação por datagrama.
+//!
+//! [`UdpTransport::recv_view`]: crate::transport::UdpTransport::recv_view
+//!
+//! [`PacketIter`] percorre um buffer com vários pacotes serializados em sequência,
+//! como um arquivo de captura. Como nenhum pacote é copiado, o buffer pode vir de
+//! um arquivo mapeado em memória (por exemplo, com o crate `memmap2`) e ser
+//! percorrido com memória limitada, independentemente do tamanho do arquivo.
+//!
+//! ## Exemplos
+//! ```rust
+//! use packet::Packet;
+//! use packet::view::PacketIter;
+//!
+//! let mut capture = Vec::new();
+//! capture.extend(Packet::new(1, 1, 7, vec![1, 2]).to_bytes());
+//! capture.extend(Packet::new(1, 2, 7, vec![3]).to_bytes());
+//! // Último pacote cortado no meio, como em uma captura interrompida.
+//! let truncated = Packet::new(1, 3, 7, vec![4, 5, 6]).to_bytes();
+//! capture.extend(&truncated[..10]);
+//!
+//! let mut packets = PacketIter::new(&capture);
+//! assert_eq!(packets.next().unwrap().unwrap().payload, [1, 2]);
+//! assert_eq!(packets.next().unwrap().unwrap().payload, [3]);
+//! assert!(packets.next().is_none());
+//! assert_eq!(packets.remainder().len(), 10);
+//! ```
+
+use alloc::borrow::Cow;
+
+use crate::{instrument, Packet, PacketError, PacketHeader};
+
+/// Nome alternativo de [`PacketRef`], a visão emprestada de um pacote.
+pub type PacketView<'a> = PacketRef<'a>;
+
+/// Pacote cujo payload é emprestado do buffer de onde foi lido.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PacketRef<'a> {
+    /// Cabeçalho do pacote.
+    pub header: PacketHeader,
+    /// Payload emprestado do buffer de entrada.
+    pub payload: &'a [u8],
+    /// Pacote serializado completo, tal como recebido.
+    bytes: &'a [u8],
+}
+
+impl<'a> PacketRef<'a> {
+    /// Interpreta um buffer que contém exatamente um pacote.
+    ///
+    /// ## Parâmetros
+    /// - `bytes`: Fatia de bytes representando o pacote completo.
+    ///
+    /// ## Retorno
+    /// - `Result<Self, PacketError>`: O pacote emprestado ou o erro encontrado.
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, PacketError> {
+        let (packet, rest) = Self::parse_prefix(bytes)?;
+        if !rest.is_empty() {
+            return Err(PacketError::PayloadSizeMismatch {
+                declared: packet.payload.len(),
+                actual: bytes.len() - PacketHeader::SIZE,
+            });
+        }
+        Ok(packet)
+    }
+
+    /// Interpreta um buffer com exatamente um pacote, com os erros de
+    /// [`Packet::from_bytes`].
+    pub(crate) fn parse_exact(bytes: &'a [u8]) -> Result<Self, PacketError> {
+        let header = PacketHeader::from_bytes(bytes)?;
+        let expected_len = header.packet_len()?;
+        if bytes.len() != expected_len {
+            return Err(PacketError::PayloadSizeMismatch {
+                declared: header.payload_size as usize,
+                actual: bytes.len() - PacketHeader::SIZE,
+            });
+        }
+        Ok(Self {
+            header,
+            payload: &bytes[PacketHeader::SIZE..],
+            bytes,
+        })
+    }
+
+    /// Interpreta o pacote no início do buffer e retorna os bytes restantes.
+    ///
+    /// ## Parâmetros
+    /// - `bytes`: Buffer que começa com um pacote serializado.
+    ///
+    /// ## Retorno
+    /// - `Result<(Self, &'a [u8]), PacketError>`: O pacote e os bytes que o seguem, ou
+    ///   [`PacketError::TooShort`] se o buffer termina antes do fim do pacote.
+    pub fn parse_prefix(bytes: &'a [u8]) -> Result<(Self, &'a [u8]), PacketError> {
+        let header = PacketHeader::from_bytes(bytes)?;
+        let len = header.packet_len()?;
+        if bytes.len() < len {
+            return Err(PacketError::TooShort {
+                expected: len,
+                actual: bytes.len(),
+            });
+        }
+
+        let (bytes, rest) = bytes.split_at(len);
+        let payload = &bytes[PacketHeader::SIZE..];
+        Ok((
+            Self {
+                header,
+                payload,
+                bytes,
+            },
+            rest,
+        ))
+    }
+
+    /// Verifica se o checksum do cabeçalho corresponde ao payload.
+    pub fn verify_checksum(&self) -> bool {
+        self.check_checksum().is_ok()
+    }
+
+    /// Verifica o checksum como [`Packet::check_checksum`], sem copiar o payload.
+    ///
+    /// ## Retorno
+    /// - `Result<(), PacketError>`: [`PacketError::ChecksumMismatch`] com o checksum
+    ///   declarado e o calculado, se eles diferem.
+    pub fn check_checksum(&self) -> Result<(), PacketError> {
+        let actual = self.header.expected_checksum(self.payload);
+        if self.header.checksum != actual {
+            instrument::checksum_mismatch(&self.header, actual);
+            return Err(PacketError::ChecksumMismatch {
+                expected: self.header.checksum,
+                actual,
+            });
+        }
+        Ok(())
+    }
+
+    /// Retorna os bytes serializados do pacote, emprestados do buffer original.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Retorna os bytes serializados do pacote sem nenhuma cópia.
+    ///
+    /// Equivalente a [`Packet::as_bytes_cow`], mas sempre emprestado: um pacote recebido
+    /// e encaminhado sem alterações não precisa ser serializado novamente.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use std::borrow::Cow;
+    /// use packet::Packet;
+    /// use packet::view::PacketRef;
+    ///
+    /// let received = Packet::new(1, 1, 7, vec![1, 2, 3]).to_bytes();
+    /// let packet = PacketRef::from_bytes(&received).unwrap();
+    ///
+    /// assert!(matches!(packet.as_bytes_cow(), Cow::Borrowed(bytes) if bytes == received));
+    /// ```
+    pub fn as_bytes_cow(&self) -> Cow<'a, [u8]> {
+        Cow::Borrowed(self.bytes)
+    }
+
+    /// Copia o pacote para um [`Packet`] independente do buffer.
+    ///
+    /// É a saída para quando o pacote precisa sobreviver ao buffer de recepção, como ao
+    /// ser enfileirado ou retido para entrega ordenada.
+    ///
+    /// ## Exemplos
+    /// ```rust
+    /// use packet::Packet;
+    /// use packet::view::PacketView;
+    ///
+    /// let packet = Packet::new(1, 1, 7, vec![1, 2, 3]);
+    /// let mut buffer = packet.to_bytes();
+    /// let owned = PacketView::from_bytes(&buffer).unwrap().to_packet();
+    ///
+    /// // O buffer é reutilizado; o pacote copiado não é afetado.
+    /// buffer.fill(0);
+    /// assert_eq!(owned, packet);
+    /// ```
+    pub fn to_packet(&self) -> Packet {
+        Packet {
+            header: self.header.clone(),
+            payload: self.payload.to_vec(),
+        }
+    }
+}
+
+impl From<PacketRef<'_>> for Packet {
+    fn from(packet: PacketRef<'_>) -> Self {
+        packet.to_packet()
+    }
+}
+
+/// Valida o cabeçalho de um pacote recebido e devolve seus bytes para reenvio.
+///
+/// Caminho mínimo para relays que não inspecionam nem alteram o pacote: apenas o
+/// tamanho, os bytes mágicos, o `header_checksum` e a consistência de `payload_size`
+/// são verificados. O
+/// payload não é lido, o checksum não é verificado nem recalculado e o `ttl` não é
+/// decrementado; para isso, use [`Packet::decrement_ttl`].
+///
+/// ## Parâmetros
+/// - `bytes`: Buffer que contém exatamente um pacote.
+///
+/// ## Retorno
+/// - `Result<&[u8], PacketError>`: O próprio `bytes`, pronto para reenvio, ou o erro
+///   encontrado no cabeçalho.
+///
+/// ## Exemplos
+/// ```rust
+/// use packet::{Packet, PacketError};
+/// use packet::view::relay_forward;
+///
+/// let received = Packet::new(1, 1, 7, vec![1, 2, 3]).to_bytes();
+/// assert_eq!(relay_forward(&received), Ok(&received[..]));
+///
+/// let cut = &received[..received.len() - 1];
+/// assert!(matches!(relay_forward(cut), Err(PacketError::TooShort { .. })));
+/// ```
+pub fn relay_forward(bytes: &[u8]) -> Result<&[u8], PacketError> {
+    PacketRef::from_bytes(bytes).map(|packet| packet.as_bytes())
+}
+
+/// Iterador sobre pacotes serializados em sequência em um único buffer.
+///
+/// A iteração termina quando o buffer acaba ou quando os bytes restantes não
+/// formam um pacote completo; esses bytes ficam disponíveis em
+/// [`PacketIter::remainder`]. Um cabeçalho inválido produz um erro e encerra a
+/// iteração, já que não é possível saber onde o próximo pacote começa.
+#[derive(Debug, Clone)]
+pub struct PacketIter<'a> {
+    /// Bytes ainda não consumidos.
+    rest: &'a [u8],
+    /// Indica que um erro já foi produzido e a iteração terminou.
+    failed: bool,
+}
+
+impl<'a> PacketIter<'a> {
+    /// Cria um iterador sobre o buffer.
+    ///
+    /// ## Parâmetros
+    /// - `bytes`: Buffer com pacotes serializados concatenados.
+    ///
+    /// ## Retorno
+    /// - `Self`: O iterador posicionado no início do buffer.
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self {
+            rest: bytes,
+            failed: false,
+        }
+    }
+
+    /// Retorna os bytes ainda não consumidos, como um pacote final truncado.
+    pub fn remainder(&self) -> &'a [u8] {
+        self.rest
+    }
+}
+
+impl<'a> Iterator for PacketIter<'a> {
+    type Item = Result<PacketRef<'a>, PacketError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.rest.is_empty() {
+            return None;
+        }
+
+        match PacketRef::parse_prefix(self.rest) {
+            Ok((packet, rest)) => {
+                self.rest = rest;
+                Some(Ok(packet))
+            }
+            Err(PacketError::TooShort { .. }) => None,
+            Err(error) => {
+                self.failed = true;
+                Some(Err(error))
+            }
+        }
+    }
+}
@@ -0,0 +1,404 @@
+//! # Módulo `websocket`
+//!
+//! Transporte de pacotes sobre WebSocket, para clientes no navegador, disponível com o
+//! recurso `websocket`.
+//!
+//! [`WebSocketTransport`] envia cada [`Packet`] como uma mensagem binária e
+//! implementa [`Transport`], de modo que o mesmo código do jogo troca pacotes com
+//! clientes UDP e WebSocket. Fora do navegador, ele usa `tungstenite` sobre TCP: um
+//! transporte criado com [`WebSocketTransport::bind`] aceita conexões e identifica
+//! cada cliente pelo endereço da conexão TCP, e um criado com
+//! `WebSocketTransport::connect` tem o servidor como único par. Em `wasm32`, usa o
+//! `WebSocket` do navegador através de `web-sys`, e só conecta: o navegador não expõe
+//! o endereço do servidor, então os pacotes recebidos vêm de `0.0.0.0:0` e o endereço
+//! de envio é ignorado.
+//!
+//! As operações não bloqueiam: `recv_packet` retorna [`PacketError::Io`] com
+//! [`io::ErrorKind::WouldBlock`] quando não há mensagens, e as conexões encerradas
+//! pelo outro lado são descartadas.
+//!
+//! ## Exemplos
+//! Um servidor que atende clientes UDP e WebSocket no mesmo laço:
+//! ```rust,no_run
+//! use packet::PacketError;
+//! use packet::transport::{Transport, UdpTransport, WebSocketTransport};
+//!
+//! let udp = UdpTransport::bind("0.0.0.0:4000").unwrap();
+//! udp.get_ref().set_nonblocking(true).unwrap();
+//! let web = WebSocketTransport::bind("0.0.0.0:4001").unwrap();
+//! let mut transports: Vec<Box<dyn Transport>> = vec![Box::new(udp), Box::new(web)];
+//!
+//! loop {
+//!     for transport in &mut transports {
+//!         match transport.recv_packet() {
+//!             Ok((from, packet)) => {
+//!                 transport.send_packet(from, &packet).unwrap();
+//!             }
+//!             Err(PacketError::Io(error)) if error.kind() == std::io::ErrorKind::WouldBlock => {}
+//!             Err(error) => eprintln!("pacote descartado: {error}"),
+//!         }
+//!     }
+//! }
+//! ```
+
+use std::io;
+use std::net::SocketAddr;
+
+use crate::transport::Transport;
+use crate::{Packet, PacketError};
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use native::WebSocketTransport;
+#[cfg(target_arch = "wasm32")]
+pub use web::WebSocketTransport;
+
+/// Interpreta uma mensagem binária recebida.
+fn decode_message(from: SocketAddr, data: &[u8]) -> Result<(SocketAddr, Packet), PacketError> {
+    Packet::from_bytes(data).map(|packet| (from, packet))
+}
+
+/// Erro retornado quando não há mensagens pendentes.
+fn would_block() -> PacketError {
+    io::Error::from(io::ErrorKind::WouldBlock).into()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod native {
+    use std::io;
+    use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+    use std::time::Duration;
+
+    use tungstenite::{Message, WebSocket};
+
+    use super::{decode_message, would_block};
+    use crate::{Packet, PacketError};
+
+    /// Tempo máximo do aperto de mão WebSocket de uma conexão aceita.
+    const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(2);
+
+    /// Transporte de pacotes sobre conexões WebSocket.
+    #[derive(Debug)]
+    pub struct WebSocketTransport {
+        /// Socket de escuta, nos transportes criados com [`WebSocketTransport::bind`].
+        listener: Option<TcpListener>,
+        /// Conexões estabelecidas e o endereço de cada par.
+        peers: Vec<(SocketAddr, WebSocket<TcpStream>)>,
+        /// Conexão a consultar primeiro na próxima recepção, em rodízio.
+        next: usize,
+    }
+
+    impl WebSocketTransport {
+        /// Cria um transporte que aceita conexões WebSocket no endereço informado.
+        ///
+        /// ## Parâmetros
+        /// - `addr`: Endereço local; use a porta `0` para uma porta qualquer.
+        ///
+        /// ## Retorno
+        /// - `io::Result<Self>`: O transporte, sem conexões, ou o erro do sistema.
+        pub fn bind(addr: impl ToSocketAddrs) -> io::Result<Self> {
+            let listener = TcpListener::bind(addr)?;
+            listener.set_nonblocking(true)?;
+            Ok(Self {
+                listener: Some(listener),
+                peers: Vec::new(),
+                next: 0,
+            })
+        }
+
+        /// Conecta a um servidor WebSocket, bloqueando até o fim do aperto de mão.
+        ///
+        /// ## Parâmetros
+        /// - `addr`: Endereço do servidor.
+        ///
+        /// ## Retorno
+        /// - `io::Result<Self>`: O transporte com o servidor como único par, ou o erro
+        ///   da conexão ou do aperto de mão.
+        pub fn connect(addr: SocketAddr) -> io::Result<Self> {
+            let stream = TcpStream::connect(addr)?;
+            let (socket, _) = tungstenite::client::client(&format!("ws://{addr}/"), stream)
+                .map_err(|error| handshake_error(error.to_string()))?;
+            socket.get_ref().set_nonblocking(true)?;
+            Ok(Self {
+                listener: None,
+                peers: vec![(addr, socket)],
+                next: 0,
+            })
+        }
+
+        /// Retorna o endereço local de escuta, ou o da conexão com o servidor.
+        pub fn local_addr(&self) -> io::Result<SocketAddr> {
+            match (&self.listener, self.peers.first()) {
+                (Some(listener), _) => listener.local_addr(),
+                (None, Some((_, socket))) => socket.get_ref().local_addr(),
+                (None, None) => Err(io::ErrorKind::NotConnected.into()),
+            }
+        }
+
+        /// Retorna os endereços dos pares conectados.
+        pub fn peers(&self) -> impl Iterator<Item = SocketAddr> + '_ {
+            self.peers.iter().map(|(addr, _)| *addr)
+        }
+
+        /// Encerra a conexão com um par.
+        ///
+        /// ## Retorno
+        /// - `bool`: `true` se o par estava conectado.
+        pub fn disconnect(&mut self, addr: SocketAddr) -> bool {
+            let Some(index) = self.peers.iter().position(|(peer, _)| *peer == addr) else {
+                return false;
+            };
+            let (_, mut socket) = self.peers.remove(index);
+            // O aviso de encerramento é uma cortesia; a conexão cai de qualquer forma.
+            let _ = socket.close(None).and_then(|()| socket.flush());
+            true
+        }
+
+        /// Aceita as conexões pendentes, concluindo o aperto de mão de cada uma.
+        ///
+        /// O aperto de mão bloqueia por até [`HANDSHAKE_TIMEOUT`] por conexão.
+        fn accept_pending(&mut self) -> io::Result<()> {
+            let Some(listener) = &self.listener else {
+                return Ok(());
+            };
+            loop {
+                let (stream, addr) = match listener.accept() {
+                    Ok(accepted) => accepted,
+                    Err(error) if error.kind() == io::ErrorKind::WouldBlock => return Ok(()),
+                    Err(error) => return Err(error),
+                };
+                stream.set_nonblocking(false)?;
+                stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT))?;
+                // Uma conexão que não conclui o aperto de mão é simplesmente descartada.
+                let Ok(socket) = tungstenite::accept(stream) else {
+                    continue;
+                };
+                socket.get_ref().set_read_timeout(None)?;
+                socket.get_ref().set_nonblocking(true)?;
+                self.peers.push((addr, socket));
+            }
+        }
+
+        /// Envia um pacote como uma mensagem binária a um par conectado.
+        ///
+        /// ## Parâmetros
+        /// - `addr`: Endereço do par.
+        /// - `packet`: Pacote a ser enviado.
+        ///
+        /// ## Retorno
+        /// - `Result<usize, PacketError>`: Quantidade de bytes do pacote; um envio que
+        ///   não cabe no buffer do socket fica na fila e é concluído nas próximas
+        ///   operações. [`PacketError::Io`] com [`io::ErrorKind::NotConnected`] se o par
+        ///   não está conectado, ou com o erro da conexão, que é então descartada.
+        pub fn send_packet(
+            &mut self,
+            addr: SocketAddr,
+            packet: &Packet,
+        ) -> Result<usize, PacketError> {
+            let Some(index) = self.peers.iter().position(|(peer, _)| *peer == addr) else {
+                return Err(io::Error::from(io::ErrorKind::NotConnected).into());
+            };
+            let bytes = packet.to_bytes();
+            let len = bytes.len();
+            match self.peers[index].1.send(Message::binary(bytes)) {
+                Ok(()) => Ok(len),
+                Err(tungstenite::Error::Io(error)) if error.kind() == io::ErrorKind::WouldBlock => {
+                    Ok(len)
+                }
+                Err(error) => {
+                    self.peers.remove(index);
+                    Err(socket_error(error).into())
+                }
+            }
+        }
+
+        /// Recebe a próxima mensagem de qualquer par, aceitando antes as conexões
+        /// pendentes.
+        ///
+        /// Os pares são consultados em rodízio, para que um par com muito tráfego não
+        /// impeça a recepção dos demais.
+        ///
+        /// ## Retorno
+        /// - `Result<(SocketAddr, Packet), PacketError>`: O par e o pacote;
+        ///   [`PacketError::Io`] com [`io::ErrorKind::WouldBlock`] se não há mensagens,
+        ///   ou o erro de interpretação de uma mensagem que não é um pacote válido.
+        pub fn recv_packet(&mut self) -> Result<(SocketAddr, Packet), PacketError> {
+            self.accept_pending()?;
+            let mut checked = 0;
+            while checked < self.peers.len() {
+                let index = (self.next + checked) % self.peers.len();
+                let (addr, socket) = &mut self.peers[index];
+                match socket.read() {
+                    Ok(Message::Binary(data)) => {
+                        self.next = index + 1;
+                        return decode_message(*addr, &data);
+                    }
+                    // Pings são respondidos pelo próprio `tungstenite` na leitura.
+                    Ok(_) => {}
+                    Err(tungstenite::Error::Io(error))
+                        if error.kind() == io::ErrorKind::WouldBlock =>
+                    {
+                        checked += 1;
+                    }
+                    Err(_) => {
+                        self.peers.remove(index);
+                    }
+                }
+            }
+            Err(would_block())
+        }
+    }
+
+    /// Converte um erro do `tungstenite` no erro de E/S correspondente.
+    fn socket_error(error: tungstenite::Error) -> io::Error {
+        match error {
+            tungstenite::Error::Io(error) => error,
+            error => io::Error::new(io::ErrorKind::ConnectionAborted, error),
+        }
+    }
+
+    /// Erro de um aperto de mão WebSocket recusado.
+    fn handshake_error(reason: String) -> io::Error {
+        io::Error::new(io::ErrorKind::ConnectionRefused, reason)
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod web {
+    use std::cell::RefCell;
+    use std::collections::VecDeque;
+    use std::io;
+    use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+    use std::rc::Rc;
+
+    use js_sys::{ArrayBuffer, Uint8Array};
+    use wasm_bindgen::closure::Closure;
+    use wasm_bindgen::{JsCast, JsValue};
+    use web_sys::{BinaryType, MessageEvent};
+
+    use super::{decode_message, would_block};
+    use crate::{Packet, PacketError};
+
+    /// Origem atribuída aos pacotes recebidos, já que o navegador não expõe o endereço
+    /// do servidor.
+    const BROWSER_PEER: SocketAddr = SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0);
+
+    /// Transporte de pacotes sobre o `WebSocket` do navegador.
+    pub struct WebSocketTransport {
+        /// Conexão do navegador.
+        socket: web_sys::WebSocket,
+        /// Mensagens binárias recebidas e ainda não lidas.
+        inbox: Rc<RefCell<VecDeque<Vec<u8>>>>,
+        /// Função registrada em `onmessage`, mantida viva enquanto o transporte existe.
+        _on_message: Closure<dyn FnMut(MessageEvent)>,
+    }
+
+    impl std::fmt::Debug for WebSocketTransport {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("WebSocketTransport")
+                .field("url", &self.socket.url())
+                .field("pending", &self.inbox.borrow().len())
+                .finish()
+        }
+    }
+
+    impl WebSocketTransport {
+        /// Abre uma conexão WebSocket com o servidor.
+        ///
+        /// A conexão é estabelecida em segundo plano; até lá,
+        /// [`WebSocketTransport::send_packet`] falha com
+        /// [`io::ErrorKind::NotConnected`].
+        ///
+        /// ## Parâmetros
+        /// - `url`: Endereço do servidor, como `wss://jogo.exemplo:4001`.
+        ///
+        /// ## Retorno
+        /// - `Result<Self, PacketError>`: O transporte, ou [`PacketError::Io`] se o
+        ///   navegador recusou o endereço.
+        pub fn connect(url: &str) -> Result<Self, PacketError> {
+            let socket = web_sys::WebSocket::new(url).map_err(js_error)?;
+            socket.set_binary_type(BinaryType::Arraybuffer);
+
+            let inbox = Rc::new(RefCell::new(VecDeque::new()));
+            let queue = Rc::clone(&inbox);
+            let on_message = Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+                if let Ok(buffer) = event.data().dyn_into::<ArrayBuffer>() {
+                    queue
+                        .borrow_mut()
+                        .push_back(Uint8Array::new(&buffer).to_vec());
+                }
+            });
+            socket.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+            Ok(Self {
+                socket,
+                inbox,
+                _on_message: on_message,
+            })
+        }
+
+        /// Indica se a conexão já foi estabelecida e continua aberta.
+        pub fn is_open(&self) -> bool {
+            self.socket.ready_state() == web_sys::WebSocket::OPEN
+        }
+
+        /// Envia um pacote como uma mensagem binária ao servidor.
+        ///
+        /// ## Retorno
+        /// - `Result<usize, PacketError>`: Quantidade de bytes do pacote, ou
+        ///   [`PacketError::Io`] com [`io::ErrorKind::NotConnected`] se a conexão não
+        ///   está aberta.
+        pub fn send_packet(
+            &mut self,
+            _addr: SocketAddr,
+            packet: &Packet,
+        ) -> Result<usize, PacketError> {
+            if !self.is_open() {
+                return Err(io::Error::from(io::ErrorKind::NotConnected).into());
+            }
+            let bytes = packet.to_bytes();
+            self.socket.send_with_u8_array(&bytes).map_err(js_error)?;
+            Ok(bytes.len())
+        }
+
+        /// Retorna a próxima mensagem recebida do servidor.
+        ///
+        /// ## Retorno
+        /// - `Result<(SocketAddr, Packet), PacketError>`: `0.0.0.0:0` e o pacote;
+        ///   [`PacketError::Io`] com [`io::ErrorKind::WouldBlock`] se não há mensagens,
+        ///   ou o erro de interpretação de uma mensagem que não é um pacote válido.
+        pub fn recv_packet(&mut self) -> Result<(SocketAddr, Packet), PacketError> {
+            let data = self.inbox.borrow_mut().pop_front();
+            match data {
+                Some(data) => decode_message(BROWSER_PEER, &data),
+                None => Err(would_block()),
+            }
+        }
+    }
+
+    impl Drop for WebSocketTransport {
+        fn drop(&mut self) {
+            self.socket.set_onmessage(None);
+            let _ = self.socket.close();
+        }
+    }
+
+    /// Converte uma exceção do navegador em um erro de E/S.
+    fn js_error(error: JsValue) -> PacketError {
+        let reason = error.as_string().unwrap_or_else(|| format!("{error:?}"));
+        io::Error::other(reason).into()
+    }
+}
+
+impl Transport for WebSocketTransport {
+    fn send_packet(&mut self, addr: SocketAddr, packet: &Packet) -> Result<usize, PacketError> {
+        WebSocketTransport::send_packet(self, addr, packet)
+    }
+
+    fn recv_packet(&mut self) -> Result<(SocketAddr, Packet), PacketError> {
+        WebSocketTransport::recv_packet(self)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn local_addr(&self) -> io::Result<SocketAddr> {
+        WebSocketTransport::local_addr(self)
+    }
+}
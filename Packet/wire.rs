@@ -0,0 +1,589 @@
+//! # Módulo `wire`
+//!
+//! Descreve o formato do cabeçalho na rede como dados, para que implementações em
+//! outras linguagens possam gerar seus decodificadores a partir da mesma fonte.
+//!
+//! ## Exemplos
+//! A descrição é verificada contra a serialização real:
+//! ```rust
+//! use packet::{Packet, PacketHeader};
+//! use packet::payload::ContentType;
+//! use packet::wire::wire_format_description;
+//!
+//! let packet = Packet::reliable(0xAB, 0x0102_0304, 0x1122_3344_5566_7788, vec![9; 3])
+//!     .with_priority(0xCD)
+//!     .with_channel_id(0x3C)
+//!     .with_content_type(ContentType::Custom(0x5A))
+//!     .with_ttl(0x2D)
+//!     .with_group_id(0x0E0F_1011)
+//!     .with_ack(0x0A0B_0C0D, 0xF0F0_F0F0);
+//! let bytes = packet.header.to_bytes();
+//! let header = &packet.header;
+//!
+//! let fields = wire_format_description();
+//! assert_eq!(fields.iter().map(|f| f.width).sum::<usize>(), PacketHeader::SIZE);
+//!
+//! for field in fields {
+//!     let raw = &bytes[field.offset..field.offset + field.width];
+//!     let mut value = [0u8; 8];
+//!     value[..field.width].copy_from_slice(raw);
+//!     let value = u64::from_le_bytes(value);
+//!
+//!     let expected = match field.name {
+//!         "magic" => u16::from_le_bytes(PacketHeader::MAGIC) as u64,
+//!         "version" => PacketHeader::VERSION as u64,
+//!         "message_type" => header.message_type as u64,
+//!         "flags" => header.flags.bits() as u64,
+//!         "priority" => header.priority as u64,
+//!         "channel_id" => header.channel_id as u64,
+//!         "content_type" => header.content_type as u64,
+//!         "ttl" => header.ttl as u64,
+//!         "sequence" => header.sequence as u64,
+//!         "player_id" => header.player_id,
+//!         "group_id" => header.group_id as u64,
+//!         "ack" => header.ack as u64,
+//!         "ack_bits" => header.ack_bits as u64,
+//!         "payload_size" => header.payload_size as u64,
+//!         "header_checksum" => header.header_checksum as u64,
+//!         "checksum" => header.checksum as u64,
+//!         other => panic!("campo não verificado: {other}"),
+//!     };
+//!     assert_eq!(value, expected, "campo {}", field.name);
+//! }
+//! ```
+//!
+//! ## Ordem dos bytes
+//! O formato padrão, [`WireFormat::STANDARD`], grava os campos numéricos com o byte
+//! menos significativo primeiro. Para conversar com implementações que esperam a
+//! ordem de rede, [`WireFormat::NETWORK`] grava todos os campos de mais de um byte,
+//! exceto os bytes mágicos, com o mais significativo primeiro, tanto no cabeçalho
+//! quanto nos prefixos de tamanho dos lotes e dos quadros. O `header_checksum` e,
+//! com [`ChecksumScope::HeaderAndPayload`], o `checksum` são calculados sobre os
+//! bytes na ordem em uso, como o outro lado os recebe.
+//!
+//! ```rust
+//! use packet::{Packet, PacketHeader};
+//! use packet::wire::WireFormat;
+//!
+//! let packet = Packet::new(1, 0x0102_0304, 7, vec![1, 2, 3]);
+//! let bytes = packet.to_bytes_with_format(WireFormat::NETWORK);
+//!
+//! let offset = PacketHeader::SEQUENCE_OFFSET;
+//! assert_eq!(bytes[offset..offset + 4], [0x01, 0x02, 0x03, 0x04]);
+//! assert_eq!(Packet::from_bytes_with_format(&bytes, WireFormat::NETWORK), Ok(packet));
+//! assert!(Packet::from_bytes(&bytes).is_err());
+//! ```
+//!
+//! ## Esquema e vetores de teste
+//! Com o recurso `std`, [`header_schema_json`] grava a disposição dos campos em JSON,
+//! e [`test_vectors_json`] grava os pacotes de [`test_vectors`] com os bytes que cada
+//! um deve produzir. Os dois arquivos ficam em `tests/vectors/`, e os testes do crate
+//! falham se eles divergem do que o código gera; outras implementações validam seus
+//! codificadores e decodificadores contra os mesmos arquivos. Para regravá-los após
+//! uma mudança intencional do formato, rode `UPDATE_WIRE_VECTORS=1 cargo test --test
+//! wire_format`.
+
+#[cfg(feature = "std")]
+use std::fmt::Write;
+
+#[cfg(feature = "std")]
+use crate::checksum::Crc32Variant;
+use crate::checksum::{crc8, ChecksumAlgorithm, ChecksumScope};
+#[cfg(feature = "std")]
+use crate::payload::ContentType;
+#[cfg(feature = "std")]
+use crate::Packet;
+use crate::{PacketFlags, PacketHeader};
+
+/// Ordem dos bytes de um campo numérico.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Endianness {
+    /// Byte menos significativo primeiro.
+    Little,
+    /// Byte mais significativo primeiro (ordem de rede).
+    Big,
+}
+
+impl Endianness {
+    /// Retorna o nome da ordem no esquema de [`header_schema_json`]: `"little"` ou
+    /// `"big"`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Little => "little",
+            Self::Big => "big",
+        }
+    }
+
+    /// Grava um valor de 16 bits nesta ordem.
+    #[cfg(feature = "std")]
+    pub(crate) fn u16_bytes(self, value: u16) -> [u8; 2] {
+        match self {
+            Self::Little => value.to_le_bytes(),
+            Self::Big => value.to_be_bytes(),
+        }
+    }
+
+    /// Lê um valor de 16 bits nesta ordem.
+    pub(crate) fn read_u16(self, bytes: [u8; 2]) -> u16 {
+        match self {
+            Self::Little => u16::from_le_bytes(bytes),
+            Self::Big => u16::from_be_bytes(bytes),
+        }
+    }
+
+    /// Grava um valor de 32 bits nesta ordem.
+    pub(crate) fn u32_bytes(self, value: u32) -> [u8; 4] {
+        match self {
+            Self::Little => value.to_le_bytes(),
+            Self::Big => value.to_be_bytes(),
+        }
+    }
+
+    /// Lê um valor de 32 bits nesta ordem.
+    pub(crate) fn read_u32(self, bytes: [u8; 4]) -> u32 {
+        match self {
+            Self::Little => u32::from_le_bytes(bytes),
+            Self::Big => u32::from_be_bytes(bytes),
+        }
+    }
+}
+
+/// Formato dos pacotes na rede.
+///
+/// Usado por [`Packet::to_bytes_with_format`](crate::Packet::to_bytes_with_format),
+/// [`Packet::from_bytes_with_format`](crate::Packet::from_bytes_with_format) e pelos
+/// lotes e quadros com prefixo de tamanho.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WireFormat {
+    /// Ordem dos bytes dos campos numéricos e dos prefixos de tamanho.
+    pub endianness: Endianness,
+}
+
+impl Default for WireFormat {
+    fn default() -> Self {
+        Self::STANDARD
+    }
+}
+
+impl WireFormat {
+    /// Formato padrão, com os campos em little-endian.
+    pub const STANDARD: Self = Self {
+        endianness: Endianness::Little,
+    };
+
+    /// Formato com os campos em ordem de rede (big-endian).
+    pub const NETWORK: Self = Self {
+        endianness: Endianness::Big,
+    };
+
+    /// Retorna a disposição dos campos do cabeçalho neste formato.
+    ///
+    /// ## Retorno
+    /// - `impl Iterator<Item = FieldDesc>`: Os campos de [`wire_format_description`],
+    ///   com a ordem dos bytes deste formato; os bytes mágicos não mudam.
+    pub fn fields(self) -> impl Iterator<Item = FieldDesc> {
+        HEADER_FIELDS.iter().map(move |field| FieldDesc {
+            endianness: if field.name == "magic" {
+                field.endianness
+            } else {
+                self.endianness
+            },
+            ..*field
+        })
+    }
+
+    /// Converte o cabeçalho de um pacote serializado de um formato para outro.
+    ///
+    /// Os campos de mais de um byte têm a ordem invertida, e o `header_checksum` e o
+    /// `checksum` que cobre o cabeçalho são corrigidos pela diferença entre os valores
+    /// esperados nas duas ordens, de modo que um pacote corrompido continua
+    /// corrompido. Buffers sem um cabeçalho completo não são alterados.
+    ///
+    /// ## Parâmetros
+    /// - `bytes`: O pacote serializado, com o payload e nada depois dele.
+    /// - `from`: Formato atual de `bytes`.
+    /// - `to`: Formato desejado.
+    pub(crate) fn convert(bytes: &mut [u8], from: Self, to: Self) {
+        if from.endianness == to.endianness || bytes.len() < PacketHeader::SIZE {
+            return;
+        }
+        let flags = from.endianness.read_u16([
+            bytes[PacketHeader::FLAGS_OFFSET],
+            bytes[PacketHeader::FLAGS_OFFSET + 1],
+        ]);
+        let flags = PacketFlags::from_bits_retain(flags);
+        let covers_header = ChecksumScope::from_flags(flags) == ChecksumScope::HeaderAndPayload;
+        let algorithm = ChecksumAlgorithm::from_flags(flags);
+
+        let old_header_checksum = crc8(&bytes[..PacketHeader::HEADER_CHECKSUM_OFFSET]);
+        let old_checksum = covers_header.then(|| header_and_payload_checksum(bytes, algorithm));
+        for field in HEADER_FIELDS.iter().filter(|field| field.name != "magic") {
+            bytes[field.offset..field.offset + field.width].reverse();
+        }
+        bytes[PacketHeader::HEADER_CHECKSUM_OFFSET] ^=
+            old_header_checksum ^ crc8(&bytes[..PacketHeader::HEADER_CHECKSUM_OFFSET]);
+        if let Some(old_checksum) = old_checksum {
+            let range = PacketHeader::CHECKSUM_OFFSET..PacketHeader::SIZE;
+            let stored = to
+                .endianness
+                .read_u32(bytes[range.clone()].try_into().unwrap());
+            let new_checksum = header_and_payload_checksum(bytes, algorithm);
+            bytes[range].copy_from_slice(
+                &to.endianness
+                    .u32_bytes(stored ^ old_checksum ^ new_checksum),
+            );
+        }
+    }
+}
+
+/// Calcula o checksum do cabeçalho, com o campo `checksum` zerado, e do payload.
+fn header_and_payload_checksum(bytes: &[u8], algorithm: ChecksumAlgorithm) -> u32 {
+    let mut header = [0; PacketHeader::SIZE];
+    header.copy_from_slice(&bytes[..PacketHeader::SIZE]);
+    header[PacketHeader::CHECKSUM_OFFSET..].fill(0);
+    algorithm.compute_parts(&[&header, &bytes[PacketHeader::SIZE..]])
+}
+
+/// Descrição de um campo do cabeçalho na rede.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldDesc {
+    /// Nome do campo, igual ao nome em [`PacketHeader`](crate::PacketHeader).
+    pub name: &'static str,
+    /// Posição do primeiro byte do campo no cabeçalho.
+    pub offset: usize,
+    /// Tamanho do campo, em bytes.
+    pub width: usize,
+    /// Ordem dos bytes do campo.
+    pub endianness: Endianness,
+}
+
+const HEADER_FIELDS: &[FieldDesc] = &[
+    FieldDesc {
+        name: "magic",
+        offset: PacketHeader::MAGIC_OFFSET,
+        width: 2,
+        endianness: Endianness::Little,
+    },
+    FieldDesc {
+        name: "version",
+        offset: PacketHeader::VERSION_OFFSET,
+        width: 1,
+        endianness: Endianness::Little,
+    },
+    FieldDesc {
+        name: "message_type",
+        offset: PacketHeader::MESSAGE_TYPE_OFFSET,
+        width: 1,
+        endianness: Endianness::Little,
+    },
+    FieldDesc {
+        name: "flags",
+        offset: PacketHeader::FLAGS_OFFSET,
+        width: 2,
+        endianness: Endianness::Little,
+    },
+    FieldDesc {
+        name: "priority",
+        offset: PacketHeader::PRIORITY_OFFSET,
+        width: 1,
+        endianness: Endianness::Little,
+    },
+    FieldDesc {
+        name: "channel_id",
+        offset: PacketHeader::CHANNEL_ID_OFFSET,
+        width: 1,
+        endianness: Endianness::Little,
+    },
+    FieldDesc {
+        name: "content_type",
+        offset: PacketHeader::CONTENT_TYPE_OFFSET,
+        width: 1,
+        endianness: Endianness::Little,
+    },
+    FieldDesc {
+        name: "ttl",
+        offset: PacketHeader::TTL_OFFSET,
+        width: 1,
+        endianness: Endianness::Little,
+    },
+    FieldDesc {
+        name: "sequence",
+        offset: PacketHeader::SEQUENCE_OFFSET,
+        width: 4,
+        endianness: Endianness::Little,
+    },
+    FieldDesc {
+        name: "player_id",
+        offset: PacketHeader::PLAYER_ID_OFFSET,
+        width: 8,
+        endianness: Endianness::Little,
+    },
+    FieldDesc {
+        name: "group_id",
+        offset: PacketHeader::GROUP_ID_OFFSET,
+        width: 4,
+        endianness: Endianness::Little,
+    },
+    FieldDesc {
+        name: "ack",
+        offset: PacketHeader::ACK_OFFSET,
+        width: 4,
+        endianness: Endianness::Little,
+    },
+    FieldDesc {
+        name: "ack_bits",
+        offset: PacketHeader::ACK_BITS_OFFSET,
+        width: 4,
+        endianness: Endianness::Little,
+    },
+    FieldDesc {
+        name: "payload_size",
+        offset: PacketHeader::PAYLOAD_SIZE_OFFSET,
+        width: 4,
+        endianness: Endianness::Little,
+    },
+    FieldDesc {
+        name: "header_checksum",
+        offset: PacketHeader::HEADER_CHECKSUM_OFFSET,
+        width: 1,
+        endianness: Endianness::Little,
+    },
+    FieldDesc {
+        name: "checksum",
+        offset: PacketHeader::CHECKSUM_OFFSET,
+        width: 4,
+        endianness: Endianness::Little,
+    },
+];
+
+/// Retorna a disposição dos campos de [`PacketHeader`](crate::PacketHeader) na rede.
+///
+/// ## Retorno
+/// - `&'static [FieldDesc]`: Os campos na ordem em que aparecem no cabeçalho serializado.
+pub fn wire_format_description() -> &'static [FieldDesc] {
+    HEADER_FIELDS
+}
+
+/// Descreve o cabeçalho em JSON, para gerar decodificadores em outras linguagens.
+///
+/// O documento traz os bytes mágicos, a versão, o tamanho do cabeçalho, a ordem dos
+/// bytes do formato e, para cada campo de [`WireFormat::fields`], o nome, a posição
+/// (`offset`), o tamanho em bytes (`size`) e a ordem dos bytes. A saída é estável:
+/// a mesma versão do crate gera sempre o mesmo texto, que pode ser guardado e
+/// comparado.
+///
+/// ## Parâmetros
+/// - `format`: Formato descrito.
+///
+/// ## Retorno
+/// - `String`: O esquema em JSON, terminado por uma quebra de linha.
+///
+/// ## Exemplos
+/// ```rust
+/// use packet::wire::{header_schema_json, WireFormat};
+///
+/// let schema = header_schema_json(WireFormat::NETWORK);
+/// assert!(schema.contains(r#""header_size": 43"#));
+/// assert!(schema.contains(
+///     r#"{ "name": "sequence", "offset": 10, "size": 4, "endianness": "big" }"#
+/// ));
+/// ```
+#[cfg(feature = "std")]
+pub fn header_schema_json(format: WireFormat) -> String {
+    let magic = core::str::from_utf8(&PacketHeader::MAGIC).expect("bytes mágicos em ASCII");
+    let mut json = String::new();
+    json.push_str("{\n");
+    let _ = writeln!(json, "  \"magic\": \"{magic}\",");
+    let _ = writeln!(json, "  \"version\": {},", PacketHeader::VERSION);
+    let _ = writeln!(json, "  \"header_size\": {},", PacketHeader::SIZE);
+    let _ = writeln!(json, "  \"endianness\": \"{}\",", format.endianness.name());
+    json.push_str("  \"fields\": [\n");
+    let fields: Vec<_> = format.fields().collect();
+    for (index, field) in fields.iter().enumerate() {
+        let _ = write!(
+            json,
+            "    {{ \"name\": \"{}\", \"offset\": {}, \"size\": {}, \"endianness\": \"{}\" }}",
+            field.name,
+            field.offset,
+            field.width,
+            field.endianness.name()
+        );
+        json.push_str(if index + 1 < fields.len() {
+            ",\n"
+        } else {
+            "\n"
+        });
+    }
+    json.push_str("  ]\n}\n");
+    json
+}
+
+/// Vetor de teste do formato: um pacote e os bytes que ele deve produzir.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestVector {
+    /// Nome do vetor, único em [`test_vectors`].
+    pub name: &'static str,
+    /// Formato da serialização.
+    pub format: WireFormat,
+    /// Pacote serializado.
+    pub packet: Packet,
+}
+
+#[cfg(feature = "std")]
+impl TestVector {
+    /// Retorna os bytes esperados, de
+    /// [`Packet::to_bytes_with_format`](crate::Packet::to_bytes_with_format).
+    pub fn expected_bytes(&self) -> Vec<u8> {
+        self.packet.to_bytes_with_format(self.format)
+    }
+}
+
+/// Retorna os vetores de teste de referência do formato.
+///
+/// Os vetores cobrem o payload vazio, todos os campos do cabeçalho preenchidos, as
+/// duas ordens de bytes e os algoritmos e abrangências do checksum. São os mesmos
+/// gravados por [`test_vectors_json`] no arquivo usado pelos testes do crate.
+///
+/// ## Retorno
+/// - `Vec<TestVector>`: Os vetores, sempre na mesma ordem.
+///
+/// ## Exemplos
+/// ```rust
+/// use packet::Packet;
+/// use packet::wire::test_vectors;
+///
+/// for vector in test_vectors() {
+///     let bytes = vector.expected_bytes();
+///     assert_eq!(Packet::from_bytes_with_format(&bytes, vector.format), Ok(vector.packet));
+/// }
+/// ```
+#[cfg(feature = "std")]
+pub fn test_vectors() -> Vec<TestVector> {
+    let all_fields = || {
+        Packet::reliable(0xAB, 0x0102_0304, 0x1122_3344_5566_7788, vec![9; 3])
+            .with_priority(0xCD)
+            .with_channel_id(0x3C)
+            .with_content_type(ContentType::Custom(0x5A))
+            .with_ttl(0x2D)
+            .with_group_id(0x0E0F_1011)
+            .with_ack(0x0A0B_0C0D, 0xF0F0_F0F0)
+    };
+    let payload = || vec![0xDE, 0xAD, 0xBE, 0xEF, 0x00, 0x7F];
+    let vector = |name, format, packet| TestVector {
+        name,
+        format,
+        packet,
+    };
+    vec![
+        vector(
+            "empty_payload",
+            WireFormat::STANDARD,
+            Packet::new(1, 42, 0x0102_0304_0506_0708, vec![]),
+        ),
+        vector("all_fields", WireFormat::STANDARD, all_fields()),
+        vector("all_fields_network", WireFormat::NETWORK, all_fields()),
+        vector(
+            "crc32",
+            WireFormat::STANDARD,
+            Packet::new(2, 7, 99, payload())
+                .with_checksum_algorithm(ChecksumAlgorithm::Crc32(Crc32Variant::Ieee)),
+        ),
+        vector(
+            "crc32c",
+            WireFormat::STANDARD,
+            Packet::new(2, 8, 99, payload())
+                .with_checksum_algorithm(ChecksumAlgorithm::Crc32(Crc32Variant::Castagnoli)),
+        ),
+        vector(
+            "xxhash32",
+            WireFormat::STANDARD,
+            Packet::new(2, 9, 99, payload()).with_checksum_algorithm(ChecksumAlgorithm::XxHash32),
+        ),
+        vector(
+            "header_and_payload_checksum",
+            WireFormat::STANDARD,
+            Packet::new(2, 10, 99, payload()).with_checksum_scope(ChecksumScope::HeaderAndPayload),
+        ),
+        vector(
+            "header_and_payload_checksum_network",
+            WireFormat::NETWORK,
+            Packet::new(2, 10, 99, payload()).with_checksum_scope(ChecksumScope::HeaderAndPayload),
+        ),
+    ]
+}
+
+/// Grava vetores de teste em JSON, para validar outras implementações.
+///
+/// Cada vetor traz o nome, a ordem dos bytes, os campos do cabeçalho definidos pelo
+/// remetente (os tamanhos e os checksums são derivados), o payload e os bytes
+/// esperados, ambos em hexadecimal minúsculo. O `player_id` é um inteiro de 64 bits
+/// sem sinal, que linguagens com números de ponto flutuante precisam ler como tal.
+///
+/// ## Parâmetros
+/// - `vectors`: Vetores gravados, como os de [`test_vectors`].
+///
+/// ## Retorno
+/// - `String`: A lista em JSON, terminada por uma quebra de linha.
+///
+/// ## Exemplos
+/// ```rust
+/// use packet::wire::{test_vectors, test_vectors_json};
+///
+/// let json = test_vectors_json(&test_vectors()[..1]);
+/// assert!(json.contains(r#""name": "empty_payload""#));
+/// assert!(json.contains(r#""payload": """#));
+/// assert!(json.contains(r#""bytes": "524e0101"#));
+/// ```
+#[cfg(feature = "std")]
+pub fn test_vectors_json(vectors: &[TestVector]) -> String {
+    let hex = |bytes: &[u8]| {
+        bytes.iter().fold(String::new(), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        })
+    };
+    let mut json = String::from("[\n");
+    for (index, vector) in vectors.iter().enumerate() {
+        let header = &vector.packet.header;
+        json.push_str("  {\n");
+        let _ = writeln!(json, "    \"name\": \"{}\",", vector.name);
+        let _ = writeln!(
+            json,
+            "    \"endianness\": \"{}\",",
+            vector.format.endianness.name()
+        );
+        json.push_str("    \"header\": {\n");
+        let fields: [(&str, u64); 11] = [
+            ("message_type", header.message_type.into()),
+            ("flags", header.flags.bits().into()),
+            ("priority", header.priority.into()),
+            ("channel_id", header.channel_id.into()),
+            ("content_type", header.content_type.into()),
+            ("ttl", header.ttl.into()),
+            ("sequence", header.sequence.into()),
+            ("player_id", header.player_id),
+            ("group_id", header.group_id.into()),
+            ("ack", header.ack.into()),
+            ("ack_bits", header.ack_bits.into()),
+        ];
+        for (field, (name, value)) in fields.iter().enumerate() {
+            let separator = if field + 1 < fields.len() { "," } else { "" };
+            let _ = writeln!(json, "      \"{name}\": {value}{separator}");
+        }
+        json.push_str("    },\n");
+        let _ = writeln!(
+            json,
+            "    \"payload\": \"{}\",",
+            hex(&vector.packet.payload)
+        );
+        let _ = writeln!(json, "    \"bytes\": \"{}\"", hex(&vector.expected_bytes()));
+        json.push_str(if index + 1 < vectors.len() {
+            "  },\n"
+        } else {
+            "  }\n"
+        });
+    }
+    json.push_str("]\n");
+    json
+}